@@ -4,7 +4,7 @@ use std::rc::Rc;
 use velox_dom::{h, text, VNode};
 use velox_style::Stylesheet;
 
-fn main() {
+fn main() -> Result<(), velox_renderer::Error> {
     let count = Rc::new(RefCell::new(0i32));
     let count_for_view = Rc::clone(&count);
     let count_for_title = Rc::clone(&count);
@@ -25,7 +25,7 @@ fn main() {
             }
         },
         move || format!("Velox Skia Interactive — count {}", *count_for_title.borrow()),
-    );
+    )
 }
 
 fn make_view(count: &Rc<RefCell<i32>>, w: u32, height: u32, sheet: &Stylesheet) -> (VNode, Stylesheet) {
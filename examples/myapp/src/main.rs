@@ -3,7 +3,7 @@ use velox_style::Stylesheet;
 
 include!(concat!(env!("OUT_DIR"), "/App.rs"));
 
-fn main() {
+fn main() -> Result<(), velox_renderer::Error> {
     // Create component state from script section
     let state = app::script_rs::State::new();
     let state_ref = std::sync::Arc::new(state);
@@ -15,5 +15,5 @@ fn main() {
     // use generated helper from SFC output to wire events to the component state
     let on_event = app::make_on_event(state_ref.clone());
     let get_title = { let state = state_ref.clone(); move || state.title.borrow().to_string() };
-    velox_renderer::run_window_vnode_skia("Velox App", make_view, on_event, get_title);
+    velox_renderer::run_window_vnode_skia("Velox App", make_view, on_event, get_title)
 }
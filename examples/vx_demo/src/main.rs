@@ -3,7 +3,7 @@ use velox_style::Stylesheet;
 
 include!(concat!(env!("OUT_DIR"), "/App.rs"));
 
-fn main() {
+fn main() -> Result<(), velox_renderer::Error> {
     let state = app::script_rs::State::new();
     let state_ref = std::sync::Arc::new(state);
 
@@ -25,5 +25,5 @@ fn main() {
         move || state.title.borrow().to_string()
     };
 
-    velox_renderer::run_window_vnode_skia("Velox VX Demo", make_view, on_event, get_title);
+    velox_renderer::run_window_vnode_skia("Velox VX Demo", make_view, on_event, get_title)
 }
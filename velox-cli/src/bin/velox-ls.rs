@@ -0,0 +1,5 @@
+//! Language server for Velox components; see `velox_cli::ls`.
+
+fn main() -> anyhow::Result<()> {
+    velox_cli::ls::run()
+}
@@ -0,0 +1,109 @@
+//! `velox test`: compile every component and run their tests.
+//!
+//! A component's tests live in a `<test>` block of the component file or in a
+//! `<Name>.test.rs` file next to it. Both are compiled into the generated module under
+//! `#[cfg(test)]`, so they see its `render_with_state`, `make_on_event`, `STYLE` and
+//! `script_rs`, and drive them through `velox_renderer::testing::TestApp`.
+//!
+//! `velox test` compiles the components first, so template and style errors are reported
+//! per file before cargo runs, then runs `cargo test` on the package in deterministic
+//! mode (`VELOX_DETERMINISTIC=1`) so snapshots and goldens match on every machine.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::{EmitMode, ProjectConfig};
+
+/// A compiled component and where its tests come from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ComponentTests {
+    pub component: PathBuf,
+    /// `#[test]` functions in the `<test>` block.
+    pub inline: usize,
+    pub file: Option<PathBuf>,
+}
+
+impl ComponentTests {
+    pub fn has_tests(&self) -> bool {
+        self.inline > 0 || self.file.is_some()
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TestOptions {
+    /// Only run tests whose name contains this.
+    pub filter: Option<String>,
+    /// Rewrite snapshots and goldens that don't match.
+    pub update: bool,
+}
+
+/// Compile every component under `dirs` into `out_dir` (stopping at the first that
+/// doesn't compile) and report the tests each one has.
+pub fn compile_components(dirs: &[PathBuf], out_dir: &Path) -> Result<Vec<ComponentTests>> {
+    let mut found = Vec::new();
+    for dir in dirs {
+        for out in crate::build_dir(dir, Some(out_dir), EmitMode::Render)? {
+            log::debug!(target: "velox::test", "compiled {}", out.path.display());
+        }
+        let mut components = Vec::new();
+        crate::collect_components(dir, &mut components)
+            .with_context(|| format!("failed to scan {}", dir.display()))?;
+        components.sort();
+        for component in components {
+            let src = fs::read_to_string(&component)
+                .with_context(|| format!("failed to read {}", component.display()))?;
+            let sfc = velox_sfc::parse_sfc(&src)
+                .map_err(|e| anyhow::anyhow!("{}: {}", component.display(), e))?;
+            let inline = sfc.test.map_or(0, |t| t.content.matches("#[test]").count());
+            let file = crate::component_test_file(&component);
+            found.push(ComponentTests {
+                component,
+                inline,
+                file,
+            });
+        }
+    }
+    Ok(found)
+}
+
+/// Compile the components of `pkg` and run its tests (see the module docs).
+pub fn test_app(pkg: &str, config: &ProjectConfig, options: &TestOptions) -> Result<()> {
+    let dirs = if config.components.is_empty() {
+        let workspace = crate::watch::Workspace::find(&config.root)?;
+        let package = workspace
+            .as_ref()
+            .and_then(|w| w.packages.iter().find(|p| p.name == pkg))
+            .with_context(|| format!("package `{}` is not in the workspace", pkg))?;
+        vec![package.dir.join("src")]
+    } else {
+        config.components.clone()
+    };
+    let out_dir = config
+        .out_dir
+        .clone()
+        .unwrap_or_else(|| config.root.join("target/velox-gen"));
+    let components = compile_components(&dirs, &out_dir)?;
+    let tested = components.iter().filter(|c| c.has_tests()).count();
+    log::info!(target: "velox::test", "{} component(s) compiled, {} with tests", components.len(), tested);
+    for c in components.iter().filter(|c| !c.has_tests()) {
+        log::info!(target: "velox::test", "no tests: {}", c.component.display());
+    }
+
+    let mut cmd = Command::new("cargo");
+    cmd.args(["test", "-p", pkg])
+        .args(config.cargo_feature_args())
+        .env("VELOX_DETERMINISTIC", "1");
+    if options.update {
+        cmd.env("VELOX_UPDATE_GOLDENS", "1");
+    }
+    if let Some(filter) = &options.filter {
+        cmd.args(["--", filter.as_str()]);
+    }
+    let status = cmd.status()?;
+    if !status.success() {
+        anyhow::bail!("component tests failed")
+    }
+    Ok(())
+}
@@ -0,0 +1,313 @@
+//! `velox.toml`: project defaults for `velox build`, `run`, `build-app` and `dev`.
+//!
+//! The CLI reads the first `velox.toml` found in the current directory or its parents;
+//! flags given on the command line win over it. Every key is optional: `[build]` takes
+//! `components`, `assets`, `out_dir`, `emit`, `plugins`, `styles` and `prune_styles`;
+//! `[app]` takes `package`, `features` and `backend`; `[window]` takes `title`, `width`
+//! and `height`; and `[dev]` takes `watch`, `include`, `exclude` and `poll_ms`. The
+//! fields of `ProjectConfig` say what each is for.
+//!
+//! Relative paths are resolved against the directory holding the file. Only the part of
+//! TOML these need is understood: tables, strings, integers, booleans and arrays of
+//! strings, one key per line.
+
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::EmitMode;
+
+pub const FILE_NAME: &str = "velox.toml";
+
+/// How often `velox dev` checks for changes unless `dev.poll_ms` says otherwise.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Size of the app window when `window` sets only one dimension.
+const DEFAULT_WINDOW_SIZE: (u32, u32) = (800, 600);
+
+/// Defaults read from `velox.toml`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProjectConfig {
+    /// The directory holding `velox.toml`.
+    pub root: PathBuf,
+    /// Component directories compiled by a bare `velox build`.
+    pub components: Vec<PathBuf>,
+    /// Asset directories `velox dev` watches.
+    pub assets: Vec<PathBuf>,
+    pub out_dir: Option<PathBuf>,
+    /// `stub` or `render`.
+    pub emit: Option<EmitMode>,
+    /// Compiler plugins run on every component, in order.
+    pub plugins: Vec<String>,
+    /// Stylesheets put before each component's own `<style>` in its `STYLE`.
+    pub styles: Vec<PathBuf>,
+    /// Whether `velox build` leaves out the rules a component can't match (see
+    /// `velox_sfc::selectors`). On unless `build.prune_styles = false`.
+    pub prune_styles: bool,
+    /// The app package for `run`, `build-app` and `dev`.
+    pub package: Option<String>,
+    /// Passed to cargo as `--features`.
+    pub features: Vec<String>,
+    /// Handed to the app as `VELOX_BACKEND`.
+    pub backend: Option<String>,
+    pub window: WindowDefaults,
+    pub dev: DevOptions,
+}
+
+/// Initial window of the app, handed to it through `VELOX_WINDOW_TITLE` and
+/// `VELOX_WINDOW_SIZE`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WindowDefaults {
+    pub title: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevOptions {
+    /// What `velox dev` watches; empty means the whole project.
+    pub watch: Vec<PathBuf>,
+    /// Globs for the watched files (see `watch::path_matches`); empty means all.
+    pub include: Vec<String>,
+    /// Globs for files to ignore; `target/` and `.git/` always are.
+    pub exclude: Vec<String>,
+    pub poll_interval: Duration,
+}
+
+impl Default for DevOptions {
+    fn default() -> Self {
+        DevOptions {
+            watch: Vec::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+}
+
+enum Value {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+    List(Vec<String>),
+}
+
+impl ProjectConfig {
+    /// No file: every default is the CLI's own.
+    pub fn empty(root: impl Into<PathBuf>) -> Self {
+        ProjectConfig {
+            root: root.into(),
+            components: Vec::new(),
+            assets: Vec::new(),
+            out_dir: None,
+            emit: None,
+            plugins: Vec::new(),
+            styles: Vec::new(),
+            prune_styles: true,
+            package: None,
+            features: Vec::new(),
+            backend: None,
+            window: WindowDefaults::default(),
+            dev: DevOptions::default(),
+        }
+    }
+
+    /// The `velox.toml` in `start` or the nearest parent that has one, or `None`.
+    pub fn find(start: &Path) -> Result<Option<Self>> {
+        for dir in start.ancestors() {
+            let path = dir.join(FILE_NAME);
+            if path.is_file() {
+                return Self::load(&path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let src = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let root = path.parent().unwrap_or(Path::new("."));
+        Self::parse(&src, root).with_context(|| format!("invalid {}", path.display()))
+    }
+
+    /// Parse `velox.toml` source; relative paths are joined to `root`.
+    pub fn parse(src: &str, root: &Path) -> Result<Self> {
+        let mut config = Self::empty(root);
+        let mut table = String::new();
+        for (i, raw) in src.lines().enumerate() {
+            let line = strip_comment(raw).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let at = || format!("line {}", i + 1);
+            if let Some(name) = line.strip_prefix('[') {
+                let Some(name) = name.strip_suffix(']') else {
+                    bail!("{}: unclosed table header", at())
+                };
+                table = name.trim().to_string();
+                if !matches!(table.as_str(), "build" | "app" | "window" | "dev") {
+                    bail!("{}: unknown table [{}]", at(), table);
+                }
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                bail!("{}: expected `key = value`", at())
+            };
+            let key = key.trim();
+            let value = parse_value(value.trim()).with_context(at)?;
+            config.set(&table, key, value).with_context(at)?;
+        }
+        Ok(config)
+    }
+
+    fn set(&mut self, table: &str, key: &str, value: Value) -> Result<()> {
+        let root = self.root.clone();
+        let paths = |v: Value| -> Result<Vec<PathBuf>> {
+            Ok(list(v)?.into_iter().map(|p| root.join(p)).collect())
+        };
+        match (table, key) {
+            ("build", "components") => self.components = paths(value)?,
+            ("build", "assets") => self.assets = paths(value)?,
+            ("build", "out_dir") => self.out_dir = Some(self.root.join(string(value)?)),
+            ("build", "emit") => {
+                let name = string(value)?;
+                self.emit = Some(
+                    EmitMode::from_str(&name, true)
+                        .map_err(|_| anyhow::anyhow!("unknown emit mode `{}`", name))?,
+                );
+            }
+            ("build", "plugins") => self.plugins = list(value)?,
+            ("build", "styles") => self.styles = paths(value)?,
+            ("build", "prune_styles") => match value {
+                Value::Bool(prune) => self.prune_styles = prune,
+                _ => bail!("`prune_styles` must be true or false"),
+            },
+            ("app", "package") => self.package = Some(string(value)?),
+            ("app", "features") => self.features = list(value)?,
+            ("app", "backend") => self.backend = Some(string(value)?),
+            ("window", "title") => self.window.title = Some(string(value)?),
+            ("window", "width") => self.window.width = Some(dimension(value)?),
+            ("window", "height") => self.window.height = Some(dimension(value)?),
+            ("dev", "watch") => self.dev.watch = paths(value)?,
+            ("dev", "include") => self.dev.include = list(value)?,
+            ("dev", "exclude") => self.dev.exclude = list(value)?,
+            ("dev", "poll_ms") => match value {
+                Value::Int(ms) if ms > 0 => {
+                    self.dev.poll_interval = Duration::from_millis(ms as u64)
+                }
+                _ => bail!("`poll_ms` must be a positive integer"),
+            },
+            ("", key) => bail!("`{}` must be inside a table", key),
+            (table, key) => bail!("unknown key `{}.{}`", table, key),
+        }
+        Ok(())
+    }
+
+    /// The package `run`, `build-app` and `dev` use when none is given.
+    pub fn package(&self, arg: Option<String>) -> Result<String> {
+        arg.or_else(|| self.package.clone())
+            .context("no package given and velox.toml sets no `app.package`")
+    }
+
+    /// What `velox dev` watches: `dev.watch`, else the whole project.
+    pub fn watch_dirs(&self) -> Vec<PathBuf> {
+        if !self.dev.watch.is_empty() {
+            return self.dev.watch.clone();
+        }
+        vec![self.root.clone()]
+    }
+
+    /// Cargo arguments selecting `features`.
+    pub fn cargo_feature_args(&self) -> Vec<String> {
+        if self.features.is_empty() {
+            return Vec::new();
+        }
+        vec!["--features".to_string(), self.features.join(",")]
+    }
+
+    /// Environment for the app process: backend and window defaults.
+    pub fn app_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = Vec::new();
+        if let Some(backend) = &self.backend {
+            env.push(("VELOX_BACKEND", backend.clone()));
+        }
+        if let Some(title) = &self.window.title {
+            env.push(("VELOX_WINDOW_TITLE", title.clone()));
+        }
+        if self.window.width.is_some() || self.window.height.is_some() {
+            let width = self.window.width.unwrap_or(DEFAULT_WINDOW_SIZE.0);
+            let height = self.window.height.unwrap_or(DEFAULT_WINDOW_SIZE.1);
+            env.push(("VELOX_WINDOW_SIZE", format!("{}x{}", width, height)));
+        }
+        env
+    }
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_value(src: &str) -> Result<Value> {
+    if let Some(inner) = src.strip_prefix('[') {
+        let Some(inner) = inner.strip_suffix(']') else {
+            bail!("unclosed array")
+        };
+        let mut items = Vec::new();
+        for item in inner.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match parse_value(item)? {
+                Value::Str(s) => items.push(s),
+                _ => bail!("arrays may only hold strings"),
+            }
+        }
+        return Ok(Value::List(items));
+    }
+    if let Some(inner) = src.strip_prefix('"') {
+        let Some(inner) = inner.strip_suffix('"') else {
+            bail!("unclosed string")
+        };
+        return Ok(Value::Str(
+            inner.replace("\\\"", "\"").replace("\\\\", "\\"),
+        ));
+    }
+    match src {
+        "true" => return Ok(Value::Bool(true)),
+        "false" => return Ok(Value::Bool(false)),
+        _ => {}
+    }
+    src.replace('_', "")
+        .parse()
+        .map(Value::Int)
+        .map_err(|_| anyhow::anyhow!("invalid value `{}`", src))
+}
+
+fn string(value: Value) -> Result<String> {
+    match value {
+        Value::Str(s) => Ok(s),
+        _ => bail!("expected a string"),
+    }
+}
+
+fn list(value: Value) -> Result<Vec<String>> {
+    match value {
+        Value::List(items) => Ok(items),
+        Value::Str(s) => Ok(vec![s]),
+        _ => bail!("expected an array of strings"),
+    }
+}
+
+fn dimension(value: Value) -> Result<u32> {
+    match value {
+        Value::Int(n) if n > 0 && n <= u32::MAX as i64 => Ok(n as u32),
+        _ => bail!("expected a positive size in pixels"),
+    }
+}
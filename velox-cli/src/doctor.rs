@@ -0,0 +1,329 @@
+//! `velox doctor`: probe the host for what the renderer backends need and suggest fixes.
+//!
+//! Backend init failures otherwise only show up at runtime, so every check here is a cheap
+//! filesystem/PATH probe that can run without initializing a GPU.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Clone, Debug)]
+pub struct Check {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// Actionable suggestion shown when the check is not Ok.
+    pub fix: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DoctorReport {
+    pub workspace_root: Option<PathBuf>,
+    pub checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    pub fn has_failures(&self) -> bool {
+        self.checks.iter().any(|c| c.status == CheckStatus::Fail)
+    }
+}
+
+impl fmt::Display for DoctorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.workspace_root {
+            Some(root) => writeln!(f, "Workspace: {}", root.display())?,
+            None => writeln!(f, "Workspace: (not inside a cargo workspace)")?,
+        }
+        for c in &self.checks {
+            let tag = match c.status {
+                CheckStatus::Ok => "ok",
+                CheckStatus::Warn => "warn",
+                CheckStatus::Fail => "FAIL",
+            };
+            writeln!(f, "[{:>4}] {}: {}", tag, c.name, c.detail)?;
+            if c.status != CheckStatus::Ok {
+                if let Some(fix) = &c.fix {
+                    writeln!(f, "       fix: {}", fix)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Same candidates the wgpu backend tries before falling back to its bundled fonts.
+const SYSTEM_FONTS: &[&str] = &[
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/google-noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/gnu-free/FreeSans.ttf",
+];
+
+const LIB_DIRS: &[&str] = &[
+    "/usr/lib",
+    "/usr/lib64",
+    "/usr/local/lib",
+    "/usr/lib/x86_64-linux-gnu",
+    "/usr/lib/aarch64-linux-gnu",
+    "/lib/x86_64-linux-gnu",
+    "/lib/aarch64-linux-gnu",
+];
+
+/// Run all checks, starting the workspace search at `start`.
+pub fn run_doctor(start: &Path) -> DoctorReport {
+    let mut report = DoctorReport {
+        workspace_root: find_workspace_root(start),
+        checks: Vec::new(),
+    };
+    if let Some(root) = report.workspace_root.clone() {
+        report.checks.extend(check_workspace(&root));
+    }
+    report
+        .checks
+        .push(check_tool("cargo", "install Rust via https://rustup.rs"));
+    if cfg!(target_os = "linux") {
+        report.checks.push(check_gpu_linux());
+        report.checks.push(check_lib(
+            "EGL (skia-native)",
+            &["libEGL.so.1", "libEGL.so"],
+            CheckStatus::Warn,
+            "install libegl1 (Debian/Ubuntu) or mesa-libEGL (Fedora)",
+        ));
+        report.checks.push(check_lib(
+            "OpenGL (skia-native)",
+            &["libGL.so.1", "libGL.so"],
+            CheckStatus::Warn,
+            "install libgl1 (Debian/Ubuntu) or mesa-libGL (Fedora)",
+        ));
+        report.checks.push(check_lib(
+            "fontconfig (skia-native)",
+            &["libfontconfig.so.1", "libfontconfig.so"],
+            CheckStatus::Warn,
+            "install libfontconfig1 / fontconfig",
+        ));
+        report.checks.push(check_lib(
+            "xkbcommon (windowing)",
+            &["libxkbcommon.so.0", "libxkbcommon.so"],
+            CheckStatus::Fail,
+            "install libxkbcommon0 / libxkbcommon",
+        ));
+        report.checks.push(check_display());
+        report.checks.push(check_tool(
+            "pkg-config",
+            "install pkg-config; native -sys crates use it to locate system libraries",
+        ));
+    }
+    report.checks.push(check_fonts());
+    report
+}
+
+/// Walk up from `start` to the first Cargo.toml that declares `[workspace]`.
+pub fn find_workspace_root(start: &Path) -> Option<PathBuf> {
+    let mut dir = Some(start);
+    while let Some(d) = dir {
+        let manifest = d.join("Cargo.toml");
+        if let Ok(txt) = fs::read_to_string(&manifest) {
+            if txt.lines().any(|l| l.trim() == "[workspace]") {
+                return Some(d.to_path_buf());
+            }
+        }
+        dir = d.parent();
+    }
+    None
+}
+
+/// Report which renderer features each workspace member enables, so it is clear which
+/// of the native checks below actually matter for this workspace.
+fn check_workspace(root: &Path) -> Vec<Check> {
+    let Ok(txt) = fs::read_to_string(root.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for member in workspace_members(&txt) {
+        let Ok(manifest) = fs::read_to_string(root.join(&member).join("Cargo.toml")) else {
+            out.push(Check {
+                name: format!("member {}", member),
+                status: CheckStatus::Fail,
+                detail: "listed in workspace but Cargo.toml is missing".into(),
+                fix: Some(format!("remove \"{}\" from [workspace] members", member)),
+            });
+            continue;
+        };
+        let Some(line) = manifest
+            .lines()
+            .find(|l| l.trim_start().starts_with("velox-renderer"))
+        else {
+            continue;
+        };
+        let features = line
+            .split_once("features")
+            .and_then(|(_, rest)| rest.split_once('[').and_then(|(_, r)| r.split_once(']')))
+            .map(|(list, _)| list.replace(['"', ' '], ""))
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "default (stub)".into());
+        out.push(Check {
+            name: format!("member {}", member),
+            status: CheckStatus::Ok,
+            detail: format!("velox-renderer features: {}", features),
+            fix: None,
+        });
+    }
+    out
+}
+
+fn workspace_members(manifest: &str) -> Vec<String> {
+    let Some(start) = manifest.find("members") else {
+        return Vec::new();
+    };
+    let rest = &manifest[start..];
+    let (Some(open), Some(close)) = (rest.find('['), rest.find(']')) else {
+        return Vec::new();
+    };
+    rest[open + 1..close]
+        .split(',')
+        .map(|m| m.trim().trim_matches('"').to_string())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+fn find_lib(names: &[&str]) -> Option<PathBuf> {
+    let extra = std::env::var("LD_LIBRARY_PATH").unwrap_or_default();
+    let dirs = extra
+        .split(':')
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .chain(LIB_DIRS.iter().map(PathBuf::from));
+    for dir in dirs {
+        for name in names {
+            let p = dir.join(name);
+            if p.exists() {
+                return Some(p);
+            }
+        }
+    }
+    None
+}
+
+fn check_lib(name: &str, libs: &[&str], missing: CheckStatus, fix: &str) -> Check {
+    match find_lib(libs) {
+        Some(p) => Check {
+            name: name.into(),
+            status: CheckStatus::Ok,
+            detail: format!("found {}", p.display()),
+            fix: None,
+        },
+        None => Check {
+            name: name.into(),
+            status: missing,
+            detail: format!("none of {} found", libs.join(", ")),
+            fix: Some(fix.into()),
+        },
+    }
+}
+
+fn find_in_path(tool: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|d| d.join(tool))
+        .find(|p| p.is_file())
+}
+
+fn check_tool(tool: &str, fix: &str) -> Check {
+    match find_in_path(tool) {
+        Some(p) => Check {
+            name: tool.into(),
+            status: CheckStatus::Ok,
+            detail: format!("found {}", p.display()),
+            fix: None,
+        },
+        None => Check {
+            name: tool.into(),
+            status: CheckStatus::Fail,
+            detail: "not found in PATH".into(),
+            fix: Some(fix.into()),
+        },
+    }
+}
+
+/// wgpu on Linux needs the Vulkan loader plus at least one installed ICD (driver manifest).
+fn check_gpu_linux() -> Check {
+    let name = "GPU adapter (wgpu)".to_string();
+    if find_lib(&["libvulkan.so.1", "libvulkan.so"]).is_none() {
+        return Check {
+            name,
+            status: CheckStatus::Warn,
+            detail: "Vulkan loader not found; wgpu will have no adapter".into(),
+            fix: Some("install libvulkan1 and a driver (mesa-vulkan-drivers), or use the skia-native backend".into()),
+        };
+    }
+    let icd_dirs = ["/usr/share/vulkan/icd.d", "/etc/vulkan/icd.d"];
+    let icds: Vec<String> = icd_dirs
+        .iter()
+        .filter_map(|d| fs::read_dir(d).ok())
+        .flat_map(|rd| rd.flatten())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .filter(|n| n.ends_with(".json"))
+        .collect();
+    if icds.is_empty() {
+        Check {
+            name,
+            status: CheckStatus::Warn,
+            detail: "Vulkan loader present but no ICD manifests installed".into(),
+            fix: Some("install a Vulkan driver (mesa-vulkan-drivers or the vendor driver)".into()),
+        }
+    } else {
+        Check {
+            name,
+            status: CheckStatus::Ok,
+            detail: format!("Vulkan drivers: {}", icds.join(", ")),
+            fix: None,
+        }
+    }
+}
+
+fn check_display() -> Check {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let x11 = std::env::var_os("DISPLAY").is_some();
+    if wayland || x11 {
+        let which = if wayland { "Wayland" } else { "X11" };
+        Check {
+            name: "display server".into(),
+            status: CheckStatus::Ok,
+            detail: format!("{} session detected", which),
+            fix: None,
+        }
+    } else {
+        Check {
+            name: "display server".into(),
+            status: CheckStatus::Warn,
+            detail: "neither WAYLAND_DISPLAY nor DISPLAY is set; windows cannot open".into(),
+            fix: Some("run from a desktop session, or use headless PNG rendering".into()),
+        }
+    }
+}
+
+fn check_fonts() -> Check {
+    match SYSTEM_FONTS.iter().find(|p| Path::new(p).exists()) {
+        Some(p) => Check {
+            name: "system fonts".into(),
+            status: CheckStatus::Ok,
+            detail: format!("found {}", p),
+            fix: None,
+        },
+        None => Check {
+            name: "system fonts".into(),
+            status: CheckStatus::Warn,
+            detail: "no known system font found; the bundled DejaVu/Noto fonts will be used".into(),
+            fix: Some("install fonts-dejavu-core or google-noto-sans-fonts".into()),
+        },
+    }
+}
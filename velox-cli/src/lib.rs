@@ -2,68 +2,77 @@ use anyhow::{Context, Result};
 use clap::ValueEnum;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, SystemTime};
 use std::process::{Command, Stdio, Child};
 use std::io::{self, Read};
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 use std::thread;
+use velox_style::SimpleSelectorKind;
 
-#[derive(Clone, Copy, Debug, ValueEnum)]
+pub mod component_tests;
+pub mod config;
+pub mod doctor;
+pub mod ls;
+pub mod watch;
+
+pub use config::ProjectConfig;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
 pub enum EmitMode {
     Stub,
     Render,
 }
 
+/// Result of compiling a single component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BuildOutput {
+    pub path: PathBuf,
+    /// False when the existing output already had identical contents.
+    pub written: bool,
+}
+
 /// Build a .vx/.vue file into a Rust module written to `out_dir`.
 pub fn build_cmd(input: &Path, out_dir: Option<&Path>, emit: EmitMode) -> Result<()> {
+    let out = build_file(input, out_dir, emit)?;
+    if out.written {
+        println!("Generated: {}", out.path.display());
+    } else {
+        println!("Up to date: {}", out.path.display());
+    }
+    Ok(())
+}
+
+/// Compile one component, leaving the output untouched when its contents did not change
+/// so downstream crates are not rebuilt. Emits `cargo:rerun-if-changed` when run from build.rs.
+pub fn build_file(input: &Path, out_dir: Option<&Path>, emit: EmitMode) -> Result<BuildOutput> {
+    emit_rerun_if_changed(input);
     let src =
         fs::read_to_string(input).with_context(|| format!("failed to read {}", input.display()))?;
 
-    let sfc = velox_sfc::parse_sfc(&src).map_err(|e| anyhow::anyhow!(e))?;
-
     let name = input
         .file_stem()
         .and_then(|s| s.to_str())
         .unwrap_or("component");
 
-    let mut code = String::new();
-
-    match emit {
-        EmitMode::Stub => {
-            code.push_str(&velox_sfc::to_stub_rs(&sfc, name));
-        }
-        EmitMode::Render => {
-            let tpl_src = sfc
-                .template
-                .as_ref()
-                .map(|t| t.content.as_str())
-                .unwrap_or("");
-            let render_fn =
-                velox_sfc::compile_template_to_rs(tpl_src, name).map_err(|e| anyhow::anyhow!(e))?;
-            // Emit stub constants then inject the render() and helpers inside the generated module
-            let mut stub = velox_sfc::to_stub_rs(&sfc, name);
-            // indent the generated functions so they live inside the module
-            let indented = render_fn
-                .lines()
-                .map(|l| format!("    {}", l))
-                .collect::<Vec<_>>()
-                .join("\n");
-            if let Some(pos) = stub.rfind("\n}\n") {
-                // insert before the final closing brace of the module
-                let before = &stub[..pos+1];
-                let after = &stub[pos+1..];
-                code.push_str(before);
-                code.push_str("\n");
-                code.push_str(&indented);
-                code.push_str("\n");
-                code.push_str(after);
-            } else {
-                // fallback: append after stub
-                code.push_str(&stub);
-                code.push_str("\n");
-                code.push_str(&render_fn);
-                code.push_str("\n");
-            }
+    let test_file = component_test_file(input);
+    if let Some(file) = &test_file {
+        emit_rerun_if_changed(file);
+    }
+    let config = config_for(input)?;
+    let plugins = velox_sfc::plugin::resolve_plugins(&config.plugins).map_err(|e| anyhow::anyhow!(e))?;
+    let src = plugins.iter().try_fold(src, |src, p| {
+        p.transform_source(src).map_err(|e| anyhow::anyhow!("{}: plugin `{}`: {}", input.display(), p.name(), e))
+    })?;
+    for file in &config.styles {
+        emit_rerun_if_changed(file);
+    }
+    let css = component_style(&src, &config, &plugins)?;
+    let code = generate_code(&src, name, emit, test_file.as_deref(), &plugins, &css)?;
+    for diagnostic in check_styles(&src)? {
+        let message = format!("{}:{}", input.display(), diagnostic);
+        if std::env::var_os("OUT_DIR").is_some() {
+            println!("cargo:warning={}", message);
+        } else {
+            log::warn!(target: "velox::cli", "{}", message);
         }
     }
 
@@ -74,13 +83,226 @@ pub fn build_cmd(input: &Path, out_dir: Option<&Path>, emit: EmitMode) -> Result
         .with_context(|| format!("failed to create {}", out_dir.display()))?;
 
     let out_path = out_dir.join(format!("{}.rs", name));
-    fs::write(&out_path, code)
-        .with_context(|| format!("failed to write {}", out_path.display()))?;
+    let written = write_if_changed(&out_path, &code)?;
+    // Tools outside Rust read the interface next to the module (`velox_sfc::interface`).
+    let interface = component_interface(&src, name, &plugins)?;
+    write_if_changed(&out_dir.join(format!("{}.json", name)), &interface.to_json())?;
+    Ok(BuildOutput { path: out_path, written })
+}
+
+/// Props, events and slots of a component, after `plugins` have rewritten its template.
+pub fn component_interface(
+    src: &str,
+    name: &str,
+    plugins: &[Arc<dyn velox_sfc::CompilerPlugin>],
+) -> Result<velox_sfc::ComponentInterface> {
+    let sfc = velox_sfc::parse_sfc(src).map_err(|e| anyhow::anyhow!(e))?;
+    Ok(velox_sfc::ComponentInterface::from_nodes(name, &template_nodes(&sfc, plugins)?))
+}
 
-    println!("Generated: {}", out_path.display());
+/// The stylesheet a component embeds as its `STYLE`: the project's shared `styles`, then
+/// its own `<style>` block. With `prune_styles`, the rules its template can never match
+/// are left out (`velox_sfc::selectors`), and the rest is reprinted; the CSS is kept as
+/// written when every rule may match.
+pub fn component_style(
+    src: &str,
+    config: &ProjectConfig,
+    plugins: &[Arc<dyn velox_sfc::CompilerPlugin>],
+) -> Result<String> {
+    let sfc = velox_sfc::parse_sfc(src).map_err(|e| anyhow::anyhow!(e))?;
+    let mut css = String::new();
+    for path in &config.styles {
+        css.push_str(&fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?);
+        css.push('\n');
+    }
+    if let Some(style) = &sfc.style {
+        css.push_str(&style.content);
+    }
+    if !config.prune_styles {
+        return Ok(css);
+    }
+    let selectors = velox_sfc::TemplateSelectors::from_nodes(&template_nodes(&sfc, plugins)?);
+    let mut sheet = velox_style::Stylesheet::parse(&css);
+    let before = sheet.rules.len();
+    sheet.rules.retain(|rule| {
+        std::iter::once(&rule.selector).chain(&rule.ancestors).all(|sel| match sel.kind {
+            SimpleSelectorKind::Tag => selectors.can_match(Some(&sel.tag), None),
+            SimpleSelectorKind::Class => selectors.can_match(None, Some(&sel.class)),
+            SimpleSelectorKind::TagClass => selectors.can_match(Some(&sel.tag), Some(&sel.class)),
+        })
+    });
+    if sheet.rules.len() == before {
+        return Ok(css);
+    }
+    log::debug!(target: "velox::cli", "left out {} style rule(s) the template can't match", before - sheet.rules.len());
+    Ok(sheet.to_css())
+}
+
+/// The parsed template of `sfc`, after `plugins` have rewritten it.
+fn template_nodes(sfc: &velox_sfc::Sfc, plugins: &[Arc<dyn velox_sfc::CompilerPlugin>]) -> Result<Vec<velox_sfc::Node>> {
+    let template = sfc.template.as_ref().map(|t| t.content.as_str()).unwrap_or("");
+    let mut nodes = velox_sfc::parse_template_to_ast(template).map_err(|e| anyhow::anyhow!(e))?;
+    for plugin in plugins {
+        plugin
+            .transform_template(&mut nodes)
+            .map_err(|e| anyhow::anyhow!("plugin `{}`: {}", plugin.name(), e))?;
+    }
+    Ok(nodes)
+}
+
+/// Compile every .vx/.vue file under `src_dir` (recursively). Only changed outputs are rewritten.
+pub fn build_dir(src_dir: &Path, out_dir: Option<&Path>, emit: EmitMode) -> Result<Vec<BuildOutput>> {
+    // Watching the directory itself makes cargo rerun when components are added or removed.
+    emit_rerun_if_changed(src_dir);
+    let mut inputs = Vec::new();
+    collect_components(src_dir, &mut inputs)
+        .with_context(|| format!("failed to scan {}", src_dir.display()))?;
+    inputs.sort();
+    inputs
+        .iter()
+        .map(|input| build_file(input, out_dir, emit))
+        .collect()
+}
+
+/// Stylesheet warnings for a component's `<style>` block, with positions in the component file.
+pub fn check_styles(src: &str) -> Result<Vec<velox_style::Diagnostic>> {
+    let sfc = velox_sfc::parse_sfc(src).map_err(|e| anyhow::anyhow!(e))?;
+    let Some(style) = &sfc.style else {
+        return Ok(Vec::new());
+    };
+    let (_, mut diagnostics) = velox_style::Stylesheet::parse_with_diagnostics(&style.content);
+    // The style body is a slice of `src`, so its offset gives the line/column shift.
+    let start = src.find(style.content.as_str()).unwrap_or(0);
+    let line_offset = src[..start].matches('\n').count() as u32;
+    let column_offset = src[..start].rsplit('\n').next().map_or(0, |l| l.chars().count() as u32);
+    for d in &mut diagnostics {
+        if d.line == 1 {
+            d.column += column_offset;
+        }
+        d.line += line_offset;
+    }
+    Ok(diagnostics)
+}
+
+fn collect_components(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_components(&path, out)?;
+        } else if matches!(path.extension().and_then(|e| e.to_str()), Some("vx" | "vue")) {
+            out.push(path);
+        }
+    }
     Ok(())
 }
 
+/// The `velox.toml` of the project holding `input`, or the defaults when there is none.
+fn config_for(input: &Path) -> Result<ProjectConfig> {
+    let dir = input.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    Ok(ProjectConfig::find(dir)?.unwrap_or_else(|| ProjectConfig::empty(dir)))
+}
+
+fn generate_code(
+    src: &str,
+    name: &str,
+    emit: EmitMode,
+    test_file: Option<&Path>,
+    plugins: &[Arc<dyn velox_sfc::CompilerPlugin>],
+    css: &str,
+) -> Result<String> {
+    let mut sfc = velox_sfc::parse_sfc(src).map_err(|e| anyhow::anyhow!(e))?;
+    let attrs = sfc.style.take().map(|s| s.attrs).unwrap_or_default();
+    sfc.style = Some(velox_sfc::StyleBlock { attrs, content: css.to_string() });
+
+    let mut code = velox_sfc::to_stub_rs(&sfc, name);
+
+    if let EmitMode::Render = emit {
+        let tpl_src = sfc
+            .template
+            .as_ref()
+            .map(|t| t.content.as_str())
+            .unwrap_or("");
+        let render_fn = velox_sfc::compile_template_with_plugins(tpl_src, name, plugins)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        // indent the generated functions so they live inside the module
+        let indented = render_fn
+            .lines()
+            .map(|l| format!("    {}", l))
+            .collect::<Vec<_>>()
+            .join("\n");
+        code = insert_into_module(code, &indented);
+    }
+    let test_files: Vec<String> = test_file.map(|p| p.display().to_string()).into_iter().collect();
+    if let Some(tests) = velox_sfc::to_test_rs(&sfc, &test_files) {
+        code = insert_into_module(code, tests.trim_end());
+    }
+    for plugin in plugins {
+        code = plugin
+            .post_process(name, code)
+            .map_err(|e| anyhow::anyhow!("plugin `{}`: {}", plugin.name(), e))?;
+    }
+    Ok(code)
+}
+
+/// Insert `body` before the final closing brace of the generated module.
+fn insert_into_module(stub: String, body: &str) -> String {
+    let mut code = String::new();
+    if let Some(pos) = stub.rfind("\n}\n") {
+        let before = &stub[..pos+1];
+        let after = &stub[pos+1..];
+        code.push_str(before);
+        code.push('\n');
+        code.push_str(body);
+        code.push('\n');
+        code.push_str(after);
+    } else {
+        // fallback: append after stub
+        code.push_str(&stub);
+        code.push('\n');
+        code.push_str(body);
+        code.push('\n');
+    }
+    code
+}
+
+/// `<Name>.test.rs` next to a component, compiled into its tests (absolute, for `include!`).
+pub fn component_test_file(component: &Path) -> Option<PathBuf> {
+    let stem = component.file_stem()?.to_str()?;
+    let file = component.with_file_name(format!("{}.test.rs", stem));
+    if !file.is_file() {
+        return None;
+    }
+    Some(file.canonicalize().unwrap_or(file))
+}
+
+/// FNV-1a; only used to compare generated outputs, not for security.
+fn content_hash(bytes: &[u8]) -> u64 {
+    let mut h: u64 = 0xcbf29ce484222325;
+    for b in bytes {
+        h ^= *b as u64;
+        h = h.wrapping_mul(0x100000001b3);
+    }
+    h
+}
+
+/// Write `code` to `path` unless the file already holds the same content. Returns true if written.
+fn write_if_changed(path: &Path, code: &str) -> Result<bool> {
+    if let Ok(existing) = fs::read(path) {
+        if existing.len() == code.len() && content_hash(&existing) == content_hash(code.as_bytes()) {
+            return Ok(false);
+        }
+    }
+    fs::write(path, code).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(true)
+}
+
+/// Cargo sets OUT_DIR only for build scripts, so this is a no-op for the standalone CLI.
+fn emit_rerun_if_changed(path: &Path) {
+    if std::env::var_os("OUT_DIR").is_some() {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+}
+
 /// Create a new example app inside `examples/<name>` with minimal boilerplate.
 pub fn init_app(name: &str) -> Result<PathBuf> {
     let root = PathBuf::from("examples").join(name);
@@ -146,7 +368,7 @@ use velox_renderer::Renderer;
 
 include!(concat!(env!("OUT_DIR"), "/App.rs"));
 
-fn main() {
+fn main() -> Result<(), velox_renderer::Error> {
     // Create component state from script section
     let state = app::script_rs::State::new();
     let state_ref = std::sync::Arc::new(state);
@@ -157,52 +379,46 @@ fn main() {
     }};
     let on_event = { let state = state_ref.clone(); move |name: &str| { match name { "inc" => state.inc(), "dec" => state.dec(), _ => {} } } };
     let get_title = { let state = state_ref.clone(); move || state.title.borrow().to_string() };
-    velox_renderer::run_window_vnode("Velox App", make_view, on_event, get_title);
+    velox_renderer::run_window_vnode("Velox App", make_view, on_event, get_title)
 }
 "#;
     fs::write(src.join("main.rs"), main_rs).context("write main.rs")?;
     // Add to workspace members if present
     if let Err(e) = add_to_workspace_members(&PathBuf::from("Cargo.toml"), &format!("examples/{}", name)) {
-        eprintln!("warning: could not update workspace members: {e}");
+        log::warn!(target: "velox::cli", "could not update workspace members: {e}");
     }
     Ok(root)
 }
 
-/// Run an app package via cargo run -p <pkg>
-pub fn run_app(pkg: &str) -> Result<()> {
-    let status = Command::new("cargo").args(["run", "-p", pkg]).status()?;
+/// Run an app package via cargo run -p <pkg>, with the features and window defaults from `config`.
+pub fn run_app(pkg: &str, config: &ProjectConfig) -> Result<()> {
+    let status = cargo_command("run", pkg, config).status()?;
     if !status.success() { anyhow::bail!("app run failed") }
     Ok(())
 }
 
-/// Build an app package via cargo build
-pub fn build_app(pkg: &str, release: bool) -> Result<()> {
-    let mut args = vec!["build", "-p", pkg];
-    if release { args.push("--release"); }
-    let status = Command::new("cargo").args(&args).status()?;
+/// Build an app package via cargo build, with the features from `config`.
+pub fn build_app(pkg: &str, release: bool, config: &ProjectConfig) -> Result<()> {
+    let mut cmd = cargo_command("build", pkg, config);
+    if release { cmd.arg("--release"); }
+    let status = cmd.status()?;
     if !status.success() { anyhow::bail!("app build failed") }
     Ok(())
 }
 
-/// Crude polling-based dev server: runs `cargo run -p <pkg>` and restarts on file changes.
-pub fn dev_app(pkg: &str, watch_dir: &Path) -> Result<()> {
-    fn latest_mtime(dir: &Path) -> SystemTime {
-        fn walk(p: &Path, cur: &mut SystemTime) {
-            if let Ok(rd) = std::fs::read_dir(p) {
-                for e in rd.flatten() {
-                    let path = e.path();
-                    if path.is_dir() { walk(&path, cur); }
-                    else if let Ok(md) = e.metadata() {
-                        if let Ok(m) = md.modified() { if m > *cur { *cur = m; } }
-                    }
-                }
-            }
-        }
-        let mut t = SystemTime::UNIX_EPOCH;
-        walk(dir, &mut t);
-        t
-    }
+/// `cargo <subcommand> -p <pkg>` with `config`'s features and app environment.
+fn cargo_command(subcommand: &str, pkg: &str, config: &ProjectConfig) -> Command {
+    let mut cmd = Command::new("cargo");
+    cmd.args([subcommand, "-p", pkg]).args(config.cargo_feature_args()).envs(config.app_env());
+    cmd
+}
 
+/// Crude polling-based dev server: runs `cargo run -p <pkg>` and, when files under
+/// `watch_dirs` change, rebuilds and restarts it if the app depends on them (see `watch`).
+/// Checks every `config.dev.poll_interval`.
+pub fn dev_app(pkg: &str, watch_dirs: &[PathBuf], config: &ProjectConfig) -> Result<()> {
+    let workspace = watch::Workspace::find(&config.root)?;
+    let mut watcher = watch::Watcher::new(watch_dirs, config);
     let mut child: Option<Child> = None;
     // command channel: 'r' => full reload, 'q' => quit
     let (tx, rx) = mpsc::channel::<char>();
@@ -223,55 +439,59 @@ pub fn dev_app(pkg: &str, watch_dir: &Path) -> Result<()> {
             }
         }
     });
-    let mut last = latest_mtime(watch_dir);
 
     let mut spawn = || -> std::io::Result<Child> {
-        Command::new("cargo")
-            .args(["run", "-p", pkg])
+        cargo_command("run", pkg, config)
             .stdin(Stdio::null())
             .stdout(Stdio::inherit())
             .stderr(Stdio::inherit())
             .spawn()
     };
 
-    println!("[dev] Watching {} (press 'r' to reload, 'q' to quit)", watch_dir.display());
+    let watched = watch_dirs.iter().map(|d| d.display().to_string()).collect::<Vec<_>>().join(", ");
+    log::info!(target: "velox::dev", "watching {} (press 'r' to reload, 'q' to quit)", watched);
     child = Some(spawn()?);
     loop {
         // poll filesystem change
-        thread::sleep(Duration::from_millis(300));
-        let now = latest_mtime(watch_dir);
+        thread::sleep(config.dev.poll_interval);
         // handle stdin commands non-blocking
         if let Ok(cmd) = rx.try_recv() {
             match cmd {
                 'r' => {
-                    println!("[dev] Manual reload triggered (r)");
+                    log::info!(target: "velox::dev", "manual reload triggered (r)");
                     if let Some(mut c) = child.take() { let _ = c.kill(); let _ = c.wait(); }
-                    print!("[dev] Reloading");
-                    io::Write::flush(&mut io::stdout())?;
-                    // simple progress dots
-                    for _ in 0..5 { print!("."); io::Write::flush(&mut io::stdout())?; thread::sleep(Duration::from_millis(120)); }
-                    println!("");
                     child = Some(spawn()?);
-                    println!("[dev] Reloaded");
+                    log::info!(target: "velox::dev", "reloaded");
                 }
                 'q' => {
-                    println!("[dev] Quit requested");
+                    log::info!(target: "velox::dev", "quit requested");
                     if let Some(mut c) = child.take() { let _ = c.kill(); let _ = c.wait(); }
                     break;
                 }
                 _ => {}
             }
         }
-        if now > last {
-            println!("[dev] Change detected — reloading");
-            last = now;
-            if let Some(mut c) = child.take() { let _ = c.kill(); let _ = c.wait(); }
-            print!("[dev] Rebuilding");
-            io::Write::flush(&mut io::stdout())?;
-            for _ in 0..5 { print!("."); io::Write::flush(&mut io::stdout())?; thread::sleep(Duration::from_millis(120)); }
-            println!("");
-            child = Some(spawn()?);
-            println!("[dev] Restarted");
+        let changed = watcher.poll();
+        if !changed.is_empty() {
+            match watch::react(&changed, pkg, workspace.as_ref(), config) {
+                watch::Reaction::Ignore => {
+                    log::debug!(target: "velox::dev", "{} changed file(s) outside {}'s dependencies", changed.len(), pkg);
+                }
+                watch::Reaction::Assets(paths) => {
+                    log::info!(target: "velox::dev", "{} asset(s) changed — not restarting", paths.len());
+                }
+                watch::Reaction::Rebuild { packages } => {
+                    log::info!(target: "velox::dev", "{} changed — rebuilding", packages.join(", "));
+                    // Build while the old instance runs, so a compile error leaves it up.
+                    if cargo_command("build", pkg, config).status()?.success() {
+                        if let Some(mut c) = child.take() { let _ = c.kill(); let _ = c.wait(); }
+                        child = Some(spawn()?);
+                        log::info!(target: "velox::dev", "restarted");
+                    } else {
+                        log::warn!(target: "velox::dev", "build failed; keeping the running app");
+                    }
+                }
+            }
         }
         if let Some(c) = &mut child {
             if let Some(status) = c.try_wait()? {
@@ -0,0 +1,432 @@
+//! `velox-ls`: a language server for `.vx`/`.vue` components.
+//!
+//! Editors start the `velox-ls` binary and talk to it over stdio. It reuses the compiler's
+//! own passes, so what it reports is what `velox build` would:
+//!
+//! - diagnostics: syntax errors, stylesheet warnings, and `@event` handlers with no
+//!   method in the `<script setup>` that declares the component's `State`;
+//! - completions: directives in a tag, the `<style>` block's classes in `class="…"`, and
+//!   script methods in `@event="…"`;
+//! - go to definition: from a handler name in the template to its `fn` in the script.
+//!
+//! The analysis functions take the document text and LSP positions, so they can be tested
+//! without a client.
+
+use anyhow::Result;
+use lsp_server::{Connection, Message, Notification, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+    PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, Request as _};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionOptions, CompletionParams, CompletionResponse,
+    Diagnostic, DiagnosticSeverity, GotoDefinitionParams, GotoDefinitionResponse, Location, OneOf,
+    Position, PublishDiagnosticsParams, Range, ServerCapabilities, TextDocumentSyncCapability,
+    TextDocumentSyncKind, Url,
+};
+use std::collections::HashMap;
+
+/// Directives the template compiler understands, with what they do.
+pub const DIRECTIVES: &[(&str, &str)] = &[
+    ("v-if", "Render the element only while the condition holds"),
+    ("v-else-if", "Alternative branch of the `v-if` before it"),
+    (
+        "v-else",
+        "Render when no branch of the `v-if` chain before it does",
+    ),
+    (
+        "v-for",
+        "Repeat the element: `item in items` or `(item, index) in items`",
+    ),
+    ("v-show", "Show the element only while the condition holds"),
+    (
+        "v-model",
+        "Bind `value` and dispatch `update:<name>` on input",
+    ),
+    (
+        "v-shortcut",
+        "Keyboard shortcut for the element's handler, e.g. `Ctrl+S`",
+    ),
+    (
+        "v-click-outside",
+        "Handler to dispatch when a press lands outside the element",
+    ),
+];
+
+/// Problems `velox build` would report for the component `src`.
+pub fn diagnostics(src: &str) -> Vec<Diagnostic> {
+    let sfc = match velox_sfc::parse_sfc(src) {
+        Ok(sfc) => sfc,
+        Err(e) => return vec![error(sfc_error_position(&e, src), first_line(&e))],
+    };
+    let mut out = Vec::new();
+    if let Some(template) = block_body(src, "template") {
+        if let Err(e) = velox_sfc::parse_template_to_ast(&src[template.clone()]) {
+            out.push(error(offset_to_position(src, template.start), &e));
+        }
+    }
+    for d in crate::check_styles(src).unwrap_or_default() {
+        let start = Position::new(d.line.saturating_sub(1), d.column.saturating_sub(1));
+        out.push(Diagnostic {
+            range: Range::new(start, start),
+            severity: Some(DiagnosticSeverity::WARNING),
+            source: Some("velox".into()),
+            message: d.message,
+            ..Default::default()
+        });
+    }
+    // Handlers compile to `state.<name>()` calls only when the script declares a State.
+    let declares_state = sfc
+        .script_setup
+        .as_ref()
+        .is_some_and(|s| s.content.contains("struct State"));
+    if declares_state {
+        let methods = script_methods(src);
+        for (name, at) in handler_refs(src) {
+            if !methods.iter().any(|(m, _)| *m == name) {
+                out.push(Diagnostic {
+                    range: range(src, at),
+                    severity: Some(DiagnosticSeverity::ERROR),
+                    source: Some("velox".into()),
+                    message: format!("no method `{}` in <script setup>", name),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+    out
+}
+
+/// Completions at `position`: directives, classes or handler names (see the module docs).
+pub fn completions(src: &str, position: Position) -> Vec<CompletionItem> {
+    let offset = position_to_offset(src, position);
+    let Some(template) = block_body(src, "template") else {
+        return Vec::new();
+    };
+    if !(template.start..=template.end).contains(&offset) {
+        return Vec::new();
+    }
+    let before = &src[template.start..offset];
+    // Inside a tag when its `<` comes after the last `>`.
+    let Some(tag_start) = before
+        .rfind('<')
+        .filter(|&lt| before.rfind('>').is_none_or(|gt| gt < lt))
+    else {
+        return Vec::new();
+    };
+    let tag = &before[tag_start..];
+    if tag.matches('"').count() % 2 == 1 {
+        let attr = tag[..tag.rfind('"').unwrap_or(0)]
+            .trim_end_matches('=')
+            .rsplit([' ', '\t', '\n'])
+            .next()
+            .unwrap_or("");
+        return match attr {
+            "class" => style_classes(src)
+                .into_iter()
+                .map(|c| item(c, CompletionItemKind::CLASS, None))
+                .collect(),
+            a if a.starts_with('@') || a == "v-click-outside" => script_methods(src)
+                .into_iter()
+                .map(|(m, _)| item(m, CompletionItemKind::METHOD, None))
+                .collect(),
+            _ => Vec::new(),
+        };
+    }
+    // Completing a directive name, not the tag name itself.
+    let word = tag.rsplit([' ', '\t', '\n']).next().unwrap_or("");
+    if word.len() == tag.len() || !(word.is_empty() || word.starts_with('v')) {
+        return Vec::new();
+    }
+    DIRECTIVES
+        .iter()
+        .map(|(name, doc)| item(name.to_string(), CompletionItemKind::KEYWORD, Some(doc)))
+        .collect()
+}
+
+/// Where the handler named at `position` in the template is defined in the script.
+pub fn definition(src: &str, position: Position) -> Option<Range> {
+    let offset = position_to_offset(src, position);
+    let (name, _) = handler_refs(src)
+        .into_iter()
+        .find(|(_, at)| at.start <= offset && offset <= at.end)?;
+    let (_, at) = script_methods(src).into_iter().find(|(m, _)| *m == name)?;
+    Some(range(src, at))
+}
+
+/// Serve the protocol on stdin/stdout until the client shuts the server down.
+pub fn run() -> Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec!["-".into(), "\"".into(), " ".into()]),
+            ..Default::default()
+        }),
+        definition_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+    let mut documents: HashMap<Url, String> = HashMap::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let result = match request.method.as_str() {
+                    Completion::METHOD => {
+                        let params: CompletionParams = serde_json::from_value(request.params)?;
+                        let doc = &params.text_document_position.text_document.uri;
+                        let items = documents
+                            .get(doc)
+                            .map(|src| completions(src, params.text_document_position.position))
+                            .unwrap_or_default();
+                        serde_json::to_value(CompletionResponse::Array(items))?
+                    }
+                    GotoDefinition::METHOD => {
+                        let params: GotoDefinitionParams = serde_json::from_value(request.params)?;
+                        let doc = params.text_document_position_params.text_document.uri;
+                        let found = documents.get(&doc).and_then(|src| {
+                            definition(src, params.text_document_position_params.position)
+                        });
+                        serde_json::to_value(found.map(|range| {
+                            GotoDefinitionResponse::Scalar(Location::new(doc, range))
+                        }))?
+                    }
+                    _ => {
+                        let error = Response::new_err(
+                            request.id,
+                            lsp_server::ErrorCode::MethodNotFound as i32,
+                            format!("unsupported request {}", request.method),
+                        );
+                        connection.sender.send(error.into())?;
+                        continue;
+                    }
+                };
+                connection
+                    .sender
+                    .send(Response::new_ok(request.id, result).into())?;
+            }
+            Message::Notification(notification) => {
+                let (uri, text) = match notification.method.as_str() {
+                    DidOpenTextDocument::METHOD => {
+                        let params: lsp_types::DidOpenTextDocumentParams =
+                            serde_json::from_value(notification.params)?;
+                        (params.text_document.uri, Some(params.text_document.text))
+                    }
+                    DidChangeTextDocument::METHOD => {
+                        let params: lsp_types::DidChangeTextDocumentParams =
+                            serde_json::from_value(notification.params)?;
+                        (
+                            params.text_document.uri,
+                            params.content_changes.into_iter().last().map(|c| c.text),
+                        )
+                    }
+                    DidCloseTextDocument::METHOD => {
+                        let params: lsp_types::DidCloseTextDocumentParams =
+                            serde_json::from_value(notification.params)?;
+                        (params.text_document.uri, None)
+                    }
+                    _ => continue,
+                };
+                let diagnostics = text.as_deref().map(diagnostics).unwrap_or_default();
+                match text {
+                    Some(text) => documents.insert(uri.clone(), text),
+                    None => documents.remove(&uri),
+                };
+                let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+                let publish = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+                connection.sender.send(publish.into())?;
+            }
+            Message::Response(_) => {}
+        }
+    }
+    // The writer thread exits once every sender is gone.
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Byte range of the body of the first `<tag>` block.
+fn block_body(src: &str, tag: &str) -> Option<std::ops::Range<usize>> {
+    block_bodies(src, tag).next()
+}
+
+fn block_bodies<'a>(
+    src: &'a str,
+    tag: &'a str,
+) -> impl Iterator<Item = std::ops::Range<usize>> + 'a {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut from = 0;
+    std::iter::from_fn(move || {
+        let at = from + src[from..].find(&open)?;
+        let start = at + src[at..].find('>')? + 1;
+        let end = start + src[start..].find(&close)?;
+        from = end + close.len();
+        Some(start..end)
+    })
+}
+
+/// Handler names in the template (`@event="name"`, `v-click-outside="name"`) and where
+/// each one is.
+fn handler_refs(src: &str) -> Vec<(String, std::ops::Range<usize>)> {
+    let Some(template) = block_body(src, "template") else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    let mut rest = template.start;
+    while let Some(at) = src[rest..template.end].find(['@', 'v']).map(|i| rest + i) {
+        rest = at + 1;
+        let attr = &src[at..template.end];
+        let name_len = attr
+            .find('=')
+            .filter(|&eq| !attr[..eq].contains(char::is_whitespace));
+        let Some(eq) = name_len else { continue };
+        if !(attr.starts_with('@') || &attr[..eq] == "v-click-outside")
+            || !attr[eq + 1..].starts_with('"')
+        {
+            continue;
+        }
+        let start = at + eq + 2;
+        let Some(len) = src[start..template.end].find('"') else {
+            break;
+        };
+        let name = src[start..start + len].trim();
+        if !name.is_empty() {
+            let lead = src[start..].len() - src[start..].trim_start().len();
+            out.push((name.to_string(), start + lead..start + lead + name.len()));
+        }
+        rest = start + len;
+    }
+    out
+}
+
+/// Methods (`fn name`) of the component's scripts and where each name is.
+fn script_methods(src: &str) -> Vec<(String, std::ops::Range<usize>)> {
+    let mut out = Vec::new();
+    for body in block_bodies(src, "script") {
+        let mut rest = body.start;
+        while let Some(at) = src[rest..body.end].find("fn ").map(|i| rest + i) {
+            rest = at + 3;
+            if at > 0 && src[..at].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+                continue;
+            }
+            let name_start = at + 3 + (src[at + 3..].len() - src[at + 3..].trim_start().len());
+            let len = src[name_start..body.end]
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(body.end - name_start);
+            if len > 0 {
+                out.push((
+                    src[name_start..name_start + len].to_string(),
+                    name_start..name_start + len,
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// Class names the `<style>` block's selectors mention, sorted.
+fn style_classes(src: &str) -> Vec<String> {
+    let Some(style) = block_body(src, "style") else {
+        return Vec::new();
+    };
+    let mut classes = std::collections::BTreeSet::new();
+    let mut depth = 0;
+    let css = &src[style];
+    for (i, c) in css.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            '.' if depth == 0 => {
+                let name: String = css[i + 1..]
+                    .chars()
+                    .take_while(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                    .collect();
+                if !name.is_empty() {
+                    classes.insert(name);
+                }
+            }
+            _ => {}
+        }
+    }
+    classes.into_iter().collect()
+}
+
+fn item(label: String, kind: CompletionItemKind, detail: Option<&str>) -> CompletionItem {
+    CompletionItem {
+        label,
+        kind: Some(kind),
+        detail: detail.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+fn error(at: Position, message: &str) -> Diagnostic {
+    Diagnostic {
+        range: Range::new(at, at),
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("velox".into()),
+        message: message.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Position of a component syntax error: the parser reports it as ` --> line:column`.
+fn sfc_error_position(message: &str, src: &str) -> Position {
+    let at = message.split("--> ").nth(1).and_then(|rest| {
+        let (line, column) = rest.lines().next()?.split_once(':')?;
+        Some((
+            line.trim().parse::<u32>().ok()?,
+            column.trim().parse::<u32>().ok()?,
+        ))
+    });
+    match at {
+        Some((line, column)) => Position::new(line.saturating_sub(1), column.saturating_sub(1)),
+        None => offset_to_position(src, 0),
+    }
+}
+
+fn first_line(message: &str) -> &str {
+    message
+        .lines()
+        .find(|l| !l.trim().is_empty() && !l.contains("-->"))
+        .unwrap_or(message)
+        .trim()
+}
+
+fn range(src: &str, at: std::ops::Range<usize>) -> Range {
+    Range::new(
+        offset_to_position(src, at.start),
+        offset_to_position(src, at.end),
+    )
+}
+
+/// LSP positions count UTF-16 code units within a line.
+fn offset_to_position(src: &str, offset: usize) -> Position {
+    let before = &src[..offset];
+    let line = before.matches('\n').count() as u32;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position::new(line, before[line_start..].encode_utf16().count() as u32)
+}
+
+fn position_to_offset(src: &str, position: Position) -> usize {
+    let mut offset = 0;
+    for (i, line) in src.split_inclusive('\n').enumerate() {
+        if i as u32 == position.line {
+            let mut units = 0;
+            for (at, c) in line.char_indices() {
+                if units >= position.character || c == '\n' {
+                    return offset + at;
+                }
+                units += c.len_utf16() as u32;
+            }
+            return offset + line.len();
+        }
+        offset += line.len();
+    }
+    src.len()
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
+use velox_cli::ProjectConfig;
 
 #[derive(Parser)]
 #[command(name = "velox", version, about = "Velox CLI")]
@@ -13,42 +14,127 @@ struct Cli {
 enum Commands {
     /// Build a .vx/.vue Single File Component into Rust.
     Build {
-        /// Path to .vx/.vue file
-        input: PathBuf,
-        /// Output directory (default: target/velox-gen)
+        /// Path to a .vx/.vue file, or a directory to compile every component in it
+        /// (default: `build.components` from velox.toml)
+        input: Option<PathBuf>,
+        /// Output directory (default: `build.out_dir`, else target/velox-gen)
         #[arg(long)]
         out_dir: Option<PathBuf>,
-        /// What to emit: stub constants or a render() function
-        #[arg(long, value_enum, default_value_t = velox_cli::EmitMode::Stub)]
-        emit: velox_cli::EmitMode,
+        /// What to emit: stub constants or a render() function (default: `build.emit`, else stub)
+        #[arg(long, value_enum)]
+        emit: Option<velox_cli::EmitMode>,
     },
     /// Initialize a new Velox app under examples/<name>
     Init { name: String },
-    /// Run an app package (cargo run -p <pkg>)
-    Run { package: String },
-    /// Build an app package (cargo build -p <pkg>)
-    BuildApp { package: String, #[arg(long)] release: bool },
+    /// Run an app package (cargo run -p <pkg>; default: `app.package`)
+    Run { package: Option<String> },
+    /// Build an app package (cargo build -p <pkg>; default: `app.package`)
+    BuildApp { package: Option<String>, #[arg(long)] release: bool },
     /// Dev server: restart app on file changes (polling)
-    Dev { package: String, #[arg(long)] watch: Option<PathBuf> },
+    Dev {
+        package: Option<String>,
+        /// Directory to watch; repeatable (default: `dev.watch`, else the whole workspace)
+        #[arg(long)]
+        watch: Vec<PathBuf>,
+    },
+    /// Compile every component and run their tests headlessly (cargo test -p <pkg>;
+    /// default: `app.package`)
+    Test {
+        package: Option<String>,
+        /// Only run tests whose name contains this
+        #[arg(long)]
+        filter: Option<String>,
+        /// Rewrite snapshots and goldens that don't match
+        #[arg(long)]
+        update: bool,
+    },
+    /// Check GPU, native libraries, and fonts needed by the renderer backends
+    Doctor,
+}
+
+/// Prints `velox::*` log records as `[dev] message`; level from `RUST_LOG` (default info).
+struct CliLogger;
+
+impl log::Log for CliLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let tag = record.target().strip_prefix("velox::").unwrap_or(record.target());
+        match record.level() {
+            log::Level::Info => eprintln!("[{}] {}", tag, record.args()),
+            level => eprintln!("[{}] {}: {}", tag, level.as_str().to_ascii_lowercase(), record.args()),
+        }
+    }
+
+    fn flush(&self) {}
 }
 
 fn main() -> Result<()> {
+    let level = std::env::var("RUST_LOG").ok().and_then(|v| v.parse().ok()).unwrap_or(log::LevelFilter::Info);
+    if log::set_logger(&CliLogger).is_ok() {
+        log::set_max_level(level);
+    }
     let cli = Cli::parse();
+    let cwd = std::env::current_dir()?;
+    let config = match ProjectConfig::find(&cwd)? {
+        Some(config) => config,
+        // Without velox.toml the project is the cargo workspace.
+        None => ProjectConfig::empty(velox_cli::watch::Workspace::find(&cwd)?.map_or(cwd.clone(), |w| w.root)),
+    };
     match cli.command {
         Commands::Build {
             input,
             out_dir,
             emit,
-        } => velox_cli::build_cmd(&input, out_dir.as_deref(), emit)?,
+        } => {
+            let out_dir = out_dir.or_else(|| config.out_dir.clone());
+            let emit = emit.or(config.emit).unwrap_or(velox_cli::EmitMode::Stub);
+            let inputs = match input {
+                Some(input) => vec![input],
+                None if !config.components.is_empty() => config.components.clone(),
+                None => anyhow::bail!("no input given and velox.toml sets no `build.components`"),
+            };
+            for input in inputs {
+                if input.is_dir() {
+                    for out in velox_cli::build_dir(&input, out_dir.as_deref(), emit)? {
+                        let status = if out.written { "Generated" } else { "Up to date" };
+                        println!("{}: {}", status, out.path.display());
+                    }
+                } else {
+                    velox_cli::build_cmd(&input, out_dir.as_deref(), emit)?
+                }
+            }
+        }
         Commands::Init { name } => {
             let path = velox_cli::init_app(&name)?;
             println!("Initialized app at {}", path.display());
         }
-        Commands::Run { package } => velox_cli::run_app(&package)?,
-        Commands::BuildApp { package, release } => velox_cli::build_app(&package, release)?,
-        Commands::Dev { package, watch } => {
-            let dir = watch.unwrap_or_else(|| PathBuf::from(format!("examples/{}", package)));
-            velox_cli::dev_app(&package, &dir)?;
+        Commands::Run { package } => velox_cli::run_app(&config.package(package)?, &config)?,
+        Commands::BuildApp { package, release } => {
+            velox_cli::build_app(&config.package(package)?, release, &config)?
+        }
+        Commands::Dev { package, mut watch } => {
+            let package = config.package(package)?;
+            if watch.is_empty() {
+                watch = config.watch_dirs();
+            }
+            velox_cli::dev_app(&package, &watch, &config)?;
+        }
+        Commands::Test { package, filter, update } => {
+            let options = velox_cli::component_tests::TestOptions { filter, update };
+            velox_cli::component_tests::test_app(&config.package(package)?, &config, &options)?;
+        }
+        Commands::Doctor => {
+            let report = velox_cli::doctor::run_doctor(&cwd);
+            print!("{}", report);
+            if report.has_failures() {
+                anyhow::bail!("doctor found problems");
+            }
         }
     }
     Ok(())
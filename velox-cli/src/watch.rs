@@ -0,0 +1,389 @@
+//! What `velox dev` watches and what it does about a change.
+//!
+//! The dev server polls every file under its watch roots (the workspace by default) that
+//! passes `dev.include`/`dev.exclude` from `velox.toml`. Changed files are mapped to the
+//! workspace package that owns them:
+//!
+//! - a package the app doesn't depend on (another example, a tool) is left alone;
+//! - files under `build.assets` are read by the app at runtime, so they don't restart it;
+//! - anything else rebuilds the app, which recompiles only the changed packages, and
+//!   restarts it once the build succeeds.
+
+use anyhow::{Context, Result};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::ProjectConfig;
+
+/// Paths `velox dev` never watches, in addition to `dev.exclude`.
+pub const DEFAULT_EXCLUDE: &[&str] = &["**/target/**", "**/.git/**"];
+
+/// A workspace member and the workspace packages it depends on (build dependencies
+/// included: `velox-cli` runs in the apps' build scripts).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Package {
+    pub name: String,
+    pub dir: PathBuf,
+    pub deps: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub packages: Vec<Package>,
+}
+
+impl Workspace {
+    /// The workspace whose root `Cargo.toml` is in `start` or the nearest parent that has a
+    /// `[workspace]` table, or `None`.
+    pub fn find(start: &Path) -> Result<Option<Self>> {
+        for dir in start.ancestors() {
+            let manifest = dir.join("Cargo.toml");
+            if fs::read_to_string(&manifest)
+                .is_ok_and(|s| s.lines().any(|l| l.trim() == "[workspace]"))
+            {
+                return Self::load(dir).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Read the members of the workspace rooted at `root`. A trailing `/*` in a member
+    /// pattern stands for every directory in it.
+    pub fn load(root: &Path) -> Result<Self> {
+        let manifest = root.join("Cargo.toml");
+        let src = fs::read_to_string(&manifest)
+            .with_context(|| format!("failed to read {}", manifest.display()))?;
+        let mut dirs = Vec::new();
+        for member in workspace_members(&src) {
+            match member.strip_suffix("/*") {
+                Some(parent) => {
+                    let Ok(entries) = fs::read_dir(root.join(parent)) else {
+                        continue;
+                    };
+                    let mut found: Vec<_> = entries
+                        .flatten()
+                        .map(|e| e.path())
+                        .filter(|p| p.is_dir())
+                        .collect();
+                    found.sort();
+                    dirs.extend(found);
+                }
+                None => dirs.push(root.join(member)),
+            }
+        }
+        let mut packages = Vec::new();
+        let mut dep_dirs = Vec::new();
+        for dir in dirs {
+            // Members listed but missing on disk are cargo's problem, not the watcher's.
+            let Ok(src) = fs::read_to_string(dir.join("Cargo.toml")) else {
+                continue;
+            };
+            let Some(name) = package_name(&src) else {
+                continue;
+            };
+            dep_dirs.push(
+                path_dependencies(&src)
+                    .into_iter()
+                    .map(|p| normalize(&dir.join(p)))
+                    .collect::<Vec<_>>(),
+            );
+            packages.push(Package {
+                name,
+                dir: normalize(&dir),
+                deps: Vec::new(),
+            });
+        }
+        let names: HashMap<PathBuf, String> = packages
+            .iter()
+            .map(|p| (p.dir.clone(), p.name.clone()))
+            .collect();
+        for (package, dirs) in packages.iter_mut().zip(dep_dirs) {
+            package.deps = dirs.iter().filter_map(|d| names.get(d).cloned()).collect();
+        }
+        Ok(Workspace {
+            root: root.to_path_buf(),
+            packages,
+        })
+    }
+
+    /// The package whose directory holds `path` (the innermost, for nested members).
+    pub fn package_of(&self, path: &Path) -> Option<&Package> {
+        let path = normalize(path);
+        self.packages
+            .iter()
+            .filter(|p| path.starts_with(&p.dir))
+            .max_by_key(|p| p.dir.components().count())
+    }
+
+    /// `package` and every workspace package it depends on, directly or not.
+    pub fn dependencies(&self, package: &str) -> BTreeSet<String> {
+        let mut out = BTreeSet::new();
+        let mut stack = vec![package.to_string()];
+        while let Some(name) = stack.pop() {
+            if !out.insert(name.clone()) {
+                continue;
+            }
+            if let Some(p) = self.packages.iter().find(|p| p.name == name) {
+                stack.extend(p.deps.iter().cloned());
+            }
+        }
+        out
+    }
+}
+
+/// What the dev server does about a batch of changed files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Reaction {
+    /// Nothing the app is built from changed.
+    Ignore,
+    /// Only runtime assets changed; the running app keeps going.
+    Assets(Vec<PathBuf>),
+    /// Rebuild and restart the app; `packages` are the changed ones it depends on.
+    Rebuild { packages: Vec<String> },
+}
+
+/// Decide what `changed` means for `app` (see the module docs). Without a workspace every
+/// change that isn't an asset rebuilds.
+pub fn react(
+    changed: &[PathBuf],
+    app: &str,
+    workspace: Option<&Workspace>,
+    config: &ProjectConfig,
+) -> Reaction {
+    let deps = workspace.map(|w| w.dependencies(app));
+    let mut assets = Vec::new();
+    let mut packages = BTreeSet::new();
+    for path in changed {
+        if config
+            .assets
+            .iter()
+            .any(|dir| normalize(path).starts_with(normalize(dir)))
+        {
+            assets.push(path.clone());
+            continue;
+        }
+        match (workspace, &deps) {
+            (Some(workspace), Some(deps)) => match workspace.package_of(path) {
+                Some(p) if deps.contains(&p.name) => {
+                    packages.insert(p.name.clone());
+                }
+                // The workspace manifest and lockfile affect every package.
+                None if path
+                    .parent()
+                    .is_some_and(|d| normalize(d) == normalize(&workspace.root)) =>
+                {
+                    packages.insert(app.to_string());
+                }
+                _ => {}
+            },
+            _ => {
+                packages.insert(app.to_string());
+            }
+        }
+    }
+    if !packages.is_empty() {
+        Reaction::Rebuild {
+            packages: packages.into_iter().collect(),
+        }
+    } else if !assets.is_empty() {
+        Reaction::Assets(assets)
+    } else {
+        Reaction::Ignore
+    }
+}
+
+/// Modification times of the watched files, to diff against the next poll.
+#[derive(Debug)]
+pub struct Watcher {
+    roots: Vec<PathBuf>,
+    base: PathBuf,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    files: HashMap<PathBuf, SystemTime>,
+}
+
+impl Watcher {
+    /// Watch the files under `roots` that pass `config`'s include/exclude patterns, which
+    /// are matched against paths relative to the project root.
+    pub fn new(roots: &[PathBuf], config: &ProjectConfig) -> Self {
+        let exclude = DEFAULT_EXCLUDE
+            .iter()
+            .map(|s| s.to_string())
+            .chain(config.dev.exclude.iter().cloned());
+        let mut watcher = Watcher {
+            roots: roots.to_vec(),
+            base: config.root.clone(),
+            include: config.dev.include.clone(),
+            exclude: exclude.collect(),
+            files: HashMap::new(),
+        };
+        watcher.files = watcher.scan();
+        watcher
+    }
+
+    /// Files added, modified, or removed since the last call, sorted.
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        let files = self.scan();
+        let mut changed: Vec<PathBuf> = files
+            .iter()
+            .filter(|(path, mtime)| self.files.get(*path) != Some(mtime))
+            .map(|(path, _)| path.clone())
+            .chain(
+                self.files
+                    .keys()
+                    .filter(|path| !files.contains_key(*path))
+                    .cloned(),
+            )
+            .collect();
+        changed.sort();
+        self.files = files;
+        changed
+    }
+
+    pub fn is_watched(&self, path: &Path) -> bool {
+        let rel = path.strip_prefix(&self.base).unwrap_or(path);
+        let rel = rel.to_string_lossy().replace('\\', "/");
+        let matches = |pattern: &String| path_matches(pattern, &rel);
+        !self.exclude.iter().any(matches)
+            && (self.include.is_empty() || self.include.iter().any(matches))
+    }
+
+    fn scan(&self) -> HashMap<PathBuf, SystemTime> {
+        fn walk(watcher: &Watcher, dir: &Path, out: &mut HashMap<PathBuf, SystemTime>) {
+            let Ok(entries) = fs::read_dir(dir) else {
+                return;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if path.is_dir() {
+                    // Skip excluded trees (target/) without walking them.
+                    let rel = path
+                        .strip_prefix(&watcher.base)
+                        .unwrap_or(&path)
+                        .to_string_lossy()
+                        .replace('\\', "/");
+                    if !watcher
+                        .exclude
+                        .iter()
+                        .any(|p| path_matches(p, &format!("{}/", rel)))
+                    {
+                        walk(watcher, &path, out);
+                    }
+                } else if watcher.is_watched(&path) {
+                    if let Ok(mtime) = entry.metadata().and_then(|m| m.modified()) {
+                        out.insert(path, mtime);
+                    }
+                }
+            }
+        }
+        let mut files = HashMap::new();
+        for root in &self.roots {
+            walk(self, root, &mut files);
+        }
+        files
+    }
+}
+
+/// Match a `/`-separated relative path against a glob: `*` and `?` stay within a path
+/// segment, `**` spans segments. A pattern without `/` matches the file name at any depth.
+pub fn path_matches(pattern: &str, path: &str) -> bool {
+    if !pattern.contains('/') {
+        return path
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .is_some_and(|name| glob(pattern.as_bytes(), name.as_bytes()));
+    }
+    glob(pattern.as_bytes(), path.as_bytes())
+}
+
+fn glob(p: &[u8], s: &[u8]) -> bool {
+    match p {
+        [] => s.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            // `**/` may also match no directories at all.
+            let rest = rest.strip_prefix(b"/").unwrap_or(rest);
+            (0..=s.len()).any(|i| glob(rest, &s[i..]))
+        }
+        [b'*', rest @ ..] => (0..=s.len())
+            .take_while(|&i| i == 0 || s[i - 1] != b'/')
+            .any(|i| glob(rest, &s[i..])),
+        [b'?', rest @ ..] => matches!(s, [c, tail @ ..] if *c != b'/' && glob(rest, tail)),
+        [c, rest @ ..] => matches!(s, [d, tail @ ..] if c == d && glob(rest, tail)),
+    }
+}
+
+/// Lexically drop `.` and `..` components, so `examples/todo/../../velox-core` and
+/// `velox-core` compare equal.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir if out.file_name().is_some() => {
+                out.pop();
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn workspace_members(manifest: &str) -> Vec<String> {
+    let Some(start) = manifest.find("members") else {
+        return Vec::new();
+    };
+    let rest = &manifest[start..];
+    let (Some(open), Some(close)) = (rest.find('['), rest.find(']')) else {
+        return Vec::new();
+    };
+    rest[open + 1..close]
+        .split(',')
+        .filter_map(unquote)
+        .collect()
+}
+
+fn package_name(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+        } else if in_package {
+            if let Some(("name", value)) = line.split_once('=').map(|(k, v)| (k.trim(), v)) {
+                return unquote(value);
+            }
+        }
+    }
+    None
+}
+
+/// `path = "..."` of the entries in `[dependencies]`, `[build-dependencies]` and their
+/// `[target.*]` forms. Dev-dependencies don't affect the app.
+fn path_dependencies(manifest: &str) -> Vec<String> {
+    let mut in_deps = false;
+    let mut out = Vec::new();
+    for line in manifest.lines().map(str::trim) {
+        if line.starts_with('[') {
+            let table = line.trim_matches(|c| c == '[' || c == ']');
+            in_deps = table.ends_with("dependencies") && !table.ends_with("dev-dependencies");
+        } else if in_deps {
+            if let Some(at) = line.find("path") {
+                let value = line[at + 4..].trim_start().strip_prefix('=').unwrap_or("");
+                if let Some(path) = value.split(['}', ',']).next().and_then(unquote) {
+                    out.push(path);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn unquote(s: &str) -> Option<String> {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .map(str::to_string)
+}
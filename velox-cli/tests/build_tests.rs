@@ -35,3 +35,100 @@ fn cli_build_emits_render_fn() {
     assert!(content.contains("pub fn render()"), "render mode should include render() fn");
 }
 
+#[test]
+fn cli_build_skips_unchanged_output() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let input = PathBuf::from(manifest_dir).join("../examples/todo/src/App.vx");
+
+    let out_dir = PathBuf::from(manifest_dir)
+        .join("../target/velox-cli-tests")
+        .join(format!("{}-cache", std::process::id()));
+
+    let first = velox_cli::build_file(&input, Some(out_dir.as_path()), velox_cli::EmitMode::Render)
+        .expect("first build");
+    assert!(first.written, "first build should write output");
+
+    let second = velox_cli::build_file(&input, Some(out_dir.as_path()), velox_cli::EmitMode::Render)
+        .expect("second build");
+    assert!(!second.written, "unchanged component should not be rewritten");
+    assert_eq!(first.path, second.path);
+}
+
+#[test]
+fn cli_build_dir_compiles_all_components() {
+    let manifest_dir = env!("CARGO_MANIFEST_DIR");
+    let src_dir = PathBuf::from(manifest_dir).join("../examples/todo/src");
+
+    let out_dir = PathBuf::from(manifest_dir)
+        .join("../target/velox-cli-tests")
+        .join(format!("{}-dir", std::process::id()));
+
+    let outputs = velox_cli::build_dir(&src_dir, Some(out_dir.as_path()), velox_cli::EmitMode::Stub)
+        .expect("build dir");
+    assert!(outputs.iter().any(|o| o.path.ends_with("App.rs")));
+}
+
+#[test]
+fn check_styles_reports_positions_in_the_component_file() {
+    let src = "<template><div class=\"a\">x</div></template>\n<style>\n.a { color: red;\n  colr: blue; }\n</style>\n";
+    let diagnostics = velox_cli::check_styles(src).expect("parse component");
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert_eq!((diagnostics[0].line, diagnostics[0].column), (4, 9));
+    assert!(diagnostics[0].message.contains("colr"));
+}
+
+#[test]
+fn cli_build_runs_plugins_enabled_in_velox_toml() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../target/velox-cli-tests")
+        .join(format!("{}-plugins", std::process::id()));
+    let src = root.join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(root.join("velox.toml"), "[build]\nplugins = [\"markdown\"]\n").unwrap();
+    let input = src.join("Docs.vx");
+    fs::write(&input, "<template><markdown>\n# Guide\n</markdown></template>\n").unwrap();
+
+    let out = velox_cli::build_file(&input, Some(root.join("gen").as_path()), velox_cli::EmitMode::Render)
+        .expect("build with plugins");
+    let code = fs::read_to_string(out.path).unwrap();
+    assert!(code.contains(r#"h("h1""#), "markdown plugin should run: {}", code);
+}
+
+#[test]
+fn cli_build_writes_the_component_interface() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../target/velox-cli-tests")
+        .join(format!("{}-interface", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    let input = root.join("Counter.vx");
+    fs::write(&input, "<template><button @click=\"inc\">{{ count }}</button></template>\n").unwrap();
+
+    velox_cli::build_file(&input, Some(root.join("gen").as_path()), velox_cli::EmitMode::Render).expect("build");
+    let json = fs::read_to_string(root.join("gen/Counter.json")).expect("interface written next to the module");
+    assert!(json.contains(r#""name": "Counter""#), "{}", json);
+    assert!(json.contains(r#""props": ["count"]"#), "{}", json);
+    assert!(json.contains(r#""events": ["inc"]"#), "{}", json);
+}
+
+#[test]
+fn cli_build_embeds_only_the_styles_a_component_can_match() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../target/velox-cli-tests")
+        .join(format!("{}-styles", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("velox.toml"), "[build]\nstyles = [\"app.css\"]\n").unwrap();
+    fs::write(root.join("app.css"), ".btn { color: red; }\n.modal .btn { color: blue; }\ntable td { padding: 2px; }\n").unwrap();
+    let input = root.join("Toolbar.vx");
+    fs::write(&input, "<template><div class=\"bar\"><button class=\"btn\">Save</button></div></template>\n<style>\n.bar { display: flex; }\n.bar:hover .icon { opacity: 1; }\n</style>\n").unwrap();
+
+    let out = velox_cli::build_file(&input, Some(root.join("gen").as_path()), velox_cli::EmitMode::Render).expect("build");
+    let code = fs::read_to_string(out.path).unwrap();
+    assert!(code.contains(".btn { color: red; }\n.bar { display: flex; }\n\"#"), "{}", code);
+    for unused in [".modal", "td {", ".icon"] {
+        assert!(!code.contains(unused), "{} should be left out: {}", unused, code);
+    }
+
+    fs::write(root.join("velox.toml"), "[build]\nstyles = [\"app.css\"]\nprune_styles = false\n").unwrap();
+    let out = velox_cli::build_file(&input, Some(root.join("gen").as_path()), velox_cli::EmitMode::Render).expect("build");
+    assert!(fs::read_to_string(out.path).unwrap().contains("table td { padding: 2px; }"));
+}
@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::PathBuf;
+
+use velox_cli::component_tests::compile_components;
+
+#[test]
+fn components_are_compiled_with_their_tests() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../target/velox-cli-tests")
+        .join(format!("{}-component-tests", std::process::id()));
+    let src = root.join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(
+        src.join("Counter.vx"),
+        "<template><button @click=\"inc\">+</button></template>\n<test>\n#[test]\nfn one() {}\n#[test]\nfn two() {}\n</test>\n",
+    )
+    .unwrap();
+    fs::write(
+        src.join("Badge.vx"),
+        "<template><span>new</span></template>\n",
+    )
+    .unwrap();
+    fs::write(src.join("Badge.test.rs"), "#[test]\nfn badge() {}\n").unwrap();
+    fs::write(src.join("Plain.vx"), "<template><p>plain</p></template>\n").unwrap();
+
+    let out = root.join("gen");
+    let found = compile_components(std::slice::from_ref(&src), &out).expect("compile");
+    let summary: Vec<_> = found
+        .iter()
+        .map(|c| {
+            (
+                c.component.file_name().unwrap().to_str().unwrap(),
+                c.inline,
+                c.file.is_some(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("Badge.vx", 0, true),
+            ("Counter.vx", 2, false),
+            ("Plain.vx", 0, false)
+        ]
+    );
+
+    let counter = fs::read_to_string(out.join("Counter.rs")).unwrap();
+    assert!(counter.contains("mod velox_tests") && counter.contains("fn two() {}"));
+    let badge = fs::read_to_string(out.join("Badge.rs")).unwrap();
+    assert!(badge.contains("include!(") && badge.contains("Badge.test.rs"));
+    assert!(!fs::read_to_string(out.join("Plain.rs"))
+        .unwrap()
+        .contains("velox_tests"));
+}
@@ -0,0 +1,76 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use velox_cli::{EmitMode, ProjectConfig};
+
+const SAMPLE: &str = r#"
+# project defaults
+[build]
+components = ["examples/notes/src"]
+assets = ["examples/notes/assets"]
+emit = "render"
+
+[app]
+package = "notes"
+features = ["wgpu", "raster"]
+backend = "skia"
+
+[window]
+title = "Notes # draft"
+width = 1024
+
+[dev]
+poll_ms = 150
+"#;
+
+#[test]
+fn velox_toml_sets_cli_defaults() {
+    let config = ProjectConfig::parse(SAMPLE, Path::new("/proj")).expect("parse");
+    assert_eq!(
+        config.components,
+        vec![PathBuf::from("/proj/examples/notes/src")]
+    );
+    assert_eq!(config.emit, Some(EmitMode::Render));
+    assert_eq!(config.package(None).unwrap(), "notes");
+    assert_eq!(config.package(Some("todo".into())).unwrap(), "todo");
+    assert_eq!(
+        config.cargo_feature_args(),
+        vec!["--features", "wgpu,raster"]
+    );
+    assert_eq!(
+        config.app_env(),
+        vec![
+            ("VELOX_BACKEND", "skia".to_string()),
+            ("VELOX_WINDOW_TITLE", "Notes # draft".to_string()),
+            ("VELOX_WINDOW_SIZE", "1024x600".to_string()),
+        ]
+    );
+    // Without `dev.watch`, dev watches the whole project.
+    assert_eq!(config.watch_dirs(), vec![PathBuf::from("/proj")]);
+    assert_eq!(config.dev.poll_interval, Duration::from_millis(150));
+}
+
+#[test]
+fn velox_toml_errors_name_the_line() {
+    let err = ProjectConfig::parse("[build]\ncomponets = [\"src\"]\n", Path::new(".")).unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains("line 2") && message.contains("build.componets"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn velox_toml_is_found_in_a_parent_directory() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../target/velox-cli-tests")
+        .join(format!("{}-config", std::process::id()));
+    let nested = root.join("examples/notes/src");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(root.join("velox.toml"), "[app]\npackage = \"notes\"\n").unwrap();
+
+    let config = ProjectConfig::find(&nested).unwrap().expect("velox.toml");
+    assert_eq!(config.root, root);
+    assert_eq!(config.package.as_deref(), Some("notes"));
+}
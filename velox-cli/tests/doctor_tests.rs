@@ -0,0 +1,37 @@
+use std::fs;
+use std::path::PathBuf;
+
+#[test]
+fn doctor_reports_workspace_renderer_features() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../target/velox-cli-tests")
+        .join(format!("{}-doctor", std::process::id()));
+    fs::create_dir_all(root.join("app")).unwrap();
+    fs::write(
+        root.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"app\", \"gone\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("app/Cargo.toml"),
+        "[package]\nname = \"app\"\n\n[dependencies]\nvelox-renderer = { path = \"../r\", features = [\"skia-native\"] }\n",
+    )
+    .unwrap();
+
+    let report = velox_cli::doctor::run_doctor(&root.join("app"));
+    assert_eq!(report.workspace_root.as_deref(), Some(root.as_path()));
+
+    let app = report
+        .checks
+        .iter()
+        .find(|c| c.name == "member app")
+        .expect("app check");
+    assert!(app.detail.contains("skia-native"));
+    let gone = report
+        .checks
+        .iter()
+        .find(|c| c.name == "member gone")
+        .expect("gone check");
+    assert_eq!(gone.status, velox_cli::doctor::CheckStatus::Fail);
+    assert!(report.to_string().contains("fix:"));
+}
@@ -0,0 +1,70 @@
+use lsp_types::{DiagnosticSeverity, Position};
+use velox_cli::ls;
+
+const COUNTER: &str = r#"<template>
+  <div class="app">
+    <button class="btn" @click="inc">+</button>
+    <button @click="reset">0</button>
+  </div>
+</template>
+
+<script setup>
+pub struct State { pub count: velox_core::Signal<i32> }
+impl State {
+    pub fn inc(&self) {}
+}
+</script>
+
+<style>
+.app { color: red; }
+.btn, .btn-primary:hover { colr: blue; }
+</style>
+"#;
+
+#[test]
+fn diagnostics_report_missing_handlers_and_style_warnings() {
+    let diagnostics = ls::diagnostics(COUNTER);
+    let missing = diagnostics
+        .iter()
+        .find(|d| d.message.contains("reset"))
+        .expect("missing handler reported");
+    assert_eq!(missing.severity, Some(DiagnosticSeverity::ERROR));
+    assert_eq!(missing.range.start, Position::new(3, 20));
+    assert!(!diagnostics.iter().any(|d| d.message.contains("`inc`")));
+    let style = diagnostics
+        .iter()
+        .find(|d| d.message.contains("colr"))
+        .expect("style warning reported");
+    assert_eq!(style.range.start.line, 16);
+
+    let broken = ls::diagnostics("<template><div></div></template>\n<script");
+    assert_eq!(broken.len(), 1);
+    assert_eq!(broken[0].severity, Some(DiagnosticSeverity::ERROR));
+}
+
+#[test]
+fn completions_offer_directives_classes_and_methods() {
+    let labels = |line: u32, character: u32| -> Vec<String> {
+        ls::completions(COUNTER, Position::new(line, character))
+            .into_iter()
+            .map(|i| i.label)
+            .collect()
+    };
+    // `<div |class="app">`
+    assert!(labels(1, 7).contains(&"v-click-outside".to_string()));
+    // `<div class="|app">`
+    assert_eq!(labels(1, 14), ["app", "btn", "btn-primary"]);
+    // `@click="|inc"`
+    assert_eq!(labels(2, 32), ["inc"]);
+    // text content and the tag name itself get nothing
+    assert!(labels(2, 42).is_empty());
+    assert!(labels(1, 4).is_empty());
+}
+
+#[test]
+fn definition_jumps_from_handler_to_script_method() {
+    let target = ls::definition(COUNTER, Position::new(2, 34)).expect("inc is defined");
+    assert_eq!(target.start, Position::new(10, 11));
+    assert_eq!(target.end, Position::new(10, 14));
+    assert_eq!(ls::definition(COUNTER, Position::new(3, 22)), None);
+}
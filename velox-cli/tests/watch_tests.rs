@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use velox_cli::watch::{path_matches, react, Reaction, Watcher, Workspace};
+use velox_cli::ProjectConfig;
+
+fn fixture(name: &str) -> PathBuf {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../target/velox-cli-tests")
+        .join(format!("{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&root);
+    let write = |path: &str, contents: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    };
+    write(
+        "Cargo.toml",
+        "[workspace]\nmembers = [\"core\", \"examples/*\"]\n",
+    );
+    write("core/Cargo.toml", "[package]\nname = \"core\"\n");
+    write("core/src/lib.rs", "");
+    write(
+        "examples/app/Cargo.toml",
+        "[package]\nname = \"app\"\n\n[dependencies]\ncore = { path = \"../../core\" }\n",
+    );
+    write("examples/app/src/main.rs", "");
+    write("examples/app/assets/logo.svg", "");
+    write("examples/other/Cargo.toml", "[package]\nname = \"other\"\n");
+    write("examples/other/src/main.rs", "");
+    root
+}
+
+#[test]
+fn globs_match_relative_paths() {
+    assert!(path_matches("**/*.rs", "examples/app/src/main.rs"));
+    assert!(path_matches("**/*.rs", "build.rs"));
+    assert!(path_matches("*.swp", "core/src/.lib.rs.swp"));
+    assert!(path_matches("**/target/**", "examples/app/target/"));
+    assert!(!path_matches("core/*.rs", "core/src/lib.rs"));
+    assert!(path_matches("core/?rc/**", "core/src/lib.rs"));
+}
+
+#[test]
+fn changes_rebuild_only_when_the_app_depends_on_them() {
+    let root = fixture("react");
+    let workspace = Workspace::load(&root).unwrap();
+    assert_eq!(
+        workspace
+            .dependencies("app")
+            .into_iter()
+            .collect::<Vec<_>>(),
+        ["app", "core"]
+    );
+
+    let mut config = ProjectConfig::empty(&root);
+    config.assets = vec![root.join("examples/app/assets")];
+    let react = |path: &str| react(&[root.join(path)], "app", Some(&workspace), &config);
+    assert_eq!(
+        react("core/src/lib.rs"),
+        Reaction::Rebuild {
+            packages: vec!["core".into()]
+        }
+    );
+    assert_eq!(react("examples/other/src/main.rs"), Reaction::Ignore);
+    assert_eq!(
+        react("examples/app/assets/logo.svg"),
+        Reaction::Assets(vec![root.join("examples/app/assets/logo.svg")])
+    );
+}
+
+#[test]
+fn watcher_reports_new_files_and_skips_excluded_ones() {
+    let root = fixture("poll");
+    let mut config = ProjectConfig::empty(&root);
+    config.dev.exclude = vec!["*.tmp".into()];
+    let mut watcher = Watcher::new(std::slice::from_ref(&root), &config);
+    assert!(watcher.poll().is_empty());
+
+    let touch = |path: &str| {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "x").unwrap();
+    };
+    touch("core/src/new.rs");
+    touch("core/src/scratch.tmp");
+    touch("target/debug/app");
+    assert_eq!(watcher.poll(), vec![root.join("core/src/new.rs")]);
+    assert!(!watcher.is_watched(Path::new("notes.tmp")));
+}
@@ -0,0 +1,223 @@
+// velox-core/src/animate.rs
+//! Spring animations that drive signals from the frame clock.
+//!
+//! `animate(&signal, target, spring)` moves a signal toward `target` with a damped
+//! spring. Each frame, `frame::tick` calls `tick(now)`, which integrates the running springs
+//! and `set()`s their signals, so effects and views see the in-between values.
+//! Animating a signal that is already moving retargets it and keeps its velocity.
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::signal::Signal;
+
+/// Damped spring parameters, with a mass of 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+}
+
+impl Default for Spring {
+    fn default() -> Self {
+        Spring {
+            stiffness: 170.0,
+            damping: 26.0,
+        }
+    }
+}
+
+impl Spring {
+    pub const GENTLE: Spring = Spring {
+        stiffness: 120.0,
+        damping: 14.0,
+    };
+    pub const WOBBLY: Spring = Spring {
+        stiffness: 180.0,
+        damping: 12.0,
+    };
+    pub const STIFF: Spring = Spring {
+        stiffness: 210.0,
+        damping: 20.0,
+    };
+}
+
+/// A value a spring can move: a fixed number of `f32` components.
+pub trait Animatable: Clone + 'static {
+    fn components(&self) -> Vec<f32>;
+    fn from_components(c: &[f32]) -> Self;
+}
+
+impl Animatable for f32 {
+    fn components(&self) -> Vec<f32> {
+        vec![*self]
+    }
+    fn from_components(c: &[f32]) -> Self {
+        c[0]
+    }
+}
+
+impl Animatable for f64 {
+    fn components(&self) -> Vec<f32> {
+        vec![*self as f32]
+    }
+    fn from_components(c: &[f32]) -> Self {
+        c[0] as f64
+    }
+}
+
+impl Animatable for (f32, f32) {
+    fn components(&self) -> Vec<f32> {
+        vec![self.0, self.1]
+    }
+    fn from_components(c: &[f32]) -> Self {
+        (c[0], c[1])
+    }
+}
+
+impl<const N: usize> Animatable for [f32; N] {
+    fn components(&self) -> Vec<f32> {
+        self.to_vec()
+    }
+    fn from_components(c: &[f32]) -> Self {
+        std::array::from_fn(|i| c[i])
+    }
+}
+
+/// Distance and speed below which a spring snaps to its target and stops.
+const REST: f32 = 1e-3;
+/// Integration step; frames are split into steps of at most this length.
+const STEP: f32 = 1.0 / 240.0;
+/// Longest frame gap integrated at once, so a stalled frame does not fling values.
+const MAX_FRAME: Duration = Duration::from_millis(64);
+
+type Writer = Rc<dyn Fn(&[f32])>;
+
+struct Running {
+    /// `Rc::as_ptr` of the driven signal, to retarget instead of stacking springs.
+    key: usize,
+    id: u64,
+    position: Vec<f32>,
+    velocity: Vec<f32>,
+    target: Vec<f32>,
+    spring: Spring,
+    last: Option<Instant>,
+    write: Writer,
+}
+
+impl Running {
+    /// Advance by `dt` seconds; returns true once at rest on the target.
+    fn step(&mut self, dt: f32) -> bool {
+        let mut left = dt;
+        while left > 0.0 {
+            let h = left.min(STEP);
+            left -= h;
+            for i in 0..self.position.len() {
+                let force = -self.spring.stiffness * (self.position[i] - self.target[i])
+                    - self.spring.damping * self.velocity[i];
+                self.velocity[i] += force * h;
+                self.position[i] += self.velocity[i] * h;
+            }
+        }
+        let settled = self
+            .position
+            .iter()
+            .zip(&self.target)
+            .zip(&self.velocity)
+            .all(|((p, t), v)| (p - t).abs() < REST && v.abs() < REST);
+        if settled {
+            self.position.clone_from(&self.target);
+        }
+        settled
+    }
+}
+
+thread_local! {
+    static RUNNING: RefCell<Vec<Running>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(1) };
+}
+
+/// Handle to a started animation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AnimationHandle {
+    id: u64,
+}
+
+impl AnimationHandle {
+    /// Stop the animation, leaving the signal at its current value.
+    pub fn cancel(&self) {
+        RUNNING.with(|r| r.borrow_mut().retain(|a| a.id != self.id));
+    }
+
+    /// Whether the animation is still moving its signal.
+    pub fn is_running(&self) -> bool {
+        RUNNING.with(|r| r.borrow().iter().any(|a| a.id == self.id))
+    }
+}
+
+/// Move `signal` toward `target` with `spring`, one step per `tick`. If the signal is
+/// already animating, its spring is retargeted and keeps its current velocity.
+pub fn animate<T: Animatable>(
+    signal: &Rc<Signal<T>>,
+    target: T,
+    spring: Spring,
+) -> AnimationHandle {
+    let key = Rc::as_ptr(signal) as *const () as usize;
+    let target = target.components();
+    RUNNING.with(|r| {
+        let mut running = r.borrow_mut();
+        if let Some(a) = running.iter_mut().find(|a| a.key == key) {
+            a.target = target;
+            a.spring = spring;
+            return AnimationHandle { id: a.id };
+        }
+        let id = NEXT_ID.with(|n| n.replace(n.get() + 1));
+        let position = signal.get().components();
+        let weak = Rc::downgrade(signal);
+        running.push(Running {
+            key,
+            id,
+            velocity: vec![0.0; position.len()],
+            position,
+            target,
+            spring,
+            last: None,
+            write: Rc::new(move |c: &[f32]| {
+                if let Some(signal) = weak.upgrade() {
+                    signal.set(T::from_components(c));
+                }
+            }),
+        });
+        AnimationHandle { id }
+    })
+}
+
+/// Advance every running animation to `now` and write the new values into their
+/// signals. Called by `frame::tick` once per frame; returns true while any animation is
+/// still running, i.e. another frame is needed.
+pub fn tick(now: Instant) -> bool {
+    let writes: Vec<(Writer, Vec<f32>)> = RUNNING.with(|r| {
+        let mut writes = Vec::new();
+        r.borrow_mut().retain_mut(|a| {
+            let dt = match a.last.replace(now) {
+                Some(last) => now.saturating_duration_since(last).min(MAX_FRAME),
+                None => return true,
+            };
+            let done = a.step(dt.as_secs_f32());
+            writes.push((a.write.clone(), a.position.clone()));
+            !done
+        });
+        writes
+    });
+    // Write after releasing the list: effects run by `set` may start or cancel animations.
+    for (write, position) in writes {
+        write(&position);
+    }
+    is_running()
+}
+
+/// Whether any spring animation is running.
+pub fn is_running() -> bool {
+    RUNNING.with(|r| !r.borrow().is_empty())
+}
@@ -0,0 +1,78 @@
+// velox-core/src/clock.rs
+//! The time animations run on.
+//!
+//! Transitions, layout animations, spring and `on_frame` callbacks, and undo coalescing
+//! read the time from `now()`. It follows the system clock unless a test installs a
+//! manual `Clock` on its thread, which stands still until advanced: `advance` moves time
+//! forward in `FRAME_STEP` frames, running each, so animation-dependent behavior is the
+//! same on every run, however slow the machine.
+//!
+//! Frame pacing and idle deadlines stay on the system clock: they schedule real work.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+/// The frame length `advance` steps by: 60 Hz.
+pub const FRAME_STEP: Duration = Duration::from_micros(16_667);
+
+thread_local! {
+    static MANUAL: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// The current time for animations on this thread: the manual clock's, if one is
+/// installed, else the system's.
+pub fn now() -> Instant {
+    MANUAL.with(|m| m.get()).unwrap_or_else(Instant::now)
+}
+
+/// Whether a manual clock is installed on this thread.
+pub fn is_manual() -> bool {
+    MANUAL.with(|m| m.get().is_some())
+}
+
+/// A manual clock, installed on this thread until dropped.
+#[derive(Debug)]
+pub struct Clock {
+    // Installed per thread, so the handle stays on it.
+    _thread: PhantomData<*const ()>,
+}
+
+impl Clock {
+    /// Install a manual clock on this thread, stopped at the current time. Replaces any
+    /// manual clock already installed.
+    pub fn manual() -> Clock {
+        MANUAL.with(|m| m.set(Some(Instant::now())));
+        Clock {
+            _thread: PhantomData,
+        }
+    }
+
+    pub fn now(&self) -> Instant {
+        now()
+    }
+
+    /// Move time forward by `ms` milliseconds, running a frame (`frame::tick`) every
+    /// `FRAME_STEP` and at the end.
+    pub fn advance(&self, ms: u64) {
+        self.advance_by(Duration::from_millis(ms));
+    }
+
+    pub fn advance_by(&self, dt: Duration) {
+        let end = now() + dt;
+        loop {
+            let t = (now() + FRAME_STEP).min(end);
+            MANUAL.with(|m| m.set(Some(t)));
+            crate::frame::tick(t);
+            if t >= end {
+                break;
+            }
+        }
+    }
+}
+
+impl Drop for Clock {
+    fn drop(&mut self) {
+        MANUAL.with(|m| m.set(None));
+    }
+}
@@ -0,0 +1,219 @@
+// velox-core/src/format.rs
+//! Locale-aware formatting of numbers, money, percentages, dates, and relative times.
+//!
+//! Every helper formats for the `i18n` locale and reads it through its signal, so
+//! formatted text re-renders when the locale changes. In templates they are called from
+//! interpolations such as `{{ fmt_currency(price, "EUR") }}`, evaluated by `i18n::eval`
+//! in the app's resolve function.
+//!
+//! The rules are pure Rust and cover the common locales; anything else gets English
+//! conventions.
+
+use crate::i18n::{self, arg_value, format_number_in, language, plural_category};
+
+/// Group thousands and show `decimals` fraction digits, e.g. `1,234.50` or `1.234,50`.
+pub fn fmt_number(n: f64, decimals: usize) -> String {
+    format_number_in(&i18n::locale(), n, decimals)
+}
+
+/// `n` as a percentage of 1, e.g. `0.256` as `26%` (`26 %` in French and German).
+pub fn fmt_percent(n: f64, decimals: usize) -> String {
+    let locale = i18n::locale();
+    let number = format_number_in(&locale, n * 100.0, decimals);
+    match language(&locale) {
+        "de" | "fr" | "es" | "sv" | "fi" | "nb" | "no" | "da" | "cs" | "sk" | "ru" | "uk"
+        | "pl" => format!("{}\u{a0}%", number),
+        _ => format!("{}%", number),
+    }
+}
+
+/// Format an amount of the ISO 4217 `currency`, with the currency's usual fraction
+/// digits and the locale's symbol placement: `$1,234.50` in `en-US`, `1.234,50 €` in
+/// `de-DE`. Unknown codes are shown as the code.
+pub fn fmt_currency(amount: f64, currency: &str) -> String {
+    let locale = i18n::locale();
+    let code = currency.to_ascii_uppercase();
+    let (symbol, decimals) = match code.as_str() {
+        "USD" => ("$", 2),
+        "EUR" => ("€", 2),
+        "GBP" => ("£", 2),
+        "JPY" => ("¥", 0),
+        "CNY" => ("¥", 2),
+        "KRW" => ("₩", 0),
+        "INR" => ("₹", 2),
+        "RUB" => ("₽", 2),
+        "BRL" => ("R$", 2),
+        "CAD" => ("CA$", 2),
+        "AUD" => ("A$", 2),
+        _ => (code.as_str(), 2),
+    };
+    let number = format_number_in(&locale, amount.abs(), decimals);
+    let sign = if amount < 0.0 && number.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        "-"
+    } else {
+        ""
+    };
+    let symbol_after = i18n::number_symbols(&locale).decimal == ','
+        && !matches!(language(&locale), "nl" | "pt" | "id");
+    let spaced =
+        symbol.chars().all(|c| c.is_ascii_alphabetic()) || matches!(language(&locale), "nl" | "pt");
+    match (symbol_after, spaced) {
+        (true, _) => format!("{}{}\u{a0}{}", sign, number, symbol),
+        (false, true) => format!("{}{}\u{a0}{}", sign, symbol, number),
+        (false, false) => format!("{}{}{}", sign, symbol, number),
+    }
+}
+
+/// A date in the locale's short form; templates pass it as `YYYY-MM-DD`.
+pub fn fmt_date(year: i32, month: u32, day: u32) -> String {
+    i18n::format_date_in(&i18n::locale(), year, month, day)
+}
+
+/// Describe an offset from now in the largest fitting unit: `-90.0` is "2 minutes ago"
+/// and `86400.0 * 3.0` is "in 3 days". Under 45 seconds either way is "now".
+pub fn fmt_relative(seconds: f64) -> String {
+    const UNITS: [(Unit, f64); 7] = [
+        (Unit::Year, 365.0 * 86400.0),
+        (Unit::Month, 30.0 * 86400.0),
+        (Unit::Week, 7.0 * 86400.0),
+        (Unit::Day, 86400.0),
+        (Unit::Hour, 3600.0),
+        (Unit::Minute, 60.0),
+        (Unit::Second, 1.0),
+    ];
+    let locale = i18n::locale();
+    let words = RelativeWords::for_language(language(&locale));
+    if seconds.abs() < 45.0 {
+        return words.now.to_string();
+    }
+    let (unit, size) = UNITS
+        .iter()
+        .find(|(_, size)| seconds.abs() >= *size * 0.9)
+        .copied()
+        .unwrap_or(UNITS[6]);
+    let count = (seconds.abs() / size).round().max(1.0);
+    let one = plural_category(&locale, count) == "one";
+    let name = words.unit(unit, one);
+    let phrase = format!("{} {}", format_number_in(&locale, count, 0), name);
+    let template = if seconds < 0.0 {
+        words.past
+    } else {
+        words.future
+    };
+    template.replace("{}", &phrase)
+}
+
+#[derive(Clone, Copy)]
+enum Unit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+struct RelativeWords {
+    now: &'static str,
+    past: &'static str,
+    future: &'static str,
+    /// Singular and plural per unit, in `Unit` order.
+    units: [(&'static str, &'static str); 7],
+}
+
+impl RelativeWords {
+    fn for_language(lang: &str) -> Self {
+        match lang {
+            "de" => RelativeWords {
+                now: "jetzt",
+                past: "vor {}",
+                future: "in {}",
+                units: [
+                    ("Jahr", "Jahren"),
+                    ("Monat", "Monaten"),
+                    ("Woche", "Wochen"),
+                    ("Tag", "Tagen"),
+                    ("Stunde", "Stunden"),
+                    ("Minute", "Minuten"),
+                    ("Sekunde", "Sekunden"),
+                ],
+            },
+            "fr" => RelativeWords {
+                now: "maintenant",
+                past: "il y a {}",
+                future: "dans {}",
+                units: [
+                    ("an", "ans"),
+                    ("mois", "mois"),
+                    ("semaine", "semaines"),
+                    ("jour", "jours"),
+                    ("heure", "heures"),
+                    ("minute", "minutes"),
+                    ("seconde", "secondes"),
+                ],
+            },
+            "es" => RelativeWords {
+                now: "ahora",
+                past: "hace {}",
+                future: "dentro de {}",
+                units: [
+                    ("año", "años"),
+                    ("mes", "meses"),
+                    ("semana", "semanas"),
+                    ("día", "días"),
+                    ("hora", "horas"),
+                    ("minuto", "minutos"),
+                    ("segundo", "segundos"),
+                ],
+            },
+            _ => RelativeWords {
+                now: "now",
+                past: "{} ago",
+                future: "in {}",
+                units: [
+                    ("year", "years"),
+                    ("month", "months"),
+                    ("week", "weeks"),
+                    ("day", "days"),
+                    ("hour", "hours"),
+                    ("minute", "minutes"),
+                    ("second", "seconds"),
+                ],
+            },
+        }
+    }
+
+    fn unit(&self, unit: Unit, one: bool) -> &'static str {
+        let (singular, plural) = self.units[unit as usize];
+        if one { singular } else { plural }
+    }
+}
+
+/// Evaluate a `fmt_*` call from a template interpolation; `args` are the call's
+/// arguments as written. Returns `None` for other names or unusable arguments.
+pub(crate) fn eval_call(
+    name: &str,
+    args: &[String],
+    resolve: &mut dyn FnMut(&str) -> String,
+) -> Option<String> {
+    let values: Vec<String> = args.iter().map(|a| arg_value(a, resolve)).collect();
+    let number = |i: usize| values.get(i).and_then(|v| v.trim().parse::<f64>().ok());
+    let decimals = || number(1).map_or(0, |d| d.max(0.0) as usize);
+    match name {
+        "fmt_number" => Some(fmt_number(number(0)?, decimals())),
+        "fmt_percent" => Some(fmt_percent(number(0)?, decimals())),
+        "fmt_relative" => Some(fmt_relative(number(0)?)),
+        "fmt_currency" => Some(fmt_currency(number(0)?, values.get(1)?)),
+        "fmt_date" => {
+            let mut parts = values
+                .first()?
+                .trim()
+                .splitn(3, '-')
+                .map(|p| p.parse::<u32>().ok());
+            let (y, m, d) = (parts.next()??, parts.next()??, parts.next()??);
+            Some(fmt_date(y as i32, m, d))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,84 @@
+// velox-core/src/frame.rs
+//! The per-frame clock, like `requestAnimationFrame`.
+//!
+//! The window runners call `tick(now)` once per frame before building the view. Each tick
+//! polls the woken async tasks (`task`), runs the `on_frame` callbacks with the time since
+//! the previous frame, advances spring animations (`animate`), then runs the effects the
+//! flush policy held back (`signal::FlushPolicy`). While anything is registered, the
+//! runners keep redrawing at the display refresh rate.
+
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+struct Callback {
+    id: u64,
+    f: Box<dyn FnMut(Duration)>,
+}
+
+thread_local! {
+    static CALLBACKS: RefCell<Vec<Callback>> = const { RefCell::new(Vec::new()) };
+    static CANCELLED: RefCell<Vec<u64>> = const { RefCell::new(Vec::new()) };
+    static LAST_TICK: Cell<Option<Instant>> = const { Cell::new(None) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(1) };
+}
+
+/// Handle to an `on_frame` registration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameHandle {
+    id: u64,
+}
+
+impl FrameHandle {
+    /// Stop calling the callback. Safe to call from inside it.
+    pub fn cancel(&self) {
+        CALLBACKS.with(|c| c.borrow_mut().retain(|cb| cb.id != self.id));
+        CANCELLED.with(|c| c.borrow_mut().push(self.id));
+    }
+}
+
+/// Call `f(dt)` every frame until the returned handle is cancelled. `dt` is the time
+/// since the previous frame (zero on the first one).
+pub fn on_frame(f: impl FnMut(Duration) + 'static) -> FrameHandle {
+    let id = NEXT_ID.with(|n| n.replace(n.get() + 1));
+    CALLBACKS.with(|c| c.borrow_mut().push(Callback { id, f: Box::new(f) }));
+    FrameHandle { id }
+}
+
+/// Run one frame at `now`: woken tasks first, then frame callbacks, then spring
+/// animations. Returns true while another frame is needed.
+pub fn tick(now: Instant) -> bool {
+    crate::task::run_ready();
+    let dt = LAST_TICK
+        .with(|t| t.replace(Some(now)))
+        .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+
+    // Take the callbacks out so they can register or cancel others while running.
+    let mut callbacks = CALLBACKS.with(|c| std::mem::take(&mut *c.borrow_mut()));
+    for cb in &mut callbacks {
+        if !CANCELLED.with(|c| c.borrow().contains(&cb.id)) {
+            (cb.f)(dt);
+        }
+    }
+    let cancelled = CANCELLED.with(|c| std::mem::take(&mut *c.borrow_mut()));
+    callbacks.retain(|cb| !cancelled.contains(&cb.id));
+    CALLBACKS.with(|c| {
+        let mut c = c.borrow_mut();
+        callbacks.append(&mut c);
+        *c = callbacks;
+    });
+
+    crate::animate::tick(now);
+    // Effects held back by `FlushPolicy::Frame` run before the view is rebuilt.
+    crate::signal::flush();
+    let running = is_running();
+    if !running {
+        // The next frame after an idle stretch starts a fresh `dt`.
+        LAST_TICK.with(|t| t.set(None));
+    }
+    running
+}
+
+/// Whether a frame callback or spring animation wants another frame.
+pub fn is_running() -> bool {
+    CALLBACKS.with(|c| !c.borrow().is_empty()) || crate::animate::is_running()
+}
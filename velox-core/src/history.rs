@@ -0,0 +1,214 @@
+// velox-core/src/history.rs
+//! Undo/redo for signals.
+//!
+//! A `History` snapshots state whenever it changes and can step back and forth through
+//! those snapshots. `for_signal` tracks one signal; `new` takes a capture and a restore
+//! function, so one history can cover several signals (capture them as a tuple). Changes
+//! are picked up by an effect, so no explicit recording is needed.
+//!
+//! - Changes within the coalescing window of the previous one merge into a single undo
+//!   step, so typing a word undoes as one edit. `checkpoint` ends the current step early.
+//! - `transaction` runs a closure and records everything it changes as one step.
+//! - `key` handles the usual bindings: `Ctrl+Z` undoes, `Ctrl+Shift+Z` and `Ctrl+Y` redo
+//!   (`Cmd` works in place of `Ctrl`).
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::signal::{Signal, effect};
+
+struct Inner<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    current: T,
+    last_change: Option<Instant>,
+    coalesce: Duration,
+    limit: usize,
+    restoring: bool,
+    transaction_depth: usize,
+}
+
+impl<T: Clone + PartialEq> Inner<T> {
+    fn record(&mut self, value: T, allow_coalesce: bool) {
+        if value == self.current {
+            return;
+        }
+        let now = crate::clock::now();
+        let coalesce = allow_coalesce
+            && !self.coalesce.is_zero()
+            && self
+                .last_change
+                .is_some_and(|last| now.duration_since(last) < self.coalesce);
+        if !coalesce {
+            self.undo.push(self.current.clone());
+            if self.undo.len() > self.limit {
+                self.undo.remove(0);
+            }
+        }
+        self.current = value;
+        self.redo.clear();
+        self.last_change = Some(now);
+    }
+}
+
+/// Undo/redo stack over captured state. Clones share the same history.
+pub struct History<T> {
+    inner: Rc<RefCell<Inner<T>>>,
+    capture: Rc<dyn Fn() -> T>,
+    restore: Rc<dyn Fn(T)>,
+}
+
+impl<T> Clone for History<T> {
+    fn clone(&self) -> Self {
+        History {
+            inner: self.inner.clone(),
+            capture: self.capture.clone(),
+            restore: self.restore.clone(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> History<T> {
+    /// Track the state read by `capture`, written back by `restore` on undo and redo.
+    pub fn new(capture: impl Fn() -> T + 'static, restore: impl Fn(T) + 'static) -> Self {
+        let inner = Rc::new(RefCell::new(Inner {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            current: capture(),
+            last_change: None,
+            coalesce: Duration::ZERO,
+            limit: 100,
+            restoring: false,
+            transaction_depth: 0,
+        }));
+        let capture: Rc<dyn Fn() -> T> = Rc::new(capture);
+        let (tracked, cap) = (inner.clone(), capture.clone());
+        effect(move || {
+            let value = cap();
+            let mut inner = tracked.borrow_mut();
+            if !inner.restoring && inner.transaction_depth == 0 {
+                inner.record(value, true);
+            }
+        });
+        History {
+            inner,
+            capture,
+            restore: Rc::new(restore),
+        }
+    }
+
+    /// Track a single signal.
+    pub fn for_signal(signal: Rc<Signal<T>>) -> Self {
+        let s = signal.clone();
+        History::new(move || signal.get(), move |v| s.set(v))
+    }
+
+    /// Merge changes that follow each other within `window` into one undo step.
+    pub fn with_coalesce(self, window: Duration) -> Self {
+        self.inner.borrow_mut().coalesce = window;
+        self
+    }
+
+    /// Keep at most `limit` undo steps (100 by default).
+    pub fn with_limit(self, limit: usize) -> Self {
+        self.inner.borrow_mut().limit = limit.max(1);
+        self
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.inner.borrow().undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.inner.borrow().redo.is_empty()
+    }
+
+    /// Restore the previous snapshot. Returns false if there is none.
+    pub fn undo(&self) -> bool {
+        let value = {
+            let mut inner = self.inner.borrow_mut();
+            let Some(prev) = inner.undo.pop() else {
+                return false;
+            };
+            let current = std::mem::replace(&mut inner.current, prev.clone());
+            inner.redo.push(current);
+            inner.last_change = None;
+            prev
+        };
+        self.apply(value);
+        true
+    }
+
+    /// Reapply the snapshot last undone. Returns false if there is none.
+    pub fn redo(&self) -> bool {
+        let value = {
+            let mut inner = self.inner.borrow_mut();
+            let Some(next) = inner.redo.pop() else {
+                return false;
+            };
+            let current = std::mem::replace(&mut inner.current, next.clone());
+            inner.undo.push(current);
+            inner.last_change = None;
+            next
+        };
+        self.apply(value);
+        true
+    }
+
+    /// Run `f` and record all its changes as a single undo step.
+    pub fn transaction<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.inner.borrow_mut().transaction_depth += 1;
+        let out = f();
+        let done = {
+            let mut inner = self.inner.borrow_mut();
+            inner.transaction_depth -= 1;
+            inner.transaction_depth == 0
+        };
+        if done {
+            let value = (self.capture)();
+            self.inner.borrow_mut().record(value, false);
+        }
+        out
+    }
+
+    /// Start a new undo step with the next change, even within the coalescing window.
+    pub fn checkpoint(&self) {
+        self.inner.borrow_mut().last_change = None;
+    }
+
+    /// Forget all steps, keeping the current state.
+    pub fn clear(&self) {
+        let mut inner = self.inner.borrow_mut();
+        inner.undo.clear();
+        inner.redo.clear();
+        inner.last_change = None;
+    }
+
+    /// Handle an undo/redo key combination such as `Ctrl+Z`. Returns whether `combo` is
+    /// one of them (even if there was nothing to undo).
+    pub fn key(&self, combo: &str) -> bool {
+        let normalized = combo
+            .to_ascii_lowercase()
+            .replace(' ', "")
+            .replace("cmd", "ctrl")
+            .replace("meta", "ctrl");
+        match normalized.as_str() {
+            "ctrl+z" => {
+                self.undo();
+                true
+            }
+            "ctrl+shift+z" | "shift+ctrl+z" | "ctrl+y" => {
+                self.redo();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn apply(&self, value: T) {
+        self.inner.borrow_mut().restoring = true;
+        (self.restore)(value);
+        self.inner.borrow_mut().restoring = false;
+    }
+}
@@ -0,0 +1,345 @@
+// velox-core/src/i18n.rs
+//! Translations and locale conventions.
+//!
+//! Messages come from key-value catalogs, one per locale, with lines such as
+//! `greeting = Hello, {name}!`. `t(key, args)` looks the key up in the current locale,
+//! then in its language (`de` for `de-AT`), then in the fallback locale, and returns the
+//! key itself if none has it. `{name}` placeholders are replaced by the arguments; with a
+//! `count` argument, a `<key>.<category>` variant for `count`'s plural category is used
+//! where the catalog has one (`cart.items.one`, `cart.items.other`).
+//!
+//! The locale is a signal, so views and effects that translate re-run on `set_locale`.
+//! Templates call `t` through interpolations such as `{{ t("greeting", name=user) }}`,
+//! evaluated by `eval` in the app's resolve function. `format_number` and `format_date`
+//! follow the locale's conventions; the `format` module has the other helpers.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::signal::Signal;
+
+/// Messages for one locale.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Catalog {
+    messages: HashMap<String, String>,
+}
+
+impl Catalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `key = value` lines. Blank lines and lines starting with `#` are skipped;
+    /// `\n` in a value is a line break.
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let mut catalog = Catalog::new();
+        for (i, line) in src.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected `key = value`", i + 1));
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(format!("line {}: empty key", i + 1));
+            }
+            catalog.insert(key, value.trim().replace("\\n", "\n"));
+        }
+        Ok(catalog)
+    }
+
+    pub fn insert(&mut self, key: impl Into<String>, message: impl Into<String>) {
+        self.messages.insert(key.into(), message.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(String::as_str)
+    }
+}
+
+thread_local! {
+    static LOCALE: Rc<Signal<String>> = Rc::new(Signal::new("en".to_string()));
+    static FALLBACK: RefCell<String> = RefCell::new("en".to_string());
+    static CATALOGS: RefCell<HashMap<String, Catalog>> = RefCell::new(HashMap::new());
+}
+
+/// The current locale. Reading it inside an effect subscribes to changes.
+pub fn locale() -> String {
+    LOCALE.with(|l| l.get())
+}
+
+/// Switch the locale; effects that translated re-run.
+pub fn set_locale(locale: &str) {
+    LOCALE.with(|l| l.set(locale.to_string()));
+}
+
+/// The locale signal, to share with script state.
+pub fn locale_signal() -> Rc<Signal<String>> {
+    LOCALE.with(|l| l.clone())
+}
+
+/// The locale used for keys missing from the current one. Defaults to `en`.
+pub fn set_fallback_locale(locale: &str) {
+    FALLBACK.with(|f| *f.borrow_mut() = locale.to_string());
+}
+
+/// Add (or replace) the catalog for `locale`. Views showing that locale re-render.
+pub fn add_catalog(locale: &str, catalog: Catalog) {
+    CATALOGS.with(|c| c.borrow_mut().insert(locale.to_string(), catalog));
+    let current = self::locale();
+    if language(&current) == language(locale) {
+        set_locale(&current);
+    }
+}
+
+/// Parse `src` with `Catalog::parse` and add it for `locale`.
+pub fn load_catalog(locale: &str, src: &str) -> Result<(), String> {
+    add_catalog(locale, Catalog::parse(src)?);
+    Ok(())
+}
+
+/// Translate `key` in the current locale, filling `{name}` placeholders from `args`.
+pub fn t(key: &str, args: &[(&str, &str)]) -> String {
+    let locale = locale();
+    let fallback = FALLBACK.with(|f| f.borrow().clone());
+    let count = args
+        .iter()
+        .find(|(k, _)| *k == "count")
+        .and_then(|(_, v)| v.parse::<f64>().ok());
+    let message = CATALOGS.with(|c| {
+        let catalogs = c.borrow();
+        let mut chain = vec![locale.as_str(), language(&locale)];
+        chain.extend([fallback.as_str(), language(&fallback)]);
+        chain
+            .iter()
+            .filter_map(|l| Some((*l, catalogs.get(*l)?)))
+            .find_map(|(l, cat)| {
+                count
+                    .and_then(|n| {
+                        let plural = format!("{}.{}", key, plural_category(l, n));
+                        cat.get(&plural)
+                            .or_else(|| cat.get(&format!("{}.other", key)))
+                    })
+                    .or_else(|| cat.get(key))
+                    .map(str::to_string)
+            })
+    });
+    let Some(message) = message else {
+        return key.to_string();
+    };
+    let mut out = String::with_capacity(message.len());
+    let mut rest = message.as_str();
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..open]);
+        let name = rest[open + 1..open + close].trim();
+        match args.iter().find(|(k, _)| *k == name) {
+            Some((_, v)) if name == "count" => {
+                out.push_str(&v.parse::<f64>().map_or(v.to_string(), format_count))
+            }
+            Some((_, v)) => out.push_str(v),
+            None => out.push_str(&rest[open..=open + close]),
+        }
+        rest = &rest[open + close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+fn format_count(n: f64) -> String {
+    let decimals = if n.fract() == 0.0 {
+        0
+    } else {
+        format!("{}", n).split_once('.').map_or(0, |(_, d)| d.len())
+    };
+    format_number(n, decimals)
+}
+
+/// Evaluate a template interpolation that calls `t`, e.g. `t("greeting", name=user)`, or
+/// one of the `format` helpers, e.g. `fmt_number(total, 2)`.
+/// Quoted arguments are literals and bare ones are resolved with `resolve`. Returns
+/// `None` when `expr` is not such a call, so the app's resolver can fall through to its
+/// own lookups.
+pub fn eval(expr: &str, resolve: &mut dyn FnMut(&str) -> String) -> Option<String> {
+    let (name, args) = parse_call(expr)?;
+    if name != "t" {
+        return crate::format::eval_call(name, &args, resolve);
+    }
+    let mut args = args.into_iter();
+    let key = unquote(&args.next()?)?;
+    let named: Vec<(String, String)> = args
+        .filter_map(|a| {
+            let (k, v) = a.split_once('=')?;
+            Some((k.trim().to_string(), arg_value(v.trim(), resolve)))
+        })
+        .collect();
+    let borrowed: Vec<(&str, &str)> = named
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    Some(t(&key, &borrowed))
+}
+
+/// Split `name(arg, arg)` into the name and its top-level arguments.
+pub(crate) fn parse_call(expr: &str) -> Option<(&str, Vec<String>)> {
+    let expr = expr.trim();
+    let open = expr.find('(')?;
+    let inner = expr.strip_suffix(')')?.get(open + 1..)?;
+    let name = expr[..open].trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return None;
+    }
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quote = None;
+    for c in inner.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => {
+                quote = None;
+                current.push(c);
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                current.push(c);
+            }
+            (None, ',') => args.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    if !current.trim().is_empty() {
+        args.push(current.trim().to_string());
+    }
+    Some((name, args))
+}
+
+pub(crate) fn unquote(s: &str) -> Option<String> {
+    let s = s.trim();
+    let q = s.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    s.strip_prefix(q)?.strip_suffix(q).map(str::to_string)
+}
+
+/// A literal when quoted or numeric, otherwise resolved as a name.
+pub(crate) fn arg_value(v: &str, resolve: &mut dyn FnMut(&str) -> String) -> String {
+    match unquote(v) {
+        Some(lit) => lit,
+        None if v.parse::<f64>().is_ok() => v.to_string(),
+        None => resolve(v),
+    }
+}
+
+/// The language part of a locale: `de` for `de-AT` or `de_AT`.
+pub fn language(locale: &str) -> &str {
+    locale.split(['-', '_']).next().unwrap_or(locale)
+}
+
+/// The CLDR plural category (`zero`, `one`, `two`, `few`, `many`, or `other`) of `n` in
+/// `locale`, for the common language families.
+pub fn plural_category(locale: &str, n: f64) -> &'static str {
+    let int = n.fract() == 0.0;
+    let i = n.abs().trunc() as u64;
+    match language(locale) {
+        "ja" | "ko" | "zh" | "th" | "vi" | "id" | "ms" | "tr" => "other",
+        "fr" | "pt" if i <= 1 => "one",
+        "fr" | "pt" => "other",
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" if int => match (i % 10, i % 100) {
+            (1, r) if r != 11 => "one",
+            (2..=4, r) if !(12..=14).contains(&r) => "few",
+            _ => "many",
+        },
+        "pl" if int => match (i, i % 10, i % 100) {
+            (1, _, _) => "one",
+            (_, 2..=4, r) if !(12..=14).contains(&r) => "few",
+            _ => "many",
+        },
+        "cs" | "sk" if int => match i {
+            1 => "one",
+            2..=4 => "few",
+            _ => "other",
+        },
+        "ar" if int => match (i, i % 100) {
+            (0, _) => "zero",
+            (1, _) => "one",
+            (2, _) => "two",
+            (_, 3..=10) => "few",
+            (_, 11..=99) => "many",
+            _ => "other",
+        },
+        "ru" | "uk" | "be" | "sr" | "hr" | "bs" | "pl" | "cs" | "sk" | "ar" => "other",
+        _ if int && i == 1 => "one",
+        _ => "other",
+    }
+}
+
+/// Decimal and grouping separators of a locale.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NumberSymbols {
+    pub decimal: char,
+    pub group: char,
+}
+
+pub fn number_symbols(locale: &str) -> NumberSymbols {
+    let (decimal, group) = match language(locale) {
+        "de" | "es" | "it" | "nl" | "pt" | "id" | "tr" | "da" | "el" | "hr" | "ro" | "sl"
+        | "sr" | "vi" => (',', '.'),
+        "fr" | "cs" | "sk" | "pl" | "ru" | "uk" | "bg" | "fi" | "sv" | "nb" | "no" | "hu"
+        | "lt" | "lv" => (',', '\u{a0}'),
+        _ if locale.ends_with("-CH") || locale.ends_with("_CH") => ('.', '\''),
+        _ => ('.', ','),
+    };
+    NumberSymbols { decimal, group }
+}
+
+/// Format `n` with `decimals` fraction digits and the current locale's separators.
+pub fn format_number(n: f64, decimals: usize) -> String {
+    format_number_in(&locale(), n, decimals)
+}
+
+pub fn format_number_in(locale: &str, n: f64, decimals: usize) -> String {
+    let symbols = number_symbols(locale);
+    let fixed = format!("{:.*}", decimals, n.abs());
+    let (int, frac) = fixed.split_once('.').unwrap_or((&fixed, ""));
+    let mut out = String::with_capacity(fixed.len() + int.len() / 3 + 1);
+    if n < 0.0 && fixed.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        out.push('-');
+    }
+    for (i, c) in int.chars().enumerate() {
+        if i > 0 && (int.len() - i) % 3 == 0 {
+            out.push(symbols.group);
+        }
+        out.push(c);
+    }
+    if !frac.is_empty() {
+        out.push(symbols.decimal);
+        out.push_str(frac);
+    }
+    out
+}
+
+/// Format a calendar date in the current locale's short numeric form.
+pub fn format_date(year: i32, month: u32, day: u32) -> String {
+    format_date_in(&locale(), year, month, day)
+}
+
+pub fn format_date_in(locale: &str, year: i32, month: u32, day: u32) -> String {
+    match language(locale) {
+        "en" if locale.len() == 2 || locale.ends_with("US") => {
+            format!("{}/{}/{}", month, day, year)
+        }
+        "en" | "fr" | "es" | "it" | "pt" | "el" | "vi" | "id" => {
+            format!("{:02}/{:02}/{}", day, month, year)
+        }
+        "de" | "ru" | "uk" | "pl" | "cs" | "sk" | "fi" | "nb" | "no" | "da" | "tr" | "ro"
+        | "hr" => {
+            format!("{:02}.{:02}.{}", day, month, year)
+        }
+        "nl" => format!("{:02}-{:02}-{}", day, month, year),
+        "ja" | "zh" => format!("{}/{:02}/{:02}", year, month, day),
+        "ko" => format!("{}. {}. {}.", year, month, day),
+        _ => format!("{}-{:02}-{:02}", year, month, day),
+    }
+}
@@ -0,0 +1,59 @@
+// velox-core/src/idle.rs
+//! Background work that runs when the window has nothing else to do, like
+//! `requestIdleCallback`.
+//!
+//! After the runners have handled pending input and drawn the frame, they call
+//! `run_idle(deadline)` with the time left before the next frame is due. Queued tasks run
+//! in order until the deadline passes; the rest wait for the next idle period. Long jobs
+//! should do a slice of work, check `IdleDeadline::time_remaining`, and re-queue
+//! themselves with `request_idle` to continue.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The end of the current idle period.
+#[derive(Clone, Copy, Debug)]
+pub struct IdleDeadline {
+    deadline: Instant,
+}
+
+impl IdleDeadline {
+    /// Time left before the next frame, zero once it has passed.
+    pub fn time_remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+}
+
+type Task = Box<dyn FnOnce(&IdleDeadline)>;
+
+thread_local! {
+    static TASKS: RefCell<VecDeque<Task>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Queue `f` to run during an idle period.
+pub fn request_idle(f: impl FnOnce(&IdleDeadline) + 'static) {
+    TASKS.with(|t| t.borrow_mut().push_back(Box::new(f)));
+}
+
+/// Whether idle tasks are waiting to run.
+pub fn has_pending() -> bool {
+    TASKS.with(|t| !t.borrow().is_empty())
+}
+
+/// Run queued tasks until `deadline`. At least one task runs per call so work always
+/// progresses; tasks queued while running wait for the next call. Returns the number of
+/// tasks run.
+pub fn run_idle(deadline: Instant) -> usize {
+    let queued = TASKS.with(|t| t.borrow().len());
+    let idle = IdleDeadline { deadline };
+    let mut ran = 0;
+    while ran < queued && (ran == 0 || Instant::now() < deadline) {
+        let Some(task) = TASKS.with(|t| t.borrow_mut().pop_front()) else {
+            break;
+        };
+        task(&idle);
+        ran += 1;
+    }
+    ran
+}
@@ -1,4 +1,18 @@
+pub mod animate;
+pub mod clock;
+pub mod format;
+pub mod frame;
+pub mod history;
+pub mod i18n;
+pub mod idle;
 pub mod lifecycle;
+pub mod machine;
+pub mod memo;
+pub mod model;
 pub mod ref_cell;
+pub mod resource;
+pub mod settings;
 pub mod signal;
+pub mod store;
+pub mod task;
 pub mod watch;
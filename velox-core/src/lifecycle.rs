@@ -1,36 +1,148 @@
 // velox-core/src/lifecycle.rs
-use std::cell::RefCell;
+//! Setup and teardown code for a component, like Vue's `onMounted`/`onUnmounted`.
+//!
+//! The renderer runs the hooks as its `RenderTree` is mounted, patched and dropped:
+//! `on_mounted` hooks once after the first mount, `on_updated` hooks after every patch
+//! that changed something, and `on_unmounted` (and `before_destroy`) hooks when the tree
+//! goes away.
+//!
+//! Hooks registered inside `Scope::run` belong to the scope: disposing it (the component
+//! going away while the app stays) drops its mounted and updated hooks and runs its
+//! unmounted hooks right then. Hooks registered outside any scope go to the scope of the
+//! next tree mounted (see `adopt_hooks`).
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use crate::signal::Scope;
+
+type Once = (u64, Box<dyn FnOnce()>);
+type Updated = Rc<RefCell<Box<dyn FnMut()>>>;
+type OnceHooks = std::thread::LocalKey<RefCell<Vec<Once>>>;
+/// A hook's id and what disposing its scope does to it.
+type Release = (u64, fn(u64));
 
 thread_local! {
-    static MOUNTED_HOOKS: RefCell<Vec<Box<dyn FnOnce()>>> = RefCell::new(Vec::new());
-    static DESTROY_HOOKS: RefCell<Vec<Box<dyn FnOnce()>>> = RefCell::new(Vec::new());
+    static MOUNTED_HOOKS: RefCell<Vec<Once>> = const { RefCell::new(Vec::new()) };
+    static UPDATED_HOOKS: RefCell<Vec<(u64, Updated)>> = const { RefCell::new(Vec::new()) };
+    static UNMOUNTED_HOOKS: RefCell<Vec<Once>> = const { RefCell::new(Vec::new()) };
+    static DESTROY_HOOKS: RefCell<Vec<Once>> = const { RefCell::new(Vec::new()) };
+    /// Hooks registered outside any scope.
+    static UNSCOPED: RefCell<Vec<Release>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(1) };
+}
+
+fn next_id() -> u64 {
+    NEXT_ID.with(|n| n.replace(n.get() + 1))
+}
+
+/// Tie hook `id` to the current scope, or keep it for `adopt_hooks`.
+fn bind(id: u64, release: fn(u64)) {
+    match Scope::current() {
+        Some(scope) => scope.on_dispose(move || release(id)),
+        None => UNSCOPED.with(|u| u.borrow_mut().push((id, release))),
+    }
+}
+
+/// Internal: give the hooks registered outside any scope so far to `scope`, as if they
+/// had been registered inside it. The renderer does this as it mounts a tree, so
+/// dropping the tree unmounts them and no other tree's.
+pub fn adopt_hooks(scope: &Scope) {
+    let hooks = UNSCOPED.with(|u| std::mem::take(&mut *u.borrow_mut()));
+    for (id, release) in hooks {
+        scope.on_dispose(move || release(id));
+    }
+}
+
+fn forget_mounted(id: u64) {
+    let _ = MOUNTED_HOOKS.try_with(|h| h.borrow_mut().retain(|(i, _)| *i != id));
+}
+
+fn forget_updated(id: u64) {
+    let _ = UPDATED_HOOKS.try_with(|h| h.borrow_mut().retain(|(i, _)| *i != id));
+}
+
+/// Take hook `id` out of `hooks` and run it, unless it already ran.
+fn run_once(hooks: &'static OnceHooks, id: u64) {
+    let hook = hooks.try_with(|h| {
+        let mut hooks = h.borrow_mut();
+        let at = hooks.iter().position(|(i, _)| *i == id)?;
+        Some(hooks.remove(at).1)
+    });
+    if let Ok(Some(hook)) = hook {
+        hook();
+    }
 }
 
 /// Register a hook to run when a component is mounted
 pub fn on_mounted(f: impl FnOnce() + 'static) {
-    MOUNTED_HOOKS.with(|h| h.borrow_mut().push(Box::new(f)));
+    let id = next_id();
+    MOUNTED_HOOKS.with(|h| h.borrow_mut().push((id, Box::new(f))));
+    bind(id, forget_mounted);
+}
+
+/// Internal: run all mounted hooks. Returns how many ran.
+pub fn run_mounted_hooks() -> usize {
+    // Taken out first, so a hook can register others.
+    let hooks = MOUNTED_HOOKS.with(|h| std::mem::take(&mut *h.borrow_mut()));
+    let count = hooks.len();
+    for (_, hook) in hooks {
+        hook();
+    }
+    count
 }
 
-/// Internal: run all mounted hooks
-pub fn run_mounted_hooks() {
-    MOUNTED_HOOKS.with(|h| {
-        for hook in h.borrow_mut().drain(..) {
+/// Register a hook to run after every update of the mounted view, until its scope is
+/// disposed.
+pub fn on_updated(f: impl FnMut() + 'static) {
+    let id = next_id();
+    UPDATED_HOOKS.with(|h| h.borrow_mut().push((id, Rc::new(RefCell::new(Box::new(f))))));
+    bind(id, forget_updated);
+}
+
+/// Internal: run the updated hooks. One removed by an earlier hook doesn't run. Returns
+/// how many ran.
+pub fn run_updated_hooks() -> usize {
+    let hooks = UPDATED_HOOKS.with(|h| h.borrow().clone());
+    let mut count = 0;
+    for (id, hook) in hooks {
+        let registered = UPDATED_HOOKS.with(|h| h.borrow().iter().any(|(i, _)| *i == id));
+        // An update triggered from inside the hook doesn't run it again.
+        if registered && let Ok(mut hook) = hook.try_borrow_mut() {
             hook();
+            count += 1;
         }
-    });
+    }
+    count
+}
+
+/// Register a hook to run when a component is unmounted: when its scope is disposed,
+/// or, outside a scope, when the view is.
+pub fn on_unmounted(f: impl FnOnce() + 'static) {
+    let id = next_id();
+    UNMOUNTED_HOOKS.with(|h| h.borrow_mut().push((id, Box::new(f))));
+    bind(id, |id| run_once(&UNMOUNTED_HOOKS, id));
+}
+
+/// Internal: run all unmounted hooks
+pub fn run_unmounted_hooks() {
+    let hooks = UNMOUNTED_HOOKS.with(|h| std::mem::take(&mut *h.borrow_mut()));
+    for (_, hook) in hooks {
+        hook();
+    }
 }
 
 /// Register a hook to run before a component is destroyed
 pub fn before_destroy(f: impl FnOnce() + 'static) {
-    DESTROY_HOOKS.with(|h| h.borrow_mut().push(Box::new(f)));
+    let id = next_id();
+    DESTROY_HOOKS.with(|h| h.borrow_mut().push((id, Box::new(f))));
+    bind(id, |id| run_once(&DESTROY_HOOKS, id));
 }
 
 /// Internal: run all destroy hooks
 pub fn run_destroy_hooks() {
-    DESTROY_HOOKS.with(|h| {
-        for hook in h.borrow_mut().drain(..) {
-            hook();
-        }
-    });
+    let hooks = DESTROY_HOOKS.with(|h| std::mem::take(&mut *h.borrow_mut()));
+    for (_, hook) in hooks {
+        hook();
+    }
 }
-
@@ -0,0 +1,169 @@
+// velox-core/src/machine.rs
+//! Finite state machines for interaction states.
+//!
+//! A `Machine<S, E>` holds its current state in a signal, so views, effects, and `watch`
+//! sources that read `state()` re-run on every transition. Transitions are declared up
+//! front: `on` maps a state and event to a target state, and `on_with` takes a function
+//! for states that carry data (a drag offset, a wizard step). The first matching rule
+//! wins; events no rule accepts are ignored. `on_enter`, `on_exit`, and `on_transition`
+//! hooks run side effects, and events sent from inside a hook are queued and handled in
+//! order after the current transition.
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use crate::signal::Signal;
+
+type Rule<S, E> = Box<dyn Fn(&S, &E) -> Option<S>>;
+type Hook<S> = Rc<dyn Fn(&S)>;
+type TransitionHook<S, E> = Rc<dyn Fn(&S, &E, &S)>;
+
+struct Inner<S, E> {
+    rules: Vec<Rule<S, E>>,
+    on_enter: Vec<(S, Hook<S>)>,
+    on_exit: Vec<(S, Hook<S>)>,
+    on_transition: Vec<TransitionHook<S, E>>,
+    queue: VecDeque<E>,
+    sending: bool,
+    /// The state as last set, read by `send` without subscribing the caller's effect.
+    current: S,
+}
+
+/// A state machine over states `S` and events `E`. Clones share the same machine.
+pub struct Machine<S, E> {
+    state: Rc<Signal<S>>,
+    inner: Rc<RefCell<Inner<S, E>>>,
+}
+
+impl<S, E> Clone for Machine<S, E> {
+    fn clone(&self) -> Self {
+        Machine {
+            state: self.state.clone(),
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S: Clone + PartialEq + 'static, E: 'static> Machine<S, E> {
+    pub fn new(initial: S) -> Self {
+        Machine {
+            state: Rc::new(Signal::new(initial.clone())),
+            inner: Rc::new(RefCell::new(Inner {
+                rules: Vec::new(),
+                on_enter: Vec::new(),
+                on_exit: Vec::new(),
+                on_transition: Vec::new(),
+                queue: VecDeque::new(),
+                sending: false,
+                current: initial,
+            })),
+        }
+    }
+
+    /// Go from `from` to `to` on `event`.
+    pub fn on(self, from: S, event: E, to: S) -> Self
+    where
+        E: PartialEq,
+    {
+        self.on_with(move |s, e| (*s == from && *e == event).then(|| to.clone()))
+    }
+
+    /// Go to the state `rule` returns for the current state and event, if any.
+    pub fn on_with(self, rule: impl Fn(&S, &E) -> Option<S> + 'static) -> Self {
+        self.inner.borrow_mut().rules.push(Box::new(rule));
+        self
+    }
+
+    /// Run `f` with the new state whenever the machine enters `state`.
+    pub fn on_enter(self, state: S, f: impl Fn(&S) + 'static) -> Self {
+        self.inner.borrow_mut().on_enter.push((state, Rc::new(f)));
+        self
+    }
+
+    /// Run `f` with the old state whenever the machine leaves `state`.
+    pub fn on_exit(self, state: S, f: impl Fn(&S) + 'static) -> Self {
+        self.inner.borrow_mut().on_exit.push((state, Rc::new(f)));
+        self
+    }
+
+    /// Run `f(from, event, to)` after every transition.
+    pub fn on_transition(self, f: impl Fn(&S, &E, &S) + 'static) -> Self {
+        self.inner.borrow_mut().on_transition.push(Rc::new(f));
+        self
+    }
+
+    /// The current state. Inside an effect this subscribes to transitions.
+    pub fn state(&self) -> S {
+        self.state.get()
+    }
+
+    pub fn is(&self, state: &S) -> bool {
+        self.state.get() == *state
+    }
+
+    /// Whether some rule accepts `event` in the current state.
+    pub fn can(&self, event: &E) -> bool {
+        let inner = self.inner.borrow();
+        inner
+            .rules
+            .iter()
+            .any(|r| r(&inner.current, event).is_some())
+    }
+
+    /// Apply `event`. Returns whether it caused a transition; events sent while another
+    /// is being handled are queued and report `false`.
+    pub fn send(&self, event: E) -> bool {
+        {
+            let mut inner = self.inner.borrow_mut();
+            if inner.sending {
+                inner.queue.push_back(event);
+                return false;
+            }
+            inner.sending = true;
+        }
+        let moved = self.step(event);
+        while let Some(event) = self.next_queued() {
+            self.step(event);
+        }
+        moved
+    }
+
+    /// Pop a queued event, or end the current `send` when there is none.
+    fn next_queued(&self) -> Option<E> {
+        let mut inner = self.inner.borrow_mut();
+        let next = inner.queue.pop_front();
+        inner.sending = next.is_some();
+        next
+    }
+
+    fn step(&self, event: E) -> bool {
+        let (from, to, exit, enter, transition) = {
+            let mut inner = self.inner.borrow_mut();
+            let from = inner.current.clone();
+            let Some(to) = inner.rules.iter().find_map(|r| r(&from, &event)) else {
+                return false;
+            };
+            inner.current = to.clone();
+            let hooks = |list: &[(S, Hook<S>)], state: &S| -> Vec<Hook<S>> {
+                list.iter()
+                    .filter(|(s, _)| s == state)
+                    .map(|(_, f)| f.clone())
+                    .collect()
+            };
+            let (exit, enter) = (hooks(&inner.on_exit, &from), hooks(&inner.on_enter, &to));
+            (from, to, exit, enter, inner.on_transition.clone())
+        };
+        for f in exit {
+            f(&from);
+        }
+        self.state.set(to.clone());
+        for f in enter {
+            f(&to);
+        }
+        for f in transition {
+            f(&from, &event, &to);
+        }
+        true
+    }
+}
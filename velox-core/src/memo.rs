@@ -0,0 +1,98 @@
+// velox-core/src/memo.rs
+//! Derived values that notify only when they change.
+//!
+//! A `Memo` recomputes its function whenever a signal it reads changes, like an
+//! `effect`, and keeps the result. Effects reading the memo rerun only when the new
+//! result differs from the last one, so a cheap-to-derive but rarely-changing value
+//! (a filtered count, a validation error) doesn't rerun everything downstream.
+//!
+//! "Differs" is `PartialEq` by default. `new_with` takes a comparator instead, for
+//! values that are costly to compare element by element (`Rc::ptr_eq` for rows rebuilt
+//! as a new `Rc` only when they change, say).
+
+use std::cell::OnceCell;
+use std::rc::Rc;
+
+use crate::signal::{EffectHandle, Signal, effect, untrack};
+
+/// A derived value; see the module docs. Clones share the value.
+pub struct Memo<T> {
+    value: Rc<OnceCell<Signal<T>>>,
+    handle: EffectHandle,
+}
+
+impl<T> Clone for Memo<T> {
+    fn clone(&self) -> Self {
+        Memo {
+            value: self.value.clone(),
+            handle: self.handle.clone(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Memo<T> {
+    /// Derive a value with `f`, comparing results with `PartialEq`.
+    pub fn new(f: impl FnMut() -> T + 'static) -> Self {
+        Self::new_with(T::eq, f)
+    }
+}
+
+impl<T: Clone + 'static> Memo<T> {
+    /// Derive a value with `f`; a result that `same(old, new)` says is the same as the
+    /// last one is dropped without notifying. Like an `effect`, the memo stops
+    /// recomputing with the `Scope` it was created in.
+    pub fn new_with(
+        same: impl Fn(&T, &T) -> bool + 'static,
+        mut f: impl FnMut() -> T + 'static,
+    ) -> Self {
+        let value: Rc<OnceCell<Signal<T>>> = Rc::new(OnceCell::new());
+        let handle = effect({
+            let value = value.clone();
+            move || {
+                let next = f();
+                match value.get() {
+                    None => {
+                        let _ = value.set(Signal::new(next));
+                    }
+                    Some(signal) => {
+                        // Comparing and notifying are not part of what the memo reads.
+                        untrack(|| {
+                            if !signal.with_value(|current| same(current, &next)) {
+                                signal.set(next);
+                            }
+                        });
+                    }
+                }
+            }
+        });
+        Memo { value, handle }
+    }
+
+    fn signal(&self) -> &Signal<T> {
+        self.value
+            .get()
+            .expect("the memo's first run sets its value")
+    }
+
+    /// The value, subscribing the current effect to its changes.
+    pub fn get(&self) -> T {
+        self.signal().get()
+    }
+
+    /// Borrow the value, subscribing like `get`, without cloning it.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        let signal = self.signal();
+        signal.track();
+        signal.with_value(f)
+    }
+
+    /// The value, without subscribing.
+    pub fn peek(&self) -> T {
+        self.signal().peek()
+    }
+
+    /// Stop recomputing; the value stays what it was.
+    pub fn dispose(&self) {
+        self.handle.dispose();
+    }
+}
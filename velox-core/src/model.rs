@@ -0,0 +1,178 @@
+// velox-core/src/model.rs
+//! Form field state: a value with validation, dirty and touched flags, and the error to
+//! show. A template's `v-model="email"` compiles against it: the generated
+//! `make_on_event` hands each `update:email` input to `state.email` (see `Bind`).
+//!
+//! Everything reads like a signal: `get`, `is_dirty`, `is_touched` and `error` subscribe
+//! the current effect or view.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use crate::signal::Signal;
+
+type Validator<T> = Box<dyn Fn(&T) -> Result<(), String>>;
+
+/// Something a `v-model` input can write to, from the raw text the input sends.
+pub trait Bind {
+    fn bind_input(&self, raw: &str);
+}
+
+struct Inner<T> {
+    value: Signal<T>,
+    /// What `is_dirty` compares against: the initial value, or the last `commit`.
+    baseline: RefCell<T>,
+    dirty: Signal<bool>,
+    touched: Signal<bool>,
+    error: Signal<Option<String>>,
+    validators: RefCell<Vec<Validator<T>>>,
+}
+
+/// A form field; see the module docs. Clones share the field.
+pub struct Model<T> {
+    inner: Rc<Inner<T>>,
+}
+
+impl<T> Clone for Model<T> {
+    fn clone(&self) -> Self {
+        Model {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Clone + PartialEq + 'static> Model<T> {
+    pub fn new(initial: T) -> Self {
+        Model {
+            inner: Rc::new(Inner {
+                value: Signal::new(initial.clone()),
+                baseline: RefCell::new(initial),
+                dirty: Signal::distinct(false),
+                touched: Signal::distinct(false),
+                error: Signal::distinct(None),
+                validators: RefCell::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Add a check run on every new value; the first `Err` is the field's error.
+    pub fn validate(self, check: impl Fn(&T) -> Result<(), String> + 'static) -> Self {
+        self.inner.validators.borrow_mut().push(Box::new(check));
+        self.revalidate();
+        self
+    }
+
+    pub fn get(&self) -> T {
+        self.inner.value.get()
+    }
+
+    /// The value, without subscribing.
+    pub fn peek(&self) -> T {
+        self.inner.value.peek()
+    }
+
+    /// Set the value from code. It doesn't mark the field touched; `input` does.
+    pub fn set(&self, value: T) {
+        let dirty = value != *self.inner.baseline.borrow();
+        self.inner.value.set(value);
+        self.inner.dirty.set(dirty);
+        self.revalidate();
+    }
+
+    /// The user left the field, or interacted with it some other way.
+    pub fn touch(&self) {
+        self.inner.touched.set(true);
+    }
+
+    /// Whether the value differs from the initial one (or the last `commit`).
+    pub fn is_dirty(&self) -> bool {
+        self.inner.dirty.get()
+    }
+
+    /// Whether the user has entered something, or `touch` was called.
+    pub fn is_touched(&self) -> bool {
+        self.inner.touched.get()
+    }
+
+    /// Why the value is invalid, or why the last input couldn't be read as a `T`.
+    pub fn error(&self) -> Option<String> {
+        self.inner.error.get()
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.error().is_none()
+    }
+
+    /// Back to the baseline value, untouched.
+    pub fn reset(&self) {
+        let baseline = self.inner.baseline.borrow().clone();
+        self.set(baseline);
+        self.inner.touched.set(false);
+    }
+
+    /// Make the current value the baseline, e.g. once it is saved: the field is no longer
+    /// dirty.
+    pub fn commit(&self) {
+        *self.inner.baseline.borrow_mut() = self.inner.value.peek();
+        self.inner.dirty.set(false);
+    }
+
+    fn revalidate(&self) {
+        let error = {
+            let validators = self.inner.validators.borrow();
+            let value = self.inner.value.peek();
+            validators.iter().find_map(|check| check(&value).err())
+        };
+        self.inner.error.set(error);
+    }
+}
+
+impl<T> Model<T>
+where
+    T: Clone + PartialEq + FromStr + 'static,
+    T::Err: fmt::Display,
+{
+    /// What the user typed: marks the field touched and sets the value, or, when `raw`
+    /// isn't a `T`, keeps the value and reports why as the error.
+    pub fn input(&self, raw: &str) {
+        self.touch();
+        match raw.parse() {
+            Ok(value) => self.set(value),
+            Err(e) => self.inner.error.set(Some(e.to_string())),
+        }
+    }
+}
+
+impl<T> Bind for Model<T>
+where
+    T: Clone + PartialEq + FromStr + 'static,
+    T::Err: fmt::Display,
+{
+    fn bind_input(&self, raw: &str) {
+        self.input(raw);
+    }
+}
+
+/// A plain signal takes the inputs that parse, and ignores the rest.
+impl<T: Clone + FromStr> Bind for Signal<T> {
+    fn bind_input(&self, raw: &str) {
+        if let Ok(value) = raw.parse() {
+            self.set(value);
+        }
+    }
+}
+
+impl<B: Bind + ?Sized> Bind for Rc<B> {
+    fn bind_input(&self, raw: &str) {
+        (**self).bind_input(raw);
+    }
+}
+
+/// The value, as `v-model` shows it in the input.
+impl<T: Clone + PartialEq + fmt::Display + 'static> fmt::Display for Model<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.get().fmt(f)
+    }
+}
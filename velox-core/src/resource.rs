@@ -0,0 +1,157 @@
+// velox-core/src/resource.rs
+//! Async data as a signal, like Solid's `createResource`.
+//!
+//! A `Resource` runs an async loader on the UI thread (`task::spawn_local`) and exposes
+//! its progress as a `ResourceState`: `Loading`, then `Ready(value)` or `Error(err)`.
+//! Reading the state inside an effect or a view subscribes to it like any signal. With a
+//! source, the loader runs again with the source's value whenever a signal the source
+//! reads changes; a load still running when that happens is dropped.
+
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+use std::rc::{Rc, Weak};
+
+use crate::signal::{EffectHandle, Signal, effect, untrack};
+use crate::task::{TaskHandle, spawn_local};
+
+/// Where a `Resource`'s load is.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResourceState<T, E> {
+    Loading,
+    Ready(T),
+    Error(E),
+}
+
+struct Inner<T, E> {
+    state: Signal<ResourceState<T, E>>,
+    task: Cell<Option<TaskHandle>>,
+    /// Runs the loader again with the last source value.
+    reload: RefCell<Box<dyn FnMut()>>,
+    source: Cell<Option<EffectHandle>>,
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> Inner<T, E> {
+    fn start(self: &Rc<Self>, load: impl Future<Output = Result<T, E>> + 'static) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        if !matches!(self.state.peek(), ResourceState::Loading) {
+            self.state.set(ResourceState::Loading);
+        }
+        let weak = Rc::downgrade(self);
+        self.task.set(Some(spawn_local(async move {
+            let result = load.await;
+            if let Some(inner) = weak.upgrade() {
+                inner.task.set(None);
+                inner.state.set(match result {
+                    Ok(value) => ResourceState::Ready(value),
+                    Err(err) => ResourceState::Error(err),
+                });
+            }
+        })));
+    }
+}
+
+impl<T, E> Drop for Inner<T, E> {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        if let Some(source) = self.source.take() {
+            source.dispose();
+        }
+    }
+}
+
+/// An async load whose state is a signal; see the module docs. Clones share the load,
+/// which stops when the last one is dropped.
+pub struct Resource<T, E> {
+    inner: Rc<Inner<T, E>>,
+}
+
+impl<T, E> Clone for Resource<T, E> {
+    fn clone(&self) -> Self {
+        Resource {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Clone + 'static, E: Clone + 'static> Resource<T, E> {
+    /// Start `loader` once; `refetch` runs it again.
+    pub fn new<F, Fut>(mut loader: F) -> Self
+    where
+        F: FnMut() -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        Self::with_source(|| (), move |()| loader())
+    }
+
+    /// Start `loader` with `source()`, and again whenever a signal `source` reads changes.
+    /// Like an `effect`, the source stops with the `Scope` it was created in.
+    pub fn with_source<K, S, F, Fut>(mut source: S, mut loader: F) -> Self
+    where
+        K: Clone + 'static,
+        S: FnMut() -> K + 'static,
+        F: FnMut(K) -> Fut + 'static,
+        Fut: Future<Output = Result<T, E>> + 'static,
+    {
+        let key: Rc<RefCell<Option<K>>> = Rc::new(RefCell::new(None));
+        let inner = Rc::new_cyclic(|weak: &Weak<Inner<T, E>>| {
+            let (weak, key) = (weak.clone(), key.clone());
+            Inner {
+                state: Signal::new(ResourceState::Loading),
+                task: Cell::new(None),
+                reload: RefCell::new(Box::new(move || {
+                    let Some(k) = key.borrow().clone() else {
+                        return;
+                    };
+                    if let Some(inner) = weak.upgrade() {
+                        inner.start(loader(k));
+                    }
+                })),
+                source: Cell::new(None),
+            }
+        });
+        let resource = Resource { inner };
+        let weak = Rc::downgrade(&resource.inner);
+        let handle = effect(move || {
+            *key.borrow_mut() = Some(source());
+            // The loader's own reads must not make the source rerun.
+            untrack(|| {
+                if let Some(inner) = weak.upgrade() {
+                    Resource { inner }.refetch();
+                }
+            });
+        });
+        resource.inner.source.set(Some(handle));
+        resource
+    }
+
+    /// The state, subscribing the current effect to it.
+    pub fn state(&self) -> ResourceState<T, E> {
+        self.inner.state.get()
+    }
+
+    /// The loaded value, or `None` while loading or after an error. Subscribes like
+    /// `state`.
+    pub fn get(&self) -> Option<T> {
+        match self.state() {
+            ResourceState::Ready(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        matches!(self.state(), ResourceState::Loading)
+    }
+
+    /// Drop the running load, if any, and start the loader again with the last source
+    /// value.
+    pub fn refetch(&self) {
+        // A loader calling `refetch` itself has just started a load.
+        if let Ok(mut reload) = self.inner.reload.try_borrow_mut() {
+            reload();
+        }
+    }
+}
@@ -0,0 +1,122 @@
+// velox-core/src/settings.rs
+//! Persistent user settings.
+//!
+//! `Settings` is a flat string key/value store saved as `key = value` lines, one per
+//! setting, in the platform's config directory: `$XDG_CONFIG_HOME` (or `~/.config`) on
+//! Linux and BSD, `~/Library/Application Support` on macOS, and `%APPDATA%` on Windows.
+//! Preferences the framework keeps for the user (the UI zoom level, for one) live here,
+//! next to the app's own.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// File name of an app's settings inside its config directory.
+pub const FILE_NAME: &str = "settings.conf";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Settings {
+    path: Option<PathBuf>,
+    values: BTreeMap<String, String>,
+}
+
+impl Settings {
+    /// Settings that are never written anywhere; `save` does nothing.
+    pub fn in_memory() -> Self {
+        Settings::default()
+    }
+
+    /// The settings of `app`, stored in `config_dir()/app/settings.conf`. Starts empty
+    /// when the file doesn't exist yet (or there is no config directory, in which case
+    /// nothing is saved).
+    pub fn open(app: &str) -> Self {
+        match config_dir() {
+            Some(dir) => Settings::load(dir.join(app).join(FILE_NAME)),
+            None => Settings::in_memory(),
+        }
+    }
+
+    /// The settings stored at `path`. A missing or unreadable file gives empty settings
+    /// that `save` will create.
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let values = std::fs::read_to_string(&path)
+            .map(|src| parse(&src))
+            .unwrap_or_default();
+        Settings {
+            path: Some(path),
+            values,
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    /// The value of `key` parsed as `T`; `None` when unset or unparsable.
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key)?.parse().ok()
+    }
+
+    /// Set `key`. Keys and values are single lines; line breaks are replaced by spaces.
+    pub fn set(&mut self, key: &str, value: impl ToString) {
+        let clean = |s: &str| s.replace(['\n', '\r'], " ").trim().to_string();
+        self.values.insert(clean(key), clean(&value.to_string()));
+    }
+
+    pub fn remove(&mut self, key: &str) {
+        self.values.remove(key);
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(String::as_str)
+    }
+
+    /// Write the settings to their file, creating its directory if needed.
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut out = String::new();
+        for (key, value) in &self.values {
+            out.push_str(key);
+            out.push_str(" = ");
+            out.push_str(value);
+            out.push('\n');
+        }
+        std::fs::write(path, out)
+    }
+}
+
+/// `key = value` lines; blank lines and `#` comments are skipped.
+fn parse(src: &str) -> BTreeMap<String, String> {
+    src.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+/// The directory per-user app configuration goes in, if the platform has one.
+pub fn config_dir() -> Option<PathBuf> {
+    let env = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    if cfg!(target_os = "windows") {
+        env("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        env("XDG_CONFIG_HOME").or_else(|| env("HOME").map(|home| home.join(".config")))
+    }
+}
@@ -4,23 +4,36 @@ use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
 use std::rc::Rc;
 
+/// An effect's closure, and whether it has been disposed.
+struct EffectCell {
+    run: RefCell<Box<dyn FnMut()>>,
+    disposed: Cell<bool>,
+}
+
+type Effect = Rc<EffectCell>;
+
 // Holds the currently running/collecting effect during dependency tracking.
 thread_local! {
-    static CURRENT_EFFECT: RefCell<Option<Rc<RefCell<Box<dyn FnMut()>>>>> =
-        RefCell::new(None);
+    static CURRENT_EFFECT: RefCell<Option<Effect>> = const { RefCell::new(None) };
 
     // Simple microtask-style scheduler queue and guards.
-    static EFFECT_QUEUE: RefCell<Vec<Rc<RefCell<Box<dyn FnMut()>>>>> =
-        RefCell::new(Vec::new());
+    static EFFECT_QUEUE: RefCell<Vec<Effect>> = const { RefCell::new(Vec::new()) };
     static QUEUED: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
-    static IS_FLUSHING: Cell<bool> = Cell::new(false);
+    static IS_FLUSHING: Cell<bool> = const { Cell::new(false) };
+    // Depth of nested `batch` calls; effects wait in the queue while it is non-zero.
+    static BATCH_DEPTH: Cell<usize> = const { Cell::new(0) };
+    static FLUSH_POLICY: Cell<FlushPolicy> = const { Cell::new(FlushPolicy::Sync) };
+    // `next_tick` callbacks waiting for the next laid-out frame.
+    static NEXT_TICK: RefCell<Vec<Box<dyn FnOnce()>>> = const { RefCell::new(Vec::new()) };
+    // Scopes inside `Scope::run`, innermost last; effects created there belong to it.
+    static SCOPES: RefCell<Vec<Scope>> = const { RefCell::new(Vec::new()) };
 }
 
-fn ptr_id(rc: &Rc<RefCell<Box<dyn FnMut()>>>) -> usize {
-    rc.as_ptr() as usize
+fn ptr_id(rc: &Effect) -> usize {
+    Rc::as_ptr(rc) as usize
 }
 
-fn enqueue_effect(eff: Rc<RefCell<Box<dyn FnMut()>>>) {
+fn enqueue_effect(eff: Effect) {
     EFFECT_QUEUE.with(|q| {
         QUEUED.with(|set| {
             let id = ptr_id(&eff);
@@ -47,35 +60,47 @@ fn flush_queue() {
         QUEUED.with(|set| {
             set.borrow_mut().remove(&ptr_id(&eff));
         });
+        run_effect(&eff);
+    }
 
-        // Extract the closure out of the RefCell so we don't hold a mutable borrow
-        // while executing it (the body may call set() and re-enqueue itself).
-        let mut func: Box<dyn FnMut()> = {
-            let mut b = eff.borrow_mut();
-            std::mem::replace(&mut *b, Box::new(|| {}))
-        };
+    IS_FLUSHING.with(|f| f.set(false));
+}
 
-        // Set current effect for dependency collection.
-        CURRENT_EFFECT.with(|cur| *cur.borrow_mut() = Some(eff.clone()));
-        // Run without holding any RefCell borrows to `eff`.
-        func();
-        // Clear current effect.
-        CURRENT_EFFECT.with(|cur| *cur.borrow_mut() = None);
+/// Run `eff` with dependency collection, unless it has been disposed.
+fn run_effect(eff: &Effect) {
+    if eff.disposed.get() {
+        return;
+    }
+    // Extract the closure out of the RefCell so we don't hold a mutable borrow
+    // while executing it (the body may call set() and re-enqueue itself).
+    let mut func: Box<dyn FnMut()> = std::mem::replace(&mut *eff.run.borrow_mut(), Box::new(|| {}));
 
-        // Put the function back into the effect cell.
-        {
-            let mut b = eff.borrow_mut();
-            *b = func;
-        }
+    // Set current effect for dependency collection, restoring the outer one after.
+    let outer = CURRENT_EFFECT.with(|cur| cur.replace(Some(eff.clone())));
+    func();
+    CURRENT_EFFECT.with(|cur| *cur.borrow_mut() = outer);
+
+    // Put the function back, unless the effect disposed itself while running.
+    if !eff.disposed.get() {
+        *eff.run.borrow_mut() = func;
     }
+}
 
-    IS_FLUSHING.with(|f| f.set(false));
+fn dispose_effect(eff: &Effect) {
+    if !eff.disposed.replace(true) {
+        // Free what the closure captures now; signals drop the effect from their
+        // subscribers the next time they are read or set.
+        let func = std::mem::replace(&mut *eff.run.borrow_mut(), Box::new(|| {}));
+        drop(func);
+    }
 }
 
 /// A reactive signal wrapping a `T: Clone`.
 pub struct Signal<T> {
     value: RefCell<T>,
-    subscribers: RefCell<Vec<Rc<RefCell<Box<dyn FnMut()>>>>>,
+    subscribers: RefCell<Vec<Effect>>,
+    /// Set for `distinct` signals: `set` skips values this says equal the current one.
+    equal: Option<fn(&T, &T) -> bool>,
 }
 
 impl<T> Signal<T>
@@ -87,61 +112,310 @@ where
         Self {
             value: RefCell::new(initial),
             subscribers: RefCell::new(Vec::new()),
+            equal: None,
         }
     }
 
     /// Read the value, and if inside an `effect`, register that effect as a subscriber.
     pub fn get(&self) -> T {
+        self.track();
+        self.value.borrow().clone()
+    }
+
+    /// Register the current effect, if any, as a subscriber.
+    pub(crate) fn track(&self) {
         CURRENT_EFFECT.with(|current| {
             if let Some(effect_rc) = current.borrow().as_ref() {
                 let mut subs = self.subscribers.borrow_mut();
+                subs.retain(|e| !e.disposed.get());
                 if !subs.iter().any(|e| Rc::ptr_eq(e, effect_rc)) {
                     subs.push(effect_rc.clone());
                 }
             }
         });
+    }
+
+    /// Borrow the value without cloning it or subscribing.
+    pub(crate) fn with_value<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.value.borrow())
+    }
+
+    /// Read the value without subscribing the current effect to it.
+    pub fn peek(&self) -> T {
         self.value.borrow().clone()
     }
 
-    /// Update the value and notify all subscribers via the scheduler.
+    /// Update the value and notify all subscribers via the scheduler. A `distinct`
+    /// signal ignores a value equal to the current one.
     pub fn set(&self, new: T) {
+        if let Some(equal) = self.equal
+            && equal(&self.value.borrow(), &new)
+        {
+            return;
+        }
         *self.value.borrow_mut() = new;
 
         // Snapshot subscribers before enqueuing.
         let subscribers = {
-            let subs = self.subscribers.borrow();
+            let mut subs = self.subscribers.borrow_mut();
+            subs.retain(|e| !e.disposed.get());
             subs.clone()
         };
 
         for subscriber in subscribers {
             enqueue_effect(subscriber);
         }
+        if BATCH_DEPTH.with(|d| d.get()) == 0 && FLUSH_POLICY.get() == FlushPolicy::Sync {
+            flush_queue();
+        }
+    }
+}
+
+impl<T> Signal<T>
+where
+    T: Clone + PartialEq,
+{
+    /// A signal whose `set` does nothing when the value is equal to the current one, so
+    /// state written every frame with the same value doesn't rerun its effects.
+    pub fn distinct(initial: T) -> Self {
+        Self { equal: Some(T::eq), ..Self::new(initial) }
+    }
+
+    /// `set`, unless `new` equals the current value. Returns whether the value changed
+    /// (and subscribers were notified).
+    pub fn set_if_changed(&self, new: T) -> bool {
+        if *self.value.borrow() == new {
+            return false;
+        }
+        self.set(new);
+        true
+    }
+}
+
+/// Run `f`, holding back the effects its `set()` calls trigger until it returns; each
+/// runs once then, however many of its signals changed. Batches nest: effects run when
+/// the outermost one ends.
+pub fn batch<R>(f: impl FnOnce() -> R) -> R {
+    struct Depth;
+    impl Drop for Depth {
+        fn drop(&mut self) {
+            BATCH_DEPTH.with(|d| d.set(d.get() - 1));
+        }
+    }
+    BATCH_DEPTH.with(|d| d.set(d.get() + 1));
+    let result = {
+        let _depth = Depth;
+        f()
+    };
+    if BATCH_DEPTH.with(|d| d.get()) == 0 && FLUSH_POLICY.get() == FlushPolicy::Sync {
+        flush_queue();
+    }
+    result
+}
+
+/// When the effects a `set()` triggers run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Inside `set`, or when the outermost `batch` ends.
+    #[default]
+    Sync,
+    /// Once the code that set the signal is done: after the window's event handler
+    /// returns, or an async task yields (`end_turn`). A handler setting several signals
+    /// runs each effect once, as if it were wrapped in `batch`.
+    Microtask,
+    /// At the end of `frame::tick`, right before the view is rebuilt, so an effect runs
+    /// at most once per frame.
+    Frame,
+}
+
+/// Choose when effects run on this thread; see `FlushPolicy`. Switching to `Sync` runs
+/// the effects still waiting.
+pub fn set_flush_policy(policy: FlushPolicy) {
+    FLUSH_POLICY.set(policy);
+    if policy == FlushPolicy::Sync {
+        flush();
+    }
+}
+
+pub fn flush_policy() -> FlushPolicy {
+    FLUSH_POLICY.get()
+}
+
+/// Run the waiting effects now, whatever the policy. Does nothing inside a `batch`,
+/// whose end runs them.
+pub fn flush() {
+    if BATCH_DEPTH.with(|d| d.get()) == 0 {
         flush_queue();
     }
 }
 
+/// The end of a turn of app code: runs the waiting effects unless the policy is
+/// `Frame`. The window runners call it after each event handler, and `task::run_ready`
+/// after polling each task.
+pub fn end_turn() {
+    if FLUSH_POLICY.get() != FlushPolicy::Frame {
+        flush();
+    }
+}
+
+/// Run `f` once the state changes made so far are on screen: after the waiting effects
+/// have run and the next frame has been built and laid out, e.g. to measure an element
+/// (`Refs::rect`) a handler just added.
+pub fn next_tick(f: impl FnOnce() + 'static) {
+    NEXT_TICK.with(|q| q.borrow_mut().push(Box::new(f)));
+    // Make sure a frame comes, even when nothing else changed.
+    crate::task::wake_loop();
+}
+
+/// Whether `next_tick` callbacks wait for a frame.
+pub fn has_next_tick() -> bool {
+    NEXT_TICK.with(|q| !q.borrow().is_empty())
+}
+
+/// Run the `next_tick` callbacks registered so far; ones they register wait for the
+/// frame after. The runners call it once a frame is laid out. Returns how many ran.
+pub fn run_next_tick() -> usize {
+    let callbacks = NEXT_TICK.with(|q| std::mem::take(&mut *q.borrow_mut()));
+    let ran = callbacks.len();
+    for f in callbacks {
+        f();
+    }
+    ran
+}
+
+/// Run `f` without subscribing the current effect to the signals it reads, e.g. to log
+/// a counter from an effect without rerunning whenever the counter changes. Effects
+/// created inside `f` still track their own reads.
+pub fn untrack<R>(f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Effect>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT_EFFECT.with(|cur| *cur.borrow_mut() = self.0.take());
+        }
+    }
+    let _restore = Restore(CURRENT_EFFECT.with(|cur| cur.take()));
+    f()
+}
+
 /// Register a closure as a reactive effect:
 /// - runs immediately to collect dependencies,
-/// - then re-runs whenever any `Signal` it `get()`s is `set()`.
-pub fn effect<F>(f: F)
+/// - then re-runs whenever any `Signal` it `get()`s is `set()`,
+/// - until the returned handle, or the `Scope` it was created in, disposes it.
+///
+/// Dropping the handle leaves the effect running.
+pub fn effect<F>(f: F) -> EffectHandle
 where
     F: FnMut() + 'static,
 {
-    let eff = Rc::new(RefCell::new(Box::new(f) as Box<dyn FnMut()>));
+    let eff: Effect = Rc::new(EffectCell { run: RefCell::new(Box::new(f)), disposed: Cell::new(false) });
+    let scope = SCOPES.with(|s| s.borrow().last().cloned());
+    if let Some(scope) = &scope
+        && !scope.is_disposed()
+    {
+        scope.0.effects.borrow_mut().push(eff.clone());
+    }
 
     // Initial run with dependency collection.
-    CURRENT_EFFECT.with(|current| *current.borrow_mut() = Some(eff.clone()));
+    run_effect(&eff);
+    if scope.is_some_and(|s| s.is_disposed()) {
+        dispose_effect(&eff);
+    }
+    EffectHandle(eff)
+}
 
-    // Extract, run, and restore (same pattern as in flush)
-    let mut func: Box<dyn FnMut()> = {
-        let mut b = eff.borrow_mut();
-        std::mem::replace(&mut *b, Box::new(|| {}))
-    };
-    func();
-    {
-        let mut b = eff.borrow_mut();
-        *b = func;
+/// Stops an effect created by `effect`.
+#[derive(Clone)]
+pub struct EffectHandle(Effect);
+
+impl EffectHandle {
+    /// Stop the effect for good and drop its closure. It unsubscribes from every signal
+    /// it read, and a run already queued is skipped. Safe to call from inside the effect.
+    pub fn dispose(&self) {
+        dispose_effect(&self.0);
     }
 
-    CURRENT_EFFECT.with(|current| *current.borrow_mut() = None);
+    pub fn is_disposed(&self) -> bool {
+        self.0.disposed.get()
+    }
+}
+
+/// Owns the effects (and `watch`es) created inside `run`, so they can be disposed
+/// together, e.g. when the component that created them unmounts.
+///
+/// Clones share the scope. It is disposed when `dispose` is called or the last clone is
+/// dropped.
+#[derive(Clone, Default)]
+pub struct Scope(Rc<ScopeInner>);
+
+#[derive(Default)]
+struct ScopeInner {
+    effects: RefCell<Vec<Effect>>,
+    cleanups: RefCell<Vec<Box<dyn FnOnce()>>>,
+    disposed: Cell<bool>,
+}
+
+impl Scope {
+    pub fn new() -> Self {
+        Scope::default()
+    }
+
+    /// Run `f`; effects it creates belong to this scope. Scopes nest: an effect belongs
+    /// to the innermost one.
+    pub fn run<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Pop;
+        impl Drop for Pop {
+            fn drop(&mut self) {
+                // Dropped outside the borrow: the last clone disposes the scope.
+                let scope = SCOPES.with(|s| s.borrow_mut().pop());
+                drop(scope);
+            }
+        }
+        SCOPES.with(|s| s.borrow_mut().push(self.clone()));
+        let _pop = Pop;
+        f()
+    }
+
+    /// The innermost scope `run` is running, if any.
+    pub fn current() -> Option<Scope> {
+        SCOPES.with(|s| s.borrow().last().cloned())
+    }
+
+    /// Run `f` when the scope is disposed, after its effects are stopped.
+    pub fn on_dispose(&self, f: impl FnOnce() + 'static) {
+        if self.is_disposed() {
+            f();
+        } else {
+            self.0.cleanups.borrow_mut().push(Box::new(f));
+        }
+    }
+
+    /// Dispose every effect created in the scope, then run its `on_dispose` callbacks.
+    /// Effects created in it afterwards are disposed right after their first run.
+    pub fn dispose(&self) {
+        self.0.dispose();
+    }
+
+    pub fn is_disposed(&self) -> bool {
+        self.0.disposed.get()
+    }
+}
+
+impl ScopeInner {
+    fn dispose(&self) {
+        self.disposed.set(true);
+        let effects = std::mem::take(&mut *self.effects.borrow_mut());
+        for eff in &effects {
+            dispose_effect(eff);
+        }
+        let cleanups = std::mem::take(&mut *self.cleanups.borrow_mut());
+        for cleanup in cleanups {
+            cleanup();
+        }
+    }
+}
+
+impl Drop for ScopeInner {
+    fn drop(&mut self) {
+        self.dispose();
+    }
 }
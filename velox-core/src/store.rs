@@ -0,0 +1,121 @@
+// velox-core/src/store.rs
+//! Shared state looked up by type, like Vue's `provide`/`inject`.
+//!
+//! An app provides a value once, typically a struct of signals, and any component below
+//! injects it by its type instead of having an `Rc` threaded through every closure on
+//! the way down. Values are kept per thread, so the UI thread's store is the app's.
+//!
+//! A value provided inside `Scope::run` is taken back when the scope is disposed, and
+//! the value it shadowed is injected again. Nested scopes can so provide their own
+//! value to the components they own, and be disposed in any order.
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::signal::Scope;
+
+/// Values keyed by type. The free functions use the current thread's store; a `Store`
+/// of your own is handy in tests.
+#[derive(Clone, Default)]
+pub struct Store {
+    values: HashMap<TypeId, Rc<dyn Any>>,
+}
+
+impl Store {
+    pub fn new() -> Self {
+        Store::default()
+    }
+
+    /// Make `value` the `T`. Returns it, and the `T` it replaces.
+    pub fn provide<T: 'static>(&mut self, value: T) -> (Rc<T>, Option<Rc<T>>) {
+        let value = Rc::new(value);
+        let previous = self.values.insert(TypeId::of::<T>(), value.clone());
+        (value, previous.and_then(|p| p.downcast().ok()))
+    }
+
+    pub fn inject<T: 'static>(&self) -> Option<Rc<T>> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .and_then(|v| v.clone().downcast().ok())
+    }
+
+    pub fn remove<T: 'static>(&mut self) -> Option<Rc<T>> {
+        self.values
+            .remove(&TypeId::of::<T>())
+            .and_then(|v| v.downcast().ok())
+    }
+
+    pub fn contains<T: 'static>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+}
+
+thread_local! {
+    static STORE: RefCell<Store> = RefCell::new(Store::new());
+    /// For each value provided inside a scope, by address, the value it shadowed: what
+    /// disposing the scope puts back, or hands on to the value shadowing it by then.
+    static SHADOWED: RefCell<HashMap<usize, Option<Rc<dyn Any>>>> = RefCell::new(HashMap::new());
+}
+
+fn address(value: &Rc<dyn Any>) -> usize {
+    Rc::as_ptr(value) as *const () as usize
+}
+
+/// Provide `value` to everything that injects a `T` on this thread, until the `Scope`
+/// this runs in (if any) is disposed. Returns the shared value.
+pub fn provide<T: 'static>(value: T) -> Rc<T> {
+    let (value, previous) = STORE.with(|s| s.borrow_mut().provide(value));
+    if let Some(scope) = Scope::current() {
+        let provided: Rc<dyn Any> = value.clone();
+        let previous = previous.map(|p| p as Rc<dyn Any>);
+        SHADOWED.with(|m| m.borrow_mut().insert(address(&provided), previous));
+        // A scope dropped at thread exit may outlive the store.
+        scope.on_dispose(move || {
+            let _ = STORE.try_with(|s| {
+                let _ = SHADOWED.try_with(|m| take_back(TypeId::of::<T>(), &provided, s, m));
+            });
+        });
+    }
+    value
+}
+
+/// Take `provided`, the `key` value of a disposed scope, out of the store.
+fn take_back(
+    key: TypeId,
+    provided: &Rc<dyn Any>,
+    store: &RefCell<Store>,
+    shadowed: &RefCell<HashMap<usize, Option<Rc<dyn Any>>>>,
+) {
+    let mut shadowed = shadowed.borrow_mut();
+    let previous = shadowed.remove(&address(provided)).flatten();
+    let mut store = store.borrow_mut();
+    if store
+        .values
+        .get(&key)
+        .is_some_and(|current| Rc::ptr_eq(current, provided))
+    {
+        match previous {
+            Some(previous) => store.values.insert(key, previous),
+            None => store.values.remove(&key),
+        };
+    } else if let Some(shadowing) = shadowed
+        .values_mut()
+        .find(|v| v.as_ref().is_some_and(|v| Rc::ptr_eq(v, provided)))
+    {
+        // Disposed out of order: the value provided over this one stays, and puts back
+        // what this one shadowed when its own scope is disposed.
+        *shadowing = previous;
+    }
+}
+
+/// The `T` provided on this thread, if any.
+pub fn inject<T: 'static>() -> Option<Rc<T>> {
+    STORE.with(|s| s.borrow().inject())
+}
+
+/// The `T` provided on this thread, or `T::default()`, provided now.
+pub fn inject_or_default<T: Default + 'static>() -> Rc<T> {
+    inject().unwrap_or_else(|| provide(T::default()))
+}
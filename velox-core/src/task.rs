@@ -0,0 +1,136 @@
+// velox-core/src/task.rs
+//! Futures run on the UI thread, for async work that updates signals (see `resource`).
+//!
+//! `spawn_local` queues a future on the current thread; it doesn't need to be `Send`, so
+//! it can hold `Rc`s and set signals directly. Futures are polled by `run_ready`, which
+//! `frame::tick` calls at the start of every frame, so a woken task runs before the view
+//! is rebuilt. The future's waker may be called from any thread (an HTTP client's, a
+//! channel's); it calls the hook installed with `set_waker`, which the window runners
+//! point at `velox_renderer::wake::wake`, so a window asleep until the next input still
+//! sees the result.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Wake, Waker};
+
+type LocalFuture = Pin<Box<dyn Future<Output = ()>>>;
+type ReadyQueue = Arc<Mutex<Vec<u64>>>;
+type WakeHook = Box<dyn Fn() + Send>;
+
+static WAKE_HOOK: Mutex<Option<WakeHook>> = Mutex::new(None);
+
+thread_local! {
+    static TASKS: RefCell<HashMap<u64, LocalFuture>> = RefCell::new(HashMap::new());
+    /// The task being polled, and whether it aborted itself, so it is not put back.
+    static POLLING: Cell<Option<u64>> = const { Cell::new(None) };
+    static ABORT_POLLING: Cell<bool> = const { Cell::new(false) };
+    static NEXT_ID: Cell<u64> = const { Cell::new(1) };
+    /// Ids of this thread's tasks whose wakers were called; shared with the wakers.
+    static READY: ReadyQueue = Arc::new(Mutex::new(Vec::new()));
+}
+
+/// Handle to a `spawn_local` task.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TaskHandle {
+    id: u64,
+}
+
+impl TaskHandle {
+    /// Drop the future without polling it again. Safe to call from inside it.
+    pub fn abort(&self) {
+        let removed = TASKS.with(|t| t.borrow_mut().remove(&self.id));
+        if removed.is_none() && POLLING.get() == Some(self.id) {
+            ABORT_POLLING.set(true);
+        }
+    }
+
+    /// Whether the future has not finished and was not aborted.
+    pub fn is_pending(&self) -> bool {
+        TASKS.with(|t| t.borrow().contains_key(&self.id))
+            || (POLLING.get() == Some(self.id) && !ABORT_POLLING.get())
+    }
+}
+
+struct TaskWaker {
+    id: u64,
+    ready: ReadyQueue,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        {
+            let mut ready = self.ready.lock().unwrap_or_else(|e| e.into_inner());
+            if ready.contains(&self.id) {
+                return;
+            }
+            ready.push(self.id);
+        }
+        wake_loop();
+    }
+}
+
+/// Run `future` on this thread. It is first polled by the next `run_ready`.
+pub fn spawn_local(future: impl Future<Output = ()> + 'static) -> TaskHandle {
+    let id = NEXT_ID.with(|n| n.replace(n.get() + 1));
+    TASKS.with(|t| t.borrow_mut().insert(id, Box::pin(future)));
+    let waker = READY.with(|ready| {
+        Arc::new(TaskWaker {
+            id,
+            ready: ready.clone(),
+        })
+    });
+    waker.wake();
+    TaskHandle { id }
+}
+
+/// Poll the tasks woken since the last call. Returns how many were polled.
+pub fn run_ready() -> usize {
+    let ready = READY.with(|r| r.clone());
+    let ids = std::mem::take(&mut *ready.lock().unwrap_or_else(|e| e.into_inner()));
+    let mut polled = 0;
+    for id in ids {
+        // Take the future out so it can spawn or abort tasks while it runs.
+        let Some(mut future) = TASKS.with(|t| t.borrow_mut().remove(&id)) else {
+            continue;
+        };
+        let waker = Waker::from(Arc::new(TaskWaker {
+            id,
+            ready: ready.clone(),
+        }));
+        polled += 1;
+        POLLING.set(Some(id));
+        ABORT_POLLING.set(false);
+        let poll = future.as_mut().poll(&mut Context::from_waker(&waker));
+        POLLING.set(None);
+        crate::signal::end_turn();
+        if poll.is_pending() && !ABORT_POLLING.get() {
+            TASKS.with(|t| t.borrow_mut().insert(id, future));
+        }
+    }
+    polled
+}
+
+/// Whether a task on this thread was woken and waits for `run_ready`.
+pub fn has_ready() -> bool {
+    READY.with(|r| !r.lock().unwrap_or_else(|e| e.into_inner()).is_empty())
+}
+
+/// Call the `set_waker` hook, so the event loop schedules a frame.
+pub(crate) fn wake_loop() {
+    if let Some(hook) = WAKE_HOOK.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        hook();
+    }
+}
+
+/// Call `hook` whenever a task is woken, from whichever thread wakes it, so the event
+/// loop can schedule a frame.
+pub fn set_waker(hook: impl Fn() + Send + 'static) {
+    *WAKE_HOOK.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(hook));
+}
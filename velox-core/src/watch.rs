@@ -1,21 +1,86 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use crate::signal::effect;
+use crate::signal::{EffectHandle, effect};
+
+/// How `watch_with` calls its callback. `WatchOptions::default()` is what `watch` does.
+///
+/// There is no `deep` option as in Vue: values are compared with `PartialEq`, so a change
+/// anywhere inside a `Vec` or struct the source returns already counts.
+#[derive(Default)]
+pub struct WatchOptions {
+    /// Also call the callback on the first run, with the baseline value as both `new` and
+    /// `old`.
+    pub immediate: bool,
+    /// Runs before each call of the callback after the first, and when the watch stops,
+    /// to undo what the last call started (a timer, a request, a subscription).
+    pub cleanup: Option<Box<dyn FnMut()>>,
+}
+
+impl WatchOptions {
+    pub fn immediate(mut self) -> Self {
+        self.immediate = true;
+        self
+    }
+
+    pub fn cleanup(mut self, cleanup: impl FnMut() + 'static) -> Self {
+        self.cleanup = Some(Box::new(cleanup));
+        self
+    }
+}
+
+/// The `cleanup` option, armed once the callback has run. The effect owns it, so it is
+/// dropped, and runs a last time, when the effect is disposed.
+struct Cleanup {
+    run: Option<Box<dyn FnMut()>>,
+    armed: bool,
+}
+
+impl Cleanup {
+    fn fire(&mut self) {
+        if std::mem::take(&mut self.armed)
+            && let Some(run) = &mut self.run
+        {
+            run();
+        }
+    }
+}
+
+impl Drop for Cleanup {
+    fn drop(&mut self) {
+        self.fire();
+    }
+}
 
 /// Watch a reactive source and call `callback(new, old)` when it changes.
 /// - Runs the source once to capture dependencies (no callback on first run)
 /// - Triggers callback only when `new != old`
+/// - Stops when the returned handle, or the `Scope` it was created in, is disposed
 ///
 /// Example:
 /// watch(|| count.get(), |new, old| { println!("{old} -> {new}"); });
-pub fn watch<T, S, F>(mut source: S, mut callback: F)
+pub fn watch<T, S, F>(source: S, callback: F) -> EffectHandle
+where
+    T: PartialEq + Clone + 'static,
+    S: FnMut() -> T + 'static,
+    F: FnMut(&T, &T) + 'static,
+{
+    watch_with(source, callback, WatchOptions::default())
+}
+
+/// `watch` with `options`: call the callback right away, or clean up after it.
+///
+/// Example:
+/// watch_with(|| query.get(), |q, _| search.start(q), WatchOptions::default().immediate().cleanup(|| search.cancel()));
+pub fn watch_with<T, S, F>(mut source: S, mut callback: F, options: WatchOptions) -> EffectHandle
 where
     T: PartialEq + Clone + 'static,
     S: FnMut() -> T + 'static,
     F: FnMut(&T, &T) + 'static,
 {
     let prev: Rc<RefCell<Option<T>>> = Rc::new(RefCell::new(None));
+    let immediate = options.immediate;
+    let mut cleanup = Cleanup { run: options.cleanup, armed: false };
 
     effect({
         let prev = prev.clone();
@@ -25,21 +90,79 @@ where
             // Borrow prev, compare, and update before calling user callback
             // so the callback can freely mutate signals.
             let mut prev_borrow = prev.borrow_mut();
-            match &*prev_borrow {
+            let (new, old) = match &*prev_borrow {
                 Some(old) => {
-                    if *old != next {
-                        let old_clone = old.clone();
-                        let next_clone = next.clone();
-                        *prev_borrow = Some(next);
-                        drop(prev_borrow); // release borrow before user code
-                        callback(&next_clone, &old_clone);
+                    if *old == next {
+                        return;
                     }
+                    let old_clone = old.clone();
+                    *prev_borrow = Some(next.clone());
+                    (next, old_clone)
                 }
                 None => {
-                    // First evaluation: record baseline, do not call callback
-                    *prev_borrow = Some(next);
+                    // First evaluation: record baseline, call back only when immediate
+                    *prev_borrow = Some(next.clone());
+                    if !immediate {
+                        return;
+                    }
+                    (next.clone(), next)
                 }
+            };
+            drop(prev_borrow); // release borrow before user code
+            cleanup.fire();
+            callback(&new, &old);
+            cleanup.armed = true;
+        }
+    })
+}
+
+/// Sources `watch_many` reads together: a tuple of up to six closures, each like the
+/// source of `watch`.
+pub trait Sources: 'static {
+    type Values: PartialEq + Clone + 'static;
+    fn read(&mut self) -> Self::Values;
+}
+
+macro_rules! impl_sources {
+    ($($source:ident $value:ident $i:tt),+) => {
+        impl<$($source, $value),+> Sources for ($($source,)+)
+        where
+            $($source: FnMut() -> $value + 'static, $value: PartialEq + Clone + 'static),+
+        {
+            type Values = ($($value,)+);
+
+            fn read(&mut self) -> Self::Values {
+                ($((self.$i)(),)+)
             }
         }
-    });
+    };
+}
+
+impl_sources!(S0 T0 0);
+impl_sources!(S0 T0 0, S1 T1 1);
+impl_sources!(S0 T0 0, S1 T1 1, S2 T2 2);
+impl_sources!(S0 T0 0, S1 T1 1, S2 T2 2, S3 T3 3);
+impl_sources!(S0 T0 0, S1 T1 1, S2 T2 2, S3 T3 3, S4 T4 4);
+impl_sources!(S0 T0 0, S1 T1 1, S2 T2 2, S3 T3 3, S4 T4 4, S5 T5 5);
+
+/// `watch` several sources at once: `callback` gets the tuple of their new values and the
+/// tuple of their old ones when any of them changes.
+///
+/// Example:
+/// watch_many((|| width.get(), || height.get()), |(w, h), (old_w, old_h)| { ... });
+pub fn watch_many<S, F>(sources: S, callback: F) -> EffectHandle
+where
+    S: Sources,
+    F: FnMut(&S::Values, &S::Values) + 'static,
+{
+    watch_many_with(sources, callback, WatchOptions::default())
+}
+
+/// `watch_many` with `options`, as `watch_with`.
+pub fn watch_many_with<S, F>(mut sources: S, callback: F, options: WatchOptions) -> EffectHandle
+where
+    S: Sources,
+    F: FnMut(&S::Values, &S::Values) + 'static,
+{
+    watch_with(move || sources.read(), callback, options)
 }
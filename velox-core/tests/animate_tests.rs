@@ -0,0 +1,45 @@
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use velox_core::animate::{Spring, animate, is_running, tick};
+use velox_core::signal::Signal;
+
+#[test]
+fn spring_moves_signal_to_target_and_stops() {
+    let x = Rc::new(Signal::new(0.0f32));
+    let handle = animate(&x, 100.0, Spring::default());
+    let t0 = Instant::now();
+    assert!(tick(t0));
+    assert_eq!(x.get(), 0.0);
+
+    tick(t0 + Duration::from_millis(16));
+    let early = x.get();
+    assert!(early > 0.0 && early < 50.0, "{}", early);
+
+    let mut t = t0;
+    for _ in 0..300 {
+        t += Duration::from_millis(16);
+        if !tick(t) {
+            break;
+        }
+    }
+    assert_eq!(x.get(), 100.0);
+    assert!(!handle.is_running());
+    assert!(!is_running());
+}
+
+#[test]
+fn retarget_keeps_one_animation_and_cancel_stops_it() {
+    let p = Rc::new(Signal::new((0.0f32, 0.0f32)));
+    let first = animate(&p, (10.0, 10.0), Spring::STIFF);
+    let t0 = Instant::now();
+    tick(t0);
+    tick(t0 + Duration::from_millis(32));
+    let second = animate(&p, (-10.0, 0.0), Spring::STIFF);
+    assert_eq!(first, second);
+
+    let before = p.get();
+    second.cancel();
+    assert!(!tick(t0 + Duration::from_millis(64)));
+    assert_eq!(p.get(), before);
+}
@@ -0,0 +1,49 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use velox_core::animate::{Spring, animate};
+use velox_core::clock::{self, Clock, FRAME_STEP};
+use velox_core::frame::on_frame;
+use velox_core::signal::Signal;
+
+#[test]
+fn manual_clock_stands_still_until_advanced() {
+    let clock = Clock::manual();
+    let t0 = clock::now();
+    assert_eq!(clock::now(), t0);
+    clock.advance(250);
+    assert_eq!(clock::now(), t0 + Duration::from_millis(250));
+    drop(clock);
+    assert!(!clock::is_manual());
+}
+
+#[test]
+fn advance_runs_frames_at_the_frame_step() {
+    let clock = Clock::manual();
+    let seen: Rc<RefCell<Vec<Duration>>> = Rc::new(RefCell::new(vec![]));
+    let seen_cb = seen.clone();
+    let handle = on_frame(move |dt| seen_cb.borrow_mut().push(dt));
+    clock.advance(40);
+    handle.cancel();
+    assert_eq!(
+        &*seen.borrow(),
+        &vec![
+            Duration::ZERO,
+            FRAME_STEP,
+            Duration::from_millis(40) - FRAME_STEP * 2
+        ]
+    );
+}
+
+#[test]
+fn springs_settle_deterministically() {
+    let clock = Clock::manual();
+    let x = Rc::new(Signal::new(0.0f32));
+    animate(&x, 100.0, Spring::default());
+    clock.advance(100);
+    let midway = x.get();
+    assert!(midway > 0.0 && midway < 100.0, "{}", midway);
+    clock.advance(3_000);
+    assert_eq!(x.get(), 100.0);
+}
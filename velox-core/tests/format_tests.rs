@@ -0,0 +1,38 @@
+use velox_core::format::{fmt_currency, fmt_percent, fmt_relative};
+use velox_core::i18n;
+
+#[test]
+fn currency_percent_and_relative_time_follow_the_locale() {
+    i18n::set_locale("en-US");
+    assert_eq!(fmt_currency(-1234.5, "usd"), "-$1,234.50");
+    assert_eq!(fmt_currency(1234.6, "JPY"), "¥1,235");
+    assert_eq!(fmt_relative(-90.0), "2 minutes ago");
+    assert_eq!(fmt_relative(86400.0), "in 1 day");
+    assert_eq!(fmt_relative(10.0), "now");
+
+    i18n::set_locale("de-DE");
+    assert_eq!(fmt_currency(1234.5, "EUR"), "1.234,50\u{a0}€");
+    assert_eq!(fmt_percent(0.256, 0), "26\u{a0}%");
+    assert_eq!(fmt_relative(-3.0 * 86400.0), "vor 3 Tagen");
+}
+
+#[test]
+fn template_calls_go_through_i18n_eval() {
+    i18n::set_locale("fr-FR");
+    let mut resolve = |k: &str| {
+        if k == "total" {
+            "1234567.5".to_string()
+        } else {
+            String::new()
+        }
+    };
+    assert_eq!(
+        i18n::eval("fmt_number(total, 1)", &mut resolve).as_deref(),
+        Some("1\u{a0}234\u{a0}567,5")
+    );
+    assert_eq!(
+        i18n::eval(r#"fmt_date("2024-03-05")"#, &mut resolve).as_deref(),
+        Some("05/03/2024")
+    );
+    assert_eq!(i18n::eval("fmt_number(missing)", &mut resolve), None);
+}
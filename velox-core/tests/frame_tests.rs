@@ -0,0 +1,41 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use velox_core::frame::{is_running, on_frame, tick};
+
+#[test]
+fn on_frame_receives_delta_time_until_cancelled() {
+    let seen: Rc<RefCell<Vec<Duration>>> = Rc::new(RefCell::new(vec![]));
+    let seen_cb = seen.clone();
+    let handle = on_frame(move |dt| seen_cb.borrow_mut().push(dt));
+    assert!(is_running());
+
+    let t0 = Instant::now();
+    assert!(tick(t0));
+    assert!(tick(t0 + Duration::from_millis(16)));
+    handle.cancel();
+    assert!(!tick(t0 + Duration::from_millis(33)));
+    assert_eq!(
+        &*seen.borrow(),
+        &vec![Duration::ZERO, Duration::from_millis(16)]
+    );
+}
+
+#[test]
+fn callback_can_cancel_itself() {
+    let count = Rc::new(RefCell::new(0));
+    let handle: Rc<RefCell<Option<velox_core::frame::FrameHandle>>> = Rc::new(RefCell::new(None));
+    let (count_cb, handle_cb) = (count.clone(), handle.clone());
+    *handle.borrow_mut() = Some(on_frame(move |_| {
+        *count_cb.borrow_mut() += 1;
+        if *count_cb.borrow() == 2 {
+            handle_cb.borrow().unwrap().cancel();
+        }
+    }));
+    let t0 = Instant::now();
+    for i in 0..4 {
+        tick(t0 + Duration::from_millis(16 * i));
+    }
+    assert_eq!(*count.borrow(), 2);
+}
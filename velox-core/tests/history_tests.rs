@@ -0,0 +1,55 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use velox_core::history::History;
+use velox_core::signal::Signal;
+
+#[test]
+fn undo_redo_and_coalescing() {
+    let text = Rc::new(Signal::new(String::new()));
+    let history = History::for_signal(text.clone()).with_coalesce(Duration::from_secs(60));
+    text.set("h".into());
+    text.set("hi".into());
+    history.checkpoint();
+    text.set("hi!".into());
+
+    assert!(history.undo());
+    assert_eq!(text.get(), "hi");
+    assert!(history.undo(), "h and hi were coalesced into one step");
+    assert_eq!(text.get(), "");
+    assert!(!history.undo());
+    assert!(history.key("Ctrl+Shift+Z"));
+    assert_eq!(text.get(), "hi");
+
+    text.set("other".into());
+    assert!(!history.can_redo(), "a new change drops the redo stack");
+}
+
+#[test]
+fn transaction_over_a_group_of_signals() {
+    let x = Rc::new(Signal::new(0));
+    let y = Rc::new(Signal::new(0));
+    let (cx, cy, rx, ry) = (x.clone(), y.clone(), x.clone(), y.clone());
+    let history = History::new(
+        move || (cx.get(), cy.get()),
+        move |(a, b)| {
+            rx.set(a);
+            ry.set(b);
+        },
+    );
+    history.transaction(|| {
+        x.set(10);
+        y.set(20);
+    });
+    y.set(25);
+
+    history.undo();
+    assert_eq!((x.get(), y.get()), (10, 20));
+    assert!(history.key("cmd+z"));
+    assert_eq!(
+        (x.get(), y.get()),
+        (0, 0),
+        "the transaction undoes as one step"
+    );
+    assert!(!history.can_undo());
+}
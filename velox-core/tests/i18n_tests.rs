@@ -0,0 +1,67 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use velox_core::i18n;
+use velox_core::signal::effect;
+
+#[test]
+fn translates_with_fallback_plurals_and_args() {
+    i18n::load_catalog("en", "greeting = Hello, {name}!\ncart.items.one = {count} item\ncart.items.other = {count} items\nonly.en = English").unwrap();
+    i18n::load_catalog("ru", "cart.items.one = {count} товар\ncart.items.few = {count} товара\ncart.items.many = {count} товаров").unwrap();
+    assert!(i18n::Catalog::parse("no equals sign").is_err());
+
+    assert_eq!(i18n::t("greeting", &[("name", "Ada")]), "Hello, Ada!");
+    assert_eq!(i18n::t("cart.items", &[("count", "1")]), "1 item");
+    assert_eq!(i18n::t("cart.items", &[("count", "1200")]), "1,200 items");
+    assert_eq!(i18n::t("missing.key", &[]), "missing.key");
+
+    i18n::set_locale("ru-RU");
+    assert_eq!(i18n::t("cart.items", &[("count", "22")]), "22 товара");
+    assert_eq!(i18n::t("cart.items", &[("count", "11")]), "11 товаров");
+    assert_eq!(i18n::t("only.en", &[]), "English", "falls back to en");
+
+    let mut resolve = |k: &str| {
+        if k == "n" {
+            "3".to_string()
+        } else {
+            String::new()
+        }
+    };
+    assert_eq!(
+        i18n::eval(r#"t("cart.items", count=n)"#, &mut resolve).as_deref(),
+        Some("3 товара")
+    );
+    assert_eq!(i18n::eval("user.name", &mut resolve), None);
+}
+
+#[test]
+fn fallback_messages_use_the_fallback_locales_plural_rules() {
+    i18n::load_catalog(
+        "en",
+        "files.one = {count} file\nfiles.other = {count} files",
+    )
+    .unwrap();
+    i18n::load_catalog("ru", "title = Файлы").unwrap();
+    i18n::set_locale("ru");
+
+    // 21 is `one` in Russian but `other` in English, whose catalog has the message.
+    assert_eq!(i18n::t("files", &[("count", "21")]), "21 files");
+    assert_eq!(i18n::t("files", &[("count", "1")]), "1 file");
+}
+
+#[test]
+fn locale_signal_rerenders_and_formats() {
+    i18n::load_catalog("en", "hi = Hi").unwrap();
+    i18n::load_catalog("de", "hi = Hallo").unwrap();
+    i18n::set_locale("en");
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let s = seen.clone();
+    effect(move || s.borrow_mut().push(i18n::t("hi", &[])));
+    i18n::set_locale("de-AT");
+    assert_eq!(*seen.borrow(), vec!["Hi".to_string(), "Hallo".to_string()]);
+
+    assert_eq!(i18n::format_number(-1234567.891, 2), "-1.234.567,89");
+    assert_eq!(i18n::format_date(2024, 3, 5), "05.03.2024");
+    assert_eq!(i18n::format_date_in("en-US", 2024, 3, 5), "3/5/2024");
+    assert_eq!(i18n::plural_category("fr", 0.0), "one");
+}
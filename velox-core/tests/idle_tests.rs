@@ -0,0 +1,41 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use velox_core::idle::{has_pending, request_idle, run_idle};
+
+#[test]
+fn idle_tasks_run_in_order_within_the_deadline() {
+    let log: Rc<RefCell<Vec<u32>>> = Rc::new(RefCell::new(vec![]));
+    for i in 0..3 {
+        let log = log.clone();
+        request_idle(move |deadline| {
+            log.borrow_mut().push(i);
+            if i == 0 {
+                assert!(deadline.time_remaining() > Duration::ZERO);
+            }
+        });
+    }
+    assert_eq!(run_idle(Instant::now() + Duration::from_secs(1)), 3);
+    assert_eq!(&*log.borrow(), &vec![0, 1, 2]);
+    assert!(!has_pending());
+}
+
+#[test]
+fn expired_deadline_still_runs_one_task_and_requeued_work_waits() {
+    let count = Rc::new(RefCell::new(0));
+    fn step(count: Rc<RefCell<u32>>) {
+        request_idle(move |_| {
+            *count.borrow_mut() += 1;
+            step(count);
+        });
+    }
+    step(count.clone());
+    request_idle(|_| {});
+    assert_eq!(run_idle(Instant::now()), 1);
+    assert_eq!(*count.borrow(), 1);
+    // The re-queued step runs after the task queued before it, not in the same call.
+    assert_eq!(run_idle(Instant::now() + Duration::from_secs(1)), 2);
+    assert_eq!(*count.borrow(), 2);
+    assert!(has_pending());
+}
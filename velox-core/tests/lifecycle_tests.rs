@@ -29,3 +29,27 @@ fn test_mounted_and_destroy_hooks() {
     assert_eq!(*v1.borrow(), 1);
     assert_eq!(*v2.borrow(), 2);
 }
+
+#[test]
+fn hooks_registered_in_a_scope_follow_it() {
+    use velox_core::lifecycle::{on_unmounted, on_updated, run_unmounted_hooks, run_updated_hooks};
+    use velox_core::signal::Scope;
+
+    let log = Rc::new(StdRefCell::new(Vec::new()));
+    let scope = Scope::new();
+    {
+        let (l1, l2, l3) = (log.clone(), log.clone(), log.clone());
+        scope.run(|| {
+            on_mounted(move || l1.borrow_mut().push("mounted"));
+            on_updated(move || l2.borrow_mut().push("updated"));
+            on_unmounted(move || l3.borrow_mut().push("unmounted"));
+        });
+    }
+    run_updated_hooks();
+    // The component goes away before the view does: its unmount hook runs now, once.
+    scope.dispose();
+    run_mounted_hooks();
+    run_updated_hooks();
+    run_unmounted_hooks();
+    assert_eq!(*log.borrow(), vec!["updated", "unmounted"]);
+}
@@ -0,0 +1,71 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use velox_core::machine::Machine;
+use velox_core::signal::effect;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Drag {
+    Idle,
+    Pressed,
+    Dragging(i32),
+}
+
+#[derive(Debug, PartialEq)]
+enum Ev {
+    Down,
+    Move(i32),
+    Up,
+}
+
+#[test]
+fn transitions_drive_effects_and_hooks() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let l = log.clone();
+    let drag = Machine::new(Drag::Idle)
+        .on(Drag::Idle, Ev::Down, Drag::Pressed)
+        .on_with(|s, e| match (s, e) {
+            (Drag::Pressed | Drag::Dragging(_), Ev::Move(x)) => Some(Drag::Dragging(*x)),
+            (Drag::Pressed | Drag::Dragging(_), Ev::Up) => Some(Drag::Idle),
+            _ => None,
+        })
+        .on_exit(Drag::Idle, move |_| l.borrow_mut().push("leave idle"));
+
+    let renders = Rc::new(RefCell::new(Vec::new()));
+    let (r, d) = (renders.clone(), drag.clone());
+    effect(move || r.borrow_mut().push(d.state()));
+
+    assert!(!drag.can(&Ev::Up));
+    assert!(!drag.send(Ev::Move(3)), "ignored while idle");
+    assert!(drag.send(Ev::Down));
+    assert!(drag.send(Ev::Move(5)));
+    assert!(drag.is(&Drag::Dragging(5)));
+    assert!(drag.send(Ev::Up));
+    assert_eq!(
+        *renders.borrow(),
+        vec![Drag::Idle, Drag::Pressed, Drag::Dragging(5), Drag::Idle]
+    );
+    assert_eq!(*log.borrow(), vec!["leave idle"]);
+}
+
+#[test]
+fn events_sent_from_hooks_are_queued() {
+    let wizard: Machine<u32, &str> = Machine::new(0).on_with(|s, e| match *e {
+        "next" if *s < 3 => Some(s + 1),
+        "back" if *s > 0 => Some(s - 1),
+        _ => None,
+    });
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let w = wizard.clone();
+    let s = seen.clone();
+    let wizard = wizard
+        .on_enter(2, move |_| {
+            // Step 2 is skipped automatically.
+            assert!(!w.send("next"));
+        })
+        .on_transition(move |from, _, to| s.borrow_mut().push((*from, *to)));
+    wizard.send("next");
+    wizard.send("next");
+    assert_eq!(wizard.state(), 3);
+    assert_eq!(*seen.borrow(), vec![(0, 1), (1, 2), (2, 3)]);
+}
@@ -0,0 +1,48 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use velox_core::memo::Memo;
+use velox_core::signal::{Signal, effect};
+
+fn count_runs<T: Clone + 'static>(memo: &Memo<T>) -> Rc<RefCell<usize>> {
+    let runs = Rc::new(RefCell::new(0));
+    let (memo, r) = (memo.clone(), runs.clone());
+    effect(move || {
+        memo.with(|_| ());
+        *r.borrow_mut() += 1;
+    });
+    runs
+}
+
+#[test]
+fn memo_notifies_only_when_the_result_changes() {
+    let n = Rc::new(Signal::new(4));
+    let parity = {
+        let n = n.clone();
+        Memo::new(move || n.get() % 2)
+    };
+    let runs = count_runs(&parity);
+
+    n.set(6);
+    assert_eq!((parity.get(), *runs.borrow()), (0, 1));
+    n.set(7);
+    assert_eq!((parity.get(), *runs.borrow()), (1, 2));
+}
+
+#[test]
+fn memo_compares_with_a_custom_comparator() {
+    let source = Rc::new(Signal::new(Rc::new(vec![1, 2, 3])));
+    let rows = {
+        let source = source.clone();
+        Memo::new_with(Rc::ptr_eq, move || source.get())
+    };
+    let runs = count_runs(&rows);
+
+    // The same allocation is the same rows, without comparing them.
+    source.set(source.peek());
+    assert_eq!(*runs.borrow(), 1);
+    // Equal contents in a new allocation count as new rows.
+    source.set(Rc::new(vec![1, 2, 3]));
+    assert_eq!(*runs.borrow(), 2);
+    assert_eq!(rows.with(|r| r.len()), 3);
+}
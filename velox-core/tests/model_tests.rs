@@ -0,0 +1,68 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use velox_core::model::{Bind, Model};
+use velox_core::signal::effect;
+
+fn age() -> Model<u32> {
+    Model::new(30).validate(|v: &u32| {
+        if *v >= 18 {
+            Ok(())
+        } else {
+            Err("Adults only".into())
+        }
+    })
+}
+
+#[test]
+fn input_tracks_dirty_touched_and_errors() {
+    let field = age();
+    assert!(!field.is_dirty() && !field.is_touched() && field.is_valid());
+
+    field.input("12");
+    assert_eq!(field.get(), 12);
+    assert!(field.is_dirty() && field.is_touched());
+    assert_eq!(field.error().as_deref(), Some("Adults only"));
+
+    // Text that isn't a `u32` keeps the value and says why.
+    field.input("abc");
+    assert_eq!(field.get(), 12);
+    assert!(field.error().is_some());
+
+    field.input("30");
+    assert!(!field.is_dirty(), "back to the initial value");
+    assert!(field.is_valid() && field.is_touched());
+
+    field.set(40);
+    field.commit();
+    assert!(!field.is_dirty());
+    field.set(30);
+    assert!(field.is_dirty(), "dirty against the committed value");
+    field.reset();
+    assert_eq!(field.get(), 40);
+    assert!(!field.is_dirty() && !field.is_touched());
+}
+
+#[test]
+fn flags_are_reactive_and_bind_routes_input() {
+    let field = Rc::new(Model::new(String::new()).validate(|v: &String| {
+        if v.contains('@') {
+            Ok(())
+        } else {
+            Err("Enter an email".into())
+        }
+    }));
+    let runs = Rc::new(Cell::new(0));
+    {
+        let (field, runs) = (field.clone(), runs.clone());
+        effect(move || {
+            field.is_valid();
+            runs.set(runs.get() + 1);
+        });
+    }
+    field.bind_input("ada");
+    assert_eq!(runs.get(), 1, "still invalid: the error didn't change");
+    field.bind_input("ada@example.com");
+    assert_eq!(runs.get(), 2);
+    assert_eq!(field.to_string(), "ada@example.com");
+}
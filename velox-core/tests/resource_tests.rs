@@ -0,0 +1,142 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+use velox_core::resource::{Resource, ResourceState};
+use velox_core::signal::{Signal, effect};
+use velox_core::task::{has_ready, run_ready, set_waker, spawn_local};
+
+/// A reply the test sends by hand, and the waker of the load waiting for it.
+type Slot = (Option<Result<String, String>>, Option<Waker>);
+
+#[derive(Clone, Default)]
+struct Reply(Rc<RefCell<Slot>>);
+
+impl Reply {
+    fn send(&self, result: Result<String, String>) {
+        let waker = {
+            let mut slot = self.0.borrow_mut();
+            slot.0 = Some(result);
+            slot.1.take()
+        };
+        waker.expect("the load is waiting").wake();
+    }
+}
+
+impl Future for Reply {
+    type Output = Result<String, String>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.0.borrow_mut();
+        match slot.0.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                slot.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[test]
+fn resource_loads_and_reloads_when_its_source_changes() {
+    let id = Rc::new(Signal::new(1));
+    let replies: Rc<RefCell<Vec<(i32, Reply)>>> = Rc::default();
+    let user = {
+        let (id, replies) = (id.clone(), replies.clone());
+        Resource::with_source(
+            move || id.get(),
+            move |id| {
+                let reply = Reply::default();
+                replies.borrow_mut().push((id, reply.clone()));
+                reply
+            },
+        )
+    };
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    {
+        let (user, seen) = (user.clone(), seen.clone());
+        effect(move || seen.borrow_mut().push(user.state()));
+    }
+    assert!(user.is_loading());
+    run_ready();
+    replies.borrow()[0].1.send(Ok("Ada".into()));
+    assert!(has_ready());
+    run_ready();
+    assert_eq!(user.get().as_deref(), Some("Ada"));
+
+    // A new id drops the running load: its late reply is ignored.
+    id.set(2);
+    run_ready();
+    id.set(3);
+    run_ready();
+    assert_eq!(
+        replies
+            .borrow()
+            .iter()
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>(),
+        [1, 2, 3]
+    );
+    replies.borrow()[1].1.send(Ok("Grace".into()));
+    run_ready();
+    assert!(user.is_loading());
+    replies.borrow()[2].1.send(Err("not found".into()));
+    run_ready();
+    assert_eq!(user.state(), ResourceState::Error("not found".into()));
+    assert_eq!(
+        *seen.borrow(),
+        [
+            ResourceState::Loading,
+            ResourceState::Ready("Ada".into()),
+            ResourceState::Loading,
+            ResourceState::Error("not found".into()),
+        ]
+    );
+
+    user.refetch();
+    assert!(user.is_loading());
+}
+
+#[test]
+fn wakers_from_other_threads_call_the_hook() {
+    let hooks = Arc::new(AtomicUsize::new(0));
+    {
+        let hooks = hooks.clone();
+        set_waker(move || {
+            hooks.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+    let done = Rc::new(Signal::new(false));
+    let d = done.clone();
+    let started = Arc::new(AtomicUsize::new(0));
+    let s = started.clone();
+    spawn_local(std::future::poll_fn(move |cx| {
+        if s.load(Ordering::SeqCst) == 2 {
+            d.set(true);
+            return Poll::Ready(());
+        }
+        if s.swap(1, Ordering::SeqCst) == 0 {
+            let (s, waker) = (s.clone(), cx.waker().clone());
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(10));
+                s.store(2, Ordering::SeqCst);
+                waker.wake();
+            });
+        }
+        Poll::Pending
+    }));
+    run_ready();
+    let before = hooks.load(Ordering::SeqCst);
+    while !has_ready() {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    assert!(hooks.load(Ordering::SeqCst) > before);
+    run_ready();
+    assert!(done.get());
+}
@@ -0,0 +1,69 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::time::Instant;
+
+use velox_core::frame;
+use velox_core::signal::{
+    FlushPolicy, Signal, effect, end_turn, next_tick, run_next_tick, set_flush_policy,
+};
+
+#[test]
+fn microtask_policy_runs_effects_once_at_the_end_of_the_turn() {
+    set_flush_policy(FlushPolicy::Microtask);
+    let a = Rc::new(Signal::new(0));
+    let b = Rc::new(Signal::new(0));
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    {
+        let (a, b, seen) = (a.clone(), b.clone(), seen.clone());
+        effect(move || seen.borrow_mut().push(a.get() + b.get()));
+    }
+    a.set(1);
+    b.set(2);
+    assert_eq!(*seen.borrow(), vec![0]);
+    end_turn();
+    assert_eq!(*seen.borrow(), vec![0, 3]);
+    set_flush_policy(FlushPolicy::Sync);
+}
+
+#[test]
+fn frame_policy_waits_for_the_frame_tick() {
+    set_flush_policy(FlushPolicy::Frame);
+    let count = Rc::new(Signal::new(0));
+    let runs = Rc::new(Cell::new(0));
+    {
+        let (count, runs) = (count.clone(), runs.clone());
+        effect(move || {
+            count.get();
+            runs.set(runs.get() + 1);
+        });
+    }
+    count.set(1);
+    end_turn();
+    assert_eq!(runs.get(), 1);
+    frame::tick(Instant::now());
+    assert_eq!(runs.get(), 2);
+
+    // Switching back to `Sync` runs what is still waiting.
+    count.set(2);
+    set_flush_policy(FlushPolicy::Sync);
+    assert_eq!(runs.get(), 3);
+}
+
+#[test]
+fn next_tick_callbacks_run_after_the_frame_and_requeue_for_the_next() {
+    let order = Rc::new(RefCell::new(Vec::new()));
+    {
+        let order = order.clone();
+        next_tick(move || {
+            order.borrow_mut().push("first");
+            let order = order.clone();
+            next_tick(move || order.borrow_mut().push("second"));
+        });
+    }
+    assert!(order.borrow().is_empty());
+    assert_eq!(run_next_tick(), 1);
+    assert_eq!(*order.borrow(), vec!["first"]);
+    assert_eq!(run_next_tick(), 1);
+    assert_eq!(*order.borrow(), vec!["first", "second"]);
+    assert_eq!(run_next_tick(), 0);
+}
@@ -0,0 +1,23 @@
+use velox_core::settings::Settings;
+
+#[test]
+fn settings_round_trip_through_their_file() {
+    let dir = std::env::temp_dir().join(format!("velox-settings-test-{}", std::process::id()));
+    let path = dir.join("app").join("settings.conf");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut settings = Settings::load(&path);
+    assert_eq!(settings.get("ui.zoom"), None);
+    settings.set("ui.zoom", 1.25);
+    settings.set("name", "two\nlines");
+    settings.save().expect("save creates the directory");
+
+    let loaded = Settings::load(&path);
+    assert_eq!(loaded.get_parsed::<f32>("ui.zoom"), Some(1.25));
+    assert_eq!(loaded.get("name"), Some("two lines"));
+    assert_eq!(loaded.keys().collect::<Vec<_>>(), vec!["name", "ui.zoom"]);
+
+    std::fs::write(&path, "# comment\n\nwidth = 300\nbroken line\n").unwrap();
+    assert_eq!(Settings::load(&path).get_parsed::<u32>("width"), Some(300));
+    let _ = std::fs::remove_dir_all(&dir);
+}
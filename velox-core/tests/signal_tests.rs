@@ -24,3 +24,129 @@ fn test_signal_and_effect() {
     count.set(42);
     assert_eq!(*observed.borrow(), 42);
 }
+
+#[test]
+fn batch_runs_each_effect_once_after_all_sets() {
+    use velox_core::signal::batch;
+
+    let (a, b) = (Rc::new(Signal::new(0)), Rc::new(Signal::new(0)));
+    let seen = Rc::new(StdRefCell::new(Vec::new()));
+    {
+        let (a, b, seen) = (a.clone(), b.clone(), seen.clone());
+        effect(move || seen.borrow_mut().push((a.get(), b.get())));
+    }
+
+    let sum = batch(|| {
+        a.set(1);
+        batch(|| b.set(2));
+        assert_eq!(seen.borrow().len(), 1, "nothing runs before the outer batch ends");
+        a.get() + b.get()
+    });
+    assert_eq!(sum, 3);
+    assert_eq!(*seen.borrow(), [(0, 0), (1, 2)]);
+
+    a.set(5);
+    assert_eq!(seen.borrow().last(), Some(&(5, 2)), "sets outside a batch still flush at once");
+}
+
+#[test]
+fn disposed_effect_stops_and_frees_its_closure() {
+    let count = Rc::new(Signal::new(0));
+    let runs = Rc::new(StdRefCell::new(0));
+    let handle = {
+        let (count, runs) = (count.clone(), runs.clone());
+        effect(move || *runs.borrow_mut() += count.get() + 1)
+    };
+    count.set(1);
+    assert_eq!(*runs.borrow(), 1 + 2);
+    assert_eq!(Rc::strong_count(&runs), 2, "the effect holds a clone");
+
+    handle.dispose();
+    assert!(handle.is_disposed());
+    assert_eq!(Rc::strong_count(&runs), 1, "disposing drops the closure");
+    count.set(2);
+    assert_eq!(*runs.borrow(), 3);
+}
+
+#[test]
+fn scope_disposes_the_effects_created_inside_it() {
+    use velox_core::signal::Scope;
+    use velox_core::watch::watch;
+
+    let count = Rc::new(Signal::new(0));
+    let log = Rc::new(StdRefCell::new(Vec::new()));
+    let outside = {
+        let (count, log) = (count.clone(), log.clone());
+        effect(move || log.borrow_mut().push(format!("outside {}", count.get())))
+    };
+    let scope = Scope::new();
+    scope.run(|| {
+        let (c, l) = (count.clone(), log.clone());
+        effect(move || l.borrow_mut().push(format!("effect {}", c.get())));
+        let (c, l) = (count.clone(), log.clone());
+        watch(move || c.get(), move |new, old| l.borrow_mut().push(format!("watch {} -> {}", old, new)));
+    });
+    let l = log.clone();
+    scope.on_dispose(move || l.borrow_mut().push("disposed".into()));
+
+    count.set(1);
+    log.borrow_mut().clear();
+    drop(scope);
+    count.set(2);
+    assert_eq!(*log.borrow(), ["disposed", "outside 2"], "dropping the last clone disposes the scope");
+    assert!(!outside.is_disposed());
+}
+
+#[test]
+fn peek_and_untrack_read_without_subscribing() {
+    use velox_core::signal::untrack;
+
+    let name = Rc::new(Signal::new("a".to_string()));
+    let saves = Rc::new(Signal::new(0));
+    let flag = Rc::new(Signal::new(false));
+    let runs = Rc::new(StdRefCell::new(Vec::new()));
+    {
+        let (name, saves, flag, runs) = (name.clone(), saves.clone(), flag.clone(), runs.clone());
+        effect(move || {
+            let logged = untrack(|| format!("{} after {} saves", name.get(), saves.get()));
+            runs.borrow_mut().push((logged, flag.peek()));
+            let _ = saves.peek();
+            // Tracking resumes after `untrack`.
+            name.get();
+        });
+    }
+    saves.set(1);
+    flag.set(true);
+    assert_eq!(runs.borrow().len(), 1, "untracked and peeked signals don't rerun the effect");
+    name.set("b".into());
+    assert_eq!(*runs.borrow(), [("a after 0 saves".to_string(), false), ("b after 1 saves".to_string(), true)]);
+}
+
+#[test]
+fn equal_values_do_not_notify_when_gated() {
+    let runs = Rc::new(StdRefCell::new(0));
+    let plain = Rc::new(Signal::new(1));
+    let distinct = Rc::new(Signal::distinct(1));
+    {
+        let (plain, distinct, runs) = (plain.clone(), distinct.clone(), runs.clone());
+        effect(move || {
+            plain.get();
+            distinct.get();
+            *runs.borrow_mut() += 1;
+        });
+    }
+    assert_eq!(*runs.borrow(), 1);
+
+    assert!(!plain.set_if_changed(1));
+    distinct.set(1);
+    assert_eq!(*runs.borrow(), 1);
+
+    assert!(plain.set_if_changed(2));
+    distinct.set(3);
+    assert_eq!(*runs.borrow(), 3);
+    assert_eq!((plain.peek(), distinct.peek()), (2, 3));
+
+    // Plain `set` still notifies for an equal value.
+    plain.set(2);
+    assert_eq!(*runs.borrow(), 4);
+}
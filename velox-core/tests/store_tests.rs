@@ -0,0 +1,53 @@
+use velox_core::signal::{Scope, Signal};
+use velox_core::store::{self, Store};
+
+struct Theme {
+    accent: Signal<&'static str>,
+}
+
+#[test]
+fn inject_finds_the_value_provided_by_type() {
+    let mut s = Store::new();
+    assert!(s.inject::<u32>().is_none());
+    s.provide(7u32);
+    s.provide("name");
+    assert_eq!(*s.inject::<u32>().unwrap(), 7);
+    assert_eq!(*s.inject::<&str>().unwrap(), "name");
+    assert_eq!(s.provide(8u32).1.as_deref(), Some(&7));
+
+    store::provide(Theme {
+        accent: Signal::new("blue"),
+    });
+    let theme = store::inject::<Theme>().expect("provided");
+    theme.accent.set("red");
+    assert_eq!(store::inject::<Theme>().unwrap().accent.get(), "red");
+}
+
+#[test]
+fn a_scope_takes_back_what_it_provided() {
+    store::provide(1i64);
+    let scope = Scope::new();
+    scope.run(|| {
+        store::provide(2i64);
+        store::provide(0.5f64);
+    });
+    assert_eq!(*store::inject::<i64>().unwrap(), 2);
+
+    scope.dispose();
+    assert_eq!(*store::inject::<i64>().unwrap(), 1);
+    assert!(store::inject::<f64>().is_none());
+}
+
+#[test]
+fn disposing_the_outer_scope_first_keeps_the_inner_value() {
+    store::provide(1u16);
+    let outer = Scope::new();
+    let inner = Scope::new();
+    outer.run(|| store::provide(2u16));
+    inner.run(|| store::provide(3u16));
+
+    outer.dispose();
+    assert_eq!(*store::inject::<u16>().unwrap(), 3);
+    inner.dispose();
+    assert_eq!(*store::inject::<u16>().unwrap(), 1);
+}
@@ -2,7 +2,7 @@ use std::cell::RefCell as StdRefCell;
 use std::rc::Rc;
 
 use velox_core::signal::Signal;
-use velox_core::watch::watch;
+use velox_core::watch::{WatchOptions, watch, watch_many, watch_with};
 
 #[test]
 fn watch_triggers_on_change_only() {
@@ -64,3 +64,49 @@ fn watch_callback_can_mutate_signals() {
     // We should see 1, then 2, then 3 (as the callback increments)
     assert_eq!(&*seen.borrow(), &vec![1, 2, 3]);
 }
+
+#[test]
+fn watch_with_immediate_and_cleanup() {
+    let count = Rc::new(Signal::new(0));
+    let log: Rc<StdRefCell<Vec<String>>> = Rc::new(StdRefCell::new(vec![]));
+
+    let handle = {
+        let count_src = count.clone();
+        let (log_cb, log_cleanup) = (log.clone(), log.clone());
+        watch_with(
+            move || count_src.get(),
+            move |new, old| log_cb.borrow_mut().push(format!("{} -> {}", old, new)),
+            WatchOptions::default().immediate().cleanup(move || log_cleanup.borrow_mut().push("cleanup".into())),
+        )
+    };
+    assert_eq!(&*log.borrow(), &vec!["0 -> 0"]);
+
+    count.set(1);
+    assert_eq!(&*log.borrow(), &vec!["0 -> 0", "cleanup", "0 -> 1"]);
+
+    handle.dispose();
+    assert_eq!(&*log.borrow(), &vec!["0 -> 0", "cleanup", "0 -> 1", "cleanup"]);
+    count.set(2);
+    assert_eq!(log.borrow().len(), 4);
+}
+
+#[test]
+fn watch_many_passes_all_values() {
+    let width = Rc::new(Signal::new(10));
+    let label = Rc::new(Signal::new(String::from("a")));
+    type Values = (i32, String);
+    let seen: Rc<StdRefCell<Vec<(Values, Values)>>> = Rc::new(StdRefCell::new(vec![]));
+
+    {
+        let (w, l, seen) = (width.clone(), label.clone(), seen.clone());
+        watch_many((move || w.get(), move || l.get()), move |new, old| seen.borrow_mut().push((new.clone(), old.clone())));
+    }
+    assert!(seen.borrow().is_empty());
+
+    width.set(20);
+    label.set("b".into());
+    assert_eq!(
+        &*seen.borrow(),
+        &vec![((20, "a".into()), (10, "a".into())), ((20, "b".into()), (20, "a".into()))]
+    );
+}
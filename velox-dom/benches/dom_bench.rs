@@ -0,0 +1,106 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+use velox_dom::diff::diff;
+use velox_dom::layout::compute_layout;
+use velox_dom::{VNode, h, text};
+
+fn keyed_list(ids: impl Iterator<Item = usize>, label: &str) -> VNode {
+    let items = ids
+        .map(|id| {
+            let key = id.to_string();
+            h(
+                "li",
+                vec![("key", key.as_str()), ("class", "item")],
+                vec![text(format!("{} {}", label, id))],
+            )
+        })
+        .collect();
+    h("ul", vec![("class", "list")], items)
+}
+
+fn bench_diff_keyed(c: &mut Criterion) {
+    let mut group = c.benchmark_group("diff_keyed_list");
+    for &n in &[1_000usize, 10_000] {
+        let old = keyed_list(0..n, "item");
+        // Prepend one row: every index shifts.
+        let prepended = keyed_list(std::iter::once(n).chain(0..n), "item");
+        // Reverse: worst case for index-based matching.
+        let reversed = keyed_list((0..n).rev(), "item");
+        // Relabel every row in place.
+        let relabeled = keyed_list(0..n, "row");
+        for (name, new) in [
+            ("prepend", &prepended),
+            ("reverse", &reversed),
+            ("relabel", &relabeled),
+        ] {
+            group.bench_with_input(BenchmarkId::new(name, n), new, |b, new| {
+                b.iter(|| diff(&old, new))
+            });
+        }
+    }
+    group.finish();
+}
+
+fn deep_tree(depth: usize) -> VNode {
+    (0..depth).fold(text("leaf"), |child, _| {
+        h("div", vec![("style", "padding:1px")], vec![child])
+    })
+}
+
+fn wide_tree(width: usize) -> VNode {
+    let children = (0..width)
+        .map(|i| {
+            let style = if i % 2 == 0 {
+                "height:10px;margin-bottom:2px"
+            } else {
+                "padding:2px 4px"
+            };
+            h("div", vec![("style", style)], vec![text("cell")])
+        })
+        .collect();
+    h("div", vec![("style", "width:800px")], children)
+}
+
+fn flex_rows(rows: usize, cols: usize) -> VNode {
+    let rows = (0..rows)
+        .map(|_| {
+            let cells = (0..cols)
+                .map(|_| h("span", vec![("style", "width:40px;height:12px")], vec![]))
+                .collect();
+            h("div", vec![("style", "display:flex;gap:4px")], cells)
+        })
+        .collect();
+    h(
+        "div",
+        vec![("style", "display:flex;flex-direction:column")],
+        rows,
+    )
+}
+
+fn bench_layout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compute_layout");
+    for &depth in &[100usize, 1_000] {
+        let tree = deep_tree(depth);
+        group.bench_with_input(BenchmarkId::new("deep", depth), &tree, |b, t| {
+            b.iter(|| compute_layout(t, 800, 600))
+        });
+    }
+    for &width in &[1_000usize, 10_000] {
+        let tree = wide_tree(width);
+        group.bench_with_input(BenchmarkId::new("wide", width), &tree, |b, t| {
+            b.iter(|| compute_layout(t, 800, 600))
+        });
+    }
+    let tree = flex_rows(200, 20);
+    group.bench_with_input(BenchmarkId::new("flex_grid", "200x20"), &tree, |b, t| {
+        b.iter(|| compute_layout(t, 800, 600))
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().without_plots();
+    targets = bench_diff_keyed, bench_layout
+}
+criterion_main!(benches);
@@ -0,0 +1,108 @@
+//! Components: named render functions a tree refers to with `VNode::Component`.
+//!
+//! A `Registry` maps names to functions from a use's props and children to the tree it
+//! stands for (an SFC's generated `render_component` is one), and `expand` replaces the
+//! uses in a tree with what they render.
+//!
+//! Expanding is left until a tree is rendered, so a diff of two views compares the uses
+//! of a component rather than everything it renders.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{Props, VNode};
+
+/// How deep components may render components; deeper uses stay unexpanded, which stops a
+/// component that renders itself.
+pub const MAX_DEPTH: usize = 64;
+
+/// A component's render function.
+pub type Render = dyn Fn(&Props, &[VNode]) -> VNode + Send + Sync;
+
+/// What `expand` renders a component with, `None` for one it doesn't know.
+pub type Resolve<'a> = dyn Fn(&str, &Props, &[VNode]) -> Option<VNode> + 'a;
+
+/// Render functions by component name.
+#[derive(Clone, Default)]
+pub struct Registry {
+    components: HashMap<String, Arc<Render>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    /// Render `name` with `render`, replacing a component registered under it before.
+    pub fn register<F>(&mut self, name: impl Into<String>, render: F) -> &mut Self
+    where
+        F: Fn(&Props, &[VNode]) -> VNode + Send + Sync + 'static,
+    {
+        self.components.insert(name.into(), Arc::new(render));
+        self
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.components.contains_key(name)
+    }
+
+    /// What the component `name` renders for `props` and `children`, if it is registered.
+    pub fn resolve(&self, name: &str, props: &Props, children: &[VNode]) -> Option<VNode> {
+        self.components
+            .get(name)
+            .map(|render| render(props, children))
+    }
+
+    /// Replace the components in `vnode` with what they render.
+    pub fn expand(&self, vnode: &mut VNode) {
+        expand(vnode, &|name, props, children| {
+            self.resolve(name, props, children)
+        });
+    }
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&String> = self.components.keys().collect();
+        names.sort();
+        f.debug_struct("Registry")
+            .field("components", &names)
+            .finish()
+    }
+}
+
+/// Replace each component in `vnode` with what `resolve` renders for it, and the
+/// components in that in turn. Ones `resolve` returns `None` for are left in place.
+pub fn expand(vnode: &mut VNode, resolve: &Resolve<'_>) {
+    expand_at(vnode, resolve, 0);
+}
+
+/// Whether `vnode` has a component anywhere in it.
+pub fn has_components(vnode: &VNode) -> bool {
+    matches!(vnode, VNode::Component { .. }) || vnode.children().iter().any(has_components)
+}
+
+fn expand_at(vnode: &mut VNode, resolve: &Resolve<'_>, depth: usize) {
+    match vnode {
+        VNode::Text(_) => {}
+        VNode::Element { children, .. } | VNode::Fragment(children) => {
+            children
+                .iter_mut()
+                .for_each(|c| expand_at(c, resolve, depth));
+        }
+        VNode::Component {
+            name,
+            props,
+            children,
+        } => {
+            if depth >= MAX_DEPTH {
+                return;
+            }
+            if let Some(mut rendered) = resolve(name, props, children) {
+                expand_at(&mut rendered, resolve, depth + 1);
+                *vnode = rendered;
+            }
+        }
+    }
+}
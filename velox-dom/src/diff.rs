@@ -1,13 +1,35 @@
-use crate::{Props, VNode};
+//! Patches that turn one tree into another.
+//!
+//! A node's patches apply in order. The children of a node are addressed by their index
+//! at the time each patch applies: an `InsertChild`, `RemoveChild` or `MoveChild` shifts
+//! the siblings after it.
+//!
+//! Children with a `key` prop (unique among their siblings) are matched by key rather
+//! than position, as `velox_renderer::reconcile_keyed_children` does, so reordering a
+//! keyed list moves its nodes instead of rewriting each one in place, and only those
+//! outside the longest run already in order. Keyed children's patches come in three
+//! passes: removals (last first), then moves and inserts from the last new index back,
+//! then updates at the new indices.
+//!
+//! `apply` makes the changes, so a tree kept between frames can be patched in place
+//! instead of rebuilt: `apply(&mut old, &diff(&old, &new))` leaves `old` equal to `new`.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::{PropValue, Props, VNode};
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(rename_all = "snake_case"))]
 pub enum Patch {
     Replace(VNode),
-    SetAttr(String, String),
+    SetAttr(String, PropValue),
     RemoveAttr(String),
     UpdateChild(usize, Vec<Patch>),
     InsertChild(usize, VNode),
     RemoveChild(usize),
+    /// Take the child at the first index out and put it back at the second.
+    MoveChild(usize, usize),
 }
 
 pub fn diff(old: &VNode, new: &VNode) -> Vec<Patch> {
@@ -39,10 +61,92 @@ pub fn diff(old: &VNode, new: &VNode) -> Vec<Patch> {
             patches.extend(diff_children(children_a, children_b));
             patches
         }
+        // A fragment is addressed like an element whose children are its nodes.
+        (VNode::Fragment(children_a), VNode::Fragment(children_b)) => diff_children(children_a, children_b),
+        // A component is a boundary: what it renders isn't in the tree to compare, so the
+        // same inputs mean nothing changed and any other ones re-render it.
+        (VNode::Component { .. }, VNode::Component { .. }) if old == new => vec![],
         _ => vec![Patch::Replace(new.clone())],
     }
 }
 
+/// A patch that doesn't fit the tree it was applied to, such as one made for another tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchError {
+    /// The node the patch was for, as child indices from the root.
+    pub path: Vec<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "patch at {:?}: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// Apply `patches` (from `diff`) to `root`, in order. Stops at the first one that doesn't
+/// fit, leaving the tree patched up to it.
+pub fn apply(root: &mut VNode, patches: &[Patch]) -> Result<(), PatchError> {
+    apply_at(root, patches, &mut Vec::new())
+}
+
+fn apply_at(node: &mut VNode, patches: &[Patch], path: &mut Vec<usize>) -> Result<(), PatchError> {
+    let fail = |path: &[usize], message: String| PatchError { path: path.to_vec(), message };
+    for patch in patches {
+        match patch {
+            Patch::Replace(new) => *node = new.clone(),
+            Patch::SetAttr(k, v) => match node {
+                VNode::Element { props, .. } => {
+                    props.insert(k.clone(), v.clone());
+                }
+                _ => return Err(fail(path, format!("cannot set {:?} on a node without props", k))),
+            },
+            Patch::RemoveAttr(k) => match node {
+                VNode::Element { props, .. } => {
+                    props.attrs.remove(k);
+                }
+                _ => return Err(fail(path, format!("cannot remove {:?} from a node without props", k))),
+            },
+            Patch::UpdateChild(i, patches) => {
+                let Some(child) = children_mut(node).and_then(|c| c.get_mut(*i)) else {
+                    return Err(fail(path, format!("no child {} to update", i)));
+                };
+                path.push(*i);
+                apply_at(child, patches, path)?;
+                path.pop();
+            }
+            Patch::InsertChild(i, new) => match children_mut(node) {
+                Some(children) if *i <= children.len() => children.insert(*i, new.clone()),
+                _ => return Err(fail(path, format!("cannot insert a child at {}", i))),
+            },
+            Patch::RemoveChild(i) => match children_mut(node) {
+                Some(children) if *i < children.len() => {
+                    children.remove(*i);
+                }
+                _ => return Err(fail(path, format!("no child {} to remove", i))),
+            },
+            Patch::MoveChild(from, to) => match children_mut(node) {
+                Some(children) if *from < children.len() && *to < children.len() => {
+                    let child = children.remove(*from);
+                    children.insert(*to, child);
+                }
+                _ => return Err(fail(path, format!("cannot move child {} to {}", from, to))),
+            },
+        }
+    }
+    Ok(())
+}
+
+/// The children patches address: an element's or a fragment's.
+fn children_mut(node: &mut VNode) -> Option<&mut Vec<VNode>> {
+    match node {
+        VNode::Element { children, .. } | VNode::Fragment(children) => Some(children),
+        VNode::Text(_) | VNode::Component { .. } => None,
+    }
+}
+
 fn diff_props(a: &Props, b: &Props) -> Vec<Patch> {
     let mut patches = Vec::new();
     // Set new and changed
@@ -54,7 +158,7 @@ fn diff_props(a: &Props, b: &Props) -> Vec<Patch> {
     }
     // Remove missing
     for k in a.attrs.keys() {
-        if !b.attrs.contains_key(k) {
+        if !b.contains(k) {
             patches.push(Patch::RemoveAttr(k.clone()));
         }
     }
@@ -62,6 +166,11 @@ fn diff_props(a: &Props, b: &Props) -> Vec<Patch> {
 }
 
 fn diff_children(a: &[VNode], b: &[VNode]) -> Vec<Patch> {
+    if let (Some(keys_a), Some(keys_b)) = (keys(a), keys(b))
+        && (keys_a.iter().any(Option::is_some) || keys_b.iter().any(Option::is_some))
+    {
+        return diff_keyed_children(a, b, &keys_a, &keys_b);
+    }
     let mut patches = Vec::new();
     let common = a.len().min(b.len());
     for i in 0..common {
@@ -85,3 +194,164 @@ fn diff_children(a: &[VNode], b: &[VNode]) -> Vec<Patch> {
     patches
 }
 
+/// What matches a child to its counterpart in the other list: its key, or for a child
+/// without one, its place among the unkeyed children.
+#[derive(Clone, PartialEq, Eq, Hash)]
+enum Identity {
+    Key(String),
+    Unkeyed(usize),
+}
+
+/// The keys of `children`, or `None` when two share one.
+fn keys(children: &[VNode]) -> Option<Vec<Option<String>>> {
+    let keys: Vec<Option<String>> = children
+        .iter()
+        .map(|c| match c {
+            VNode::Element { props, .. } => props.get("key").map(|k| k.to_string()),
+            _ => None,
+        })
+        .collect();
+    let mut seen = HashSet::new();
+    keys.iter().flatten().all(|k| seen.insert(k)).then_some(keys)
+}
+
+fn identities(keys: &[Option<String>]) -> Vec<Identity> {
+    let mut unkeyed = 0;
+    keys.iter()
+        .map(|k| match k {
+            Some(k) => Identity::Key(k.clone()),
+            None => {
+                unkeyed += 1;
+                Identity::Unkeyed(unkeyed - 1)
+            }
+        })
+        .collect()
+}
+
+fn diff_keyed_children(a: &[VNode], b: &[VNode], keys_a: &[Option<String>], keys_b: &[Option<String>]) -> Vec<Patch> {
+    let (ids_a, ids_b) = (identities(keys_a), identities(keys_b));
+    let old_index: HashMap<&Identity, usize> = ids_a.iter().enumerate().map(|(i, id)| (id, i)).collect();
+    let new_index: HashMap<&Identity, usize> = ids_b.iter().enumerate().map(|(j, id)| (id, j)).collect();
+    let mut patches = Vec::new();
+
+    // Removes, last first so the earlier indices hold. `kept` is the new index of each
+    // child left, in their old order.
+    let mut kept = Vec::with_capacity(b.len());
+    for (i, id) in ids_a.iter().enumerate().rev() {
+        match new_index.get(id) {
+            Some(&j) => kept.push(j),
+            None => patches.push(Patch::RemoveChild(i)),
+        }
+    }
+    kept.reverse();
+
+    // The longest run of kept children already in the new order stays put; the others
+    // move, each once.
+    let stays = longest_increasing(&kept);
+    let mut kept_at = vec![None; b.len()];
+    for (k, &j) in kept.iter().enumerate() {
+        kept_at[j] = Some(k);
+    }
+
+    // Moves and inserts, from the last new index back: each child moved or inserted goes
+    // just before the one after it. Slots order the children of every step, so a Fenwick
+    // tree over them gives a child's index: a kept child starts in slot `(k, 1, 0)`, the
+    // `k`th kept, and a placed one ends in `(s, 0, j)`, before the `s`th kept, the next
+    // child staying after it.
+    let mut placed = vec![None; b.len()];
+    let mut next_staying = kept.len();
+    for j in (0..b.len()).rev() {
+        match kept_at[j] {
+            Some(k) if stays[k] => next_staying = k,
+            _ => placed[j] = Some((next_staying, 0u8, j)),
+        }
+    }
+    let mut slots: Vec<(usize, u8, usize)> =
+        (0..kept.len()).map(|k| (k, 1, 0)).chain(placed.iter().flatten().copied()).collect();
+    slots.sort_unstable();
+    let rank = |slot: (usize, u8, usize)| slots.binary_search(&slot).expect("every slot is ranked");
+    let mut occupied = Occupied::new(slots.len());
+    for k in 0..kept.len() {
+        occupied.add(rank((k, 1, 0)), true);
+    }
+    for j in (0..b.len()).rev() {
+        let Some(slot) = placed[j] else { continue };
+        let from = kept_at[j].map(|k| {
+            let at = rank((k, 1, 0));
+            occupied.add(at, false);
+            occupied.before(at)
+        });
+        let at = rank(slot);
+        let to = occupied.before(at);
+        occupied.add(at, true);
+        match from {
+            Some(from) if from == to => {}
+            Some(from) => patches.push(Patch::MoveChild(from, to)),
+            None => patches.push(Patch::InsertChild(to, b[j].clone())),
+        }
+    }
+
+    // Updates of the nodes kept, where they are now.
+    for (j, id) in ids_b.iter().enumerate() {
+        if let Some(&i) = old_index.get(id) {
+            let child_patches = diff(&a[i], &b[j]);
+            if !child_patches.is_empty() {
+                patches.push(Patch::UpdateChild(j, child_patches));
+            }
+        }
+    }
+    patches
+}
+
+/// Which of `seq`, distinct values, are in a longest increasing subsequence; of several,
+/// the one starting earliest.
+fn longest_increasing(seq: &[usize]) -> Vec<bool> {
+    // Patience sorting from the back: `heads[l]` starts the increasing run of length
+    // `l + 1` with the largest first value, and `next` links each start to the rest.
+    let mut heads: Vec<usize> = Vec::new();
+    let mut next = vec![None; seq.len()];
+    for i in (0..seq.len()).rev() {
+        let l = heads.partition_point(|&h| seq[h] > seq[i]);
+        if l > 0 {
+            next[i] = Some(heads[l - 1]);
+        }
+        match heads.get_mut(l) {
+            Some(head) => *head = i,
+            None => heads.push(i),
+        }
+    }
+    let mut in_run = vec![false; seq.len()];
+    let mut at = heads.last().copied();
+    while let Some(i) = at {
+        in_run[i] = true;
+        at = next[i];
+    }
+    in_run
+}
+
+/// Which slots hold a child, counted with a Fenwick tree.
+struct Occupied(Vec<isize>);
+
+impl Occupied {
+    fn new(len: usize) -> Self {
+        Occupied(vec![0; len + 1])
+    }
+
+    fn add(&mut self, slot: usize, filled: bool) {
+        let mut i = slot + 1;
+        while i < self.0.len() {
+            self.0[i] += if filled { 1 } else { -1 };
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// How many slots before `slot` hold a child: its index among its siblings.
+    fn before(&self, slot: usize) -> usize {
+        let (mut i, mut count) = (slot, 0);
+        while i > 0 {
+            count += self.0[i];
+            i -= i & i.wrapping_neg();
+        }
+        count as usize
+    }
+}
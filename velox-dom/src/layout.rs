@@ -1,4 +1,4 @@
-use crate::VNode;
+use crate::{Props, VNode};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rect { pub x: i32, pub y: i32, pub w: i32, pub h: i32 }
@@ -54,8 +54,9 @@ fn style_lookup_str(style: Option<&str>, key: &str) -> Option<String> {
     None
 }
 
-fn style_box_sides(style: Option<&str>, base: &str) -> (i32, i32, i32, i32) {
-    // returns (left, right, top, bottom)
+/// The `margin` or `padding` (`base`) sides set in an inline style, as the layout reads
+/// them: `(left, right, top, bottom)` in px.
+pub fn style_box_sides(style: Option<&str>, base: &str) -> (i32, i32, i32, i32) {
     let s = style.unwrap_or("");
     let mut get = |k: &str| -> Option<i32> {
         for decl in s.split(';') {
@@ -78,166 +79,258 @@ fn style_box_sides(style: Option<&str>, base: &str) -> (i32, i32, i32, i32) {
 /// Very simple block layout: each element is stacked vertically, full width
 /// unless width/height are provided via inline `style` (width/height in px).
 pub fn compute_layout(node: &VNode, viewport_w: i32, viewport_h: i32) -> LayoutNode {
-    fn at(node: &VNode, x: i32, y: i32, avail_w: i32, avail_h: i32) -> LayoutNode {
-        match node {
-            VNode::Text(t) => {
-                let len = t.chars().count() as i32;
-                let w = if len > 0 { len * 8 } else { 0 }; // simple estimate
-                LayoutNode { rect: Rect { x, y, w, h: 16 }, children: vec![] }
+    layout_at(node, 0, 0, viewport_w, viewport_h, false)
+}
+
+/// Parallel `compute_layout`: element children of wide nodes are laid out concurrently on
+/// the rayon pool and then placed, giving the same tree as the sequential pass.
+#[cfg(feature = "parallel")]
+pub fn compute_layout_parallel(node: &VNode, viewport_w: i32, viewport_h: i32) -> LayoutNode {
+    layout_at(node, 0, 0, viewport_w, viewport_h, true)
+}
+
+/// Below this many element children a node is laid out on the current thread.
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_CHILDREN: usize = 8;
+
+fn translated(node: &LayoutNode, dx: i32, dy: i32) -> LayoutNode {
+    LayoutNode {
+        rect: Rect { x: node.rect.x + dx, y: node.rect.y + dy, ..node.rect },
+        children: node.children.iter().map(|c| translated(c, dx, dy)).collect(),
+    }
+}
+
+/// An element child's layout depends only on its available size, never on where it is
+/// placed, so wide nodes can lay out element children at the origin up front (in
+/// parallel) and translate them into position. Text children stay inline.
+#[cfg(feature = "parallel")]
+fn layout_children_at_origin(children: &[&VNode], avail_w: i32, avail_h: i32, parallel: bool) -> Vec<Option<LayoutNode>> {
+    use rayon::prelude::*;
+    let elements = children.iter().filter(|c| matches!(c, VNode::Element { .. })).count();
+    if !parallel || elements < PARALLEL_MIN_CHILDREN {
+        return Vec::new();
+    }
+    children
+        .par_iter()
+        .map(|c| match c {
+            VNode::Element { .. } => Some(layout_at(c, 0, 0, avail_w, avail_h, true)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn layout_children_at_origin(_children: &[&VNode], _avail_w: i32, _avail_h: i32, _parallel: bool) -> Vec<Option<LayoutNode>> {
+    Vec::new()
+}
+
+fn layout_at(node: &VNode, x: i32, y: i32, avail_w: i32, avail_h: i32, parallel: bool) -> LayoutNode {
+    match node {
+        VNode::Text(t) => {
+            let len = t.chars().count() as i32;
+            let w = if len > 0 { len * 8 } else { 0 }; // simple estimate
+            LayoutNode { rect: Rect { x, y, w, h: 16 }, children: vec![] }
+        }
+        VNode::Element { tag, props, children } => layout_element(tag, props, children, x, y, avail_w, avail_h, parallel),
+        // Laid out on its own (as a root), a fragment is an anonymous block.
+        VNode::Fragment(children) => layout_element("", &Props::new(), children, x, y, avail_w, avail_h, parallel),
+        // Only left in the tree when nothing renders it.
+        VNode::Component { .. } => LayoutNode { rect: Rect { x, y, w: 0, h: 0 }, children: vec![] },
+    }
+}
+
+/// Regroup the layouts of `flatten_fragments(children)` into the shape of `children`: a
+/// fragment gets the bounds of its nodes (an empty one a point where it would be).
+fn regroup(children: &[VNode], flat: &mut std::vec::IntoIter<LayoutNode>, at: (i32, i32)) -> Vec<LayoutNode> {
+    let mut out = Vec::with_capacity(children.len());
+    let mut cursor = at;
+    for c in children {
+        let ln = match c {
+            VNode::Fragment(inner) => {
+                let kids = regroup(inner, flat, cursor);
+                let rect = kids.iter().map(|k| k.rect).reduce(|a, b| {
+                    let (x, y) = (a.x.min(b.x), a.y.min(b.y));
+                    let (r, bottom) = ((a.x + a.w).max(b.x + b.w), (a.y + a.h).max(b.y + b.h));
+                    Rect { x, y, w: r - x, h: bottom - y }
+                });
+                LayoutNode { rect: rect.unwrap_or(Rect { x: cursor.0, y: cursor.1, w: 0, h: 0 }), children: kids }
             }
-            VNode::Element { tag, props, children } => {
-                let style = props.attrs.get("style").map(|s| s.as_str());
-                let (ml, mr, mt, mb) = style_box_sides(style, "margin");
-                let (pl, pr, pt, pb) = style_box_sides(style, "padding");
-                let is_root = matches!(tag.as_str(), "body" | "html");
-
-                // Element outer position with margins
-                let elem_x = x + ml;
-                let elem_y = y + mt;
-
-                // Determine width: if set, use as content+padding width; else take available width
-                let declared_w = style_lookup_len(style, "width", avail_w);
-                let rect_w = if is_root {
-                    (avail_w - ml - mr).max(1)
-                } else {
-                    declared_w.unwrap_or(avail_w)
-                };
-
-                // Content box
-                let content_x = elem_x + pl;
-                let content_y_start = elem_y + pt;
-                let content_w = (rect_w - pl - pr).max(0);
-
-                // Layout strategy: block (default) or flex
-                let display = props.attrs.get("style").and_then(|s| {
-                    for decl in s.split(';') { let d=decl.trim(); if d.is_empty(){continue;} if let Some((k,v))=d.split_once(':'){ if k.trim()=="display" { return Some(v.trim()); } } }
-                    None
-                }).unwrap_or("block");
-
-                let mut laid_children = Vec::new();
-                if display == "flex" {
-                    // Minimal flexbox: direction (row|column), gap, align-items (start|center|end), justify-content (flex-start|center|space-between)
-                    let flex_dir = props.attrs.get("style").and_then(|s| {
-                        for decl in s.split(';') { let d=decl.trim(); if d.is_empty(){continue;} if let Some((k,v))=d.split_once(':'){ if k.trim()=="flex-direction" { return Some(v.trim()); } } }
-                        None
-                    }).unwrap_or("row");
-                    let gap = style_lookup_len(style, "gap", 0).unwrap_or(0);
-                    let mut cursor_x = content_x;
-                    let mut cursor_y = content_y_start;
-                    let mut line_max_h = 0;
-                    if flex_dir == "column" {
-                        for c in children {
-                            let child_ln = at(c, content_x, cursor_y, content_w, (avail_h - pt - pb).max(0));
-                            cursor_y = child_ln.rect.y + child_ln.rect.h + gap;
-                            laid_children.push(child_ln);
-                        }
-                    } else { // row
-                        for c in children {
-                            let child_ln = at(c, cursor_x, content_y_start, content_w, (avail_h - pt - pb).max(0));
-                            cursor_x = child_ln.rect.x + child_ln.rect.w + gap;
-                            if child_ln.rect.h > line_max_h { line_max_h = child_ln.rect.h; }
-                            laid_children.push(child_ln);
-                        }
-                        // set all y to top for now (no align-items support beyond start)
-                        for ln in &mut laid_children { ln.rect.y = content_y_start; }
-                    }
-                } else { // block with inline text flow
-                    let mut cur_x = content_x;
-                    let mut cur_y = content_y_start;
-                    let mut line_h = 0;
-                    let mut max_y_end = content_y_start;
-                    for c in children {
-                        let is_text = matches!(c, VNode::Text(_));
-                        if !is_text && cur_x != content_x {
-                            cur_y += line_h;
-                            cur_x = content_x;
-                            line_h = 0;
-                        }
-
-                        let child_ln = at(
-                            c,
-                            cur_x,
-                            cur_y,
-                            (content_w - (cur_x - content_x)).max(0),
-                            (avail_h - pt - pb).max(0),
-                        );
-
-                        if is_text {
-                            let line_limit = content_x + content_w;
-                            if cur_x != content_x && (cur_x + child_ln.rect.w) > line_limit {
-                                cur_y += line_h.max(child_ln.rect.h);
-                                cur_x = content_x;
-                                line_h = 0;
-                            }
-                        }
-
-                        let child_ln = if is_text {
-                            at(
-                                c,
-                                cur_x,
-                                cur_y,
-                                (content_w - (cur_x - content_x)).max(0),
-                                (avail_h - pt - pb).max(0),
-                            )
-                        } else {
-                            child_ln
-                        };
-
-                        if is_text {
-                            cur_x += child_ln.rect.w;
-                            line_h = line_h.max(child_ln.rect.h);
-                        } else {
-                            let child_style = match c { VNode::Element { props, .. } => props.attrs.get("style").map(|s| s.as_str()), _ => None };
-                            let (_cml, _cmr, _cmt, cmb) = style_box_sides(child_style, "margin");
-                            cur_y = child_ln.rect.y + child_ln.rect.h + cmb;
-                            cur_x = content_x;
-                            line_h = 0;
-                        }
-
-                        max_y_end = max_y_end.max(child_ln.rect.y + child_ln.rect.h);
-                        laid_children.push(child_ln);
-                    }
-                    if line_h > 0 {
-                        max_y_end = max_y_end.max(cur_y + line_h);
-                    }
-                    cur_y = max_y_end;
-                }
+            _ => match flat.next() {
+                Some(ln) => ln,
+                None => break,
+            },
+        };
+        cursor = (ln.rect.x + ln.rect.w, ln.rect.y);
+        out.push(ln);
+    }
+    out
+}
+
+#[allow(clippy::too_many_arguments)]
+fn layout_element(tag: &str, props: &Props, children: &[VNode], x: i32, y: i32, avail_w: i32, avail_h: i32, parallel: bool) -> LayoutNode {
+    let at = |node: &VNode, x: i32, y: i32, avail_w: i32, avail_h: i32| layout_at(node, x, y, avail_w, avail_h, parallel);
+    let flat = crate::flatten_fragments(children);
+    let style = props.str("style");
+    let (ml, mr, mt, mb) = style_box_sides(style, "margin");
+    let (pl, pr, pt, pb) = style_box_sides(style, "padding");
+    let is_root = matches!(tag, "body" | "html");
+
+    // Element outer position with margins
+    let elem_x = x + ml;
+    let elem_y = y + mt;
+
+    // Determine width: if set, use as content+padding width; else take available width
+    let declared_w = style_lookup_len(style, "width", avail_w);
+    let rect_w = if is_root {
+        (avail_w - ml - mr).max(1)
+    } else {
+        declared_w.unwrap_or(avail_w)
+    };
 
-                // Height: declared or content height + paddings
-                let declared_h = style_lookup_len(style, "height", avail_h);
-                let content_h = laid_children
-                    .iter()
-                    .map(|c| c.rect.y + c.rect.h)
-                    .max()
-                    .map(|max_y| (max_y - content_y_start).max(0))
-                    .unwrap_or(0);
-                let rect_h = if is_root {
-                    (avail_h - mt - mb).max(1)
-                } else {
-                    declared_h.unwrap_or(content_h + pt + pb)
-                };
-
-                if tag == "button" && children.len() == 1 {
-                    if let Some(child) = laid_children.get_mut(0) {
-                        let content_h = (rect_h - pt - pb).max(0);
-                        let child_h = child.rect.h;
-                        let offset_y = ((content_h - child_h).max(0)) / 2;
-                        child.rect.y = elem_y + pt + offset_y;
-
-                        let align = style_lookup_str(style, "text-align").unwrap_or_else(|| "left".to_string());
-                        let child_w = child.rect.w;
-                        let offset_x = match align.as_str() {
-                            "center" => ((content_w - child_w).max(0)) / 2,
-                            "right" => (content_w - child_w).max(0),
-                            _ => 0,
-                        };
-                        child.rect.x = content_x + offset_x;
-                    }
+    // Content box
+    let content_x = elem_x + pl;
+    let content_y_start = elem_y + pt;
+    let content_w = (rect_w - pl - pr).max(0);
+
+    // Layout strategy: block (default) or flex
+    let display = props.str("style").and_then(|s| {
+        for decl in s.split(';') { let d=decl.trim(); if d.is_empty(){continue;} if let Some((k,v))=d.split_once(':'){ if k.trim()=="display" { return Some(v.trim()); } } }
+        None
+    }).unwrap_or("block");
+
+    let child_avail_h = (avail_h - pt - pb).max(0);
+    let at_origin = layout_children_at_origin(&flat, content_w, child_avail_h, parallel);
+    let place = |i: usize, c: &VNode, x: i32, y: i32, aw: i32, ah: i32| match at_origin.get(i) {
+        Some(Some(ln)) => translated(ln, x, y),
+        _ => at(c, x, y, aw, ah),
+    };
+
+    let mut laid_children = Vec::new();
+    if display == "flex" {
+        // Minimal flexbox: direction (row|column), gap, align-items (start|center|end), justify-content (flex-start|center|space-between)
+        let flex_dir = props.str("style").and_then(|s| {
+            for decl in s.split(';') { let d=decl.trim(); if d.is_empty(){continue;} if let Some((k,v))=d.split_once(':'){ if k.trim()=="flex-direction" { return Some(v.trim()); } } }
+            None
+        }).unwrap_or("row");
+        let gap = style_lookup_len(style, "gap", 0).unwrap_or(0);
+        let mut cursor_x = content_x;
+        let mut cursor_y = content_y_start;
+        let mut line_max_h = 0;
+        if flex_dir == "column" {
+            for (i, &c) in flat.iter().enumerate() {
+                let child_ln = place(i, c, content_x, cursor_y, content_w, child_avail_h);
+                cursor_y = child_ln.rect.y + child_ln.rect.h + gap;
+                laid_children.push(child_ln);
+            }
+        } else { // row
+            for (i, &c) in flat.iter().enumerate() {
+                let child_ln = place(i, c, cursor_x, content_y_start, content_w, child_avail_h);
+                cursor_x = child_ln.rect.x + child_ln.rect.w + gap;
+                if child_ln.rect.h > line_max_h { line_max_h = child_ln.rect.h; }
+                laid_children.push(child_ln);
+            }
+            // set all y to top for now (no align-items support beyond start)
+            for ln in &mut laid_children { ln.rect.y = content_y_start; }
+        }
+    } else { // block with inline text flow
+        let mut cur_x = content_x;
+        let mut cur_y = content_y_start;
+        let mut line_h = 0;
+        let mut max_y_end = content_y_start;
+        for (i, &c) in flat.iter().enumerate() {
+            let is_text = matches!(c, VNode::Text(_));
+            if !is_text && cur_x != content_x {
+                cur_y += line_h;
+                cur_x = content_x;
+                line_h = 0;
+            }
+
+            let child_ln = place(
+                i,
+                c,
+                cur_x,
+                cur_y,
+                (content_w - (cur_x - content_x)).max(0),
+                child_avail_h,
+            );
+
+            if is_text {
+                let line_limit = content_x + content_w;
+                if cur_x != content_x && (cur_x + child_ln.rect.w) > line_limit {
+                    cur_y += line_h.max(child_ln.rect.h);
+                    cur_x = content_x;
+                    line_h = 0;
                 }
+            }
 
-                LayoutNode { rect: Rect { x: elem_x, y: elem_y, w: rect_w, h: rect_h }, children: laid_children }
+            let child_ln = if is_text {
+                at(
+                    c,
+                    cur_x,
+                    cur_y,
+                    (content_w - (cur_x - content_x)).max(0),
+                    (avail_h - pt - pb).max(0),
+                )
+            } else {
+                child_ln
+            };
+
+            if is_text {
+                cur_x += child_ln.rect.w;
+                line_h = line_h.max(child_ln.rect.h);
+            } else {
+                let child_style = match c { VNode::Element { props, .. } => props.str("style"), _ => None };
+                let (_cml, _cmr, _cmt, cmb) = style_box_sides(child_style, "margin");
+                cur_y = child_ln.rect.y + child_ln.rect.h + cmb;
+                cur_x = content_x;
+                line_h = 0;
             }
+
+            max_y_end = max_y_end.max(child_ln.rect.y + child_ln.rect.h);
+            laid_children.push(child_ln);
+        }
+        if line_h > 0 {
+            max_y_end = max_y_end.max(cur_y + line_h);
         }
+        cur_y = max_y_end;
     }
-    at(node, 0, 0, viewport_w, viewport_h)
+
+    // Height: declared or content height + paddings
+    let declared_h = style_lookup_len(style, "height", avail_h);
+    let content_h = laid_children
+        .iter()
+        .map(|c| c.rect.y + c.rect.h)
+        .max()
+        .map(|max_y| (max_y - content_y_start).max(0))
+        .unwrap_or(0);
+    let rect_h = if is_root {
+        (avail_h - mt - mb).max(1)
+    } else {
+        declared_h.unwrap_or(content_h + pt + pb)
+    };
+
+    if tag == "button" && flat.len() == 1 {
+        if let Some(child) = laid_children.get_mut(0) {
+            let content_h = (rect_h - pt - pb).max(0);
+            let child_h = child.rect.h;
+            let offset_y = ((content_h - child_h).max(0)) / 2;
+            child.rect.y = elem_y + pt + offset_y;
+
+            let align = style_lookup_str(style, "text-align").unwrap_or_else(|| "left".to_string());
+            let child_w = child.rect.w;
+            let offset_x = match align.as_str() {
+                "center" => ((content_w - child_w).max(0)) / 2,
+                "right" => (content_w - child_w).max(0),
+                _ => 0,
+            };
+            child.rect.x = content_x + offset_x;
+        }
+    }
+
+    let laid_children = if children.iter().any(|c| matches!(c, VNode::Fragment(_))) {
+        regroup(children, &mut laid_children.into_iter(), (content_x, content_y_start))
+    } else {
+        laid_children
+    };
+    LayoutNode { rect: Rect { x: elem_x, y: elem_y, w: rect_w, h: rect_h }, children: laid_children }
 }
@@ -1,18 +1,51 @@
 use std::collections::HashMap;
 
+/// With the `serde` feature a text node is a string, an element an object with `tag`,
+/// `attrs`, and `children`, a fragment an array of nodes, and a component an object with
+/// `component`, `attrs`, and `children` (see `wire`).
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(untagged))]
 pub enum VNode {
     Element {
         tag: String,
+        #[cfg_attr(feature = "serde", serde(rename = "attrs", default))]
         props: Props,
+        #[cfg_attr(feature = "serde", serde(default))]
         children: Vec<VNode>,
     },
     Text(String),
+    /// Sibling nodes without an element around them, e.g. a template with several roots.
+    /// Layout and styling see its children as children of the fragment's parent; it has
+    /// no box of its own.
+    Fragment(Vec<VNode>),
+    /// A use of the component registered as `name`, e.g. `<TodoItem :item="x"/>`, which
+    /// `component::expand` replaces with what it renders for `props` and `children`.
+    /// Diffing compares it as a whole without looking at what it renders; anywhere else
+    /// an unexpanded one is an empty node with no box.
+    Component {
+        #[cfg_attr(feature = "serde", serde(rename = "component"))]
+        name: String,
+        #[cfg_attr(feature = "serde", serde(rename = "attrs", default))]
+        props: Props,
+        #[cfg_attr(feature = "serde", serde(default))]
+        children: Vec<VNode>,
+    },
+}
+
+impl VNode {
+    /// An element's or fragment's children; none for text or a component, whose children
+    /// are only its input until it is expanded.
+    pub fn children(&self) -> &[VNode] {
+        match self {
+            VNode::Element { children, .. } | VNode::Fragment(children) => children,
+            VNode::Text(_) | VNode::Component { .. } => &[],
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Props {
-    pub attrs: HashMap<String, String>,
+    pub attrs: HashMap<String, PropValue>,
 }
 
 impl Props {
@@ -21,10 +54,44 @@ impl Props {
             attrs: HashMap::new(),
         }
     }
-    pub fn set(mut self, k: impl Into<String>, v: impl Into<String>) -> Self {
-        self.attrs.insert(k.into(), v.into());
+    pub fn set(mut self, k: impl Into<String>, v: impl Into<PropValue>) -> Self {
+        self.insert(k, v);
         self
     }
+
+    /// Set `k`, returning what it was. A string under an `on:<event>` key is stored as
+    /// a `PropValue::Handler`.
+    pub fn insert(&mut self, k: impl Into<String>, v: impl Into<PropValue>) -> Option<PropValue> {
+        let k = k.into();
+        let v = match v.into() {
+            PropValue::Str(s) if prop::is_handler_key(&k) => PropValue::Handler(s),
+            v => v,
+        };
+        self.attrs.insert(k, v)
+    }
+
+    pub fn get(&self, k: &str) -> Option<&PropValue> {
+        self.attrs.get(k)
+    }
+
+    /// The text of the string or handler under `k`.
+    pub fn str(&self, k: &str) -> Option<&str> {
+        self.attrs.get(k).and_then(PropValue::as_str)
+    }
+
+    /// The number under `k`, or a string there that parses as one.
+    pub fn num(&self, k: &str) -> Option<f64> {
+        self.attrs.get(k).and_then(PropValue::as_num)
+    }
+
+    /// The flag under `k`, or a `true`/`false` string there.
+    pub fn flag(&self, k: &str) -> Option<bool> {
+        self.attrs.get(k).and_then(PropValue::as_bool)
+    }
+
+    pub fn contains(&self, k: &str) -> bool {
+        self.attrs.contains_key(k)
+    }
 }
 
 // Allow concise props creation
@@ -37,7 +104,7 @@ impl From<Vec<(&str, &str)>> for Props {
     fn from(v: Vec<(&str, &str)>) -> Self {
         let mut p = Props::new();
         for (k, v) in v {
-            p.attrs.insert(k.to_string(), v.to_string());
+            p.insert(k, v);
         }
         p
     }
@@ -53,9 +120,37 @@ pub fn h(tag: impl Into<String>, props: impl Into<Props>, children: Vec<VNode>)
 pub fn text(t: impl Into<String>) -> VNode {
     VNode::Text(t.into())
 }
+pub fn fragment(children: Vec<VNode>) -> VNode {
+    VNode::Fragment(children)
+}
+pub fn component(name: impl Into<String>, props: impl Into<Props>, children: Vec<VNode>) -> VNode {
+    VNode::Component { name: name.into(), props: props.into(), children }
+}
+
+/// `children` with every fragment among them replaced by its own children, recursively:
+/// the nodes a parent lays out and styles as its children.
+pub fn flatten_fragments(children: &[VNode]) -> Vec<&VNode> {
+    fn push<'a>(children: &'a [VNode], out: &mut Vec<&'a VNode>) {
+        for c in children {
+            match c {
+                VNode::Fragment(inner) => push(inner, out),
+                _ => out.push(c),
+            }
+        }
+    }
+    let mut out = Vec::with_capacity(children.len());
+    push(children, &mut out);
+    out
+}
 
+pub mod component;
 pub mod diff;
 pub mod layout;
+pub mod prop;
+#[cfg(feature = "serde")]
+pub mod wire;
+
+pub use prop::PropValue;
 
 #[cfg(test)]
 mod tests {
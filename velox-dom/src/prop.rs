@@ -0,0 +1,147 @@
+//! Typed prop values.
+//!
+//! A prop is a string (`class`, `style`), a number, a flag, the name of the app handler
+//! an `on:<event>` prop dispatches, or a list of values. `Props::set` takes any of them.
+//!
+//! A string set under an `on:<event>` key (but not its `on:<event>-payload`) is a
+//! `Handler`. Readers that want text use `Props::str`, which gives it for strings and
+//! handlers; `Display` writes any value the way a template would have spelled it.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropValue {
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    /// The name of the app handler an `on:<event>` prop dispatches.
+    Handler(String),
+    List(Vec<PropValue>),
+}
+
+impl PropValue {
+    /// The text of a string or handler.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            PropValue::Str(s) | PropValue::Handler(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// A number, or a string that parses as one.
+    pub fn as_num(&self) -> Option<f64> {
+        match self {
+            PropValue::Num(n) => Some(*n),
+            PropValue::Str(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// A flag, or the string `true` or `false`.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            PropValue::Bool(b) => Some(*b),
+            PropValue::Str(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[PropValue]> {
+        match self {
+            PropValue::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn is_handler(&self) -> bool {
+        matches!(self, PropValue::Handler(_))
+    }
+}
+
+/// Whether a string under `key` names a handler: `on:<event>`, but not the
+/// `on:<event>-payload` sent with it.
+pub fn is_handler_key(key: &str) -> bool {
+    key.starts_with("on:") && !key.ends_with("-payload")
+}
+
+/// Strings and handlers as they are, numbers and flags as Rust prints them, and lists
+/// space-separated (as a `class` list is).
+impl fmt::Display for PropValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropValue::Str(s) | PropValue::Handler(s) => f.write_str(s),
+            PropValue::Num(n) => write!(f, "{}", n),
+            PropValue::Bool(b) => write!(f, "{}", b),
+            PropValue::List(items) => {
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(" ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A string or handler with this text.
+impl PartialEq<str> for PropValue {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for PropValue {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl PartialEq<String> for PropValue {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == Some(other.as_str())
+    }
+}
+
+impl From<&str> for PropValue {
+    fn from(s: &str) -> Self {
+        PropValue::Str(s.to_string())
+    }
+}
+
+impl From<String> for PropValue {
+    fn from(s: String) -> Self {
+        PropValue::Str(s)
+    }
+}
+
+impl From<&String> for PropValue {
+    fn from(s: &String) -> Self {
+        PropValue::Str(s.clone())
+    }
+}
+
+impl From<bool> for PropValue {
+    fn from(b: bool) -> Self {
+        PropValue::Bool(b)
+    }
+}
+
+impl From<Vec<PropValue>> for PropValue {
+    fn from(items: Vec<PropValue>) -> Self {
+        PropValue::List(items)
+    }
+}
+
+macro_rules! from_number {
+    ($($t:ty),*) => {
+        $(impl From<$t> for PropValue {
+            fn from(n: $t) -> Self {
+                PropValue::Num(n as f64)
+            }
+        })*
+    };
+}
+
+from_number!(f64, f32, i32, i64, u32, u64, usize);
@@ -0,0 +1,121 @@
+//! The serialized form of trees and patches, shared by snapshots, IPC, devtools, and
+//! persistence.
+//!
+//! A `VNode` text node is a string, and an element an object with its `tag`, `attrs` and
+//! `children` (either of the last two may be left out). A fragment is an array of its
+//! nodes, and a component an object with its `component` name, `attrs` and `children`.
+//! `Props` serialize as their attribute map, keys sorted so the same tree always gives
+//! the same bytes, and a handler as its name. A `Patch` is tagged with its snake_case
+//! variant name, e.g. `{"set_attr":["class","primary"]}`.
+//!
+//! Anything stored or sent to another program goes in a `Versioned` envelope. Readers
+//! ignore fields they don't know, so adding a field with a default keeps
+//! `SCHEMA_VERSION`; other changes bump it, and readers refuse versions they don't know.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{PropValue, Props};
+
+/// The version of the format this build reads and writes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// `data` stamped with the schema version it was written in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Versioned<T> {
+    pub schema: u32,
+    pub data: T,
+}
+
+impl<T> Versioned<T> {
+    /// Stamp `data` with `SCHEMA_VERSION`.
+    pub fn new(data: T) -> Self {
+        Versioned {
+            schema: SCHEMA_VERSION,
+            data,
+        }
+    }
+
+    /// The data, if it was written in a version this build can read.
+    pub fn into_data(self) -> Result<T, SchemaError> {
+        match self.schema {
+            SCHEMA_VERSION => Ok(self.data),
+            found => Err(SchemaError { found }),
+        }
+    }
+}
+
+/// Data written in a schema version this build does not read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    pub found: u32,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "schema version {} is not supported (this build reads {})",
+            self.found, SCHEMA_VERSION
+        )
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Serialize a map with sorted keys; for `HashMap` fields of serialized types.
+pub fn serialize_sorted<V: Serialize, S: Serializer>(
+    map: &HashMap<String, V>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+impl Serialize for Props {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_sorted(&self.attrs, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Props {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let attrs: HashMap<String, PropValue> = HashMap::deserialize(deserializer)?;
+        let mut props = Props::new();
+        for (k, v) in attrs {
+            props.insert(k, v);
+        }
+        Ok(props)
+    }
+}
+
+impl Serialize for PropValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            PropValue::Str(s) | PropValue::Handler(s) => serializer.serialize_str(s),
+            PropValue::Num(n) => serializer.serialize_f64(*n),
+            PropValue::Bool(b) => serializer.serialize_bool(*b),
+            PropValue::List(items) => items.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for PropValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Value {
+            Str(String),
+            Num(f64),
+            Bool(bool),
+            List(Vec<PropValue>),
+        }
+        Ok(match Value::deserialize(deserializer)? {
+            Value::Str(s) => PropValue::Str(s),
+            Value::Num(n) => PropValue::Num(n),
+            Value::Bool(b) => PropValue::Bool(b),
+            Value::List(items) => PropValue::List(items),
+        })
+    }
+}
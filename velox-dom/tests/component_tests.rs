@@ -0,0 +1,112 @@
+use velox_dom::component::{Registry, has_components};
+use velox_dom::diff::{Patch, diff};
+use velox_dom::layout::compute_layout;
+use velox_dom::{Props, VNode, component, h, text};
+
+fn todo_item(props: &Props, children: &[VNode]) -> VNode {
+    let label = props.str("item").unwrap_or_default().to_string();
+    let mut kids = vec![text(label)];
+    kids.extend(children.iter().cloned());
+    h("li", Props::new().set("class", "todo"), kids)
+}
+
+#[test]
+fn registered_components_expand_into_what_they_render() {
+    let mut components = Registry::new();
+    components.register("TodoItem", todo_item);
+    components.register("TodoList", |props: &Props, _: &[VNode]| {
+        let items = props.str("items").unwrap_or("");
+        let uses = items
+            .split(',')
+            .map(|i| component("TodoItem", Props::new().set("item", i), vec![]))
+            .collect();
+        h("ul", (), uses)
+    });
+
+    let mut tree = h(
+        "main",
+        (),
+        vec![
+            component("TodoList", Props::new().set("items", "Milk,Eggs"), vec![]),
+            component(
+                "TodoItem",
+                Props::new().set("item", "Bread"),
+                vec![h("b", (), vec![text("!")])],
+            ),
+            component("Missing", (), vec![]),
+        ],
+    );
+    components.expand(&mut tree);
+
+    let li = |label: &str, extra: Vec<VNode>| {
+        let mut kids = vec![text(label)];
+        kids.extend(extra);
+        h("li", Props::new().set("class", "todo"), kids)
+    };
+    assert_eq!(
+        tree,
+        h(
+            "main",
+            (),
+            vec![
+                h("ul", (), vec![li("Milk", vec![]), li("Eggs", vec![])]),
+                li("Bread", vec![h("b", (), vec![text("!")])]),
+                component("Missing", (), vec![]),
+            ]
+        )
+    );
+    assert!(has_components(&tree));
+
+    // An unexpanded component takes no space.
+    let layout = compute_layout(&tree, 200, 200);
+    assert_eq!(
+        (layout.children[2].rect.w, layout.children[2].rect.h),
+        (0, 0)
+    );
+}
+
+#[test]
+fn diff_stops_at_components() {
+    let old = h(
+        "ul",
+        (),
+        vec![
+            component("TodoItem", Props::new().set("item", "Milk"), vec![]),
+            component("TodoItem", Props::new().set("item", "Eggs"), vec![]),
+        ],
+    );
+    let new = h(
+        "ul",
+        (),
+        vec![
+            component("TodoItem", Props::new().set("item", "Milk"), vec![]),
+            component("TodoItem", Props::new().set("item", "Bread"), vec![]),
+        ],
+    );
+    assert_eq!(
+        diff(&old, &new),
+        vec![Patch::UpdateChild(
+            1,
+            vec![Patch::Replace(new.children()[1].clone())]
+        )]
+    );
+}
+
+#[test]
+fn a_component_that_renders_itself_stops_expanding() {
+    let mut components = Registry::new();
+    components.register("Loop", |_: &Props, _: &[VNode]| {
+        h("div", (), vec![component("Loop", (), vec![])])
+    });
+    let mut tree = component("Loop", (), vec![]);
+    components.expand(&mut tree);
+
+    let mut depth = 0;
+    let mut node = &tree;
+    while let VNode::Element { children, .. } = node {
+        depth += 1;
+        node = &children[0];
+    }
+    assert_eq!(depth, velox_dom::component::MAX_DEPTH);
+    assert_eq!(node, &component("Loop", (), vec![]));
+}
@@ -1,4 +1,4 @@
-use velox_dom::{diff::{diff, Patch}, h, text, Props, VNode};
+use velox_dom::{diff::{apply, diff, Patch, PatchError}, fragment, h, text, Props, VNode};
 
 #[test]
 fn props_set_and_remove() {
@@ -49,3 +49,80 @@ fn text_change_replaces() {
     assert_eq!(patches, vec![Patch::Replace(b.clone())]);
 }
 
+#[test]
+fn fragments_diff_their_children() {
+    let a = fragment(vec![h("p", Props::new(), vec![text("a")]), text("b")]);
+    let b = fragment(vec![h("p", Props::new(), vec![text("a")]), text("b"), text("c")]);
+    assert_eq!(diff(&a, &b), vec![Patch::InsertChild(2, text("c"))]);
+    assert_eq!(diff(&a, &text("a")), vec![Patch::Replace(text("a"))]);
+}
+
+#[test]
+fn keyed_children_move_instead_of_rewriting_in_place() {
+    let item = |k: &str| h("li", Props::new().set("key", k), vec![text(k)]);
+    let list = |keys: &[&str]| h("ul", Props::new(), keys.iter().map(|k| item(k)).collect());
+
+    let a = list(&["a", "b", "c"]);
+    assert_eq!(diff(&a, &list(&["x", "a", "b", "c"])), vec![Patch::InsertChild(0, item("x"))]);
+    assert_eq!(diff(&a, &list(&["c", "a", "b"])), vec![Patch::MoveChild(2, 0)]);
+    assert_eq!(diff(&a, &list(&["c", "b"])), vec![Patch::RemoveChild(0), Patch::MoveChild(1, 0)]);
+
+    // A kept node's own changes come last, at its new index.
+    let b = h("ul", Props::new(), vec![item("b"), h("li", Props::new().set("key", "a").set("class", "done"), vec![text("a")])]);
+    assert_eq!(
+        diff(&list(&["a", "b"]), &b),
+        vec![Patch::MoveChild(1, 0), Patch::UpdateChild(1, vec![Patch::SetAttr("class".into(), "done".into())])]
+    );
+
+    // Only the children outside the longest run already in order move.
+    assert_eq!(diff(&list(&["a", "b", "c", "d"]), &list(&["b", "c", "d", "a"])), vec![Patch::MoveChild(0, 3)]);
+}
+
+#[test]
+fn reversing_a_long_keyed_list_moves_all_but_one() {
+    let list = |keys: &mut dyn Iterator<Item = usize>| {
+        let items = keys.map(|k| h("li", Props::new().set("key", k.to_string()), vec![text(k.to_string())])).collect();
+        h("ul", Props::new(), items)
+    };
+    let old = list(&mut (0..1000));
+    let new = list(&mut (0..1000).rev());
+    let patches = diff(&old, &new);
+    assert_eq!(patches.len(), 999);
+    assert!(patches.iter().all(|p| matches!(p, Patch::MoveChild(..))));
+    let mut tree = old.clone();
+    apply(&mut tree, &patches).unwrap();
+    assert_eq!(tree, new);
+
+    // A shuffle with removals and inserts.
+    let shuffled = list(&mut (0..1200).map(|i| (i * 337) % 1200).filter(|k| k % 7 != 0));
+    let mut tree = old.clone();
+    apply(&mut tree, &diff(&old, &shuffled)).unwrap();
+    assert_eq!(tree, shuffled);
+}
+
+#[test]
+fn apply_turns_the_old_tree_into_the_new_one() {
+    let item = |k: &str, class: &str| h("li", Props::new().set("key", k).set("class", class), vec![text(k)]);
+    let pairs = [
+        (
+            h("ul", Props::new().set("id", "list"), vec![item("a", ""), item("b", ""), item("c", "")]),
+            h("ul", Props::new().set("role", "list"), vec![item("d", ""), item("c", "done"), item("a", "")]),
+        ),
+        (
+            h("div", Props::new(), vec![text("a"), h("p", Props::new().set("on:click", "go"), vec![text("b")])]),
+            h("div", Props::new(), vec![text("x"), h("p", Props::new(), vec![]), text("y")]),
+        ),
+        (fragment(vec![text("a"), text("b")]), fragment(vec![h("hr", Props::new(), vec![])])),
+        (text("a"), h("p", Props::new(), vec![])),
+    ];
+    for (old, new) in pairs {
+        let mut tree = old.clone();
+        apply(&mut tree, &diff(&old, &new)).unwrap();
+        assert_eq!(tree, new);
+    }
+
+    let mut tree = h("ul", Props::new(), vec![text("a")]);
+    let err = apply(&mut tree, &[Patch::UpdateChild(0, vec![Patch::MoveChild(0, 1)])]).unwrap_err();
+    assert_eq!(err, PatchError { path: vec![0], message: "cannot move child 0 to 1".into() });
+    assert!(apply(&mut tree, &[Patch::RemoveChild(1)]).is_err());
+}
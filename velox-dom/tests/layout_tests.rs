@@ -1,4 +1,4 @@
-use velox_dom::{h, text, Props, layout::{compute_layout, Rect}};
+use velox_dom::{fragment, h, text, Props, layout::{compute_layout, Rect}};
 
 #[test]
 fn block_stacks_children_and_uses_style_size() {
@@ -13,3 +13,21 @@ fn block_stacks_children_and_uses_style_size() {
     assert_eq!(lt.children[0].rect.y, 0);
     assert!(lt.children[1].rect.y >= lt.children[0].rect.h);
 }
+
+#[test]
+fn fragment_children_flow_as_the_parents_and_it_spans_them() {
+    let boxed = |h_px: i32| h("div", Props::new().set("style", format!("height: {}px;", h_px)), vec![]);
+    let root = h(
+        "div",
+        Props::new().set("style", "width: 300px;"),
+        vec![boxed(10), fragment(vec![boxed(20), boxed(30)]), boxed(40)],
+    );
+    let lt = compute_layout(&root, 800, 600);
+    assert_eq!(lt.children.len(), 3, "laid out in the shape of the tree");
+    let frag = &lt.children[1];
+    assert_eq!(frag.children.len(), 2);
+    assert_eq!(frag.children[0].rect.y, 10);
+    assert_eq!(frag.children[1].rect.y, 30);
+    assert_eq!((frag.rect.y, frag.rect.h), (10, 50));
+    assert_eq!(lt.children[2].rect.y, 60);
+}
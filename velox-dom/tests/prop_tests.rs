@@ -0,0 +1,63 @@
+use velox_dom::diff::{Patch, diff};
+use velox_dom::{PropValue, Props, h};
+
+#[test]
+fn props_keep_their_types() {
+    let props = Props::new()
+        .set("class", "slider")
+        .set("value", 0.5)
+        .set("disabled", false)
+        .set("on:input", "volume")
+        .set("on:input-payload", "master")
+        .set("steps", vec![PropValue::from(1), PropValue::from(2)]);
+    assert_eq!(
+        props.get("on:input"),
+        Some(&PropValue::Handler("volume".into()))
+    );
+    assert_eq!(
+        props.get("on:input-payload"),
+        Some(&PropValue::Str("master".into()))
+    );
+    assert_eq!(props.str("on:input"), Some("volume"));
+    assert_eq!(props.str("value"), None);
+    assert_eq!(props.num("value"), Some(0.5));
+    assert_eq!(props.flag("disabled"), Some(false));
+    assert_eq!(
+        props
+            .get("steps")
+            .and_then(PropValue::as_list)
+            .map(<[_]>::len),
+        Some(2)
+    );
+
+    // Strings that spell numbers and flags read as them; everything prints as written.
+    let written = Props::new().set("tabindex", "3").set("hidden", "true");
+    assert_eq!(
+        (written.num("tabindex"), written.flag("hidden")),
+        (Some(3.0), Some(true))
+    );
+    assert_eq!(props.attrs["value"].to_string(), "0.5");
+    assert_eq!(props.attrs["steps"].to_string(), "1 2");
+    assert_eq!(props.attrs["class"], "slider");
+}
+
+#[test]
+fn diff_compares_typed_values() {
+    let old = h(
+        "input",
+        Props::new().set("value", 1).set("checked", true),
+        vec![],
+    );
+    let new = h(
+        "input",
+        Props::new().set("value", 1.0).set("checked", "true"),
+        vec![],
+    );
+    assert_eq!(
+        diff(&old, &new),
+        vec![Patch::SetAttr(
+            "checked".into(),
+            PropValue::Str("true".into())
+        )]
+    );
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "serde")]
+
+use velox_dom::diff::{Patch, diff};
+use velox_dom::wire::{SCHEMA_VERSION, SchemaError, Versioned};
+use velox_dom::{PropValue, Props, VNode, component, fragment, h, text};
+
+#[test]
+fn trees_and_patches_round_trip_through_json() {
+    let old = h(
+        "ul",
+        Props::new().set("class", "list"),
+        vec![h("li", (), vec![text("a")])],
+    );
+    let new = h(
+        "ul",
+        Props::new().set("class", "list").set("aria-label", "Items"),
+        vec![h("li", (), vec![text("a")]), h("li", (), vec![text("b")])],
+    );
+    let json = serde_json::to_string(&new).unwrap();
+    assert_eq!(
+        json,
+        r#"{"tag":"ul","attrs":{"aria-label":"Items","class":"list"},"children":[{"tag":"li","attrs":{},"children":["a"]},{"tag":"li","attrs":{},"children":["b"]}]}"#
+    );
+    assert_eq!(serde_json::from_str::<VNode>(&json).unwrap(), new);
+    assert_eq!(
+        serde_json::from_str::<VNode>(r#"{"tag":"br"}"#).unwrap(),
+        h("br", (), vec![])
+    );
+    let both = fragment(vec![text("a"), h("br", (), vec![])]);
+    assert_eq!(
+        serde_json::to_string(&both).unwrap(),
+        r#"["a",{"tag":"br","attrs":{},"children":[]}]"#
+    );
+    assert_eq!(
+        serde_json::from_str::<VNode>(r#"["a",{"tag":"br"}]"#).unwrap(),
+        both
+    );
+    let item = component("TodoItem", Props::new().set("item", "Milk"), vec![]);
+    assert_eq!(
+        serde_json::to_string(&item).unwrap(),
+        r#"{"component":"TodoItem","attrs":{"item":"Milk"},"children":[]}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<VNode>(r#"{"component":"TodoItem","attrs":{"item":"Milk"}}"#)
+            .unwrap(),
+        item
+    );
+
+    let patches = diff(&old, &new);
+    let json = serde_json::to_string(&patches).unwrap();
+    assert!(
+        json.contains(r#"{"set_attr":["aria-label","Items"]}"#),
+        "{}",
+        json
+    );
+    assert_eq!(serde_json::from_str::<Vec<Patch>>(&json).unwrap(), patches);
+}
+
+#[test]
+fn prop_values_serialize_as_json_values() {
+    let props = Props::new()
+        .set("on:click", "save")
+        .set("step", 0.5)
+        .set("open", true)
+        .set("tags", vec![PropValue::from("a")]);
+    let json = serde_json::to_string(&props).unwrap();
+    assert_eq!(
+        json,
+        r#"{"on:click":"save","open":true,"step":0.5,"tags":["a"]}"#
+    );
+    // A handler is a handler again when read back.
+    assert_eq!(serde_json::from_str::<Props>(&json).unwrap(), props);
+}
+
+#[test]
+fn envelopes_carry_the_schema_version() {
+    let json = serde_json::to_string(&Versioned::new(text("hi"))).unwrap();
+    assert_eq!(
+        json,
+        format!(r#"{{"schema":{},"data":"hi"}}"#, SCHEMA_VERSION)
+    );
+
+    // Fields a newer writer added are ignored; a newer schema is refused.
+    let read = |json: &str| {
+        serde_json::from_str::<Versioned<VNode>>(json)
+            .unwrap()
+            .into_data()
+    };
+    assert_eq!(
+        read(r#"{"schema":1,"data":{"tag":"p","key":"x"},"written_by":"0.2"}"#),
+        Ok(h("p", (), vec![]))
+    );
+    assert_eq!(
+        read(r#"{"schema":2,"data":"hi"}"#),
+        Err(SchemaError { found: 2 })
+    );
+}
@@ -0,0 +1,71 @@
+/*
+ * C API of libvelox (the velox-ffi crate): velox views embedded in C, C++, Python, ...
+ *
+ * Functions return 0 (or a non-null pointer) on success and -1 (or NULL) on failure;
+ * velox_last_error() then describes the failure. Strings are NUL-terminated UTF-8 and
+ * copied before the call returns. An app belongs to the thread that created it. A panic
+ * inside the library is reported as a failure; the callback must not unwind (throw,
+ * longjmp) out of its call.
+ */
+#ifndef VELOX_H
+#define VELOX_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct VeloxApp VeloxApp;
+
+/* Called for every handler the view dispatches; payload is NULL when the element sets
+ * none. Runs after the view has handled the event, so it may call back into the app. */
+typedef void (*VeloxEventCallback)(void *user_data, const char *handler, const char *payload);
+
+#define VELOX_EVENT_POINTER_MOVE 1u
+#define VELOX_EVENT_POINTER_DOWN 2u
+#define VELOX_EVENT_POINTER_LEAVE 3u
+#define VELOX_EVENT_RESIZE 4u
+
+/* Input pushed by the host, in physical pixels. Fields a kind doesn't use are ignored. */
+typedef struct VeloxEvent {
+    uint32_t kind;
+    float x;
+    float y;
+    uint32_t width;
+    uint32_t height;
+    float scale_factor;
+} VeloxEvent;
+
+/* Message of the last failed call on this thread, or NULL. */
+const char *velox_last_error(void);
+
+/* An app showing `template` (the inside of a <template> block) styled by `style` (CSS).
+ * NULL if the template doesn't parse. */
+VeloxApp *velox_app_create(const char *template_src, const char *style);
+void velox_app_destroy(VeloxApp *app);
+
+/* Set a state value read by {{ key }}, :attr="key", v-if and v-show. */
+int velox_app_set_state(VeloxApp *app, const char *key, const char *value);
+/* Replace the event callback; NULL removes it. */
+int velox_app_set_event_callback(VeloxApp *app, VeloxEventCallback callback, void *user_data);
+
+/* Push input; returns 1 if the view took it and the host should ignore it, else 0. */
+int velox_app_push_event(VeloxApp *app, const VeloxEvent *event);
+/* Advance one frame; returns 1 if the view changed and should be drawn again, else 0. */
+int velox_app_update(VeloxApp *app, double dt_ms);
+/* Draw the view into `out` as straight-alpha RGBA rows of `stride` bytes, at the size
+ * of the last VELOX_EVENT_RESIZE; `width` and `height` (if not NULL) receive it.
+ * Available when libvelox is built with the `raster` feature (the default). */
+int velox_app_render_rgba(VeloxApp *app, uint8_t *out, size_t len, size_t stride, uint32_t *width, uint32_t *height);
+
+/* Open a window showing the app. Does not return once the window is open; -1 if none
+ * could be opened. */
+int velox_app_run(VeloxApp *app, const char *title);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* VELOX_H */
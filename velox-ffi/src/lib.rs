@@ -0,0 +1,428 @@
+//! C API for embedding velox views in hosts written in other languages.
+//!
+//! The library builds as `libvelox` (`cdylib`); `include/velox.h` declares what it
+//! exports. A host creates an app from a template and a stylesheet (see `template`),
+//! sets its state as string key/value pairs, and receives the handlers its elements
+//! dispatch through a callback. `velox_app_run` opens a window and never returns; a host
+//! that owns its event loop instead sends a `VELOX_EVENT_RESIZE` event and pointer
+//! events, and each frame calls `velox_app_update` and draws `velox_app_render_rgba`.
+//!
+//! Every function returns 0 (or a non-null pointer) on success and -1 (or null) on
+//! failure, described by `velox_last_error`. Strings are NUL-terminated UTF-8, copied
+//! before the call returns. An app belongs to the thread that created it. Panics are
+//! caught at the boundary; the callback must not unwind out of its call.
+
+pub mod template;
+
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString, c_char, c_int, c_void};
+use std::panic::AssertUnwindSafe;
+use std::rc::Rc;
+use std::time::Duration;
+
+use velox_renderer::VeloxUi;
+
+use crate::template::View;
+
+/// `fn(user_data, handler, payload)`; `payload` is null when the element sets none.
+pub type VeloxEventCallback = Option<
+    unsafe extern "C" fn(user_data: *mut c_void, handler: *const c_char, payload: *const c_char),
+>;
+
+pub const VELOX_EVENT_POINTER_MOVE: u32 = 1;
+pub const VELOX_EVENT_POINTER_DOWN: u32 = 2;
+pub const VELOX_EVENT_POINTER_LEAVE: u32 = 3;
+pub const VELOX_EVENT_RESIZE: u32 = 4;
+
+/// Input pushed by the host. Positions and sizes are in physical pixels; the fields a
+/// `kind` doesn't use are ignored.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct VeloxEvent {
+    pub kind: u32,
+    pub x: f32,
+    pub y: f32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f32,
+}
+
+#[derive(Clone, Copy)]
+struct Callback {
+    f: VeloxEventCallback,
+    user_data: *mut c_void,
+}
+
+impl Callback {
+    fn call(self, handler: &str, payload: Option<&str>) {
+        let Some(f) = self.f else { return };
+        // Names with an interior NUL can't cross the C boundary; they can't come from a
+        // parsed template either.
+        let Ok(handler) = CString::new(handler) else {
+            return;
+        };
+        let payload = payload.and_then(|p| CString::new(p).ok());
+        // SAFETY: the host registered `f` for `user_data` and keeps both valid while the
+        // app lives.
+        unsafe {
+            f(
+                self.user_data,
+                handler.as_ptr(),
+                payload.as_ref().map_or(std::ptr::null(), |p| p.as_ptr()),
+            )
+        }
+    }
+}
+
+type State = Rc<RefCell<BTreeMap<String, String>>>;
+type Dispatched = Rc<RefCell<Vec<(String, Option<String>)>>>;
+
+/// An app created by `velox_app_create`.
+pub struct VeloxApp {
+    view: Rc<View>,
+    state: State,
+    callback: Rc<Cell<Callback>>,
+    /// Handlers the view dispatched during the current call, passed to the callback once
+    /// the app is no longer borrowed, so the callback may call back into it.
+    dispatched: Dispatched,
+    ui: VeloxUi,
+}
+
+impl VeloxApp {
+    fn new(view: View) -> Self {
+        let view = Rc::new(view);
+        let state = State::default();
+        let callback = Rc::new(Cell::new(Callback {
+            f: None,
+            user_data: std::ptr::null_mut(),
+        }));
+        let dispatched = Dispatched::default();
+        let on_event = {
+            let dispatched = dispatched.clone();
+            move |handler: &str, payload: Option<&str>| {
+                dispatched
+                    .borrow_mut()
+                    .push((handler.to_string(), payload.map(str::to_string)))
+            }
+        };
+        let ui = VeloxUi::new(make_view(&view, &state), on_event);
+        VeloxApp {
+            view,
+            state,
+            callback,
+            dispatched,
+            ui,
+        }
+    }
+}
+
+fn make_view(
+    view: &Rc<View>,
+    state: &State,
+) -> impl FnMut(u32, u32) -> (velox_dom::VNode, velox_style::Stylesheet) + 'static {
+    let (view, state) = (view.clone(), state.clone());
+    move |_w, _h| (view.render(&state.borrow()), view.sheet.clone())
+}
+
+/// Run `f` on the app, then hand what its view dispatched to the host's callback.
+///
+/// # Safety
+/// `app` is null or a live app.
+unsafe fn with_app(app: *mut VeloxApp, f: impl FnOnce(&mut VeloxApp) -> c_int) -> c_int {
+    // SAFETY: per the caller's contract.
+    let Some(app) = (unsafe { app.as_mut() }) else {
+        return set_error("app is null");
+    };
+    let result = catch_panic(-1, || f(app));
+    let (callback, dispatched) = (app.callback.get(), app.dispatched.take());
+    // `app` is not used past this point: the callback may call back into it.
+    for (handler, payload) in dispatched {
+        callback.call(&handler, payload.as_deref());
+    }
+    result
+}
+
+/// Run `f`, turning a panic into `failed` with the panic message as the last error: a
+/// panic must not unwind into the host.
+fn catch_panic<T>(failed: T, f: impl FnOnce() -> T) -> T {
+    std::panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("unknown panic");
+        set_error(format!("panic: {}", message));
+        failed
+    })
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(message: impl Into<String>) -> c_int {
+    let message = message.into().replace('\0', " ");
+    LAST_ERROR.with(|e| *e.borrow_mut() = CString::new(message).ok());
+    -1
+}
+
+/// # Safety
+/// `s` is null or a NUL-terminated string valid for the call.
+unsafe fn read_str<'a>(s: *const c_char, what: &str) -> Result<&'a str, c_int> {
+    if s.is_null() {
+        return Err(set_error(format!("{} is null", what)));
+    }
+    // SAFETY: non-null, and NUL-terminated per the caller's contract.
+    unsafe { CStr::from_ptr(s) }
+        .to_str()
+        .map_err(|_| set_error(format!("{} is not UTF-8", what)))
+}
+
+/// The message of the last failed call on this thread, or null. Valid until the next
+/// failing call.
+#[unsafe(no_mangle)]
+pub extern "C" fn velox_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(std::ptr::null(), |s| s.as_ptr()))
+}
+
+/// Create an app from a template and CSS; null if the template doesn't parse.
+///
+/// # Safety
+/// Both arguments are NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn velox_app_create(
+    template: *const c_char,
+    style: *const c_char,
+) -> *mut VeloxApp {
+    // SAFETY: per this function's contract.
+    let (template, style) =
+        match unsafe { (read_str(template, "template"), read_str(style, "style")) } {
+            (Ok(t), Ok(s)) => (t, s),
+            _ => return std::ptr::null_mut(),
+        };
+    catch_panic(std::ptr::null_mut(), || {
+        match View::parse(template, style) {
+            Ok(view) => Box::into_raw(Box::new(VeloxApp::new(view))),
+            Err(e) => {
+                set_error(format!("template: {}", e));
+                std::ptr::null_mut()
+            }
+        }
+    })
+}
+
+/// Free an app. Null is ignored.
+///
+/// # Safety
+/// `app` comes from `velox_app_create` and is not used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn velox_app_destroy(app: *mut VeloxApp) {
+    if !app.is_null() {
+        // SAFETY: per this function's contract.
+        drop(unsafe { Box::from_raw(app) });
+    }
+}
+
+/// Set `key` of the app's state to `value`; the view shows it on the next update.
+///
+/// # Safety
+/// `app` is a live app; `key` and `value` are NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn velox_app_set_state(
+    app: *mut VeloxApp,
+    key: *const c_char,
+    value: *const c_char,
+) -> c_int {
+    // SAFETY: per this function's contract.
+    let (key, value) = match unsafe { (read_str(key, "key"), read_str(value, "value")) } {
+        (Ok(k), Ok(v)) => (k, v),
+        _ => return -1,
+    };
+    // SAFETY: per this function's contract.
+    unsafe {
+        with_app(app, |app| {
+            app.state
+                .borrow_mut()
+                .insert(key.to_string(), value.to_string());
+            app.ui.invalidate();
+            0
+        })
+    }
+}
+
+/// Call `callback` with `user_data` for every handler the view dispatches (clicks,
+/// click-outside). Replaces the previous callback; null removes it. The callback runs
+/// after the view has handled the event, so it may call back into the app.
+///
+/// # Safety
+/// `app` is a live app; `callback` may be called with `user_data` until it is replaced
+/// or the app destroyed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn velox_app_set_event_callback(
+    app: *mut VeloxApp,
+    callback: VeloxEventCallback,
+    user_data: *mut c_void,
+) -> c_int {
+    // SAFETY: per this function's contract.
+    unsafe {
+        with_app(app, |app| {
+            app.callback.set(Callback {
+                f: callback,
+                user_data,
+            });
+            0
+        })
+    }
+}
+
+/// Push input from the host. Returns 1 if the view took it (a press on one of its
+/// handlers, or the pointer over one), so the host should not act on it too; 0 if not.
+///
+/// # Safety
+/// `app` is a live app; `event` points to a `VeloxEvent`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn velox_app_push_event(
+    app: *mut VeloxApp,
+    event: *const VeloxEvent,
+) -> c_int {
+    // SAFETY: per this function's contract.
+    let Some(event) = (unsafe { event.as_ref() }).copied() else {
+        return set_error("event is null");
+    };
+    // SAFETY: per this function's contract.
+    unsafe {
+        with_app(app, |app| {
+            let taken = match event.kind {
+                VELOX_EVENT_POINTER_MOVE => app.ui.pointer_moved(event.x, event.y),
+                VELOX_EVENT_POINTER_DOWN => {
+                    app.ui.pointer_moved(event.x, event.y);
+                    app.ui.pointer_pressed()
+                }
+                VELOX_EVENT_POINTER_LEAVE => {
+                    app.ui.pointer_left();
+                    false
+                }
+                VELOX_EVENT_RESIZE => {
+                    let scale = if event.scale_factor > 0.0 {
+                        event.scale_factor
+                    } else {
+                        1.0
+                    };
+                    app.ui.resize(event.width, event.height, scale);
+                    false
+                }
+                other => return set_error(format!("unknown event kind {}", other)),
+            };
+            taken as c_int
+        })
+    }
+}
+
+/// Advance one host frame, `dt_ms` milliseconds after the last (see `VeloxUi::update`).
+/// Returns 1 if the view changed and should be drawn again, else 0.
+///
+/// # Safety
+/// `app` is a live app.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn velox_app_update(app: *mut VeloxApp, dt_ms: f64) -> c_int {
+    let dt = Duration::from_secs_f64(dt_ms.max(0.0) / 1000.0);
+    // SAFETY: per this function's contract.
+    unsafe { with_app(app, |app| app.ui.update(dt) as c_int) }
+}
+
+/// Draw the view as of the last update into `out`: straight-alpha RGBA rows of `stride`
+/// bytes, at the size of the last `VELOX_EVENT_RESIZE` (stored in `width` and `height`
+/// when they are not null). Fails if `len` bytes can't hold it.
+///
+/// # Safety
+/// `app` is a live app; `out` is writable for `len` bytes; `width` and `height` are
+/// null or writable.
+#[cfg(feature = "raster")]
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn velox_app_render_rgba(
+    app: *mut VeloxApp,
+    out: *mut u8,
+    len: usize,
+    stride: usize,
+    width: *mut u32,
+    height: *mut u32,
+) -> c_int {
+    let render = |app: &mut VeloxApp| {
+        let pixmap = match velox_renderer::raster::render_display_list(
+            app.ui.display_list(),
+            app.ui.scale(),
+        ) {
+            Ok(p) => p,
+            Err(e) => return set_error(e.to_string()),
+        };
+        let (w, h) = (pixmap.width(), pixmap.height());
+        let Some(image) = velox_renderer::RgbaImage::from_premultiplied(w, h, pixmap.take()) else {
+            return set_error("render: bad pixel buffer");
+        };
+        let row = w as usize * 4;
+        // Every row but the last takes `stride` bytes; the sum may not fit in a `usize`.
+        let needed = match (h as usize).checked_sub(1) {
+            Some(rows) => stride.checked_mul(rows).and_then(|n| n.checked_add(row)),
+            None => Some(0),
+        };
+        let Some(needed) = needed else {
+            return set_error(format!("stride {} overflows for {} rows", stride, h));
+        };
+        if out.is_null() || stride < row || len < needed {
+            return set_error(format!("buffer too small for {}x{} pixels", w, h));
+        }
+        // SAFETY: `out` holds `len` bytes, which covers every row written below.
+        let out = unsafe { std::slice::from_raw_parts_mut(out, len) };
+        for (y, src) in image.data.chunks_exact(row).enumerate() {
+            out[y * stride..y * stride + row].copy_from_slice(src);
+        }
+        // SAFETY: null or writable, per this function's contract.
+        unsafe {
+            if let Some(width) = width.as_mut() {
+                *width = w;
+            }
+            if let Some(height) = height.as_mut() {
+                *height = h;
+            }
+        }
+        0
+    };
+    // SAFETY: per this function's contract.
+    unsafe { with_app(app, render) }
+}
+
+/// Open a window titled `title` showing the app, on the backend `VELOX_BACKEND` selects.
+/// Once the window is open this does not return: the process exits with it. Returns -1
+/// if no window could be opened; the app stays usable.
+///
+/// # Safety
+/// `app` is a live app; `title` is a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn velox_app_run(app: *mut VeloxApp, title: *const c_char) -> c_int {
+    // SAFETY: per this function's contract.
+    let title = match unsafe { read_str(title, "title") } {
+        Ok(t) => t.to_string(),
+        Err(e) => return e,
+    };
+    // SAFETY: per this function's contract.
+    let Some(app) = (unsafe { app.as_ref() }) else {
+        return set_error("app is null");
+    };
+    // The window runner owns the handlers; `app` is not used past this point, so the
+    // callback may call back into it.
+    let (make_view, callback) = (make_view(&app.view, &app.state), app.callback.clone());
+    let on_event =
+        move |handler: &str, payload: Option<&str>| callback.get().call(handler, payload);
+    let get_title = {
+        let title = title.clone();
+        move || title.clone()
+    };
+    let config = velox_renderer::RendererConfig::from_env();
+    catch_panic(-1, || {
+        match velox_renderer::run_app(&title, &config, make_view, on_event, get_title) {
+            Ok(()) => 0,
+            Err(e) => set_error(e.to_string()),
+        }
+    })
+}
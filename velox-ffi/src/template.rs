@@ -0,0 +1,96 @@
+//! Views for hosts without Rust: a template rendered against string state at runtime.
+//!
+//! A compiled component resolves its template in generated Rust. A host calling through
+//! the C API has no generated code, so `View` walks the parsed template on every frame
+//! instead. It understands the part of the template language that needs no Rust:
+//!
+//! - static attributes, and `{{ key }}` and `:attr="key"` read from the state;
+//! - `@event="handler"` and `v-click-outside="handler"`, dispatched to the host;
+//! - `v-if="key"` and `v-show="key"`, true unless the value is missing, empty, `false`
+//!   or `0`.
+//!
+//! Other directives are ignored. A template with several roots is wrapped in a `<div>`.
+
+use std::collections::BTreeMap;
+
+use velox_dom::{Props, VNode, h, text};
+use velox_sfc::{AttrKind, Node};
+use velox_style::Stylesheet;
+
+/// A parsed template and its stylesheet.
+#[derive(Clone, Debug)]
+pub struct View {
+    nodes: Vec<Node>,
+    pub sheet: Stylesheet,
+}
+
+impl View {
+    /// Parse `template` (the inside of a `<template>` block) and `style` (CSS).
+    pub fn parse(template: &str, style: &str) -> Result<Self, String> {
+        let nodes = velox_sfc::parse_template_to_ast(template)?;
+        Ok(View {
+            nodes,
+            sheet: Stylesheet::parse(style),
+        })
+    }
+
+    /// The tree for `state`, unstyled.
+    pub fn render(&self, state: &BTreeMap<String, String>) -> VNode {
+        let mut roots = Vec::new();
+        render_nodes(&self.nodes, state, &mut roots);
+        match roots.len() {
+            1 if matches!(roots[0], VNode::Element { .. }) => roots.remove(0),
+            _ => h("div", Props::new(), roots),
+        }
+    }
+}
+
+fn truthy(state: &BTreeMap<String, String>, key: &str) -> bool {
+    state
+        .get(key.trim())
+        .is_some_and(|v| !matches!(v.trim(), "" | "false" | "0"))
+}
+
+fn render_nodes(nodes: &[Node], state: &BTreeMap<String, String>, out: &mut Vec<VNode>) {
+    let lookup = |key: &str| state.get(key.trim()).cloned().unwrap_or_default();
+    for node in nodes {
+        match node {
+            Node::Text(t) if t.trim().is_empty() => {}
+            Node::Text(t) => out.push(text(t.trim())),
+            Node::Interpolation(expr) => out.push(text(lookup(expr))),
+            Node::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                let mut props = Props::new();
+                let mut shown = true;
+                for a in attrs {
+                    let value = a.value.as_deref().unwrap_or("");
+                    match (&a.kind, a.name.as_str()) {
+                        (AttrKind::Static, name) => props = props.set(name, value),
+                        (AttrKind::Bind, name) => {
+                            props =
+                                props.set(name, lookup(if value.is_empty() { name } else { value }))
+                        }
+                        (AttrKind::On, name) => {
+                            props = props.set(format!("on:{}", name), value.trim())
+                        }
+                        (AttrKind::Directive, "click-outside") => {
+                            props = props.set("on:click-outside", value.trim())
+                        }
+                        (AttrKind::Directive, "if" | "show") => shown &= truthy(state, value),
+                        (AttrKind::Directive, _) => {}
+                    }
+                }
+                if !shown {
+                    continue;
+                }
+                let mut kids = Vec::new();
+                render_nodes(children, state, &mut kids);
+                out.push(h(tag.as_str(), props, kids));
+            }
+        }
+    }
+}
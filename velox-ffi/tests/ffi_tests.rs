@@ -0,0 +1,147 @@
+use std::ffi::{CStr, CString, c_char, c_void};
+use std::ptr;
+
+use velox::*;
+
+unsafe extern "C" fn record(
+    user_data: *mut c_void,
+    handler: *const c_char,
+    payload: *const c_char,
+) {
+    let events = unsafe { &mut *(user_data as *mut Vec<(String, bool)>) };
+    let handler = unsafe { CStr::from_ptr(handler) }
+        .to_str()
+        .unwrap()
+        .to_string();
+    events.push((handler, !payload.is_null()));
+}
+
+fn event(kind: u32, x: f32, y: f32) -> VeloxEvent {
+    VeloxEvent {
+        kind,
+        x,
+        y,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn host_drives_an_app_through_the_c_api() {
+    let template = CString::new(r#"<div><button class="inc" @click="inc">Count {{ count }}</button><p v-if="done">Done</p></div>"#).unwrap();
+    let style =
+        CString::new("button { width: 100px; height: 40px; background: #336699; }").unwrap();
+    let mut events: Vec<(String, bool)> = Vec::new();
+    unsafe {
+        let app = velox_app_create(template.as_ptr(), style.as_ptr());
+        assert!(!app.is_null());
+        assert_eq!(
+            velox_app_set_event_callback(app, Some(record), &mut events as *mut _ as *mut c_void),
+            0
+        );
+        let (count, one) = (CString::new("count").unwrap(), CString::new("1").unwrap());
+        assert_eq!(velox_app_set_state(app, count.as_ptr(), one.as_ptr()), 0);
+        let resize = VeloxEvent {
+            kind: VELOX_EVENT_RESIZE,
+            width: 200,
+            height: 100,
+            scale_factor: 1.0,
+            ..Default::default()
+        };
+        assert_eq!(velox_app_push_event(app, &resize), 0);
+        assert_eq!(velox_app_update(app, 16.0), 1);
+
+        assert_eq!(
+            velox_app_push_event(app, &event(VELOX_EVENT_POINTER_DOWN, 190.0, 90.0)),
+            0
+        );
+        assert_eq!(
+            velox_app_push_event(app, &event(VELOX_EVENT_POINTER_DOWN, 50.0, 20.0)),
+            1
+        );
+        assert_eq!(
+            events,
+            [("inc".to_string(), true)],
+            "clicks without a payload of their own get the position"
+        );
+
+        let (mut width, mut height) = (0u32, 0u32);
+        let mut pixels = vec![0u8; 200 * 100 * 4];
+        assert_eq!(
+            velox_app_render_rgba(
+                app,
+                pixels.as_mut_ptr(),
+                pixels.len(),
+                200 * 4,
+                &mut width,
+                &mut height
+            ),
+            0
+        );
+        assert_eq!((width, height), (200, 100));
+        assert_eq!(
+            &pixels[(20 * 200 + 50) * 4..][..4],
+            &[0x33, 0x66, 0x99, 0xff],
+            "the button's background"
+        );
+        velox_app_destroy(app);
+    }
+}
+
+#[test]
+fn failures_are_reported_through_last_error() {
+    let style = CString::new("").unwrap();
+    unsafe {
+        assert!(velox_app_create(ptr::null(), style.as_ptr()).is_null());
+        assert_eq!(
+            CStr::from_ptr(velox_last_error()).to_str().unwrap(),
+            "template is null"
+        );
+
+        assert_eq!(velox_app_update(ptr::null_mut(), 16.0), -1);
+        assert_eq!(
+            CStr::from_ptr(velox_last_error()).to_str().unwrap(),
+            "app is null"
+        );
+
+        let ok = CString::new("<p>hi</p>").unwrap();
+        let app = velox_app_create(ok.as_ptr(), style.as_ptr());
+        velox_app_update(app, 16.0);
+        let mut small = vec![0u8; 16];
+        assert_eq!(
+            velox_app_render_rgba(
+                app,
+                small.as_mut_ptr(),
+                small.len(),
+                16,
+                ptr::null_mut(),
+                ptr::null_mut()
+            ),
+            -1
+        );
+        let resize = VeloxEvent {
+            kind: VELOX_EVENT_RESIZE,
+            width: 4,
+            height: 4,
+            scale_factor: 1.0,
+            ..Default::default()
+        };
+        velox_app_push_event(app, &resize);
+        velox_app_update(app, 16.0);
+        assert_eq!(
+            velox_app_render_rgba(
+                app,
+                small.as_mut_ptr(),
+                small.len(),
+                usize::MAX,
+                ptr::null_mut(),
+                ptr::null_mut()
+            ),
+            -1
+        );
+        assert_eq!(
+            CStr::from_ptr(velox_last_error()).to_str().unwrap(),
+            format!("stride {} overflows for 4 rows", usize::MAX)
+        );
+        velox_app_destroy(app);
+    }
+}
@@ -0,0 +1,89 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+use velox_dom::diff::diff;
+use velox_dom::layout::compute_layout;
+use velox_dom::{VNode, h, text};
+use velox_renderer::display_list::{ApproxMeasure, build_display_list};
+use velox_style::{Stylesheet, apply_styles};
+
+fn todo_list(count: usize, done_every: usize) -> VNode {
+    let items = (0..count)
+        .map(|i| {
+            let key = i.to_string();
+            let class = if i % done_every == 0 {
+                "todo done"
+            } else {
+                "todo"
+            };
+            h(
+                "div",
+                vec![("key", key.as_str()), ("class", class)],
+                vec![
+                    h(
+                        "span",
+                        vec![("class", "label")],
+                        vec![text(format!("task {}", i))],
+                    ),
+                    h("button", vec![("on:click", "remove")], vec![text("x")]),
+                ],
+            )
+        })
+        .collect();
+    h("div", vec![("class", "app")], items)
+}
+
+fn sheet() -> Stylesheet {
+    Stylesheet::parse(
+        ".app { width: 640px; padding: 8px; background-color: #FAFAFA; } \
+         .todo { padding: 4px; margin-bottom: 2px; border: 1px solid #DDDDDD; } \
+         .done { color: #999999; text-decoration: underline; } \
+         .label { font-size: 14px; } \
+         button { background-color: #EE4444; color: #FFFFFF; }",
+    )
+}
+
+/// One frame after a state change: diff, reconcile keyed children, cascade, lay out, and
+/// build the display list. Painting is excluded so the number is backend-independent.
+fn bench_frame_cost(c: &mut Criterion) {
+    let mut group = c.benchmark_group("frame_cost");
+    group.sample_size(20);
+    let sheet = sheet();
+    for &count in &[100usize, 1_000] {
+        let prev = todo_list(count, 3);
+        let next = todo_list(count, 2);
+        group.bench_with_input(BenchmarkId::from_parameter(count), &next, |b, next| {
+            b.iter(|| {
+                let patches = diff(&prev, next);
+                let (
+                    VNode::Element {
+                        children: old_children,
+                        ..
+                    },
+                    VNode::Element {
+                        tag,
+                        props,
+                        children,
+                    },
+                ) = (&prev, next)
+                else {
+                    unreachable!()
+                };
+                let mut reconciled = old_children.clone();
+                velox_renderer::reconcile_keyed_children(&mut reconciled, children);
+                let tree = h(tag.clone(), props.clone(), reconciled);
+                let styled = apply_styles(&tree, &sheet);
+                let layout = compute_layout(&styled, 640, 480);
+                let list = build_display_list(&styled, &layout, &mut ApproxMeasure);
+                (patches.len(), list.commands.len())
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().without_plots();
+    targets = bench_frame_cost
+}
+criterion_main!(benches);
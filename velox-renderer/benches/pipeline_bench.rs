@@ -0,0 +1,64 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+use velox_dom::{VNode, h, text};
+use velox_style::Stylesheet;
+
+/// `rows` cards of `cols` cells each: (1 + cols * 2) nodes per row.
+fn build_grid(rows: usize, cols: usize) -> VNode {
+    let rows = (0..rows)
+        .map(|r| {
+            let cells = (0..cols)
+                .map(|c| {
+                    h(
+                        "span",
+                        vec![("class", if c % 2 == 0 { "cell" } else { "cell alt" })],
+                        vec![text(format!("{}:{}", r, c))],
+                    )
+                })
+                .collect();
+            h("div", vec![("class", "row")], cells)
+        })
+        .collect();
+    h("div", vec![("style", "width:1024px")], rows)
+}
+
+fn sheet() -> Stylesheet {
+    Stylesheet::parse(
+        ".row { padding: 2px; margin-bottom: 1px; background-color: #EEEEEE; } \
+         .cell { color: #333333; font-size: 12px; } \
+         .alt { color: #666666; } \
+         span { line-height: 14px; }",
+    )
+}
+
+fn bench_style_layout(c: &mut Criterion) {
+    let mut group = c.benchmark_group("style_layout_10k");
+    group.sample_size(10);
+    let sheet = sheet();
+    // 500 rows x 10 cells -> ~10.5k nodes.
+    let tree = build_grid(500, 10);
+    group.bench_with_input(BenchmarkId::new("sequential", "10k"), &tree, |b, t| {
+        b.iter(|| {
+            let styled = velox_style::apply_styles(t, &sheet);
+            velox_dom::layout::compute_layout(&styled, 1024, 768)
+        });
+    });
+    #[cfg(feature = "parallel")]
+    group.bench_with_input(BenchmarkId::new("parallel", "10k"), &tree, |b, t| {
+        b.iter(|| {
+            let styled = velox_style::apply_styles_parallel(t, &sheet);
+            velox_dom::layout::compute_layout_parallel(&styled, 1024, 768)
+        });
+    });
+    group.bench_with_input(BenchmarkId::new("prepare_frame", "10k"), &tree, |b, t| {
+        b.iter(|| velox_renderer::pipeline::prepare_frame(t, &sheet, &|_, _| false, 1024, 768));
+    });
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().without_plots();
+    targets = bench_style_layout
+}
+criterion_main!(benches);
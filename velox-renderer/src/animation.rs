@@ -0,0 +1,380 @@
+//! Transitions.
+//!
+//! Elements whose style declares `transition` for `left`, `top`, `width`, `height`, or
+//! `all` animate between layouts (FLIP): each frame, `LayoutAnimator::animate` compares the
+//! fresh layout with the previous one and rewrites the rects of transitioning elements so
+//! they move from where they were drawn to where layout now puts them. Descendants move
+//! with an animating ancestor. Elements are matched across frames by their `key`
+//! attribute, falling back to their position among their siblings, so reordered keyed
+//! lists animate too.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use velox_dom::VNode;
+use velox_dom::layout::LayoutNode;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    Ease,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// `cubic-bezier(x1, y1, x2, y2)`.
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing {
+    /// Parse a CSS timing-function keyword or `cubic-bezier(...)`.
+    pub fn parse(value: &str) -> Option<Easing> {
+        let value = value.trim().to_ascii_lowercase();
+        match value.as_str() {
+            "linear" => Some(Easing::Linear),
+            "ease" => Some(Easing::Ease),
+            "ease-in" => Some(Easing::EaseIn),
+            "ease-out" => Some(Easing::EaseOut),
+            "ease-in-out" => Some(Easing::EaseInOut),
+            _ => {
+                let args = value.strip_prefix("cubic-bezier(")?.strip_suffix(')')?;
+                let n: Vec<f32> = args
+                    .split(',')
+                    .map(|a| a.trim().parse().ok())
+                    .collect::<Option<_>>()?;
+                match n[..] {
+                    [x1, y1, x2, y2] if (0.0..=1.0).contains(&x1) && (0.0..=1.0).contains(&x2) => {
+                        Some(Easing::CubicBezier(x1, y1, x2, y2))
+                    }
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// Eased progress for linear progress `t` in `0..=1`.
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        let (x1, y1, x2, y2) = match self {
+            Easing::Linear => return t,
+            Easing::Ease => (0.25, 0.1, 0.25, 1.0),
+            Easing::EaseIn => (0.42, 0.0, 1.0, 1.0),
+            Easing::EaseOut => (0.0, 0.0, 0.58, 1.0),
+            Easing::EaseInOut => (0.42, 0.0, 0.58, 1.0),
+            Easing::CubicBezier(x1, y1, x2, y2) => (x1, y1, x2, y2),
+        };
+        let bezier = |a: f32, b: f32, s: f32| {
+            3.0 * a * s * (1.0 - s).powi(2) + 3.0 * b * s * s * (1.0 - s) + s.powi(3)
+        };
+        // x(s) is monotonic for x1, x2 in 0..=1, so bisection finds the s with x(s) = t.
+        let (mut lo, mut hi) = (0.0f32, 1.0f32);
+        for _ in 0..24 {
+            let mid = (lo + hi) / 2.0;
+            if bezier(x1, x2, mid) < t {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        bezier(y1, y2, (lo + hi) / 2.0)
+    }
+}
+
+/// Duration, delay, and easing of one transitioning property.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Timing {
+    pub duration: Duration,
+    pub delay: Duration,
+    pub easing: Easing,
+}
+
+impl Timing {
+    /// Eased progress `elapsed` after the transition started, or `None` once it is done.
+    pub fn progress(&self, elapsed: Duration) -> Option<f32> {
+        let Some(running) = elapsed.checked_sub(self.delay) else {
+            return Some(0.0);
+        };
+        if running >= self.duration {
+            return None;
+        }
+        Some(
+            self.easing
+                .apply(running.as_secs_f32() / self.duration.as_secs_f32()),
+        )
+    }
+}
+
+/// The layout properties a `transition` declaration animates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LayoutTransitions {
+    pub left: Option<Timing>,
+    pub top: Option<Timing>,
+    pub width: Option<Timing>,
+    pub height: Option<Timing>,
+}
+
+impl LayoutTransitions {
+    pub fn is_empty(&self) -> bool {
+        self.channels().iter().all(Option::is_none)
+    }
+
+    fn channels(&self) -> [Option<Timing>; 4] {
+        [self.left, self.top, self.width, self.height]
+    }
+}
+
+fn parse_time(value: &str) -> Option<Duration> {
+    let micros = if let Some(ms) = value.strip_suffix("ms") {
+        ms.parse::<f64>().ok()? * 1e3
+    } else {
+        value.strip_suffix('s')?.parse::<f64>().ok()? * 1e6
+    };
+    (micros >= 0.0).then(|| Duration::from_micros(micros.round() as u64))
+}
+
+/// Parse a CSS `transition` value (`<property> <duration> [<easing>] [<delay>]`, comma
+/// separated) into `(property, timing)` pairs; the property defaults to `all`.
+pub fn parse_transition_list(value: &str) -> Vec<(String, Timing)> {
+    let mut depth = 0;
+    let mut start = 0;
+    let mut items = Vec::new();
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&value[start..]);
+
+    let mut out = Vec::new();
+    for item in items {
+        let (mut property, mut times, mut easing) = ("all", Vec::new(), Easing::Ease);
+        let mut rest = item.trim();
+        while !rest.is_empty() {
+            // A `cubic-bezier(...)` argument list contains spaces; keep it in one token.
+            let end = match rest.find('(') {
+                Some(open) if !rest[..open].contains(char::is_whitespace) => {
+                    rest.find(')').map_or(rest.len(), |c| c + 1)
+                }
+                _ => rest.find(char::is_whitespace).unwrap_or(rest.len()),
+            };
+            let token = &rest[..end];
+            rest = rest[end..].trim_start();
+            if let Some(time) = parse_time(token) {
+                times.push(time);
+            } else if let Some(e) = Easing::parse(token) {
+                easing = e;
+            } else {
+                property = token;
+            }
+        }
+        let Some(&duration) = times.first() else {
+            continue;
+        };
+        let timing = Timing {
+            duration,
+            delay: times.get(1).copied().unwrap_or_default(),
+            easing,
+        };
+        out.push((property.to_ascii_lowercase(), timing));
+    }
+    out
+}
+
+/// The layout properties named by a `transition` value. Other properties are ignored.
+pub fn parse_transition(value: &str) -> LayoutTransitions {
+    let mut out = LayoutTransitions::default();
+    for (property, timing) in parse_transition_list(value) {
+        let timing = Some(timing);
+        match property.as_str() {
+            "all" => {
+                out = LayoutTransitions {
+                    left: timing,
+                    top: timing,
+                    width: timing,
+                    height: timing,
+                }
+            }
+            "left" => out.left = timing,
+            "top" => out.top = timing,
+            "width" => out.width = timing,
+            "height" => out.height = timing,
+            _ => {}
+        }
+    }
+    out
+}
+
+pub(crate) fn transition_decl(style: &str) -> Option<&str> {
+    style
+        .split(';')
+        .filter_map(|d| d.split_once(':'))
+        .find(|(k, _)| k.trim() == "transition")
+        .map(|(_, v)| v.trim())
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Channel {
+    from: f32,
+    start: Instant,
+    timing: Timing,
+}
+
+#[derive(Clone, Debug)]
+struct Track {
+    /// Layout rect (`x`, `y`, `w`, `h`) from the last frame, before any animation.
+    target: [i32; 4],
+    channels: [Option<Channel>; 4],
+    frame: u64,
+}
+
+impl Track {
+    /// Drawn value of channel `i` at `now`; finished channels are dropped.
+    fn value(&mut self, i: usize, now: Instant) -> f32 {
+        let target = self.target[i] as f32;
+        let Some(ch) = self.channels[i] else {
+            return target;
+        };
+        match ch.timing.progress(now.saturating_duration_since(ch.start)) {
+            Some(p) => ch.from + (target - ch.from) * p,
+            None => {
+                self.channels[i] = None;
+                target
+            }
+        }
+    }
+}
+
+/// Interpolates layout between frames for elements with a layout `transition`.
+#[derive(Debug, Default)]
+pub struct LayoutAnimator {
+    tracks: HashMap<String, Track>,
+    frame: u64,
+}
+
+impl LayoutAnimator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rewrite `layout` (freshly computed for `vnode`) to the rects to draw at `now`.
+    /// Returns true while a transition is still running, i.e. another frame is needed.
+    pub fn animate(&mut self, vnode: &VNode, layout: &mut LayoutNode, now: Instant) -> bool {
+        self.frame += 1;
+        let mut path = String::new();
+        let running = self.walk(vnode, layout, &mut path, (0, 0), now);
+        let frame = self.frame;
+        self.tracks.retain(|_, t| t.frame == frame);
+        running
+    }
+
+    /// Forget all positions, e.g. after the whole view was replaced.
+    pub fn clear(&mut self) {
+        self.tracks.clear();
+    }
+
+    /// Elements whose last drawn rects are kept.
+    pub fn tracked(&self) -> usize {
+        self.tracks.len()
+    }
+
+    fn walk(
+        &mut self,
+        vnode: &VNode,
+        layout: &mut LayoutNode,
+        path: &mut String,
+        offset: (i32, i32),
+        now: Instant,
+    ) -> bool {
+        layout.rect.x += offset.0;
+        layout.rect.y += offset.1;
+        if let VNode::Fragment(children) = vnode {
+            let mut running = false;
+            let len = path.len();
+            for (i, (child, child_layout)) in
+                children.iter().zip(layout.children.iter_mut()).enumerate()
+            {
+                push_segment(path, child, i);
+                running |= self.walk(child, child_layout, path, offset, now);
+                path.truncate(len);
+            }
+            return running;
+        }
+        let VNode::Element {
+            props, children, ..
+        } = vnode
+        else {
+            return false;
+        };
+        let mut running = false;
+        let mut child_offset = offset;
+
+        let spec = props
+            .str("style")
+            .and_then(|s| transition_decl(s))
+            .map(parse_transition)
+            .unwrap_or_default();
+        if !spec.is_empty() {
+            let r = layout.rect;
+            let target = [r.x - offset.0, r.y - offset.1, r.w, r.h];
+            let frame = self.frame;
+            let track = self.tracks.entry(path.clone()).or_insert_with(|| Track {
+                target,
+                channels: [None; 4],
+                frame,
+            });
+            for (i, timing) in spec.channels().into_iter().enumerate() {
+                if target[i] == track.target[i] {
+                    continue;
+                }
+                let from = track.value(i, now);
+                track.channels[i] = timing.map(|timing| Channel {
+                    from,
+                    start: now,
+                    timing,
+                });
+            }
+            track.target = target;
+            track.frame = frame;
+            let drawn: [i32; 4] = std::array::from_fn(|i| track.value(i, now).round() as i32);
+            running = track.channels.iter().any(Option::is_some);
+            layout.rect.x = drawn[0] + offset.0;
+            layout.rect.y = drawn[1] + offset.1;
+            layout.rect.w = drawn[2];
+            layout.rect.h = drawn[3];
+            child_offset = (
+                offset.0 + drawn[0] - target[0],
+                offset.1 + drawn[1] - target[1],
+            );
+        }
+
+        let len = path.len();
+        for (i, (child, child_layout)) in
+            children.iter().zip(layout.children.iter_mut()).enumerate()
+        {
+            push_segment(path, child, i);
+            running |= self.walk(child, child_layout, path, child_offset, now);
+            path.truncate(len);
+        }
+        running
+    }
+}
+
+/// Append `child`'s identity under its parent to `path`: its `key` attribute if it has
+/// one, otherwise its index.
+pub(crate) fn push_segment(path: &mut String, child: &VNode, index: usize) {
+    match child {
+        VNode::Element { props, .. } if props.contains("key") => {
+            path.push_str("/k:");
+            path.push_str(&props.attrs["key"].to_string());
+        }
+        _ => {
+            path.push('/');
+            path.push_str(&index.to_string());
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Per-frame arena.
+//!
+//! A frame allocates thousands of short-lived values while building its display list:
+//! the command buffer itself, z-order index lists, and word-wrap candidates for every text
+//! run. `FrameArena` keeps the command buffer's capacity across frames and serves the
+//! scratch data from a bump allocator that is reset, not freed, when the next frame begins.
+//! Runners reach it through `with_frame_arena`, one per thread.
+
+use std::cell::RefCell;
+
+use bumpalo::Bump;
+use velox_dom::VNode;
+use velox_dom::layout::LayoutNode;
+
+use crate::display_list::{DisplayList, TextMeasure, build_display_list_into};
+
+thread_local! {
+    static FRAME_ARENA: RefCell<FrameArena> = RefCell::new(FrameArena::new());
+}
+
+pub struct FrameArena {
+    bump: Bump,
+    list: DisplayList,
+    frames: u64,
+}
+
+impl Default for FrameArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameArena {
+    pub fn new() -> Self {
+        FrameArena {
+            bump: Bump::new(),
+            list: DisplayList {
+                width: 0.0,
+                height: 0.0,
+                commands: Vec::new(),
+            },
+            frames: 0,
+        }
+    }
+
+    /// Start a new frame: drop last frame's commands and rewind the bump allocator. Both
+    /// keep their memory, so a steady-state frame allocates nothing here.
+    pub fn reset(&mut self) {
+        self.bump.reset();
+        self.list.commands.clear();
+        self.frames += 1;
+    }
+
+    /// Scratch allocator for data that only lives until the next `reset`.
+    pub fn bump(&self) -> &Bump {
+        &self.bump
+    }
+
+    /// Build this frame's display list into the reused command buffer.
+    pub fn build_display_list(
+        &mut self,
+        vnode: &VNode,
+        layout: &LayoutNode,
+        measure: &mut dyn TextMeasure,
+    ) -> &mut DisplayList {
+        build_display_list_into(&mut self.list, vnode, layout, measure, &self.bump);
+        &mut self.list
+    }
+
+    /// Bytes held by the bump allocator (its chunks survive `reset`).
+    pub fn allocated_bytes(&self) -> usize {
+        self.bump.allocated_bytes()
+    }
+
+    /// Commands the reused buffer can hold without growing.
+    pub fn command_capacity(&self) -> usize {
+        self.list.commands.capacity()
+    }
+
+    /// Number of frames started on this arena.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+}
+
+/// Run `f` with this thread's arena, reset for a new frame. A nested call (e.g. a
+/// renderer invoked while another frame is being built) gets a temporary arena instead.
+pub fn with_frame_arena<R>(f: impl FnOnce(&mut FrameArena) -> R) -> R {
+    FRAME_ARENA.with(|cell| match cell.try_borrow_mut() {
+        Ok(mut arena) => {
+            arena.reset();
+            f(&mut arena)
+        }
+        Err(_) => f(&mut FrameArena::new()),
+    })
+}
+
+/// Bytes this thread's arena keeps between frames: bump chunks and the command buffer.
+pub(crate) fn retained_bytes() -> usize {
+    FRAME_ARENA
+        .try_with(|cell| {
+            cell.try_borrow().map_or(0, |a| {
+                a.allocated_bytes()
+                    + a.command_capacity()
+                        * std::mem::size_of::<crate::display_list::RenderCommand>()
+            })
+        })
+        .unwrap_or(0)
+}
+
+/// Release this thread's arena memory; the next frame starts from empty buffers.
+pub(crate) fn release() {
+    FRAME_ARENA.with(|cell| {
+        if let Ok(mut arena) = cell.try_borrow_mut() {
+            let frames = arena.frames;
+            *arena = FrameArena {
+                frames,
+                ..FrameArena::new()
+            };
+        }
+    });
+}
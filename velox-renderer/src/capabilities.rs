@@ -0,0 +1,97 @@
+//! What each backend can draw, so apps and styling can degrade gracefully instead of
+//! relying on a backend silently ignoring a property.
+
+use velox_dom::VNode;
+
+use crate::config::Backend;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// `filter: blur()/brightness()` are honored.
+    pub filters: bool,
+    /// Affine transforms (scale/translate/rotate) are honored.
+    pub transforms: bool,
+    /// `border-radius` and rounded clips are drawn.
+    pub rounded_corners: bool,
+    /// LCD subpixel anti-aliasing is available for text.
+    pub subpixel_text: bool,
+    /// Largest texture/surface edge in pixels; 0 when nothing is drawn.
+    pub max_texture_size: u32,
+}
+
+/// Capabilities of `backend` as compiled into this binary. `Auto` reports the stub's.
+pub fn capabilities_for(backend: Backend) -> Capabilities {
+    match backend {
+        Backend::Wgpu => Capabilities {
+            filters: false,
+            transforms: false,
+            rounded_corners: false,
+            subpixel_text: false,
+            // wgpu's default limits (`max_texture_dimension_2d`).
+            max_texture_size: 8192,
+        },
+        Backend::Skia => Capabilities {
+            filters: true,
+            transforms: true,
+            rounded_corners: true,
+            subpixel_text: true,
+            max_texture_size: 16384,
+        },
+        // Skia's raster surface when built, otherwise tiny-skia (no filters).
+        Backend::Raster if cfg!(feature = "skia-native") => capabilities_for(Backend::Skia),
+        Backend::Raster => Capabilities {
+            filters: false,
+            transforms: true,
+            rounded_corners: true,
+            subpixel_text: true,
+            max_texture_size: 16384,
+        },
+        Backend::Auto | Backend::Stub => Capabilities::default(),
+    }
+}
+
+/// Drop style declarations `caps` cannot render (e.g. `filter` on wgpu), so that a blur
+/// doesn't turn into a hard edge halfway through the pipeline.
+pub fn strip_unsupported(vnode: &VNode, caps: &Capabilities) -> VNode {
+    match vnode {
+        VNode::Text(_) | VNode::Component { .. } => vnode.clone(),
+        VNode::Element {
+            tag,
+            props,
+            children,
+        } => {
+            let mut props = props.clone();
+            if let Some(style) = props.str("style") {
+                let kept: Vec<&str> = style
+                    .split(';')
+                    .map(str::trim)
+                    .filter(|d| !d.is_empty())
+                    .filter(|d| {
+                        let key = d.split_once(':').map(|(k, _)| k.trim()).unwrap_or("");
+                        match key {
+                            "filter" | "backdrop-filter" => caps.filters,
+                            "transform" => caps.transforms,
+                            _ => true,
+                        }
+                    })
+                    .collect();
+                let joined = kept.join("; ");
+                props.insert("style", joined);
+            }
+            VNode::Element {
+                tag: tag.clone(),
+                props,
+                children: children
+                    .iter()
+                    .map(|c| strip_unsupported(c, caps))
+                    .collect(),
+            }
+        }
+        VNode::Fragment(children) => VNode::Fragment(
+            children
+                .iter()
+                .map(|c| strip_unsupported(c, caps))
+                .collect(),
+        ),
+    }
+}
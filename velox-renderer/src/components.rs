@@ -0,0 +1,45 @@
+//! Expanding components in the views the runners render.
+//!
+//! A view may use components (`VNode::Component`, `<TodoItem :item="x"/>` in a template).
+//! The window runners, `VeloxUi`, and `TestApp` replace them in every view with what the
+//! installed resolver renders for them before styling. Most apps install a registry of
+//! their SFCs with `set_registry`; `set_resolver` takes any function of a component's
+//! name, props, and children instead.
+//! A component nothing resolves stays in the tree and renders nothing.
+
+use std::sync::{Arc, Mutex};
+
+use velox_dom::component::Registry;
+use velox_dom::{Props, VNode};
+
+/// Renders a component, or `None` for one it doesn't know.
+pub type Resolver = dyn Fn(&str, &Props, &[VNode]) -> Option<VNode> + Send + Sync;
+
+static RESOLVER: Mutex<Option<Arc<Resolver>>> = Mutex::new(None);
+
+/// Render components with `resolve` from the next frame on, replacing the last resolver.
+pub fn set_resolver<F>(resolve: F)
+where
+    F: Fn(&str, &Props, &[VNode]) -> Option<VNode> + Send + Sync + 'static,
+{
+    *RESOLVER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(resolve));
+}
+
+/// Render the components registered in `registry`.
+pub fn set_registry(registry: Registry) {
+    set_resolver(move |name, props, children| registry.resolve(name, props, children));
+}
+
+/// Remove the resolver; components render nothing.
+pub fn clear_resolver() {
+    *RESOLVER.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Replace the components in `vnode` with what the resolver renders for them.
+pub fn expand(vnode: &mut VNode) {
+    // Cloned out so a resolver may itself install another one.
+    let resolver = RESOLVER.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(resolve) = resolver {
+        velox_dom::component::expand(vnode, &*resolve);
+    }
+}
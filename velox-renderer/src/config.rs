@@ -0,0 +1,302 @@
+//! Runtime backend selection.
+//!
+//! Cargo features decide which backends are compiled in; `RendererConfig` picks one of them
+//! at startup and falls back along `FALLBACK_CHAIN` when the preferred backend can't start
+//! (e.g. no GPU adapter on a CI box or a remote session).
+//!
+//! `TextQuality` travels with the config and is published per UI thread by `run_app`, so
+//! every backend's font setup reads the same anti-aliasing, hinting, and gamma settings.
+//!
+//! Deterministic mode (`set_deterministic`) pins everything that varies between machines
+//! so golden tests (`golden`) get identical frames: text is drawn with
+//! `TextQuality::DETERMINISTIC` in the bundled font, and the runners ignore the display
+//! scale, zoom, and system text scaling.
+
+use std::cell::Cell;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Backend {
+    /// Try every compiled backend in `FALLBACK_CHAIN` order.
+    #[default]
+    Auto,
+    Wgpu,
+    /// Skia with a GL context.
+    Skia,
+    /// CPU rasterization presented through a software buffer: Skia's raster surface when
+    /// `skia-native` is built, otherwise the tiny-skia backend (`raster` feature).
+    Raster,
+    /// No window; the tree is mounted but nothing is drawn.
+    Stub,
+}
+
+/// Order in which backends are tried for `Auto`, and after a requested backend fails.
+pub const FALLBACK_CHAIN: [Backend; 4] =
+    [Backend::Wgpu, Backend::Skia, Backend::Raster, Backend::Stub];
+
+impl Backend {
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Auto => "auto",
+            Backend::Wgpu => "wgpu",
+            Backend::Skia => "skia",
+            Backend::Raster => "raster",
+            Backend::Stub => "stub",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "auto" => Some(Backend::Auto),
+            "wgpu" => Some(Backend::Wgpu),
+            "skia" => Some(Backend::Skia),
+            "raster" => Some(Backend::Raster),
+            "stub" => Some(Backend::Stub),
+            _ => None,
+        }
+    }
+
+    /// Whether support for this backend was compiled into the binary.
+    pub fn is_compiled(self) -> bool {
+        match self {
+            Backend::Wgpu => cfg!(feature = "wgpu"),
+            Backend::Skia => cfg!(feature = "skia-native"),
+            Backend::Raster => cfg!(any(feature = "skia-native", feature = "raster")),
+            Backend::Auto | Backend::Stub => true,
+        }
+    }
+}
+
+/// Glyph edge anti-aliasing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum TextAntialias {
+    #[default]
+    Grayscale,
+    /// LCD subpixel AA (horizontal RGB stripe); falls back to grayscale on backends
+    /// without `Capabilities::subpixel_text`.
+    Subpixel,
+    /// Hard edges, no anti-aliasing.
+    Aliased,
+}
+
+/// How strongly glyph outlines are snapped to the pixel grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Hinting {
+    None,
+    /// Snap baselines only.
+    #[default]
+    Slight,
+    /// Snap baselines and the start of each run.
+    Normal,
+    /// Snap every glyph origin.
+    Full,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TextQuality {
+    pub antialias: TextAntialias,
+    pub hinting: Hinting,
+    /// Coverage gamma; values above 1.0 thicken light text on dark backgrounds.
+    pub gamma: f32,
+}
+
+impl Default for TextQuality {
+    fn default() -> Self {
+        TextQuality {
+            antialias: TextAntialias::Grayscale,
+            hinting: Hinting::Slight,
+            gamma: 1.0,
+        }
+    }
+}
+
+impl TextQuality {
+    /// What deterministic mode draws with: grayscale anti-aliasing without hinting, which
+    /// depend on neither the display nor the font rasterizer's platform tuning.
+    pub const DETERMINISTIC: TextQuality = TextQuality {
+        antialias: TextAntialias::Grayscale,
+        hinting: Hinting::None,
+        gamma: 1.0,
+    };
+
+    /// Apply gamma (and aliasing) to a glyph coverage sample in `0..=1`.
+    pub fn adjust_coverage(&self, coverage: f32) -> f32 {
+        let c = coverage.clamp(0.0, 1.0);
+        match self.antialias {
+            TextAntialias::Aliased => {
+                if c >= 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            _ if self.gamma > 0.0 && self.gamma != 1.0 => c.powf(1.0 / self.gamma),
+            _ => c,
+        }
+    }
+
+    /// Read `VELOX_TEXT_AA` (grayscale|subpixel|none), `VELOX_TEXT_HINTING`
+    /// (none|slight|normal|full), and `VELOX_TEXT_GAMMA`; unset or invalid values keep defaults.
+    pub fn from_env() -> Self {
+        let mut q = TextQuality::default();
+        if let Ok(v) = std::env::var("VELOX_TEXT_AA") {
+            match v.trim().to_ascii_lowercase().as_str() {
+                "grayscale" | "gray" => q.antialias = TextAntialias::Grayscale,
+                "subpixel" | "lcd" => q.antialias = TextAntialias::Subpixel,
+                "none" | "aliased" => q.antialias = TextAntialias::Aliased,
+                _ => {}
+            }
+        }
+        if let Ok(v) = std::env::var("VELOX_TEXT_HINTING") {
+            match v.trim().to_ascii_lowercase().as_str() {
+                "none" => q.hinting = Hinting::None,
+                "slight" => q.hinting = Hinting::Slight,
+                "normal" => q.hinting = Hinting::Normal,
+                "full" => q.hinting = Hinting::Full,
+                _ => {}
+            }
+        }
+        if let Some(g) = std::env::var("VELOX_TEXT_GAMMA")
+            .ok()
+            .and_then(|v| v.trim().parse::<f32>().ok())
+            && g > 0.0
+        {
+            q.gamma = g;
+        }
+        q
+    }
+}
+
+thread_local! {
+    static TEXT_QUALITY: Cell<TextQuality> = Cell::new(TextQuality::default());
+    static DETERMINISTIC: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Text settings backends use on this thread; `run_app` sets them from its config.
+pub fn set_text_quality(quality: TextQuality) {
+    TEXT_QUALITY.with(|q| q.set(quality));
+}
+
+/// The text settings in effect: `TextQuality::DETERMINISTIC` in deterministic mode.
+pub fn text_quality() -> TextQuality {
+    if is_deterministic() {
+        return TextQuality::DETERMINISTIC;
+    }
+    TEXT_QUALITY.with(|q| q.get())
+}
+
+/// Render machine-independent frames on this thread (see the module docs).
+pub fn set_deterministic(on: bool) {
+    DETERMINISTIC.with(|d| d.set(on));
+}
+
+pub fn is_deterministic() -> bool {
+    DETERMINISTIC.with(|d| d.get())
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RendererConfig {
+    pub backend: Backend,
+    pub text: TextQuality,
+    /// Deterministic mode, for golden tests.
+    pub deterministic: bool,
+}
+
+impl RendererConfig {
+    pub fn new(backend: Backend) -> Self {
+        Self {
+            backend,
+            text: TextQuality::default(),
+            deterministic: false,
+        }
+    }
+
+    /// Read `VELOX_BACKEND` (auto|wgpu|skia|raster|stub); unknown or unset values mean `Auto`.
+    /// Text settings come from `TextQuality::from_env`, and `VELOX_DETERMINISTIC=1` turns
+    /// on deterministic mode.
+    pub fn from_env() -> Self {
+        let backend = std::env::var("VELOX_BACKEND")
+            .ok()
+            .and_then(|v| Backend::from_name(&v))
+            .unwrap_or_default();
+        let deterministic =
+            std::env::var("VELOX_DETERMINISTIC").is_ok_and(|v| matches!(v.trim(), "1" | "true"));
+        Self {
+            backend,
+            text: TextQuality::from_env(),
+            deterministic,
+        }
+    }
+}
+
+/// Size of new windows when `VELOX_WINDOW_SIZE` doesn't set one.
+pub const DEFAULT_WINDOW_SIZE: (u32, u32) = (800, 600);
+
+/// Initial inner size of the runners' windows: `VELOX_WINDOW_SIZE` as `WIDTHxHEIGHT`
+/// (`velox run` and `velox dev` set it from `velox.toml`), else `DEFAULT_WINDOW_SIZE`.
+pub fn window_size() -> (u32, u32) {
+    std::env::var("VELOX_WINDOW_SIZE")
+        .ok()
+        .and_then(|v| parse_window_size(&v))
+        .unwrap_or(DEFAULT_WINDOW_SIZE)
+}
+
+/// `"1024x768"` as `(1024, 768)`; zero or malformed sizes are `None`.
+pub fn parse_window_size(value: &str) -> Option<(u32, u32)> {
+    let (w, h) = value.trim().split_once(['x', 'X'])?;
+    let (w, h) = (w.trim().parse().ok()?, h.trim().parse().ok()?);
+    (w > 0 && h > 0).then_some((w, h))
+}
+
+/// The initial window title: `VELOX_WINDOW_TITLE` when set, else `title`.
+pub fn window_title(title: &str) -> String {
+    std::env::var("VELOX_WINDOW_TITLE")
+        .ok()
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| title.to_string())
+}
+
+/// Resolve `config` to a concrete backend by probing the host.
+pub fn select_backend(config: &RendererConfig) -> Backend {
+    select_backend_with(config, probe)
+}
+
+/// Like `select_backend`, but with a caller-supplied availability check (used by tests).
+/// Never returns `Auto`; `Stub` is always the last resort.
+pub fn select_backend_with(
+    config: &RendererConfig,
+    available: impl Fn(Backend) -> bool,
+) -> Backend {
+    let start = FALLBACK_CHAIN
+        .iter()
+        .position(|b| *b == config.backend)
+        .unwrap_or(0);
+    for backend in &FALLBACK_CHAIN[start..] {
+        if *backend == Backend::Stub || (backend.is_compiled() && available(*backend)) {
+            if config.backend != Backend::Auto && config.backend != *backend {
+                log::warn!(
+                    target: "velox::renderer",
+                    "{} backend unavailable, falling back to {}",
+                    config.backend.name(),
+                    backend.name()
+                );
+            }
+            log::info!(target: "velox::renderer", "selected {} backend", backend.name());
+            return *backend;
+        }
+    }
+    Backend::Stub
+}
+
+/// Cheap runtime check that a compiled backend can actually start on this host.
+fn probe(backend: Backend) -> bool {
+    match backend {
+        #[cfg(feature = "wgpu")]
+        Backend::Wgpu => crate::wgpu_backend::adapter_available(),
+        #[cfg(all(feature = "skia-native", unix))]
+        Backend::Skia => crate::skia_gl::create_context().is_ok(),
+        #[cfg(any(feature = "skia-native", feature = "raster"))]
+        Backend::Raster => true,
+        Backend::Stub => true,
+        _ => false,
+    }
+}
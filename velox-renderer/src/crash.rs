@@ -0,0 +1,223 @@
+//! Crash reports: what the app showed and did when it panicked.
+//!
+//! `install_crash_reporter(dir)` installs a panic hook (after the existing one, which
+//! still prints the message). When the UI thread panics, the hook writes a report
+//! directory under `dir` with:
+//!
+//! - `report.txt`: the panic message and location, a backtrace, and the last
+//!   `EVENT_HISTORY` events the runner dispatched to the app, oldest first;
+//! - `tree.txt`: the last frame's view tree, styles applied;
+//! - `screenshot.png`: the last presented frame, when the backend could read it back.
+//!
+//! Recording costs a tree clone per frame and a frame copy per present, so it is opt-in.
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+use std::time::Instant;
+
+use velox_dom::VNode;
+
+use crate::pixels::PixelRect;
+
+/// How many dispatched events a report lists.
+pub const EVENT_HISTORY: usize = 50;
+
+/// One event handed to the app's `on_event`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RecordedEvent {
+    /// Milliseconds since the reporter was installed.
+    pub at_ms: u128,
+    pub handler: String,
+    pub payload: Option<String>,
+}
+
+thread_local! {
+    static DIR: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+    static STARTED: Cell<Option<Instant>> = const { Cell::new(None) };
+    static EVENTS: RefCell<VecDeque<RecordedEvent>> = const { RefCell::new(VecDeque::new()) };
+    static TREE: RefCell<Option<VNode>> = const { RefCell::new(None) };
+}
+
+static HOOK: Once = Once::new();
+
+/// Write a crash report under `dir` when this thread panics (see the module docs).
+pub fn install_crash_reporter(dir: impl Into<PathBuf>) {
+    DIR.with(|d| *d.borrow_mut() = Some(dir.into()));
+    STARTED.with(|s| s.set(Some(Instant::now())));
+    crate::pixels::enable_readback(true);
+    HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            previous(info);
+            // Only threads that installed the reporter have a directory.
+            let Some(dir) = DIR
+                .try_with(|d| d.try_borrow().ok().and_then(|d| d.clone()))
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+            let location = info
+                .location()
+                .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+            let message = info
+                .payload()
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| info.payload().downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "<non-string panic payload>".to_string());
+            match write_report(&dir, &message, location.as_deref()) {
+                Ok(path) => eprintln!("velox: crash report written to {}", path.display()),
+                Err(e) => eprintln!("velox: writing crash report failed: {}", e),
+            }
+        }));
+    });
+}
+
+pub fn is_installed() -> bool {
+    DIR.with(|d| d.borrow().is_some())
+}
+
+/// The events recorded on this thread, oldest first.
+pub fn recent_events() -> Vec<RecordedEvent> {
+    EVENTS.with(|e| e.borrow().iter().cloned().collect())
+}
+
+/// Write a report for `message` (panicking at `location`) into a new directory under
+/// `dir`, returning the report directory.
+pub fn write_report(dir: &Path, message: &str, location: Option<&str>) -> std::io::Result<PathBuf> {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut out = dir.join(format!("velox-crash-{}", secs));
+    let mut n = 1;
+    while out.exists() {
+        n += 1;
+        out = dir.join(format!("velox-crash-{}-{}", secs, n));
+    }
+    std::fs::create_dir_all(&out)?;
+
+    let mut report = String::new();
+    let _ = writeln!(report, "panic: {}", message);
+    if let Some(location) = location {
+        let _ = writeln!(report, "at: {}", location);
+    }
+    let _ = writeln!(
+        report,
+        "\nbacktrace:\n{}",
+        std::backtrace::Backtrace::force_capture()
+    );
+    let events = EVENTS
+        .try_with(|e| e.try_borrow().map(|e| e.clone()).unwrap_or_default())
+        .unwrap_or_default();
+    let _ = writeln!(report, "last {} events:", events.len());
+    for ev in &events {
+        let _ = match &ev.payload {
+            Some(payload) => writeln!(report, "  +{}ms {} {}", ev.at_ms, ev.handler, payload),
+            None => writeln!(report, "  +{}ms {}", ev.at_ms, ev.handler),
+        };
+    }
+    std::fs::write(out.join("report.txt"), report)?;
+
+    let tree = TREE
+        .try_with(|t| t.try_borrow().ok().and_then(|t| t.as_ref().map(dump_tree)))
+        .ok()
+        .flatten();
+    std::fs::write(
+        out.join("tree.txt"),
+        tree.as_deref().unwrap_or("no frame was drawn\n"),
+    )?;
+
+    if let Some(frame) = crate::pixels::read_pixels(PixelRect::new(0, 0, u32::MAX, u32::MAX)) {
+        frame
+            .save_png(out.join("screenshot.png"))
+            .map_err(std::io::Error::other)?;
+    }
+    Ok(out)
+}
+
+/// `vnode` as indented HTML-like text, attributes sorted.
+pub fn dump_tree(vnode: &VNode) -> String {
+    fn rec(node: &VNode, depth: usize, out: &mut String) {
+        let indent = "  ".repeat(depth);
+        match node {
+            VNode::Text(t) => {
+                let _ = writeln!(out, "{}{:?}", indent, t);
+            }
+            VNode::Element {
+                tag,
+                props,
+                children,
+            } => {
+                let mut attrs: Vec<_> = props.attrs.iter().collect();
+                attrs.sort_by(|a, b| a.0.cmp(b.0));
+                let _ = write!(out, "{}<{}", indent, tag);
+                for (k, v) in attrs {
+                    let _ = write!(out, " {}={:?}", k, v.to_string());
+                }
+                let _ = writeln!(out, ">");
+                for child in children {
+                    rec(child, depth + 1, out);
+                }
+            }
+            VNode::Fragment(children) => {
+                let _ = writeln!(out, "{}<>", indent);
+                for child in children {
+                    rec(child, depth + 1, out);
+                }
+            }
+            VNode::Component {
+                name,
+                props,
+                children,
+            } => {
+                let mut attrs: Vec<_> = props.attrs.iter().collect();
+                attrs.sort_by(|a, b| a.0.cmp(b.0));
+                let _ = write!(out, "{}<{} (component)", indent, name);
+                for (k, v) in attrs {
+                    let _ = write!(out, " {}={:?}", k, v.to_string());
+                }
+                let _ = writeln!(out, ">");
+                for child in children {
+                    rec(child, depth + 1, out);
+                }
+            }
+        }
+    }
+    let mut out = String::new();
+    rec(vnode, 0, &mut out);
+    out
+}
+
+/// Remember an event dispatched to the app; no-op unless installed. The window runners
+/// call this (and `record_tree`) themselves; hosts running their own loop can too.
+pub fn record_event(handler: &str, payload: Option<&str>) {
+    if !is_installed() {
+        return;
+    }
+    let at_ms = STARTED
+        .with(|s| s.get())
+        .map_or(0, |t| t.elapsed().as_millis());
+    EVENTS.with(|e| {
+        let mut events = e.borrow_mut();
+        if events.len() == EVENT_HISTORY {
+            events.pop_front();
+        }
+        events.push_back(RecordedEvent {
+            at_ms,
+            handler: handler.to_string(),
+            payload: payload.map(str::to_string),
+        });
+    });
+}
+
+/// Remember the frame's tree; no-op unless installed.
+pub fn record_tree(vnode: &VNode) {
+    if is_installed() {
+        TREE.with(|t| *t.borrow_mut() = Some(vnode.clone()));
+    }
+}
@@ -0,0 +1,78 @@
+//! Damage regions: the parts of a frame that differ from the previous one.
+//!
+//! The CPU-presented runners (Skia raster and tiny-skia) compare each frame with the last
+//! one they presented, tile by tile, and pass the changed area to the compositor with
+//! softbuffer's `present_with_damage`, so a blinking caret or a hover highlight updates a
+//! few tiles instead of the whole window. A frame with no changes is not presented at
+//! all. The wgpu runner presents whole frames.
+
+use crate::pixels::PixelRect;
+
+/// Side of the square tiles frames are compared in, in physical pixels.
+pub const TILE: u32 = 64;
+
+/// Past this fraction of the frame, damage is reported as the whole frame: one large
+/// copy beats many small ones.
+const FULL_FRACTION: f32 = 0.5;
+
+/// The regions where `next` differs from `prev`, both tightly packed RGBA frames of
+/// `width`x`height` pixels. Tiles are merged into rows and rows into bands, so a changed
+/// area comes back as a few rects. Without a usable `prev` (the first frame, or a size
+/// change) the whole frame is damaged; identical frames give no rects.
+pub fn frame_damage(prev: Option<&[u8]>, next: &[u8], width: u32, height: u32) -> Vec<PixelRect> {
+    let full = vec![PixelRect::new(0, 0, width, height)];
+    let len = (width as usize) * (height as usize) * 4;
+    let Some(prev) = prev.filter(|p| p.len() == len && next.len() == len) else {
+        return full;
+    };
+    if width == 0 || height == 0 {
+        return Vec::new();
+    }
+    let stride = width as usize * 4;
+    let (cols, rows) = (width.div_ceil(TILE), height.div_ceil(TILE));
+    let mut rects: Vec<PixelRect> = Vec::new();
+    let mut area = 0u64;
+    for row in 0..rows {
+        let y = row * TILE;
+        let h = TILE.min(height - y);
+        let mut dirty = vec![false; cols as usize];
+        for line in y..y + h {
+            let start = line as usize * stride;
+            let (a, b) = (&prev[start..start + stride], &next[start..start + stride]);
+            if a == b {
+                continue;
+            }
+            for (col, d) in dirty.iter_mut().enumerate().filter(|(_, d)| !**d) {
+                let x0 = col * TILE as usize * 4;
+                let x1 = (x0 + TILE as usize * 4).min(stride);
+                *d = a[x0..x1] != b[x0..x1];
+            }
+        }
+        // Runs of dirty tiles in this row, merged with an identical run in the row above.
+        let mut col = 0;
+        while col < cols as usize {
+            if !dirty[col] {
+                col += 1;
+                continue;
+            }
+            let run = col;
+            while col < cols as usize && dirty[col] {
+                col += 1;
+            }
+            let x = run as u32 * TILE;
+            let w = (col as u32 * TILE).min(width) - x;
+            area += w as u64 * h as u64;
+            match rects
+                .iter_mut()
+                .find(|r| r.x == x && r.width == w && r.y + r.height == y)
+            {
+                Some(r) => r.height += h,
+                None => rects.push(PixelRect::new(x, y, w, h)),
+            }
+        }
+    }
+    if area as f32 > width as f32 * height as f32 * FULL_FRACTION {
+        return full;
+    }
+    rects
+}
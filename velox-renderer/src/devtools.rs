@@ -0,0 +1,148 @@
+//! Layout debugging overlays.
+//!
+//! With the box overlay on, every element gets three outlines drawn over the frame:
+//! its margin box (orange), its border box as laid out (yellow), and its content box
+//! inside the padding (blue). The baseline overlay underlines each line of text at its
+//! baseline (magenta). Both are appended to the display list, so every backend shows
+//! them.
+//!
+//! Overlays start from the `VELOX_DEBUG_BOXES=1` and `VELOX_DEBUG_BASELINES=1`
+//! environment variables; a devtools panel (or the app) switches them at runtime with
+//! `set_overlays`.
+
+use std::cell::Cell;
+
+use velox_dom::VNode;
+use velox_dom::layout::{LayoutNode, style_box_sides};
+
+use crate::display_list::{Color, DisplayList, RectF, RenderCommand, TextMeasure};
+
+pub const MARGIN_COLOR: Color = Color::rgba(246, 178, 107, 200);
+pub const BORDER_COLOR: Color = Color::rgba(255, 229, 153, 220);
+pub const CONTENT_COLOR: Color = Color::rgba(111, 168, 220, 220);
+pub const BASELINE_COLOR: Color = Color::rgba(255, 0, 255, 200);
+
+/// Which overlays to draw.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Overlays {
+    pub boxes: bool,
+    pub baselines: bool,
+}
+
+impl Overlays {
+    /// Overlays requested by `VELOX_DEBUG_BOXES` and `VELOX_DEBUG_BASELINES`.
+    pub fn from_env() -> Self {
+        let on = |name: &str| std::env::var(name).ok().as_deref() == Some("1");
+        Overlays {
+            boxes: on("VELOX_DEBUG_BOXES"),
+            baselines: on("VELOX_DEBUG_BASELINES"),
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        self.boxes || self.baselines
+    }
+}
+
+thread_local! {
+    static OVERLAYS: Cell<Option<Overlays>> = const { Cell::new(None) };
+}
+
+/// The overlays in effect: the last `set_overlays`, or the environment.
+pub fn overlays() -> Overlays {
+    OVERLAYS
+        .with(|o| o.get())
+        .unwrap_or_else(Overlays::from_env)
+}
+
+/// Override the environment for frames built on this thread.
+pub fn set_overlays(overlays: Overlays) {
+    OVERLAYS.with(|o| o.set(Some(overlays)));
+}
+
+/// Append the commands for `overlays` to `list`, built from `vnode` at `layout`.
+pub fn append_overlays(
+    list: &mut DisplayList,
+    vnode: &VNode,
+    layout: &LayoutNode,
+    overlays: Overlays,
+    measure: &mut dyn TextMeasure,
+) {
+    if overlays.baselines {
+        // Right after each line, so the marker shares its transform and clip.
+        let commands = std::mem::take(&mut list.commands);
+        for command in commands {
+            let marker = match &command {
+                RenderCommand::Text {
+                    x,
+                    baseline,
+                    text,
+                    size,
+                    family,
+                    ..
+                } => Some(RenderCommand::Rect {
+                    rect: RectF::new(
+                        *x,
+                        *baseline,
+                        measure.measure(family.as_deref(), *size, text),
+                        1.0,
+                    ),
+                    color: BASELINE_COLOR,
+                }),
+                _ => None,
+            };
+            list.commands.push(command);
+            list.commands.extend(marker);
+        }
+    }
+    if overlays.boxes {
+        push_boxes(&mut list.commands, vnode, layout);
+    }
+}
+
+fn push_boxes(out: &mut Vec<RenderCommand>, node: &VNode, layout: &LayoutNode) {
+    if let VNode::Fragment(children) = node {
+        for (child, child_layout) in children.iter().zip(&layout.children) {
+            push_boxes(out, child, child_layout);
+        }
+        return;
+    }
+    let VNode::Element {
+        props, children, ..
+    } = node
+    else {
+        return;
+    };
+    let style = props.str("style");
+    let (ml, mr, mt, mb) = style_box_sides(style, "margin");
+    let (pl, pr, pt, pb) = style_box_sides(style, "padding");
+    let r = layout.rect;
+    let outline = |x: i32, y: i32, w: i32, h: i32, color: Color| RenderCommand::Border {
+        rect: RectF::new(x as f32, y as f32, w.max(0) as f32, h.max(0) as f32),
+        radius: 0.0,
+        width: 1.0,
+        color,
+    };
+    if ml != 0 || mr != 0 || mt != 0 || mb != 0 {
+        out.push(outline(
+            r.x - ml,
+            r.y - mt,
+            r.w + ml + mr,
+            r.h + mt + mb,
+            MARGIN_COLOR,
+        ));
+    }
+    out.push(outline(r.x, r.y, r.w, r.h, BORDER_COLOR));
+    if pl != 0 || pr != 0 || pt != 0 || pb != 0 {
+        out.push(outline(
+            r.x + pl,
+            r.y + pt,
+            r.w - pl - pr,
+            r.h - pt - pb,
+            CONTENT_COLOR,
+        ));
+    }
+    for (child, child_layout) in children.iter().zip(&layout.children) {
+        push_boxes(out, child, child_layout);
+    }
+}
@@ -0,0 +1,807 @@
+//! Backend-neutral display list.
+//!
+//! The styled VNode tree and its layout are walked once into a flat `Vec<RenderCommand>`;
+//! backends (Skia, tiny-skia raster, wgpu) only execute commands, so inline-style parsing,
+//! z-ordering, clipping, and text wrapping live here instead of in every backend.
+
+use bumpalo::Bump;
+use bumpalo::collections::{String as BumpString, Vec as BumpVec};
+use velox_dom::VNode;
+use velox_dom::layout::{LayoutNode, Rect};
+
+use crate::render_props::NodeSpan;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Color {
+    pub const BLACK: Color = Color {
+        r: 0,
+        g: 0,
+        b: 0,
+        a: 255,
+    };
+    pub const WHITE: Color = Color {
+        r: 255,
+        g: 255,
+        b: 255,
+        a: 255,
+    };
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Color { r, g, b, a }
+    }
+
+    /// `#rgb`, `#rrggbb`, or `#rrggbbaa`.
+    pub fn parse(value: &str) -> Option<Self> {
+        let hex = value.trim().strip_prefix('#')?;
+        let byte = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        match hex.len() {
+            3 => {
+                let nib = |i: usize| {
+                    u8::from_str_radix(hex.get(i..i + 1)?, 16)
+                        .ok()
+                        .map(|v| v * 17)
+                };
+                Some(Color::rgba(nib(0)?, nib(1)?, nib(2)?, 255))
+            }
+            6 => Some(Color::rgba(byte(0)?, byte(2)?, byte(4)?, 255)),
+            8 => Some(Color::rgba(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+            _ => None,
+        }
+    }
+
+    pub fn with_opacity(self, opacity: f32) -> Self {
+        let a = ((self.a as f32) * opacity).round().clamp(0.0, 255.0) as u8;
+        Color { a, ..self }
+    }
+}
+
+/// Rectangle in logical pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RectF {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl RectF {
+    pub const fn new(x: f32, y: f32, w: f32, h: f32) -> Self {
+        RectF { x, y, w, h }
+    }
+
+    pub fn right(&self) -> f32 {
+        self.x + self.w
+    }
+
+    pub fn bottom(&self) -> f32 {
+        self.y + self.h
+    }
+}
+
+impl From<Rect> for RectF {
+    fn from(r: Rect) -> Self {
+        RectF::new(r.x as f32, r.y as f32, r.w as f32, r.h as f32)
+    }
+}
+
+/// Image filters from the `filter` property.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Filter {
+    pub blur: Option<f32>,
+    pub brightness: Option<f32>,
+}
+
+/// Scale then translate: `p' = p * scale + translate`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform2D {
+    pub sx: f32,
+    pub sy: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl Transform2D {
+    pub const IDENTITY: Transform2D = Transform2D {
+        sx: 1.0,
+        sy: 1.0,
+        tx: 0.0,
+        ty: 0.0,
+    };
+
+    /// Apply `self` after `inner`.
+    pub fn then(self, inner: Transform2D) -> Transform2D {
+        Transform2D {
+            sx: self.sx * inner.sx,
+            sy: self.sy * inner.sy,
+            tx: self.sx * inner.tx + self.tx,
+            ty: self.sy * inner.ty + self.ty,
+        }
+    }
+
+    pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+        (x * self.sx + self.tx, y * self.sy + self.ty)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RenderCommand {
+    Rect {
+        rect: RectF,
+        color: Color,
+    },
+    RRect {
+        rect: RectF,
+        radius: f32,
+        color: Color,
+    },
+    /// Stroked outline; `radius` 0 means square corners.
+    Border {
+        rect: RectF,
+        radius: f32,
+        width: f32,
+        color: Color,
+    },
+    /// One already wrapped and aligned line of text, positioned at its baseline.
+    Text {
+        x: f32,
+        baseline: f32,
+        text: String,
+        size: f32,
+        family: Option<String>,
+        bold: bool,
+        color: Color,
+    },
+    Image {
+        rect: RectF,
+        src: String,
+        opacity: f32,
+        filter: Filter,
+    },
+    /// Intersect the clip with `rect` (rounded when `radius` > 0) until the matching `PopClip`.
+    PushClip {
+        rect: RectF,
+        radius: f32,
+    },
+    PopClip,
+    PushTransform(Transform2D),
+    PopTransform,
+}
+
+/// Commands for one frame. `width`/`height` are the logical viewport; `build_display_list`
+/// uses the root layout box, and renderers with a known viewport overwrite them.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DisplayList {
+    pub width: f32,
+    pub height: f32,
+    pub commands: Vec<RenderCommand>,
+}
+
+/// Text measurement used for wrapping and alignment. Backends pass their own font metrics.
+pub trait TextMeasure {
+    fn measure(&mut self, family: Option<&str>, size: f32, text: &str) -> f32;
+}
+
+/// Fixed-advance estimate (0.6em per char) for callers without font access.
+pub struct ApproxMeasure;
+
+impl TextMeasure for ApproxMeasure {
+    fn measure(&mut self, _family: Option<&str>, size: f32, text: &str) -> f32 {
+        text.chars().count() as f32 * size * 0.6
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Debug)]
+struct TextStyle {
+    color: Color,
+    align: Align,
+    underline: bool,
+    bold: bool,
+    size: f32,
+    family: Option<String>,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        TextStyle {
+            color: Color::BLACK,
+            align: Align::Left,
+            underline: false,
+            bold: false,
+            size: 14.0,
+            family: None,
+        }
+    }
+}
+
+#[derive(Default)]
+struct BoxStyle {
+    background: Option<Color>,
+    border: Option<(f32, Color)>,
+    radius: Option<f32>,
+    overflow_hidden: bool,
+    clip_inset: Option<(f32, f32, f32, f32)>,
+    opacity: Option<f32>,
+    filter: Filter,
+    transform: Option<(f32, f32, f32, f32)>,
+    /// Each axis as (pixels, fraction of the box); the center when unset.
+    transform_origin: Option<((f32, f32), (f32, f32))>,
+}
+
+impl BoxStyle {
+    /// The `transform` of a box drawn at `rect`, about its `transform-origin`.
+    fn transform_at(&self, rect: RectF) -> Option<Transform2D> {
+        let (sx, sy, tx, ty) = self.transform?;
+        let ((ox, fx), (oy, fy)) = self.transform_origin.unwrap_or(((0.0, 0.5), (0.0, 0.5)));
+        let (cx, cy) = (rect.x + ox + fx * rect.w, rect.y + oy + fy * rect.h);
+        Some(Transform2D {
+            sx,
+            sy,
+            tx: cx * (1.0 - sx) + tx,
+            ty: cy * (1.0 - sy) + ty,
+        })
+    }
+}
+
+fn decls(style: &str) -> impl Iterator<Item = (&str, &str)> {
+    style
+        .split(';')
+        .filter_map(|d| d.split_once(':'))
+        .map(|(k, v)| (k.trim(), v.trim()))
+}
+
+fn parse_px(value: &str) -> Option<f32> {
+    let v = value.trim();
+    v.strip_suffix("px").unwrap_or(v).trim().parse::<f32>().ok()
+}
+
+/// `inset(<top> [<right> [<bottom> [<left>]]] [round ...])` -> (top, right, bottom, left).
+fn parse_clip_inset(value: &str) -> Option<(f32, f32, f32, f32)> {
+    let inner = value.trim().strip_prefix("inset(")?.strip_suffix(')')?;
+    let inner = inner.split("round").next().unwrap_or(inner);
+    let parts: Vec<f32> = inner
+        .split_whitespace()
+        .map(parse_px)
+        .collect::<Option<_>>()?;
+    match parts[..] {
+        [a] => Some((a, a, a, a)),
+        [a, b] => Some((a, b, a, b)),
+        [a, b, c] => Some((a, b, c, b)),
+        [a, b, c, d] => Some((a, b, c, d)),
+        _ => None,
+    }
+}
+
+/// `<x> [<y>]` as pixel and fraction-of-the-box parts per axis: keywords (`left`, `top`,
+/// `center`, ...), percentages, or pixel lengths.
+fn parse_transform_origin(value: &str) -> Option<((f32, f32), (f32, f32))> {
+    let part = |v: &str| match v {
+        "left" | "top" => Some((0.0, 0.0)),
+        "center" => Some((0.0, 0.5)),
+        "right" | "bottom" => Some((0.0, 1.0)),
+        _ => match v.strip_suffix('%') {
+            Some(pct) => pct.trim().parse::<f32>().ok().map(|p| (0.0, p / 100.0)),
+            None => parse_px(v).map(|px| (px, 0.0)),
+        },
+    };
+    let words: Vec<&str> = value.split_whitespace().collect();
+    match words[..] {
+        // A lone vertical keyword sets y; anything else alone sets x.
+        [y @ ("top" | "bottom")] => Some(((0.0, 0.5), part(y)?)),
+        [x] => Some((part(x)?, (0.0, 0.5))),
+        [y @ ("top" | "bottom"), x @ ("left" | "right" | "center")] => Some((part(x)?, part(y)?)),
+        [x, y, ..] => Some((part(x)?, part(y)?)),
+        [] => None,
+    }
+}
+
+/// `translate(x[, y]) scale(sx[, sy])` -> (sx, sy, tx, ty); other functions are ignored.
+fn parse_transform(value: &str) -> Option<(f32, f32, f32, f32)> {
+    let (mut sx, mut sy, mut tx, mut ty) = (1.0, 1.0, 0.0, 0.0);
+    let mut any = false;
+    for part in value.split(')') {
+        let Some((name, args)) = part.trim().split_once('(') else {
+            continue;
+        };
+        let nums: Vec<f32> = args
+            .split([',', ' '])
+            .filter(|s| !s.is_empty())
+            .filter_map(parse_px)
+            .collect();
+        match (name.trim(), nums.as_slice()) {
+            ("translate", [x]) => (tx, any) = (*x, true),
+            ("translate", [x, y, ..]) => (tx, ty, any) = (*x, *y, true),
+            ("translateX", [x]) => (tx, any) = (*x, true),
+            ("translateY", [y]) => (ty, any) = (*y, true),
+            ("scale", [s]) => (sx, sy, any) = (*s, *s, true),
+            ("scale", [x, y, ..]) => (sx, sy, any) = (*x, *y, true),
+            _ => {}
+        }
+    }
+    any.then_some((sx, sy, tx, ty))
+}
+
+fn parse_box_style(style: &str) -> BoxStyle {
+    let mut out = BoxStyle::default();
+    for (key, val) in decls(style) {
+        match key {
+            "background-color" | "background" => out.background = Color::parse(val),
+            "border" => {
+                let (mut width, mut color, mut solid) = (1.0, Color::BLACK, false);
+                for part in val.split_whitespace() {
+                    if part.eq_ignore_ascii_case("solid") {
+                        solid = true;
+                    } else if let Some(c) = Color::parse(part) {
+                        color = c;
+                    } else if let Some(w) = part.strip_suffix("px").and_then(|p| p.parse().ok()) {
+                        width = w;
+                    }
+                }
+                out.border = solid.then_some((width, color));
+            }
+            "border-radius" => {
+                out.radius = val.strip_suffix("px").and_then(|p| p.trim().parse().ok())
+            }
+            "overflow" => out.overflow_hidden = val.eq_ignore_ascii_case("hidden"),
+            "clip-path" => out.clip_inset = parse_clip_inset(val),
+            "opacity" => out.opacity = val.parse::<f32>().ok().map(|o| o.clamp(0.0, 1.0)),
+            "filter" => {
+                for part in val.split(')') {
+                    let part = part.trim();
+                    if let Some(v) = part.strip_prefix("blur(") {
+                        out.filter.blur = v
+                            .trim()
+                            .strip_suffix("px")
+                            .and_then(|p| p.parse::<f32>().ok())
+                            .map(|b| b.max(0.0));
+                    } else if let Some(v) = part.strip_prefix("brightness(") {
+                        out.filter.brightness = v.trim().parse::<f32>().ok().map(|b| b.max(0.0));
+                    }
+                }
+            }
+            "transform" => out.transform = parse_transform(val),
+            "transform-origin" => out.transform_origin = parse_transform_origin(val),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn parse_text_style(style: &str, base: &TextStyle) -> TextStyle {
+    let mut out = base.clone();
+    for (key, val) in decls(style) {
+        match key {
+            "color" => {
+                if let Some(c) = Color::parse(val) {
+                    out.color = c;
+                }
+            }
+            "text-align" => {
+                out.align = match val.to_ascii_lowercase().as_str() {
+                    "center" => Align::Center,
+                    "right" => Align::Right,
+                    _ => Align::Left,
+                }
+            }
+            "text-decoration" => {
+                let v = val.to_ascii_lowercase();
+                if v.contains("underline") {
+                    out.underline = true;
+                } else if v == "none" {
+                    out.underline = false;
+                }
+            }
+            "font-size" => {
+                if let Some(px) = parse_px(val) {
+                    out.size = px.max(1.0);
+                }
+            }
+            "font-weight" => {
+                out.bold = val.eq_ignore_ascii_case("bold")
+                    || val.parse::<i32>().map(|w| w >= 600).unwrap_or(false);
+            }
+            "font-family" => {
+                let first = val
+                    .split(',')
+                    .next()
+                    .unwrap_or("")
+                    .trim()
+                    .trim_matches(['"', '\''])
+                    .trim();
+                if !first.is_empty() {
+                    out.family = Some(first.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+fn z_index(node: &VNode) -> i32 {
+    match node {
+        VNode::Element { props, .. } => props
+            .str("style")
+            .and_then(|s| {
+                decls(s)
+                    .find(|(k, _)| *k == "z-index")
+                    .and_then(|(_, v)| v.parse().ok())
+            })
+            .unwrap_or(0),
+        VNode::Text(_) | VNode::Fragment(_) | VNode::Component { .. } => 0,
+    }
+}
+
+/// Greedy word wrap against `max_width` (no limit when <= 0). Returns (line, width) pairs.
+pub fn wrap_text(
+    text: &str,
+    max_width: f32,
+    measure: &mut dyn TextMeasure,
+    family: Option<&str>,
+    size: f32,
+) -> Vec<(String, f32)> {
+    let scratch = Bump::new();
+    wrap_lines(text, max_width, measure, family, size, &scratch)
+        .into_iter()
+        .map(|(line, w)| (line.to_string(), w))
+        .collect()
+}
+
+/// `wrap_text` with the candidate strings and lines allocated in `scratch`.
+fn wrap_lines<'b>(
+    text: &str,
+    max_width: f32,
+    measure: &mut dyn TextMeasure,
+    family: Option<&str>,
+    size: f32,
+    scratch: &'b Bump,
+) -> BumpVec<'b, (&'b str, f32)> {
+    let limit = if max_width <= 0.0 {
+        f32::INFINITY
+    } else {
+        max_width
+    };
+    let mut lines = BumpVec::new_in(scratch);
+    for para in text.split('\n') {
+        if para.trim().is_empty() {
+            lines.push(("", 0.0));
+            continue;
+        }
+        let mut current = BumpString::new_in(scratch);
+        let mut candidate = BumpString::new_in(scratch);
+        let mut current_w = 0.0;
+        for word in para.split_whitespace() {
+            candidate.clear();
+            if !current.is_empty() {
+                candidate.push_str(&current);
+                candidate.push(' ');
+            }
+            candidate.push_str(word);
+            let candidate_w = measure.measure(family, size, &candidate);
+            if candidate_w <= limit || current.is_empty() {
+                std::mem::swap(&mut current, &mut candidate);
+                current_w = candidate_w;
+            } else {
+                lines.push((current.into_bump_str(), current_w));
+                current = BumpString::from_str_in(word, scratch);
+                current_w = measure.measure(family, size, &current);
+            }
+        }
+        if !current.is_empty() {
+            lines.push((current.into_bump_str(), current_w));
+        }
+    }
+    lines
+}
+
+/// Walk the styled tree and its layout (from `velox_dom::layout::compute_layout`) into a display list.
+pub fn build_display_list(
+    vnode: &VNode,
+    layout: &LayoutNode,
+    measure: &mut dyn TextMeasure,
+) -> DisplayList {
+    let mut list = DisplayList {
+        width: 0.0,
+        height: 0.0,
+        commands: Vec::new(),
+    };
+    build_display_list_into(&mut list, vnode, layout, measure, &Bump::new());
+    list
+}
+
+/// Build into an existing list, reusing its command buffer; per-node scratch (z-order,
+/// wrapped lines) goes to `scratch`. This is what `arena::FrameArena` calls each frame.
+/// Render properties set with `render_props::set_render_props` are applied, and enabled
+/// `devtools` overlays are appended.
+pub fn build_display_list_into(
+    list: &mut DisplayList,
+    vnode: &VNode,
+    layout: &LayoutNode,
+    measure: &mut dyn TextMeasure,
+    scratch: &Bump,
+) {
+    if crate::render_props::has_render_props() {
+        let mut spans = Vec::new();
+        build_commands(list, vnode, layout, measure, scratch, Some(&mut spans));
+        let base = std::mem::take(&mut list.commands);
+        crate::render_props::compose(
+            &base,
+            &spans,
+            crate::render_props::render_props,
+            &mut list.commands,
+        );
+    } else {
+        build_commands(list, vnode, layout, measure, scratch, None);
+    }
+    let overlays = crate::devtools::overlays();
+    if overlays.any() {
+        crate::devtools::append_overlays(list, vnode, layout, overlays, measure);
+    }
+}
+
+/// `layout` as it is drawn: every box moved and scaled by the `transform`s on it and
+/// its ancestors, and cut to the `overflow: hidden` boxes around it. Hit tests run
+/// against it, so a press lands on what is under it on screen.
+pub fn drawn_layout(vnode: &VNode, layout: &LayoutNode) -> LayoutNode {
+    fn map(t: Transform2D, clip: Option<RectF>, r: Rect) -> Rect {
+        let (x0, y0) = t.apply(r.x as f32, r.y as f32);
+        let (x1, y1) = t.apply((r.x + r.w) as f32, (r.y + r.h) as f32);
+        let (mut x0, mut y0, mut x1, mut y1) = (x0.min(x1), y0.min(y1), x0.max(x1), y0.max(y1));
+        if let Some(c) = clip {
+            (x0, y0) = (x0.clamp(c.x, c.right()), y0.clamp(c.y, c.bottom()));
+            (x1, y1) = (x1.clamp(c.x, c.right()), y1.clamp(c.y, c.bottom()));
+        }
+        let (x, y) = (x0.round() as i32, y0.round() as i32);
+        Rect {
+            x,
+            y,
+            w: x1.round() as i32 - x,
+            h: y1.round() as i32 - y,
+        }
+    }
+    fn rec(node: &VNode, layout: &LayoutNode, t: Transform2D, clip: Option<RectF>) -> LayoutNode {
+        let (t, inner_clip) = match node {
+            VNode::Element { props, .. } => {
+                let boxed = parse_box_style(props.str("style").unwrap_or(""));
+                let t = boxed
+                    .transform_at(RectF::from(layout.rect))
+                    .map_or(t, |local| t.then(local));
+                // The clip is already within the outer one.
+                let inner_clip = if boxed.overflow_hidden {
+                    Some(RectF::from(map(t, clip, layout.rect)))
+                } else {
+                    clip
+                };
+                (t, inner_clip)
+            }
+            _ => (t, clip),
+        };
+        LayoutNode {
+            rect: map(t, clip, layout.rect),
+            children: node
+                .children()
+                .iter()
+                .zip(&layout.children)
+                .map(|(c, l)| rec(c, l, t, inner_clip))
+                .collect(),
+        }
+    }
+    rec(vnode, layout, Transform2D::IDENTITY, None)
+}
+
+/// Build the commands for `vnode` into `list`, recording each `ref` element's commands
+/// in `spans` (in tree order) when given.
+pub(crate) fn build_commands(
+    list: &mut DisplayList,
+    vnode: &VNode,
+    layout: &LayoutNode,
+    measure: &mut dyn TextMeasure,
+    scratch: &Bump,
+    spans: Option<&mut Vec<NodeSpan>>,
+) {
+    let root = RectF::from(layout.rect);
+    (list.width, list.height) = (root.w, root.h);
+    list.commands.clear();
+    let mut builder = Builder {
+        out: &mut list.commands,
+        measure,
+        scratch,
+        spans,
+    };
+    builder.node(vnode, layout, root, &TextStyle::default(), 1.0);
+}
+
+struct Builder<'a> {
+    out: &'a mut Vec<RenderCommand>,
+    measure: &'a mut dyn TextMeasure,
+    scratch: &'a Bump,
+    spans: Option<&'a mut Vec<NodeSpan>>,
+}
+
+impl Builder<'_> {
+    fn node(
+        &mut self,
+        node: &VNode,
+        layout: &LayoutNode,
+        container: RectF,
+        text: &TextStyle,
+        opacity: f32,
+    ) {
+        let rect = RectF::from(layout.rect);
+        match node {
+            VNode::Element {
+                props, children, ..
+            } => {
+                // Reserve the span before the children's, keeping spans in tree order.
+                let span = match (&mut self.spans, props.str("ref")) {
+                    (Some(spans), Some(name)) => {
+                        let start = self.out.len();
+                        spans.push(NodeSpan {
+                            name: name.to_string(),
+                            rect,
+                            commands: start..start,
+                        });
+                        Some(spans.len() - 1)
+                    }
+                    _ => None,
+                };
+                let style = props.str("style").unwrap_or("");
+                let boxed = parse_box_style(style);
+                let child_text = parse_text_style(style, text);
+                let opacity = (opacity * boxed.opacity.unwrap_or(1.0)).clamp(0.0, 1.0);
+
+                // CSS transforms default to a centered origin.
+                let transformed = if let Some(t) = boxed.transform_at(rect) {
+                    self.out.push(RenderCommand::PushTransform(t));
+                    true
+                } else {
+                    false
+                };
+
+                let radius = boxed.radius.unwrap_or(0.0);
+                if let Some(bg) = boxed.background {
+                    let color = bg.with_opacity(opacity);
+                    self.out.push(if radius > 0.0 {
+                        RenderCommand::RRect {
+                            rect,
+                            radius,
+                            color,
+                        }
+                    } else {
+                        RenderCommand::Rect { rect, color }
+                    });
+                }
+                if let Some((width, color)) = boxed.border {
+                    self.out.push(RenderCommand::Border {
+                        rect,
+                        radius,
+                        width,
+                        color: color.with_opacity(opacity),
+                    });
+                }
+                if let Some(src) = props.str("src") {
+                    self.out.push(RenderCommand::Image {
+                        rect,
+                        src: src.to_string(),
+                        opacity,
+                        filter: boxed.filter,
+                    });
+                }
+
+                let mut clips = 0;
+                if radius > 0.0 || boxed.overflow_hidden {
+                    self.out.push(RenderCommand::PushClip { rect, radius });
+                    clips += 1;
+                }
+                if let Some((top, right, bottom, left)) = boxed.clip_inset {
+                    let inset = RectF::new(
+                        rect.x + left,
+                        rect.y + top,
+                        (rect.w - left - right).max(0.0),
+                        (rect.h - top - bottom).max(0.0),
+                    );
+                    self.out.push(RenderCommand::PushClip {
+                        rect: inset,
+                        radius: 0.0,
+                    });
+                    clips += 1;
+                }
+
+                let mut order = BumpVec::from_iter_in(0..children.len(), self.scratch);
+                order.sort_by_key(|&i| (z_index(&children[i]), i));
+                for i in order {
+                    if let Some(child_layout) = layout.children.get(i) {
+                        self.node(&children[i], child_layout, rect, &child_text, opacity);
+                    }
+                }
+
+                for _ in 0..clips {
+                    self.out.push(RenderCommand::PopClip);
+                }
+                if transformed {
+                    self.out.push(RenderCommand::PopTransform);
+                }
+                if let (Some(spans), Some(i)) = (&mut self.spans, span) {
+                    spans[i].commands.end = self.out.len();
+                }
+            }
+            // Its nodes draw as children of the enclosing element.
+            VNode::Fragment(children) => {
+                let mut order = BumpVec::from_iter_in(0..children.len(), self.scratch);
+                order.sort_by_key(|&i| (z_index(&children[i]), i));
+                for i in order {
+                    if let Some(child_layout) = layout.children.get(i) {
+                        self.node(&children[i], child_layout, container, text, opacity);
+                    }
+                }
+            }
+            VNode::Text(t) => {
+                let family = text.family.as_deref();
+                let lines = wrap_lines(
+                    t,
+                    container.w,
+                    self.measure,
+                    family,
+                    text.size,
+                    self.scratch,
+                );
+                let line_height = text.size * 1.2;
+                // Leaves spanning their container align against it, with a small inset.
+                let spans = rect.w >= container.w - 0.5;
+                let align_rect = if spans { container } else { rect };
+                let padding = if spans { 2.0 } else { 0.0 };
+                let bottom = rect.y + rect.h.max(line_height);
+                let color = text.color.with_opacity(opacity);
+                for (idx, (line, line_w)) in lines.into_iter().enumerate() {
+                    let baseline = rect.y + text.size + idx as f32 * line_height;
+                    if baseline > bottom {
+                        break;
+                    }
+                    let x = match text.align {
+                        Align::Left => align_rect.x + padding,
+                        Align::Center => align_rect.x + (align_rect.w - line_w) * 0.5,
+                        Align::Right => {
+                            (align_rect.right() - line_w - padding).max(align_rect.x + padding)
+                        }
+                    };
+                    if text.underline {
+                        self.out.push(RenderCommand::Rect {
+                            rect: RectF::new(x, baseline + 0.5, line_w, 1.0),
+                            color,
+                        });
+                    }
+                    self.out.push(RenderCommand::Text {
+                        x,
+                        baseline,
+                        text: line.to_string(),
+                        size: text.size,
+                        family: text.family.clone(),
+                        bold: text.bold,
+                        color,
+                    });
+                }
+            }
+            // Unexpanded: nothing to draw.
+            VNode::Component { .. } => {}
+        }
+    }
+}
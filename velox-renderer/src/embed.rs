@@ -0,0 +1,716 @@
+//! Velox as an overlay inside someone else's event loop and render pass.
+//!
+//! The window runners own the event loop. `VeloxUi` is for hosts that already have one,
+//! such as game engines and tools that want a velox panel over their own frame. The host
+//! forwards input to `handle_event` (acting on it only when that returns false), calls
+//! `update` once per frame, and calls `render` with its own encoder and target after
+//! drawing the scene.
+//!
+//! Sizes and pointer positions are in physical pixels. The view is laid out at the
+//! logical size, as in a window. `render` loads the target instead of clearing it, so
+//! only what the view paints covers the host's frame. Hosts with their own renderer can
+//! draw `display_list()` themselves, or the quads and text runs from `tessellate`.
+
+use std::time::{Duration, Instant};
+
+use velox_dom::VNode;
+use velox_style::Stylesheet;
+
+use crate::animation::LayoutAnimator;
+use crate::display_list::{Color, DisplayList, RectF, RenderCommand};
+use crate::gesture::Gesture;
+use crate::input::ViewInput;
+use crate::pen::PenSample;
+use crate::view::{ViewState, logical_size, recompute_targets, styled_view};
+
+type MakeView = Box<dyn FnMut(u32, u32) -> (VNode, Stylesheet)>;
+type OnEvent = Box<dyn FnMut(&str, Option<&str>)>;
+
+/// A velox view hosted by another application: see the module docs.
+pub struct VeloxUi {
+    make_view: MakeView,
+    on_event: OnEvent,
+    width: u32,
+    height: u32,
+    scale_factor: f32,
+    view_state: ViewState,
+    animator: LayoutAnimator,
+    /// The mouse (`None`) and touch points by the host's ids, in logical pixels.
+    input: ViewInput<Option<u64>>,
+    /// The view as mounted by the first `update`; dropping `VeloxUi` unmounts it.
+    tree: Option<crate::RenderTree>,
+    list: DisplayList,
+    dirty: bool,
+    animating: bool,
+    #[cfg(feature = "wgpu")]
+    gpu: Option<gpu::Gpu>,
+    /// Whether Ctrl is held, which makes the wheel zoom.
+    #[cfg(feature = "wgpu")]
+    ctrl: bool,
+}
+
+impl VeloxUi {
+    /// A view over a `testing::DEFAULT_SIZE` target until the host calls `resize`. The
+    /// first frame is built by the first `update`.
+    pub fn new<V, E>(make_view: V, mut on_event: E) -> Self
+    where
+        V: FnMut(u32, u32) -> (VNode, Stylesheet) + 'static,
+        E: FnMut(&str, Option<&str>) + 'static,
+    {
+        let (width, height) = crate::testing::DEFAULT_SIZE;
+        VeloxUi {
+            make_view: Box::new(make_view),
+            // Dispatched events are kept for crash reports, as in the runners.
+            on_event: Box::new(move |handler, payload| {
+                crate::crash::record_event(handler, payload);
+                on_event(handler, payload);
+                velox_core::signal::end_turn();
+            }),
+            width,
+            height,
+            scale_factor: 1.0,
+            view_state: ViewState::default(),
+            animator: LayoutAnimator::new(),
+            input: ViewInput::new(None),
+            tree: None,
+            list: DisplayList::default(),
+            dirty: true,
+            animating: false,
+            #[cfg(feature = "wgpu")]
+            gpu: None,
+            #[cfg(feature = "wgpu")]
+            ctrl: false,
+        }
+    }
+
+    /// The host's render target changed: `width`x`height` physical pixels at the
+    /// display's `scale_factor`.
+    pub fn resize(&mut self, width: u32, height: u32, scale_factor: f32) {
+        let (width, height) = (width.max(1), height.max(1));
+        if (width, height, scale_factor) != (self.width, self.height, self.scale_factor) {
+            self.width = width;
+            self.height = height;
+            self.scale_factor = scale_factor;
+            self.dirty = true;
+        }
+    }
+
+    /// Size of the render target, in physical pixels.
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// The scale the view is drawn at: the display's scale factor times the zoom.
+    pub fn scale(&self) -> f32 {
+        crate::zoom::ui_scale(self.scale_factor)
+    }
+
+    /// The pointer moved to `(x, y)` in physical pixels. Returns whether it is over
+    /// something the view handles, so the host should not act on it too. Hover follows
+    /// at the next `update`, with the last position moved to (see `pointer`).
+    pub fn pointer_moved(&mut self, x: f32, y: f32) -> bool {
+        let scale = self.scale();
+        self.dirty |= self
+            .input
+            .moved(None, x / scale, y / scale, &mut *self.on_event);
+        self.wants_pointer() || self.input.is_dragging()
+    }
+
+    /// The pointer left the host's window.
+    pub fn pointer_left(&mut self) {
+        self.dirty |= self.input.left();
+    }
+
+    /// Whether the pointer is over an element with a click, hover or pan handler.
+    pub fn wants_pointer(&self) -> bool {
+        let Some((x, y)) = self.input.pointer else {
+            return false;
+        };
+        let targets = &self.input.targets;
+        targets.click_at(x, y).is_some()
+            || targets.hover_at(x, y).is_some()
+            || targets.pan_at(x, y).is_some()
+    }
+
+    /// The primary button went down at the last pointer position. Click-outside handlers
+    /// run first, then the click under the pointer; handlers without a payload of their
+    /// own get the position. A press on an `on:pan` element elsewhere starts dragging it
+    /// (see `viewport`). Returns whether a click handler or a drag took the press, which
+    /// the host should then ignore.
+    pub fn pointer_pressed(&mut self) -> bool {
+        let press = self.input.pressed(None, &mut *self.on_event);
+        self.dirty |= press.handled;
+        press.taken
+    }
+
+    /// The primary button went up, ending a drag. Returns whether there was one.
+    pub fn pointer_released(&mut self) -> bool {
+        self.input.released(None, &mut *self.on_event)
+    }
+
+    /// The wheel turned by `(dx, dy)` physical pixels (positive to move the content right
+    /// and down) at the last pointer position; with `zoom` (Ctrl held) it zooms instead.
+    /// Returns whether an `on:pan` or `on:zoom` element took it (see `viewport`).
+    pub fn pointer_scrolled(&mut self, dx: f32, dy: f32, zoom: bool) -> bool {
+        let scale = self.scale();
+        let handled = self
+            .input
+            .wheel((dx / scale, dy / scale), zoom, &mut *self.on_event);
+        self.dirty |= handled;
+        handled
+    }
+
+    /// A pen, stylus or finger sample at a position in physical pixels. `touch` is the
+    /// host's id for the touch point; the handler sees it as a pointer id. Returns
+    /// whether an `on:pen` element took it (see `pen`), a two-finger gesture an `on:zoom`
+    /// or `on:rotate` one (see `gesture`), or a drag an `on:pan` one (see `viewport`).
+    pub fn pen(&mut self, touch: u64, sample: PenSample) -> bool {
+        let scale = self.scale();
+        let sample = PenSample {
+            x: sample.x / scale,
+            y: sample.y / scale,
+            ..sample
+        };
+        let handled = self.input.touch(Some(touch), sample, &mut *self.on_event);
+        self.dirty |= handled;
+        handled
+    }
+
+    /// A trackpad pinch at the last pointer position; a `delta` of 0.1 is 10% larger.
+    /// Returns whether an `on:zoom` element took it.
+    pub fn pointer_magnified(&mut self, delta: f32) -> bool {
+        self.trackpad(|x, y| Gesture::magnify(delta, x, y))
+    }
+
+    /// A trackpad rotation at the last pointer position, in degrees counterclockwise.
+    /// Returns whether an `on:rotate` element took it.
+    pub fn pointer_rotated(&mut self, degrees: f32) -> bool {
+        self.trackpad(|x, y| Gesture::rotate_degrees(degrees, x, y))
+    }
+
+    fn trackpad(&mut self, gesture: impl FnOnce(f32, f32) -> Gesture) -> bool {
+        let handled = self.input.trackpad(gesture, &mut *self.on_event);
+        self.dirty |= handled;
+        handled
+    }
+
+    /// Send `handler` to the app directly, as the runners do for keyboard shortcuts, and
+    /// rebuild the view on the next `update`.
+    pub fn emit(&mut self, handler: &str, payload: Option<&str>) {
+        (self.on_event)(handler, payload);
+        self.dirty = true;
+    }
+
+    /// The view's state may have changed outside its handlers; rebuild it on the next
+    /// `update`.
+    pub fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    /// Advance one host frame, `dt` after the last. Idle tasks (`velox_core::idle`) run
+    /// for up to a quarter of `dt`, leaving the rest of the frame to the host. The view
+    /// is rebuilt when input, idle work, a woken async task or `invalidate` changed it, or while a transition,
+    /// spring or layout animation runs. Returns whether it was, that is whether the next
+    /// `render` draws something new.
+    pub fn update(&mut self, dt: Duration) -> bool {
+        #[cfg(feature = "wgpu")]
+        if let Some(gpu) = &mut self.gpu {
+            gpu.recall();
+        }
+        if velox_core::idle::has_pending()
+            && velox_core::idle::run_idle(Instant::now() + dt / 4) > 0
+        {
+            self.dirty = true;
+        }
+        // Woken tasks run as the view is rebuilt (`velox_core::frame::tick`).
+        if velox_core::task::has_ready() || velox_core::signal::has_next_tick() {
+            self.dirty = true;
+        }
+        self.dirty |= self.input.update_hover();
+        if !(self.dirty || self.animating) {
+            return false;
+        }
+        self.dirty = false;
+        self.build();
+        true
+    }
+
+    /// What the last `update` built, in logical pixels.
+    pub fn display_list(&self) -> &DisplayList {
+        &self.list
+    }
+
+    fn build(&mut self) {
+        let (vw, vh) = logical_size(self.width as i32, self.height as i32, self.scale());
+        let (vnode, _sheet) = styled_view(
+            &mut self.make_view,
+            vw,
+            vh,
+            self.input.hovered,
+            &mut self.view_state,
+        );
+        // Lifecycle hooks that change state show at the next `update`, like the callbacks below.
+        if crate::commit_frame(&mut self.tree, &vnode) {
+            self.dirty = true;
+        }
+        let handlers = &self.tree.as_ref().expect("commit_frame mounts the tree").handlers;
+        recompute_targets(&vnode, vw, vh, handlers, &mut self.input.targets);
+        let mut layout = crate::pipeline::layout_tree(&vnode, vw, vh);
+        self.animating = self
+            .animator
+            .animate(&vnode, &mut layout, velox_core::clock::now())
+            || self.view_state.transitions.is_running()
+            || velox_core::frame::is_running();
+        crate::crash::record_tree(&vnode);
+        crate::stats::record_frame(
+            &vnode,
+            self.animator.tracked() + self.view_state.transitions.tracked(),
+        );
+        self.list = crate::display_list::build_display_list(
+            &vnode,
+            &layout,
+            &mut crate::display_list::ApproxMeasure,
+        );
+        self.list.width = vw as f32;
+        self.list.height = vh as f32;
+        // Callbacks that change state show at the next `update`.
+        if velox_core::signal::run_next_tick() > 0 {
+            self.dirty = true;
+        }
+    }
+}
+
+/// A corner of a filled quad: position in clip space, straight-alpha RGBA color.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct Vertex {
+    pub pos: [f32; 2],
+    pub color: [f32; 4],
+}
+
+/// One line of text to draw, in physical pixels from the top left of the target.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextRun<'a> {
+    pub x: f32,
+    /// Top of the line box (the baseline less an approximate ascent).
+    pub top: f32,
+    pub size: f32,
+    pub text: &'a str,
+    pub family: Option<&'a str>,
+    pub bold: bool,
+    pub color: [f32; 4],
+    pub clip: Option<RectF>,
+}
+
+/// A display list ready for a GPU: triangles for fills and borders, and text runs.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mesh<'a> {
+    /// Triangle list, six vertices per quad.
+    pub vertices: Vec<Vertex>,
+    pub text: Vec<TextRun<'a>>,
+}
+
+/// Turn `list` (logical pixels) into a mesh for a `width`x`height` target drawn at
+/// `scale`. Fills and borders become quads clipped on the CPU; rounded corners, images
+/// and transforms are not drawn, as in the wgpu runner.
+pub fn tessellate(list: &DisplayList, width: u32, height: u32, scale: f32) -> Mesh<'_> {
+    let (w, h) = (width.max(1) as f32, height.max(1) as f32);
+    let to_ndc = |x: f32, y: f32| [x / w * 2.0 - 1.0, 1.0 - y / h * 2.0];
+    let scaled = |r: RectF| RectF::new(r.x * scale, r.y * scale, r.w * scale, r.h * scale);
+    let rgba = |c: Color| {
+        [
+            c.r as f32 / 255.0,
+            c.g as f32 / 255.0,
+            c.b as f32 / 255.0,
+            c.a as f32 / 255.0,
+        ]
+    };
+    let mut mesh = Mesh::default();
+    let push_quad = |vertices: &mut Vec<Vertex>, r: RectF, clip: Option<RectF>, color: Color| {
+        let r = scaled(r);
+        let (mut x0, mut y0, mut x1, mut y1) = (r.x, r.y, r.right(), r.bottom());
+        if let Some(c) = clip {
+            x0 = x0.max(c.x);
+            y0 = y0.max(c.y);
+            x1 = x1.min(c.right());
+            y1 = y1.min(c.bottom());
+        }
+        if x1 <= x0 || y1 <= y0 || color.a == 0 {
+            return;
+        }
+        let color = rgba(color);
+        for (x, y) in [(x0, y0), (x1, y0), (x1, y1), (x0, y0), (x1, y1), (x0, y1)] {
+            vertices.push(Vertex {
+                pos: to_ndc(x, y),
+                color,
+            });
+        }
+    };
+    let mut clips: Vec<RectF> = Vec::new();
+    for cmd in &list.commands {
+        let clip = clips.last().copied();
+        match cmd {
+            RenderCommand::Rect { rect, color } | RenderCommand::RRect { rect, color, .. } => {
+                push_quad(&mut mesh.vertices, *rect, clip, *color)
+            }
+            RenderCommand::Border {
+                rect, width, color, ..
+            } => {
+                let bw = *width;
+                push_quad(
+                    &mut mesh.vertices,
+                    RectF::new(rect.x, rect.y, rect.w, bw),
+                    clip,
+                    *color,
+                );
+                push_quad(
+                    &mut mesh.vertices,
+                    RectF::new(rect.x, rect.bottom() - bw, rect.w, bw),
+                    clip,
+                    *color,
+                );
+                push_quad(
+                    &mut mesh.vertices,
+                    RectF::new(rect.x, rect.y, bw, rect.h),
+                    clip,
+                    *color,
+                );
+                push_quad(
+                    &mut mesh.vertices,
+                    RectF::new(rect.right() - bw, rect.y, bw, rect.h),
+                    clip,
+                    *color,
+                );
+            }
+            RenderCommand::Text {
+                x,
+                baseline,
+                text,
+                size,
+                family,
+                bold,
+                color,
+            } => mesh.text.push(TextRun {
+                x: x * scale,
+                top: (baseline - size * 0.8) * scale,
+                size: size * scale,
+                text,
+                family: family.as_deref(),
+                bold: *bold,
+                color: rgba(*color),
+                clip,
+            }),
+            RenderCommand::PushClip { rect, .. } => {
+                let rect = scaled(*rect);
+                let next = match clip {
+                    Some(c) => {
+                        let x = rect.x.max(c.x);
+                        let y = rect.y.max(c.y);
+                        RectF::new(
+                            x,
+                            y,
+                            (rect.right().min(c.right()) - x).max(0.0),
+                            (rect.bottom().min(c.bottom()) - y).max(0.0),
+                        )
+                    }
+                    None => rect,
+                };
+                clips.push(next);
+            }
+            RenderCommand::PopClip => {
+                clips.pop();
+            }
+            RenderCommand::Image { .. }
+            | RenderCommand::PushTransform(_)
+            | RenderCommand::PopTransform => {}
+        }
+    }
+    mesh
+}
+
+#[cfg(feature = "wgpu")]
+impl VeloxUi {
+    /// Draw with the host's wgpu device into targets of `format`.
+    pub fn with_wgpu(
+        mut self,
+        device: std::sync::Arc<wgpu::Device>,
+        queue: std::sync::Arc<wgpu::Queue>,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        self.gpu = Some(gpu::Gpu::new(device, queue, format));
+        self
+    }
+
+    /// Forward a window event. Returns whether the view took it (a press or the pointer
+    /// over one of its handlers), in which case the host should not act on it too.
+    pub fn handle_event(&mut self, event: &winit::event::WindowEvent<'_>) -> bool {
+        use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+        match event {
+            WindowEvent::CursorMoved { position, .. } => {
+                self.pointer_moved(position.x as f32, position.y as f32)
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.pointer_left();
+                false
+            }
+            WindowEvent::MouseInput {
+                state: ElementState::Pressed,
+                button: MouseButton::Left,
+                ..
+            } => self.pointer_pressed(),
+            WindowEvent::MouseInput {
+                state: ElementState::Released,
+                button: MouseButton::Left,
+                ..
+            } => self.pointer_released() || self.wants_pointer(),
+            WindowEvent::MouseInput { .. } => self.wants_pointer(),
+            WindowEvent::MouseWheel { delta, .. } => {
+                let (dx, dy) = match *delta {
+                    MouseScrollDelta::LineDelta(x, y) => {
+                        let line = crate::viewport::LINE_PIXELS * self.scale();
+                        (x * line, y * line)
+                    }
+                    MouseScrollDelta::PixelDelta(p) => (p.x as f32, p.y as f32),
+                };
+                self.pointer_scrolled(dx, dy, self.ctrl)
+            }
+            WindowEvent::ModifiersChanged(m) => {
+                self.ctrl = m.ctrl();
+                false
+            }
+            WindowEvent::Touch(touch) => {
+                let sample =
+                    crate::pen::from_touch(touch, touch.location.x as f32, touch.location.y as f32);
+                self.pen(touch.id, sample)
+            }
+            WindowEvent::TouchpadMagnify { delta, .. } => self.pointer_magnified(*delta as f32),
+            WindowEvent::TouchpadRotate { delta, .. } => self.pointer_rotated(*delta),
+            WindowEvent::Resized(size) => {
+                self.resize(size.width, size.height, self.scale_factor);
+                false
+            }
+            WindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => {
+                self.resize(
+                    new_inner_size.width,
+                    new_inner_size.height,
+                    *scale_factor as f32,
+                );
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Record the view's draw calls into `encoder`, over what `target` already holds.
+    /// Does nothing until `with_wgpu` is called. The host submits the encoder.
+    pub fn render(&mut self, encoder: &mut wgpu::CommandEncoder, target: &wgpu::TextureView) {
+        let scale = self.scale();
+        let Some(gpu) = &mut self.gpu else { return };
+        let mesh = tessellate(&self.list, self.width, self.height, scale);
+        gpu.draw(encoder, target, &mesh, self.width, self.height);
+    }
+}
+
+#[cfg(feature = "wgpu")]
+mod gpu {
+    use std::sync::Arc;
+
+    use super::{Mesh, Vertex};
+
+    pub(super) struct Gpu {
+        device: Arc<wgpu::Device>,
+        queue: Arc<wgpu::Queue>,
+        pipeline: wgpu::RenderPipeline,
+        glyph: Option<(wgpu_glyph::GlyphBrush<()>, wgpu::util::StagingBelt)>,
+        /// The staging belt has chunks in a submitted (or soon submitted) encoder.
+        in_flight: bool,
+    }
+
+    impl Gpu {
+        pub(super) fn new(
+            device: Arc<wgpu::Device>,
+            queue: Arc<wgpu::Queue>,
+            format: wgpu::TextureFormat,
+        ) -> Self {
+            let shader_src = r#"
+                struct VsOut { @builtin(position) position: vec4<f32>, @location(0) color: vec4<f32>, };
+                @vertex fn vs(@location(0) pos: vec2<f32>, @location(1) color: vec4<f32>) -> VsOut {
+                    var out: VsOut; out.position = vec4<f32>(pos, 0.0, 1.0); out.color = color; return out;
+                }
+                @fragment fn fs(@location(0) color: vec4<f32>) -> @location(0) vec4<f32> { return color; }
+            "#;
+            let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some("velox-embed-shader"),
+                source: wgpu::ShaderSource::Wgsl(shader_src.into()),
+            });
+            let vlayout = wgpu::VertexBufferLayout {
+                array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+                step_mode: wgpu::VertexStepMode::Vertex,
+                attributes: &[
+                    wgpu::VertexAttribute {
+                        format: wgpu::VertexFormat::Float32x2,
+                        offset: 0,
+                        shader_location: 0,
+                    },
+                    wgpu::VertexAttribute {
+                        format: wgpu::VertexFormat::Float32x4,
+                        offset: 8,
+                        shader_location: 1,
+                    },
+                ],
+            };
+            let pl_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some("velox-embed-pl"),
+                bind_group_layouts: &[],
+                push_constant_ranges: &[],
+            });
+            let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+                label: Some("velox-embed-pipeline"),
+                layout: Some(&pl_layout),
+                vertex: wgpu::VertexState {
+                    module: &shader,
+                    entry_point: "vs",
+                    buffers: &[vlayout],
+                },
+                fragment: Some(wgpu::FragmentState {
+                    module: &shader,
+                    entry_point: "fs",
+                    targets: &[Some(wgpu::ColorTargetState {
+                        format,
+                        blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                        write_mask: wgpu::ColorWrites::ALL,
+                    })],
+                }),
+                primitive: wgpu::PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: wgpu::MultisampleState::default(),
+                multiview: None,
+            });
+            let mut fonts: Vec<ab_glyph::FontArc> = Vec::new();
+            fonts.extend(crate::load_system_font());
+            fonts.extend(
+                ab_glyph::FontArc::try_from_slice(include_bytes!("../assets/DejaVuSans.ttf")).ok(),
+            );
+            fonts.extend(
+                ab_glyph::FontArc::try_from_slice(include_bytes!("../assets/NotoSans-Regular.ttf"))
+                    .ok(),
+            );
+            let filter = if crate::config::text_quality().antialias
+                == crate::config::TextAntialias::Aliased
+            {
+                wgpu::FilterMode::Nearest
+            } else {
+                wgpu::FilterMode::Linear
+            };
+            let glyph = (!fonts.is_empty()).then(|| {
+                let brush = wgpu_glyph::GlyphBrushBuilder::using_fonts(fonts)
+                    .texture_filter_method(filter)
+                    .build(&device, format);
+                (brush, wgpu::util::StagingBelt::new(1024))
+            });
+            Gpu {
+                device,
+                queue,
+                pipeline,
+                glyph,
+                in_flight: false,
+            }
+        }
+
+        /// Reclaim the staging belt once the host has submitted the last frame.
+        pub(super) fn recall(&mut self) {
+            if let Some((_, belt)) = &mut self.glyph
+                && std::mem::take(&mut self.in_flight)
+            {
+                belt.recall();
+            }
+        }
+
+        pub(super) fn draw(
+            &mut self,
+            encoder: &mut wgpu::CommandEncoder,
+            target: &wgpu::TextureView,
+            mesh: &Mesh,
+            width: u32,
+            height: u32,
+        ) {
+            if !mesh.vertices.is_empty() {
+                let bytes = mesh.vertices.len() * std::mem::size_of::<Vertex>();
+                crate::stats::record_gpu_buffers(bytes);
+                let buf = self.device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("velox-embed-quads"),
+                    size: bytes as u64,
+                    usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                self.queue
+                    .write_buffer(&buf, 0, bytemuck::cast_slice(&mesh.vertices));
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                    label: Some("velox-embed-pass"),
+                    color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                        view: target,
+                        resolve_target: None,
+                        ops: wgpu::Operations {
+                            load: wgpu::LoadOp::Load,
+                            store: true,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                });
+                rpass.set_pipeline(&self.pipeline);
+                rpass.set_vertex_buffer(0, buf.slice(..));
+                rpass.draw(0..mesh.vertices.len() as u32, 0..1);
+            }
+            let Some((brush, belt)) = &mut self.glyph else {
+                return;
+            };
+            if mesh.text.is_empty() {
+                return;
+            }
+            use wgpu_glyph::{FontId, Layout, Section, Text, VerticalAlign};
+            let hinted = crate::config::text_quality().hinting != crate::config::Hinting::None;
+            for run in &mesh.text {
+                let font_id = match run.family.map(str::to_ascii_lowercase) {
+                    Some(f) if f.contains("dejavu") => 1,
+                    Some(f) if f.contains("noto") => 2,
+                    _ => 0,
+                };
+                let (x, top) = if hinted {
+                    (run.x.round(), run.top.round())
+                } else {
+                    (run.x, run.top)
+                };
+                let bounds = match run.clip {
+                    Some(c) => ((c.right() - x).max(0.0), (c.bottom() - top).max(0.0)),
+                    None => (f32::INFINITY, f32::INFINITY),
+                };
+                let offsets: &[(f32, f32)] = if run.bold {
+                    &[(0.0, 0.0), (0.6, 0.0), (0.0, 0.6)]
+                } else {
+                    &[(0.0, 0.0)]
+                };
+                for (ox, oy) in offsets {
+                    brush.queue(Section {
+                        screen_position: (x + ox, top + oy),
+                        bounds,
+                        layout: Layout::default_single_line().v_align(VerticalAlign::Top),
+                        text: vec![
+                            Text::new(run.text)
+                                .with_color(run.color)
+                                .with_scale(run.size)
+                                .with_font_id(FontId(font_id)),
+                        ],
+                    });
+                }
+            }
+            if let Err(e) = brush.draw_queued(&self.device, belt, encoder, target, width, height) {
+                log::error!(target: "velox::renderer::wgpu", "overlay text: {}", e);
+            }
+            belt.finish();
+            self.in_flight = true;
+        }
+    }
+}
@@ -0,0 +1,433 @@
+//! Enter/leave transitions for conditional content.
+//!
+//! Elements marked `data-transition="<name>"` (what `<transition name="...">` compiles to
+//! in a `.vx` template) animate when they appear in or disappear from the view, as with
+//! Vue's `<transition>`: an entering element has the class `<name>-enter-active` and
+//! animates from the `.<name>-enter-from` rule, and a leaving one stays in place as its
+//! last rendered copy with `<name>-leave-active`, animating to `.<name>-leave-to` before
+//! it is removed. Timing comes from the `transition` declaration on the active class.
+//!
+//! Numbers with units, `#hex` colors, and `transform` functions are interpolated; other
+//! values switch halfway. Content already on the first frame does not animate in.
+//! Elements are matched across frames by `key`, so give `v-if`/`v-else` branches
+//! distinct keys to cross-fade them.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use velox_dom::VNode;
+use velox_style::{SimpleSelectorKind, Stylesheet};
+
+use crate::animation::{parse_transition_list, push_segment, transition_decl};
+use crate::display_list::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Phase {
+    Shown,
+    Enter,
+    Leave,
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    name: String,
+    phase: Phase,
+    start: Instant,
+    parent: String,
+    index: usize,
+    /// Last raw (unstyled) copy, reinserted while leaving.
+    node: VNode,
+    frame: u64,
+    done: bool,
+}
+
+/// Tracks `data-transition` elements across frames. Call `prepare` on the raw view before
+/// the cascade and `apply` on the styled tree after it.
+#[derive(Debug, Default)]
+pub struct EnterLeave {
+    entries: HashMap<String, Entry>,
+    frame: u64,
+    running: bool,
+}
+
+impl EnterLeave {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start enter transitions for newly shown elements, start leave transitions for
+    /// removed ones, and reinsert elements that are still leaving. Adds the
+    /// `<name>-enter-active` / `<name>-leave-active` classes.
+    pub fn prepare(&mut self, vnode: &mut VNode, now: Instant) {
+        self.frame += 1;
+        let mut path = String::new();
+        self.mark(vnode, &mut path, now);
+
+        let frame = self.frame;
+        self.entries
+            .retain(|_, e| e.frame == frame || !(e.phase == Phase::Leave && e.done));
+        for entry in self
+            .entries
+            .values_mut()
+            .filter(|e| e.frame != frame && e.phase != Phase::Leave)
+        {
+            entry.phase = Phase::Leave;
+            entry.start = now;
+            entry.done = false;
+        }
+
+        let mut leaving: Vec<(String, Entry)> = self
+            .entries
+            .iter()
+            .filter(|(_, e)| e.phase == Phase::Leave)
+            .map(|(p, e)| (p.clone(), e.clone()))
+            .collect();
+        leaving.sort_by(|a, b| (&a.1.parent, a.1.index).cmp(&(&b.1.parent, b.1.index)));
+        for (path, entry) in &leaving {
+            // Leaving descendants of a leaving element go out with their ancestor's copy.
+            let nested = leaving
+                .iter()
+                .any(|(other, _)| other != path && path.starts_with(&format!("{}/", other)));
+            if nested || path.is_empty() {
+                self.entries.remove(path);
+                continue;
+            }
+            let Some(VNode::Element { children, .. }) = find_mut(vnode, &entry.parent) else {
+                self.entries.remove(path);
+                continue;
+            };
+            let node = with_class(entry.node.clone(), &format!("{}-leave-active", entry.name));
+            let index = entry.index.min(children.len());
+            // `v-if` leaves an empty text node in the slot of a hidden element.
+            match children.get(index) {
+                Some(VNode::Text(t)) if t.is_empty() => children[index] = node,
+                _ => children.insert(index, node),
+            }
+        }
+    }
+
+    fn mark(&mut self, vnode: &mut VNode, path: &mut String, now: Instant) {
+        let (VNode::Element { children, .. } | VNode::Fragment(children)) = vnode else {
+            return;
+        };
+        let parent = path.clone();
+        for (i, child) in children.iter_mut().enumerate() {
+            let len = path.len();
+            push_segment(path, child, i);
+            if let VNode::Element { props, .. } = child
+                && let Some(name) = props.str("data-transition")
+            {
+                let name = if name.is_empty() {
+                    "v".to_string()
+                } else {
+                    name.to_string()
+                };
+                let first = self.frame == 1;
+                let entry = self.entries.entry(path.clone()).or_insert_with(|| Entry {
+                    name: name.clone(),
+                    phase: if first { Phase::Shown } else { Phase::Enter },
+                    start: now,
+                    parent: parent.clone(),
+                    index: i,
+                    node: VNode::Text(String::new()),
+                    frame: 0,
+                    done: false,
+                });
+                if entry.phase == Phase::Leave {
+                    // Shown again before it finished leaving.
+                    entry.phase = Phase::Enter;
+                    entry.start = now;
+                    entry.done = false;
+                }
+                entry.name = name;
+                entry.node = child.clone();
+                entry.frame = self.frame;
+                (entry.parent, entry.index) = (parent.clone(), i);
+                if entry.phase == Phase::Enter {
+                    let class = format!("{}-enter-active", entry.name);
+                    *child =
+                        with_class(std::mem::replace(child, VNode::Text(String::new())), &class);
+                }
+            }
+            self.mark(child, path, now);
+            path.truncate(len);
+        }
+    }
+
+    /// Interpolate the styles of entering and leaving elements in the styled tree.
+    /// Returns true while a transition is still running, i.e. another frame is needed.
+    pub fn apply(&mut self, styled: &mut VNode, sheet: &Stylesheet, now: Instant) -> bool {
+        let mut path = String::new();
+        self.running = false;
+        self.style(styled, sheet, &mut path, now);
+        self.running
+    }
+
+    /// Whether the last `apply` left a transition running.
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+
+    /// Elements whose transition state is kept.
+    pub fn tracked(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn style(&mut self, vnode: &mut VNode, sheet: &Stylesheet, path: &mut String, now: Instant) {
+        let (props, children) = match vnode {
+            VNode::Element {
+                props, children, ..
+            } => (Some(props), children),
+            VNode::Fragment(children) => (None, children),
+            VNode::Text(_) | VNode::Component { .. } => return,
+        };
+        if let Some(props) = props
+            && let Some(entry) = self.entries.get_mut(path.as_str())
+            && matches!(entry.phase, Phase::Enter | Phase::Leave)
+            && !entry.done
+        {
+            let style = props.str("style").unwrap_or("");
+            let timings = transition_decl(style)
+                .map(parse_transition_list)
+                .unwrap_or_default();
+            let elapsed = now.saturating_duration_since(entry.start);
+            let mut computed: Vec<(String, String)> = decls(style)
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+            let (edge, entering) = match entry.phase {
+                Phase::Enter => (
+                    class_decls(sheet, &format!("{}-enter-from", entry.name)),
+                    true,
+                ),
+                _ => (
+                    class_decls(sheet, &format!("{}-leave-to", entry.name)),
+                    false,
+                ),
+            };
+            for (prop, edge_value) in edge {
+                let current = computed
+                    .iter()
+                    .find(|(k, _)| *k == prop)
+                    .map(|(_, v)| v.clone())
+                    .or_else(|| velox_style::properties::initial_value(&prop).map(str::to_string))
+                    .unwrap_or_default();
+                let timing = timings
+                    .iter()
+                    .find(|(p, _)| *p == prop || p == "all")
+                    .map(|(_, t)| *t);
+                let t = timing.and_then(|t| t.progress(elapsed)).unwrap_or(1.0);
+                let value = if entering {
+                    interpolate(&edge_value, &current, t)
+                } else {
+                    interpolate(&current, &edge_value, t)
+                };
+                match computed.iter_mut().find(|(k, _)| *k == prop) {
+                    Some(slot) => slot.1 = value,
+                    None => computed.push((prop, value)),
+                }
+            }
+            computed.sort();
+            let serialized: Vec<String> = computed
+                .iter()
+                .map(|(k, v)| format!("{}: {};", k, v))
+                .collect();
+            props.insert("style", serialized.join(" "));
+
+            let total = timings
+                .iter()
+                .map(|(_, t)| t.delay + t.duration)
+                .max()
+                .unwrap_or(Duration::ZERO);
+            if elapsed >= total {
+                entry.done = true;
+                if entry.phase == Phase::Enter {
+                    entry.phase = Phase::Shown;
+                }
+            } else {
+                self.running = true;
+            }
+        }
+        for (i, child) in children.iter_mut().enumerate() {
+            let len = path.len();
+            push_segment(path, child, i);
+            self.style(child, sheet, path, now);
+            path.truncate(len);
+        }
+    }
+}
+
+fn with_class(node: VNode, class: &str) -> VNode {
+    match node {
+        VNode::Element {
+            tag,
+            mut props,
+            children,
+        } => {
+            let classes = match props.str("class") {
+                Some(existing) if !existing.trim().is_empty() => format!("{} {}", existing, class),
+                _ => class.to_string(),
+            };
+            props.insert("class", classes);
+            VNode::Element {
+                tag,
+                props,
+                children,
+            }
+        }
+        text => text,
+    }
+}
+
+fn find_mut<'a>(vnode: &'a mut VNode, path: &str) -> Option<&'a mut VNode> {
+    let mut node = vnode;
+    for segment in path.split('/').skip(1) {
+        let (VNode::Element { children, .. } | VNode::Fragment(children)) = node else {
+            return None;
+        };
+        let index = match segment.strip_prefix("k:") {
+            Some(key) => children.iter().position(
+                |c| matches!(c, VNode::Element { props, .. } if props.str("key") == Some(key)),
+            )?,
+            None => segment.parse().ok()?,
+        };
+        node = children.get_mut(index)?;
+    }
+    Some(node)
+}
+
+fn decls(style: &str) -> impl Iterator<Item = (&str, &str)> {
+    style
+        .split(';')
+        .filter_map(|d| d.split_once(':'))
+        .map(|(k, v)| (k.trim(), v.trim()))
+}
+
+/// Declarations of plain `.class` rules (no tag, hover, or ancestors) for `class`.
+fn class_decls(sheet: &Stylesheet, class: &str) -> Vec<(String, String)> {
+    let mut out: Vec<(String, String)> = Vec::new();
+    for rule in &sheet.rules {
+        let sel = &rule.selector;
+        if sel.kind != SimpleSelectorKind::Class
+            || sel.class != class
+            || sel.hover
+            || !rule.ancestors.is_empty()
+        {
+            continue;
+        }
+        for (k, v) in rule.decls.iter().chain(&rule.important) {
+            match out.iter_mut().find(|(name, _)| name == k) {
+                Some(slot) => slot.1 = v.clone(),
+                None => out.push((k.clone(), v.clone())),
+            }
+        }
+    }
+    out
+}
+
+/// Blend two CSS values at `t` in `0..=1`.
+pub fn interpolate(from: &str, to: &str, t: f32) -> String {
+    if t <= 0.0 {
+        return from.to_string();
+    }
+    if t >= 1.0 {
+        return to.to_string();
+    }
+    if let (Some(a), Some(b)) = (Color::parse(from), Color::parse(to)) {
+        let mix = |x: u8, y: u8| (x as f32 + (y as f32 - x as f32) * t).round() as u8;
+        return format!(
+            "#{:02x}{:02x}{:02x}{:02x}",
+            mix(a.r, b.r),
+            mix(a.g, b.g),
+            mix(a.b, b.b),
+            mix(a.a, b.a)
+        );
+    }
+    // `transform: none` blends with the identity version of the other side.
+    let (from, to) = match (from.trim(), to.trim()) {
+        ("none", other) => (identity_transform(other), other.to_string()),
+        (other, "none") => (other.to_string(), identity_transform(other)),
+        (a, b) => (a.to_string(), b.to_string()),
+    };
+    let (skel_a, nums_a) = split_numbers(&from);
+    let (skel_b, nums_b) = split_numbers(&to);
+    if skel_a != skel_b || nums_a.is_empty() {
+        return if t < 0.5 { from } else { to };
+    }
+    let mut out = String::new();
+    for (i, part) in skel_a.iter().enumerate() {
+        out.push_str(part);
+        if let (Some(a), Some(b)) = (nums_a.get(i), nums_b.get(i)) {
+            out.push_str(&format_number(a + (b - a) * t));
+        }
+    }
+    out
+}
+
+/// Up to three decimals, without trailing zeros.
+fn format_number(v: f32) -> String {
+    let v = if v.abs() < 0.0005 { 0.0 } else { v };
+    let text = format!("{:.3}", v);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Split `translate(-4px, 2.5px)` into `["translate(", "px, ", "px)"]` and `[-4, 2.5]`.
+fn split_numbers(value: &str) -> (Vec<String>, Vec<f32>) {
+    let (mut skeleton, mut numbers) = (vec![String::new()], Vec::new());
+    let chars: Vec<char> = value.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let prev_ident = i > 0 && (chars[i - 1].is_ascii_alphanumeric() || chars[i - 1] == '_');
+        let starts_number = !prev_ident
+            && (c.is_ascii_digit()
+                || (matches!(c, '-' | '.')
+                    && chars
+                        .get(i + 1)
+                        .is_some_and(|n| n.is_ascii_digit() || *n == '.')));
+        if starts_number {
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            match text.parse::<f32>() {
+                Ok(n) => {
+                    numbers.push(n);
+                    skeleton.push(String::new());
+                }
+                Err(_) => skeleton.last_mut().unwrap().push_str(&text),
+            }
+            continue;
+        }
+        skeleton.last_mut().unwrap().push(c);
+        i += 1;
+    }
+    (skeleton, numbers)
+}
+
+/// `translateY(12px) scale(0.9)` -> `translateY(0px) scale(1)`.
+fn identity_transform(value: &str) -> String {
+    let mut out = String::new();
+    for part in value.split_inclusive(')') {
+        let Some((name, args)) = part.split_once('(') else {
+            out.push_str(part);
+            continue;
+        };
+        let neutral = if name.trim().starts_with("scale") {
+            1.0
+        } else {
+            0.0
+        };
+        let (skeleton, numbers) = split_numbers(args);
+        out.push_str(name);
+        out.push('(');
+        for (i, piece) in skeleton.iter().enumerate() {
+            out.push_str(piece);
+            if i < numbers.len() {
+                out.push_str(&format!("{}", neutral));
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,81 @@
+//! Error type for the renderer's fallible APIs.
+//!
+//! Backend setup, offscreen rendering, presentation, recording, and frame streaming all
+//! return `Result<_, Error>`; window runners return it for failures that happen before the
+//! event loop starts instead of panicking.
+
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    /// No GPU adapter is compatible with the window surface.
+    AdapterNotFound,
+    /// The adapter refused to create a device.
+    Device(String),
+    /// The OS window could not be created.
+    Window(String),
+    /// A window, GPU, or offscreen surface could not be created.
+    Surface(String),
+    /// The surface stopped accepting frames (display lost, context reset).
+    SurfaceLost,
+    /// EGL/GL context or Skia `DirectContext` setup failed.
+    GlContext(String),
+    /// No usable font could be loaded.
+    FontLoad(String),
+    /// Image bytes could not be decoded.
+    ImageDecode(String),
+    /// A surface or frame with unusable dimensions.
+    InvalidSize {
+        width: u32,
+        height: u32,
+    },
+    /// Copying a finished frame to the window failed.
+    Present(String),
+    /// PNG, GIF, or video encoding failed.
+    Encode(String),
+    /// A display-list frame or `ipc` message could not be serialized or parsed.
+    Serialize(String),
+    /// An interaction script could not be parsed, or no longer matches the app
+    /// (`replay`).
+    Replay(String),
+    /// The backend was not compiled into this build.
+    Unsupported(&'static str),
+    Io(std::io::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::AdapterNotFound => write!(f, "no suitable GPU adapter found"),
+            Error::Device(e) => write!(f, "failed to create GPU device: {}", e),
+            Error::Window(e) => write!(f, "failed to create window: {}", e),
+            Error::Surface(e) => write!(f, "failed to create surface: {}", e),
+            Error::SurfaceLost => write!(f, "surface lost"),
+            Error::GlContext(e) => write!(f, "GL context error: {}", e),
+            Error::FontLoad(e) => write!(f, "failed to load font: {}", e),
+            Error::ImageDecode(e) => write!(f, "failed to decode image: {}", e),
+            Error::InvalidSize { width, height } => write!(f, "invalid size {}x{}", width, height),
+            Error::Present(e) => write!(f, "present failed: {}", e),
+            Error::Encode(e) => write!(f, "encode failed: {}", e),
+            Error::Serialize(e) => write!(f, "frame (de)serialization failed: {}", e),
+            Error::Replay(e) => write!(f, "replay failed: {}", e),
+            Error::Unsupported(what) => write!(f, "{} is not enabled in this build", what),
+            Error::Io(e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use velox_dom::VNode;
+use velox_dom::diff::Patch;
 
 use crate::RenderTree;
 
@@ -21,6 +22,16 @@ impl EventRegistry {
     pub fn has(&self, name: &str) -> bool {
         self.handlers.contains_key(name)
     }
+    /// Call the handler registered as `name`. Returns false if there is none.
+    pub fn emit(&mut self, name: &str) -> bool {
+        match self.handlers.get_mut(name) {
+            Some(cb) => {
+                cb();
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -37,32 +48,182 @@ pub struct HoverTarget {
 }
 
 pub fn is_hoverable(tag: &str, props: &velox_dom::Props) -> bool {
-    if props.attrs.contains_key("on:click") || tag == "button" {
+    if props.contains("on:click") || tag == "button" {
         return true;
     }
     props
-        .attrs
-        .get("class")
+        .str("class")
         .map(|s| s.split_whitespace().any(|c| c == "btn"))
         .unwrap_or(false)
 }
 
+/// Collect the clickable rects of a laid-out tree. A `<button type="submit">` without its
+/// own `on:click` dispatches the `on:submit` handler of the `<form>` around it.
 pub fn collect_click_targets(
     vnode: &VNode,
     layout: &velox_dom::layout::LayoutNode,
     out: &mut Vec<ClickTarget>,
 ) {
-    match vnode {
-        VNode::Text(_) => {}
-        VNode::Element { props, children, .. } => {
-            if let Some(handler) = props.attrs.get("on:click").cloned() {
-                let payload = props.attrs.get("on:click-payload").cloned();
-                out.push(ClickTarget { rect: layout.rect, handler, payload });
-            }
-            for (child, child_layout) in children.iter().zip(&layout.children) {
-                collect_click_targets(child, child_layout, out);
+    out.extend(click_targets(&HandlerTable::from_tree(vnode), vnode, layout));
+}
+
+/// The click targets of `handlers`, the table of `vnode`, in document order: its `click`
+/// nodes, and the submit buttons of its `submit` forms.
+pub fn click_targets(
+    handlers: &HandlerTable,
+    vnode: &VNode,
+    layout: &velox_dom::layout::LayoutNode,
+) -> Vec<ClickTarget> {
+    let mut by_node: BTreeMap<Vec<usize>, ClickTarget> =
+        handler_targets(handlers, "click", Some("on:click-payload"), vnode, layout)
+        .into_iter()
+        .collect();
+    for (form, handler) in handlers.nodes("submit") {
+        let Some((node, node_layout)) = node_at(vnode, layout, form) else { continue };
+        collect_submit_buttons(node, node_layout, &mut form.to_vec(), handler, &mut by_node);
+    }
+    by_node.into_values().collect()
+}
+
+/// The submit buttons without an `on:click` under the form `node` at key `path`; a form
+/// inside it with an `on:submit` of its own has its own entry.
+fn collect_submit_buttons(
+    node: &VNode,
+    layout: &velox_dom::layout::LayoutNode,
+    path: &mut Vec<usize>,
+    handler: &str,
+    out: &mut BTreeMap<Vec<usize>, ClickTarget>,
+) {
+    for (i, (child, child_layout)) in node.children().iter().zip(&layout.children).enumerate() {
+        path.push(i);
+        match child {
+            VNode::Element { tag, props, .. } if tag == "form" && props.contains("on:submit") => {}
+            VNode::Element { tag, props, .. }
+                if tag == "button" && !props.contains("on:click") && props.str("type") == Some("submit") =>
+            {
+                let target = ClickTarget { rect: child_layout.rect, handler: handler.to_string(), payload: None };
+                out.insert(path.clone(), target);
             }
+            _ => collect_submit_buttons(child, child_layout, path, handler, out),
         }
+        path.pop();
+    }
+}
+
+/// The targets of the `event` handlers in `handlers`, each with the node's rect in
+/// `layout` and the value of its `payload` attribute, keyed by node.
+fn handler_targets(
+    handlers: &HandlerTable,
+    event: &str,
+    payload: Option<&str>,
+    vnode: &VNode,
+    layout: &velox_dom::layout::LayoutNode,
+) -> Vec<(Vec<usize>, ClickTarget)> {
+    handlers
+        .nodes(event)
+        .filter_map(|(key, handler)| {
+            let (node, node_layout) = node_at(vnode, layout, key)?;
+            let payload = match (node, payload) {
+                (VNode::Element { props, .. }, Some(attr)) => props.str(attr).map(str::to_string),
+                _ => None,
+            };
+            Some((key.to_vec(), ClickTarget { rect: node_layout.rect, handler: handler.to_string(), payload }))
+        })
+        .collect()
+}
+
+/// The node at `key` in `vnode`, with its layout.
+fn node_at<'a>(
+    vnode: &'a VNode,
+    layout: &'a velox_dom::layout::LayoutNode,
+    key: &[usize],
+) -> Option<(&'a VNode, &'a velox_dom::layout::LayoutNode)> {
+    key.iter().try_fold((vnode, layout), |(node, layout), &i| {
+        Some((node.children().get(i)?, layout.children.get(i)?))
+    })
+}
+
+/// Collect the rects of elements with an `on:click-outside` handler (`v-click-outside` in a
+/// template). Dropdowns, popovers and modals use it to close when a press lands anywhere
+/// else; put it on the element holding both the panel and the button that opens it, or
+/// pressing the button closes the panel and reopens it.
+pub fn collect_click_outside_targets(
+    vnode: &VNode,
+    layout: &velox_dom::layout::LayoutNode,
+    out: &mut Vec<ClickTarget>,
+) {
+    if let VNode::Element { props, .. } = vnode
+        && let Some(handler) = props.str("on:click-outside").map(str::to_string)
+    {
+        let payload = props.str("on:click-outside-payload").map(str::to_string);
+        out.push(ClickTarget { rect: layout.rect, handler, payload });
+    }
+    for (child, child_layout) in vnode.children().iter().zip(&layout.children) {
+        collect_click_outside_targets(child, child_layout, out);
+    }
+}
+
+/// Collect the rects of elements with an `on:pen` handler: surfaces that take pen, stylus
+/// and touch strokes (see `pen`).
+pub fn collect_pen_targets(vnode: &VNode, layout: &velox_dom::layout::LayoutNode, out: &mut Vec<ClickTarget>) {
+    if let VNode::Element { props, .. } = vnode
+        && let Some(handler) = props.str("on:pen").map(str::to_string)
+    {
+        // The handler gets the sample as its payload (`pen::PenSample::payload`).
+        out.push(ClickTarget { rect: layout.rect, handler, payload: None });
+    }
+    for (child, child_layout) in vnode.children().iter().zip(&layout.children) {
+        collect_pen_targets(child, child_layout, out);
+    }
+}
+
+/// Collect the rects of elements with `on:zoom` and `on:rotate` handlers: what pinch and
+/// rotate gestures go to (see `gesture`).
+pub fn collect_gesture_targets(
+    vnode: &VNode,
+    layout: &velox_dom::layout::LayoutNode,
+    zoom: &mut Vec<ClickTarget>,
+    rotate: &mut Vec<ClickTarget>,
+) {
+    if let VNode::Element { props, .. } = vnode {
+        if let Some(handler) = props.str("on:zoom").map(str::to_string) {
+            zoom.push(ClickTarget { rect: layout.rect, handler, payload: None });
+        }
+        if let Some(handler) = props.str("on:rotate").map(str::to_string) {
+            rotate.push(ClickTarget { rect: layout.rect, handler, payload: None });
+        }
+    }
+    for (child, child_layout) in vnode.children().iter().zip(&layout.children) {
+        collect_gesture_targets(child, child_layout, zoom, rotate);
+    }
+}
+
+/// Collect the rects of elements with an `on:pan` handler: what drags and the mouse wheel
+/// move the content of (see `viewport`). An `on:pan-payload` is sent along as the `id`.
+pub fn collect_pan_targets(vnode: &VNode, layout: &velox_dom::layout::LayoutNode, out: &mut Vec<ClickTarget>) {
+    if let VNode::Element { props, .. } = vnode
+        && let Some(handler) = props.str("on:pan").map(str::to_string)
+    {
+        let payload = props.str("on:pan-payload").map(str::to_string);
+        out.push(ClickTarget { rect: layout.rect, handler, payload });
+    }
+    for (child, child_layout) in vnode.children().iter().zip(&layout.children) {
+        collect_pan_targets(child, child_layout, out);
+    }
+}
+
+/// Collect the rects of `on:pan` elements with an `on:pan-end` handler, told where a drag
+/// on them ended. The `on:pan-payload` is sent along as the `id`.
+pub fn collect_pan_end_targets(vnode: &VNode, layout: &velox_dom::layout::LayoutNode, out: &mut Vec<ClickTarget>) {
+    if let VNode::Element { props, .. } = vnode
+        && props.contains("on:pan")
+        && let Some(handler) = props.str("on:pan-end").map(str::to_string)
+    {
+        let payload = props.str("on:pan-payload").map(str::to_string);
+        out.push(ClickTarget { rect: layout.rect, handler, payload });
+    }
+    for (child, child_layout) in vnode.children().iter().zip(&layout.children) {
+        collect_pan_end_targets(child, child_layout, out);
     }
 }
 
@@ -72,20 +233,21 @@ pub fn collect_hover_targets(
     out: &mut Vec<HoverTarget>,
 ) {
     match vnode {
-        VNode::Text(_) => {}
+        VNode::Text(_) | VNode::Component { .. } => {}
         VNode::Element { tag, props, children, .. } => {
             if is_hoverable(tag, props) {
-                let id = props
-                    .attrs
-                    .get("data-hover-id")
-                    .and_then(|v| v.parse::<u32>().ok())
-                    .unwrap_or(0);
+                let id = props.num("data-hover-id").map_or(0, |id| id as u32);
                 out.push(HoverTarget { rect: layout.rect, id });
             }
             for (child, child_layout) in children.iter().zip(&layout.children) {
                 collect_hover_targets(child, child_layout, out);
             }
         }
+        VNode::Fragment(children) => {
+            for (child, child_layout) in children.iter().zip(&layout.children) {
+                collect_hover_targets(child, child_layout, out);
+            }
+        }
     }
 }
 
@@ -95,55 +257,425 @@ pub fn hit_test_click<'a>(
     y: f32,
 ) -> Option<(&'a str, Option<&'a str>)> {
     for target in targets {
-        let r = target.rect;
-        let x0 = r.x as f32;
-        let y0 = r.y as f32;
-        let x1 = (r.x + r.w) as f32;
-        let y1 = (r.y + r.h) as f32;
-        if x >= x0 && x <= x1 && y >= y0 && y <= y1 {
+        if contains(target.rect, x, y) {
             return Some((target.handler.as_str(), target.payload.as_deref()));
         }
     }
     None
 }
 
+/// The click-outside handlers a press at `(x, y)` dispatches: every target whose rect
+/// doesn't contain the point, in document order. Runners dispatch them before the click
+/// target under the press.
+pub fn hit_test_click_outside(targets: &[ClickTarget], x: f32, y: f32) -> Vec<(&str, Option<&str>)> {
+    targets
+        .iter()
+        .filter(|t| !contains(t.rect, x, y))
+        .map(|t| (t.handler.as_str(), t.payload.as_deref()))
+        .collect()
+}
+
+/// Dispatch a left press at `(x, y)`, in content coordinates: click-outside handlers
+/// first, then the click target under the press. Handlers without a payload of their
+/// own get `position`, the press in viewport coordinates, and the id of the `pointer`
+/// that pressed, as `{"x":..,"y":..,"pointer":..}`. Returns whether any handler ran, and
+/// the click handler that did.
+pub(crate) fn dispatch_press(
+    targets: &Targets,
+    (x, y): (f32, f32),
+    position: (f32, f32),
+    pointer: u64,
+    on_event: &mut dyn FnMut(&str, Option<&str>),
+) -> (bool, Option<String>) {
+    let position = crate::pointer::position_payload(pointer, position.0, position.1);
+    let mut handled = false;
+    for (handler, payload) in hit_test_click_outside(&targets.outside, x, y) {
+        on_event(handler, Some(payload.unwrap_or(&position)));
+        handled = true;
+    }
+    let target = targets.click_at(x, y).map(|(handler, payload)| {
+        on_event(handler, Some(payload.unwrap_or(&position)));
+        handler.to_string()
+    });
+    (handled || target.is_some(), target)
+}
+
 pub fn hit_test_hover(targets: &[HoverTarget], x: f32, y: f32) -> Option<u32> {
     for target in targets {
-        let r = target.rect;
-        let x0 = r.x as f32;
-        let y0 = r.y as f32;
-        let x1 = (r.x + r.w) as f32;
-        let y1 = (r.y + r.h) as f32;
-        if x >= x0 && x <= x1 && y >= y0 && y <= y1 {
+        if contains(target.rect, x, y) {
             return Some(target.id);
         }
     }
     None
 }
 
-/// Dispatches an event by scanning the VNode tree for props of the form
-/// `on:<event>` and invoking registered callbacks with the string value.
-/// Returns the number of callbacks invoked.
-pub fn dispatch(event: &str, tree: &RenderTree, registry: &mut EventRegistry) -> usize {
-    let mut invoked = 0;
-    let key = format!("on:{}", event);
-    fn walk(node: &VNode, key: &str, out: &mut Vec<String>) {
-        match node {
-            VNode::Text(_) => {}
-            VNode::Element { props, children, .. } => {
-                if let Some(v) = props.attrs.get(key) {
-                    out.push(v.clone());
+fn contains(r: velox_dom::layout::Rect, x: f32, y: f32) -> bool {
+    x >= r.x as f32 && x <= (r.x + r.w) as f32 && y >= r.y as f32 && y <= (r.y + r.h) as f32
+}
+
+/// Side of a `HitGrid` cell, in logical pixels.
+const GRID_CELL: i32 = 64;
+
+/// A uniform grid over a list of rects, so a hit test only checks the rects overlapping
+/// the cell under the point instead of the whole list. Like the linear `hit_test_*`
+/// functions, the first rect in list order containing the point wins.
+///
+/// `sync` updates the grid for a new list of rects, moving only the rects that changed,
+/// so a frame that patches a few nodes re-indexes a few targets.
+#[derive(Debug, Clone, Default)]
+pub struct HitGrid {
+    rects: Vec<velox_dom::layout::Rect>,
+    /// Indices into `rects`, ascending, of the rects overlapping each cell.
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl HitGrid {
+    pub fn new(rects: &[velox_dom::layout::Rect]) -> Self {
+        let mut grid = HitGrid::default();
+        grid.sync(rects);
+        grid
+    }
+
+    /// Index `rects` instead of the current list. Returns how many entries moved.
+    pub fn sync(&mut self, rects: &[velox_dom::layout::Rect]) -> usize {
+        let mut moved = 0;
+        for i in rects.len()..self.rects.len() {
+            self.place(i, self.rects[i], false);
+            moved += 1;
+        }
+        self.rects.truncate(rects.len());
+        for (i, &rect) in rects.iter().enumerate() {
+            match self.rects.get(i) {
+                Some(&old) if old == rect => continue,
+                Some(&old) => {
+                    self.place(i, old, false);
+                    self.rects[i] = rect;
+                }
+                None => self.rects.push(rect),
+            }
+            self.place(i, rect, true);
+            moved += 1;
+        }
+        moved
+    }
+
+    /// The index of the first rect containing `(x, y)`.
+    pub fn hit(&self, x: f32, y: f32) -> Option<usize> {
+        let cell = ((x.floor() as i32).div_euclid(GRID_CELL), (y.floor() as i32).div_euclid(GRID_CELL));
+        self.cells.get(&cell)?.iter().copied().find(|&i| contains(self.rects[i], x, y))
+    }
+
+    pub fn len(&self) -> usize {
+        self.rects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Add index `i` to, or remove it from, the cells `rect` overlaps.
+    fn place(&mut self, i: usize, rect: velox_dom::layout::Rect, add: bool) {
+        if rect.w < 0 || rect.h < 0 {
+            return;
+        }
+        let span = |start: i32, len: i32| start.div_euclid(GRID_CELL)..=(start + len).div_euclid(GRID_CELL);
+        for cx in span(rect.x, rect.w) {
+            for cy in span(rect.y, rect.h) {
+                let cell = self.cells.entry((cx, cy)).or_default();
+                match (cell.binary_search(&i), add) {
+                    (Err(at), true) => cell.insert(at, i),
+                    (Ok(at), false) => {
+                        cell.remove(at);
+                        if cell.is_empty() {
+                            self.cells.remove(&(cx, cy));
+                        }
+                    }
+                    _ => {}
                 }
-                for c in children {
-                    walk(c, key, out);
+            }
+        }
+    }
+}
+
+/// The click, click-outside, hover, pen, gesture and pan targets of a laid-out frame, with
+/// grids over the click and hover rects for the hit tests run on every pointer move and
+/// press. Their rects are where the elements are drawn (`display_list::drawn_layout`).
+#[derive(Debug, Clone, Default)]
+pub struct Targets {
+    pub click: Vec<ClickTarget>,
+    pub outside: Vec<ClickTarget>,
+    pub hover: Vec<HoverTarget>,
+    pub pen: Vec<ClickTarget>,
+    pub zoom: Vec<ClickTarget>,
+    pub rotate: Vec<ClickTarget>,
+    pub pan: Vec<ClickTarget>,
+    pub pan_end: Vec<ClickTarget>,
+    click_grid: HitGrid,
+    hover_grid: HitGrid,
+}
+
+impl Targets {
+    /// Collect the targets of `vnode` laid out as `layout`, replacing the previous ones.
+    /// Handlers are looked up by node key in `handlers`, the table of `vnode` (see
+    /// `RenderTree::handlers`); only hover targets take a walk over the tree.
+    pub fn collect(&mut self, vnode: &VNode, layout: &velox_dom::layout::LayoutNode, handlers: &HandlerTable) {
+        let drawn = crate::display_list::drawn_layout(vnode, layout);
+        let layout = &drawn;
+        let targets = |event: &str, payload: Option<&str>| -> Vec<ClickTarget> {
+            handler_targets(handlers, event, payload, vnode, layout).into_iter().map(|(_, t)| t).collect()
+        };
+        self.click = click_targets(handlers, vnode, layout);
+        self.outside = targets("click-outside", Some("on:click-outside-payload"));
+        self.hover.clear();
+        collect_hover_targets(vnode, layout, &mut self.hover);
+        // Pen samples and gestures are the payload of these (`pen::PenSample::payload`).
+        self.pen = targets("pen", None);
+        self.zoom = targets("zoom", None);
+        self.rotate = targets("rotate", None);
+        // An `on:pan-payload` is sent along as the `id` of both.
+        self.pan = targets("pan", Some("on:pan-payload"));
+        self.pan_end = handler_targets(handlers, "pan-end", Some("on:pan-payload"), vnode, layout)
+            .into_iter()
+            .filter(|(key, _)| handlers.get(key, "pan").is_some())
+            .map(|(_, t)| t)
+            .collect();
+        self.click_grid.sync(&self.click.iter().map(|t| t.rect).collect::<Vec<_>>());
+        self.hover_grid.sync(&self.hover.iter().map(|t| t.rect).collect::<Vec<_>>());
+    }
+
+    /// `hit_test_click` over the click targets.
+    pub fn click_at(&self, x: f32, y: f32) -> Option<(&str, Option<&str>)> {
+        let target = &self.click[self.click_grid.hit(x, y)?];
+        Some((target.handler.as_str(), target.payload.as_deref()))
+    }
+
+    /// `hit_test_hover` over the hover targets.
+    pub fn hover_at(&self, x: f32, y: f32) -> Option<u32> {
+        Some(self.hover[self.hover_grid.hit(x, y)?].id)
+    }
+
+    /// The innermost `on:pen` element at `(x, y)`.
+    pub fn pen_at(&self, x: f32, y: f32) -> Option<&ClickTarget> {
+        self.pen.iter().rev().find(|t| contains(t.rect, x, y))
+    }
+
+    /// The innermost `on:zoom` element at `(x, y)`.
+    pub fn zoom_at(&self, x: f32, y: f32) -> Option<&ClickTarget> {
+        self.zoom.iter().rev().find(|t| contains(t.rect, x, y))
+    }
+
+    /// The innermost `on:rotate` element at `(x, y)`.
+    pub fn rotate_at(&self, x: f32, y: f32) -> Option<&ClickTarget> {
+        self.rotate.iter().rev().find(|t| contains(t.rect, x, y))
+    }
+
+    /// The innermost `on:pan` element at `(x, y)`.
+    pub fn pan_at(&self, x: f32, y: f32) -> Option<&ClickTarget> {
+        self.pan.iter().rev().find(|t| contains(t.rect, x, y))
+    }
+
+    /// The innermost `on:pan` element with an `on:pan-end` handler at `(x, y)`.
+    pub fn pan_end_at(&self, x: f32, y: f32) -> Option<&ClickTarget> {
+        self.pan_end.iter().rev().find(|t| contains(t.rect, x, y))
+    }
+}
+
+/// The event an `on:<event>` attribute handles; `on:<event>-payload` carries data, not a
+/// handler.
+fn event_of(attr: &str) -> Option<&str> {
+    attr.strip_prefix("on:").filter(|event| !event.ends_with("-payload"))
+}
+
+/// A `(node key, event, handler)` entry of a compiled component's `HANDLERS`.
+pub type StaticHandler<'a> = (&'a [usize], &'a str, &'a str);
+
+/// The handlers of a tree: for each node key and event, the id of the handler its
+/// `on:<event>` attribute names. A node key is the path of child indices from the root,
+/// as `diff` addresses nodes, so `[]` is the root and `[1, 0]` the first child of its
+/// second child.
+///
+/// `RenderTree` builds one when mounted and keeps it up to date with the patches of each
+/// update, so dispatching an event, or resolving the element a hit test found, reads the
+/// table instead of scanning the tree. A compiled component's `HANDLERS` (see
+/// `from_static`) lists the entries of its nodes whose position doesn't depend on data.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HandlerTable {
+    /// Event, then node key in document order.
+    events: HashMap<String, BTreeMap<Vec<usize>, String>>,
+}
+
+impl HandlerTable {
+    pub fn from_tree(root: &VNode) -> Self {
+        let mut table = HandlerTable::default();
+        table.insert_subtree(&mut Vec::new(), root);
+        table
+    }
+
+    /// A table from `(node key, event, handler)` entries, as in the `HANDLERS` constant
+    /// `velox-sfc` generates for a template.
+    pub fn from_static(entries: &[StaticHandler<'_>]) -> Self {
+        let mut table = HandlerTable::default();
+        for (node, event, handler) in entries {
+            table.set(node.to_vec(), event, handler);
+        }
+        table
+    }
+
+    /// The handler the node at `node` has for `event`, e.g. the element a click hit.
+    pub fn get(&self, node: &[usize], event: &str) -> Option<&str> {
+        self.events.get(event)?.get(node).map(String::as_str)
+    }
+
+    /// The handlers for `event`, in document order.
+    pub fn handlers<'a>(&'a self, event: &str) -> impl Iterator<Item = &'a str> + 'a {
+        self.nodes(event).map(|(_, handler)| handler)
+    }
+
+    /// The nodes with a handler for `event` and their handlers, in document order.
+    pub fn nodes<'a>(&'a self, event: &str) -> impl Iterator<Item = (&'a [usize], &'a str)> + 'a {
+        self.events
+            .get(event)
+            .into_iter()
+            .flat_map(|nodes| nodes.iter().map(|(node, handler)| (node.as_slice(), handler.as_str())))
+    }
+
+    /// The entries of a compiled component's `HANDLERS` this table lacks or has another
+    /// handler for. None for the table of a tree its `render` built, whatever data filled it.
+    pub fn missing_static<'a>(&self, entries: &[StaticHandler<'a>]) -> Vec<StaticHandler<'a>> {
+        entries.iter().copied().filter(|&(node, event, handler)| self.get(node, event) != Some(handler)).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Update the table for `patches`, the `diff` of the tree it was built from to a new
+    /// tree. Only the patched nodes, and the siblings an inserted, removed or moved child
+    /// shifts, are touched.
+    pub fn apply(&mut self, patches: &[Patch]) {
+        self.apply_at(&mut Vec::new(), patches);
+        self.events.retain(|_, nodes| !nodes.is_empty());
+    }
+
+    fn apply_at(&mut self, path: &mut Vec<usize>, patches: &[Patch]) {
+        for patch in patches {
+            match patch {
+                Patch::Replace(node) => {
+                    self.remove_subtree(path);
+                    self.insert_subtree(path, node);
+                }
+                Patch::SetAttr(attr, handler) => {
+                    if let Some(event) = event_of(attr)
+                        && let Some(handler) = handler.as_str()
+                    {
+                        self.set(path.clone(), event, handler);
+                    }
+                }
+                Patch::RemoveAttr(attr) => {
+                    if let Some(nodes) = event_of(attr).and_then(|event| self.events.get_mut(event)) {
+                        nodes.remove(path.as_slice());
+                    }
+                }
+                Patch::UpdateChild(i, patches) => {
+                    path.push(*i);
+                    self.apply_at(path, patches);
+                    path.pop();
+                }
+                Patch::InsertChild(i, node) => {
+                    self.shift_children(path, *i, true);
+                    path.push(*i);
+                    self.insert_subtree(path, node);
+                    path.pop();
+                }
+                Patch::RemoveChild(i) => {
+                    path.push(*i);
+                    self.remove_subtree(path);
+                    path.pop();
+                    self.shift_children(path, *i + 1, false);
+                }
+                Patch::MoveChild(from, to) => {
+                    path.push(*from);
+                    let moved = self.take_subtree(path);
+                    path.pop();
+                    self.shift_children(path, *from + 1, false);
+                    self.shift_children(path, *to, true);
+                    let depth = path.len();
+                    for (event, mut node, handler) in moved {
+                        node[depth] = *to;
+                        self.events.entry(event).or_default().insert(node, handler);
+                    }
                 }
             }
         }
     }
-    let mut targets = Vec::new();
-    walk(&tree.root, &key, &mut targets);
-    for name in targets {
-        if let Some(cb) = registry.handlers.get_mut(&name) {
+
+    fn set(&mut self, node: Vec<usize>, event: &str, handler: &str) {
+        self.events.entry(event.to_string()).or_default().insert(node, handler.to_string());
+    }
+
+    fn insert_subtree(&mut self, path: &mut Vec<usize>, node: &VNode) {
+        if let VNode::Element { props, .. } = node {
+            for (attr, handler) in &props.attrs {
+                if let Some(event) = event_of(attr)
+                    && let Some(handler) = handler.as_str()
+                {
+                    self.set(path.clone(), event, handler);
+                }
+            }
+        }
+        for (i, child) in node.children().iter().enumerate() {
+            path.push(i);
+            self.insert_subtree(path, child);
+            path.pop();
+        }
+    }
+
+    fn remove_subtree(&mut self, path: &[usize]) {
+        for nodes in self.events.values_mut() {
+            nodes.retain(|node, _| !node.starts_with(path));
+        }
+    }
+
+    /// Remove the entries of the subtree at `path`, returning them as `(event, node,
+    /// handler)`.
+    fn take_subtree(&mut self, path: &[usize]) -> Vec<(String, Vec<usize>, String)> {
+        let mut taken = Vec::new();
+        for (event, nodes) in &mut self.events {
+            let (moved, kept): (BTreeMap<_, _>, BTreeMap<_, _>) =
+                std::mem::take(nodes).into_iter().partition(|(node, _)| node.starts_with(path));
+            *nodes = kept;
+            taken.extend(moved.into_iter().map(|(node, handler)| (event.clone(), node, handler)));
+        }
+        taken
+    }
+
+    /// Move the children of `parent` from index `from` on one place later (`later`) or
+    /// earlier, with their subtrees.
+    fn shift_children(&mut self, parent: &[usize], from: usize, later: bool) {
+        let depth = parent.len();
+        for nodes in self.events.values_mut() {
+            let (moved, kept): (BTreeMap<_, _>, BTreeMap<_, _>) = std::mem::take(nodes)
+                .into_iter()
+                .partition(|(node, _)| node.len() > depth && node.starts_with(parent) && node[depth] >= from);
+            *nodes = kept;
+            for (mut node, handler) in moved {
+                node[depth] = if later { node[depth] + 1 } else { node[depth] - 1 };
+                nodes.insert(node, handler);
+            }
+        }
+    }
+}
+
+/// Dispatches an event to the handler of every `on:<event>` prop in the tree, as
+/// recorded in its `HandlerTable`. Returns the number of callbacks invoked.
+pub fn dispatch(event: &str, tree: &RenderTree, registry: &mut EventRegistry) -> usize {
+    let mut invoked = 0;
+    for name in tree.handlers.handlers(event) {
+        if let Some(cb) = registry.handlers.get_mut(name) {
             cb();
             invoked += 1;
         }
@@ -168,7 +700,7 @@ impl Runtime {
 
     /// Call on mouse left-button press; detects double-click within 400ms.
     pub fn mouse_click(&mut self) -> usize {
-        let now = Instant::now();
+        let now = velox_core::clock::now();
         let clicks = if let Some(prev) = self.last_click {
             if now.duration_since(prev) <= Duration::from_millis(400) {
                 self.last_click = None;
@@ -0,0 +1,214 @@
+//! Two-finger pinch and rotate.
+//!
+//! An element with an `on:zoom` handler can be pinched, and one with `on:rotate` twisted.
+//! Both come from two fingers on a touch screen, and on macOS from the trackpad's
+//! magnify and rotate gestures. Each event carries the change since the previous one and
+//! the gesture's center, relative to the element's top-left corner in logical pixels,
+//! as `{"scale":1.04,"x":120,"y":86}` or `{"angle":-0.02,"x":120,"y":86}`.
+//!
+//! `scale` multiplies the current zoom (above 1 is spreading the fingers apart). `angle`
+//! is in radians, clockwise on screen. A touch gesture stays with the elements under its
+//! center when the second finger lands, wherever the fingers go after.
+
+use velox_dom::layout::Rect;
+
+use crate::events::{ClickTarget, Targets};
+use crate::pen::PenPhase;
+
+/// A recognized change of a two-finger gesture, centered at `(x, y)` in layout
+/// coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Gesture {
+    /// The distance between the fingers changed by a factor of `scale`.
+    Zoom { scale: f32, x: f32, y: f32 },
+    /// The fingers turned by `angle` radians, clockwise.
+    Rotate { angle: f32, x: f32, y: f32 },
+}
+
+impl Gesture {
+    /// The handler payload for an element at `rect`.
+    pub fn payload(&self, rect: Rect) -> String {
+        match *self {
+            Gesture::Zoom { scale, x, y } => {
+                format!(
+                    "{{\"scale\":{},\"x\":{},\"y\":{}}}",
+                    scale,
+                    x - rect.x as f32,
+                    y - rect.y as f32
+                )
+            }
+            Gesture::Rotate { angle, x, y } => {
+                format!(
+                    "{{\"angle\":{},\"x\":{},\"y\":{}}}",
+                    angle,
+                    x - rect.x as f32,
+                    y - rect.y as f32
+                )
+            }
+        }
+    }
+
+    /// A trackpad magnify report (`delta` of 0.1 is 10% larger).
+    pub fn magnify(delta: f32, x: f32, y: f32) -> Self {
+        Gesture::Zoom {
+            scale: (1.0 + delta).max(0.01),
+            x,
+            y,
+        }
+    }
+
+    /// A trackpad rotate report, in degrees counterclockwise as macOS gives them.
+    pub fn rotate_degrees(ccw: f32, x: f32, y: f32) -> Self {
+        Gesture::Rotate {
+            angle: -ccw.to_radians(),
+            x,
+            y,
+        }
+    }
+}
+
+/// The touch points down and the elements a two-finger gesture is captured by.
+#[derive(Debug, Clone, Default)]
+pub struct Gestures {
+    /// In the order they landed; the first two make the gesture.
+    points: Vec<(u64, (f32, f32))>,
+    zoom: Option<(String, Rect)>,
+    rotate: Option<(String, Rect)>,
+}
+
+impl Gestures {
+    pub fn new() -> Self {
+        Gestures::default()
+    }
+
+    /// Track a touch of `pointer` at `(x, y)` in layout coordinates, dispatching the
+    /// pinch and turn it makes to the captured handlers. Returns whether one got it.
+    pub fn touch(
+        &mut self,
+        pointer: u64,
+        phase: PenPhase,
+        x: f32,
+        y: f32,
+        targets: &Targets,
+        on_event: &mut dyn FnMut(&str, Option<&str>),
+    ) -> bool {
+        match phase {
+            PenPhase::Down => {
+                self.points.retain(|(p, _)| *p != pointer);
+                self.points.push((pointer, (x, y)));
+                if self.points.len() == 2 {
+                    let (cx, cy) = Self::span(self.points[0].1, self.points[1].1).0;
+                    let capture = |t: &ClickTarget| (t.handler.clone(), t.rect);
+                    self.zoom = targets.zoom_at(cx, cy).map(capture);
+                    self.rotate = targets.rotate_at(cx, cy).map(capture);
+                }
+                false
+            }
+            PenPhase::Move => {
+                let Some(i) = self.points.iter().position(|(p, _)| *p == pointer) else {
+                    return false;
+                };
+                let before = self.points[i].1;
+                self.points[i].1 = (x, y);
+                if i > 1 || self.points.len() < 2 {
+                    return false;
+                }
+                let other = self.points[1 - i].1;
+                let (_, d0, a0) = Self::span(before, other);
+                let ((cx, cy), d1, a1) = Self::span((x, y), other);
+                let mut handled = false;
+                if let Some((handler, rect)) = &self.zoom
+                    && d0 > 0.0
+                    && d1 != d0
+                {
+                    on_event(
+                        handler,
+                        Some(
+                            &Gesture::Zoom {
+                                scale: d1 / d0,
+                                x: cx,
+                                y: cy,
+                            }
+                            .payload(*rect),
+                        ),
+                    );
+                    handled = true;
+                }
+                let angle = wrap_angle(a1 - a0);
+                if let Some((handler, rect)) = &self.rotate
+                    && angle != 0.0
+                {
+                    on_event(
+                        handler,
+                        Some(
+                            &Gesture::Rotate {
+                                angle,
+                                x: cx,
+                                y: cy,
+                            }
+                            .payload(*rect),
+                        ),
+                    );
+                    handled = true;
+                }
+                handled
+            }
+            PenPhase::Up | PenPhase::Cancel => {
+                let Some(i) = self.points.iter().position(|(p, _)| *p == pointer) else {
+                    return false;
+                };
+                self.points.remove(i);
+                // Lifting one of the two fingers ends the gesture; a third one down
+                // doesn't start another.
+                if i < 2 {
+                    self.zoom = None;
+                    self.rotate = None;
+                }
+                false
+            }
+        }
+    }
+
+    /// Whether two fingers are down on something that takes the gesture.
+    pub fn is_active(&self) -> bool {
+        self.points.len() >= 2 && (self.zoom.is_some() || self.rotate.is_some())
+    }
+
+    /// The center of two points, their distance, and the angle from the second to the first.
+    fn span(a: (f32, f32), b: (f32, f32)) -> ((f32, f32), f32, f32) {
+        let (dx, dy) = (a.0 - b.0, a.1 - b.1);
+        (
+            ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0),
+            dx.hypot(dy),
+            dy.atan2(dx),
+        )
+    }
+}
+
+/// Send a trackpad gesture to the innermost element under its center that handles it.
+/// Returns whether one did.
+pub fn dispatch(
+    gesture: Gesture,
+    targets: &Targets,
+    on_event: &mut dyn FnMut(&str, Option<&str>),
+) -> bool {
+    let target = match gesture {
+        Gesture::Zoom { x, y, .. } => targets.zoom_at(x, y),
+        Gesture::Rotate { x, y, .. } => targets.rotate_at(x, y),
+    };
+    let Some(target) = target else { return false };
+    on_event(&target.handler, Some(&gesture.payload(target.rect)));
+    true
+}
+
+/// `angle` in `-PI..=PI`, so a turn across the axis isn't a full circle back.
+fn wrap_angle(angle: f32) -> f32 {
+    use std::f32::consts::{PI, TAU};
+    if angle > PI {
+        angle - TAU
+    } else if angle < -PI {
+        angle + TAU
+    } else {
+        angle
+    }
+}
@@ -0,0 +1,112 @@
+//! Golden image tests: compare a rendered frame with a reference PNG checked into the
+//! repository.
+//!
+//! Render in deterministic mode (`config::set_deterministic(true)`, or
+//! `VELOX_DETERMINISTIC=1` for `run_app`) so the frame is the same on every machine:
+//! grayscale unhinted text, the bundled DejaVu Sans instead of installed fonts, a scale
+//! factor of 1 with no zoom or system text scaling. The pipeline never iterates a hash
+//! map to produce output, so nothing depends on hash seeds.
+//!
+//! A missing golden fails the check; set `VELOX_UPDATE_GOLDENS=1` to write it, or to
+//! rewrite the ones that no longer match after an intended rendering change. On a mismatch
+//! the new frame is saved next to the golden as `<name>.actual.png` for review.
+
+use std::path::{Path, PathBuf};
+
+use crate::pixels::RgbaImage;
+
+/// Set to rewrite goldens that don't match.
+pub const UPDATE_ENV: &str = "VELOX_UPDATE_GOLDENS";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Golden {
+    Matched,
+    /// There is no golden at the path and updating wasn't requested.
+    Missing,
+    /// Updating was requested; the image was saved as the golden.
+    Written,
+    Mismatch {
+        /// Where the rendered image was saved.
+        actual: PathBuf,
+        /// Pixels that differ; every pixel when the sizes differ.
+        differing: usize,
+    },
+}
+
+/// Compare `image` with the golden PNG at `path`. See the module docs for when the golden
+/// is written.
+pub fn check_golden(path: impl AsRef<Path>, image: &RgbaImage) -> Result<Golden, crate::Error> {
+    let path = path.as_ref();
+    let update = std::env::var_os(UPDATE_ENV).is_some_and(|v| !v.is_empty() && v != "0");
+    if update {
+        write_golden(path, image)?;
+        return Ok(Golden::Written);
+    }
+    let expected = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Golden::Missing),
+        Err(e) => return Err(e.into()),
+    };
+    let differing = match RgbaImage::decode_png(&expected) {
+        Some(golden) if (golden.width, golden.height) == (image.width, image.height) => golden
+            .data
+            .chunks_exact(4)
+            .zip(image.data.chunks_exact(4))
+            .filter(|(a, b)| a != b)
+            .count(),
+        _ => (image.width as usize) * (image.height as usize),
+    };
+    if differing == 0 {
+        return Ok(Golden::Matched);
+    }
+    let actual = path.with_extension("actual.png");
+    image.save_png(&actual)?;
+    Ok(Golden::Mismatch { actual, differing })
+}
+
+/// Save `image` as the golden at `path`, creating its directory.
+pub fn write_golden(path: impl AsRef<Path>, image: &RgbaImage) -> Result<(), crate::Error> {
+    let path = path.as_ref();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    image.save_png(path)
+}
+
+/// Panic unless `image` matches the golden at `path` (see `check_golden`).
+pub fn assert_golden(path: impl AsRef<Path>, image: &RgbaImage) {
+    let path = path.as_ref();
+    expect_match(path, check_golden(path, image));
+}
+
+/// Like `assert_golden`, but a missing golden is written instead of failing. For the
+/// Skia tests, whose goldens can only be rendered where Skia's native libraries are
+/// installed: their first run there records the PNGs to commit.
+pub fn assert_golden_or_record(path: impl AsRef<Path>, image: &RgbaImage) {
+    let path = path.as_ref();
+    let result = match check_golden(path, image) {
+        Ok(Golden::Missing) => write_golden(path, image).map(|()| Golden::Written),
+        result => result,
+    };
+    expect_match(path, result);
+}
+
+fn expect_match(path: &Path, result: Result<Golden, crate::Error>) {
+    match result {
+        Ok(Golden::Matched) => {}
+        Ok(Golden::Written) => eprintln!("wrote golden {}", path.display()),
+        Ok(Golden::Missing) => panic!(
+            "no golden at {}; set {}=1 to write it",
+            path.display(),
+            UPDATE_ENV
+        ),
+        Ok(Golden::Mismatch { actual, differing }) => panic!(
+            "{} pixels differ from golden {} (rendered: {}); set {}=1 to accept",
+            differing,
+            path.display(),
+            actual.display(),
+            UPDATE_ENV
+        ),
+        Err(e) => panic!("golden {}: {}", path.display(), e),
+    }
+}
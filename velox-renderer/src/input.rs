@@ -0,0 +1,267 @@
+//! Input handling shared by the window runners and `VeloxUi`: pointer, touch, trackpad,
+//! wheel and shortcut input is hit-tested against the last frame's targets and dispatched
+//! the same way by each of them, and a script played with `replay::play_in_window` takes
+//! the same paths as real input.
+
+use std::hash::Hash;
+use std::time::Instant;
+
+use crate::events::Targets;
+use crate::gesture::{Gesture, Gestures};
+use crate::pen::{PenPhase, PenSample, PenStrokes};
+use crate::pointer::{PointerIds, PointerMoves};
+use crate::refs::Refs;
+use crate::replay::Input as Recorded;
+use crate::shortcuts::{Shortcut, Shortcuts};
+use crate::viewport::Pans;
+
+type OnEvent<'a> = &'a mut dyn FnMut(&str, Option<&str>);
+
+/// What a press did.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Press {
+    /// A handler ran or a drag started, so the view may have changed.
+    pub(crate) handled: bool,
+    /// A click handler or a drag took the press; a host should not act on it too.
+    pub(crate) taken: bool,
+}
+
+/// The input state of one view. `K` tells pointers apart (the mouse and touch points by
+/// device), positions are logical pixels in the viewport, and every method that can run
+/// a handler returns whether it did.
+pub(crate) struct ViewInput<K> {
+    /// The window's refs, whose scroll offset turns viewport positions into content
+    /// positions. `VeloxUi` has none: it doesn't scroll.
+    refs: Option<Refs>,
+    shortcuts: Shortcuts,
+    /// Last pointer position.
+    pub(crate) pointer: Option<(f32, f32)>,
+    pub(crate) hovered: Option<u32>,
+    /// Hit-test targets of the last frame.
+    pub(crate) targets: Targets,
+    moves: PointerMoves,
+    pens: PenStrokes,
+    gestures: Gestures,
+    pans: Pans,
+    ids: PointerIds<K>,
+}
+
+impl<K: Eq + Hash> ViewInput<K> {
+    pub(crate) fn new(refs: Option<Refs>) -> Self {
+        ViewInput {
+            refs,
+            shortcuts: Shortcuts::current(),
+            pointer: None,
+            hovered: None,
+            targets: Targets::default(),
+            moves: PointerMoves::new(),
+            pens: PenStrokes::new(),
+            gestures: Gestures::new(),
+            pans: Pans::new(),
+            ids: PointerIds::new(),
+        }
+    }
+
+    fn content(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let (sx, sy) = self.refs.as_ref().map_or((0, 0), Refs::scroll_offset);
+        (x + sx as f32, y + sy as f32)
+    }
+
+    /// Pointer `key` moved to `(x, y)`. Hover follows in `update_hover`, once per batch of
+    /// moves; a drag follows now.
+    pub(crate) fn moved(&mut self, key: K, x: f32, y: f32, on_event: OnEvent) -> bool {
+        self.pointer = Some((x, y));
+        let id = self.ids.id(key);
+        let moved = self.moves.moved(id, x, y, on_event);
+        let (cx, cy) = self.content((x, y));
+        self.pans
+            .pointer(id, PenPhase::Move, cx, cy, &self.targets, on_event)
+            || moved
+    }
+
+    /// Whether a drag of an `on:pan` element is under way.
+    pub(crate) fn is_dragging(&self) -> bool {
+        self.pans.is_dragging()
+    }
+
+    /// The pointer left the view. Returns whether something was hovered.
+    pub(crate) fn left(&mut self) -> bool {
+        self.pointer = None;
+        self.moves.take();
+        self.hovered.take().is_some()
+    }
+
+    /// Hit-test hover at the last position moved to since the last call. Returns whether
+    /// the hovered element changed.
+    pub(crate) fn update_hover(&mut self) -> bool {
+        let Some(at) = self.moves.take() else {
+            return false;
+        };
+        let (x, y) = self.content(at);
+        let hovered = self.targets.hover_at(x, y);
+        if hovered == self.hovered {
+            return false;
+        }
+        self.hovered = hovered;
+        crate::replay::record(Recorded::Move { x: at.0, y: at.1 });
+        true
+    }
+
+    /// The primary button of `key` went down at the last pointer position: click-outside
+    /// handlers run, then the click under it (see `events::dispatch_press`), and a press
+    /// on an `on:pan` element starts dragging it.
+    pub(crate) fn pressed(&mut self, key: K, on_event: OnEvent) -> Press {
+        let Some(at) = self.pointer else {
+            return Press::default();
+        };
+        let id = self.ids.id(key);
+        let (handled, target) = self.click(at, id, on_event);
+        let clicked = target.is_some();
+        crate::replay::record(Recorded::Click {
+            x: at.0,
+            y: at.1,
+            target,
+        });
+        let (cx, cy) = self.content(at);
+        let dragging = self
+            .pans
+            .pointer(id, PenPhase::Down, cx, cy, &self.targets, on_event);
+        Press {
+            handled: handled || dragging,
+            taken: clicked || dragging,
+        }
+    }
+
+    fn click(&mut self, at: (f32, f32), id: u64, on_event: OnEvent) -> (bool, Option<String>) {
+        crate::events::dispatch_press(&self.targets, self.content(at), at, id, on_event)
+    }
+
+    /// The primary button of `key` went up, ending a drag. Returns whether there was one.
+    pub(crate) fn released(&mut self, key: K, on_event: OnEvent) -> bool {
+        let id = self.ids.id(key);
+        self.pans
+            .pointer(id, PenPhase::Up, 0.0, 0.0, &self.targets, on_event)
+    }
+
+    /// A pen, stylus or finger sample from touch point `key`: strokes go to the `on:pen`
+    /// element they start on, two-finger pinches and turns to the `on:zoom`/`on:rotate`
+    /// ones, and other finger drags to the `on:pan` one.
+    pub(crate) fn touch(&mut self, key: K, sample: PenSample, on_event: OnEvent) -> bool {
+        let (x, y) = self.content((sample.x, sample.y));
+        let sample = PenSample { x, y, ..sample };
+        let id = match sample.phase {
+            // A lifted finger's id is free for the next touch.
+            PenPhase::Up | PenPhase::Cancel => self.ids.release(&key),
+            _ => Some(self.ids.id(key)),
+        };
+        let Some(id) = id else { return false };
+        let drawn = self.pens.sample(id, sample, &self.targets, on_event);
+        let gesture = self
+            .gestures
+            .touch(id, sample.phase, x, y, &self.targets, on_event);
+        let panned = if self.gestures.is_active() {
+            // A second finger turns a drag into a pinch.
+            self.pans
+                .pointer(id, PenPhase::Cancel, x, y, &self.targets, on_event);
+            false
+        } else {
+            !drawn
+                && self
+                    .pans
+                    .pointer(id, sample.phase, x, y, &self.targets, on_event)
+        };
+        gesture || drawn || panned
+    }
+
+    /// A trackpad pinch or turn, centered on the last pointer position.
+    pub(crate) fn trackpad(
+        &mut self,
+        gesture: impl FnOnce(f32, f32) -> Gesture,
+        on_event: OnEvent,
+    ) -> bool {
+        let Some(at) = self.pointer else { return false };
+        let (x, y) = self.content(at);
+        crate::gesture::dispatch(gesture(x, y), &self.targets, on_event)
+    }
+
+    /// The wheel turned by `(dx, dy)` (positive to move the content right and down) at the
+    /// last pointer position. Over a viewport it pans it, or zooms it with `zoom` (Ctrl
+    /// held); elsewhere it scrolls the window, if the view has one. Returns whether it
+    /// did any of these.
+    pub(crate) fn wheel(&mut self, (dx, dy): (f32, f32), zoom: bool, on_event: OnEvent) -> bool {
+        let at = self.pointer.map(|at| self.content(at));
+        if at.is_some_and(|at| crate::viewport::wheel((dx, dy), zoom, at, &self.targets, on_event))
+        {
+            return true;
+        }
+        match &self.refs {
+            Some(refs) if !zoom => {
+                let (sx, sy) = refs.scroll_offset();
+                refs.scroll_to(sx - dx.round() as i32, sy - dy.round() as i32);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// A key combination was pressed: run the handler bound to it, if any.
+    #[cfg_attr(
+        not(any(feature = "skia-native", feature = "raster", feature = "wgpu")),
+        allow(dead_code)
+    )]
+    pub(crate) fn shortcut(&mut self, shortcut: &Shortcut, on_event: OnEvent) -> bool {
+        let ran = self.run_shortcut(shortcut, on_event);
+        if ran {
+            crate::replay::record(Recorded::Key(shortcut.to_string()));
+        }
+        ran
+    }
+
+    /// Replace the bindings with the `shortcut` attributes of the view just built.
+    #[cfg_attr(
+        not(any(feature = "skia-native", feature = "raster", feature = "wgpu")),
+        allow(dead_code)
+    )]
+    pub(crate) fn update_shortcuts(&self, vnode: &velox_dom::VNode) {
+        self.shortcuts.update_from_view(vnode);
+    }
+
+    fn run_shortcut(&mut self, shortcut: &Shortcut, on_event: OnEvent) -> bool {
+        let Some((handler, payload)) = self.shortcuts.resolve(shortcut) else {
+            return false;
+        };
+        if !crate::shortcuts::run_builtin(&handler, payload.as_deref()) {
+            on_event(&handler, payload.as_deref());
+        }
+        true
+    }
+
+    /// Play the scripted inputs due at `now`. Returns whether there were any.
+    #[cfg_attr(
+        not(any(feature = "skia-native", feature = "raster", feature = "wgpu")),
+        allow(dead_code)
+    )]
+    pub(crate) fn replay_due(&mut self, now: Instant, on_event: OnEvent) -> bool {
+        let due = crate::replay::due(now);
+        for input in &due {
+            match input {
+                Recorded::Move { x, y } => {
+                    self.pointer = Some((*x, *y));
+                    let (cx, cy) = self.content((*x, *y));
+                    self.hovered = self.targets.hover_at(cx, cy);
+                }
+                Recorded::Click { x, y, .. } => {
+                    self.pointer = Some((*x, *y));
+                    self.click((*x, *y), crate::pointer::PRIMARY_POINTER, on_event);
+                }
+                Recorded::Key(combo) => {
+                    if let Some(shortcut) = Shortcut::parse(combo) {
+                        self.run_shortcut(&shortcut, on_event);
+                    }
+                }
+                Recorded::Emit { handler, payload } => on_event(handler, payload.as_deref()),
+            }
+        }
+        !due.is_empty()
+    }
+}
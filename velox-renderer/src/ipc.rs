@@ -0,0 +1,305 @@
+//! Driving a velox window from another process.
+//!
+//! `serve` turns velox into a render server. A host process (a script, or the backend of
+//! an Electron-style app) sends views as newline-delimited JSON over stdio
+//! (`serve_stdio`) or a unix socket (`serve_unix`), and velox sends the user's events
+//! back the same way. Each line is an object whose `type` names a `Request` (`render`,
+//! with the view in `VNode`'s serialized form from `velox_dom::wire` and an optional
+//! `style`, or `title`) or a `Message` (`resize`, `event` or `error`).
+//!
+//! The window is empty until the first `render`, and the process exits when the host
+//! closes its end of the stream.
+
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+
+use serde_json::{Value, json};
+use velox_dom::{PropValue, Props, VNode, h, text};
+use velox_style::Stylesheet;
+
+use crate::{Error, RendererConfig};
+
+/// A message from the host.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    /// Show `view`, styled by `style`, or by the previous stylesheet when `None`.
+    Render { view: VNode, style: Option<String> },
+    /// Set the window title.
+    Title(String),
+}
+
+/// A message to the host.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    /// The view dispatched `handler`.
+    Event {
+        handler: String,
+        payload: Option<String>,
+    },
+    /// The view is now laid out at `width`x`height` logical pixels.
+    Resize { width: u32, height: u32 },
+    /// A request could not be decoded; it was skipped.
+    Error(String),
+}
+
+impl Request {
+    /// Decode one line of the protocol.
+    pub fn parse(line: &str) -> Result<Self, Error> {
+        let value: Value =
+            serde_json::from_str(line).map_err(|e| Error::Serialize(e.to_string()))?;
+        match value.get("type").and_then(Value::as_str) {
+            Some("render") => {
+                let view = node_from_json(
+                    value
+                        .get("view")
+                        .ok_or_else(|| invalid("render without a view"))?,
+                )?;
+                let style = match value.get("style") {
+                    None | Some(Value::Null) => None,
+                    Some(Value::String(css)) => Some(css.clone()),
+                    Some(_) => return Err(invalid("style must be a string")),
+                };
+                Ok(Request::Render { view, style })
+            }
+            Some("title") => match value.get("title") {
+                Some(Value::String(title)) => Ok(Request::Title(title.clone())),
+                _ => Err(invalid("title must be a string")),
+            },
+            Some(other) => Err(invalid(format!("unknown message type {:?}", other))),
+            None => Err(invalid("message without a type")),
+        }
+    }
+}
+
+impl Message {
+    pub fn to_json(&self) -> Value {
+        match self {
+            Message::Event { handler, payload } => {
+                json!({ "type": "event", "handler": handler, "payload": payload })
+            }
+            Message::Resize { width, height } => {
+                json!({ "type": "resize", "width": width, "height": height })
+            }
+            Message::Error(message) => json!({ "type": "error", "message": message }),
+        }
+    }
+}
+
+/// Write `message` as one line and flush, so the host sees it right away.
+pub fn write_message<W: Write + ?Sized>(out: &mut W, message: &Message) -> Result<(), Error> {
+    serde_json::to_writer(&mut *out, &message.to_json())
+        .map_err(|e| Error::Serialize(e.to_string()))?;
+    out.write_all(b"\n")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Decode a node of the protocol. Like `VNode`'s `Deserialize`, attribute values may be
+/// strings, numbers, booleans, or arrays of them; unlike it, this says which part of a
+/// node is wrong.
+pub fn node_from_json(value: &Value) -> Result<VNode, Error> {
+    let element = match value {
+        Value::String(t) => return Ok(text(t.as_str())),
+        Value::Object(element) => element,
+        _ => return Err(invalid("a node must be a string or an object")),
+    };
+    let tag = element
+        .get("tag")
+        .and_then(Value::as_str)
+        .ok_or_else(|| invalid("element without a tag"))?;
+    let mut props = Props::new();
+    match element.get("attrs") {
+        None | Some(Value::Null) => {}
+        Some(Value::Object(attrs)) => {
+            for (name, value) in attrs {
+                let value = prop_from_json(value).ok_or_else(|| {
+                    invalid(format!(
+                        "attribute {:?} of <{}> must be a string, number, boolean or array",
+                        name, tag
+                    ))
+                })?;
+                props = props.set(name.as_str(), value);
+            }
+        }
+        Some(_) => return Err(invalid(format!("attrs of <{}> must be an object", tag))),
+    }
+    let children = match element.get("children") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(children)) => children
+            .iter()
+            .map(node_from_json)
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err(invalid(format!("children of <{}> must be an array", tag))),
+    };
+    Ok(h(tag, props, children))
+}
+
+fn prop_from_json(value: &Value) -> Option<PropValue> {
+    Some(match value {
+        Value::String(s) => PropValue::Str(s.clone()),
+        Value::Number(n) => PropValue::Num(n.as_f64()?),
+        Value::Bool(b) => PropValue::Bool(*b),
+        Value::Array(items) => {
+            PropValue::List(items.iter().map(prop_from_json).collect::<Option<_>>()?)
+        }
+        _ => return None,
+    })
+}
+
+/// Encode `node` for the protocol; the inverse of `node_from_json`.
+pub fn node_to_json(node: &VNode) -> Value {
+    serde_json::to_value(node).expect("a VNode always serializes")
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::Serialize(message.into())
+}
+
+/// What the host has sent so far.
+#[derive(Default)]
+struct Shared {
+    view: Option<VNode>,
+    css: String,
+    /// Bumped when `css` changes, so the window parses it once per change.
+    css_version: u64,
+    title: String,
+}
+
+type Output = Arc<Mutex<dyn Write + Send>>;
+
+fn send(output: &Output, message: &Message) {
+    let mut out = output.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = write_message(&mut *out, message) {
+        log::warn!(target: "velox::renderer::ipc", "host did not take a message: {}", e);
+    }
+}
+
+/// Open a window showing the views read from `input` and write its events to `output`.
+/// See the module docs for the protocol. Returns like `run_app`. The process exits when
+/// `input` ends.
+pub fn serve<R, W>(title: &str, config: &RendererConfig, input: R, output: W) -> Result<(), Error>
+where
+    R: BufRead + Send + 'static,
+    W: Write + Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        title: title.to_string(),
+        ..Default::default()
+    }));
+    let output: Output = Arc::new(Mutex::new(output));
+    {
+        let (shared, output) = (shared.clone(), output.clone());
+        std::thread::Builder::new()
+            .name("velox-ipc".into())
+            .spawn(move || {
+                read_requests(input, &shared, &output);
+                // The host is gone, and nothing is left to show.
+                std::process::exit(0);
+            })?;
+    }
+
+    let (view_shared, title_shared, event_output) = (shared.clone(), shared, output.clone());
+    let mut sheet = (0, Stylesheet::default());
+    let mut size = None;
+    crate::run_app(
+        title,
+        config,
+        move |width, height| {
+            if size != Some((width, height)) {
+                size = Some((width, height));
+                send(&output, &Message::Resize { width, height });
+            }
+            let shared = view_shared.lock().unwrap_or_else(|e| e.into_inner());
+            if shared.css_version != sheet.0 {
+                sheet = (shared.css_version, Stylesheet::parse(&shared.css));
+            }
+            let view = shared
+                .view
+                .clone()
+                .unwrap_or_else(|| h("div", Props::new(), vec![]));
+            (view, sheet.1.clone())
+        },
+        move |handler, payload| {
+            let message = Message::Event {
+                handler: handler.to_string(),
+                payload: payload.map(str::to_string),
+            };
+            send(&event_output, &message);
+        },
+        move || {
+            title_shared
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .title
+                .clone()
+        },
+    )
+}
+
+fn read_requests<R: BufRead>(input: R, shared: &Mutex<Shared>, output: &Output) {
+    for (i, line) in input.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                log::error!(target: "velox::renderer::ipc", "reading from the host failed: {}", e);
+                return;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match Request::parse(&line) {
+            Ok(request) => {
+                let mut shared = shared.lock().unwrap_or_else(|e| e.into_inner());
+                match request {
+                    Request::Render { view, style } => {
+                        shared.view = Some(view);
+                        if let Some(css) = style
+                            && css != shared.css
+                        {
+                            shared.css = css;
+                            shared.css_version += 1;
+                        }
+                    }
+                    Request::Title(title) => shared.title = title,
+                }
+                drop(shared);
+                crate::wake::wake();
+            }
+            Err(e) => {
+                let reason = match e {
+                    Error::Serialize(reason) => reason,
+                    e => e.to_string(),
+                };
+                send(
+                    output,
+                    &Message::Error(format!("line {}: {}", i + 1, reason)),
+                );
+            }
+        }
+    }
+}
+
+/// `serve` over the process's stdin and stdout. Log to stderr only: anything else written
+/// to stdout would corrupt the stream.
+pub fn serve_stdio(title: &str, config: &RendererConfig) -> Result<(), Error> {
+    serve(
+        title,
+        config,
+        std::io::BufReader::new(std::io::stdin()),
+        std::io::stdout(),
+    )
+}
+
+/// `serve` over a unix socket bound at `path`, once the host has connected to it.
+#[cfg(unix)]
+pub fn serve_unix(
+    path: impl AsRef<std::path::Path>,
+    title: &str,
+    config: &RendererConfig,
+) -> Result<(), Error> {
+    let listener = std::os::unix::net::UnixListener::bind(path)?;
+    let (stream, _) = listener.accept()?;
+    let input = std::io::BufReader::new(stream.try_clone()?);
+    serve(title, config, input, stream)
+}
@@ -0,0 +1,62 @@
+//! winit keyboard input to `Shortcut`s, for the window runners.
+
+use winit::event::{ModifiersState, VirtualKeyCode};
+
+use crate::shortcuts::Shortcut;
+
+/// The shortcut for a key press, or `None` for keys without a name (and bare modifiers).
+pub(crate) fn shortcut(key: VirtualKeyCode, modifiers: ModifiersState) -> Option<Shortcut> {
+    use VirtualKeyCode as K;
+    let name = match key {
+        K::Escape => "Escape".to_string(),
+        K::Return | K::NumpadEnter => "Enter".to_string(),
+        K::Space => "Space".to_string(),
+        K::Tab => "Tab".to_string(),
+        K::Back => "Backspace".to_string(),
+        K::Delete => "Delete".to_string(),
+        K::Insert => "Insert".to_string(),
+        K::Home => "Home".to_string(),
+        K::End => "End".to_string(),
+        K::PageUp => "PageUp".to_string(),
+        K::PageDown => "PageDown".to_string(),
+        K::Left => "ArrowLeft".to_string(),
+        K::Right => "ArrowRight".to_string(),
+        K::Up => "ArrowUp".to_string(),
+        K::Down => "ArrowDown".to_string(),
+        K::Minus | K::NumpadSubtract => "-".to_string(),
+        K::Equals | K::NumpadEquals => "=".to_string(),
+        K::Plus | K::NumpadAdd => "+".to_string(),
+        K::Comma | K::NumpadComma => ",".to_string(),
+        K::Period | K::NumpadDecimal => ".".to_string(),
+        K::Slash | K::NumpadDivide => "/".to_string(),
+        K::Asterisk | K::NumpadMultiply => "*".to_string(),
+        K::Semicolon => ";".to_string(),
+        K::Apostrophe => "'".to_string(),
+        K::Grave => "`".to_string(),
+        K::Backslash => "\\".to_string(),
+        K::LBracket => "[".to_string(),
+        K::RBracket => "]".to_string(),
+        _ => {
+            // Letters (`A`), digits (`Key1`, `Numpad1`), and function keys (`F5`).
+            let debug = format!("{:?}", key);
+            let name = debug
+                .strip_prefix("Key")
+                .or_else(|| debug.strip_prefix("Numpad"))
+                .unwrap_or(&debug);
+            let is_fn = name.len() <= 3
+                && name.starts_with('F')
+                && name[1..].chars().all(|c| c.is_ascii_digit());
+            if name.len() != 1 && !is_fn {
+                return None;
+            }
+            name.to_string()
+        }
+    };
+    Some(Shortcut::new(
+        &name,
+        modifiers.ctrl(),
+        modifiers.alt(),
+        modifiers.shift(),
+        modifiers.logo(),
+    ))
+}
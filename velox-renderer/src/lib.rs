@@ -5,7 +5,54 @@ use velox_dom::VNode;
 use velox_style::{Stylesheet, apply_styles_with_hover};
 use std::collections::{HashMap, HashSet};
 
+pub mod animation;
+pub mod arena;
+pub mod capabilities;
+pub mod components;
+pub mod config;
+pub mod crash;
+pub mod damage;
+pub mod devtools;
+pub mod display_list;
+pub mod embed;
+pub mod enter_leave;
+pub mod error;
 pub mod events;
+pub mod gesture;
+pub mod golden;
+pub mod live_regions;
+pub mod logging;
+pub mod pacing;
+pub mod pen;
+pub mod pipeline;
+pub mod pixels;
+pub mod pointer;
+pub mod print;
+pub mod recording;
+pub mod refs;
+pub mod render_props;
+pub mod replay;
+pub mod screenshot;
+pub mod shortcuts;
+pub mod stats;
+pub mod testing;
+pub mod text_scale;
+pub mod viewport;
+pub mod visibility;
+pub mod wake;
+pub mod zoom;
+#[cfg(feature = "serde")]
+pub mod ipc;
+#[cfg(feature = "serde")]
+pub mod remote;
+
+pub use capabilities::Capabilities;
+pub use config::{Backend, Hinting, RendererConfig, TextAntialias, TextQuality};
+pub use embed::VeloxUi;
+pub use error::Error;
+pub use logging::init_logging;
+pub use pixels::{PixelRect, RgbaImage};
+pub use stats::{RendererStats, stats, trim_caches};
 
 // Native Skia GL helper module (feature-gated)
 #[cfg(feature = "skia-native")]
@@ -16,13 +63,68 @@ mod skia_surface;
 #[cfg(feature = "skia-native")]
 mod skia_render;
 #[cfg(feature = "skia-native")]
-pub use skia_render::{render_vnode_to_raster_png, render_vnode_to_raster_png_with_scale};
-
-/// In-memory representation of a mounted tree (stubbed for now).
+pub use skia_render::{
+    render_display_list_to_png, render_vnode_to_raster_image, render_vnode_to_raster_png, render_vnode_to_raster_png_with_scale,
+};
+// Software rasterizer fallback (tiny-skia).
+#[cfg(feature = "raster")]
+pub mod raster;
+#[cfg(feature = "raster")]
+mod raster_window;
+#[cfg(feature = "raster")]
+pub use raster_window::run_window_vnode_raster;
+// winit key presses to shortcuts, shared by the window runners.
+#[cfg(any(feature = "wgpu", feature = "skia-native", feature = "raster"))]
+mod keys;
+// Shared plumbing for the CPU-presented window runners.
+#[cfg(any(feature = "skia-native", feature = "raster"))]
+mod present;
+// Shared by the window runners and `embed::VeloxUi`.
+mod input;
+mod view;
+
+/// In-memory representation of a mounted tree (stubbed for now). Mounting one runs the
+/// `velox_core::lifecycle` mounted hooks, `update` the updated hooks, and dropping it the
+/// unmounted hooks of its scope. The window runners and `VeloxUi` mount their view on its
+/// first frame, update it on every later one, and drop it with the window.
 pub struct RenderTree {
     pub root: VNode,
     pub node_count: usize,
     pub text_count: usize,
+    /// The `on:<event>` handlers of `root`, kept in step by `update`.
+    pub handlers: events::HandlerTable,
+    /// Owns the hooks registered outside any scope before the tree was mounted.
+    scope: velox_core::signal::Scope,
+}
+
+impl RenderTree {
+    /// Bring the tree up to `next`, patching it and the handler table in place instead of
+    /// rebuilding them.
+    pub fn update(&mut self, next: &VNode) {
+        self.patch(next);
+    }
+
+    /// `update`, returning how many updated hooks ran.
+    fn patch(&mut self, next: &VNode) -> usize {
+        let patches = velox_dom::diff::diff(&self.root, next);
+        if patches.is_empty() {
+            return 0;
+        }
+        self.handlers.apply(&patches);
+        let mut counts = (0, 0);
+        summarize(next, &mut counts);
+        (self.node_count, self.text_count) = counts;
+        let applied = velox_dom::diff::apply(&mut self.root, &patches);
+        // Patches `diff` made for this very tree always fit it.
+        debug_assert!(applied.is_ok(), "diff made patches its own tree rejects: {applied:?}");
+        velox_core::lifecycle::run_updated_hooks()
+    }
+}
+
+impl Drop for RenderTree {
+    fn drop(&mut self) {
+        self.scope.dispose();
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -31,6 +133,8 @@ pub struct A11yNode {
     pub role: String,
     pub name: String,
     pub rect: velox_dom::layout::Rect,
+    /// Set for `aria-live` regions (see `live_regions`).
+    pub live: Option<live_regions::Politeness>,
     pub children: Vec<A11yNode>,
 }
 
@@ -45,25 +149,49 @@ fn summarize(v: &VNode, counts: &mut (usize, usize)) {
             counts.0 += 1;
             counts.1 += 1;
         }
-        VNode::Element { children, .. } => {
+        VNode::Element { children, .. } | VNode::Fragment(children) => {
             counts.0 += 1;
             for c in children {
                 summarize(c, counts);
             }
         }
+        VNode::Component { .. } => counts.0 += 1,
     }
 }
 
 fn build_render_tree(v: &VNode) -> RenderTree {
+    mount_tree(v).0
+}
+
+/// Mount `v`, returning the tree and how many mounted hooks ran.
+fn mount_tree(v: &VNode) -> (RenderTree, usize) {
     let mut counts = (0, 0);
     summarize(v, &mut counts);
-    RenderTree { root: v.clone(), node_count: counts.0, text_count: counts.1 }
+    let scope = velox_core::signal::Scope::new();
+    velox_core::lifecycle::adopt_hooks(&scope);
+    let tree = RenderTree { root: v.clone(), node_count: counts.0, text_count: counts.1, handlers: events::HandlerTable::from_tree(v), scope };
+    let ran = velox_core::lifecycle::run_mounted_hooks();
+    (tree, ran)
+}
+
+/// Mount `vnode` on a view's first frame and patch the mounted tree with it on later
+/// ones. Returns whether a lifecycle hook ran; it may have changed what the view shows.
+pub(crate) fn commit_frame(tree: &mut Option<RenderTree>, vnode: &VNode) -> bool {
+    let ran = match tree {
+        Some(tree) => tree.patch(vnode),
+        None => {
+            let (mounted, ran) = mount_tree(vnode);
+            *tree = Some(mounted);
+            ran
+        }
+    };
+    ran > 0
 }
 
 fn vnode_text_content(node: &VNode) -> String {
     match node {
         VNode::Text(t) => t.clone(),
-        VNode::Element { children, .. } => {
+        VNode::Element { children, .. } | VNode::Fragment(children) => {
             let mut out = String::new();
             for ch in children {
                 let s = vnode_text_content(ch);
@@ -76,12 +204,13 @@ fn vnode_text_content(node: &VNode) -> String {
             }
             out
         }
+        VNode::Component { .. } => String::new(),
     }
 }
 
 fn a11y_role_for(tag: &str, props: &velox_dom::Props) -> String {
-    if let Some(role) = props.attrs.get("role") {
-        return role.clone();
+    if let Some(role) = props.str("role") {
+        return role.to_string();
     }
     match tag {
         "button" => "button",
@@ -95,12 +224,12 @@ fn a11y_role_for(tag: &str, props: &velox_dom::Props) -> String {
 }
 
 fn a11y_name_for(tag: &str, props: &velox_dom::Props, node: &VNode) -> String {
-    if let Some(label) = props.attrs.get("aria-label") {
-        return label.clone();
+    if let Some(label) = props.str("aria-label") {
+        return label.to_string();
     }
     if tag == "img" {
-        if let Some(alt) = props.attrs.get("alt") {
-            return alt.clone();
+        if let Some(alt) = props.str("alt") {
+            return alt.to_string();
         }
     }
     vnode_text_content(node)
@@ -119,21 +248,44 @@ fn build_a11y_tree_with_layout(
             role: "text".to_string(),
             name: t.clone(),
             rect: layout.rect,
+            live: None,
             children: Vec::new(),
         },
         VNode::Element { tag, props, children, .. } => {
             let mut child_nodes = Vec::new();
-            for (ch, ch_layout) in children.iter().zip(&layout.children) {
-                child_nodes.push(build_a11y_tree_with_layout(ch, ch_layout, next_id));
-            }
+            a11y_children(children, layout, next_id, &mut child_nodes);
             A11yNode {
                 id,
                 role: a11y_role_for(tag, props),
                 name: a11y_name_for(tag, props, vnode),
                 rect: layout.rect,
+                live: live_regions::Politeness::of(props),
                 children: child_nodes,
             }
         }
+        // Only a root fragment or component gets here; it stands for the view, an unnamed group.
+        VNode::Fragment(_) | VNode::Component { .. } => {
+            let mut child_nodes = Vec::new();
+            a11y_children(vnode.children(), layout, next_id, &mut child_nodes);
+            A11yNode { id, role: "group".to_string(), name: String::new(), rect: layout.rect, live: None, children: child_nodes }
+        }
+    }
+}
+
+/// The accessible nodes of `children`, with the nodes of fragments among them in their place
+/// and unexpanded components left out.
+fn a11y_children(
+    children: &[VNode],
+    layout: &velox_dom::layout::LayoutNode,
+    next_id: &mut usize,
+    out: &mut Vec<A11yNode>,
+) {
+    for (ch, ch_layout) in children.iter().zip(&layout.children) {
+        match ch {
+            VNode::Fragment(inner) => a11y_children(inner, ch_layout, next_id, out),
+            VNode::Component { .. } => {}
+            _ => out.push(build_a11y_tree_with_layout(ch, ch_layout, next_id)),
+        }
     }
 }
 
@@ -155,8 +307,8 @@ pub fn reconcile_keyed_children(old: &mut Vec<VNode>, new: &Vec<VNode>) {
     let mut key_to_index: HashMap<String, usize> = HashMap::new();
     for (i, n) in old.iter().enumerate() {
         if let VNode::Element { props, .. } = n {
-            if let Some(k) = props.attrs.get("key") {
-                key_to_index.insert(k.clone(), i);
+            if let Some(k) = props.get("key") {
+                key_to_index.insert(k.to_string(), i);
             }
         }
     }
@@ -164,8 +316,8 @@ pub fn reconcile_keyed_children(old: &mut Vec<VNode>, new: &Vec<VNode>) {
     let mut out: Vec<VNode> = Vec::with_capacity(new.len());
     for nn in new.iter() {
         if let VNode::Element { props: nprops, .. } = nn {
-            if let Some(k) = nprops.attrs.get("key") {
-                if let Some(&idx) = key_to_index.get(k) {
+            if let Some(k) = nprops.get("key") {
+                if let Some(&idx) = key_to_index.get(&k.to_string()) {
                     out.push(old[idx].clone());
                     used.insert(idx);
                     continue;
@@ -181,6 +333,18 @@ pub fn reconcile_keyed_children(old: &mut Vec<VNode>, new: &Vec<VNode>) {
 pub trait Renderer {
     fn backend_name(&self) -> &'static str;
     fn mount(&self, vnode: &VNode) -> RenderTree;
+    /// Features this backend supports; defaults to the stub's (nothing drawn).
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+    }
+    /// Read back `rect` of the last presented frame (see `pixels::enable_readback`).
+    fn read_pixels(&self, rect: pixels::PixelRect) -> Option<RgbaImage> {
+        pixels::read_pixels(rect)
+    }
+    /// Save the next presented frame to `path` as a PNG (see `screenshot`).
+    fn capture_screenshot(&self, path: &std::path::Path) {
+        screenshot::capture_screenshot(path)
+    }
 }
 
 #[cfg(feature = "wgpu")]
@@ -201,7 +365,7 @@ pub mod wgpu_backend {
         })) {
             Some(a) => a,
             None => {
-                eprintln!("wgpu backend: no adapter found; retrying with fallback adapter...");
+                log::warn!(target: "velox::renderer::wgpu", "no adapter found; retrying with fallback adapter");
                 match pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
                     power_preference: _wgpu::PowerPreference::HighPerformance,
                     compatible_surface: None,
@@ -209,7 +373,7 @@ pub mod wgpu_backend {
                 })) {
                     Some(a2) => a2,
                     None => {
-                        eprintln!("wgpu backend: no adapter found even with fallback (init skipped)");
+                        log::error!(target: "velox::renderer::wgpu", "no adapter found even with fallback (init skipped)");
                         return;
                     }
                 }
@@ -223,25 +387,117 @@ pub mod wgpu_backend {
         }, None)) {
             Ok((_device, _queue)) => {
                 let info = adapter.get_info();
-                eprintln!("wgpu backend: init OK — adapter='{}'", info.name);
+                log::info!(target: "velox::renderer::wgpu", "init OK — adapter='{}'", info.name);
             }
             Err(e) => {
-                eprintln!("wgpu backend: failed to request device: {:?}", e);
+                log::error!(target: "velox::renderer::wgpu", "failed to request device: {:?}", e);
             }
         }
     }
 
+    /// Surface, adapter, device, and queue for a window runner.
+    pub(crate) fn window_gpu(
+        window: &_winit::window::Window,
+    ) -> Result<(_wgpu::Surface, _wgpu::Adapter, _wgpu::Device, _wgpu::Queue), crate::Error> {
+        let instance = _wgpu::Instance::default();
+        let surface = unsafe { instance.create_surface(window) }.map_err(|e| crate::Error::Surface(e.to_string()))?;
+        let adapter = pollster::block_on(instance.request_adapter(&_wgpu::RequestAdapterOptions {
+            power_preference: _wgpu::PowerPreference::HighPerformance,
+            compatible_surface: Some(&surface),
+            force_fallback_adapter: false,
+        }))
+        .ok_or(crate::Error::AdapterNotFound)?;
+        let (device, queue) = pollster::block_on(adapter.request_device(
+            &_wgpu::DeviceDescriptor {
+                label: Some("velox-device"),
+                features: _wgpu::Features::empty(),
+                limits: _wgpu::Limits::default(),
+            },
+            None,
+        ))
+        .map_err(|e| crate::Error::Device(e.to_string()))?;
+        Ok((surface, adapter, device, queue))
+    }
+
+    /// True if an adapter (a real GPU or wgpu's software fallback) can be obtained.
+    pub fn adapter_available() -> bool {
+        let instance = _wgpu::Instance::new(_wgpu::InstanceDescriptor { backends: _wgpu::Backends::all(), dx12_shader_compiler: Default::default() });
+        [false, true].into_iter().any(|force_fallback_adapter| {
+            pollster::block_on(instance.request_adapter(&_wgpu::RequestAdapterOptions {
+                power_preference: _wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter,
+            }))
+            .is_some()
+        })
+    }
+
+    /// Copy a presented surface texture back to the CPU as straight RGBA.
+    /// The surface must be configured with `TextureUsages::COPY_SRC`.
+    pub(crate) fn read_texture(
+        device: &_wgpu::Device,
+        queue: &_wgpu::Queue,
+        texture: &_wgpu::Texture,
+        format: _wgpu::TextureFormat,
+        width: u32,
+        height: u32,
+    ) -> Option<crate::RgbaImage> {
+        let bgra = matches!(format, _wgpu::TextureFormat::Bgra8Unorm | _wgpu::TextureFormat::Bgra8UnormSrgb);
+        if !bgra && !matches!(format, _wgpu::TextureFormat::Rgba8Unorm | _wgpu::TextureFormat::Rgba8UnormSrgb) {
+            return None;
+        }
+        // Rows in a texture-to-buffer copy must be 256-byte aligned.
+        let row = width * 4;
+        let padded = row.div_ceil(_wgpu::COPY_BYTES_PER_ROW_ALIGNMENT) * _wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&_wgpu::BufferDescriptor {
+            label: Some("velox-readback"),
+            size: (padded * height) as u64,
+            usage: _wgpu::BufferUsages::COPY_DST | _wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&_wgpu::CommandEncoderDescriptor { label: Some("velox-readback") });
+        encoder.copy_texture_to_buffer(
+            _wgpu::ImageCopyTexture { texture, mip_level: 0, origin: _wgpu::Origin3d::ZERO, aspect: _wgpu::TextureAspect::All },
+            _wgpu::ImageCopyBuffer {
+                buffer: &buffer,
+                layout: _wgpu::ImageDataLayout { offset: 0, bytes_per_row: Some(padded), rows_per_image: Some(height) },
+            },
+            _wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+        );
+        queue.submit(Some(encoder.finish()));
+        let slice = buffer.slice(..);
+        slice.map_async(_wgpu::MapMode::Read, |_| {});
+        device.poll(_wgpu::Maintain::Wait);
+        let mut data = Vec::with_capacity((row * height) as usize);
+        {
+            let mapped = slice.get_mapped_range();
+            for line in mapped.chunks_exact(padded as usize) {
+                data.extend_from_slice(&line[..row as usize]);
+            }
+        }
+        buffer.unmap();
+        if bgra {
+            for px in data.chunks_exact_mut(4) {
+                px.swap(0, 2);
+            }
+        }
+        crate::RgbaImage::from_rgba(width, height, data)
+    }
+
     pub struct WgpuRenderer;
     impl crate::Renderer for WgpuRenderer {
         fn backend_name(&self) -> &'static str {
             "wgpu"
         }
+        fn capabilities(&self) -> crate::Capabilities {
+            crate::capabilities::capabilities_for(crate::Backend::Wgpu)
+        }
         fn mount(&self, vnode: &velox_dom::VNode) -> crate::RenderTree {
             // Try a GPU-backed present; log errors but do not fail the mount.
             #[cfg(all(feature = "skia-native", unix))]
             {
                 if let Err(e) = crate::skia_gl::draw_gpu_test_frame(256, 256) {
-                    eprintln!("skia backend: GPU present failed: {}", e);
+                    log::error!(target: "velox::skia", "GPU present failed: {}", e);
                 }
             }
             crate::build_render_tree(vnode)
@@ -259,15 +515,17 @@ pub mod skia_backend {
     #[cfg(feature = "skia-native")]
     use raw_window_handle::HasRawWindowHandle;
 
+    pub use crate::skia_surface::ExternalFramebuffer;
+
     pub fn init() {
         match skia_gl::create_context() {
             Ok(gl_ctx) => {
                 match gl_ctx.into_direct_context() {
-                    Some(_dctx) => eprintln!("skia backend: init OK (DirectContext created)"),
-                    None => eprintln!("skia backend: init failed: couldn't create DirectContext"),
+                    Some(_dctx) => log::info!(target: "velox::skia", "init OK (DirectContext created)"),
+                    None => log::error!(target: "velox::skia", "init failed: couldn't create DirectContext"),
                 }
             }
-            Err(e) => eprintln!("skia backend: init failed: {}", e),
+            Err(e) => log::error!(target: "velox::skia", "init failed: {}", e),
         }
     }
 
@@ -276,14 +534,34 @@ pub mod skia_backend {
     }
 
     impl SkiaRenderer {
-        pub fn with_window(window: &impl HasRawWindowHandle, width: i32, height: i32) -> Result<Self, String> {
-            match skia_surface::create_window_surface_from_handle(window, width, height) {
-                Ok(s) => Ok(SkiaRenderer { surface: Some(s) }),
-                Err(e) => Err(e),
-            }
+        pub fn with_window(window: &impl HasRawWindowHandle, width: i32, height: i32) -> Result<Self, crate::Error> {
+            let surface = skia_surface::create_window_surface_from_handle(window, width, height)?;
+            Ok(SkiaRenderer { surface: Some(surface) })
         }
 
-        pub fn present(&mut self) -> Result<(), String> {
+        /// Render into a framebuffer of a GL context the app created and keeps (see
+        /// `ExternalFramebuffer`), which must be current on this thread. `load` resolves
+        /// GL function names in it, e.g. through `eglGetProcAddress`. The app makes the
+        /// context current before each `render` and swaps buffers after it.
+        pub fn with_external_gl<F>(load: F, framebuffer: ExternalFramebuffer) -> Result<Self, crate::Error>
+        where
+            F: FnMut(&str) -> *const std::ffi::c_void,
+        {
+            let surface = skia_surface::SkiaSurface::from_external_gl(load, framebuffer)?;
+            Ok(SkiaRenderer { surface: Some(surface) })
+        }
+
+        /// Draw `vnode`, styled by `sheet`, and flush it to the GPU. GL state the app
+        /// changed since the last frame is reset first; presenting is left to the app.
+        pub fn render(&mut self, vnode: &velox_dom::VNode, sheet: &velox_style::Stylesheet) -> Result<(), crate::Error> {
+            let Some(s) = &mut self.surface else { return Ok(()) };
+            let styled = crate::pipeline::style_tree(vnode, sheet, &|_, _| false);
+            s.reset_gl_state();
+            crate::skia_render::skia_impl::render_frame(s, &styled, sheet)?;
+            s.present()
+        }
+
+        pub fn present(&mut self) -> Result<(), crate::Error> {
             if let Some(s) = &mut self.surface {
                 s.present()
             } else {
@@ -291,7 +569,7 @@ pub mod skia_backend {
             }
         }
 
-        pub fn resize(&mut self, width: i32, height: i32) -> Result<(), String> {
+        pub fn resize(&mut self, width: i32, height: i32) -> Result<(), crate::Error> {
             if let Some(s) = &mut self.surface {
                 s.resize(width, height)
             } else {
@@ -304,6 +582,9 @@ pub mod skia_backend {
         fn backend_name(&self) -> &'static str {
             "skia"
         }
+        fn capabilities(&self) -> crate::Capabilities {
+            crate::capabilities::capabilities_for(crate::Backend::Skia)
+        }
         fn mount(&self, vnode: &velox_dom::VNode) -> crate::RenderTree {
             crate::build_render_tree(vnode)
         }
@@ -384,324 +665,333 @@ pub use events::Runtime as EventRuntime;
 
 /// Test helper: exercise a small Skia draw path (native-only).
 #[cfg(all(feature = "skia-native", unix))]
-pub fn skia_draw_test_frame() -> Result<(), String> {
+pub fn skia_draw_test_frame() -> Result<(), Error> {
     crate::skia_gl::draw_gpu_test_frame(256, 256)
 }
 
 /// Convenience wrapper to create a Skia `DirectContext` from the crate root.
 #[cfg(all(feature = "skia-native", unix))]
-pub fn create_direct_context() -> Result<skia_safe::gpu::DirectContext, String> {
+pub fn create_direct_context() -> Result<skia_safe::gpu::DirectContext, Error> {
     crate::skia_gl::create_direct_context()
 }
 
+/// Open a window on the backend chosen by `config` at runtime, falling back along
+/// `config::FALLBACK_CHAIN`. With only the stub available the view is built once and
+/// the function returns; it is never shown, so it is not mounted. Errors are setup failures of the selected backend (no window,
+/// no adapter, no surface); once the event loop runs, the process exits with it.
+pub fn run_app<F, G, H>(title: &str, config: &RendererConfig, mut make_view: F, on_event: G, get_title: H) -> Result<(), Error>
+where
+    F: FnMut(u32, u32) -> (velox_dom::VNode, Stylesheet) + 'static,
+    G: FnMut(&str, Option<&str>) + 'static,
+    H: FnMut() -> String + 'static,
+{
+    // Keeps backend diagnostics visible for apps that did not install a logger of their own.
+    logging::init_logging();
+    config::set_text_quality(config.text);
+    config::set_deterministic(config.deterministic);
+    match config::select_backend(config) {
+        #[cfg(feature = "wgpu")]
+        Backend::Wgpu => run_window_vnode(title, make_view, on_event, get_title),
+        #[cfg(feature = "skia-native")]
+        Backend::Skia | Backend::Raster => run_window_vnode_skia(title, make_view, on_event, get_title),
+        #[cfg(all(feature = "raster", not(feature = "skia-native")))]
+        Backend::Raster => run_window_vnode_raster(title, make_view, on_event, get_title),
+        _ => {
+            let _ = (on_event, get_title);
+            let (width, height) = config::window_size();
+            let (mut vnode, _sheet) = make_view(width, height);
+            components::expand(&mut vnode);
+            let mut counts = (0, 0);
+            summarize(&vnode, &mut counts);
+            log::warn!(
+                target: "velox::renderer",
+                "no window backend available for '{}'; built {} nodes",
+                title, counts.0
+            );
+            Ok(())
+        }
+    }
+}
+
 #[cfg(feature = "skia-native")]
-pub fn run_window_vnode_skia<F, G, H>(title: &str, mut make_view: F, mut on_event: G, mut get_title: H)
+pub fn run_window_vnode_skia<F, G, H>(title: &str, mut make_view: F, mut on_event: G, mut get_title: H) -> Result<(), Error>
 where
     F: FnMut(u32, u32) -> (velox_dom::VNode, Stylesheet) + 'static,
     G: FnMut(&str, Option<&str>) + 'static,
     H: FnMut() -> String + 'static,
 {
     use winit::dpi::PhysicalSize;
-    use winit::event::{ElementState, Event, MouseButton, StartCause, WindowEvent};
+    use winit::event::{ElementState, Event, KeyboardInput, MouseButton, MouseScrollDelta, StartCause, WindowEvent};
     use winit::event_loop::{ControlFlow, EventLoop};
     use winit::window::WindowBuilder;
-
-    struct SoftbufferPresenter {
-        _context: softbuffer::Context,
-        surface: softbuffer::Surface,
-        width: u32,
-        height: u32,
-        rgba: Vec<u8>,
-    }
-
-    impl SoftbufferPresenter {
-        fn new(window: &winit::window::Window, width: u32, height: u32) -> Result<Self, String> {
-            let context = unsafe {
-                softbuffer::Context::new(window)
-                    .map_err(|e| format!("softbuffer context failed: {}", e))?
-            };
-            let mut surface = unsafe {
-                softbuffer::Surface::new(&context, window)
-                    .map_err(|e| format!("softbuffer surface failed: {}", e))?
-            };
-            let w = width.max(1);
-            let h = height.max(1);
-            surface
-                .resize(std::num::NonZeroU32::new(w).unwrap(), std::num::NonZeroU32::new(h).unwrap())
-                .map_err(|e| format!("softbuffer resize failed: {}", e))?;
-            Ok(Self {
-                _context: context,
-                surface,
-                width: w,
-                height: h,
-                rgba: vec![0u8; (w as usize) * (h as usize) * 4],
-            })
-        }
-
-        fn resize(&mut self, width: u32, height: u32) -> Result<(), String> {
-            let w = width.max(1);
-            let h = height.max(1);
-            if w == self.width && h == self.height {
-                return Ok(());
-            }
-            self.surface
-                .resize(std::num::NonZeroU32::new(w).unwrap(), std::num::NonZeroU32::new(h).unwrap())
-                .map_err(|e| format!("softbuffer resize failed: {}", e))?;
-            self.width = w;
-            self.height = h;
-            self.rgba.resize((w as usize) * (h as usize) * 4, 0);
-            Ok(())
-        }
-
-        fn present(&mut self, skia_surface: &mut crate::skia_surface::SkiaSurface) -> Result<(), String> {
-            let width = skia_surface.width.max(1) as u32;
-            let height = skia_surface.height.max(1) as u32;
-            self.resize(width, height)?;
-
-            let info = skia_safe::ImageInfo::new(
-                (self.width as i32, self.height as i32),
-                skia_safe::ColorType::RGBA8888,
-                skia_safe::AlphaType::Premul,
-                None,
-            );
-            let row_bytes = (self.width * 4) as usize;
-            if !skia_surface.read_pixels(&info, &mut self.rgba, row_bytes, (0, 0)) {
-                return Err("skia: read_pixels failed".to_string());
-            }
-
-            let mut buffer = self
-                .surface
-                .buffer_mut()
-                .map_err(|e| format!("softbuffer buffer_mut failed: {}", e))?;
-            let pixels: &mut [u32] = &mut buffer;
-            let pixel_count = (self.width as usize) * (self.height as usize);
-            if pixels.len() < pixel_count {
-                return Err("softbuffer: buffer smaller than expected".to_string());
-            }
-            for (i, pixel) in pixels.iter_mut().take(pixel_count).enumerate() {
-                let base = i * 4;
-                let r = self.rgba[base] as u32;
-                let g = self.rgba[base + 1] as u32;
-                let b = self.rgba[base + 2] as u32;
-                *pixel = (r << 16) | (g << 8) | b;
-            }
-            buffer
-                .present()
-                .map_err(|e| format!("softbuffer present failed: {}", e))?;
-            Ok(())
+    use crate::present::SoftbufferPresenter;
+    use crate::view::{logical_size, recompute_targets, styled_view};
+
+    fn present_surface(
+        presenter: &mut SoftbufferPresenter,
+        rgba: &mut Vec<u8>,
+        skia_surface: &mut crate::skia_surface::SkiaSurface,
+    ) -> Result<(), Error> {
+        let width = skia_surface.width.max(1) as u32;
+        let height = skia_surface.height.max(1) as u32;
+        rgba.resize((width as usize) * (height as usize) * 4, 0);
+        let info = skia_safe::ImageInfo::new(
+            (width as i32, height as i32),
+            skia_safe::ColorType::RGBA8888,
+            skia_safe::AlphaType::Premul,
+            None,
+        );
+        let row_bytes = (width * 4) as usize;
+        if !skia_surface.read_pixels(&info, rgba, row_bytes, (0, 0)) {
+            return Err(Error::Present("skia: read_pixels failed".into()));
         }
+        presenter.present_rgba(rgba, width, height)
     }
 
+    // Dispatched events are kept for crash reports.
+    let mut on_event = move |handler: &str, payload: Option<&str>| {
+        crate::crash::record_event(handler, payload);
+        on_event(handler, payload);
+        // Effects held back by `FlushPolicy::Microtask` run once the handler is done.
+        velox_core::signal::end_turn();
+    };
     let event_loop = EventLoop::new();
+    // Restyle when the system text scale (the `rem` basis) changes.
+    let proxy = event_loop.create_proxy();
+    crate::text_scale::follow_system(move || proxy.send_event(()).is_ok());
+    // Rebuild the view when another thread calls `wake::wake`.
+    let proxy = event_loop.create_proxy();
+    crate::wake::set_waker(move || proxy.send_event(()).is_ok());
     let window = WindowBuilder::new()
-        .with_title(title)
-        .with_inner_size(PhysicalSize::new(800, 600))
+        .with_title(crate::config::window_title(title))
+        .with_inner_size(PhysicalSize::<u32>::from(crate::config::window_size()))
         .build(&event_loop)
-        .expect("failed to create window");
+        .map_err(|e| Error::Window(e.to_string()))?;
 
     let size = window.inner_size();
-    let mut renderer = match crate::skia_surface::SkiaSurface::new_raster(size.width as i32, size.height as i32) {
-        Ok(surface) => skia_backend::SkiaRenderer { surface: Some(surface) },
-        Err(e) => panic!("failed to create SkiaSurface: {}", e),
-    };
-    let mut presenter = match SoftbufferPresenter::new(&window, size.width, size.height) {
-        Ok(p) => p,
-        Err(e) => panic!("failed to create softbuffer presenter: {}", e),
-    };
+    let surface = crate::skia_surface::SkiaSurface::new_raster(size.width as i32, size.height as i32)?;
+    let mut renderer = skia_backend::SkiaRenderer { surface: Some(surface) };
+    let mut presenter = SoftbufferPresenter::new(&window, size.width, size.height)?;
+    let mut rgba: Vec<u8> = Vec::new();
     let mut scale_factor = window.scale_factor() as f32;
-    let mut mouse_pos = (0.0f32, 0.0f32);
-    let mut hovered_id: Option<u32> = None;
-    let mut click_targets: Vec<crate::events::ClickTarget> = Vec::new();
-    let mut hover_targets: Vec<crate::events::HoverTarget> = Vec::new();
-
-    fn logical_size(width: i32, height: i32, scale_factor: f32) -> (u32, u32) {
-        let w = ((width as f32) / scale_factor).round().max(1.0) as u32;
-        let h = ((height as f32) / scale_factor).round().max(1.0) as u32;
-        (w, h)
-    }
-
-    fn recompute_targets(
-        vnode: &velox_dom::VNode,
-        width: u32,
-        height: u32,
-        click_targets: &mut Vec<crate::events::ClickTarget>,
-        hover_targets: &mut Vec<crate::events::HoverTarget>,
-    ) {
-        let layout = velox_dom::layout::compute_layout(vnode, width as i32, height as i32);
-        click_targets.clear();
-        crate::events::collect_click_targets(vnode, &layout, click_targets);
-        hover_targets.clear();
-        crate::events::collect_hover_targets(vnode, &layout, hover_targets);
-    }
-
-    fn with_hover_ids(vnode: &velox_dom::VNode, next_id: &mut u32) -> velox_dom::VNode {
-        match vnode {
-            velox_dom::VNode::Text(_) => vnode.clone(),
-            velox_dom::VNode::Element { tag, props, children } => {
-                let mut new_props = props.clone();
-                if crate::events::is_hoverable(tag, props) {
-                    let id = *next_id;
-                    *next_id += 1;
-                    new_props = new_props.set("data-hover-id", id.to_string());
-                }
-                let new_children = children.iter().map(|c| with_hover_ids(c, next_id)).collect();
-                velox_dom::VNode::Element { tag: tag.clone(), props: new_props, children: new_children }
-            }
-        }
-    }
+    let mut view_state = crate::view::ViewState::default();
+    let mut tree: Option<RenderTree> = None;
+    let refs = crate::refs::Refs::current();
+    // Mice by device, and touch points by device and touch id.
+    let mut input = crate::input::ViewInput::<(winit::event::DeviceId, Option<u64>)>::new(Some(refs.clone()));
+    let mut visibility = crate::visibility::VisibilityTracker::new();
+    let mut live_regions = crate::live_regions::LiveRegions::new();
+    let mut modifiers = winit::event::ModifiersState::empty();
 
     if let Some(s) = &mut renderer.surface {
-        s.set_scale_factor(scale_factor);
-        let (vw, vh) = logical_size(s.width, s.height, scale_factor);
-        let (vnode_raw, sheet) = make_view(vw, vh);
-        let mut next_id = 1u32;
-        let vnode_tagged = with_hover_ids(&vnode_raw, &mut next_id);
-        let vnode = apply_styles_with_hover(
-            &vnode_tagged,
-            &sheet,
-            &|_tag, props| {
-                props
-                    .attrs
-                    .get("data-hover-id")
-                    .and_then(|v| v.parse::<u32>().ok())
-                    .map(|id| Some(id) == hovered_id)
-                    .unwrap_or(false)
-            },
-        );
-        recompute_targets(&vnode, vw, vh, &mut click_targets, &mut hover_targets);
+        s.set_scale_factor(crate::zoom::ui_scale(scale_factor));
+        let (vw, vh) = logical_size(s.width, s.height, crate::zoom::ui_scale(scale_factor));
+        let (vnode, _sheet) = styled_view(&mut make_view, vw, vh, input.hovered, &mut view_state);
+        // Not mounted until the first frame: build the handler table for these targets.
+        recompute_targets(&vnode, vw, vh, &crate::events::HandlerTable::from_tree(&vnode), &mut input.targets);
     }
 
+    let mut pacer = crate::pacing::FramePacer::new();
+    pacer.set_refresh_rate(crate::pacing::monitor_refresh_millihertz(&window));
+    let mut animator = crate::animation::LayoutAnimator::new();
+    let mut animating = false;
+    crate::replay::start_from_env();
+
     event_loop.run(move |event, _, control_flow| {
         *control_flow = ControlFlow::Wait;
+        let mut draw = false;
         match event {
-            Event::NewEvents(StartCause::Init) => {
+            Event::NewEvents(StartCause::Init) | Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
                 window.request_redraw();
             }
             Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
+                crate::replay::finish();
+                // The view goes away with the window: its unmounted hooks run now.
+                tree = None;
                 *control_flow = ControlFlow::Exit;
             }
+            Event::WindowEvent { event: WindowEvent::Occluded(occluded), .. } => {
+                if pacer.set_occluded(occluded) {
+                    // What the window showed while hidden is gone; present it whole.
+                    presenter.invalidate();
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::Moved(_), .. } => {
+                pacer.set_refresh_rate(crate::pacing::monitor_refresh_millihertz(&window));
+            }
             Event::WindowEvent { event: WindowEvent::Resized(new_size), .. } => {
+                pacer.set_size(new_size.width, new_size.height);
                 let _ = renderer.resize(new_size.width as i32, new_size.height as i32);
                 let _ = presenter.resize(new_size.width, new_size.height);
-                if let Some(s) = &mut renderer.surface {
-                    s.set_scale_factor(scale_factor);
-                    let (vw, vh) = logical_size(s.width, s.height, scale_factor);
-                    let (vnode_raw, sheet) = make_view(vw, vh);
-                    let mut next_id = 1u32;
-                    let vnode_tagged = with_hover_ids(&vnode_raw, &mut next_id);
-                    let vnode = apply_styles_with_hover(
-                        &vnode_tagged,
-                        &sheet,
-                        &|_tag, props| {
-                            props
-                                .attrs
-                                .get("data-hover-id")
-                                .and_then(|v| v.parse::<u32>().ok())
-                                .map(|id| Some(id) == hovered_id)
-                                .unwrap_or(false)
-                        },
-                    );
-                    recompute_targets(&vnode, vw, vh, &mut click_targets, &mut hover_targets);
+                // Draw the new size now rather than after the event queue drains; the
+                // frame lays the view out and refreshes the hit targets.
+                if pacer.resize_frame_due(std::time::Instant::now()) {
+                    draw = true;
+                } else {
+                    window.request_redraw();
                 }
-                window.request_redraw();
             }
             Event::WindowEvent { event: WindowEvent::ScaleFactorChanged { scale_factor: new_scale, new_inner_size, .. }, .. } => {
                 scale_factor = new_scale as f32;
+                pacer.set_refresh_rate(crate::pacing::monitor_refresh_millihertz(&window));
                 let _ = renderer.resize(new_inner_size.width as i32, new_inner_size.height as i32);
                 let _ = presenter.resize(new_inner_size.width, new_inner_size.height);
                 if let Some(s) = &mut renderer.surface {
-                    s.set_scale_factor(scale_factor);
-                    let (vw, vh) = logical_size(s.width, s.height, scale_factor);
-                    let (vnode_raw, sheet) = make_view(vw, vh);
-                    let mut next_id = 1u32;
-                    let vnode_tagged = with_hover_ids(&vnode_raw, &mut next_id);
-                    let vnode = apply_styles_with_hover(
-                        &vnode_tagged,
-                        &sheet,
-                        &|_tag, props| {
-                            props
-                                .attrs
-                                .get("data-hover-id")
-                                .and_then(|v| v.parse::<u32>().ok())
-                                .map(|id| Some(id) == hovered_id)
-                                .unwrap_or(false)
-                        },
-                    );
-                    recompute_targets(&vnode, vw, vh, &mut click_targets, &mut hover_targets);
+                    s.set_scale_factor(crate::zoom::ui_scale(scale_factor));
+                    let (vw, vh) = logical_size(s.width, s.height, crate::zoom::ui_scale(scale_factor));
+                    let (vnode, _sheet) = styled_view(&mut make_view, vw, vh, input.hovered, &mut view_state);
+                    let handlers = crate::events::HandlerTable::from_tree(&vnode);
+                    recompute_targets(&vnode, vw, vh, &handlers, &mut input.targets);
                 }
                 window.request_redraw();
             }
-            Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => {
-                mouse_pos = (
-                    position.x as f32 / scale_factor,
-                    position.y as f32 / scale_factor,
-                );
-                let now_hovered = crate::events::hit_test_hover(&hover_targets, mouse_pos.0, mouse_pos.1);
-                if now_hovered != hovered_id {
-                    hovered_id = now_hovered;
+            Event::WindowEvent { event: WindowEvent::CursorMoved { device_id, position, .. }, .. } => {
+                let scale = crate::zoom::ui_scale(scale_factor);
+                // Hover is hit-tested once the queued events are handled (`MainEventsCleared`).
+                if input.moved((device_id, None), position.x as f32 / scale, position.y as f32 / scale, &mut on_event) {
                     window.request_redraw();
                 }
             }
-            Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
-                if let Some((handler, payload_opt)) = crate::events::hit_test_click(&click_targets, mouse_pos.0, mouse_pos.1) {
-                    let payload_owned = payload_opt
-                        .map(|p| p.to_string())
-                        .unwrap_or_else(|| format!("{{\"x\":{},\"y\":{}}}", mouse_pos.0, mouse_pos.1));
-                    on_event(handler, Some(&payload_owned));
-                    if let Some(s) = &mut renderer.surface {
-                        let (vw, vh) = logical_size(s.width, s.height, scale_factor);
-                        let (vnode_raw, sheet) = make_view(vw, vh);
-                        let mut next_id = 1u32;
-                        let vnode_tagged = with_hover_ids(&vnode_raw, &mut next_id);
-                        let vnode = apply_styles_with_hover(
-                            &vnode_tagged,
-                            &sheet,
-                            &|_tag, props| {
-                                props
-                                    .attrs
-                                    .get("data-hover-id")
-                                    .and_then(|v| v.parse::<u32>().ok())
-                                    .map(|id| Some(id) == hovered_id)
-                                    .unwrap_or(false)
-                            },
-                        );
-                        recompute_targets(&vnode, vw, vh, &mut click_targets, &mut hover_targets);
-                    }
-                    window.set_title(&get_title());
+            Event::WindowEvent { event: WindowEvent::CursorLeft { .. }, .. } => {
+                if input.left() {
                     window.request_redraw();
                 }
             }
-            Event::RedrawRequested(_) => {
-                // Render VNode -> Skia frame and present.
-                if let Some(s) = &mut renderer.surface {
-                    s.set_scale_factor(scale_factor);
-                    let (vw, vh) = logical_size(s.width, s.height, scale_factor);
-                    let (vnode_raw, sheet) = make_view(vw, vh);
-                    let mut next_id = 1u32;
-                    let vnode_tagged = with_hover_ids(&vnode_raw, &mut next_id);
-                    let vnode = apply_styles_with_hover(
-                        &vnode_tagged,
-                        &sheet,
-                        &|_tag, props| {
-                            props
-                                .attrs
-                                .get("data-hover-id")
-                                .and_then(|v| v.parse::<u32>().ok())
-                                .map(|id| Some(id) == hovered_id)
-                                .unwrap_or(false)
-                        },
-                    );
-                    recompute_targets(&vnode, vw, vh, &mut click_targets, &mut hover_targets);
-                    if let Err(e) = crate::skia_render::skia_impl::render_frame(s, &vnode, &sheet) {
-                        eprintln!("skia render error: {}", e);
-                    }
-                    if let Err(e) = presenter.present(s) {
-                        eprintln!("skia present error: {}", e);
+            Event::WindowEvent { event: WindowEvent::Touch(touch), .. } => {
+                let scale = crate::zoom::ui_scale(scale_factor);
+                let sample = crate::pen::from_touch(&touch, touch.location.x as f32 / scale, touch.location.y as f32 / scale);
+                if input.touch((touch.device_id, Some(touch.id)), sample, &mut on_event) {
+                    window.request_redraw();
+                }
+            }
+            // macOS trackpad gestures, centered on the cursor.
+            Event::WindowEvent { event: WindowEvent::TouchpadMagnify { delta, .. }, .. } => {
+                if input.trackpad(|x, y| crate::gesture::Gesture::magnify(delta as f32, x, y), &mut on_event) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::TouchpadRotate { delta, .. }, .. } => {
+                if input.trackpad(|x, y| crate::gesture::Gesture::rotate_degrees(delta, x, y), &mut on_event) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { device_id, state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
+                // The frame this asks for restyles the view and refreshes the hit targets.
+                if input.pressed((device_id, None), &mut on_event).handled {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { device_id, state: ElementState::Released, button: MouseButton::Left, .. }, .. } => {
+                if input.released((device_id, None), &mut on_event) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::MouseWheel { delta, .. }, .. } => {
+                // Over a viewport the wheel pans it, or zooms it with Ctrl; elsewhere it
+                // scrolls the window.
+                let scale = crate::zoom::ui_scale(scale_factor);
+                let (dx, dy) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x * crate::viewport::LINE_PIXELS, y * crate::viewport::LINE_PIXELS),
+                    MouseScrollDelta::PixelDelta(p) => (p.x as f32 / scale, p.y as f32 / scale),
+                };
+                if input.wheel((dx, dy), modifiers.ctrl(), &mut on_event) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::ModifiersChanged(m), .. } => {
+                modifiers = m;
+            }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(key), .. }, .. }, .. } => {
+                if let Some(shortcut) = crate::keys::shortcut(key, modifiers)
+                    && input.shortcut(&shortcut, &mut on_event)
+                {
+                    window.request_redraw();
+                }
+            }
+            Event::UserEvent(()) => window.request_redraw(),
+            // The moves queued since the last pass update hover once, at the last position.
+            Event::MainEventsCleared => {
+                if input.update_hover() {
+                    window.request_redraw();
+                }
+            }
+            Event::RedrawRequested(_) => draw = true,
+            // Input is handled and the frame drawn: run idle tasks, then keep drawing at
+            // the refresh rate while a transition or spring runs.
+            Event::RedrawEventsCleared => {
+                if velox_core::idle::has_pending() && !pacer.is_paused() {
+                    let deadline = pacer.idle_deadline(std::time::Instant::now(), animating);
+                    if velox_core::idle::run_idle(deadline) > 0 {
+                        // Idle work usually updates state the view shows.
+                        window.request_redraw();
                     }
                 }
+                if animating && let Some(at) = pacer.next_frame_at() {
+                    *control_flow = ControlFlow::WaitUntil(at);
+                }
             }
             _ => {}
         }
+        // A script played with `replay::play_in_window` takes the same paths as real input.
+        if input.replay_due(std::time::Instant::now(), &mut on_event) {
+            window.request_redraw();
+        }
+        // Render VNode -> Skia frame and present.
+        if draw && pacer.begin_frame(std::time::Instant::now()) {
+            if let Some(s) = &mut renderer.surface {
+                s.set_scale_factor(crate::zoom::ui_scale(scale_factor));
+                let (vw, vh) = logical_size(s.width, s.height, crate::zoom::ui_scale(scale_factor));
+                let (vnode, _sheet) = styled_view(&mut make_view, vw, vh, input.hovered, &mut view_state);
+                if let Some(title) = view_state.title_change(&mut get_title) {
+                    window.set_title(&title);
+                }
+                input.update_shortcuts(&vnode);
+                // Mount the view on the first frame and patch it on later ones, running the
+                // lifecycle hooks; state they set shows in the next frame.
+                if crate::commit_frame(&mut tree, &vnode) {
+                    window.request_redraw();
+                }
+                let handlers = &tree.as_ref().expect("commit_frame mounts the tree").handlers;
+                recompute_targets(&vnode, vw, vh, handlers, &mut input.targets);
+                let mut layout = crate::pipeline::layout_tree(&vnode, vw, vh);
+                animating = animator.animate(&vnode, &mut layout, velox_core::clock::now()) || view_state.transitions.is_running()
+                    || velox_core::frame::is_running();
+                if refs.update(&vnode, &layout, vw, vh) {
+                    // A resize observer ran; draw again with the state it set.
+                    window.request_redraw();
+                }
+                // The frame is laid out: `next_tick` callbacks can measure it.
+                if velox_core::signal::run_next_tick() > 0 {
+                    window.request_redraw();
+                }
+                crate::refs::scroll_layout(&mut layout, refs.scroll_offset());
+                let viewport = velox_dom::layout::Rect { x: 0, y: 0, w: vw as i32, h: vh as i32 };
+                for ev in visibility.update(&vnode, &layout, viewport) {
+                    on_event(&ev.handler, ev.payload.as_deref());
+                    window.request_redraw();
+                }
+                crate::crash::record_tree(&vnode);
+                crate::stats::record_frame(&vnode, animator.tracked() + view_state.transitions.tracked());
+                for announcement in live_regions.update(&vnode) {
+                    crate::live_regions::announce(&announcement);
+                }
+                if let Err(e) = crate::skia_render::skia_impl::render_frame_with_layout(s, &vnode, &layout) {
+                    log::error!(target: "velox::skia", "render error: {}", e);
+                }
+                if let Err(e) = present_surface(&mut presenter, &mut rgba, s) {
+                    log::error!(target: "velox::skia", "present error: {}", e);
+                }
+            }
+        }
+        // Wake up for the next scripted step.
+        if let Some(at) = crate::replay::next_at() {
+            match *control_flow {
+                ControlFlow::ExitWithCode(_) => {}
+                ControlFlow::WaitUntil(t) if t <= at => {}
+                _ => *control_flow = ControlFlow::WaitUntil(at),
+            }
+        }
     });
 }
 
@@ -727,7 +1017,7 @@ fn load_system_font() -> Option<ab_glyph::FontArc> {
 }
 
 #[cfg(feature = "wgpu")]
-pub fn run_window_vnode<F, G, H>(title: &str, mut make_view: F, mut on_event: G, mut get_title: H)
+pub fn run_window_vnode<F, G, H>(title: &str, mut make_view: F, mut on_event: G, mut get_title: H) -> Result<(), Error>
 where
     F: FnMut(u32, u32) -> (velox_dom::VNode, Stylesheet) + 'static,
     G: FnMut(&str, Option<&str>) + 'static,
@@ -739,33 +1029,35 @@ where
     use winit::window::WindowBuilder;
 
     // Setup window
+    // Dispatched events are kept for crash reports.
+    let mut on_event = move |handler: &str, payload: Option<&str>| {
+        crate::crash::record_event(handler, payload);
+        on_event(handler, payload);
+        // Effects held back by `FlushPolicy::Microtask` run once the handler is done.
+        velox_core::signal::end_turn();
+    };
+    let mut make_view = move |width: u32, height: u32| {
+        let (mut vnode, sheet) = make_view(width, height);
+        crate::components::expand(&mut vnode);
+        (vnode, sheet)
+    };
     let event_loop = EventLoop::new();
+    // Restyle when the system text scale (the `rem` basis) changes.
+    let proxy = event_loop.create_proxy();
+    crate::text_scale::follow_system(move || proxy.send_event(()).is_ok());
+    // Rebuild the view when another thread calls `wake::wake`.
+    let proxy = event_loop.create_proxy();
+    crate::wake::set_waker(move || proxy.send_event(()).is_ok());
     let window = WindowBuilder::new()
-        .with_title(title)
-        .with_inner_size(PhysicalSize::new(800, 600))
+        .with_title(crate::config::window_title(title))
+        .with_inner_size(PhysicalSize::<u32>::from(crate::config::window_size()))
         .build(&event_loop)
-        .expect("window");
+        .map_err(|e| Error::Window(e.to_string()))?;
     let mut size = window.inner_size();
     let _title_owned = title.to_string();
 
     // WGPU setup (reuse pipeline from run_window)
-    let instance = wgpu::Instance::default();
-    let surface = unsafe { instance.create_surface(&window) }.expect("surface");
-    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-        power_preference: wgpu::PowerPreference::HighPerformance,
-        compatible_surface: Some(&surface),
-        force_fallback_adapter: false,
-    }))
-    .expect("adapter");
-    let (device, queue) = pollster::block_on(adapter.request_device(
-        &wgpu::DeviceDescriptor {
-            label: Some("velox-device"),
-            features: wgpu::Features::empty(),
-            limits: wgpu::Limits::default(),
-        },
-        None,
-    ))
-    .expect("device");
+    let (surface, adapter, device, queue) = wgpu_backend::window_gpu(&window)?;
 
     if size.width == 0 || size.height == 0 {
         size = PhysicalSize::new(800, 600);
@@ -774,7 +1066,8 @@ where
     let caps = surface.get_capabilities(&adapter);
     let format = caps.formats[0];
     let mut config = wgpu::SurfaceConfiguration {
-        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        // COPY_SRC lets `pixels::read_pixels` read the swapchain image back.
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
         format,
         width: size.width,
         height: size.height,
@@ -842,8 +1135,7 @@ where
     // (helpers defined once above)
     fn has_class(props: &velox_dom::Props, class: &str) -> bool {
         props
-            .attrs
-            .get("class")
+            .str("class")
             .map(|s| s.split_whitespace().any(|c| c == class))
             .unwrap_or(false)
     }
@@ -856,7 +1148,7 @@ where
             return Some(layout.rect);
         }
         match vnode {
-            velox_dom::VNode::Element { children, .. } => {
+            velox_dom::VNode::Element { children, .. } | velox_dom::VNode::Fragment(children) => {
                 for (i, ch) in children.iter().enumerate() {
                     if let Some(lc) = layout.children.get(i) {
                         if let Some(r) = find_rect_pred(ch, lc, pred) {
@@ -866,36 +1158,11 @@ where
                 }
                 None
             }
-            velox_dom::VNode::Text(_) => None,
-        }
-    }
-    fn find_text_in_class(vnode: &velox_dom::VNode, class: &str) -> Option<String> {
-        fn first_text(node: &velox_dom::VNode) -> Option<String> {
-            match node {
-                velox_dom::VNode::Text(t) => {
-                    let s = t.trim();
-                    if s.is_empty() { None } else { Some(s.to_string()) }
-                }
-                velox_dom::VNode::Element { children, .. } => {
-                    for ch in children { if let Some(s) = first_text(ch) { return Some(s); } }
-                    None
-                }
-            }
-        }
-        match vnode {
-            velox_dom::VNode::Element { props, children, .. } => {
-                if has_class(props, class) {
-                    return first_text(vnode);
-                }
-                for ch in children { if let Some(s) = find_text_in_class(ch, class) { return Some(s); } }
-                None
-            }
-            _ => None,
+            velox_dom::VNode::Text(_) | velox_dom::VNode::Component { .. } => None,
         }
     }
     let mut btn_rect: (f32, f32, f32, f32) = (0.0, 0.0, 0.0, 0.0);
     let mut hovered = false;
-    let mut mouse = (0.0f32, 0.0f32);
     let mut bg_color: [f32; 4] = [0.12, 0.12, 0.14, 1.0];
     let mut text_color: [f32; 4] = [0.90, 0.93, 0.95, 1.0];
     let mut font_size: f32 = 18.0;
@@ -905,7 +1172,8 @@ where
     let mut btn_handler: Option<String> = None;
     let mut btn_pad_left: f32 = 0.0;
     let mut btn_pad_top: f32 = 0.0;
-    let mut click_targets: Vec<(f32,f32,f32,f32,String, Option<String>)> = Vec::new();
+    // Mice by device, in physical pixels like the layout below.
+    let mut input = crate::input::ViewInput::<winit::event::DeviceId>::new(None);
 
     // Keep previous vnode around so we can attempt keyed reconciliation between frames.
     let mut prev_vnode: Option<velox_dom::VNode> = None;
@@ -929,7 +1197,9 @@ where
         if let Some(sys) = load_system_font() { fonts.push(sys); }
         if let Ok(f) = ab_glyph::FontArc::try_from_slice(include_bytes!("../assets/DejaVuSans.ttf")) { fonts.push(f); }
         if let Ok(f) = ab_glyph::FontArc::try_from_slice(include_bytes!("../assets/NotoSans-Regular.ttf")) { fonts.push(f); }
-        if fonts.is_empty() { None } else { Some((wgpu_glyph::GlyphBrushBuilder::using_fonts(fonts).build(&device, format), wgpu::util::StagingBelt::new(1024))) }
+        // glyph_brush rasterizes grayscale coverage; Subpixel falls back to it, Aliased samples nearest.
+        let filter = if crate::config::text_quality().antialias == crate::config::TextAntialias::Aliased { wgpu::FilterMode::Nearest } else { wgpu::FilterMode::Linear };
+        if fonts.is_empty() { None } else { Some((wgpu_glyph::GlyphBrushBuilder::using_fonts(fonts).texture_filter_method(filter).build(&device, format), wgpu::util::StagingBelt::new(1024))) }
     };
 
     // style helpers
@@ -969,48 +1239,6 @@ where
         }
         default
     }
-    fn parse_text_align(style: Option<&str>) -> wgpu_glyph::HorizontalAlign {
-        if let Some(v) = style_lookup(style, "text-align") {
-            let v = v.to_ascii_lowercase();
-            if v.contains("center") { return wgpu_glyph::HorizontalAlign::Center; }
-            if v.contains("right") { return wgpu_glyph::HorizontalAlign::Right; }
-        }
-        wgpu_glyph::HorizontalAlign::Left
-    }
-    fn parse_font_family_id(style: Option<&str>) -> usize {
-        if let Some(v) = style_lookup(style, "font-family") {
-            let v = v.to_ascii_lowercase();
-            if v.contains("dejavu") { return 1; }
-            if v.contains("noto") { return 2; }
-        }
-        0
-    }
-    fn style_lookup<'a>(style: Option<&'a str>, key: &str) -> Option<&'a str> {
-        let s = style?;
-        for decl in s.split(';') {
-            let d = decl.trim(); if d.is_empty() { continue; }
-            if let Some((k, v)) = d.split_once(':') { if k.trim() == key { return Some(v.trim()); } }
-        }
-        None
-    }
-    fn parse_font_weight(style: Option<&str>) -> bool {
-        if let Some(v) = style_lookup(style, "font-weight") {
-            if v.eq_ignore_ascii_case("bold") { return true; }
-            if let Ok(n) = v.parse::<i32>() { return n >= 600; }
-        }
-        false
-    }
-    #[derive(Clone, Copy, Default)]
-    struct TextDecor { underline: bool, line_through: bool }
-    fn parse_text_decoration(style: Option<&str>) -> TextDecor {
-        if let Some(v) = style_lookup(style, "text-decoration") {
-            let mut td = TextDecor::default();
-            for part in v.split_whitespace() { let p = part.trim().to_ascii_lowercase(); if p == "underline" { td.underline = true; } else if p == "line-through" { td.line_through = true; } }
-            return td;
-        }
-        TextDecor::default()
-    }
-    fn approx_text_width_px(s: &str, font_size: f32) -> f32 { (s.chars().count() as f32) * font_size * 0.6 }
 
     // Helper to find the first element matching a predicate and return its rect and props
     fn find_node_and_rect<'a>(
@@ -1024,7 +1252,7 @@ where
             }
         }
         match vnode {
-            velox_dom::VNode::Element { children, .. } => {
+            velox_dom::VNode::Element { children, .. } | velox_dom::VNode::Fragment(children) => {
                 for (i, ch) in children.iter().enumerate() {
                     if let Some(lc) = layout.children.get(i) {
                         if let Some(found) = find_node_and_rect(ch, lc, pred) {
@@ -1034,7 +1262,7 @@ where
                 }
                 None
             }
-            velox_dom::VNode::Text(_) => None,
+            velox_dom::VNode::Text(_) | velox_dom::VNode::Component { .. } => None,
         }
     }
 
@@ -1055,53 +1283,36 @@ where
         btn_handler: &mut Option<String>,
         btn_pad_left: &mut f32,
         btn_pad_top: &mut f32,
-        click_targets: &mut Vec<(f32,f32,f32,f32,String, Option<String>)>,
+        targets: &mut crate::events::Targets,
         queue: &wgpu::Queue,
         vbuf: &wgpu::Buffer,
     ) {
         let is_hovered = |tag: &str, props: &velox_dom::Props| -> bool {
-            hovered_btn && (props.attrs.contains_key("on:click") || tag == "button" || has_class(props, "btn"))
+            hovered_btn && (props.contains("on:click") || tag == "button" || has_class(props, "btn"))
         };
         let vnode = apply_styles_with_hover(vnode_raw, sheet, &is_hovered);
         // root styles
         if let velox_dom::VNode::Element { ref props, .. } = vnode {
-            *bg_color = parse_color(props.attrs.get("style").map(|s| s.as_str()), "background", *bg_color);
-            *text_color = parse_color(props.attrs.get("style").map(|s| s.as_str()), "color", *text_color);
-            *font_size = parse_px_f32(props.attrs.get("style").map(|s| s.as_str()), "font-size", *font_size);
+            *bg_color = parse_color(props.str("style"), "background", *bg_color);
+            *text_color = parse_color(props.str("style"), "color", *text_color);
+            *font_size = parse_px_f32(props.str("style"), "font-size", *font_size);
         }
         // layout and clickable target
         let layout = velox_dom::layout::compute_layout(&vnode, viewport_w as i32, viewport_h as i32);
         let pred = |n: &velox_dom::VNode| match n {
             velox_dom::VNode::Element { props, tag, .. } => {
-                props.attrs.contains_key("on:click") || *tag == "button" || has_class(props, "btn")
+                props.contains("on:click") || *tag == "button" || has_class(props, "btn")
             }
             _ => false,
         };
-        // collect all clickable targets for event hit testing
-        fn collect_clicks(vnode: &velox_dom::VNode, layout: &velox_dom::layout::LayoutNode, out: &mut Vec<(f32,f32,f32,f32,String, Option<String>)>) {
-            match vnode {
-                velox_dom::VNode::Text(_) => {}
-                velox_dom::VNode::Element { props, children, .. } => {
-                    if let Some(handler) = props.attrs.get("on:click").cloned() {
-                        let payload = props.attrs.get("on:click-payload").cloned();
-                        let r = layout.rect;
-                        out.push((r.x as f32, r.y as f32, (r.x + r.w) as f32, (r.y + r.h) as f32, handler, payload));
-                    }
-                    for (i,ch) in children.iter().enumerate() {
-                        if let Some(lc) = layout.children.get(i) { collect_clicks(ch, lc, out); }
-                    }
-                }
-            }
-        }
-        click_targets.clear();
-        collect_clicks(&vnode, &layout, click_targets);
+        targets.collect(&vnode, &layout, &crate::events::HandlerTable::from_tree(&vnode));
         if let Some((r, props, children)) = find_node_and_rect(&vnode, &layout, &pred) {
             *btn_rect = (r.x as f32, r.y as f32, (r.x + r.w) as f32, (r.y + r.h) as f32);
             // element styles
-            let style_str = props.attrs.get("style").map(|s| s.as_str());
+            let style_str = props.str("style");
             *btn_color = parse_color(style_str, "background", *btn_color);
             *btn_text_color = parse_color(style_str, "color", *text_color);
-            *btn_handler = props.attrs.get("on:click").cloned();
+            *btn_handler = props.str("on:click").map(str::to_string);
             // padding for label position
             let pad_left = parse_px_f32(style_str, "padding-left", parse_px_f32(style_str, "padding", 0.0));
             let pad_top = parse_px_f32(style_str, "padding-top", parse_px_f32(style_str, "padding", 0.0));
@@ -1132,51 +1343,146 @@ where
 
     {
         let (vnode_raw, sheet) = make_view(config.width, config.height);
-        recompute_from_vnode(&vnode_raw, &sheet, false, config.width, config.height, &mut bg_color, &mut text_color, &mut font_size, &mut btn_rect, &mut btn_color, &mut btn_text_color, &mut btn_text, &mut btn_handler, &mut btn_pad_left, &mut btn_pad_top, &mut click_targets, &queue, &vbuf);
-        // set initial title from SFC state
-        window.set_title(&get_title());
+        recompute_from_vnode(&vnode_raw, &sheet, false, config.width, config.height, &mut bg_color, &mut text_color, &mut font_size, &mut btn_rect, &mut btn_color, &mut btn_text_color, &mut btn_text, &mut btn_handler, &mut btn_pad_left, &mut btn_pad_top, &mut input.targets, &queue, &vbuf);
     }
+    // Set the initial title from SFC state; later frames keep it in sync.
+    let mut shown_title = get_title();
+    window.set_title(&shown_title);
+
+    let mut pacer = crate::pacing::FramePacer::new();
+    pacer.set_refresh_rate(crate::pacing::monitor_refresh_millihertz(&window));
+    let mut frame_arena = crate::arena::FrameArena::new();
+    let mut layout_animator = crate::animation::LayoutAnimator::new();
+    let mut enter_leave = crate::enter_leave::EnterLeave::new();
+    let mut visibility = crate::visibility::VisibilityTracker::new();
+    let mut live_regions = crate::live_regions::LiveRegions::new();
+    let mut modifiers = winit::event::ModifiersState::empty();
+    let mut targets_stale = false;
+    let mut tree: Option<RenderTree> = None;
+    crate::replay::start_from_env();
 
-    let _ = event_loop.run(move |event, _, control_flow| match event {
-        Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => { *control_flow = ControlFlow::Exit; }
-        Event::WindowEvent { event: WindowEvent::Resized(sz), .. } => {
-            config.width = sz.width.max(1);
-            config.height = sz.height.max(1);
-            surface.configure(&device, &config);
-            let (vnode_raw, sheet) = make_view(config.width, config.height);
-            recompute_from_vnode(&vnode_raw, &sheet, hovered, config.width, config.height, &mut bg_color, &mut text_color, &mut font_size, &mut btn_rect, &mut btn_color, &mut btn_text_color, &mut btn_text, &mut btn_handler, &mut btn_pad_left, &mut btn_pad_top, &mut click_targets, &queue, &vbuf);
-            window.request_redraw();
-        }
-        Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => {
-            mouse = (position.x as f32, position.y as f32);
-            let (x0,y0,x1,y1) = btn_rect;
-            let h = mouse.0>=x0&&mouse.0<=x1&&mouse.1>=y0&&mouse.1<=y1;
-            if h!=hovered {
-                hovered=h;
-                // recompute styles with hover
-                let (vnode_raw, sheet) = make_view(config.width, config.height);
-                recompute_from_vnode(&vnode_raw, &sheet, hovered, config.width, config.height, &mut bg_color, &mut text_color, &mut font_size, &mut btn_rect, &mut btn_color, &mut btn_text_color, &mut btn_text, &mut btn_handler, &mut btn_pad_left, &mut btn_pad_top, &mut click_targets, &queue, &vbuf);
+    event_loop.run(move |event, _, control_flow| {
+        let mut draw = false;
+        match event {
+            Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
+                crate::replay::finish();
+                // The view goes away with the window: its unmounted hooks run now.
+                tree = None;
+                *control_flow = ControlFlow::Exit;
             }
-        }
-        Event::WindowEvent { event: WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
-            // dispatch to first matching clickable rect
-            if let Some((_,_,_,_, name, payload_opt)) = click_targets.iter().find(|(x0,y0,x1,y1,_,_)| mouse.0>=*x0&&mouse.0<=*x1&&mouse.1>=*y0&&mouse.1<=*y1) {
-                // Prepare payload: prefer explicit payload from attribute, otherwise forward mouse coords as JSON
-                let payload_owned = payload_opt.clone().unwrap_or_else(|| format!("{{\"x\":{},\"y\":{}}}", mouse.0, mouse.1));
-                on_event(name, Some(&payload_owned));
-                let (vnode_raw, sheet) = make_view(config.width, config.height);
-                recompute_from_vnode(&vnode_raw, &sheet, hovered, config.width, config.height, &mut bg_color, &mut text_color, &mut font_size, &mut btn_rect, &mut btn_color, &mut btn_text_color, &mut btn_text, &mut btn_handler, &mut btn_pad_left, &mut btn_pad_top, &mut click_targets, &queue, &vbuf);
-                window.set_title(&get_title());
+            Event::WindowEvent { event: WindowEvent::Occluded(occluded), .. } => {
+                if pacer.set_occluded(occluded) { window.request_redraw(); }
+            }
+            Event::WindowEvent { event: WindowEvent::Moved(_) | WindowEvent::ScaleFactorChanged { .. }, .. } => {
+                pacer.set_refresh_rate(crate::pacing::monitor_refresh_millihertz(&window));
+            }
+            Event::WindowEvent { event: WindowEvent::Resized(sz), .. } => {
+                pacer.set_size(sz.width, sz.height);
+                config.width = sz.width.max(1);
+                config.height = sz.height.max(1);
+                surface.configure(&device, &config);
+                // Draw the new size now rather than after the event queue drains, and lay out
+                // the hit targets once per drawn frame, not once per resize event.
+                targets_stale = true;
+                if pacer.resize_frame_due(std::time::Instant::now()) {
+                    draw = true;
+                } else {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::CursorMoved { device_id, position, .. }, .. } => {
+                // Hover is hit-tested once the queued events are handled (`MainEventsCleared`).
+                if input.moved(device_id, position.x as f32, position.y as f32, &mut on_event) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::CursorLeft { .. }, .. } => {
+                input.left();
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { device_id, state: ElementState::Pressed, button: MouseButton::Left, .. }, .. } => {
+                // The next frame rebuilds the view and the hit targets.
+                if input.pressed(device_id, &mut on_event).handled {
+                    targets_stale = true;
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::MouseInput { device_id, state: ElementState::Released, button: MouseButton::Left, .. }, .. } => {
+                if input.released(device_id, &mut on_event) {
+                    targets_stale = true;
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent { event: WindowEvent::ModifiersChanged(m), .. } => { modifiers = m; }
+            Event::WindowEvent { event: WindowEvent::KeyboardInput { input: winit::event::KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(key), .. }, .. }, .. } => {
+                if let Some(shortcut) = crate::keys::shortcut(key, modifiers)
+                    && input.shortcut(&shortcut, &mut on_event)
+                {
+                    targets_stale = true;
+                    window.request_redraw();
+                }
+            }
+            Event::UserEvent(()) => {
+                targets_stale = true;
                 window.request_redraw();
             }
+            Event::RedrawRequested(_) => draw = true,
+            // Redraw continuously at the monitor's refresh rate, running idle tasks between
+            // frames; sleep while hidden or minimized.
+            Event::MainEventsCleared => {
+                // The moves queued since the last pass update hover once, at the last position.
+                input.update_hover();
+                if let Some((mx, my)) = input.pointer {
+                    let (x0,y0,x1,y1) = btn_rect;
+                    let h = mx>=x0&&mx<=x1&&my>=y0&&my<=y1;
+                    if h!=hovered {
+                        hovered=h;
+                        // recompute styles with hover
+                        let (vnode_raw, sheet) = make_view(config.width, config.height);
+                        recompute_from_vnode(&vnode_raw, &sheet, hovered, config.width, config.height, &mut bg_color, &mut text_color, &mut font_size, &mut btn_rect, &mut btn_color, &mut btn_text_color, &mut btn_text, &mut btn_handler, &mut btn_pad_left, &mut btn_pad_top, &mut input.targets, &queue, &vbuf);
+                    }
+                }
+                match pacer.next_frame_at() {
+                    Some(at) if at <= std::time::Instant::now() => {
+                        window.request_redraw();
+                        *control_flow = ControlFlow::WaitUntil(std::time::Instant::now() + pacer.frame_interval());
+                    }
+                    Some(at) => {
+                        // Spend the time until the next frame on idle tasks.
+                        if velox_core::idle::has_pending() {
+                            velox_core::idle::run_idle(pacer.idle_deadline(std::time::Instant::now(), true));
+                        }
+                        *control_flow = ControlFlow::WaitUntil(at);
+                    }
+                    None => *control_flow = ControlFlow::Wait,
+                }
+            }
+            _ => {}
         }
-        Event::RedrawRequested(_) => {
+        if input.replay_due(std::time::Instant::now(), &mut on_event) {
+            targets_stale = true;
+            window.request_redraw();
+        }
+        if draw && pacer.begin_frame(std::time::Instant::now()) {
+            if targets_stale {
+                let (vnode_raw, sheet) = make_view(config.width, config.height);
+                recompute_from_vnode(&vnode_raw, &sheet, hovered, config.width, config.height, &mut bg_color, &mut text_color, &mut font_size, &mut btn_rect, &mut btn_color, &mut btn_text_color, &mut btn_text, &mut btn_handler, &mut btn_pad_left, &mut btn_pad_top, &mut input.targets, &queue, &vbuf);
+                targets_stale = false;
+            }
             let frame = match surface.get_current_texture() { Ok(f)=>f, Err(wgpu::SurfaceError::Lost)=>{ surface.configure(&device, &config); return; }, Err(_) => return };
             let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
             let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("velox-enc") });
             // Build and draw quads for all clickable buttons
             // Compute vnode + layout once for this frame
-            let (frame_vnode_raw, frame_sheet) = make_view(config.width, config.height);
+            let now = velox_core::clock::now();
+            velox_core::frame::tick(now);
+            let (mut frame_vnode_raw, frame_sheet) = make_view(config.width, config.height);
+            let frame_title = crate::pipeline::take_title(&mut frame_vnode_raw).unwrap_or_else(&mut get_title);
+            if frame_title != shown_title {
+                window.set_title(&frame_title);
+                shown_title = frame_title;
+            }
+            input.update_shortcuts(&frame_vnode_raw);
+            enter_leave.prepare(&mut frame_vnode_raw, now);
             // Attempt keyed reconciliation with prior frame to prefer node reuse when `key` props are present
             let frame_vnode_reconciled = if let Some(mut old) = prev_vnode.take() {
                 match (&mut old, &frame_vnode_raw) {
@@ -1190,257 +1496,181 @@ where
             } else {
                 frame_vnode_raw.clone()
             };
-            let frame_vnode = apply_styles_with_hover(&frame_vnode_reconciled, &frame_sheet, &|tag, props| hovered && (props.attrs.contains_key("on:click") || tag == "button" || has_class(props, "btn")));
-            fn collect_click_nodes<'a>(vnode: &'a velox_dom::VNode, layout: &velox_dom::layout::LayoutNode, out: &mut Vec<(velox_dom::layout::Rect, &'a velox_dom::Props, &'a [velox_dom::VNode])>) {
-                match vnode {
-                    velox_dom::VNode::Text(_) => {}
-                    velox_dom::VNode::Element { props, children, .. } => {
-                        if props.attrs.contains_key("on:click") { out.push((layout.rect, props, children.as_slice())); }
-                        for (i, ch) in children.iter().enumerate() { if let Some(lc) = layout.children.get(i) { collect_click_nodes(ch, lc, out); } }
+            let mut frame_vnode = crate::pipeline::style_tree(&frame_vnode_reconciled, &frame_sheet, &|tag, props| hovered && (props.contains("on:click") || tag == "button" || has_class(props, "btn")));
+            enter_leave.apply(&mut frame_vnode, &frame_sheet, now);
+            // Mount the view on the first frame and patch it on later ones, running the
+            // lifecycle hooks; state they set shows in the next frame.
+            if crate::commit_frame(&mut tree, &frame_vnode) {
+                window.request_redraw();
+            }
+            let mut layout2 = crate::pipeline::layout_tree(&frame_vnode, config.width, config.height);
+            layout_animator.animate(&frame_vnode, &mut layout2, now);
+            crate::refs::Refs::current().update(&frame_vnode, &layout2, config.width, config.height);
+            if velox_core::signal::run_next_tick() > 0 {
+                window.request_redraw();
+            }
+            let viewport = velox_dom::layout::Rect { x: 0, y: 0, w: config.width as i32, h: config.height as i32 };
+            for ev in visibility.update(&frame_vnode, &layout2, viewport) {
+                on_event(&ev.handler, ev.payload.as_deref());
+            }
+            crate::crash::record_tree(&frame_vnode);
+            crate::stats::record_frame(&frame_vnode, layout_animator.tracked() + enter_leave.tracked());
+            for announcement in live_regions.update(&frame_vnode) {
+                crate::live_regions::announce(&announcement);
+            }
+            frame_arena.reset();
+            let list = frame_arena.build_display_list(&frame_vnode, &layout2, &mut crate::display_list::ApproxMeasure);
+            prev_vnode = Some(frame_vnode_reconciled);
+
+            // Fills and borders become colored quads, clipped on the CPU against the current
+            // clip rect; wgpu has no rounded corners or transforms yet (see `capabilities_for`).
+            use crate::display_list::{RectF, RenderCommand};
+            let to = |x: f32, y: f32| -> [f32;2] { [ (x / config.width as f32) * 2.0 - 1.0, 1.0 - (y / config.height as f32) * 2.0 ] };
+            let mut verts_all: Vec<Vertex> = Vec::new();
+            let mut push_quad = |r: RectF, clip: Option<RectF>, color: crate::display_list::Color| {
+                let (mut x0, mut y0, mut x1, mut y1) = (r.x, r.y, r.right(), r.bottom());
+                if let Some(c) = clip {
+                    x0 = x0.max(c.x); y0 = y0.max(c.y); x1 = x1.min(c.right()); y1 = y1.min(c.bottom());
+                }
+                if x1 <= x0 || y1 <= y0 { return; }
+                let color = [color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0];
+                verts_all.push(Vertex{pos:to(x0,y0),color});
+                verts_all.push(Vertex{pos:to(x1,y0),color});
+                verts_all.push(Vertex{pos:to(x1,y1),color});
+                verts_all.push(Vertex{pos:to(x0,y0),color});
+                verts_all.push(Vertex{pos:to(x1,y1),color});
+                verts_all.push(Vertex{pos:to(x0,y1),color});
+            };
+            let mut clips: Vec<RectF> = Vec::new();
+            let mut texts: Vec<(&RenderCommand, Option<RectF>)> = Vec::new();
+            for cmd in &list.commands {
+                let clip = clips.last().copied();
+                match cmd {
+                    RenderCommand::Rect { rect, color } | RenderCommand::RRect { rect, color, .. } => push_quad(*rect, clip, *color),
+                    RenderCommand::Border { rect, width, color, .. } => {
+                        let w = *width;
+                        push_quad(RectF::new(rect.x, rect.y, rect.w, w), clip, *color);
+                        push_quad(RectF::new(rect.x, rect.bottom() - w, rect.w, w), clip, *color);
+                        push_quad(RectF::new(rect.x, rect.y, w, rect.h), clip, *color);
+                        push_quad(RectF::new(rect.right() - w, rect.y, w, rect.h), clip, *color);
                     }
+                    RenderCommand::Text { .. } => texts.push((cmd, clip)),
+                    RenderCommand::PushClip { rect, .. } => {
+                        let next = match clip {
+                            Some(c) => {
+                                let x = rect.x.max(c.x);
+                                let y = rect.y.max(c.y);
+                                RectF::new(x, y, (rect.right().min(c.right()) - x).max(0.0), (rect.bottom().min(c.bottom()) - y).max(0.0))
+                            }
+                            None => *rect,
+                        };
+                        clips.push(next);
+                    }
+                    RenderCommand::PopClip => { clips.pop(); }
+                    RenderCommand::Image { .. } | RenderCommand::PushTransform(_) | RenderCommand::PopTransform => {}
                 }
             }
-            let layout2 = velox_dom::layout::compute_layout(&frame_vnode, config.width as i32, config.height as i32);
-            let mut buttons: Vec<(velox_dom::layout::Rect, &velox_dom::Props, &[velox_dom::VNode])> = Vec::new();
-            collect_click_nodes(&frame_vnode, &layout2, &mut buttons);
-            let mut verts_all: Vec<Vertex> = Vec::with_capacity(buttons.len() * 6);
-            for (rect, props, _) in &buttons {
-                let style_str = props.attrs.get("style").map(|s| s.as_str());
-                let color = parse_color(style_str, "background", [0.2,0.5,0.8,1.0]);
-                let (x0,y0,x1,y1) = (rect.x as f32, rect.y as f32, (rect.x+rect.w) as f32, (rect.y+rect.h) as f32);
-                let to = |x: f32, y: f32| -> [f32;2] { [ (x / config.width as f32) * 2.0 - 1.0, 1.0 - (y / config.height as f32) * 2.0 ] };
-                let (r,g,b) = (color[0], color[1], color[2]);
-                verts_all.push(Vertex{pos:to(x0,y0),color:[r,g,b]});
-                verts_all.push(Vertex{pos:to(x1,y0),color:[r,g,b]});
-                verts_all.push(Vertex{pos:to(x1,y1),color:[r,g,b]});
-                verts_all.push(Vertex{pos:to(x0,y0),color:[r,g,b]});
-                verts_all.push(Vertex{pos:to(x1,y1),color:[r,g,b]});
-                verts_all.push(Vertex{pos:to(x0,y1),color:[r,g,b]});
-            }
             {
-                if !verts_all.is_empty() {
-                    let quad_buf = device.create_buffer(&wgpu::BufferDescriptor { label: Some("velox-quads"), size: (verts_all.len()*std::mem::size_of::<Vertex>()) as u64, usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST, mapped_at_creation: false });
-                    queue.write_buffer(&quad_buf, 0, bytemuck::cast_slice(&verts_all));
-                    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor { label: Some("velox-pass"), color_attachments: &[Some(wgpu::RenderPassColorAttachment { view: &view, resolve_target: None, ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color { r: bg_color[0] as f64, g: bg_color[1] as f64, b: bg_color[2] as f64, a: bg_color[3] as f64 }), store: true } })], depth_stencil_attachment: None });
-                    rpass.set_pipeline(&pipeline);
-                    rpass.set_vertex_buffer(0, quad_buf.slice(..));
+                let clear = wgpu::Color { r: bg_color[0] as f64, g: bg_color[1] as f64, b: bg_color[2] as f64, a: bg_color[3] as f64 };
+                let quad_bytes = verts_all.len() * std::mem::size_of::<Vertex>();
+                crate::stats::record_gpu_buffers(vbuf.size() as usize + quad_bytes);
+                let quad_buf = (!verts_all.is_empty()).then(|| {
+                    let buf = device.create_buffer(&wgpu::BufferDescriptor { label: Some("velox-quads"), size: quad_bytes as u64, usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST, mapped_at_creation: false });
+                    queue.write_buffer(&buf, 0, bytemuck::cast_slice(&verts_all));
+                    buf
+                });
+                let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor { label: Some("velox-pass"), color_attachments: &[Some(wgpu::RenderPassColorAttachment { view: &view, resolve_target: None, ops: wgpu::Operations { load: wgpu::LoadOp::Clear(clear), store: true } })], depth_stencil_attachment: None });
+                rpass.set_pipeline(&pipeline);
+                if let Some(buf) = &quad_buf {
+                    rpass.set_vertex_buffer(0, buf.slice(..));
                     rpass.draw(0..(verts_all.len() as u32), 0..1);
-                } else {
-                    let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor { label: Some("velox-pass"), color_attachments: &[Some(wgpu::RenderPassColorAttachment { view: &view, resolve_target: None, ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color { r: bg_color[0] as f64, g: bg_color[1] as f64, b: bg_color[2] as f64, a: bg_color[3] as f64 }), store: true } })], depth_stencil_attachment: None });
-                    rpass.set_pipeline(&pipeline);
                 }
             }
-            // draw texts from vnode: button label and count using their own styles
             if let Some((ref mut glyph_brush, ref mut staging_belt)) = glyph {
-                use wgpu_glyph::{Section, Text, Layout, HorizontalAlign, VerticalAlign, FontId};
-                let (x0,y0,x1,y1) = btn_rect;
-                let (vnode_raw, sheet) = make_view(config.width, config.height);
-                let vnode = apply_styles_with_hover(&vnode_raw, &sheet, &|tag, props| hovered && (props.attrs.contains_key("on:click") || tag == "button" || has_class(props, "btn")));
-
-                // helpers to locate nodes
-                fn find_rect_for_class<'a>(vnode: &'a velox_dom::VNode, layout: &velox_dom::layout::LayoutNode, class: &str) -> Option<(velox_dom::layout::Rect, &'a velox_dom::Props)> {
-                    match vnode {
-                        velox_dom::VNode::Text(_) => None,
-                        velox_dom::VNode::Element { props, children, .. } => {
-                            let has = props.attrs.get("class").map(|s| s.split_whitespace().any(|c| c == class)).unwrap_or(false);
-                            if has { return Some((layout.rect, props)); }
-                            for (i, ch) in children.iter().enumerate() { if let Some(lc) = layout.children.get(i) { if let Some(v) = find_rect_for_class(ch, lc, class) { return Some(v); } } }
-                            None
-                        }
-                    }
-                }
-                fn find_click_node<'a>(vnode: &'a velox_dom::VNode, layout: &velox_dom::layout::LayoutNode) -> Option<(&'a velox_dom::Props)> {
-                    match vnode {
-                        velox_dom::VNode::Text(_) => None,
-                        velox_dom::VNode::Element { tag, props, children, .. } => {
-                            let is_btn = props.attrs.contains_key("on:click") || *tag == "button" || props.attrs.get("class").map(|s| s.split_whitespace().any(|c| c == "btn")).unwrap_or(false);
-                            if is_btn { return Some(props); }
-                            for (i, ch) in children.iter().enumerate() { let _ = layout.children.get(i)?; if let Some(p) = find_click_node(ch, &layout.children[i]) { return Some(p); } }
-                            None
-                        }
+                use wgpu_glyph::{Section, Text, Layout, VerticalAlign, FontId};
+                for (cmd, clip) in &texts {
+                    let RenderCommand::Text { x, baseline, text, size, family, bold, color } = cmd else { continue };
+                    let font_id = match family.as_deref().map(str::to_ascii_lowercase) {
+                        Some(f) if f.contains("dejavu") => 1,
+                        Some(f) if f.contains("noto") => 2,
+                        _ => 0,
+                    };
+                    let rgba = [color.r as f32 / 255.0, color.g as f32 / 255.0, color.b as f32 / 255.0, color.a as f32 / 255.0];
+                    // glyph_brush positions the top of the line box; approximate the ascent.
+                    let mut top = baseline - size * 0.8;
+                    let mut x = *x;
+                    // Hinting snaps the run to whole pixels, matching the raster backend.
+                    if crate::config::text_quality().hinting != crate::config::Hinting::None {
+                        top = top.round();
+                        x = x.round();
                     }
-                }
-                let layout2 = velox_dom::layout::compute_layout(&vnode, config.width as i32, config.height as i32);
-
-                // button text placement with line-height and bold/decoration
-                let btn_style = find_click_node(&vnode, &layout2).and_then(|p| p.attrs.get("style")).map(|s| s.as_str());
-                let btn_line_h = parse_px_f32(btn_style, "line-height", font_size);
-                let btn_font_size = parse_px_f32(btn_style, "font-size", font_size);
-                // padding right/bottom
-                let btn_pad_right = parse_px_f32(btn_style, "padding-right", parse_px_f32(btn_style, "padding", 0.0));
-                let btn_pad_bottom = parse_px_f32(btn_style, "padding-bottom", parse_px_f32(btn_style, "padding", 0.0));
-                // text top-left for glyph_brush (Section position is top-left), vertically centered in line box
-                let mut label_pos = (x0 + btn_pad_left, y0 + btn_pad_top + (btn_line_h - btn_font_size).max(0.0) * 0.5);
-                if label_pos.1 + btn_font_size > y1 - 1.0 { label_pos.1 = (y1 - 1.0 - btn_font_size).max(y0 + btn_pad_top); }
-                let label = if btn_text.is_empty() { String::new() } else { btn_text.clone() };
-                let btn_td = parse_text_decoration(btn_style);
-                let btn_bold = parse_font_weight(btn_style);
-                let btn_italic = style_lookup(btn_style, "font-style").map(|v| v.eq_ignore_ascii_case("italic")).unwrap_or(false);
-                let btn_align = parse_text_align(btn_style);
-                let btn_font_id = parse_font_family_id(btn_style);
-                if !label.is_empty() {
-                    let mut offsets: Vec<(f32,f32)> = if btn_bold { vec![(0.0,0.0),(0.6,0.0),(0.0,0.6)] } else { vec![(0.0,0.0)] };
-                    if btn_italic { offsets.push((0.4, 0.0)); }
-                    let bounds = ( (x1 - x0 - btn_pad_left - btn_pad_right).max(0.0), (y1 - y0 - btn_pad_top - btn_pad_bottom).max(0.0) );
-                    let layout = Layout::default().h_align(btn_align).v_align(VerticalAlign::Top);
+                    let bounds = match clip {
+                        Some(c) => ((c.right() - x).max(0.0), (c.bottom() - top).max(0.0)),
+                        None => (f32::INFINITY, f32::INFINITY),
+                    };
+                    let offsets: &[(f32, f32)] = if *bold { &[(0.0,0.0),(0.6,0.0),(0.0,0.6)] } else { &[(0.0,0.0)] };
                     for (ox, oy) in offsets {
                         glyph_brush.queue(Section {
-                            screen_position: (label_pos.0 + ox, label_pos.1 + oy),
+                            screen_position: (x + ox, top + oy),
                             bounds,
-                            layout,
-                            text: vec![Text::new(&label).with_color(btn_text_color).with_scale(btn_font_size).with_font_id(FontId(btn_font_id))],
-                            ..Default::default()
-                        });
-                    }
-                }
-
-                // Save reconciled vnode for next frame
-                prev_vnode = Some(frame_vnode_reconciled);
-
-                // count text placement with its own padding/line-height and bold/decoration
-                let (count_text, count_pos, count_style, count_bounds) = if let Some((rect, props)) = find_rect_for_class(&vnode, &layout2, "count") {
-                    let style_str = props.attrs.get("style").map(|s| s.as_str());
-                    let cp_l = parse_px_f32(style_str, "padding-left", parse_px_f32(style_str, "padding", 0.0));
-                    let cp_t = parse_px_f32(style_str, "padding-top", parse_px_f32(style_str, "padding", 0.0));
-                    let cp_r = parse_px_f32(style_str, "padding-right", parse_px_f32(style_str, "padding", 0.0));
-                    let cp_b = parse_px_f32(style_str, "padding-bottom", parse_px_f32(style_str, "padding", 0.0));
-                    let line_h = parse_px_f32(style_str, "line-height", font_size);
-                    let count_font_size = parse_px_f32(style_str, "font-size", font_size);
-                    let mut pos_y = rect.y as f32 + cp_t + (line_h - count_font_size).max(0.0) * 0.5;
-                    if pos_y + count_font_size > (rect.y + rect.h - 1) as f32 { pos_y = (rect.y + rect.h - 1) as f32 - count_font_size; }
-                    let pos = (rect.x as f32 + cp_l, pos_y);
-                    // Allow vertical overflow to be visible by giving a tall bound down to bottom of viewport
-                    let bounds_h = (config.height as f32 - rect.y as f32).max((rect.h as f32 - cp_t - cp_b).max(0.0));
-                    let bounds = ( (rect.w as f32 - cp_l - cp_r).max(0.0), bounds_h );
-                    (find_text_in_class(&vnode, "count").unwrap_or_default(), pos, style_str, bounds)
-                } else { (String::new(), (x0, y0), None, (0.0, 0.0)) };
-                let count_td = parse_text_decoration(count_style);
-                let count_bold = parse_font_weight(count_style);
-                let count_italic = style_lookup(count_style, "font-style").map(|v| v.eq_ignore_ascii_case("italic")).unwrap_or(false);
-                let count_align = parse_text_align(count_style);
-                let count_font_id = parse_font_family_id(count_style);
-                if !count_text.is_empty() {
-                    let mut offsets: Vec<(f32,f32)> = if count_bold { vec![(0.0,0.0),(0.6,0.0),(0.0,0.6)] } else { vec![(0.0,0.0)] };
-                    if count_italic { offsets.push((0.4, 0.0)); }
-                    let count_font_size = parse_px_f32(count_style, "font-size", font_size);
-                    let layout = Layout::default().h_align(count_align).v_align(VerticalAlign::Top);
-                    for (ox, oy) in offsets {
-                        glyph_brush.queue(Section {
-                            screen_position: (count_pos.0 + ox, count_pos.1 + oy),
-                            bounds: count_bounds,
-                            layout,
-                            text: vec![Text::new(&count_text).with_color(text_color).with_scale(count_font_size).with_font_id(FontId(count_font_id))],
+                            layout: Layout::default_single_line().v_align(VerticalAlign::Top),
+                            text: vec![Text::new(text).with_color(rgba).with_scale(*size).with_font_id(FontId(font_id))],
                             ..Default::default()
                         });
                     }
                 }
                 let _ = glyph_brush.draw_queued(&device, staging_belt, &mut encoder, &view, config.width, config.height);
                 staging_belt.finish();
-                // Text decorations as thin quads in a second pass
-                let mut deco_verts: Vec<Vertex> = Vec::new();
-                let mut push_rect = |x0: f32, y0: f32, x1: f32, y1: f32, color: [f32;3]| {
-                    let to = |x: f32, y: f32| [ (x / config.width as f32) * 2.0 - 1.0, 1.0 - (y / config.height as f32) * 2.0 ];
-                    deco_verts.push(Vertex { pos: to(x0,y0), color });
-                    deco_verts.push(Vertex { pos: to(x1,y0), color });
-                    deco_verts.push(Vertex { pos: to(x1,y1), color });
-                    deco_verts.push(Vertex { pos: to(x0,y0), color });
-                    deco_verts.push(Vertex { pos: to(x1,y1), color });
-                    deco_verts.push(Vertex { pos: to(x0,y1), color });
-                };
-                let thickness = 1.0f32.max(font_size.max(parse_px_f32(btn_style, "font-size", font_size)).max(parse_px_f32(count_style, "font-size", font_size)) * 0.06);
-                if !label.is_empty() && (btn_td.underline || btn_td.line_through) {
-                    let fs = parse_px_f32(btn_style, "font-size", font_size);
-                    let w = approx_text_width_px(&label, fs);
-                    let y_u = (label_pos.1 + fs + thickness).min(y1 - 1.0);
-                    let y_s = label_pos.1 + fs * 0.65;
-                    if btn_td.underline { push_rect(label_pos.0, y_u, label_pos.0 + w, (y_u + thickness).min(y1 - 1.0), [btn_text_color[0], btn_text_color[1], btn_text_color[2]]); }
-                    if btn_td.line_through { push_rect(label_pos.0, y_s, label_pos.0 + w, y_s + thickness, [btn_text_color[0], btn_text_color[1], btn_text_color[2]]); }
-                    // overline
-                    if style_lookup(btn_style, "text-decoration").map(|v| v.to_ascii_lowercase().contains("overline")).unwrap_or(false) {
-                        let y_o = (label_pos.1).max(y0 + btn_pad_top);
-                        push_rect(label_pos.0, y_o, label_pos.0 + w, (y_o + thickness).min(y1 - 1.0), [btn_text_color[0], btn_text_color[1], btn_text_color[2]]);
-                    }
-                }
-                if !count_text.is_empty() && (count_td.underline || count_td.line_through) {
-                    let cf = parse_px_f32(count_style, "font-size", font_size);
-                    let w = approx_text_width_px(&count_text, cf);
-                    let y_u = count_pos.1 + cf + thickness;
-                    let y_s = count_pos.1 + cf * 0.65;
-                    if count_td.underline { push_rect(count_pos.0, y_u, count_pos.0 + w, y_u + thickness, [text_color[0], text_color[1], text_color[2]]); }
-                    if count_td.line_through { push_rect(count_pos.0, y_s, count_pos.0 + w, y_s + thickness, [text_color[0], text_color[1], text_color[2]]); }
-                    if style_lookup(count_style, "text-decoration").map(|v| v.to_ascii_lowercase().contains("overline")).unwrap_or(false) {
-                        let y_o = count_pos.1;
-                        push_rect(count_pos.0, y_o, count_pos.0 + w, y_o + thickness, [text_color[0], text_color[1], text_color[2]]);
-                    }
-                }
-                if !deco_verts.is_empty() {
-                    let deco_buf = device.create_buffer(&wgpu::BufferDescriptor { label: Some("velox-deco"), size: (deco_verts.len() * std::mem::size_of::<Vertex>()) as u64, usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST, mapped_at_creation: false });
-                    queue.write_buffer(&deco_buf, 0, bytemuck::cast_slice(&deco_verts));
-                    {
-                        let mut rpass2 = encoder.begin_render_pass(&wgpu::RenderPassDescriptor { label: Some("velox-deco-pass"), color_attachments: &[Some(wgpu::RenderPassColorAttachment { view: &view, resolve_target: None, ops: wgpu::Operations { load: wgpu::LoadOp::Load, store: true } })], depth_stencil_attachment: None });
-                        rpass2.set_pipeline(&pipeline);
-                        rpass2.set_vertex_buffer(0, deco_buf.slice(..));
-                        rpass2.draw(0..(deco_verts.len() as u32), 0..1);
-                    }
-                }
                 queue.submit(Some(encoder.finish()));
                 device.poll(wgpu::Maintain::Wait);
                 staging_belt.recall();
-                frame.present();
             } else {
                 queue.submit(Some(encoder.finish()));
-                frame.present();
+            }
+            if crate::pixels::readback_enabled() || crate::screenshot::is_pending() {
+                if let Some(img) = wgpu_backend::read_texture(&device, &queue, &frame.texture, config.format, config.width, config.height) {
+                    crate::screenshot::store_frame(&img);
+                    crate::pixels::store_frame(img);
+                }
+            }
+            frame.present();
+        }
+        // Wake up for the next scripted step.
+        if let Some(at) = crate::replay::next_at() {
+            match *control_flow {
+                ControlFlow::ExitWithCode(_) => {}
+                ControlFlow::WaitUntil(t) if t <= at => {}
+                _ => *control_flow = ControlFlow::WaitUntil(at),
             }
         }
-        Event::MainEventsCleared => { window.request_redraw(); }
-        _ => {}
     });
 }
 
 // Minimal window runner using winit when `wgpu` feature is enabled.
 #[cfg(feature = "wgpu")]
-pub fn run_window(title: &str) {
+pub fn run_window(title: &str) -> Result<(), Error> {
     use wgpu::SurfaceError;
     use winit::dpi::PhysicalSize;
     use winit::event::{Event, WindowEvent};
     use winit::event_loop::{ControlFlow, EventLoop};
     use winit::window::WindowBuilder;
 
-    println!("[window] launching '{}'", title);
+    log::info!(target: "velox::renderer", "launching window '{}'", title);
     let event_loop = EventLoop::new();
     let window = match WindowBuilder::new()
-        .with_title(title)
-        .with_inner_size(PhysicalSize::new(800, 600))
+        .with_title(crate::config::window_title(title))
+        .with_inner_size(PhysicalSize::<u32>::from(crate::config::window_size()))
         .build(&event_loop)
     {
         Ok(w) => {
-            println!("[window] opened: {}", title);
+            log::info!(target: "velox::renderer", "window opened: {}", title);
             w
         }
-        Err(e) => {
-            eprintln!("[window] failed to create window: {}", e);
-            return;
-        }
+        Err(e) => return Err(Error::Window(e.to_string())),
     };
 
     // WGPU setup
-    let instance = wgpu::Instance::default();
-    let surface = unsafe { instance.create_surface(&window) }.expect("create surface");
-    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
-        power_preference: wgpu::PowerPreference::HighPerformance,
-        compatible_surface: Some(&surface),
-        force_fallback_adapter: false,
-    }))
-    .expect("no suitable GPU adapters");
-    let (device, queue) = pollster::block_on(adapter.request_device(
-        &wgpu::DeviceDescriptor {
-            label: Some("velox-device"),
-            features: wgpu::Features::empty(),
-            limits: wgpu::Limits::default(),
-        },
-        None,
-    ))
-    .expect("request device");
+    let (surface, adapter, device, queue) = wgpu_backend::window_gpu(&window)?;
 
     let mut size = window.inner_size();
     if size.width == 0 || size.height == 0 {
@@ -1587,7 +1817,7 @@ pub fn run_window(title: &str) {
     let render_pipeline = render_pipeline;
     let title_owned = title.to_string();
 
-    let _ = event_loop.run(move |event, _, control_flow| match event {
+    event_loop.run(move |event, _, control_flow| match event {
         Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
             *control_flow = ControlFlow::Exit;
         }
@@ -1636,7 +1866,7 @@ pub fn run_window(title: &str) {
 }
 
 #[cfg(feature = "wgpu")]
-pub fn run_window_counter<F>(title: &str, mut on_change: F)
+pub fn run_window_counter<F>(title: &str, mut on_change: F) -> Result<(), Error>
 where
     F: FnMut(i32) + 'static,
 {
@@ -1646,15 +1876,11 @@ where
     use winit::window::WindowBuilder;
 
     let event_loop = EventLoop::new();
-    let window = WindowBuilder::new().with_title(title).with_inner_size(PhysicalSize::new(800,600)).build(&event_loop).expect("window");
+    let window = WindowBuilder::new().with_title(crate::config::window_title(title)).with_inner_size(PhysicalSize::<u32>::from(crate::config::window_size())).build(&event_loop).map_err(|e| Error::Window(e.to_string()))?;
     let title_owned = title.to_string();
 
     // Reuse the rendering path
-    // Minimal re-init by calling into `run_window`-like setup inline to avoid refactor
-    let instance = wgpu::Instance::default();
-    let surface = unsafe { instance.create_surface(&window) }.expect("surface");
-    let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions { power_preference: wgpu::PowerPreference::HighPerformance, compatible_surface: Some(&surface), force_fallback_adapter: false })).expect("adapter");
-    let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor { label: Some("velox-device"), features: wgpu::Features::empty(), limits: wgpu::Limits::default() }, None)).expect("device");
+    let (surface, adapter, device, queue) = wgpu_backend::window_gpu(&window)?;
     let mut size = window.inner_size();
     if size.width == 0 || size.height == 0 { size = PhysicalSize::new(800, 600); window.set_inner_size(size); }
     let caps = surface.get_capabilities(&adapter);
@@ -1694,7 +1920,7 @@ where
     let mut count = 0;
     on_change(count);
 
-    let _ = event_loop.run(move |event, _, control_flow| match event {
+    event_loop.run(move |event, _, control_flow| match event {
         Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => { *control_flow = ControlFlow::Exit; }
         Event::WindowEvent { event: WindowEvent::Resized(sz), .. } => { config.width = sz.width.max(1); config.height = sz.height.max(1); surface.configure(&device, &config); queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&make_quad(config.width, config.height, hovered))); window.request_redraw(); }
         Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => { mouse = (position.x as f32, position.y as f32); let bw=200.0; let bh=80.0; let cx=config.width as f32/2.0; let cy=config.height as f32/2.0; let x0=cx-bw/2.0; let y0=cy-bh/2.0; let x1=cx+bw/2.0; let y1=cy+bh/2.0; let h = mouse.0>=x0 && mouse.0<=x1 && mouse.1>=y0 && mouse.1<=y1; if h!=hovered { hovered=h; queue.write_buffer(&vbuf, 0, bytemuck::cast_slice(&make_quad(config.width, config.height, hovered))); } window.request_redraw(); }
@@ -1718,7 +1944,7 @@ where
 }
 
 #[cfg(feature = "wgpu")]
-pub fn run_counter_window() {
+pub fn run_counter_window() -> Result<(), Error> {
     use winit::event::{ElementState, Event, MouseButton, WindowEvent};
     use winit::event_loop::{ControlFlow, EventLoop};
     use winit::window::WindowBuilder;
@@ -1727,14 +1953,14 @@ pub fn run_counter_window() {
     let window = WindowBuilder::new()
         .with_title("Velox - Count: 0 (click to increment)")
         .build(&event_loop)
-        .expect("create window");
+        .map_err(|e| Error::Window(e.to_string()))?;
 
     let mut count: i32 = 0;
     let mut update_title = move |c: i32| {
         window.set_title(&format!("Velox - Count: {} (click to increment)", c));
     };
 
-    let _ = event_loop.run(move |event, _, control_flow| match event {
+    event_loop.run(move |event, _, control_flow| match event {
         Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => {
             *control_flow = ControlFlow::Exit;
         }
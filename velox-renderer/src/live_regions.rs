@@ -0,0 +1,136 @@
+//! Screen-reader live regions (`aria-live`).
+//!
+//! An element with `aria-live="polite"` or `aria-live="assertive"` (or the implicit roles
+//! `status`, `log` and `alert`) is a live region: when its text changes, the new text is
+//! announced without moving focus, which is how toasts and validation errors reach
+//! screen-reader users. The window runners diff the live regions of every frame and
+//! hand each change to the announcer set with `set_announcer`, where the accessibility
+//! adapter speaks it; without one, announcements are logged under `velox::a11y`.
+//!
+//! Regions are matched between frames by `id` or `key`, falling back to their position
+//! in the tree. Content already shown on the first frame is not announced, and a region
+//! is announced as a whole (like `aria-atomic="true"`).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use velox_dom::{Props, VNode};
+
+/// How urgently a live region's changes are announced.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Politeness {
+    /// After the user is idle.
+    Polite,
+    /// Interrupting whatever is being read.
+    Assertive,
+}
+
+impl Politeness {
+    /// The politeness of an element: its `aria-live` value, else its role's default.
+    /// `aria-live="off"` opts out.
+    pub fn of(props: &Props) -> Option<Politeness> {
+        match props
+            .str("aria-live")
+            .map(|v| v.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("polite") => return Some(Politeness::Polite),
+            Some("assertive") => return Some(Politeness::Assertive),
+            Some(_) => return None,
+            None => {}
+        }
+        match props.str("role") {
+            Some("alert") => Some(Politeness::Assertive),
+            Some("status" | "log") => Some(Politeness::Polite),
+            _ => None,
+        }
+    }
+}
+
+/// Text to announce.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Announcement {
+    pub text: String,
+    pub politeness: Politeness,
+}
+
+/// The live regions of the last frame, to diff the next against.
+#[derive(Debug, Default)]
+pub struct LiveRegions {
+    last: HashMap<String, String>,
+    started: bool,
+}
+
+impl LiveRegions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The announcements for `vnode`'s live regions that changed since the last call.
+    pub fn update(&mut self, vnode: &VNode) -> Vec<Announcement> {
+        let mut regions = Vec::new();
+        collect(vnode, &mut String::new(), &mut regions);
+        let mut out = Vec::new();
+        let mut next = HashMap::with_capacity(regions.len());
+        for (key, text, politeness) in regions {
+            let changed = self.last.get(&key) != Some(&text);
+            if self.started && changed && !text.is_empty() {
+                out.push(Announcement {
+                    text: text.clone(),
+                    politeness,
+                });
+            }
+            next.insert(key, text);
+        }
+        self.last = next;
+        self.started = true;
+        // Assertive first: they interrupt, polite ones queue behind.
+        out.sort_by_key(|a| a.politeness != Politeness::Assertive);
+        out
+    }
+}
+
+fn collect(node: &VNode, path: &mut String, out: &mut Vec<(String, String, Politeness)>) {
+    let (VNode::Element { children, .. } | VNode::Fragment(children)) = node else {
+        return;
+    };
+    if let VNode::Element { props, .. } = node
+        && let Some(politeness) = Politeness::of(props)
+    {
+        let key = match props.get("id").or_else(|| props.get("key")) {
+            Some(id) => format!("#{}", id),
+            None => path.clone(),
+        };
+        let text = crate::vnode_text_content(node)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        out.push((key, text, politeness));
+        return;
+    }
+    for (i, child) in children.iter().enumerate() {
+        let len = path.len();
+        path.push_str(&format!("/{}", i));
+        collect(child, path, out);
+        path.truncate(len);
+    }
+}
+
+type Announcer = Box<dyn Fn(&Announcement)>;
+
+thread_local! {
+    static ANNOUNCER: RefCell<Option<Announcer>> = const { RefCell::new(None) };
+}
+
+/// Send announcements on this thread to `f` (the accessibility adapter).
+pub fn set_announcer(f: impl Fn(&Announcement) + 'static) {
+    ANNOUNCER.with(|a| *a.borrow_mut() = Some(Box::new(f)));
+}
+
+/// Announce `announcement` through the announcer, or log it when there is none.
+pub fn announce(announcement: &Announcement) {
+    ANNOUNCER.with(|a| match a.borrow().as_ref() {
+        Some(f) => f(announcement),
+        None => log::info!(target: "velox::a11y", "{:?}: {}", announcement.politeness, announcement.text),
+    });
+}
@@ -0,0 +1,109 @@
+//! Logging for the renderer.
+//!
+//! Diagnostics go through the `log` facade under these targets:
+//!
+//! - `velox::renderer` — backend selection and window runners
+//! - `velox::renderer::wgpu` — adapter/device setup
+//! - `velox::renderer::raster` — tiny-skia rendering and presentation
+//! - `velox::skia` — Skia GL context, surfaces, and rendering
+//!
+//! Apps that already install a logger (env_logger, tracing-log, ...) get these records
+//! there. Otherwise `init_logging()` installs a small stderr logger configured by
+//! `RUST_LOG` (`info`, `velox::skia=debug`, `warn,velox=info`, ...).
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// Filter used when `RUST_LOG` is unset: warnings from everything, info from velox.
+pub const DEFAULT_FILTER: &str = "warn,velox=info";
+
+/// Parsed `RUST_LOG`-style filter: an optional default level plus `target=level` entries.
+/// The longest matching target prefix (on `::` boundaries) wins.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogFilter {
+    default: LevelFilter,
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    /// Parse a comma-separated spec; unknown levels are ignored.
+    pub fn parse(spec: &str) -> Self {
+        let mut filter = LogFilter {
+            default: LevelFilter::Error,
+            directives: Vec::new(),
+        };
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.split_once('=') {
+                Some((target, level)) => {
+                    if let Ok(level) = level.trim().parse() {
+                        filter.directives.push((target.trim().to_string(), level));
+                    }
+                }
+                None => match part.parse() {
+                    Ok(level) => filter.default = level,
+                    // A bare target enables everything under it.
+                    Err(_) => filter
+                        .directives
+                        .push((part.to_string(), LevelFilter::Trace)),
+                },
+            }
+        }
+        filter
+    }
+
+    pub fn level_for(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .filter(|(prefix, _)| {
+                target == prefix
+                    || target
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.starts_with("::"))
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    /// Most verbose level any target can reach.
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, l)| *l)
+            .fold(self.default, Ord::max)
+    }
+}
+
+struct StderrLogger {
+    filter: LogFilter,
+}
+
+impl Log for StderrLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install the stderr logger using `RUST_LOG` (or `DEFAULT_FILTER`). Returns false if a
+/// logger was already installed, in which case that logger keeps receiving records.
+pub fn init_logging() -> bool {
+    let spec = std::env::var("RUST_LOG").unwrap_or_else(|_| DEFAULT_FILTER.to_string());
+    init_logging_with(&spec)
+}
+
+/// Install the stderr logger with an explicit filter spec.
+pub fn init_logging_with(spec: &str) -> bool {
+    let filter = LogFilter::parse(spec);
+    let max = filter.max_level();
+    if log::set_boxed_logger(Box::new(StderrLogger { filter })).is_err() {
+        return false;
+    }
+    log::set_max_level(max);
+    true
+}
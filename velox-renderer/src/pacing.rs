@@ -0,0 +1,130 @@
+//! Frame pacing for the window runners.
+//!
+//! Rendering pauses while the window is minimized (zero-sized) or reported occluded by
+//! the compositor, and continuous redraws are capped at the refresh rate of the monitor
+//! the window is currently on. Redraw requests that arrive while paused are dropped; the
+//! runner redraws once when the window becomes visible again. While the window is being
+//! resized, frames are drawn inside the resize events, at most one per refresh interval.
+
+use std::time::{Duration, Instant};
+
+/// Used when the platform does not report a refresh rate.
+pub const DEFAULT_REFRESH_MILLIHERTZ: u32 = 60_000;
+
+/// Longest idle period handed to idle tasks when no frame is scheduled, so input that
+/// arrives meanwhile is still handled promptly.
+pub const MAX_IDLE_PERIOD: Duration = Duration::from_millis(50);
+
+/// Time kept free before a scheduled frame.
+const IDLE_MARGIN: Duration = Duration::from_millis(1);
+
+#[derive(Clone, Debug)]
+pub struct FramePacer {
+    occluded: bool,
+    minimized: bool,
+    refresh_millihertz: u32,
+    last_frame: Option<Instant>,
+}
+
+impl Default for FramePacer {
+    fn default() -> Self {
+        FramePacer {
+            occluded: false,
+            minimized: false,
+            refresh_millihertz: DEFAULT_REFRESH_MILLIHERTZ,
+            last_frame: None,
+        }
+    }
+}
+
+impl FramePacer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// True while the window is minimized or occluded; nothing should be rendered.
+    pub fn is_paused(&self) -> bool {
+        self.occluded || self.minimized
+    }
+
+    /// Record a compositor occlusion change. Returns true when the window just became
+    /// visible again and should redraw.
+    pub fn set_occluded(&mut self, occluded: bool) -> bool {
+        let was_paused = self.is_paused();
+        self.occluded = occluded;
+        was_paused && !self.is_paused()
+    }
+
+    /// Record the window's inner size; a zero dimension means minimized. Returns true
+    /// when the window was just restored and should redraw.
+    pub fn set_size(&mut self, width: u32, height: u32) -> bool {
+        let was_paused = self.is_paused();
+        self.minimized = width == 0 || height == 0;
+        was_paused && !self.is_paused()
+    }
+
+    /// Adopt the current monitor's refresh rate (`None` keeps 60 Hz).
+    pub fn set_refresh_rate(&mut self, millihertz: Option<u32>) {
+        self.refresh_millihertz = millihertz
+            .filter(|m| *m > 0)
+            .unwrap_or(DEFAULT_REFRESH_MILLIHERTZ);
+    }
+
+    pub fn refresh_millihertz(&self) -> u32 {
+        self.refresh_millihertz
+    }
+
+    /// Minimum time between continuous frames.
+    pub fn frame_interval(&self) -> Duration {
+        Duration::from_nanos(1_000_000_000_000 / self.refresh_millihertz as u64)
+    }
+
+    /// Called when a redraw is requested. Returns whether to render now.
+    pub fn begin_frame(&mut self, now: Instant) -> bool {
+        if self.is_paused() {
+            return false;
+        }
+        self.last_frame = Some(now);
+        true
+    }
+
+    /// Whether a resize arriving at `now` should be drawn right away. Runners draw inside
+    /// the resize event so the window never shows a stretched or stale frame, but an
+    /// interactive resize delivers events faster than the refresh rate: those arriving
+    /// within a frame interval of the last frame only request a redraw.
+    pub fn resize_frame_due(&self, now: Instant) -> bool {
+        !self.is_paused()
+            && self
+                .last_frame
+                .is_none_or(|t| now >= t + self.frame_interval())
+    }
+
+    /// When the next continuous frame is due, or `None` while paused.
+    pub fn next_frame_at(&self) -> Option<Instant> {
+        if self.is_paused() {
+            return None;
+        }
+        Some(
+            self.last_frame
+                .map_or_else(Instant::now, |t| t + self.frame_interval()),
+        )
+    }
+
+    /// Deadline for idle work starting at `now`: just before the next frame while frames
+    /// are running continuously, otherwise `MAX_IDLE_PERIOD` from now.
+    pub fn idle_deadline(&self, now: Instant, animating: bool) -> Instant {
+        let cap = now + MAX_IDLE_PERIOD;
+        match self.next_frame_at() {
+            Some(at) if animating => at.checked_sub(IDLE_MARGIN).unwrap_or(at).clamp(now, cap),
+            _ => cap,
+        }
+    }
+}
+
+/// Refresh rate of the monitor `window` is on, if the platform reports one.
+#[cfg(any(feature = "wgpu", feature = "skia-native", feature = "raster"))]
+pub(crate) fn monitor_refresh_millihertz(window: &winit::window::Window) -> Option<u32> {
+    window
+        .current_monitor()
+        .and_then(|m| m.refresh_rate_millihertz())
+}
@@ -0,0 +1,145 @@
+//! Pen, stylus and touch strokes, with pressure and tilt.
+//!
+//! An element with an `on:pen` handler is a drawing surface. A stroke that starts on it
+//! sends the handler every sample until the pen lifts, even where the pen strays outside
+//! the element, so a drawing or annotation app gets the whole line. The payload is a
+//! `PenSample` as JSON, positioned relative to the element's top-left corner in logical
+//! pixels.
+//!
+//! `pressure` runs from 0 to 1; devices that can't sense it report 0.5 while touching.
+//! `tilt` is the pen's angle from upright in radians, `null` where the platform doesn't
+//! report it. `pointer` tells apart fingers touching at once (see `pointer`).
+
+use std::collections::HashMap;
+
+use velox_dom::layout::Rect;
+
+use crate::events::Targets;
+
+/// Where a sample is in its stroke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PenPhase {
+    Down,
+    Move,
+    Up,
+    /// The system took the stroke over, e.g. for a gesture; drop what it drew.
+    Cancel,
+}
+
+impl PenPhase {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PenPhase::Down => "down",
+            PenPhase::Move => "move",
+            PenPhase::Up => "up",
+            PenPhase::Cancel => "cancel",
+        }
+    }
+}
+
+/// One report from a pen or finger, at `(x, y)` in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PenSample {
+    pub phase: PenPhase,
+    pub x: f32,
+    pub y: f32,
+    pub pressure: f32,
+    pub tilt: Option<f32>,
+}
+
+impl PenSample {
+    /// Pressure reported by devices that don't sense it.
+    pub const DEFAULT_PRESSURE: f32 = 0.5;
+
+    /// The handler payload for a sample of `pointer` on an element at `rect`.
+    pub fn payload(&self, pointer: u64, rect: Rect) -> String {
+        let tilt = self.tilt.map_or("null".to_string(), |t| t.to_string());
+        format!(
+            "{{\"phase\":\"{}\",\"pointer\":{},\"x\":{},\"y\":{},\"pressure\":{},\"tilt\":{}}}",
+            self.phase.as_str(),
+            pointer,
+            self.x - rect.x as f32,
+            self.y - rect.y as f32,
+            self.pressure,
+            tilt
+        )
+    }
+}
+
+/// The `on:pen` element each stroke in progress started on.
+#[derive(Debug, Clone, Default)]
+pub struct PenStrokes {
+    captured: HashMap<u64, (String, Rect)>,
+}
+
+impl PenStrokes {
+    pub fn new() -> Self {
+        PenStrokes::default()
+    }
+
+    /// Route a sample of `pointer`, at `(x, y)` in layout coordinates (scroll included),
+    /// to the element its stroke started on. Returns whether a handler got it.
+    pub fn sample(
+        &mut self,
+        pointer: u64,
+        sample: PenSample,
+        targets: &Targets,
+        on_event: &mut dyn FnMut(&str, Option<&str>),
+    ) -> bool {
+        let (handler, rect) = match sample.phase {
+            PenPhase::Down => {
+                let Some(target) = targets.pen_at(sample.x, sample.y) else {
+                    return false;
+                };
+                let captured = (target.handler.clone(), target.rect);
+                self.captured.insert(pointer, captured.clone());
+                captured
+            }
+            PenPhase::Move => match self.captured.get(&pointer) {
+                Some(captured) => captured.clone(),
+                None => return false,
+            },
+            PenPhase::Up | PenPhase::Cancel => match self.captured.remove(&pointer) {
+                Some(captured) => captured,
+                None => return false,
+            },
+        };
+        on_event(&handler, Some(&sample.payload(pointer, rect)));
+        true
+    }
+
+    /// Whether a stroke is in progress on some element.
+    pub fn is_drawing(&self) -> bool {
+        !self.captured.is_empty()
+    }
+}
+
+/// A winit touch report as a sample at `(x, y)`, the caller's conversion of its location.
+#[cfg(any(feature = "wgpu", feature = "skia-native", feature = "raster"))]
+pub(crate) fn from_touch(touch: &winit::event::Touch, x: f32, y: f32) -> PenSample {
+    use winit::event::{Force, TouchPhase};
+
+    let phase = match touch.phase {
+        TouchPhase::Started => PenPhase::Down,
+        TouchPhase::Moved => PenPhase::Move,
+        TouchPhase::Ended => PenPhase::Up,
+        TouchPhase::Cancelled => PenPhase::Cancel,
+    };
+    let pressure = touch
+        .force
+        .map_or(PenSample::DEFAULT_PRESSURE, |f| f.normalized() as f32);
+    let tilt = match touch.force {
+        Some(Force::Calibrated {
+            altitude_angle: Some(altitude),
+            ..
+        }) => Some(std::f32::consts::FRAC_PI_2 - altitude as f32),
+        _ => None,
+    };
+    PenSample {
+        phase,
+        x,
+        y,
+        pressure,
+        tilt,
+    }
+}
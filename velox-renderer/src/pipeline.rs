@@ -0,0 +1,187 @@
+//! Style + layout stage of the frame loop.
+//!
+//! `style_tree` and `layout_tree` pick the rayon-backed cascade and layout when the
+//! `parallel` feature is on, so wide trees are processed on all cores. `LayoutWorker` runs
+//! the whole stage on a background thread, letting a runner lay out frame N+1 while frame
+//! N is being submitted to the GPU.
+
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+
+use velox_dom::layout::LayoutNode;
+use velox_dom::{Props, VNode};
+use velox_style::Stylesheet;
+
+/// A styled tree and its layout, ready to be turned into a display list.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PreparedFrame {
+    pub vnode: VNode,
+    pub layout: LayoutNode,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Hover predicate for trees tagged with `data-hover-id` by the window runners.
+pub fn hover_id_matches(props: &Props, hovered: Option<u32>) -> bool {
+    props
+        .num("data-hover-id")
+        .is_some_and(|id| Some(id as u32) == hovered)
+}
+
+/// Remove the first `<title>` element from `vnode` and return its text. The window
+/// runners use it as the window title, so a title bound to state follows it every frame.
+pub fn take_title(vnode: &mut VNode) -> Option<String> {
+    fn text_of(node: &VNode, out: &mut String) {
+        match node {
+            VNode::Text(t) => out.push_str(t),
+            VNode::Element { children, .. } | VNode::Fragment(children) => {
+                children.iter().for_each(|c| text_of(c, out))
+            }
+            VNode::Component { .. } => {}
+        }
+    }
+    let (VNode::Element { children, .. } | VNode::Fragment(children)) = vnode else {
+        return None;
+    };
+    if let Some(i) = children
+        .iter()
+        .position(|c| matches!(c, VNode::Element { tag, .. } if tag == "title"))
+    {
+        let mut title = String::new();
+        text_of(&children.remove(i), &mut title);
+        return Some(title.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+    children.iter_mut().find_map(take_title)
+}
+
+/// Cascade `sheet` over `vnode`.
+pub fn style_tree<F>(vnode: &VNode, sheet: &Stylesheet, is_hovered: &F) -> VNode
+where
+    F: Fn(&str, &Props) -> bool + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        velox_style::apply_styles_with_hover_parallel(vnode, sheet, is_hovered)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        velox_style::apply_styles_with_hover(vnode, sheet, is_hovered)
+    }
+}
+
+/// Lay out a styled tree for a `width`x`height` viewport.
+pub fn layout_tree(vnode: &VNode, width: u32, height: u32) -> LayoutNode {
+    #[cfg(feature = "parallel")]
+    {
+        velox_dom::layout::compute_layout_parallel(vnode, width as i32, height as i32)
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        velox_dom::layout::compute_layout(vnode, width as i32, height as i32)
+    }
+}
+
+/// Style and lay out one frame.
+pub fn prepare_frame<F>(
+    vnode: &VNode,
+    sheet: &Stylesheet,
+    is_hovered: &F,
+    width: u32,
+    height: u32,
+) -> PreparedFrame
+where
+    F: Fn(&str, &Props) -> bool + Sync,
+{
+    let vnode = style_tree(vnode, sheet, is_hovered);
+    let layout = layout_tree(&vnode, width, height);
+    PreparedFrame {
+        vnode,
+        layout,
+        width,
+        height,
+    }
+}
+
+struct LayoutJob {
+    vnode: VNode,
+    sheet: Stylesheet,
+    hovered: Option<u32>,
+    width: u32,
+    height: u32,
+}
+
+/// Background thread that prepares frames in submission order.
+pub struct LayoutWorker {
+    jobs: Option<Sender<LayoutJob>>,
+    done: Receiver<PreparedFrame>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl LayoutWorker {
+    /// Start the worker thread; fails only if the OS refuses to spawn it.
+    pub fn spawn() -> Result<Self, crate::Error> {
+        let (jobs, job_rx) = mpsc::channel::<LayoutJob>();
+        let (done_tx, done) = mpsc::channel();
+        let thread = std::thread::Builder::new()
+            .name("velox-layout".into())
+            .spawn(move || {
+                for job in job_rx {
+                    let hovered = job.hovered;
+                    let frame = prepare_frame(
+                        &job.vnode,
+                        &job.sheet,
+                        &|_, props| hover_id_matches(props, hovered),
+                        job.width,
+                        job.height,
+                    );
+                    if done_tx.send(frame).is_err() {
+                        break;
+                    }
+                }
+            })?;
+        Ok(LayoutWorker {
+            jobs: Some(jobs),
+            done,
+            thread: Some(thread),
+        })
+    }
+
+    /// Queue a raw (unstyled) tree; `hovered` is matched against `data-hover-id`.
+    pub fn submit(
+        &self,
+        vnode: VNode,
+        sheet: Stylesheet,
+        hovered: Option<u32>,
+        width: u32,
+        height: u32,
+    ) {
+        if let Some(jobs) = &self.jobs {
+            let _ = jobs.send(LayoutJob {
+                vnode,
+                sheet,
+                hovered,
+                width,
+                height,
+            });
+        }
+    }
+
+    /// The next finished frame, if one is ready.
+    pub fn try_recv(&self) -> Option<PreparedFrame> {
+        self.done.try_recv().ok()
+    }
+
+    /// Block until the next submitted frame is ready.
+    pub fn recv(&self) -> Option<PreparedFrame> {
+        self.done.recv().ok()
+    }
+}
+
+impl Drop for LayoutWorker {
+    fn drop(&mut self) {
+        self.jobs.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
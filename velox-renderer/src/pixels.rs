@@ -0,0 +1,264 @@
+//! CPU-side RGBA images shared by frame capture and pixel readback.
+//!
+//! With readback enabled, every window runner keeps a copy of the last presented frame so
+//! `read_pixels` can answer pixel queries (test assertions, eyedropper tools).
+//! `RgbaImage::encode_png` writes images out without any backend's codec, so screenshots
+//! and captured frames save the same way on every backend.
+
+use std::cell::{Cell, RefCell};
+
+thread_local! {
+    static READBACK: Cell<bool> = const { Cell::new(false) };
+    static LAST_FRAME: RefCell<Option<RgbaImage>> = const { RefCell::new(None) };
+}
+
+/// Rectangle in physical pixels.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PixelRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PixelRect {
+    pub const fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        PixelRect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+/// Tightly packed 8-bit RGBA pixels (non-premultiplied), row-major.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl RgbaImage {
+    pub fn new(width: u32, height: u32) -> Self {
+        RgbaImage {
+            width,
+            height,
+            data: vec![0; (width as usize) * (height as usize) * 4],
+        }
+    }
+
+    /// Wrap `data`, which must hold exactly `width * height` pixels.
+    pub fn from_rgba(width: u32, height: u32, data: Vec<u8>) -> Option<Self> {
+        (data.len() == (width as usize) * (height as usize) * 4).then_some(RgbaImage {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Convert premultiplied RGBA (tiny-skia/Skia surfaces) to straight alpha.
+    pub fn from_premultiplied(width: u32, height: u32, mut data: Vec<u8>) -> Option<Self> {
+        for px in data.chunks_exact_mut(4) {
+            let a = px[3] as u32;
+            if a != 0 && a != 255 {
+                for c in &mut px[..3] {
+                    *c = ((*c as u32 * 255 + a / 2) / a).min(255) as u8;
+                }
+            }
+        }
+        Self::from_rgba(width, height, data)
+    }
+
+    /// Copy out `rect`, clamped to the image bounds.
+    pub fn crop(&self, rect: PixelRect) -> RgbaImage {
+        let x0 = rect.x.min(self.width);
+        let y0 = rect.y.min(self.height);
+        let x1 = rect.x.saturating_add(rect.width).min(self.width);
+        let y1 = rect.y.saturating_add(rect.height).min(self.height);
+        let mut out = RgbaImage::new(x1 - x0, y1 - y0);
+        let row = (out.width as usize) * 4;
+        for (dst, y) in out.data.chunks_exact_mut(row.max(1)).zip(y0..y1) {
+            let start = ((y as usize) * (self.width as usize) + x0 as usize) * 4;
+            dst.copy_from_slice(&self.data[start..start + row]);
+        }
+        out
+    }
+
+    /// Encode as an 8-bit RGBA PNG. The image data is stored uncompressed, trading file
+    /// size for an encoder that needs no codec dependency.
+    pub fn encode_png(&self) -> Vec<u8> {
+        fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+            out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            let start = out.len();
+            out.extend_from_slice(kind);
+            out.extend_from_slice(data);
+            let crc = crc32(&out[start..]);
+            out.extend_from_slice(&crc.to_be_bytes());
+        }
+        // Each scanline is prefixed with filter type 0 (none).
+        let row = (self.width as usize) * 4;
+        let mut raw = Vec::with_capacity((row + 1) * self.height as usize);
+        for line in self
+            .data
+            .chunks_exact(row.max(1))
+            .take(self.height as usize)
+        {
+            raw.push(0);
+            raw.extend_from_slice(line);
+        }
+        // zlib stream of stored deflate blocks (at most 65535 bytes each).
+        let mut zlib = vec![0x78, 0x01];
+        let blocks: Vec<&[u8]> = if raw.is_empty() {
+            vec![&[]]
+        } else {
+            raw.chunks(0xffff).collect()
+        };
+        for (i, block) in blocks.iter().enumerate() {
+            zlib.push((i + 1 == blocks.len()) as u8);
+            let len = block.len() as u16;
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // Bit depth 8, color type 6 (RGBA), default compression, filter, no interlace.
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+
+        let mut out = b"\x89PNG\r\n\x1a\n".to_vec();
+        chunk(&mut out, b"IHDR", &header);
+        chunk(&mut out, b"IDAT", &zlib);
+        chunk(&mut out, b"IEND", &[]);
+        out
+    }
+
+    /// Decode a PNG written by `encode_png`: 8-bit RGBA, unfiltered, stored deflate
+    /// blocks. Anything else (including PNGs from other encoders) gives `None`.
+    pub fn decode_png(png: &[u8]) -> Option<RgbaImage> {
+        let mut rest = png.strip_prefix(b"\x89PNG\r\n\x1a\n")?;
+        let (mut header, mut zlib) = (None, Vec::new());
+        while rest.len() >= 12 {
+            let len = u32::from_be_bytes(rest[..4].try_into().ok()?) as usize;
+            let data = rest.get(8..8 + len)?;
+            match &rest[4..8] {
+                b"IHDR" => header = Some(data),
+                b"IDAT" => zlib.extend_from_slice(data),
+                _ => {}
+            }
+            rest = rest.get(12 + len..)?;
+        }
+        let header = header.filter(|h| h.len() == 13 && h[8..] == [8, 6, 0, 0, 0])?;
+        let width = u32::from_be_bytes(header[..4].try_into().ok()?);
+        let height = u32::from_be_bytes(header[4..8].try_into().ok()?);
+        let mut stream = zlib.get(2..)?;
+        let mut raw = Vec::new();
+        loop {
+            let (&head, tail) = stream.split_first()?;
+            if head & 0b110 != 0 {
+                return None;
+            }
+            let len = u16::from_le_bytes(tail.get(..2)?.try_into().ok()?) as usize;
+            raw.extend_from_slice(tail.get(4..4 + len)?);
+            stream = &tail[4 + len..];
+            if head & 1 == 1 {
+                break;
+            }
+        }
+        let row = (width as usize) * 4;
+        let mut data = Vec::with_capacity(row * height as usize);
+        for line in raw.chunks(row + 1).take(height as usize) {
+            if line.len() != row + 1 || line[0] != 0 {
+                return None;
+            }
+            data.extend_from_slice(&line[1..]);
+        }
+        RgbaImage::from_rgba(width, height, data)
+    }
+
+    /// Write the image to `path` as a PNG (see `encode_png`).
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> Result<(), crate::Error> {
+        std::fs::write(path, self.encode_png())?;
+        Ok(())
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let i = ((y as usize) * (self.width as usize) + x as usize) * 4;
+        Some([
+            self.data[i],
+            self.data[i + 1],
+            self.data[i + 2],
+            self.data[i + 3],
+        ])
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(5552) {
+        for &x in chunk {
+            a += x as u32;
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+/// Keep a copy of each presented frame for `read_pixels`. Off by default: it costs a frame
+/// copy per present, and a GPU-to-CPU transfer on wgpu.
+pub fn enable_readback(enabled: bool) {
+    READBACK.with(|r| r.set(enabled));
+    if !enabled {
+        LAST_FRAME.with(|f| f.borrow_mut().take());
+    }
+}
+
+pub fn readback_enabled() -> bool {
+    READBACK.with(|r| r.get())
+}
+
+/// Read `rect` from the last frame presented on this thread. `None` until readback is
+/// enabled and a frame has been presented.
+pub fn read_pixels(rect: PixelRect) -> Option<RgbaImage> {
+    LAST_FRAME.with(|f| f.borrow().as_ref().map(|img| img.crop(rect)))
+}
+
+/// Bytes of the frame kept for readback.
+pub(crate) fn kept_frame_bytes() -> usize {
+    LAST_FRAME.with(|f| f.borrow().as_ref().map_or(0, |img| img.data.len()))
+}
+
+/// Called by runners after presenting; no-op unless readback is enabled.
+#[cfg_attr(
+    not(any(feature = "skia-native", feature = "raster", feature = "wgpu")),
+    allow(dead_code)
+)]
+pub(crate) fn store_frame(image: RgbaImage) {
+    if readback_enabled() {
+        LAST_FRAME.with(|f| *f.borrow_mut() = Some(image));
+    }
+}
@@ -0,0 +1,117 @@
+//! Coalescing pointer moves.
+//!
+//! A pointer reports moves far more often than the screen refreshes. The window runners
+//! and `VeloxUi` keep only the latest position and hit-test hover with it once per pass
+//! of the event loop (`PointerMoves::take`), so a burst of moves costs one hover check
+//! and at most one re-style and layout. Apps that need every move, to draw a stroke or
+//! recognize a gesture, opt in with `set_raw_moves(Some("pointer-move"))`: each move is
+//! then dispatched as `{"x":..,"y":..,"pointer":1}` as it arrives.
+//!
+//! Pointer ids tell simultaneous pointers apart: two mice, or the fingers of a pinch.
+//! The runners number each device and touch point as it first shows up (`PointerIds`),
+//! starting at `PRIMARY_POINTER`; presses and pen strokes carry the same ids.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+thread_local! {
+    static RAW_MOVES: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Dispatch every pointer move to `handler`, or stop with `None`.
+pub fn set_raw_moves(handler: Option<&str>) {
+    RAW_MOVES.with(|h| *h.borrow_mut() = handler.map(str::to_string));
+}
+
+/// The handler raw moves go to, if an app opted in.
+pub fn raw_moves() -> Option<String> {
+    RAW_MOVES.with(|h| h.borrow().clone())
+}
+
+/// The id of the first pointer seen, and of input from hosts that report one pointer.
+pub const PRIMARY_POINTER: u64 = 1;
+
+/// Small, stable ids for the pointers input comes from, keyed by whatever the platform
+/// identifies them with (a device, or a device and touch point).
+#[derive(Debug, Clone)]
+pub struct PointerIds<K> {
+    ids: HashMap<K, u64>,
+    next: u64,
+}
+
+impl<K> Default for PointerIds<K> {
+    fn default() -> Self {
+        PointerIds {
+            ids: HashMap::new(),
+            next: PRIMARY_POINTER,
+        }
+    }
+}
+
+impl<K: Eq + Hash> PointerIds<K> {
+    pub fn new() -> Self {
+        PointerIds::default()
+    }
+
+    /// The id of `key`, numbering it if it is new.
+    pub fn id(&mut self, key: K) -> u64 {
+        let next = &mut self.next;
+        *self.ids.entry(key).or_insert_with(|| {
+            *next += 1;
+            *next - 1
+        })
+    }
+
+    /// Forget `key`, e.g. a touch point that lifted; it gets a new id if it comes back.
+    pub fn release(&mut self, key: &K) -> Option<u64> {
+        self.ids.remove(key)
+    }
+}
+
+/// The pointer moves since the hover state was last updated.
+#[derive(Debug, Clone, Default)]
+pub struct PointerMoves {
+    pending: Option<(f32, f32)>,
+    coalesced: usize,
+}
+
+impl PointerMoves {
+    pub fn new() -> Self {
+        PointerMoves::default()
+    }
+
+    /// Pointer `pointer` moved to `(x, y)`. Dispatches the move to the `raw_moves`
+    /// handler, if any, and returns whether it did.
+    pub fn moved(
+        &mut self,
+        pointer: u64,
+        x: f32,
+        y: f32,
+        on_event: &mut dyn FnMut(&str, Option<&str>),
+    ) -> bool {
+        if self.pending.replace((x, y)).is_some() {
+            self.coalesced += 1;
+        }
+        let Some(handler) = raw_moves() else {
+            return false;
+        };
+        on_event(&handler, Some(&position_payload(pointer, x, y)));
+        true
+    }
+
+    /// The latest position since the last call, or `None` when the pointer hasn't moved.
+    pub fn take(&mut self) -> Option<(f32, f32)> {
+        self.pending.take()
+    }
+
+    /// How many moves were superseded by a later one before being taken.
+    pub fn coalesced(&self) -> usize {
+        self.coalesced
+    }
+}
+
+/// `{"x":..,"y":..,"pointer":..}`, the payload of pointer moves and presses.
+pub(crate) fn position_payload(pointer: u64, x: f32, y: f32) -> String {
+    format!("{{\"x\":{},\"y\":{},\"pointer\":{}}}", x, y, pointer)
+}
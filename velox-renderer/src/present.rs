@@ -0,0 +1,128 @@
+//! Present CPU-rendered RGBA frames to a winit window through softbuffer.
+//! Shared by the Skia raster window path and the tiny-skia software backend.
+
+use crate::Error;
+
+pub(crate) struct SoftbufferPresenter {
+    _context: softbuffer::Context,
+    surface: softbuffer::Surface,
+    width: u32,
+    height: u32,
+    /// The last presented frame, diffed against the next for damage.
+    last: Vec<u8>,
+}
+
+impl SoftbufferPresenter {
+    pub(crate) fn new(
+        window: &winit::window::Window,
+        width: u32,
+        height: u32,
+    ) -> Result<Self, Error> {
+        let context = unsafe {
+            softbuffer::Context::new(window)
+                .map_err(|e| Error::Surface(format!("softbuffer context: {}", e)))?
+        };
+        let mut surface = unsafe {
+            softbuffer::Surface::new(&context, window)
+                .map_err(|e| Error::Surface(format!("softbuffer: {}", e)))?
+        };
+        let w = width.max(1);
+        let h = height.max(1);
+        surface
+            .resize(
+                std::num::NonZeroU32::new(w).unwrap(),
+                std::num::NonZeroU32::new(h).unwrap(),
+            )
+            .map_err(|e| Error::Present(format!("softbuffer resize: {}", e)))?;
+        Ok(Self {
+            _context: context,
+            surface,
+            width: w,
+            height: h,
+            last: Vec::new(),
+        })
+    }
+
+    pub(crate) fn resize(&mut self, width: u32, height: u32) -> Result<(), Error> {
+        let w = width.max(1);
+        let h = height.max(1);
+        if w == self.width && h == self.height {
+            return Ok(());
+        }
+        self.surface
+            .resize(
+                std::num::NonZeroU32::new(w).unwrap(),
+                std::num::NonZeroU32::new(h).unwrap(),
+            )
+            .map_err(|e| Error::Present(format!("softbuffer resize: {}", e)))?;
+        self.width = w;
+        self.height = h;
+        self.last.clear();
+        Ok(())
+    }
+
+    /// Present the next frame in full, whatever it has in common with the last.
+    pub(crate) fn invalidate(&mut self) {
+        self.last.clear();
+    }
+
+    /// Copy an opaque RGBA8 frame of `width`x`height` pixels to the window, telling the
+    /// compositor only about the regions that changed since the last one (see `damage`).
+    pub(crate) fn present_rgba(
+        &mut self,
+        rgba: &[u8],
+        width: u32,
+        height: u32,
+    ) -> Result<(), Error> {
+        self.resize(width, height)?;
+        let pixel_count = (self.width as usize) * (self.height as usize);
+        if rgba.len() < pixel_count * 4 {
+            return Err(Error::InvalidSize { width, height });
+        }
+        let frame = &rgba[..pixel_count * 4];
+        let last = (!self.last.is_empty()).then_some(self.last.as_slice());
+        let damage: Vec<softbuffer::Rect> =
+            crate::damage::frame_damage(last, frame, self.width, self.height)
+                .into_iter()
+                .filter_map(|r| {
+                    Some(softbuffer::Rect {
+                        x: r.x,
+                        y: r.y,
+                        width: std::num::NonZeroU32::new(r.width)?,
+                        height: std::num::NonZeroU32::new(r.height)?,
+                    })
+                })
+                .collect();
+        if !damage.is_empty() {
+            // The buffer may hold an older frame than the last one, so it is always filled
+            // in full; only the presented region shrinks.
+            let mut buffer = self
+                .surface
+                .buffer_mut()
+                .map_err(|e| Error::Present(format!("softbuffer buffer_mut: {}", e)))?;
+            let pixels: &mut [u32] = &mut buffer;
+            if pixels.len() < pixel_count {
+                return Err(Error::SurfaceLost);
+            }
+            for (pixel, px) in pixels.iter_mut().zip(frame.chunks_exact(4)) {
+                *pixel = ((px[0] as u32) << 16) | ((px[1] as u32) << 8) | px[2] as u32;
+            }
+            buffer
+                .present_with_damage(&damage)
+                .map_err(|e| Error::Present(format!("softbuffer: {}", e)))?;
+            self.last.clear();
+            self.last.extend_from_slice(frame);
+        }
+        crate::recording::capture(rgba, self.width, self.height);
+        if crate::pixels::readback_enabled() || crate::screenshot::is_pending() {
+            let len = pixel_count * 4;
+            if let Some(img) =
+                crate::pixels::RgbaImage::from_rgba(self.width, self.height, rgba[..len].to_vec())
+            {
+                crate::screenshot::store_frame(&img);
+                crate::pixels::store_frame(img);
+            }
+        }
+        Ok(())
+    }
+}
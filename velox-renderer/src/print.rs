@@ -0,0 +1,217 @@
+//! Paged output: page sizes, pagination, PDF export, and printing.
+//!
+//! Content is styled with the sheet's `@media print` rules, laid out at the page width
+//! in CSS pixels (96 per inch; PDF output is vector), and cut into pages of the page
+//! height. `break-before: page` and `break-after: page` (or the `page-break-*: always`
+//! forms) start a new page at or after an element. `break-inside: avoid` keeps an element
+//! on one page when it fits, as text lines and childless boxes always are: a page ends
+//! above the first such box that would cross its bottom edge, unless that box starts the
+//! page (then it is cut, since it cannot fit on any page).
+//!
+//! `export_pdf` and `print_window` need the `pdf` feature (Skia's PDF backend); without
+//! it, they return `Error::Unsupported`.
+
+use velox_dom::VNode;
+use velox_dom::layout::LayoutNode;
+use velox_style::Stylesheet;
+
+use crate::Error;
+
+/// CSS pixels per PDF point.
+pub const PX_PER_PT: f32 = 96.0 / 72.0;
+
+/// A page size in points (1/72 inch).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PageSize {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl PageSize {
+    pub const A4: PageSize = PageSize {
+        width: 595.0,
+        height: 842.0,
+    };
+    pub const A5: PageSize = PageSize {
+        width: 420.0,
+        height: 595.0,
+    };
+    pub const LETTER: PageSize = PageSize {
+        width: 612.0,
+        height: 792.0,
+    };
+    pub const LEGAL: PageSize = PageSize {
+        width: 612.0,
+        height: 1008.0,
+    };
+
+    pub const fn new(width: f32, height: f32) -> Self {
+        PageSize { width, height }
+    }
+
+    pub fn landscape(self) -> Self {
+        PageSize {
+            width: self.width.max(self.height),
+            height: self.width.min(self.height),
+        }
+    }
+
+    /// The page size in CSS pixels, the units content is laid out in.
+    pub fn size_px(&self) -> (f32, f32) {
+        (self.width * PX_PER_PT, self.height * PX_PER_PT)
+    }
+}
+
+/// Split laid-out content into pages `page_height` px tall, following the page-break
+/// hints in `vnode`'s styles. Returns each page's `(top, bottom)` in layout coordinates;
+/// there is always at least one page.
+pub fn paginate(vnode: &VNode, layout: &LayoutNode, page_height: f32) -> Vec<(f32, f32)> {
+    #[derive(Default)]
+    struct Hints {
+        /// Boxes a cut must not cross, as `(top, bottom)`.
+        keep: Vec<(f32, f32)>,
+        forced: Vec<f32>,
+        end: f32,
+    }
+    fn collect(node: &VNode, layout: &LayoutNode, hints: &mut Hints) {
+        let (y, bottom) = (layout.rect.y as f32, (layout.rect.y + layout.rect.h) as f32);
+        hints.end = hints.end.max(bottom);
+        if let VNode::Fragment(children) = node {
+            for (child, child_layout) in children.iter().zip(&layout.children) {
+                collect(child, child_layout, hints);
+            }
+            return;
+        }
+        let VNode::Element {
+            props, children, ..
+        } = node
+        else {
+            hints.keep.push((y, bottom));
+            return;
+        };
+        let style = props.str("style").unwrap_or("");
+        let hint = |names: [&str; 2], values: &[&str]| {
+            style
+                .split(';')
+                .filter_map(|d| d.split_once(':'))
+                .any(|(k, v)| {
+                    names.contains(&k.trim())
+                        && values.iter().any(|x| v.trim().eq_ignore_ascii_case(x))
+                })
+        };
+        if hint(["break-before", "page-break-before"], &["page", "always"]) {
+            hints.forced.push(y);
+        }
+        if hint(["break-after", "page-break-after"], &["page", "always"]) {
+            hints.forced.push(bottom);
+        }
+        if children.is_empty() || hint(["break-inside", "page-break-inside"], &["avoid"]) {
+            hints.keep.push((y, bottom));
+        }
+        for (child, child_layout) in children.iter().zip(&layout.children) {
+            collect(child, child_layout, hints);
+        }
+    }
+    let page_height = page_height.max(1.0);
+    let mut hints = Hints::default();
+    collect(vnode, layout, &mut hints);
+    let end = hints.end;
+
+    let mut pages = Vec::new();
+    let mut top = 0.0;
+    loop {
+        let limit = top + page_height;
+        let forced = hints
+            .forced
+            .iter()
+            .copied()
+            .filter(|&f| f > top && f < limit.min(end))
+            .fold(f32::INFINITY, f32::min);
+        if forced.is_finite() {
+            pages.push((top, forced));
+            top = forced;
+            continue;
+        }
+        if end <= limit {
+            break;
+        }
+        let cut = hints
+            .keep
+            .iter()
+            .filter(|&&(y, bottom)| y > top && y < limit && bottom > limit)
+            .map(|&(y, _)| y)
+            .fold(limit, f32::min);
+        pages.push((top, cut));
+        top = cut;
+    }
+    pages.push((top, end.max(top)));
+    pages
+}
+
+/// Render `vnode` styled by `sheet` (with its `@media print` rules) to a PDF document
+/// of `page`-sized pages.
+pub fn export_pdf(vnode: &VNode, sheet: &Stylesheet, page: PageSize) -> Result<Vec<u8>, Error> {
+    #[cfg(feature = "pdf")]
+    {
+        let (width, height) = page.size_px();
+        let sheet = sheet.for_media("print");
+        let frame = crate::pipeline::prepare_frame(
+            vnode,
+            &sheet,
+            &|_, _| false,
+            width.round() as u32,
+            height.round() as u32,
+        );
+        let pages = paginate(&frame.vnode, &frame.layout, height);
+        crate::skia_render::skia_impl::render_pdf(&frame.vnode, &frame.layout, &pages, page)
+    }
+    #[cfg(not(feature = "pdf"))]
+    {
+        let _ = (vnode, sheet, page);
+        Err(Error::Unsupported("pdf"))
+    }
+}
+
+/// Where `print_window` sends its output.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PrintTarget {
+    /// The default printer, through the platform's print command: `lp` on Linux and
+    /// BSD, `lpr` on macOS, and the shell's print verb on Windows.
+    System,
+    /// Write the PDF to this path instead.
+    Pdf(std::path::PathBuf),
+}
+
+/// Print the window's current view: lay it out again for `page` with the print styles,
+/// export it as PDF, and hand that to `target`.
+pub fn print_window(
+    vnode: &VNode,
+    sheet: &Stylesheet,
+    page: PageSize,
+    target: &PrintTarget,
+) -> Result<(), Error> {
+    let pdf = export_pdf(vnode, sheet, page)?;
+    match target {
+        PrintTarget::Pdf(path) => std::fs::write(path, pdf)?,
+        PrintTarget::System => {
+            let path = std::env::temp_dir().join(format!("velox-print-{}.pdf", std::process::id()));
+            std::fs::write(&path, pdf)?;
+            let mut command = if cfg!(target_os = "windows") {
+                let mut c = std::process::Command::new("cmd");
+                c.args(["/C", "start", "", "/print"]);
+                c
+            } else if cfg!(target_os = "macos") {
+                std::process::Command::new("lpr")
+            } else {
+                std::process::Command::new("lp")
+            };
+            let status = command.arg(&path).status()?;
+            if !status.success() {
+                return Err(
+                    std::io::Error::other(format!("print command exited with {}", status)).into(),
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,490 @@
+//! Software raster backend built on tiny-skia.
+//!
+//! Used when neither wgpu nor native Skia can start (no GPU, no EGL, CI containers).
+//! It executes the shared display list (fills, borders, clips, transforms, text); images
+//! are skipped. Text prefers the bundled DejaVu Sans so output does not depend on installed fonts.
+
+use ab_glyph::{Font, FontArc, GlyphId, PxScale, ScaleFont, point};
+use tiny_skia::{FillRule, Mask, Paint, Path, PathBuilder, Pixmap, Stroke, Transform};
+use velox_dom::VNode;
+use velox_dom::layout::{LayoutNode, compute_layout};
+
+use crate::Error;
+use crate::config::{Hinting, TextAntialias, TextQuality};
+use crate::display_list::{Color, DisplayList, RectF, RenderCommand, TextMeasure, Transform2D};
+
+const SYSTEM_FONTS: &[&str] = &[
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/google-noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+    "/usr/share/fonts/gnu-free/FreeSans.ttf",
+];
+
+/// Bundled fonts first, then well-known system paths.
+fn load_font() -> Result<FontArc, Error> {
+    let bundled: [&'static [u8]; 2] = [
+        include_bytes!("../assets/DejaVuSans.ttf"),
+        include_bytes!("../assets/NotoSans-Regular.ttf"),
+    ];
+    bundled
+        .into_iter()
+        .find_map(|b| FontArc::try_from_slice(b).ok())
+        .or_else(|| {
+            SYSTEM_FONTS
+                .iter()
+                .filter_map(|p| std::fs::read(p).ok())
+                .find_map(|bytes| FontArc::try_from_vec(bytes).ok())
+        })
+        .ok_or_else(|| Error::FontLoad("no bundled or system font could be parsed".into()))
+}
+
+/// Without a font, text commands are skipped and everything else still renders.
+fn font_or_skip_text() -> Option<FontArc> {
+    load_font()
+        .inspect_err(|e| log::warn!(target: "velox::renderer::raster", "{}; text is skipped", e))
+        .ok()
+}
+
+/// Glyph-advance measurement for display-list text layout.
+struct FontMeasure<'a>(Option<&'a FontArc>);
+
+impl TextMeasure for FontMeasure<'_> {
+    fn measure(&mut self, _family: Option<&str>, size: f32, text: &str) -> f32 {
+        let Some(font) = self.0 else {
+            return 0.0;
+        };
+        let sf = font.as_scaled(PxScale::from(size));
+        let mut w = 0.0;
+        let mut prev: Option<GlyphId> = None;
+        for c in text.chars() {
+            let id = sf.glyph_id(c);
+            if let Some(p) = prev {
+                w += sf.kern(p, id);
+            }
+            w += sf.h_advance(id);
+            prev = Some(id);
+        }
+        w
+    }
+}
+
+fn rect_path(rect: RectF, radius: f32) -> Option<Path> {
+    let RectF { x, y, w, h } = rect;
+    let r = radius.min(w / 2.0).min(h / 2.0).max(0.0);
+    if r <= 0.0 {
+        return tiny_skia::Rect::from_xywh(x, y, w, h).map(PathBuilder::from_rect);
+    }
+    // Circular corners approximated with cubics (k = 0.5523).
+    let k = r * 0.552_284_8;
+    let mut pb = PathBuilder::new();
+    pb.move_to(x + r, y);
+    pb.line_to(x + w - r, y);
+    pb.cubic_to(x + w - r + k, y, x + w, y + r - k, x + w, y + r);
+    pb.line_to(x + w, y + h - r);
+    pb.cubic_to(x + w, y + h - r + k, x + w - r + k, y + h, x + w - r, y + h);
+    pb.line_to(x + r, y + h);
+    pb.cubic_to(x + r - k, y + h, x, y + h - r + k, x, y + h - r);
+    pb.line_to(x, y + r);
+    pb.cubic_to(x, y + r - k, x + r - k, y, x + r, y);
+    pb.close();
+    pb.finish()
+}
+
+fn paint_for(color: Color) -> Paint<'static> {
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color.r, color.g, color.b, color.a);
+    paint.anti_alias = true;
+    paint
+}
+
+/// Current clip: a mask for shapes plus its bounds (physical pixels) for text.
+#[derive(Clone)]
+struct Clip {
+    mask: Mask,
+    bounds: (f32, f32, f32, f32),
+}
+
+struct Painter {
+    pixmap: Pixmap,
+    scale: f32,
+    font: Option<FontArc>,
+    quality: TextQuality,
+    transforms: Vec<Transform2D>,
+    clips: Vec<Clip>,
+}
+
+impl Painter {
+    fn current(&self) -> Transform2D {
+        self.transforms
+            .last()
+            .copied()
+            .unwrap_or(Transform2D::IDENTITY)
+    }
+
+    fn transform(&self) -> Transform {
+        let t = self.current();
+        Transform::from_row(
+            t.sx * self.scale,
+            0.0,
+            0.0,
+            t.sy * self.scale,
+            t.tx * self.scale,
+            t.ty * self.scale,
+        )
+    }
+
+    /// Blend one coverage sample (physical pixel) into the premultiplied pixmap.
+    fn blend(&mut self, px: i32, py: i32, color: Color, coverage: f32) {
+        let (w, h) = (self.pixmap.width() as i32, self.pixmap.height() as i32);
+        if px < 0 || py < 0 || px >= w || py >= h {
+            return;
+        }
+        if let Some(c) = self.clips.last() {
+            let (cx, cy, cw, ch) = c.bounds;
+            let (fx, fy) = (px as f32, py as f32);
+            if fx < cx || fy < cy || fx >= cx + cw || fy >= cy + ch {
+                return;
+            }
+        }
+        let a = (color.a as f32 / 255.0) * coverage.clamp(0.0, 1.0);
+        if a <= 0.0 {
+            return;
+        }
+        let idx = ((py * w + px) * 4) as usize;
+        let data = self.pixmap.data_mut();
+        let src = [
+            color.r as f32 * a,
+            color.g as f32 * a,
+            color.b as f32 * a,
+            255.0 * a,
+        ];
+        for (i, s) in src.iter().enumerate() {
+            let d = data[idx + i] as f32;
+            data[idx + i] = (s + d * (1.0 - a)).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+
+    fn fill(&mut self, rect: RectF, radius: f32, color: Color) {
+        if let Some(path) = rect_path(rect, radius) {
+            let transform = self.transform();
+            let mask = self.clips.last().map(|c| &c.mask);
+            self.pixmap
+                .fill_path(&path, &paint_for(color), FillRule::Winding, transform, mask);
+        }
+    }
+
+    fn stroke(&mut self, rect: RectF, radius: f32, width: f32, color: Color) {
+        if let Some(path) = rect_path(rect, radius) {
+            let stroke = Stroke {
+                width,
+                ..Stroke::default()
+            };
+            let transform = self.transform();
+            let mask = self.clips.last().map(|c| &c.mask);
+            self.pixmap
+                .stroke_path(&path, &paint_for(color), &stroke, transform, mask);
+        }
+    }
+
+    /// Blend per-channel (LCD) coverage into the premultiplied pixmap.
+    fn blend_lcd(&mut self, px: i32, py: i32, color: Color, coverage: [f32; 3]) {
+        let (w, h) = (self.pixmap.width() as i32, self.pixmap.height() as i32);
+        if px < 0 || py < 0 || px >= w || py >= h {
+            return;
+        }
+        if let Some(c) = self.clips.last() {
+            let (cx, cy, cw, ch) = c.bounds;
+            let (fx, fy) = (px as f32, py as f32);
+            if fx < cx || fy < cy || fx >= cx + cw || fy >= cy + ch {
+                return;
+            }
+        }
+        let alpha = color.a as f32 / 255.0;
+        let idx = ((py * w + px) * 4) as usize;
+        let data = self.pixmap.data_mut();
+        let src = [color.r, color.g, color.b];
+        for i in 0..3 {
+            let a = alpha * coverage[i];
+            let d = data[idx + i] as f32;
+            data[idx + i] = (src[i] as f32 * a + d * (1.0 - a))
+                .round()
+                .clamp(0.0, 255.0) as u8;
+        }
+        let a = alpha * (coverage[0] + coverage[1] + coverage[2]) / 3.0;
+        let d = data[idx + 3] as f32;
+        data[idx + 3] = (255.0 * a + d * (1.0 - a)).round().clamp(0.0, 255.0) as u8;
+    }
+
+    fn draw_text(&mut self, text: &str, x: f32, baseline: f32, size: f32, color: Color) {
+        let Some(font) = self.font.clone() else {
+            return;
+        };
+        let quality = self.quality;
+        let lcd = quality.antialias == TextAntialias::Subpixel;
+        let t = self.current();
+        let (x, baseline) = t.apply(x, baseline);
+        let size = size * t.sy * self.scale;
+        let sf = font.as_scaled(PxScale::from(size));
+        let mut caret = x * self.scale;
+        let mut base = baseline * self.scale;
+        if quality.hinting != Hinting::None {
+            base = base.round();
+        }
+        if matches!(quality.hinting, Hinting::Normal | Hinting::Full) {
+            caret = caret.round();
+        }
+        // LCD text is rasterized at 3x horizontal resolution, one sample per subpixel.
+        let h_mul = if lcd { 3.0 } else { 1.0 };
+        let glyph_scale = PxScale {
+            x: size * h_mul,
+            y: size,
+        };
+        let mut prev: Option<GlyphId> = None;
+        for c in text.chars() {
+            let id = sf.glyph_id(c);
+            if let Some(p) = prev {
+                caret += sf.kern(p, id);
+            }
+            let origin = if quality.hinting == Hinting::Full {
+                caret.round()
+            } else {
+                caret
+            };
+            let glyph = id.with_scale_and_position(glyph_scale, point(origin * h_mul, base));
+            caret += sf.h_advance(id);
+            prev = Some(id);
+            let Some(outlined) = font.outline_glyph(glyph) else {
+                continue;
+            };
+            let bounds = outlined.px_bounds();
+            if lcd {
+                let gw = bounds.width() as usize + 3;
+                let gh = bounds.height() as usize + 1;
+                let mut samples = vec![0.0f32; gw * gh];
+                outlined.draw(|gx, gy, cov| {
+                    if let Some(s) = samples.get_mut(gy as usize * gw + gx as usize) {
+                        *s = quality.adjust_coverage(cov);
+                    }
+                });
+                let sx0 = bounds.min.x as i32;
+                let px0 = sx0.div_euclid(3);
+                let px1 = (sx0 + gw as i32).div_euclid(3);
+                for gy in 0..gh {
+                    for px in px0..=px1 {
+                        let mut cov = [0.0f32; 3];
+                        for (ch, c) in cov.iter_mut().enumerate() {
+                            let sx = px * 3 + ch as i32 - sx0;
+                            if sx >= 0 && (sx as usize) < gw {
+                                *c = samples[gy * gw + sx as usize];
+                            }
+                        }
+                        if cov.iter().any(|c| *c > 0.0) {
+                            self.blend_lcd(px, bounds.min.y as i32 + gy as i32, color, cov);
+                        }
+                    }
+                }
+            } else {
+                outlined.draw(|gx, gy, cov| {
+                    let cov = quality.adjust_coverage(cov);
+                    self.blend(
+                        bounds.min.x as i32 + gx as i32,
+                        bounds.min.y as i32 + gy as i32,
+                        color,
+                        cov,
+                    );
+                });
+            }
+        }
+    }
+
+    fn push_clip(&mut self, rect: RectF, radius: f32) {
+        let transform = self.transform();
+        let Some(path) = rect_path(rect, radius) else {
+            // Degenerate clip: nothing inside it is visible.
+            let mask =
+                Mask::new(self.pixmap.width(), self.pixmap.height()).expect("non-empty pixmap");
+            self.clips.push(Clip {
+                mask,
+                bounds: (0.0, 0.0, 0.0, 0.0),
+            });
+            return;
+        };
+        let t = self.current();
+        let (x0, y0) = t.apply(rect.x, rect.y);
+        let (x1, y1) = t.apply(rect.right(), rect.bottom());
+        let own = (
+            x0 * self.scale,
+            y0 * self.scale,
+            (x1 - x0) * self.scale,
+            (y1 - y0) * self.scale,
+        );
+        let clip = match self.clips.last() {
+            Some(parent) => {
+                let mut mask = parent.mask.clone();
+                mask.intersect_path(&path, FillRule::Winding, true, transform);
+                let (px, py, pw, ph) = parent.bounds;
+                let left = px.max(own.0);
+                let top = py.max(own.1);
+                let right = (px + pw).min(own.0 + own.2);
+                let bottom = (py + ph).min(own.1 + own.3);
+                Clip {
+                    mask,
+                    bounds: (left, top, (right - left).max(0.0), (bottom - top).max(0.0)),
+                }
+            }
+            None => {
+                let mut mask =
+                    Mask::new(self.pixmap.width(), self.pixmap.height()).expect("non-empty pixmap");
+                mask.fill_path(&path, FillRule::Winding, true, transform);
+                Clip { mask, bounds: own }
+            }
+        };
+        self.clips.push(clip);
+    }
+
+    fn execute(&mut self, list: &DisplayList) {
+        for cmd in &list.commands {
+            match cmd {
+                RenderCommand::Rect { rect, color } => self.fill(*rect, 0.0, *color),
+                RenderCommand::RRect {
+                    rect,
+                    radius,
+                    color,
+                } => self.fill(*rect, *radius, *color),
+                RenderCommand::Border {
+                    rect,
+                    radius,
+                    width,
+                    color,
+                } => self.stroke(*rect, *radius, *width, *color),
+                RenderCommand::Text {
+                    x,
+                    baseline,
+                    text,
+                    size,
+                    color,
+                    ..
+                } => self.draw_text(text, *x, *baseline, *size, *color),
+                // No image decoding in the software backend.
+                RenderCommand::Image { .. } => {}
+                RenderCommand::PushClip { rect, radius } => self.push_clip(*rect, *radius),
+                RenderCommand::PopClip => {
+                    self.clips.pop();
+                }
+                RenderCommand::PushTransform(t) => {
+                    let combined = self.current().then(*t);
+                    self.transforms.push(combined);
+                }
+                RenderCommand::PopTransform => {
+                    self.transforms.pop();
+                }
+            }
+        }
+    }
+}
+
+/// Render `vnode` (with styles already applied) into a premultiplied RGBA pixmap.
+/// `width`/`height` are logical pixels; the pixmap is `scale` times larger.
+pub fn render_to_pixmap(
+    vnode: &VNode,
+    width: u32,
+    height: u32,
+    scale: f32,
+) -> Result<Pixmap, Error> {
+    let layout = compute_layout(vnode, width as i32, height as i32);
+    render_layout_to_pixmap(vnode, &layout, width, height, scale)
+}
+
+/// Like `render_to_pixmap`, drawing `vnode` at the rects in `layout` (e.g. one rewritten
+/// by `animation::LayoutAnimator`).
+pub fn render_layout_to_pixmap(
+    vnode: &VNode,
+    layout: &LayoutNode,
+    width: u32,
+    height: u32,
+    scale: f32,
+) -> Result<Pixmap, Error> {
+    let font = font_or_skip_text();
+    crate::arena::with_frame_arena(|arena| {
+        let list = arena.build_display_list(vnode, layout, &mut FontMeasure(font.as_ref()));
+        (list.width, list.height) = (width as f32, height as f32);
+        paint_list(list, scale, font)
+    })
+}
+
+/// Like `render_to_pixmap`, but returns straight-alpha RGBA (e.g. for pixel assertions).
+pub fn render_to_image(
+    vnode: &VNode,
+    width: u32,
+    height: u32,
+    scale: f32,
+) -> Result<crate::RgbaImage, Error> {
+    let pixmap = render_to_pixmap(vnode, width, height, scale)?;
+    let (width, height) = (pixmap.width(), pixmap.height());
+    crate::RgbaImage::from_premultiplied(width, height, pixmap.take())
+        .ok_or(Error::InvalidSize { width, height })
+}
+
+/// Execute an already built display list (e.g. one received via `remote`).
+pub fn render_display_list(list: &DisplayList, scale: f32) -> Result<Pixmap, Error> {
+    paint_list(list, scale, font_or_skip_text())
+}
+
+fn paint_list(list: &DisplayList, scale: f32, font: Option<FontArc>) -> Result<Pixmap, Error> {
+    let scale = if scale > 0.0 { scale } else { 1.0 };
+    let pw = (list.width * scale).round().max(1.0) as u32;
+    let ph = (list.height * scale).round().max(1.0) as u32;
+    // Checked up front: tiny-skia would try (and abort on failing) to allocate huge pixmaps.
+    let max = crate::capabilities::capabilities_for(crate::Backend::Raster).max_texture_size;
+    if pw > max || ph > max {
+        return Err(Error::InvalidSize {
+            width: pw,
+            height: ph,
+        });
+    }
+    let mut pixmap = Pixmap::new(pw, ph).ok_or(Error::InvalidSize {
+        width: pw,
+        height: ph,
+    })?;
+    pixmap.fill(tiny_skia::Color::WHITE);
+    let quality = crate::config::text_quality();
+    let mut painter = Painter {
+        pixmap,
+        scale,
+        font,
+        quality,
+        transforms: Vec::new(),
+        clips: Vec::new(),
+    };
+    painter.execute(list);
+    Ok(painter.pixmap)
+}
+
+/// Render `vnode` to PNG bytes with the software rasterizer.
+pub fn render_vnode_to_png(
+    vnode: &VNode,
+    width: u32,
+    height: u32,
+    scale: f32,
+) -> Result<Vec<u8>, Error> {
+    render_to_pixmap(vnode, width, height, scale)?
+        .encode_png()
+        .map_err(|e| Error::Encode(format!("png: {}", e)))
+}
+
+/// `Renderer` implementation for the software backend.
+pub struct RasterRenderer;
+
+impl crate::Renderer for RasterRenderer {
+    fn backend_name(&self) -> &'static str {
+        "raster"
+    }
+    fn capabilities(&self) -> crate::Capabilities {
+        crate::capabilities::capabilities_for(crate::Backend::Raster)
+    }
+    fn mount(&self, vnode: &VNode) -> crate::RenderTree {
+        crate::build_render_tree(vnode)
+    }
+}
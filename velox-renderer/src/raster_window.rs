@@ -0,0 +1,367 @@
+//! Window runner for the tiny-skia software backend.
+
+use std::time::Instant;
+
+use velox_style::Stylesheet;
+
+use crate::Error;
+use crate::animation::LayoutAnimator;
+use crate::input::ViewInput;
+use crate::pacing::{FramePacer, monitor_refresh_millihertz};
+use crate::present::SoftbufferPresenter;
+use crate::view::{ViewState, logical_size, recompute_targets, styled_view};
+
+/// Same contract as `run_window_vnode_skia`, but every frame is rasterized on the CPU
+/// with tiny-skia, so it runs without a GPU, EGL, or a Skia build.
+pub fn run_window_vnode_raster<F, G, H>(
+    title: &str,
+    mut make_view: F,
+    mut on_event: G,
+    mut get_title: H,
+) -> Result<(), Error>
+where
+    F: FnMut(u32, u32) -> (velox_dom::VNode, Stylesheet) + 'static,
+    G: FnMut(&str, Option<&str>) + 'static,
+    H: FnMut() -> String + 'static,
+{
+    use winit::dpi::PhysicalSize;
+    use winit::event::{
+        ElementState, Event, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta,
+        StartCause, WindowEvent,
+    };
+    use winit::event_loop::{ControlFlow, EventLoop};
+    use winit::window::WindowBuilder;
+
+    // Dispatched events are kept for crash reports.
+    let mut on_event = move |handler: &str, payload: Option<&str>| {
+        crate::crash::record_event(handler, payload);
+        on_event(handler, payload);
+        // Effects held back by `FlushPolicy::Microtask` run once the handler is done.
+        velox_core::signal::end_turn();
+    };
+    let event_loop = EventLoop::new();
+    // Restyle when the system text scale (the `rem` basis) changes.
+    let proxy = event_loop.create_proxy();
+    crate::text_scale::follow_system(move || proxy.send_event(()).is_ok());
+    // Rebuild the view when another thread calls `wake::wake`.
+    let proxy = event_loop.create_proxy();
+    crate::wake::set_waker(move || proxy.send_event(()).is_ok());
+    let window = WindowBuilder::new()
+        .with_title(crate::config::window_title(title))
+        .with_inner_size(PhysicalSize::<u32>::from(crate::config::window_size()))
+        .build(&event_loop)
+        .map_err(|e| Error::Window(e.to_string()))?;
+
+    let mut size = window.inner_size();
+    let mut presenter = SoftbufferPresenter::new(&window, size.width, size.height)?;
+    let mut scale_factor = window.scale_factor() as f32;
+
+    let mut pacer = FramePacer::new();
+    pacer.set_refresh_rate(monitor_refresh_millihertz(&window));
+    let mut animator = LayoutAnimator::new();
+    let mut view_state = ViewState::default();
+    let mut tree: Option<crate::RenderTree> = None;
+    let refs = crate::refs::Refs::current();
+    // Mice by device, and touch points by device and touch id.
+    let mut input = ViewInput::<(winit::event::DeviceId, Option<u64>)>::new(Some(refs.clone()));
+    let mut visibility = crate::visibility::VisibilityTracker::new();
+    let mut live_regions = crate::live_regions::LiveRegions::new();
+    let mut modifiers = ModifiersState::empty();
+    let mut animating = false;
+    crate::replay::start_from_env();
+
+    event_loop.run(move |event, _, control_flow| {
+        *control_flow = ControlFlow::Wait;
+        let mut draw = false;
+        match event {
+            Event::NewEvents(StartCause::Init)
+            | Event::NewEvents(StartCause::ResumeTimeReached { .. }) => {
+                window.request_redraw();
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CloseRequested,
+                ..
+            } => {
+                crate::replay::finish();
+                // The view goes away with the window: its unmounted hooks run now.
+                tree = None;
+                *control_flow = ControlFlow::Exit;
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Occluded(occluded),
+                ..
+            } => {
+                if pacer.set_occluded(occluded) {
+                    // What the window showed while hidden is gone; present it whole.
+                    presenter.invalidate();
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Moved(_),
+                ..
+            } => {
+                pacer.set_refresh_rate(monitor_refresh_millihertz(&window));
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(new_size),
+                ..
+            } => {
+                pacer.set_size(new_size.width, new_size.height);
+                size = new_size;
+                let _ = presenter.resize(size.width, size.height);
+                // Draw the new size now rather than after the event queue drains.
+                if pacer.resize_frame_due(Instant::now()) {
+                    draw = true;
+                } else {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::ScaleFactorChanged {
+                        scale_factor: new_scale,
+                        new_inner_size,
+                        ..
+                    },
+                ..
+            } => {
+                scale_factor = new_scale as f32;
+                pacer.set_refresh_rate(monitor_refresh_millihertz(&window));
+                size = *new_inner_size;
+                let _ = presenter.resize(size.width, size.height);
+                window.request_redraw();
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::CursorMoved {
+                        device_id,
+                        position,
+                        ..
+                    },
+                ..
+            } => {
+                let scale = crate::zoom::ui_scale(scale_factor);
+                // Hover is hit-tested once the queued events are handled (`MainEventsCleared`).
+                if input.moved(
+                    (device_id, None),
+                    position.x as f32 / scale,
+                    position.y as f32 / scale,
+                    &mut on_event,
+                ) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::CursorLeft { .. },
+                ..
+            } => {
+                if input.left() {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::Touch(touch),
+                ..
+            } => {
+                let scale = crate::zoom::ui_scale(scale_factor);
+                let sample = crate::pen::from_touch(
+                    &touch,
+                    touch.location.x as f32 / scale,
+                    touch.location.y as f32 / scale,
+                );
+                if input.touch((touch.device_id, Some(touch.id)), sample, &mut on_event) {
+                    window.request_redraw();
+                }
+            }
+            // macOS trackpad gestures, centered on the cursor.
+            Event::WindowEvent {
+                event: WindowEvent::TouchpadMagnify { delta, .. },
+                ..
+            } => {
+                if input.trackpad(
+                    |x, y| crate::gesture::Gesture::magnify(delta as f32, x, y),
+                    &mut on_event,
+                ) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::TouchpadRotate { delta, .. },
+                ..
+            } => {
+                if input.trackpad(
+                    |x, y| crate::gesture::Gesture::rotate_degrees(delta, x, y),
+                    &mut on_event,
+                ) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        device_id,
+                        state: ElementState::Pressed,
+                        button: MouseButton::Left,
+                        ..
+                    },
+                ..
+            } => {
+                // The frame this asks for restyles the view and refreshes the hit targets.
+                if input.pressed((device_id, None), &mut on_event).handled {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::MouseInput {
+                        device_id,
+                        state: ElementState::Released,
+                        button: MouseButton::Left,
+                        ..
+                    },
+                ..
+            } => {
+                if input.released((device_id, None), &mut on_event) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::MouseWheel { delta, .. },
+                ..
+            } => {
+                // Over a viewport the wheel pans it, or zooms it with Ctrl; elsewhere it
+                // scrolls the window.
+                let scale = crate::zoom::ui_scale(scale_factor);
+                let (dx, dy) = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (
+                        x * crate::viewport::LINE_PIXELS,
+                        y * crate::viewport::LINE_PIXELS,
+                    ),
+                    MouseScrollDelta::PixelDelta(p) => (p.x as f32 / scale, p.y as f32 / scale),
+                };
+                if input.wheel((dx, dy), modifiers.ctrl(), &mut on_event) {
+                    window.request_redraw();
+                }
+            }
+            Event::WindowEvent {
+                event: WindowEvent::ModifiersChanged(m),
+                ..
+            } => {
+                modifiers = m;
+            }
+            Event::WindowEvent {
+                event:
+                    WindowEvent::KeyboardInput {
+                        input:
+                            KeyboardInput {
+                                state: ElementState::Pressed,
+                                virtual_keycode: Some(key),
+                                ..
+                            },
+                        ..
+                    },
+                ..
+            } => {
+                if let Some(shortcut) = crate::keys::shortcut(key, modifiers)
+                    && input.shortcut(&shortcut, &mut on_event)
+                {
+                    window.request_redraw();
+                }
+            }
+            Event::UserEvent(()) => window.request_redraw(),
+            // The moves queued since the last pass update hover once, at the last position.
+            Event::MainEventsCleared => {
+                if input.update_hover() {
+                    window.request_redraw();
+                }
+            }
+            Event::RedrawRequested(_) => draw = true,
+            // Input is handled and the frame drawn: run idle tasks, then keep drawing at
+            // the refresh rate while a transition or spring runs.
+            Event::RedrawEventsCleared => {
+                if velox_core::idle::has_pending() && !pacer.is_paused() {
+                    let deadline = pacer.idle_deadline(Instant::now(), animating);
+                    if velox_core::idle::run_idle(deadline) > 0 {
+                        // Idle work usually updates state the view shows.
+                        window.request_redraw();
+                    }
+                }
+                if animating && let Some(at) = pacer.next_frame_at() {
+                    *control_flow = ControlFlow::WaitUntil(at);
+                }
+            }
+            _ => {}
+        }
+        // A script played with `replay::play_in_window` takes the same paths as real input.
+        if input.replay_due(Instant::now(), &mut on_event) {
+            window.request_redraw();
+        }
+        if draw && pacer.begin_frame(Instant::now()) {
+            let scale = crate::zoom::ui_scale(scale_factor);
+            let (vw, vh) = logical_size(size.width as i32, size.height as i32, scale);
+            let (vnode, _sheet) =
+                styled_view(&mut make_view, vw, vh, input.hovered, &mut view_state);
+            if let Some(title) = view_state.title_change(&mut get_title) {
+                window.set_title(&title);
+            }
+            input.update_shortcuts(&vnode);
+            // Mount the view on the first frame and patch it on later ones, running the
+            // lifecycle hooks; state they set shows in the next frame.
+            if crate::commit_frame(&mut tree, &vnode) {
+                window.request_redraw();
+            }
+            let handlers = &tree.as_ref().expect("commit_frame mounts the tree").handlers;
+            recompute_targets(&vnode, vw, vh, handlers, &mut input.targets);
+            let mut layout = crate::pipeline::layout_tree(&vnode, vw, vh);
+            animating = animator.animate(&vnode, &mut layout, velox_core::clock::now())
+                || view_state.transitions.is_running()
+                || velox_core::frame::is_running();
+            if refs.update(&vnode, &layout, vw, vh) {
+                // A resize observer ran; draw again with the state it set.
+                window.request_redraw();
+            }
+            // The frame is laid out: `next_tick` callbacks can measure it.
+            if velox_core::signal::run_next_tick() > 0 {
+                window.request_redraw();
+            }
+            crate::refs::scroll_layout(&mut layout, refs.scroll_offset());
+            let viewport = velox_dom::layout::Rect {
+                x: 0,
+                y: 0,
+                w: vw as i32,
+                h: vh as i32,
+            };
+            for ev in visibility.update(&vnode, &layout, viewport) {
+                on_event(&ev.handler, ev.payload.as_deref());
+                window.request_redraw();
+            }
+            crate::crash::record_tree(&vnode);
+            crate::stats::record_frame(
+                &vnode,
+                animator.tracked() + view_state.transitions.tracked(),
+            );
+            for announcement in live_regions.update(&vnode) {
+                crate::live_regions::announce(&announcement);
+            }
+            match crate::raster::render_layout_to_pixmap(&vnode, &layout, vw, vh, scale) {
+                Ok(pixmap) => {
+                    if let Err(e) =
+                        presenter.present_rgba(pixmap.data(), pixmap.width(), pixmap.height())
+                    {
+                        log::error!(target: "velox::renderer::raster", "present error: {}", e);
+                    }
+                }
+                Err(e) => log::error!(target: "velox::renderer::raster", "render error: {}", e),
+            }
+        }
+        // Wake up for the next scripted step.
+        if let Some(at) = crate::replay::next_at() {
+            match *control_flow {
+                ControlFlow::ExitWithCode(_) => {}
+                ControlFlow::WaitUntil(t) if t <= at => {}
+                _ => *control_flow = ControlFlow::WaitUntil(at),
+            }
+        }
+    });
+}
@@ -0,0 +1,191 @@
+//! Capture consecutive frames and export them as an animated GIF (`gif` feature) or an
+//! mp4 (`mp4` feature, encoded by an `ffmpeg` binary on PATH).
+//!
+//! Frames come from two places: `record_frames(n)` arms the window runners that present
+//! CPU frames (Skia raster and the tiny-skia backend) to keep the next `n` presented frames,
+//! and `record_offscreen` renders frames headlessly with the software rasterizer, which is
+//! what tests use to produce demo clips.
+
+use std::cell::RefCell;
+
+use crate::pixels::RgbaImage;
+
+struct Recording {
+    remaining: usize,
+    frames: Vec<RgbaImage>,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<Recording>> = const { RefCell::new(None) };
+}
+
+/// Keep the next `count` frames presented by the window runner on this thread.
+pub fn record_frames(count: usize) {
+    RECORDING.with(|r| {
+        *r.borrow_mut() = Some(Recording {
+            remaining: count,
+            frames: Vec::with_capacity(count),
+        });
+    });
+}
+
+/// True while a `record_frames` request still wants frames.
+pub fn is_recording() -> bool {
+    RECORDING.with(|r| r.borrow().as_ref().is_some_and(|rec| rec.remaining > 0))
+}
+
+/// Stop recording and return the frames captured so far.
+pub fn take_frames() -> Vec<RgbaImage> {
+    RECORDING.with(|r| {
+        r.borrow_mut()
+            .take()
+            .map(|rec| rec.frames)
+            .unwrap_or_default()
+    })
+}
+
+/// Called by runners after presenting an opaque RGBA frame.
+#[cfg_attr(
+    not(any(feature = "skia-native", feature = "raster")),
+    allow(dead_code)
+)]
+pub(crate) fn capture(rgba: &[u8], width: u32, height: u32) {
+    RECORDING.with(|r| {
+        let mut slot = r.borrow_mut();
+        let Some(rec) = slot.as_mut() else { return };
+        if rec.remaining == 0 {
+            return;
+        }
+        let len = (width as usize) * (height as usize) * 4;
+        if let Some(frame) = rgba
+            .get(..len)
+            .and_then(|d| RgbaImage::from_rgba(width, height, d.to_vec()))
+        {
+            rec.frames.push(frame);
+            rec.remaining -= 1;
+        }
+    });
+}
+
+/// Render `count` frames headlessly; `frame(i)` returns the styled tree for frame `i`.
+#[cfg(feature = "raster")]
+pub fn record_offscreen<F>(
+    count: usize,
+    width: u32,
+    height: u32,
+    scale: f32,
+    mut frame: F,
+) -> Result<Vec<RgbaImage>, crate::Error>
+where
+    F: FnMut(usize) -> velox_dom::VNode,
+{
+    (0..count)
+        .map(|i| crate::raster::render_to_image(&frame(i), width, height, scale))
+        .collect()
+}
+
+/// Encode `frames` as a looping GIF, `delay_ms` per frame (rounded to GIF's 10ms units).
+#[cfg(feature = "gif")]
+pub fn encode_gif<W: std::io::Write>(
+    frames: &[RgbaImage],
+    delay_ms: u32,
+    out: W,
+) -> Result<(), crate::Error> {
+    use crate::Error;
+
+    let first = frames
+        .first()
+        .ok_or_else(|| Error::Encode("no frames to encode".into()))?;
+    let (w, h) = (first.width, first.height);
+    let (gw, gh) = (u16::try_from(w), u16::try_from(h));
+    // GIF dimensions are 16-bit.
+    let (Ok(gw), Ok(gh)) = (gw, gh) else {
+        return Err(Error::InvalidSize {
+            width: w,
+            height: h,
+        });
+    };
+    let gif_err = |e: gif::EncodingError| Error::Encode(format!("gif: {}", e));
+    let mut encoder = gif::Encoder::new(out, gw, gh, &[]).map_err(gif_err)?;
+    encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_err)?;
+    let delay = (delay_ms / 10).min(u16::MAX as u32) as u16;
+    for img in frames {
+        if (img.width, img.height) != (w, h) {
+            return Err(Error::InvalidSize {
+                width: img.width,
+                height: img.height,
+            });
+        }
+        let mut data = img.data.clone();
+        let mut frame = gif::Frame::from_rgba_speed(gw, gh, &mut data, 10);
+        frame.delay = delay;
+        encoder.write_frame(&frame).map_err(gif_err)?;
+    }
+    Ok(())
+}
+
+/// Encode `frames` to an H.264 mp4 at `path` by piping raw RGBA into `ffmpeg`.
+#[cfg(feature = "mp4")]
+pub fn encode_mp4(
+    frames: &[RgbaImage],
+    fps: u32,
+    path: &std::path::Path,
+) -> Result<(), crate::Error> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    use crate::Error;
+
+    let first = frames
+        .first()
+        .ok_or_else(|| Error::Encode("no frames to encode".into()))?;
+    if let Some(img) = frames
+        .iter()
+        .find(|img| (img.width, img.height) != (first.width, first.height))
+    {
+        return Err(Error::InvalidSize {
+            width: img.width,
+            height: img.height,
+        });
+    }
+    let mut child = Command::new("ffmpeg")
+        .args([
+            "-loglevel",
+            "error",
+            "-y",
+            "-f",
+            "rawvideo",
+            "-pix_fmt",
+            "rgba",
+            "-s",
+        ])
+        .arg(format!("{}x{}", first.width, first.height))
+        .args(["-r", &fps.max(1).to_string(), "-i", "-"])
+        // yuv420p needs even dimensions.
+        .args([
+            "-vf",
+            "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+            "-pix_fmt",
+            "yuv420p",
+        ])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::Encode(format!("failed to start ffmpeg: {}", e)))?;
+    {
+        // Dropping stdin at the end of this block signals EOF to ffmpeg.
+        let mut stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| Error::Encode("ffmpeg stdin unavailable".into()))?;
+        for img in frames {
+            stdin.write_all(&img.data)?;
+        }
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(Error::Encode(format!("ffmpeg exited with {}", status)))
+    }
+}
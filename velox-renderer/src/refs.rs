@@ -0,0 +1,231 @@
+//! Element refs: layout access for elements marked `ref="name"`.
+//!
+//! After each frame's layout, the window runners record the rect of every element with a
+//! `ref` attribute in the window's `Refs`. Event handlers (and the view) reach it with
+//! `Refs::current()` to read a ref's rect, scroll it into view, or focus it.
+//!
+//! Rects are in logical pixels in view coordinates, i.e. before the window scroll offset
+//! is applied. The window scrolls vertically and horizontally only as far as needed to
+//! show a ref, and never past the content.
+//!
+//! `observe` works like `ResizeObserver`: the callback gets the ref's rect on the first
+//! frame it is laid out and again whenever its size or position changes.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use velox_dom::VNode;
+use velox_dom::layout::{LayoutNode, Rect};
+
+type Observer = Rc<RefCell<dyn FnMut(Rect)>>;
+
+#[derive(Default)]
+struct Inner {
+    rects: HashMap<String, Rect>,
+    viewport: (i32, i32),
+    content: (i32, i32),
+    scroll: (i32, i32),
+    focused: Option<String>,
+    /// `(id, ref name, last rect reported, callback)`.
+    observers: Vec<(u64, String, Option<Rect>, Observer)>,
+    next_observer: u64,
+}
+
+impl std::fmt::Debug for Inner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Inner")
+            .field("rects", &self.rects)
+            .field("scroll", &self.scroll)
+            .field("focused", &self.focused)
+            .field("observers", &self.observers.len())
+            .finish()
+    }
+}
+
+/// Identifies an `observe` registration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ObserverId(u64);
+
+/// Handle to a window's refs. Clones share the same state.
+#[derive(Clone, Debug, Default)]
+pub struct Refs {
+    inner: Rc<RefCell<Inner>>,
+}
+
+thread_local! {
+    static CURRENT: Refs = Refs::default();
+}
+
+impl Refs {
+    /// Standalone refs, e.g. for a custom runner or tests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The refs of the window running on this thread.
+    pub fn current() -> Refs {
+        CURRENT.with(Refs::clone)
+    }
+
+    /// Rect of `name` from the last frame's layout.
+    pub fn rect(&self, name: &str) -> Option<Rect> {
+        self.inner.borrow().rects.get(name).copied()
+    }
+
+    /// Names of all refs in the last frame.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.inner.borrow().rects.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Scroll the window just enough to show `name`. Returns false if there is no such ref.
+    pub fn scroll_into_view(&self, name: &str) -> bool {
+        let mut inner = self.inner.borrow_mut();
+        let Some(r) = inner.rects.get(name).copied() else {
+            return false;
+        };
+        let (vw, vh) = inner.viewport;
+        let reveal = |scroll: i32, start: i32, len: i32, view: i32| {
+            if start < scroll {
+                start
+            } else if start + len > scroll + view {
+                (start + len - view).min(start)
+            } else {
+                scroll
+            }
+        };
+        let (sx, sy) = inner.scroll;
+        inner.scroll = (reveal(sx, r.x, r.w, vw), reveal(sy, r.y, r.h, vh));
+        inner.clamp_scroll();
+        true
+    }
+
+    /// Current window scroll offset in logical pixels.
+    pub fn scroll_offset(&self) -> (i32, i32) {
+        self.inner.borrow().scroll
+    }
+
+    /// Scroll the window to `(x, y)`, clamped to the content.
+    pub fn scroll_to(&self, x: i32, y: i32) {
+        let mut inner = self.inner.borrow_mut();
+        inner.scroll = (x, y);
+        inner.clamp_scroll();
+    }
+
+    /// Give `name` focus. Returns false if there is no such ref.
+    pub fn focus(&self, name: &str) -> bool {
+        let mut inner = self.inner.borrow_mut();
+        if !inner.rects.contains_key(name) {
+            return false;
+        }
+        inner.focused = Some(name.to_string());
+        true
+    }
+
+    pub fn blur(&self) {
+        self.inner.borrow_mut().focused = None;
+    }
+
+    /// The focused ref, if any. Views read this to style the focused element.
+    pub fn focused(&self) -> Option<String> {
+        self.inner.borrow().focused.clone()
+    }
+
+    /// Call `f` with the rect of `name` whenever it changes, starting with the first frame
+    /// it is laid out in.
+    pub fn observe(&self, name: &str, f: impl FnMut(Rect) + 'static) -> ObserverId {
+        let mut inner = self.inner.borrow_mut();
+        inner.next_observer += 1;
+        let id = inner.next_observer;
+        inner
+            .observers
+            .push((id, name.to_string(), None, Rc::new(RefCell::new(f))));
+        ObserverId(id)
+    }
+
+    pub fn unobserve(&self, id: ObserverId) {
+        self.inner
+            .borrow_mut()
+            .observers
+            .retain(|(i, ..)| *i != id.0);
+    }
+
+    /// Record the refs of a laid-out frame with a `width`x`height` viewport and notify
+    /// observers of changed rects. Called by the runners after layout; focus moves off refs
+    /// that disappeared. Returns true if an observer was called, since it may have changed
+    /// state the view depends on.
+    pub fn update(&self, vnode: &VNode, layout: &LayoutNode, width: u32, height: u32) -> bool {
+        let mut notify: Vec<(Observer, Rect)> = Vec::new();
+        {
+            let mut inner = self.inner.borrow_mut();
+            let inner = &mut *inner;
+            inner.rects.clear();
+            let mut content = (0, 0);
+            collect(vnode, layout, &mut inner.rects, &mut content);
+            inner.content = content;
+            inner.viewport = (width as i32, height as i32);
+            if let Some(name) = &inner.focused
+                && !inner.rects.contains_key(name)
+            {
+                inner.focused = None;
+            }
+            inner.clamp_scroll();
+            for (_, name, last, f) in &mut inner.observers {
+                if let Some(&rect) = inner.rects.get(name.as_str())
+                    && *last != Some(rect)
+                {
+                    *last = Some(rect);
+                    notify.push((f.clone(), rect));
+                }
+            }
+        }
+        // Call without holding the borrow: observers may read refs or observe more.
+        for (f, rect) in &notify {
+            (f.borrow_mut())(*rect);
+        }
+        !notify.is_empty()
+    }
+}
+
+impl Inner {
+    fn clamp_scroll(&mut self) {
+        let max_x = (self.content.0 - self.viewport.0).max(0);
+        let max_y = (self.content.1 - self.viewport.1).max(0);
+        self.scroll = (self.scroll.0.clamp(0, max_x), self.scroll.1.clamp(0, max_y));
+    }
+}
+
+fn collect(
+    vnode: &VNode,
+    layout: &LayoutNode,
+    rects: &mut HashMap<String, Rect>,
+    content: &mut (i32, i32),
+) {
+    let r = layout.rect;
+    *content = (content.0.max(r.x + r.w), content.1.max(r.y + r.h));
+    if let VNode::Element { props, .. } = vnode
+        && let Some(name) = props.str("ref")
+    {
+        rects.entry(name.to_string()).or_insert(r);
+    }
+    for (child, child_layout) in vnode.children().iter().zip(&layout.children) {
+        collect(child, child_layout, rects, content);
+    }
+}
+
+/// Apply the window scroll offset to a laid-out frame: everything below the root moves
+/// up/left by `offset`, while the root stays put as the window background.
+pub fn scroll_layout(layout: &mut LayoutNode, offset: (i32, i32)) {
+    fn shift(layout: &mut LayoutNode, dx: i32, dy: i32) {
+        layout.rect.x -= dx;
+        layout.rect.y -= dy;
+        for child in &mut layout.children {
+            shift(child, dx, dy);
+        }
+    }
+    for child in &mut layout.children {
+        shift(child, offset.0, offset.1);
+    }
+}
@@ -0,0 +1,105 @@
+//! Streaming display lists between processes.
+//!
+//! Frames are written as newline-delimited JSON, one `DisplayList` per line, so any byte
+//! stream works as transport (pipe, socket, file). `play` decodes a stream and hands each
+//! frame to a `FrameSink`, which can wrap any backend's display-list entry point.
+
+use std::io::{BufRead, Write};
+
+use crate::Error;
+use crate::display_list::DisplayList;
+
+/// Serialize one frame as a single JSON line.
+pub fn write_frame<W: Write>(out: &mut W, list: &DisplayList) -> Result<(), Error> {
+    serde_json::to_writer(&mut *out, list).map_err(|e| Error::Serialize(e.to_string()))?;
+    out.write_all(b"\n")?;
+    Ok(())
+}
+
+/// Writes frames to a stream, flushing after each one so readers see complete frames.
+pub struct FrameWriter<W: Write> {
+    out: W,
+    frames: u64,
+}
+
+impl<W: Write> FrameWriter<W> {
+    pub fn new(out: W) -> Self {
+        FrameWriter { out, frames: 0 }
+    }
+
+    pub fn write(&mut self, list: &DisplayList) -> Result<(), Error> {
+        write_frame(&mut self.out, list)?;
+        self.out.flush()?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Number of frames written so far.
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+/// Iterates the frames of a newline-delimited stream; blank lines are skipped.
+pub struct FrameReader<R: BufRead> {
+    input: R,
+    line: String,
+}
+
+impl<R: BufRead> FrameReader<R> {
+    pub fn new(input: R) -> Self {
+        FrameReader {
+            input,
+            line: String::new(),
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for FrameReader<R> {
+    type Item = Result<DisplayList, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            match self.input.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) if self.line.trim().is_empty() => continue,
+                Ok(_) => {
+                    return Some(
+                        serde_json::from_str(self.line.trim_end())
+                            .map_err(|e| Error::Serialize(e.to_string())),
+                    );
+                }
+                Err(e) => return Some(Err(Error::Io(e))),
+            }
+        }
+    }
+}
+
+/// Receives decoded frames during playback.
+pub trait FrameSink {
+    fn present(&mut self, list: &DisplayList) -> Result<(), Error>;
+}
+
+impl<F> FrameSink for F
+where
+    F: FnMut(&DisplayList) -> Result<(), Error>,
+{
+    fn present(&mut self, list: &DisplayList) -> Result<(), Error> {
+        self(list)
+    }
+}
+
+/// Replay every frame in `input` into `sink`. Returns the number of frames played.
+pub fn play<R: BufRead>(input: R, sink: &mut dyn FrameSink) -> Result<usize, Error> {
+    let mut played = 0;
+    for frame in FrameReader::new(input) {
+        sink.present(&frame?)?;
+        played += 1;
+    }
+    Ok(played)
+}
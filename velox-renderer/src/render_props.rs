@@ -0,0 +1,242 @@
+//! Render properties: per-element opacity, transform, and visibility applied on top of
+//! a built display list.
+//!
+//! While building a display list, the builder records which commands each element with a
+//! `ref` attribute produced. Render properties keyed by that ref name are then applied to
+//! those commands alone: opacity scales their colors, a transform wraps them in a
+//! `PushTransform` (about the element's center, like CSS), and hidden elements' commands
+//! are left out. No style is parsed, no layout is run, and no text is re-wrapped, so
+//! animating a fade or a slide only re-composes the commands.
+//!
+//! `RetainedFrame` keeps a built frame and re-composes it on demand, for hosts that
+//! redraw a retained frame. The window runners build every frame, and apply the
+//! properties set with `set_render_props` on this thread.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use bumpalo::Bump;
+use velox_dom::VNode;
+use velox_dom::layout::LayoutNode;
+
+use crate::display_list::{
+    DisplayList, RectF, RenderCommand, TextMeasure, Transform2D, build_commands,
+};
+
+/// Opacity, transform, and visibility for one element.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RenderProps {
+    /// Multiplies the element's own opacity (and its subtree's).
+    pub opacity: f32,
+    /// Applied about the center of the element's box.
+    pub transform: Option<Transform2D>,
+    pub visible: bool,
+}
+
+impl Default for RenderProps {
+    fn default() -> Self {
+        RenderProps {
+            opacity: 1.0,
+            transform: None,
+            visible: true,
+        }
+    }
+}
+
+impl RenderProps {
+    pub fn with_opacity(self, opacity: f32) -> Self {
+        RenderProps {
+            opacity: opacity.clamp(0.0, 1.0),
+            ..self
+        }
+    }
+
+    pub fn with_transform(self, transform: Transform2D) -> Self {
+        RenderProps {
+            transform: Some(transform),
+            ..self
+        }
+    }
+
+    pub fn with_visible(self, visible: bool) -> Self {
+        RenderProps { visible, ..self }
+    }
+
+    fn is_default(&self) -> bool {
+        *self == RenderProps::default()
+    }
+}
+
+/// The commands one `ref` element produced: its subtree's, in `commands`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeSpan {
+    pub name: String,
+    pub rect: RectF,
+    pub commands: Range<usize>,
+}
+
+/// A built display list and its element spans, re-composed with render properties
+/// without rebuilding.
+#[derive(Clone, Debug, Default)]
+pub struct RetainedFrame {
+    base: DisplayList,
+    spans: Vec<NodeSpan>,
+    props: HashMap<String, RenderProps>,
+}
+
+impl RetainedFrame {
+    /// Build `vnode` at `layout`, keeping the spans of its `ref` elements.
+    pub fn build(vnode: &VNode, layout: &LayoutNode, measure: &mut dyn TextMeasure) -> Self {
+        let mut frame = RetainedFrame::default();
+        build_commands(
+            &mut frame.base,
+            vnode,
+            layout,
+            measure,
+            &Bump::new(),
+            Some(&mut frame.spans),
+        );
+        frame
+    }
+
+    /// The display list as built, without render properties.
+    pub fn base(&self) -> &DisplayList {
+        &self.base
+    }
+
+    pub fn spans(&self) -> &[NodeSpan] {
+        &self.spans
+    }
+
+    /// Set the render properties of the element with `ref="name"`.
+    pub fn set_props(&mut self, name: &str, props: RenderProps) {
+        self.props.insert(name.to_string(), props);
+    }
+
+    pub fn props(&self, name: &str) -> RenderProps {
+        self.props.get(name).copied().unwrap_or_default()
+    }
+
+    pub fn clear_props(&mut self, name: &str) {
+        self.props.remove(name);
+    }
+
+    /// The frame with the current render properties applied.
+    pub fn display_list(&self) -> DisplayList {
+        let mut list = DisplayList {
+            width: self.base.width,
+            height: self.base.height,
+            commands: Vec::new(),
+        };
+        compose(
+            &self.base.commands,
+            &self.spans,
+            |name| self.props.get(name).copied(),
+            &mut list.commands,
+        );
+        list
+    }
+}
+
+thread_local! {
+    static RENDER_PROPS: RefCell<HashMap<String, RenderProps>> = RefCell::new(HashMap::new());
+}
+
+/// Set the render properties the display lists built on this thread apply to the
+/// element with `ref="name"`.
+pub fn set_render_props(name: &str, props: RenderProps) {
+    RENDER_PROPS.with(|p| p.borrow_mut().insert(name.to_string(), props));
+}
+
+pub fn clear_render_props(name: &str) {
+    RENDER_PROPS.with(|p| p.borrow_mut().remove(name));
+}
+
+/// The properties set for `name` on this thread, if any.
+pub fn render_props(name: &str) -> Option<RenderProps> {
+    RENDER_PROPS.with(|p| p.borrow().get(name).copied())
+}
+
+pub(crate) fn has_render_props() -> bool {
+    RENDER_PROPS.with(|p| !p.borrow().is_empty())
+}
+
+/// Copy `commands` to `out`, applying `props` to the commands of each span.
+pub(crate) fn compose(
+    commands: &[RenderCommand],
+    spans: &[NodeSpan],
+    props: impl Fn(&str) -> Option<RenderProps>,
+    out: &mut Vec<RenderCommand>,
+) {
+    struct Open {
+        end: usize,
+        opacity: f32,
+        hidden: bool,
+        transformed: bool,
+    }
+    // Spans are in tree order, so each starts no earlier than the one before it and
+    // nests inside any still open.
+    let mut pending = spans
+        .iter()
+        .filter(|s| !s.commands.is_empty())
+        .filter_map(|s| Some((s, props(&s.name).filter(|p| !p.is_default())?)))
+        .peekable();
+    let mut open: Vec<Open> = Vec::new();
+    out.reserve(commands.len());
+    for i in 0..=commands.len() {
+        while open.last().is_some_and(|o| o.end <= i) {
+            let o = open.pop().unwrap();
+            if o.transformed {
+                out.push(RenderCommand::PopTransform);
+            }
+        }
+        while let Some((span, p)) = pending.next_if(|(s, _)| s.commands.start == i) {
+            let (opacity, hidden) = open.last().map_or((1.0, false), |o| (o.opacity, o.hidden));
+            let hidden = hidden || !p.visible;
+            let transformed = !hidden && p.transform.is_some();
+            if let Some(t) = p.transform.filter(|_| !hidden) {
+                let (cx, cy) = (
+                    span.rect.x + span.rect.w / 2.0,
+                    span.rect.y + span.rect.h / 2.0,
+                );
+                out.push(RenderCommand::PushTransform(Transform2D {
+                    sx: t.sx,
+                    sy: t.sy,
+                    tx: cx * (1.0 - t.sx) + t.tx,
+                    ty: cy * (1.0 - t.sy) + t.ty,
+                }));
+            }
+            open.push(Open {
+                end: span.commands.end,
+                opacity: opacity * p.opacity,
+                hidden,
+                transformed,
+            });
+        }
+        let Some(command) = commands.get(i) else {
+            break;
+        };
+        match open.last() {
+            Some(o) if o.hidden => {}
+            Some(o) if o.opacity < 1.0 => out.push(fade(command, o.opacity)),
+            _ => out.push(command.clone()),
+        }
+    }
+}
+
+fn fade(command: &RenderCommand, opacity: f32) -> RenderCommand {
+    let mut command = command.clone();
+    match &mut command {
+        RenderCommand::Rect { color, .. }
+        | RenderCommand::RRect { color, .. }
+        | RenderCommand::Border { color, .. }
+        | RenderCommand::Text { color, .. } => *color = color.with_opacity(opacity),
+        RenderCommand::Image { opacity: o, .. } => *o *= opacity,
+        RenderCommand::PushClip { .. }
+        | RenderCommand::PopClip
+        | RenderCommand::PushTransform(_)
+        | RenderCommand::PopTransform => {}
+    }
+    command
+}
@@ -0,0 +1,370 @@
+//! Recording what the user does and playing it back.
+//!
+//! A `Script` is a list of timed inputs: pointer moves, clicks with the handler they hit,
+//! shortcut keys, and events sent to the app directly. The window runners and `VeloxUi`
+//! record input while `start_recording` is on. `play_in_window` replays a script in the
+//! next window at its recorded pace, e.g. for demos. `Script::play` replays it headlessly
+//! against a `TestApp` and fails when a click no longer hits the handler it hit when
+//! recorded, so a recorded flow doubles as a regression test. In an app,
+//! `VELOX_RECORD=flow.txt` records the session when the window closes, and
+//! `VELOX_REPLAY=flow.txt` plays it back.
+//!
+//! Scripts are text, one step per line (`350 click 120 40 inc`): the milliseconds since
+//! the start, the input, and its arguments, with positions in logical pixels.
+
+use std::cell::RefCell;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use velox_core::clock::Clock;
+
+use crate::Error;
+use crate::shortcuts::{Shortcut, Shortcuts};
+use crate::testing::TestApp;
+
+/// Record the window session into this file when the window closes.
+pub const RECORD_ENV: &str = "VELOX_RECORD";
+/// Play this script in the window.
+pub const REPLAY_ENV: &str = "VELOX_REPLAY";
+
+/// One input, in logical viewport pixels.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Input {
+    /// The pointer moved to `(x, y)`.
+    Move { x: f32, y: f32 },
+    /// A left press at `(x, y)`, and the click handler it hit.
+    Click {
+        x: f32,
+        y: f32,
+        target: Option<String>,
+    },
+    /// A shortcut key, as `Shortcut` prints it (`Ctrl+Z`).
+    Key(String),
+    /// An event sent to the app without hit-testing.
+    Emit {
+        handler: String,
+        payload: Option<String>,
+    },
+}
+
+/// An input and when it happened, in milliseconds since the script started.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    pub at_ms: u64,
+    pub input: Input,
+}
+
+/// A recorded or hand-written interaction: see the module docs. In the text form `#`
+/// starts a comment, and an `emit` payload runs to the end of the line, with `\n` and
+/// `\\` escaped. Only moves that change what is hovered are recorded.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Script {
+    pub steps: Vec<Step>,
+}
+
+impl Script {
+    /// Parse the text form. Errors name the line.
+    pub fn parse(src: &str) -> Result<Self, Error> {
+        let mut steps = Vec::new();
+        for (i, line) in src.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let step = parse_step(line)
+                .ok_or_else(|| Error::Replay(format!("line {}: cannot read `{}`", i + 1, line)))?;
+            steps.push(step);
+        }
+        Ok(Script { steps })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Script::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        std::fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// Replay the script against `app`, without waiting between steps. With a manual
+    /// `clock`, time is advanced by each gap first, so animations and debounces see the
+    /// recorded timing. Moves are skipped: a `TestApp` has no pointer.
+    ///
+    /// Fails at the first click that hits a different handler than when recorded, and at
+    /// the first key no longer bound.
+    pub fn play(&self, app: &mut TestApp, clock: Option<&Clock>) -> Result<(), Error> {
+        let mut last_ms = 0;
+        for (n, step) in self.steps.iter().enumerate() {
+            if let Some(clock) = clock {
+                clock.advance(step.at_ms.saturating_sub(last_ms));
+                last_ms = step.at_ms;
+            }
+            let fail = |what: String| {
+                Err(Error::Replay(format!(
+                    "step {} ({}): {}",
+                    n + 1,
+                    step.input,
+                    what
+                )))
+            };
+            match &step.input {
+                Input::Move { .. } => {}
+                Input::Click { x, y, target } => {
+                    let frame = app.frame();
+                    let mut targets = Vec::new();
+                    crate::events::collect_click_targets(&frame.vnode, &frame.layout, &mut targets);
+                    let hit = crate::events::hit_test_click(&targets, *x, *y)
+                        .map(|(handler, _)| handler.to_string());
+                    if hit != *target {
+                        return fail(format!("hit {}", hit.as_deref().unwrap_or("nothing")));
+                    }
+                    app.click_at(*x, *y);
+                }
+                Input::Key(combo) => {
+                    let Some(shortcut) = Shortcut::parse(combo) else {
+                        return fail("not a key".into());
+                    };
+                    let shortcuts = Shortcuts::current();
+                    shortcuts.update_from_view(app.tree());
+                    let Some((handler, payload)) = shortcuts.resolve(&shortcut) else {
+                        return fail("not bound".into());
+                    };
+                    if !crate::shortcuts::run_builtin(&handler, payload.as_deref()) {
+                        app.emit(&handler, payload.as_deref());
+                    }
+                }
+                Input::Emit { handler, payload } => {
+                    app.emit(handler, payload.as_deref());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_step(line: &str) -> Option<Step> {
+    let (at, rest) = line.split_once(char::is_whitespace)?;
+    let at_ms = at.parse().ok()?;
+    let rest = rest.trim_start();
+    let (kind, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    let mut words = args.split_whitespace();
+    let mut coord = || words.next().and_then(|w| w.parse::<f32>().ok());
+    let input = match kind {
+        "move" => Input::Move {
+            x: coord()?,
+            y: coord()?,
+        },
+        "click" => {
+            let (x, y) = (coord()?, coord()?);
+            Input::Click {
+                x,
+                y,
+                target: words.next().map(str::to_string),
+            }
+        }
+        "key" if !args.trim().is_empty() => Input::Key(args.trim().to_string()),
+        "emit" if !args.trim().is_empty() => {
+            let args = args.trim_start();
+            let (handler, payload) = args
+                .split_once(' ')
+                .map_or((args, None), |(h, p)| (h, Some(unescape(p))));
+            Input::Emit {
+                handler: handler.to_string(),
+                payload,
+            }
+        }
+        _ => return None,
+    };
+    Some(Step { at_ms, input })
+}
+
+fn unescape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::Move { x, y } => write!(f, "move {} {}", x, y),
+            Input::Click {
+                x,
+                y,
+                target: Some(target),
+            } => write!(f, "click {} {} {}", x, y, target),
+            Input::Click { x, y, target: None } => write!(f, "click {} {}", x, y),
+            Input::Key(combo) => write!(f, "key {}", combo),
+            Input::Emit {
+                handler,
+                payload: None,
+            } => write!(f, "emit {}", handler),
+            Input::Emit {
+                handler,
+                payload: Some(payload),
+            } => {
+                write!(
+                    f,
+                    "emit {} {}",
+                    handler,
+                    payload.replace('\\', "\\\\").replace('\n', "\\n")
+                )
+            }
+        }
+    }
+}
+
+/// The text form `Script::parse` reads.
+impl fmt::Display for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            writeln!(f, "{} {}", step.at_ms, step.input)?;
+        }
+        Ok(())
+    }
+}
+
+struct Player {
+    script: Script,
+    next: usize,
+    started: Option<Instant>,
+}
+
+thread_local! {
+    static RECORDING: RefCell<Option<(Instant, Script)>> = const { RefCell::new(None) };
+    /// Where `finish` saves the recording started by `RECORD_ENV`.
+    static RECORD_PATH: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+    static PLAYER: RefCell<Option<Player>> = const { RefCell::new(None) };
+}
+
+/// Record the input of windows on this thread from now on.
+pub fn start_recording() {
+    RECORDING.with(|r| *r.borrow_mut() = Some((Instant::now(), Script::default())));
+}
+
+pub fn is_recording() -> bool {
+    RECORDING.with(|r| r.borrow().is_some())
+}
+
+/// Stop recording and return what was recorded.
+pub fn stop_recording() -> Script {
+    RECORDING
+        .with(|r| r.borrow_mut().take())
+        .map(|(_, script)| script)
+        .unwrap_or_default()
+}
+
+/// Replay `script` in the next window opened on this thread, starting with its first
+/// frame. Real input keeps working alongside it.
+pub fn play_in_window(script: Script) {
+    PLAYER.with(|p| {
+        *p.borrow_mut() = Some(Player {
+            script,
+            next: 0,
+            started: None,
+        })
+    });
+}
+
+/// Whether a script is waiting for or playing in a window.
+pub fn is_playing() -> bool {
+    PLAYER.with(|p| p.borrow().is_some())
+}
+
+/// Called by the runners for the input they handle.
+pub(crate) fn record(input: Input) {
+    RECORDING.with(|r| {
+        if let Some((started, script)) = r.borrow_mut().as_mut() {
+            let at_ms = started.elapsed().as_millis() as u64;
+            script.steps.push(Step { at_ms, input });
+        }
+    });
+}
+
+/// The inputs of the playing script due by `now`, in order.
+pub(crate) fn due(now: Instant) -> Vec<Input> {
+    PLAYER.with(|p| {
+        let mut player = p.borrow_mut();
+        let Some(pl) = player.as_mut() else {
+            return Vec::new();
+        };
+        let started = *pl.started.get_or_insert(now);
+        let mut out = Vec::new();
+        while let Some(step) = pl.script.steps.get(pl.next)
+            && started + Duration::from_millis(step.at_ms) <= now
+        {
+            out.push(step.input.clone());
+            pl.next += 1;
+        }
+        if pl.next >= pl.script.steps.len() {
+            *player = None;
+        }
+        out
+    })
+}
+
+/// When the next step of the playing script is due, for runners to wake up then.
+#[cfg_attr(
+    not(any(feature = "skia-native", feature = "raster", feature = "wgpu")),
+    allow(dead_code)
+)]
+pub(crate) fn next_at() -> Option<Instant> {
+    PLAYER.with(|p| {
+        let player = p.borrow();
+        let pl = player.as_ref()?;
+        let step = pl.script.steps.get(pl.next)?;
+        Some(pl.started? + Duration::from_millis(step.at_ms))
+    })
+}
+
+/// Start recording or playing as `RECORD_ENV` and `REPLAY_ENV` ask; the runners call
+/// this when their window opens.
+#[cfg_attr(
+    not(any(feature = "skia-native", feature = "raster", feature = "wgpu")),
+    allow(dead_code)
+)]
+pub(crate) fn start_from_env() {
+    if let Some(path) = std::env::var_os(RECORD_ENV).filter(|p| !p.is_empty()) {
+        RECORD_PATH.with(|p| *p.borrow_mut() = Some(path.into()));
+        start_recording();
+    }
+    if let Some(path) = std::env::var_os(REPLAY_ENV).filter(|p| !p.is_empty()) {
+        match Script::load(&path) {
+            Ok(script) => play_in_window(script),
+            Err(e) => {
+                log::error!(target: "velox::renderer", "not replaying {}: {}", Path::new(&path).display(), e)
+            }
+        }
+    }
+}
+
+/// Save the recording `start_from_env` started; the runners call this when their
+/// window closes.
+#[cfg_attr(
+    not(any(feature = "skia-native", feature = "raster", feature = "wgpu")),
+    allow(dead_code)
+)]
+pub(crate) fn finish() {
+    let Some(path) = RECORD_PATH.with(|p| p.borrow_mut().take()) else {
+        return;
+    };
+    match stop_recording().save(&path) {
+        Ok(()) => log::info!(target: "velox::renderer", "recorded input to {}", path.display()),
+        Err(e) => {
+            log::error!(target: "velox::renderer", "saving the recording to {} failed: {}", path.display(), e)
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Screenshots of the live window.
+//!
+//! `capture_screenshot(path)` asks the window runner on this thread to save the next
+//! frame it presents as a PNG: the softbuffer runners (Skia raster and tiny-skia) save
+//! the frame they just copied to the window, and the wgpu runner reads the swapchain
+//! texture back before presenting. Pressing `F12` does the same with a timestamped file
+//! in the working directory, through the default `screenshot` shortcut; apps can rebind
+//! `F12` in their own views, which shadows it.
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use crate::pixels::RgbaImage;
+
+/// The default combination, bound in the global shortcut scope.
+pub const SHORTCUT: &str = "F12";
+/// The handler name the default binding dispatches; runners handle it themselves.
+pub const HANDLER: &str = "screenshot";
+
+thread_local! {
+    static PENDING: RefCell<Option<PathBuf>> = const { RefCell::new(None) };
+}
+
+/// Save the next frame presented on this thread to `path` as a PNG. A second request
+/// before that frame replaces the first.
+pub fn capture_screenshot(path: impl Into<PathBuf>) {
+    PENDING.with(|p| *p.borrow_mut() = Some(path.into()));
+}
+
+/// True while a requested screenshot waits for its frame.
+pub fn is_pending() -> bool {
+    PENDING.with(|p| p.borrow().is_some())
+}
+
+/// `velox-screenshot-<unix seconds>.png` in the working directory, where `F12` saves.
+pub fn default_path() -> PathBuf {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    PathBuf::from(format!("velox-screenshot-{}.png", secs))
+}
+
+/// Take a resolved shortcut the runner handles itself. Returns whether it was one.
+pub(crate) fn handle_shortcut(handler: &str, payload: Option<&str>) -> bool {
+    if handler != HANDLER || payload.is_some() {
+        return false;
+    }
+    capture_screenshot(default_path());
+    true
+}
+
+/// Called by runners with each presented frame; saves it if a screenshot is pending.
+#[cfg_attr(
+    not(any(feature = "wgpu", feature = "skia-native", feature = "raster")),
+    allow(dead_code)
+)]
+pub(crate) fn store_frame(image: &RgbaImage) {
+    let Some(path) = PENDING.with(|p| p.borrow_mut().take()) else {
+        return;
+    };
+    match image.save_png(&path) {
+        Ok(()) => log::info!(target: "velox::renderer", "saved screenshot to {}", path.display()),
+        Err(e) => log::error!(target: "velox::renderer", "screenshot {}: {}", path.display(), e),
+    }
+}
@@ -0,0 +1,485 @@
+//! Keyboard shortcuts.
+//!
+//! A `Shortcuts` registry maps key combinations such as `Ctrl+S` to handler names, which
+//! the window runners dispatch through `on_event` like clicks (or, with `dispatch`,
+//! through an `EventRegistry`). `bind` adds a global binding, and `push_scope` /
+//! `pop_scope` stack named scopes whose bindings override the globals while pushed. In
+//! the view, `v-shortcut="Ctrl+S"` binds the combination to the element's `on:click` (or
+//! `on:shortcut`) handler and payload, and bindings inside a `shortcut-scope` element (a
+//! modal, say) override everything outside it. The runners refresh these every frame.
+//!
+//! Binding one combination to two handlers in the same scope is a conflict: it is logged
+//! once as a warning and listed by `conflicts`. In code the later binding wins; in the
+//! view the first in tree order does.
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::rc::Rc;
+
+use velox_dom::VNode;
+
+use crate::events::EventRegistry;
+
+/// A key with modifiers. Keys are named like the DOM's `KeyboardEvent.key`, with letters
+/// in upper case and `Space` for the space bar; `Cmd`, `Meta`, and `Super` all mean the
+/// logo key.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+    pub key: String,
+}
+
+impl Shortcut {
+    pub fn new(key: &str, ctrl: bool, alt: bool, shift: bool, meta: bool) -> Self {
+        Shortcut {
+            ctrl,
+            alt,
+            shift,
+            meta,
+            key: normalize_key(key),
+        }
+    }
+
+    /// Parse `Ctrl+Shift+Z`-style text; modifiers in any order and case. `None` without
+    /// a key or with an unknown modifier.
+    pub fn parse(s: &str) -> Option<Shortcut> {
+        let parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        // A trailing `+` is the plus key itself: `Ctrl++`.
+        let (mods, key) = match parts.as_slice() {
+            [mods @ .., "", ""] if !mods.is_empty() => (mods, "+"),
+            [mods @ .., key] => (mods, *key),
+            [] => return None,
+        };
+        if key.is_empty() {
+            return None;
+        }
+        let mut shortcut = Shortcut::new(key, false, false, false, false);
+        for m in mods {
+            match m.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => shortcut.ctrl = true,
+                "alt" | "option" => shortcut.alt = true,
+                "shift" => shortcut.shift = true,
+                "cmd" | "meta" | "super" | "win" => shortcut.meta = true,
+                _ => return None,
+            }
+        }
+        Some(shortcut)
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (on, name) in [
+            (self.ctrl, "Ctrl+"),
+            (self.alt, "Alt+"),
+            (self.shift, "Shift+"),
+            (self.meta, "Meta+"),
+        ] {
+            if on {
+                f.write_str(name)?;
+            }
+        }
+        f.write_str(&self.key)
+    }
+}
+
+fn normalize_key(key: &str) -> String {
+    match key.to_ascii_lowercase().as_str() {
+        "esc" | "escape" => "Escape".into(),
+        "enter" | "return" => "Enter".into(),
+        "space" | " " => "Space".into(),
+        "del" | "delete" => "Delete".into(),
+        "backspace" => "Backspace".into(),
+        "tab" => "Tab".into(),
+        "up" | "arrowup" => "ArrowUp".into(),
+        "down" | "arrowdown" => "ArrowDown".into(),
+        "left" | "arrowleft" => "ArrowLeft".into(),
+        "right" | "arrowright" => "ArrowRight".into(),
+        "home" => "Home".into(),
+        "end" => "End".into(),
+        "pageup" => "PageUp".into(),
+        "pagedown" => "PageDown".into(),
+        _ if key.chars().count() == 1 => key.to_uppercase(),
+        _ if key.starts_with(['f', 'F'])
+            && key.len() <= 3
+            && key[1..].chars().all(|c| c.is_ascii_digit()) =>
+        {
+            key.to_uppercase()
+        }
+        _ => key.to_string(),
+    }
+}
+
+/// Global bindings every registry starts with: the undo/redo keys dispatch `undo` and
+/// `redo`, for the app to route to its `History`. `F12` (`screenshot`) and the zoom keys
+/// (`zoom-in`, `zoom-out`, `zoom-reset`) are handled by the window runners themselves; see
+/// `screenshot` and `zoom`. `bind` or `unbind` replaces them.
+pub const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    ("Ctrl+Z", "undo"),
+    ("Meta+Z", "undo"),
+    ("Ctrl+Shift+Z", "redo"),
+    ("Meta+Shift+Z", "redo"),
+    ("Ctrl+Y", "redo"),
+    (crate::screenshot::SHORTCUT, crate::screenshot::HANDLER),
+    ("Ctrl+=", crate::zoom::ZOOM_IN),
+    ("Ctrl+Shift+=", crate::zoom::ZOOM_IN),
+    ("Ctrl++", crate::zoom::ZOOM_IN),
+    ("Ctrl+-", crate::zoom::ZOOM_OUT),
+    ("Ctrl+0", crate::zoom::ZOOM_RESET),
+    ("Meta+=", crate::zoom::ZOOM_IN),
+    ("Meta+Shift+=", crate::zoom::ZOOM_IN),
+    ("Meta+-", crate::zoom::ZOOM_OUT),
+    ("Meta+0", crate::zoom::ZOOM_RESET),
+];
+
+/// Run a default binding the window runners handle themselves (screenshots and zoom).
+/// Returns whether `handler` was one; anything else goes to the app.
+#[cfg_attr(
+    not(any(feature = "wgpu", feature = "skia-native", feature = "raster")),
+    allow(dead_code)
+)]
+pub(crate) fn run_builtin(handler: &str, payload: Option<&str>) -> bool {
+    crate::screenshot::handle_shortcut(handler, payload)
+        || crate::zoom::handle_shortcut(handler, payload)
+}
+
+/// A combination bound to two handlers in one scope.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub scope: String,
+    pub shortcut: String,
+    pub handlers: (String, String),
+}
+
+#[derive(Clone, Debug)]
+struct Binding {
+    shortcut: Shortcut,
+    handler: String,
+    payload: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Scope {
+    name: String,
+    bindings: Vec<Binding>,
+}
+
+impl Scope {
+    fn find(&self, shortcut: &Shortcut) -> Option<&Binding> {
+        self.bindings.iter().find(|b| &b.shortcut == shortcut)
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    global: Scope,
+    /// Pushed from code, bottom to top.
+    stack: Vec<Scope>,
+    /// From the view: unscoped bindings, then `shortcut-scope` elements with their depth.
+    view_global: Scope,
+    view_scopes: Vec<(usize, Scope)>,
+    conflicts: Vec<Conflict>,
+    /// Warnings already logged.
+    warned: HashSet<String>,
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        let bindings = DEFAULT_BINDINGS
+            .iter()
+            .filter_map(|(combo, handler)| {
+                Some(Binding {
+                    shortcut: Shortcut::parse(combo)?,
+                    handler: handler.to_string(),
+                    payload: None,
+                })
+            })
+            .collect();
+        Inner {
+            global: Scope {
+                name: "global".into(),
+                bindings,
+            },
+            stack: Vec::new(),
+            view_global: Scope::default(),
+            view_scopes: Vec::new(),
+            conflicts: Vec::new(),
+            warned: HashSet::new(),
+        }
+    }
+}
+
+impl Inner {
+    fn conflict(&mut self, scope: &str, shortcut: &Shortcut, existing: &str, new: &str) {
+        let c = Conflict {
+            scope: scope.to_string(),
+            shortcut: shortcut.to_string(),
+            handlers: (existing.to_string(), new.to_string()),
+        };
+        self.warn_once(format!(
+            "{} is bound to both `{}` and `{}` in scope `{}`",
+            c.shortcut, existing, new, scope
+        ));
+        if !self.conflicts.contains(&c) {
+            self.conflicts.push(c);
+        }
+    }
+
+    /// Scopes in resolution order: view scopes (innermost first), pushed scopes (top
+    /// first), then the globals.
+    fn scopes(&self) -> Vec<&Scope> {
+        let mut view_scopes: Vec<&(usize, Scope)> = self.view_scopes.iter().collect();
+        view_scopes.sort_by_key(|s| std::cmp::Reverse(s.0));
+        let mut out: Vec<&Scope> = view_scopes.into_iter().map(|(_, s)| s).collect();
+        out.extend(self.stack.iter().rev());
+        out.extend([&self.view_global, &self.global]);
+        out
+    }
+
+    fn warn_once(&mut self, message: String) {
+        if !self.warned.contains(&message) {
+            log::warn!(target: "velox::shortcuts", "{}", message);
+            self.warned.insert(message);
+        }
+    }
+}
+
+/// Handle to a window's shortcut registry. Clones share the same state.
+#[derive(Clone, Debug, Default)]
+pub struct Shortcuts {
+    inner: Rc<RefCell<Inner>>,
+}
+
+thread_local! {
+    static CURRENT: Shortcuts = Shortcuts::default();
+}
+
+impl Shortcuts {
+    /// A standalone registry, e.g. for a custom runner or tests.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The registry of the window running on this thread.
+    pub fn current() -> Shortcuts {
+        CURRENT.with(Shortcuts::clone)
+    }
+
+    /// Bind `combo` globally. Rebinding it to another handler replaces the old binding
+    /// and reports a conflict, unless the old one was a default binding.
+    pub fn bind(&self, combo: &str, handler: &str) -> Result<(), String> {
+        let shortcut =
+            Shortcut::parse(combo).ok_or_else(|| format!("invalid shortcut `{}`", combo))?;
+        let mut inner = self.inner.borrow_mut();
+        if let Some(i) = inner
+            .global
+            .bindings
+            .iter()
+            .position(|b| b.shortcut == shortcut)
+        {
+            let existing = inner.global.bindings.remove(i);
+            let is_default = DEFAULT_BINDINGS.iter().any(|(c, h)| {
+                Shortcut::parse(c).as_ref() == Some(&shortcut) && *h == existing.handler
+            });
+            if existing.handler != handler && !is_default {
+                inner.conflict("global", &shortcut, &existing.handler, handler);
+            }
+        }
+        inner.global.bindings.push(Binding {
+            shortcut,
+            handler: handler.to_string(),
+            payload: None,
+        });
+        Ok(())
+    }
+
+    pub fn unbind(&self, combo: &str) {
+        if let Some(shortcut) = Shortcut::parse(combo) {
+            self.inner
+                .borrow_mut()
+                .global
+                .bindings
+                .retain(|b| b.shortcut != shortcut);
+        }
+    }
+
+    /// Push a scope whose `(combo, handler)` bindings override everything below it.
+    pub fn push_scope(&self, name: &str, bindings: &[(&str, &str)]) -> Result<(), String> {
+        let mut scope = Scope {
+            name: name.to_string(),
+            bindings: Vec::with_capacity(bindings.len()),
+        };
+        let mut inner = self.inner.borrow_mut();
+        for (combo, handler) in bindings {
+            let shortcut =
+                Shortcut::parse(combo).ok_or_else(|| format!("invalid shortcut `{}`", combo))?;
+            if let Some(existing) = scope.find(&shortcut) {
+                let existing = existing.handler.clone();
+                inner.conflict(name, &shortcut, &existing, handler);
+                scope.bindings.retain(|b| b.shortcut != shortcut);
+            }
+            scope.bindings.push(Binding {
+                shortcut,
+                handler: handler.to_string(),
+                payload: None,
+            });
+        }
+        inner.stack.push(scope);
+        Ok(())
+    }
+
+    /// Remove the topmost scope named `name`. Returns false if none is pushed.
+    pub fn pop_scope(&self, name: &str) -> bool {
+        let mut inner = self.inner.borrow_mut();
+        let Some(i) = inner.stack.iter().rposition(|s| s.name == name) else {
+            return false;
+        };
+        inner.stack.remove(i);
+        true
+    }
+
+    /// Replace the view's bindings with the `shortcut` attributes in `vnode`.
+    pub fn update_from_view(&self, vnode: &VNode) {
+        let mut global = Scope {
+            name: "global".into(),
+            bindings: Vec::new(),
+        };
+        let mut scopes = Vec::new();
+        let mut found = Vec::new();
+        collect(vnode, None, 0, &mut global, &mut scopes, &mut found);
+        let mut inner = self.inner.borrow_mut();
+        for problem in found {
+            match problem {
+                Found::Conflict(scope, shortcut, existing, new) => {
+                    inner.conflict(&scope, &shortcut, &existing, &new)
+                }
+                Found::Invalid(message) => inner.warn_once(message),
+            }
+        }
+        inner.view_global = global;
+        inner.view_scopes = scopes;
+    }
+
+    /// The handler and payload `shortcut` triggers, searching view scopes (innermost
+    /// first), then pushed scopes (top first), then the globals.
+    pub fn resolve(&self, shortcut: &Shortcut) -> Option<(String, Option<String>)> {
+        let inner = self.inner.borrow();
+        inner
+            .scopes()
+            .into_iter()
+            .find_map(|s| s.find(shortcut))
+            .map(|b| (b.handler.clone(), b.payload.clone()))
+    }
+
+    /// Resolve `shortcut` and call its handler in `registry`. Returns whether one ran.
+    pub fn dispatch(&self, shortcut: &Shortcut, registry: &mut EventRegistry) -> bool {
+        match self.resolve(shortcut) {
+            Some((handler, _)) => registry.emit(&handler),
+            None => false,
+        }
+    }
+
+    /// Every active binding as `(combo, handler)`, in resolution order, e.g. to show
+    /// shortcut hints next to commands. Combos shadowed by a higher scope are left out.
+    pub fn bindings(&self) -> Vec<(String, String)> {
+        let inner = self.inner.borrow();
+        let mut seen = HashSet::new();
+        inner
+            .scopes()
+            .into_iter()
+            .flat_map(|s| s.bindings.iter())
+            .filter(|b| seen.insert(b.shortcut.clone()))
+            .map(|b| (b.shortcut.to_string(), b.handler.clone()))
+            .collect()
+    }
+
+    /// Conflicts found so far.
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        self.inner.borrow().conflicts.clone()
+    }
+}
+
+enum Found {
+    Conflict(String, Shortcut, String, String),
+    Invalid(String),
+}
+
+fn collect(
+    node: &VNode,
+    scope: Option<usize>,
+    depth: usize,
+    global: &mut Scope,
+    scopes: &mut Vec<(usize, Scope)>,
+    found: &mut Vec<Found>,
+) {
+    if let VNode::Fragment(children) = node {
+        for c in children {
+            collect(c, scope, depth, global, scopes, found);
+        }
+        return;
+    }
+    let VNode::Element {
+        props, children, ..
+    } = node
+    else {
+        return;
+    };
+    let scope = match props.str("shortcut-scope") {
+        Some(name) => {
+            scopes.push((
+                depth,
+                Scope {
+                    name: name.to_string(),
+                    bindings: Vec::new(),
+                },
+            ));
+            Some(scopes.len() - 1)
+        }
+        None => scope,
+    };
+    if let Some(combo) = props.str("shortcut") {
+        let handler = props.str("on:click").or_else(|| props.str("on:shortcut"));
+        match (Shortcut::parse(combo), handler) {
+            (Some(shortcut), Some(handler)) => {
+                let target = match scope {
+                    Some(i) => &mut scopes[i].1,
+                    None => &mut *global,
+                };
+                let payload_key = if props.contains("on:click") {
+                    "on:click-payload"
+                } else {
+                    "on:shortcut-payload"
+                };
+                let payload = props.str(payload_key).map(str::to_string);
+                match target.find(&shortcut) {
+                    Some(existing) if existing.handler != *handler => {
+                        found.push(Found::Conflict(
+                            target.name.clone(),
+                            shortcut,
+                            existing.handler.clone(),
+                            handler.to_string(),
+                        ));
+                    }
+                    Some(_) => {}
+                    None => target.bindings.push(Binding {
+                        shortcut,
+                        handler: handler.to_string(),
+                        payload,
+                    }),
+                }
+            }
+            (None, _) => found.push(Found::Invalid(format!("invalid shortcut `{}`", combo))),
+            (Some(_), None) => found.push(Found::Invalid(format!(
+                "shortcut `{}` has no on:click or on:shortcut handler",
+                combo
+            ))),
+        }
+    }
+    for c in children {
+        collect(c, scope, depth + 1, global, scopes, found);
+    }
+}
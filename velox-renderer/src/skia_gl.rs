@@ -23,6 +23,8 @@ mod unix_impl {
     use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
     use glow::HasContext;
 
+    use crate::Error;
+
     pub struct SkiaGlContext {
         // EGL handles
         pub egl_display: egl::EGLDisplay,
@@ -41,7 +43,7 @@ mod unix_impl {
                 skia_safe::gpu::direct_contexts::make_gl(iface, None)
             }
 
-        pub fn make_current(&self) -> Result<(), String> {
+        pub fn make_current(&self) -> Result<(), Error> {
             if egl::make_current(
                 self.egl_display,
                 self.egl_surface,
@@ -50,7 +52,7 @@ mod unix_impl {
             ) {
                 Ok(())
             } else {
-                Err("egl: make_current failed".into())
+                Err(Error::GlContext("egl: make_current failed".into()))
             }
         }
     }
@@ -80,42 +82,40 @@ mod unix_impl {
         egl::choose_config(dpy, attribs, 1)
     }
 
-    pub fn create_context_from_winit(window: &impl HasRawWindowHandle) -> Result<SkiaGlContext, String> {
+    pub fn create_context_from_winit(window: &impl HasRawWindowHandle) -> Result<SkiaGlContext, Error> {
         // Acquire raw handle (currently unused) and implement a minimal EGL init path.
         let _raw = window.raw_window_handle();
 
         // Initialize EGL display
         let display = egl::get_display(egl::EGL_DEFAULT_DISPLAY).ok_or_else(|| {
-            let msg = "egl: no display".to_string();
-            eprintln!("[skia_gl] {}", msg);
-            msg
+            log::error!(target: "velox::skia", "egl: no display");
+            Error::GlContext("egl: no display".into())
         })?;
         let mut major: egl::EGLint = 0;
         let mut minor: egl::EGLint = 0;
         if !egl::initialize(display, &mut major, &mut minor) {
-            eprintln!("[skia_gl] egl: failed to initialize (major={}, minor={})", major, minor);
-            return Err("egl: failed to initialize".into());
+            log::error!(target: "velox::skia", "egl: failed to initialize (major={}, minor={})", major, minor);
+            return Err(Error::GlContext("egl: failed to initialize".into()));
         }
 
         let config = choose_egl_config(display).ok_or_else(|| {
-            let msg = "egl: no config".to_string();
-            eprintln!("[skia_gl] {}", msg);
-            msg
+            log::error!(target: "velox::skia", "egl: no config");
+            Error::GlContext("egl: no config".into())
         })?;
 
         // Create an EGL context
         let ctx_attribs: &[egl::EGLint] = &[egl::EGL_CONTEXT_CLIENT_VERSION as egl::EGLint, 2, egl::EGL_NONE as egl::EGLint];
-        let context = egl::create_context(display, config, egl::EGL_NO_CONTEXT, ctx_attribs).ok_or_else(|| "egl: failed to create context".to_string())?;
+        let context = egl::create_context(display, config, egl::EGL_NO_CONTEXT, ctx_attribs).ok_or_else(|| Error::GlContext("egl: failed to create context".into()))?;
 
         // Create a pbuffer surface as a default headless surface
         let pbuffer_attribs: &[egl::EGLint] = &[egl::EGL_WIDTH as egl::EGLint, 1, egl::EGL_HEIGHT as egl::EGLint, 1, egl::EGL_NONE as egl::EGLint];
-        let surface = egl::create_pbuffer_surface(display, config, pbuffer_attribs).ok_or_else(|| "egl: failed to create pbuffer surface".to_string())?;
+        let surface = egl::create_pbuffer_surface(display, config, pbuffer_attribs).ok_or_else(|| Error::GlContext("egl: failed to create pbuffer surface".into()))?;
 
         // Make context current
         if !egl::make_current(display, surface, surface, context) {
             egl::destroy_surface(display, surface);
             egl::destroy_context(display, context);
-            return Err("egl: make_current failed".into());
+            return Err(Error::GlContext("egl: make_current failed".into()));
         }
 
         // Build skia-safe GL interface from current GL funcs
@@ -127,7 +127,7 @@ mod unix_impl {
 
         let iface = match interface {
             Some(i) => i,
-            None => return Err("skia: failed to create GL interface".into()),
+            None => return Err(Error::GlContext("skia: failed to create GL interface".into())),
         };
 
         Ok(SkiaGlContext {
@@ -139,35 +139,33 @@ mod unix_impl {
     }
 
     /// Create a headless pbuffer-backed EGL context (no window required).
-    pub fn create_headless_context() -> Result<SkiaGlContext, String> {
+    pub fn create_headless_context() -> Result<SkiaGlContext, Error> {
         let display = egl::get_display(egl::EGL_DEFAULT_DISPLAY).ok_or_else(|| {
-            let msg = "egl: no display".to_string();
-            eprintln!("[skia_gl] {}", msg);
-            msg
+            log::error!(target: "velox::skia", "egl: no display");
+            Error::GlContext("egl: no display".into())
         })?;
         let mut major: egl::EGLint = 0;
         let mut minor: egl::EGLint = 0;
         if !egl::initialize(display, &mut major, &mut minor) {
-            eprintln!("[skia_gl] egl: failed to initialize (major={}, minor={})", major, minor);
-            return Err("egl: failed to initialize".into());
+            log::error!(target: "velox::skia", "egl: failed to initialize (major={}, minor={})", major, minor);
+            return Err(Error::GlContext("egl: failed to initialize".into()));
         }
 
         let config = choose_egl_config(display).ok_or_else(|| {
-            let msg = "egl: no config".to_string();
-            eprintln!("[skia_gl] {}", msg);
-            msg
+            log::error!(target: "velox::skia", "egl: no config");
+            Error::GlContext("egl: no config".into())
         })?;
 
         let ctx_attribs: &[egl::EGLint] = &[egl::EGL_CONTEXT_CLIENT_VERSION as egl::EGLint, 2, egl::EGL_NONE as egl::EGLint];
-        let context = egl::create_context(display, config, egl::EGL_NO_CONTEXT, ctx_attribs).ok_or_else(|| "egl: failed to create context".to_string())?;
+        let context = egl::create_context(display, config, egl::EGL_NO_CONTEXT, ctx_attribs).ok_or_else(|| Error::GlContext("egl: failed to create context".into()))?;
 
         let pbuffer_attribs: &[egl::EGLint] = &[egl::EGL_WIDTH as egl::EGLint, 1, egl::EGL_HEIGHT as egl::EGLint, 1, egl::EGL_NONE as egl::EGLint];
-        let surface = egl::create_pbuffer_surface(display, config, pbuffer_attribs).ok_or_else(|| "egl: failed to create pbuffer surface".to_string())?;
+        let surface = egl::create_pbuffer_surface(display, config, pbuffer_attribs).ok_or_else(|| Error::GlContext("egl: failed to create pbuffer surface".into()))?;
 
         if !egl::make_current(display, surface, surface, context) {
             egl::destroy_surface(display, surface);
             egl::destroy_context(display, context);
-            return Err("egl: make_current failed".into());
+            return Err(Error::GlContext("egl: make_current failed".into()));
         }
 
         let interface = unsafe { skia_safe::gpu::gl::Interface::new_load_with(|name: &str| {
@@ -177,7 +175,7 @@ mod unix_impl {
 
         let iface = match interface {
             Some(i) => i,
-            None => return Err("skia: failed to create GL interface".into()),
+            None => return Err(Error::GlContext("skia: failed to create GL interface".into())),
         };
 
         Ok(SkiaGlContext {
@@ -190,10 +188,10 @@ mod unix_impl {
 
     /// Try to draw a very small test frame. Prefer GPU-backed surface when a
     /// `DirectContext` is available; otherwise fall back to a CPU raster surface.
-    pub fn draw_test_frame() -> Result<(), String> {
+    pub fn draw_test_frame() -> Result<(), Error> {
         // Try to create a DirectContext; if it fails, continue with raster fallback.
         let dctx = match skia_safe::gpu::direct_contexts::make_gl(
-            &create_headless_context()?.interface.clone().ok_or_else(|| "no gl interface".to_string())?,
+            &create_headless_context()?.interface.clone().ok_or_else(|| Error::GlContext("no gl interface".into()))?,
             None,
         ) {
             Some(dc) => Some(dc),
@@ -202,7 +200,7 @@ mod unix_impl {
 
         // Create a small raster surface and draw a colored rect into it.
         let mut surface = skia_safe::surfaces::raster_n32_premul((64, 64))
-            .ok_or_else(|| "skia: failed to create raster surface".to_string())?;
+            .ok_or(Error::InvalidSize { width: 64, height: 64 })?;
         let canvas = surface.canvas();
         canvas.clear(skia_safe::Color::WHITE);
         let mut paint = skia_safe::Paint::default();
@@ -223,11 +221,11 @@ mod unix_impl {
     }
 
     /// Create a GPU-backed FBO + Skia GPU surface, draw a test rect, and present.
-    pub fn draw_gpu_test_frame(width: i32, height: i32) -> Result<(), String> {
+    pub fn draw_gpu_test_frame(width: i32, height: i32) -> Result<(), Error> {
         // Create headless context and DirectContext
         let gl_ctx = create_headless_context()?;
         let _ = gl_ctx.make_current();
-        let mut dctx = gl_ctx.into_direct_context().ok_or_else(|| "skia: could not create DirectContext".to_string())?;
+        let mut dctx = gl_ctx.into_direct_context().ok_or_else(|| Error::GlContext("skia: could not create DirectContext".into()))?;
 
         // Attempt to build a GPU-backed Surface using the current framebuffer.
         let mut surface = {
@@ -257,11 +255,11 @@ mod unix_impl {
         };
 
         if surface.is_none() {
-            eprintln!("[skia_gl] GPU surface creation failed; falling back to raster");
+            log::warn!(target: "velox::skia", "GPU surface creation failed; falling back to raster");
             surface = skia_safe::surfaces::raster_n32_premul((width, height));
         }
 
-        let mut surface = surface.ok_or_else(|| "skia: failed to create surface".to_string())?;
+        let mut surface = surface.ok_or_else(|| Error::Surface("skia: GPU and raster surfaces both failed".into()))?;
         let canvas = surface.canvas();
         canvas.clear(skia_safe::Color::WHITE);
         let mut paint = skia_safe::Paint::default();
@@ -284,21 +282,21 @@ pub use unix_impl::*;
 
 #[cfg(all(feature = "skia-native", unix))]
 /// Convenience: create a `skia_safe::gpu::DirectContext` from a headless EGL context.
-pub fn create_direct_context() -> Result<skia_safe::gpu::DirectContext, String> {
+pub fn create_direct_context() -> Result<skia_safe::gpu::DirectContext, crate::Error> {
     let ctx = unix_impl::create_headless_context()?;
-    ctx.into_direct_context().ok_or_else(|| "skia: could not create DirectContext".to_string())
+    ctx.into_direct_context().ok_or_else(|| crate::Error::GlContext("skia: could not create DirectContext".into()))
 }
 
 #[cfg(not(all(feature = "skia-native", unix)))]
 pub struct SkiaGlContext { _private: () }
 
 #[cfg(not(all(feature = "skia-native", unix)))]
-pub fn create_context() -> Result<SkiaGlContext, String> {
-    Err("skia_gl: platform not supported in this build".into())
+pub fn create_context() -> Result<SkiaGlContext, crate::Error> {
+    Err(crate::Error::Unsupported("skia_gl on this platform"))
 }
 
 #[cfg(all(feature = "skia-native", unix))]
-pub fn create_context() -> Result<SkiaGlContext, String> {
+pub fn create_context() -> Result<SkiaGlContext, crate::Error> {
     // Create a headless pbuffer-backed context for CI and headless environments.
     unix_impl::create_headless_context()
 }
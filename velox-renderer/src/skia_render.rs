@@ -1,8 +1,5 @@
-//! Lightweight Skia raster renderer helpers (Phase 1 minimal implementation).
-//!
-//! Provides a small helper to render a `VNode` into a raster PNG byte buffer.
-//! This file is intentionally minimal for Phase 1: it draws element background
-//! rectangles (via inline `style` attr parsing) and placeholders for text.
+//! Skia renderer: executes the shared display list (see `display_list`) on a Skia canvas,
+//! either a GL-backed window surface or a CPU raster surface for PNG output.
 //!
 #![allow(unused)]
 
@@ -14,12 +11,10 @@ pub mod skia_impl {
     use super::*;
     use skia_safe as sk;
     use std::collections::HashMap;
-
-    #[derive(Clone, Copy)]
-    struct BorderSpec {
-        width: f32,
-        color: sk::Color,
-    }
+    use crate::Error;
+    use crate::display_list::{
+        Color, DisplayList, Filter, RectF, RenderCommand, TextMeasure,
+    };
 
     #[derive(Hash, Eq, PartialEq, Clone)]
     struct FontKey {
@@ -27,313 +22,16 @@ pub mod skia_impl {
         size_key: u32,
     }
 
-    #[derive(Clone, Copy)]
-    enum TextAlign {
-        Left,
-        Center,
-        Right,
-    }
-
-    #[derive(Clone, Copy)]
-    struct TextStyle {
-        color: sk::Color,
-        align: TextAlign,
-        underline: bool,
-        font_size: f32,
-    }
-
-    #[derive(Clone, Copy)]
-    struct ClipInsets {
-        top: f32,
-        right: f32,
-        bottom: f32,
-        left: f32,
-    }
-
-    #[derive(Clone, Copy, Default)]
-    struct FilterSpec {
-        blur_sigma: Option<f32>,
-        brightness: Option<f32>,
-    }
-
-    fn parse_color_hex(value: &str) -> Option<sk::Color> {
-        let hex = value.strip_prefix('#')?;
-        if hex.len() == 6 {
-            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-            return Some(sk::Color::from_argb(255, r, g, b));
-        }
-        if hex.len() == 8 {
-            let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-            let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-            let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-            let a = u8::from_str_radix(&hex[6..8], 16).ok()?;
-            return Some(sk::Color::from_argb(a, r, g, b));
-        }
-        None
-    }
-
-    fn parse_border_value(value: &str) -> Option<BorderSpec> {
-        let mut width: Option<f32> = None;
-        let mut color: Option<sk::Color> = None;
-        let mut is_solid = false;
-
-        for part in value.split_whitespace() {
-            if let Some(px) = part.strip_suffix("px") {
-                if let Ok(v) = px.parse::<f32>() {
-                    width = Some(v);
-                }
-            } else if part.eq_ignore_ascii_case("solid") {
-                is_solid = true;
-            } else if let Some(col) = parse_color_hex(part) {
-                color = Some(col);
-            }
-        }
-
-        if !is_solid {
-            return None;
-        }
-
-        Some(BorderSpec {
-            width: width.unwrap_or(1.0),
-            color: color.unwrap_or_else(|| sk::Color::from_argb(255, 0, 0, 0)),
-        })
-    }
-
-    fn parse_px_value(value: &str) -> Option<f32> {
-        value.strip_suffix("px").and_then(|px| px.trim().parse::<f32>().ok())
-    }
-
-    fn parse_float_value(value: &str) -> Option<f32> {
-        value.trim().parse::<f32>().ok()
-    }
-
-    fn parse_font_family(value: &str) -> Option<String> {
-        let first = value.split(',').next()?.trim();
-        let trimmed = first.trim_matches('"').trim_matches('\'').trim();
-        if trimmed.is_empty() {
-            None
-        } else {
-            Some(trimmed.to_string())
-        }
-    }
-
-    fn parse_clip_inset(value: &str) -> Option<ClipInsets> {
-        let value = value.trim();
-        if !value.starts_with("inset(") || !value.ends_with(')') {
-            return None;
-        }
-        let inner = value.trim_start_matches("inset(").trim_end_matches(')');
-        let inner = inner.split("round").next().unwrap_or(inner).trim();
-        let mut parts: Vec<f32> = Vec::new();
-        for part in inner.split_whitespace() {
-            if let Some(px) = parse_px_value(part) {
-                parts.push(px);
-            } else {
-                return None;
-            }
-        }
-        let (top, right, bottom, left) = match parts.len() {
-            1 => (parts[0], parts[0], parts[0], parts[0]),
-            2 => (parts[0], parts[1], parts[0], parts[1]),
-            3 => (parts[0], parts[1], parts[2], parts[1]),
-            4 => (parts[0], parts[1], parts[2], parts[3]),
-            _ => return None,
-        };
-        Some(ClipInsets { top, right, bottom, left })
-    }
-
-    fn parse_style_attr(
-        style: &str,
-    ) -> (
-        Option<sk::Color>,
-        Option<BorderSpec>,
-        Option<f32>,
-        bool,
-        Option<ClipInsets>,
-        f32,
-        FilterSpec,
-        i32,
-    ) {
-        let mut bg = None;
-        let mut border = None;
-        let mut radius = None;
-        let mut overflow_hidden = false;
-        let mut clip_inset = None;
-        let mut opacity = 1.0f32;
-        let mut filters = FilterSpec::default();
-        let mut z_index = 0i32;
-
-        for decl in style.split(';') {
-            let d = decl.trim();
-            if d.is_empty() {
-                continue;
-            }
-            if let Some((k, v)) = d.split_once(':') {
-                let key = k.trim();
-                let val = v.trim();
-                if key == "background-color" || key == "background" {
-                    bg = parse_color_hex(val);
-                } else if key == "border" {
-                    border = parse_border_value(val);
-                } else if key == "border-radius" {
-                    if let Some(px) = parse_px_value(val) {
-                        radius = Some(px);
-                    }
-                } else if key == "overflow" {
-                    overflow_hidden = val.eq_ignore_ascii_case("hidden");
-                } else if key == "clip-path" {
-                    clip_inset = parse_clip_inset(val);
-                } else if key == "opacity" {
-                    if let Some(alpha) = parse_float_value(val) {
-                        opacity = alpha.clamp(0.0, 1.0);
-                    }
-                } else if key == "filter" {
-                    for part in val.split(')') {
-                        let part = part.trim();
-                        if part.is_empty() {
-                            continue;
-                        }
-                        if let Some(value) = part.strip_prefix("blur(") {
-                            if let Some(px) = parse_px_value(value.trim()) {
-                                filters.blur_sigma = Some(px.max(0.0));
-                            }
-                        } else if let Some(value) = part.strip_prefix("brightness(") {
-                            if let Some(f) = parse_float_value(value.trim()) {
-                                filters.brightness = Some(f.max(0.0));
-                            }
-                        }
-                    }
-                } else if key == "z-index" {
-                    if let Ok(z) = val.parse::<i32>() {
-                        z_index = z;
-                    }
-                }
-            }
-        }
-
-        (
-            bg,
-            border,
-            radius,
-            overflow_hidden,
-            clip_inset,
-            opacity,
-            filters,
-            z_index,
-        )
-    }
-
-    fn z_index_for_props(props: &velox_dom::Props) -> i32 {
-        if let Some(style) = props.attrs.get("style") {
-            for decl in style.split(';') {
-                let d = decl.trim();
-                if d.is_empty() {
-                    continue;
-                }
-                if let Some((k, v)) = d.split_once(':') {
-                    if k.trim() == "z-index" {
-                        if let Ok(z) = v.trim().parse::<i32>() {
-                            return z;
-                        }
-                    }
-                }
-            }
-        }
-        0
-    }
-
-    fn parse_text_style(style: &str, base: TextStyle, family: &str) -> (TextStyle, String) {
-        let mut text_style = base;
-        let mut font_family = family.to_string();
-        for decl in style.split(';') {
-            let d = decl.trim();
-            if d.is_empty() {
-                continue;
-            }
-            if let Some((k, v)) = d.split_once(':') {
-                let key = k.trim();
-                let val = v.trim();
-                if key == "color" {
-                    if let Some(color) = parse_color_hex(val) {
-                        text_style.color = color;
-                    }
-                } else if key == "text-align" {
-                    text_style.align = match val.to_ascii_lowercase().as_str() {
-                        "center" => TextAlign::Center,
-                        "right" => TextAlign::Right,
-                        _ => TextAlign::Left,
-                    };
-                } else if key == "text-decoration" {
-                    let val_l = val.to_ascii_lowercase();
-                    if val_l.contains("underline") {
-                        text_style.underline = true;
-                    } else if val_l == "none" {
-                        text_style.underline = false;
-                    }
-                } else if key == "font-size" {
-                    if let Some(px) = parse_px_value(val).or_else(|| parse_float_value(val)) {
-                        text_style.font_size = px.max(1.0);
-                    }
-                } else if key == "font-family" {
-                    if let Some(family) = parse_font_family(val) {
-                        font_family = family;
-                    }
-                }
-            }
-        }
-        (text_style, font_family)
-    }
-
-    fn inset_rect(rect: sk::Rect, inset: ClipInsets) -> sk::Rect {
-        let left = rect.left + inset.left;
-        let top = rect.top + inset.top;
-        let width = (rect.width() - inset.left - inset.right).max(0.0);
-        let height = (rect.height() - inset.top - inset.bottom).max(0.0);
-        sk::Rect::from_xywh(left, top, width, height)
-    }
-
-    fn apply_clips(
-        canvas: &sk::Canvas,
-        rect: sk::Rect,
-        rrect: Option<sk::RRect>,
-        overflow_hidden: bool,
-        clip_inset: Option<ClipInsets>,
-    ) -> bool {
-        let needs_clip = rrect.is_some() || overflow_hidden || clip_inset.is_some();
-        if !needs_clip {
-            return false;
-        }
-        canvas.save();
-        if let Some(rrect) = rrect {
-            canvas.clip_rrect(rrect, sk::ClipOp::Intersect, true);
-        } else if overflow_hidden {
-            canvas.clip_rect(rect, sk::ClipOp::Intersect, true);
-        }
-        if let Some(inset) = clip_inset {
-            let clip_rect = inset_rect(rect, inset);
-            canvas.clip_rect(clip_rect, sk::ClipOp::Intersect, true);
-        }
-        true
+    fn sk_color(c: Color) -> sk::Color {
+        sk::Color::from_argb(c.a, c.r, c.g, c.b)
     }
 
-    fn text_x_for_align(container: sk::Rect, text_w: f32, align: TextAlign) -> f32 {
-        let padding = 2.0;
-        match align {
-            TextAlign::Left => container.left + padding,
-            TextAlign::Center => container.left + (container.width() - text_w) * 0.5,
-            TextAlign::Right => (container.right - text_w - padding).max(container.left + padding),
-        }
+    fn sk_rect(r: RectF) -> sk::Rect {
+        sk::Rect::from_xywh(r.x, r.y, r.w, r.h)
     }
 
-    fn color_with_opacity(color: sk::Color, opacity: f32) -> sk::Color {
-        let a = ((color.a() as f32) * opacity).round().clamp(0.0, 255.0) as u8;
-        sk::Color::from_argb(a, color.r(), color.g(), color.b())
-    }
-
-    fn apply_filters_to_paint(paint: &mut sk::Paint, filters: FilterSpec) {
-        if let Some(sigma) = filters.blur_sigma {
+    fn apply_filters_to_paint(paint: &mut sk::Paint, filters: Filter) {
+        if let Some(sigma) = filters.blur {
             if sigma > 0.0 {
                 paint.set_image_filter(sk::image_filters::blur((sigma, sigma), None, None, None));
             }
@@ -350,52 +48,13 @@ pub mod skia_impl {
         }
     }
 
-    fn layout_text_lines(
-        text: &str,
-        max_width: f32,
-        fonts: &mut FontCache,
-        family: &str,
-        size: f32,
-    ) -> Vec<(String, f32)> {
-        let limit = if max_width <= 0.0 { f32::INFINITY } else { max_width };
-        let mut lines = Vec::new();
-        for para in text.split('\n') {
-            if para.trim().is_empty() {
-                lines.push((String::new(), 0.0));
-                continue;
-            }
-            let mut current = String::new();
-            let mut current_w = 0.0;
-            for word in para.split_whitespace() {
-                let candidate = if current.is_empty() {
-                    word.to_string()
-                } else {
-                    format!("{} {}", current, word)
-                };
-                let candidate_w = fonts.measure_text(family, size, &candidate);
-                if candidate_w <= limit || current.is_empty() {
-                    current = candidate;
-                    current_w = candidate_w;
-                } else {
-                    lines.push((current, current_w));
-                    current = word.to_string();
-                    current_w = fonts.measure_text(family, size, &current);
-                }
-            }
-            if !current.is_empty() {
-                lines.push((current, current_w));
-            }
-        }
-        lines
-    }
-
     fn collect_debug_hit_rects(
         vnode: &VNode,
         layout: &velox_dom::layout::LayoutNode,
         out: &mut Vec<velox_dom::layout::Rect>,
     ) {
         match vnode {
-            VNode::Text(_) => {}
+            VNode::Text(_) | VNode::Component { .. } => {}
             VNode::Element { tag, props, children, .. } => {
                 if crate::events::is_hoverable(tag, props) {
                     out.push(layout.rect);
@@ -404,6 +63,11 @@ pub mod skia_impl {
                     collect_debug_hit_rects(child, child_layout, out);
                 }
             }
+            VNode::Fragment(children) => {
+                for (child, child_layout) in children.iter().zip(&layout.children) {
+                    collect_debug_hit_rects(child, child_layout, out);
+                }
+            }
         }
     }
 
@@ -411,7 +75,6 @@ pub mod skia_impl {
         fill: sk::Paint,
         stroke: sk::Paint,
         text: sk::Paint,
-        underline: sk::Paint,
         image: sk::Paint,
     }
 
@@ -424,16 +87,12 @@ pub mod skia_impl {
             stroke.set_style(skia_safe::paint::Style::Stroke);
             let mut text = sk::Paint::default();
             text.set_anti_alias(true);
-            let mut underline = sk::Paint::default();
-            underline.set_anti_alias(true);
-            underline.set_stroke_width(1.0);
             let mut image = sk::Paint::default();
             image.set_anti_alias(true);
             RenderPaints {
                 fill,
                 stroke,
                 text,
-                underline,
                 image,
             }
         }
@@ -448,243 +107,49 @@ pub mod skia_impl {
             ImageCache { images: HashMap::new() }
         }
 
-        fn load(&mut self, src: &str) -> Option<sk::Image> {
+        fn load(&mut self, src: &str) -> Result<sk::Image, Error> {
             if let Some(img) = self.images.get(src) {
-                return Some(img.clone());
+                return Ok(img.clone());
             }
-            let bytes = std::fs::read(src).ok()?;
+            let bytes = std::fs::read(src)?;
             let data = sk::Data::new_copy(&bytes);
-            let image = sk::Image::from_encoded(data)?;
+            let image = sk::Image::from_encoded(data).ok_or_else(|| Error::ImageDecode(src.to_string()))?;
             self.images.insert(src.to_string(), image.clone());
-            Some(image)
+            Ok(image)
         }
     }
 
-    /// Render `vnode` into a PNG-encoded raster image.
-    ///
-    /// This is a minimal proof-of-concept renderer used in Phase 1. It:
-    /// - Creates a CPU raster `Surface`
-    /// - Draws element backgrounds parsed from a `style` attr (`background-color:#RRGGBB`)
-    /// - Draws simple placeholders for text nodes
-    /// - Returns PNG bytes
+    /// Render `vnode` into a PNG-encoded raster image at 1x.
     pub fn render_vnode_to_raster_png(
         vnode: &VNode,
-        _sheet: &Stylesheet,
+        sheet: &Stylesheet,
         width: i32,
         height: i32,
-    ) -> Result<Vec<u8>, String> {
-        let mut surface = sk::surfaces::raster_n32_premul((width, height))
-            .ok_or_else(|| "skia: failed to create raster surface".to_string())?;
-        let canvas = surface.canvas();
-        canvas.clear(sk::Color::WHITE);
-
-        let default_text_style = TextStyle {
-            color: sk::Color::from_argb(255, 0, 0, 0),
-            align: TextAlign::Left,
-            underline: false,
-            font_size: 14.0,
-        };
-
-        let mut fonts = FontCache::new();
-        let mut images = ImageCache::new();
-        let default_family = fonts.default_family();
-        let default_text_style = TextStyle {
-            color: sk::Color::from_argb(255, 0, 0, 0),
-            align: TextAlign::Left,
-            underline: false,
-            font_size: 14.0,
-        };
-        let mut paints = RenderPaints::new();
-
-        fn draw_node(
-            canvas: &sk::Canvas,
-            node: &VNode,
-            rect: sk::Rect,
-            container_rect: sk::Rect,
-            text_style: TextStyle,
-            font_family: &str,
-            fonts: &mut FontCache,
-            paints: &mut RenderPaints,
-            images: &mut ImageCache,
-            inherited_opacity: f32,
-        ) {
-            match node {
-                VNode::Element { props, children, .. } => {
-                    let mut clip_rrect = None;
-                    let mut overflow_hidden = false;
-                    let mut clip_inset = None;
-                    let mut child_text_style = text_style;
-                    let mut child_family = font_family.to_string();
-                    let mut opacity = inherited_opacity;
-                    let mut filters = FilterSpec::default();
-                    if let Some(s) = props.attrs.get("style") {
-                        let (bg, border, radius, overflow, inset, alpha, filter_spec, _z) =
-                            parse_style_attr(s);
-                        let rect = rect;
-                        let rrect = radius.map(|r| sk::RRect::new_rect_xy(rect, r, r));
-                        if let Some(rrect) = rrect {
-                            clip_rrect = Some(rrect);
-                        }
-                        overflow_hidden = overflow;
-                        clip_inset = inset;
-                        let (style, family) = parse_text_style(s, text_style, font_family);
-                        child_text_style = style;
-                        child_family = family;
-                        opacity = (opacity * alpha).clamp(0.0, 1.0);
-                        filters = filter_spec;
-                        if let Some(bg) = bg {
-                            paints.fill.set_color(color_with_opacity(bg, opacity));
-                            if let Some(rrect) = rrect {
-                                canvas.draw_rrect(rrect, &paints.fill);
-                            } else {
-                                canvas.draw_rect(rect, &paints.fill);
-                            }
-                        }
-
-                        if let Some(border) = border {
-                            paints.stroke.set_stroke_width(border.width);
-                            paints.stroke.set_color(color_with_opacity(border.color, opacity));
-                            if let Some(rrect) = rrect {
-                                canvas.draw_rrect(rrect, &paints.stroke);
-                            } else {
-                                canvas.draw_rect(rect, &paints.stroke);
-                            }
-                        }
-                    }
-
-                    if let Some(src) = props.attrs.get("src") {
-                        paints.image.set_image_filter(None);
-                        paints.image.set_color_filter(None);
-                        paints.image.set_alpha_f(opacity);
-                        apply_filters_to_paint(&mut paints.image, filters);
-                        if let Some(img) = images.load(src) {
-                            canvas.draw_image_rect(
-                                img,
-                                None,
-                                rect,
-                                &paints.image,
-                            );
-                        }
-                    }
+    ) -> Result<Vec<u8>, Error> {
+        render_vnode_to_raster_png_with_scale(vnode, sheet, width, height, 1.0)
+    }
 
-                    // Naive child layout: stack children vertically
-                    let child_count = children.len().max(1);
-                    let child_h = rect.height() / (child_count as f32);
-                    let rect = rect;
-                    let did_clip = apply_clips(canvas, rect, clip_rrect, overflow_hidden, clip_inset);
-                    let mut ordered: Vec<(i32, usize, &VNode)> = children
-                        .iter()
-                        .enumerate()
-                        .map(|(i, ch)| {
-                            let z = match ch {
-                                VNode::Element { props, .. } => z_index_for_props(props),
-                                _ => 0,
-                            };
-                            (z, i, ch)
-                        })
-                        .collect();
-                    ordered.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
-                    for (_, original_idx, ch) in ordered.iter() {
-                        let child_rect = sk::Rect::from_xywh(
-                            rect.left,
-                            rect.top + *original_idx as f32 * child_h,
-                            rect.width(),
-                            child_h,
-                        );
-                        draw_node(
-                            canvas,
-                            ch,
-                            child_rect,
-                            rect,
-                            child_text_style,
-                            &child_family,
-                            fonts,
-                            paints,
-                            images,
-                            opacity,
-                        );
-                    }
-                    if did_clip {
-                        canvas.restore();
-                    }
-                }
-                VNode::Text(t) => {
-                    paints
-                        .text
-                        .set_color(color_with_opacity(text_style.color, inherited_opacity));
-                    let font_size = text_style.font_size;
-                    let font = fonts.font(font_family, font_size);
-                    let lines = layout_text_lines(
-                        t.as_str(),
-                        container_rect.width(),
-                        fonts,
-                        font_family,
-                        font_size,
-                    );
-                    let line_height = font_size * 1.2;
-                    let layout_rect = sk::Rect::from_xywh(
-                        rect.left,
-                        rect.top,
-                        rect.width(),
-                        rect.height(),
-                    );
-                    let align_rect = if layout_rect.width() >= container_rect.width() - 0.5 {
-                        container_rect
-                    } else {
-                        layout_rect
-                    };
-                    let text_bottom = rect.top + rect.height().max(line_height);
-                    for (idx, (line, line_w)) in lines.into_iter().enumerate() {
-                        let ty = rect.top + font_size + (idx as f32) * line_height;
-                        if ty > text_bottom {
-                            break;
-                        }
-                        let padding = if align_rect.width() >= container_rect.width() - 0.5 {
-                            2.0
-                        } else {
-                            0.0
-                        };
-                        let tx = match text_style.align {
-                            TextAlign::Left => align_rect.left + padding,
-                            TextAlign::Center => align_rect.left + (align_rect.width() - line_w) * 0.5,
-                            TextAlign::Right => (align_rect.right - line_w - padding).max(align_rect.left + padding),
-                        };
-                        #[allow(unused_must_use)]
-                        {
-                            let _ = canvas.draw_str(line.as_str(), (tx, ty), &font, &paints.text);
-                        }
-                        if text_style.underline {
-                            paints
-                                .underline
-                                .set_color(color_with_opacity(text_style.color, inherited_opacity));
-                            let uy = ty + 1.0;
-                            canvas.draw_line((tx, uy), (tx + line_w, uy), &paints.underline);
-                        }
-                    }
-                }
-            }
+    /// Render `vnode` into straight-alpha RGBA pixels with a scale factor applied (e.g.
+    /// for `golden::assert_golden`).
+    pub fn render_vnode_to_raster_image(
+        vnode: &VNode,
+        sheet: &Stylesheet,
+        width: i32,
+        height: i32,
+        scale_factor: f32,
+    ) -> Result<crate::RgbaImage, Error> {
+        let physical_w = ((width as f32) * scale_factor).round() as i32;
+        let physical_h = ((height as f32) * scale_factor).round() as i32;
+        let mut surface = crate::skia_surface::SkiaSurface::new_raster(physical_w, physical_h)?;
+        surface.set_scale_factor(scale_factor);
+        render_frame(&mut surface, vnode, sheet)?;
+        let (w, h) = (physical_w.max(1) as u32, physical_h.max(1) as u32);
+        let info = sk::ImageInfo::new((w as i32, h as i32), sk::ColorType::RGBA8888, sk::AlphaType::Premul, None);
+        let mut data = vec![0u8; (w as usize) * (h as usize) * 4];
+        if !surface.read_pixels(&info, &mut data, (w * 4) as usize, (0, 0)) {
+            return Err(Error::Encode("skia: read_pixels failed".into()));
         }
-
-        let root_rect = sk::Rect::from_xywh(0.0, 0.0, width as f32, height as f32);
-        draw_node(
-            canvas,
-            vnode,
-            root_rect,
-            root_rect,
-            default_text_style,
-            &default_family,
-            &mut fonts,
-            &mut paints,
-            &mut images,
-            1.0,
-        );
-
-        let image = surface.image_snapshot();
-        #[allow(deprecated)]
-        let data = image
-            .encode_to_data(skia_safe::EncodedImageFormat::PNG)
-            .ok_or_else(|| "skia: failed to encode image".to_string())?;
-        Ok(data.as_bytes().to_vec())
+        crate::RgbaImage::from_premultiplied(w, h, data).ok_or(Error::InvalidSize { width: w, height: h })
     }
 
     /// Render `vnode` into a PNG-encoded raster image with a scale factor applied.
@@ -694,7 +159,7 @@ pub mod skia_impl {
         width: i32,
         height: i32,
         scale_factor: f32,
-    ) -> Result<Vec<u8>, String> {
+    ) -> Result<Vec<u8>, Error> {
         let physical_w = ((width as f32) * scale_factor).round() as i32;
         let physical_h = ((height as f32) * scale_factor).round() as i32;
         let mut surface = crate::skia_surface::SkiaSurface::new_raster(physical_w, physical_h)?;
@@ -702,7 +167,6 @@ pub mod skia_impl {
         render_frame(&mut surface, vnode, sheet)?;
         surface.encode_png()
     }
-
     /// Minimal FontCache for mapping sizes to `skia_safe::Font`.
     pub struct FontCache {
         typefaces: HashMap<String, sk::Typeface>,
@@ -710,6 +174,24 @@ pub mod skia_impl {
         default_family: String,
     }
 
+    /// Map `TextQuality` onto Skia font edging and hinting. Gamma is left to Skia's own
+    /// rasterizer, which applies its gamma-correct blending.
+    fn apply_text_quality(font: &mut sk::Font, quality: crate::config::TextQuality) {
+        use crate::config::{Hinting, TextAntialias};
+        font.set_edging(match quality.antialias {
+            TextAntialias::Grayscale => sk::font::Edging::AntiAlias,
+            TextAntialias::Subpixel => sk::font::Edging::SubpixelAntiAlias,
+            TextAntialias::Aliased => sk::font::Edging::Alias,
+        });
+        font.set_subpixel(quality.antialias == TextAntialias::Subpixel);
+        font.set_hinting(match quality.hinting {
+            Hinting::None => sk::FontHinting::None,
+            Hinting::Slight => sk::FontHinting::Slight,
+            Hinting::Normal => sk::FontHinting::Normal,
+            Hinting::Full => sk::FontHinting::Full,
+        });
+    }
+
     impl FontCache {
         /// Attempt to load a system font or bundled fallback fonts.
         pub fn new() -> Self {
@@ -744,13 +226,14 @@ pub mod skia_impl {
             if let Some(font) = self.fonts.get(&key) {
                 return font.clone();
             }
-            let font = if let Some(tf) = self.get_or_load_family(family) {
+            let mut font = if let Some(tf) = self.get_or_load_family(family) {
                 sk::Font::new(tf, size)
             } else {
                 let mut f = sk::Font::default();
                 f.set_size(size);
                 f
             };
+            apply_text_quality(&mut font, crate::config::text_quality());
             self.fonts.insert(key, font.clone());
             font
         }
@@ -778,7 +261,9 @@ pub mod skia_impl {
         ];
 
         let font_mgr = sk::FontMgr::default();
-        for p in CANDIDATES {
+        // Installed fonts differ between machines; deterministic frames use the bundled one.
+        let deterministic = crate::config::is_deterministic();
+        for p in CANDIDATES.iter().filter(|_| !deterministic) {
             if let Ok(bytes) = fs::read(p) {
                 if let Some(tf) = font_mgr.new_from_data(&bytes, None) {
                     return Some(tf);
@@ -823,234 +308,170 @@ pub mod skia_impl {
         let fallback_mgr = sk::FontMgr::new();
         fallback_mgr.legacy_make_typeface(None, sk::FontStyle::default())
     }
+    impl TextMeasure for FontCache {
+        fn measure(&mut self, family: Option<&str>, size: f32, text: &str) -> f32 {
+            let family = family.map(str::to_string).unwrap_or_else(|| self.default_family());
+            self.measure_text(&family, size, text)
+        }
+    }
+
+    /// Replay a display list onto `canvas`.
+    fn execute(
+        canvas: &sk::Canvas,
+        list: &DisplayList,
+        fonts: &mut FontCache,
+        images: &mut ImageCache,
+        paints: &mut RenderPaints,
+    ) {
+        for cmd in &list.commands {
+            match cmd {
+                RenderCommand::Rect { rect, color } => {
+                    paints.fill.set_color(sk_color(*color));
+                    canvas.draw_rect(sk_rect(*rect), &paints.fill);
+                }
+                RenderCommand::RRect { rect, radius, color } => {
+                    paints.fill.set_color(sk_color(*color));
+                    canvas.draw_rrect(sk::RRect::new_rect_xy(sk_rect(*rect), *radius, *radius), &paints.fill);
+                }
+                RenderCommand::Border { rect, radius, width, color } => {
+                    paints.stroke.set_stroke_width(*width);
+                    paints.stroke.set_color(sk_color(*color));
+                    if *radius > 0.0 {
+                        canvas.draw_rrect(sk::RRect::new_rect_xy(sk_rect(*rect), *radius, *radius), &paints.stroke);
+                    } else {
+                        canvas.draw_rect(sk_rect(*rect), &paints.stroke);
+                    }
+                }
+                RenderCommand::Text { x, baseline, text, size, family, bold, color } => {
+                    let family = family.clone().unwrap_or_else(|| fonts.default_family());
+                    let mut font = fonts.font(&family, *size);
+                    font.set_embolden(*bold);
+                    paints.text.set_color(sk_color(*color));
+                    canvas.draw_str(text.as_str(), (*x, *baseline), &font, &paints.text);
+                }
+                RenderCommand::Image { rect, src, opacity, filter } => {
+                    paints.image.set_image_filter(None);
+                    paints.image.set_color_filter(None);
+                    paints.image.set_alpha_f(*opacity);
+                    apply_filters_to_paint(&mut paints.image, *filter);
+                    match images.load(src) {
+                        Ok(img) => {
+                            canvas.draw_image_rect(img, None, sk_rect(*rect), &paints.image);
+                        }
+                        Err(e) => log::warn!(target: "velox::skia", "image '{}' skipped: {}", src, e),
+                    }
+                }
+                RenderCommand::PushClip { rect, radius } => {
+                    canvas.save();
+                    if *radius > 0.0 {
+                        canvas.clip_rrect(sk::RRect::new_rect_xy(sk_rect(*rect), *radius, *radius), sk::ClipOp::Intersect, true);
+                    } else {
+                        canvas.clip_rect(sk_rect(*rect), sk::ClipOp::Intersect, true);
+                    }
+                }
+                RenderCommand::PushTransform(t) => {
+                    canvas.save();
+                    canvas.translate((t.tx, t.ty));
+                    canvas.scale((t.sx, t.sy));
+                }
+                RenderCommand::PopClip | RenderCommand::PopTransform => {
+                    canvas.restore();
+                }
+            }
+        }
+    }
+
+    fn draw_list(canvas: &sk::Canvas, list: &DisplayList, scale: f32, fonts: &mut FontCache) {
+        let mut images = ImageCache::new();
+        let mut paints = RenderPaints::new();
+        canvas.clear(sk::Color::WHITE);
+        canvas.save();
+        canvas.scale((scale, scale));
+        execute(canvas, list, fonts, &mut images, &mut paints);
+    }
+
+    /// Execute an already built display list (e.g. one received via `remote`) on a raster
+    /// surface and return PNG bytes.
+    pub fn render_display_list_to_png(list: &DisplayList, scale_factor: f32) -> Result<Vec<u8>, Error> {
+        let scale = scale_factor.max(1.0);
+        let physical_w = (list.width * scale).round().max(1.0) as i32;
+        let physical_h = (list.height * scale).round().max(1.0) as i32;
+        let mut surface = crate::skia_surface::SkiaSurface::new_raster(physical_w, physical_h)?;
+        let mut fonts = FontCache::new();
+        draw_list(surface.canvas(), list, scale, &mut fonts);
+        surface.canvas().restore();
+        surface.encode_png()
+    }
+
+    /// Draw `vnode` at `layout` onto PDF pages, one per `(top, bottom)` slice of `pages`
+    /// (see `print::paginate`). Layout coordinates are CSS pixels, scaled to points.
+    #[cfg(feature = "pdf")]
+    pub fn render_pdf(
+        vnode: &VNode,
+        layout: &velox_dom::layout::LayoutNode,
+        pages: &[(f32, f32)],
+        page: crate::print::PageSize,
+    ) -> Result<Vec<u8>, Error> {
+        let mut fonts = FontCache::new();
+        let list = crate::display_list::build_display_list(vnode, layout, &mut fonts);
+        let mut images = ImageCache::new();
+        let mut paints = RenderPaints::new();
+        let (width_px, _) = page.size_px();
+        let scale = 1.0 / crate::print::PX_PER_PT;
+
+        let mut out = Vec::new();
+        let mut doc = sk::pdf::new_document(&mut out, None);
+        for &(top, bottom) in pages {
+            let mut on_page = doc.begin_page((page.width, page.height), None);
+            let canvas = on_page.canvas();
+            canvas.save();
+            canvas.scale((scale, scale));
+            canvas.clip_rect(sk::Rect::from_xywh(0.0, 0.0, width_px, bottom - top), sk::ClipOp::Intersect, true);
+            canvas.translate((0.0, -top));
+            execute(canvas, &list, &mut fonts, &mut images, &mut paints);
+            canvas.restore();
+            doc = on_page.end_page();
+        }
+        doc.close();
+        if out.is_empty() {
+            return Err(Error::Encode("PDF document is empty".into()));
+        }
+        Ok(out)
+    }
 
     /// Render a VNode tree into an existing `SkiaSurface`.
     pub fn render_frame(
         surface: &mut crate::skia_surface::SkiaSurface,
         vnode: &VNode,
         _sheet: &Stylesheet,
-    ) -> Result<(), String> {
+    ) -> Result<(), Error> {
         // Compute layout using the existing velox-dom layout system.
         let scale = surface.scale_factor().max(1.0);
         let width_i = ((surface.width as f32) / scale).round().max(1.0) as i32;
         let height_i = ((surface.height as f32) / scale).round().max(1.0) as i32;
         let layout_root = velox_dom::layout::compute_layout(vnode, width_i, height_i);
+        render_frame_with_layout(surface, vnode, &layout_root)
+    }
 
-        let canvas = surface.canvas();
-        canvas.clear(sk::Color::WHITE);
-        canvas.save();
-        canvas.scale((scale, scale));
+    /// Like `render_frame`, drawing `vnode` at the rects in `layout_root` (e.g. one
+    /// rewritten by `animation::LayoutAnimator`).
+    pub fn render_frame_with_layout(
+        surface: &mut crate::skia_surface::SkiaSurface,
+        vnode: &VNode,
+        layout_root: &velox_dom::layout::LayoutNode,
+    ) -> Result<(), Error> {
+        let scale = surface.scale_factor().max(1.0);
+        let width_i = ((surface.width as f32) / scale).round().max(1.0) as i32;
+        let height_i = ((surface.height as f32) / scale).round().max(1.0) as i32;
 
         let mut fonts = FontCache::new();
-        let mut images = ImageCache::new();
-        let default_text_style = TextStyle {
-            color: sk::Color::from_argb(255, 0, 0, 0),
-            align: TextAlign::Left,
-            underline: false,
-            font_size: 14.0,
-        };
-        let default_family = fonts.default_family();
-        let mut paints = RenderPaints::new();
-
-        fn render_with_layout(
-            canvas: &sk::Canvas,
-            node: &VNode,
-            layout: &velox_dom::layout::LayoutNode,
-            container_rect: sk::Rect,
-            fonts: &mut FontCache,
-            text_style: TextStyle,
-            font_family: &str,
-            paints: &mut RenderPaints,
-            images: &mut ImageCache,
-            inherited_opacity: f32,
-        ) {
-            match node {
-                VNode::Element { props, children, .. } => {
-                    let mut clip_rrect = None;
-                    let mut overflow_hidden = false;
-                    let mut clip_inset = None;
-                    let mut child_text_style = text_style;
-                    let mut child_family = font_family.to_string();
-                    let mut opacity = inherited_opacity;
-                    let mut filters = FilterSpec::default();
-                    if let Some(s) = props.attrs.get("style") {
-                        let (bg, border, radius, overflow, inset, alpha, filter_spec, _z) =
-                            parse_style_attr(s);
-                        let rect = sk::Rect::from_xywh(
-                            layout.rect.x as f32,
-                            layout.rect.y as f32,
-                            layout.rect.w as f32,
-                            layout.rect.h as f32,
-                        );
-                        let rrect = radius.map(|r| sk::RRect::new_rect_xy(rect, r, r));
-                        if let Some(rrect) = rrect {
-                            clip_rrect = Some(rrect);
-                        }
-                        overflow_hidden = overflow;
-                        clip_inset = inset;
-                        let (style, family) = parse_text_style(s, text_style, font_family);
-                        child_text_style = style;
-                        child_family = family;
-                        opacity = (opacity * alpha).clamp(0.0, 1.0);
-                        filters = filter_spec;
-                        if let Some(bg) = bg {
-                            paints.fill.set_color(color_with_opacity(bg, opacity));
-                            if let Some(rrect) = rrect {
-                                canvas.draw_rrect(rrect, &paints.fill);
-                            } else {
-                                canvas.draw_rect(rect, &paints.fill);
-                            }
-                        }
-                        if let Some(border) = border {
-                            paints.stroke.set_stroke_width(border.width);
-                            paints.stroke.set_color(color_with_opacity(border.color, opacity));
-                            if let Some(rrect) = rrect {
-                                canvas.draw_rrect(rrect, &paints.stroke);
-                            } else {
-                                canvas.draw_rect(rect, &paints.stroke);
-                            }
-                        }
-                    }
-
-                    if let Some(src) = props.attrs.get("src") {
-                        paints.image.set_image_filter(None);
-                        paints.image.set_color_filter(None);
-                        paints.image.set_alpha_f(opacity);
-                        apply_filters_to_paint(&mut paints.image, filters);
-                        if let Some(img) = images.load(src) {
-                            let rect = sk::Rect::from_xywh(
-                                layout.rect.x as f32,
-                                layout.rect.y as f32,
-                                layout.rect.w as f32,
-                                layout.rect.h as f32,
-                            );
-                            canvas.draw_image_rect(
-                                img,
-                                None,
-                                rect,
-                                &paints.image,
-                            );
-                        }
-                    }
-
-                    // Render children in order using their layout nodes
-                    let rect = sk::Rect::from_xywh(
-                        layout.rect.x as f32,
-                        layout.rect.y as f32,
-                        layout.rect.w as f32,
-                        layout.rect.h as f32,
-                    );
-                    let did_clip = apply_clips(canvas, rect, clip_rrect, overflow_hidden, clip_inset);
-                    let mut ordered: Vec<(i32, usize)> = children
-                        .iter()
-                        .enumerate()
-                        .map(|(i, ch)| {
-                            let z = match ch {
-                                VNode::Element { props, .. } => z_index_for_props(props),
-                                _ => 0,
-                            };
-                            (z, i)
-                        })
-                        .collect();
-                    ordered.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.cmp(&b.1)));
-                    for (_, idx) in ordered {
-                        if let Some(child) = children.get(idx) {
-                            if let Some(child_layout) = layout.children.get(idx) {
-                                render_with_layout(
-                                    canvas,
-                                    child,
-                                    child_layout,
-                                    rect,
-                                    fonts,
-                                    child_text_style,
-                                    &child_family,
-                                    paints,
-                                    images,
-                                    opacity,
-                                );
-                            }
-                        }
-                    }
-                    if did_clip {
-                        canvas.restore();
-                    }
-                }
-                VNode::Text(t) => {
-                    paints
-                        .text
-                        .set_color(color_with_opacity(text_style.color, inherited_opacity));
-                    let font_size = text_style.font_size;
-                    let font = fonts.font(font_family, font_size);
-                    let lines = layout_text_lines(
-                        t.as_str(),
-                        container_rect.width(),
-                        fonts,
-                        font_family,
-                        font_size,
-                    );
-                    let line_height = font_size * 1.2;
-                    let layout_rect = sk::Rect::from_xywh(
-                        layout.rect.x as f32,
-                        layout.rect.y as f32,
-                        layout.rect.w as f32,
-                        layout.rect.h as f32,
-                    );
-                    let align_rect = if layout_rect.width() >= container_rect.width() - 0.5 {
-                        container_rect
-                    } else {
-                        layout_rect
-                    };
-                    let text_bottom = (layout.rect.y as f32)
-                        + (layout.rect.h as f32).max(line_height);
-                    for (idx, (line, line_w)) in lines.into_iter().enumerate() {
-                        let ty = layout.rect.y as f32 + font_size + (idx as f32) * line_height;
-                        if ty > text_bottom {
-                            break;
-                        }
-                        let padding = if align_rect.width() >= container_rect.width() - 0.5 {
-                            2.0
-                        } else {
-                            0.0
-                        };
-                        let tx = match text_style.align {
-                            TextAlign::Left => align_rect.left + padding,
-                            TextAlign::Center => align_rect.left + (align_rect.width() - line_w) * 0.5,
-                            TextAlign::Right => (align_rect.right - line_w - padding).max(align_rect.left + padding),
-                        };
-                        #[allow(unused_must_use)]
-                        {
-                            let _ = canvas.draw_str(line.as_str(), (tx, ty), &font, &paints.text);
-                        }
-                        if text_style.underline {
-                            paints
-                                .underline
-                                .set_color(color_with_opacity(text_style.color, inherited_opacity));
-                            let uy = ty + 1.0;
-                            canvas.draw_line((tx, uy), (tx + line_w, uy), &paints.underline);
-                        }
-                    }
-                }
-            }
-        }
+        let canvas = surface.canvas();
+        crate::arena::with_frame_arena(|arena| {
+            let list = arena.build_display_list(vnode, layout_root, &mut fonts);
+            (list.width, list.height) = (width_i as f32, height_i as f32);
+            draw_list(canvas, list, scale, &mut fonts);
+        });
 
-        let root_rect = sk::Rect::from_xywh(
-            layout_root.rect.x as f32,
-            layout_root.rect.y as f32,
-            layout_root.rect.w as f32,
-            layout_root.rect.h as f32,
-        );
-        render_with_layout(
-            canvas,
-            vnode,
-            &layout_root,
-            root_rect,
-            &mut fonts,
-            default_text_style,
-            &default_family,
-            &mut paints,
-            &mut images,
-            1.0,
-        );
         let debug_overlay = std::env::var("VELOX_DEBUG_HIT_RECTS")
             .ok()
             .as_deref()
@@ -1063,10 +484,10 @@ pub mod skia_impl {
             .unwrap_or(false);
         if debug_overlay || debug_log {
             let mut rects = Vec::new();
-            collect_debug_hit_rects(vnode, &layout_root, &mut rects);
+            collect_debug_hit_rects(vnode, layout_root, &mut rects);
             if debug_log {
                 for r in &rects {
-                    eprintln!("[skia debug] hit rect: x={} y={} w={} h={}", r.x, r.y, r.w, r.h);
+                    log::info!(target: "velox::skia", "hit rect: x={} y={} w={} h={}", r.x, r.y, r.w, r.h);
                 }
             }
             if debug_overlay {
@@ -1107,23 +528,18 @@ pub mod skia_impl {
                 )],
             );
 
-            let mut surface =
-                crate::skia_surface::SkiaSurface::new_raster(64, 64).expect("surface");
-            render_frame(&mut surface, &vnode, &Stylesheet::default()).expect("render");
-            let path = "target/skia_overflow_clip.png";
-            surface.save_png(path).expect("save png");
-            let png = std::fs::read(path).expect("read png");
-
-            let checksum = fnv1a(&png);
-            println!("overflow-hidden checksum: 0x{checksum:08x}");
-            // Update this checksum after regenerating the raster output.
-            const EXPECTED_OVERFLOW_CHECKSUM: u32 = 0xf74653e7;
-            assert_eq!(checksum, EXPECTED_OVERFLOW_CHECKSUM);
+            crate::config::set_deterministic(true);
+            let image = render_vnode_to_raster_image(&vnode, &Stylesheet::default(), 64, 64, 1.0)
+                .expect("render");
+            crate::golden::assert_golden_or_record(
+                concat!(env!("CARGO_MANIFEST_DIR"), "/tests/goldens/skia_overflow_clip.png"),
+                &image,
+            );
         }
 
         #[test]
         #[ignore]
-        fn render_z_index_overlap_checksum() {
+        fn render_z_index_overlap_golden() {
             let vnode = h(
                 "div",
                 vec![("style", "background-color:#FFFFFF;width:64px;height:64px")],
@@ -1143,18 +559,13 @@ pub mod skia_impl {
                 ],
             );
 
-            let mut surface =
-                crate::skia_surface::SkiaSurface::new_raster(64, 64).expect("surface");
-            render_frame(&mut surface, &vnode, &Stylesheet::default()).expect("render");
-            let path = "target/skia_z_index.png";
-            surface.save_png(path).expect("save png");
-            let png = std::fs::read(path).expect("read png");
-
-            let checksum = fnv1a(&png);
-            println!("z-index checksum: 0x{checksum:08x}");
-            // Update this checksum after regenerating the raster output.
-            const EXPECTED_Z_INDEX_CHECKSUM: u32 = 0x0c864983;
-            assert_eq!(checksum, EXPECTED_Z_INDEX_CHECKSUM);
+            crate::config::set_deterministic(true);
+            let image = render_vnode_to_raster_image(&vnode, &Stylesheet::default(), 64, 64, 1.0)
+                .expect("render");
+            crate::golden::assert_golden_or_record(
+                concat!(env!("CARGO_MANIFEST_DIR"), "/tests/goldens/skia_z_index.png"),
+                &image,
+            );
         }
 
         #[test]
@@ -1172,15 +583,6 @@ pub mod skia_impl {
             collect_debug_hit_rects(&vnode, &layout, &mut rects);
             assert_eq!(rects.len(), 1);
         }
-
-        fn fnv1a(bytes: &[u8]) -> u32 {
-            let mut hash: u32 = 0x811c9dc5;
-            for b in bytes {
-                hash ^= *b as u32;
-                hash = hash.wrapping_mul(0x01000193);
-            }
-            hash
-        }
     }
 }
 
@@ -1193,8 +595,8 @@ pub mod skia_impl {
         _sheet: &Stylesheet,
         _width: i32,
         _height: i32,
-    ) -> Result<Vec<u8>, String> {
-        Err("skia-native feature not enabled".into())
+    ) -> Result<Vec<u8>, crate::Error> {
+        Err(crate::Error::Unsupported("skia-native"))
     }
 
     pub fn render_vnode_to_raster_png_with_scale(
@@ -1203,10 +605,29 @@ pub mod skia_impl {
         _width: i32,
         _height: i32,
         _scale_factor: f32,
-    ) -> Result<Vec<u8>, String> {
-        Err("skia-native feature not enabled".into())
+    ) -> Result<Vec<u8>, crate::Error> {
+        Err(crate::Error::Unsupported("skia-native"))
+    }
+
+    pub fn render_vnode_to_raster_image(
+        _vnode: &VNode,
+        _sheet: &Stylesheet,
+        _width: i32,
+        _height: i32,
+        _scale_factor: f32,
+    ) -> Result<crate::RgbaImage, crate::Error> {
+        Err(crate::Error::Unsupported("skia-native"))
+    }
+
+    pub fn render_display_list_to_png(
+        _list: &crate::display_list::DisplayList,
+        _scale_factor: f32,
+    ) -> Result<Vec<u8>, crate::Error> {
+        Err(crate::Error::Unsupported("skia-native"))
     }
 }
 
 pub use skia_impl::render_vnode_to_raster_png;
 pub use skia_impl::render_vnode_to_raster_png_with_scale;
+pub use skia_impl::render_vnode_to_raster_image;
+pub use skia_impl::render_display_list_to_png;
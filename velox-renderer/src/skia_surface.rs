@@ -1,7 +1,7 @@
 //! Minimal Skia surface wrapper (Phase 1).
 //!
-//! Provides a small `SkiaSurface` helper for raster surfaces and a
-//! placeholder for future window-backed surface creation.
+//! Provides a small `SkiaSurface` helper for raster surfaces, window-backed
+//! surfaces, and surfaces over a framebuffer of a GL context the app owns.
 #![allow(unused)]
 
 #[cfg(feature = "skia-native")]
@@ -10,6 +10,14 @@ mod native {
     use std::path::Path;
     use glow::HasContext;
 
+    use crate::Error;
+
+    /// Raster surfaces only fail for empty or oversized dimensions.
+    fn raster_surface(width: i32, height: i32) -> Result<sk::Surface, Error> {
+        sk::surfaces::raster_n32_premul((width, height))
+            .ok_or(Error::InvalidSize { width: width.max(0) as u32, height: height.max(0) as u32 })
+    }
+
     pub struct SkiaSurface {
         surface: sk::Surface,
         pub width: i32,
@@ -20,13 +28,33 @@ mod native {
         // Keep the native GL/EGL context alive while the Skia surface exists.
         #[cfg(all(feature = "skia-native", unix))]
         pub _gl_ctx: Option<crate::skia_gl::SkiaGlContext>,
+        // The host's framebuffer, when drawing into a GL context velox did not create.
+        external: Option<ExternalFramebuffer>,
+    }
+
+    /// A framebuffer in a GL context the app created itself (its own EGL, GLX or WGL
+    /// setup), for `SkiaSurface::from_external_gl`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct ExternalFramebuffer {
+        /// GL name of the framebuffer object; 0 is the context's default framebuffer.
+        pub fbo: u32,
+        pub width: i32,
+        pub height: i32,
+        pub samples: usize,
+        pub stencil_bits: usize,
+    }
+
+    impl ExternalFramebuffer {
+        /// A single-sampled framebuffer with an 8-bit stencil.
+        pub fn new(fbo: u32, width: i32, height: i32) -> Self {
+            ExternalFramebuffer { fbo, width, height, samples: 0, stencil_bits: 8 }
+        }
     }
 
     impl SkiaSurface {
         /// Create a CPU raster SkiaSurface.
-        pub fn new_raster(width: i32, height: i32) -> Result<Self, String> {
-            let surface = sk::surfaces::raster_n32_premul((width, height))
-                .ok_or_else(|| "skia: failed to create raster surface".to_string())?;
+        pub fn new_raster(width: i32, height: i32) -> Result<Self, Error> {
+            let surface = raster_surface(width, height)?;
             Ok(SkiaSurface {
                 surface,
                 width,
@@ -35,9 +63,65 @@ mod native {
                 _gpu_ctx: None,
                 #[cfg(all(feature = "skia-native", unix))]
                 _gl_ctx: None,
+                external: None,
             })
         }
 
+        /// Draw into `framebuffer` of the GL context current on this thread, which the
+        /// app owns. `load` resolves GL function names in that context (`eglGetProcAddress`,
+        /// `glXGetProcAddress`, ...). Velox never makes the context current, swaps it or
+        /// destroys it: the app makes it current before each frame and presents it after.
+        pub fn from_external_gl<F>(load: F, framebuffer: ExternalFramebuffer) -> Result<Self, Error>
+        where
+            F: FnMut(&str) -> *const std::ffi::c_void,
+        {
+            let interface = sk::gpu::gl::Interface::new_load_with(load)
+                .ok_or_else(|| Error::GlContext("skia: could not load GL functions from the host context".into()))?;
+            let mut dctx = sk::gpu::direct_contexts::make_gl(interface, None)
+                .ok_or_else(|| Error::GlContext("skia: could not create DirectContext".into()))?;
+            let surface = wrap_framebuffer(&mut dctx, framebuffer)
+                .ok_or_else(|| Error::Surface(format!("skia: could not wrap framebuffer {}", framebuffer.fbo)))?;
+            Ok(SkiaSurface {
+                surface,
+                width: framebuffer.width,
+                height: framebuffer.height,
+                scale_factor: 1.0,
+                _gpu_ctx: Some(dctx),
+                #[cfg(all(feature = "skia-native", unix))]
+                _gl_ctx: None,
+                external: Some(framebuffer),
+            })
+        }
+
+        /// Draw into another framebuffer of the same external context, e.g. one the app
+        /// recreated at a new size.
+        pub fn set_external_framebuffer(&mut self, framebuffer: ExternalFramebuffer) -> Result<(), Error> {
+            let Some(dctx) = &mut self._gpu_ctx else {
+                return Err(Error::Unsupported("external framebuffers on a raster surface"));
+            };
+            self.surface = wrap_framebuffer(dctx, framebuffer)
+                .ok_or_else(|| Error::Surface(format!("skia: could not wrap framebuffer {}", framebuffer.fbo)))?;
+            self.width = framebuffer.width;
+            self.height = framebuffer.height;
+            self.external = Some(framebuffer);
+            Ok(())
+        }
+
+        /// Whether this surface draws into a framebuffer the app owns.
+        pub fn is_external(&self) -> bool {
+            self.external.is_some()
+        }
+
+        /// Forget the GL state Skia last set, which the app may have changed since; Skia
+        /// sets what it needs again on the next draw. Only external contexts need this.
+        pub fn reset_gl_state(&mut self) {
+            if self.external.is_some()
+                && let Some(dctx) = &mut self._gpu_ctx
+            {
+                dctx.reset(None);
+            }
+        }
+
         /// Return a reference to the canvas.
             pub fn canvas(&mut self) -> &sk::Canvas {
             // Prefer the mutable canvas accessor when available.
@@ -57,22 +141,18 @@ mod native {
         }
 
         /// Save the current surface snapshot to a PNG file (for debugging/tests).
-        pub fn save_png<P: AsRef<Path>>(&mut self, path: P) -> Result<(), String> {
-            let img = self.surface.image_snapshot();
-            #[allow(deprecated)]
-            let data = img
-                .encode_to_data(skia_safe::EncodedImageFormat::PNG)
-                .ok_or_else(|| "skia: failed to encode image".to_string())?;
-            std::fs::write(path, data.as_bytes()).map_err(|e| format!("write failed: {}", e))
+        pub fn save_png<P: AsRef<Path>>(&mut self, path: P) -> Result<(), Error> {
+            std::fs::write(path, self.encode_png()?)?;
+            Ok(())
         }
 
         /// Encode the current surface snapshot as PNG bytes.
-        pub fn encode_png(&mut self) -> Result<Vec<u8>, String> {
+        pub fn encode_png(&mut self) -> Result<Vec<u8>, Error> {
             let img = self.surface.image_snapshot();
             #[allow(deprecated)]
             let data = img
                 .encode_to_data(skia_safe::EncodedImageFormat::PNG)
-                .ok_or_else(|| "skia: failed to encode image".to_string())?;
+                .ok_or_else(|| Error::Encode("skia: png".into()))?;
             Ok(data.as_bytes().to_vec())
         }
 
@@ -80,7 +160,7 @@ mod native {
         ///
         /// For GPU-backed surfaces this will flush and submit the `DirectContext`.
         /// For raster surfaces this is a no-op.
-        pub fn present(&mut self) -> Result<(), String> {
+        pub fn present(&mut self) -> Result<(), Error> {
             #[cfg(all(feature = "skia-native", unix))]
             if let Some(gl_ctx) = &self._gl_ctx {
                 let _ = gl_ctx.make_current();
@@ -116,7 +196,11 @@ mod native {
 
         /// Resize the surface. Recreate a GPU-backed surface when a `DirectContext`
         /// is available; otherwise recreate a CPU raster surface.
-        pub fn resize(&mut self, width: i32, height: i32) -> Result<(), String> {
+        pub fn resize(&mut self, width: i32, height: i32) -> Result<(), Error> {
+            // The app's framebuffer keeps its id; wrap it again at the new size.
+            if let Some(framebuffer) = self.external {
+                return self.set_external_framebuffer(ExternalFramebuffer { width, height, ..framebuffer });
+            }
             self.width = width;
             self.height = height;
 
@@ -134,9 +218,7 @@ mod native {
             }
 
             // Raster fallback
-            let surface = sk::surfaces::raster_n32_premul((width, height))
-                .ok_or_else(|| "skia: failed to create raster surface on resize".to_string())?;
-            self.surface = surface;
+            self.surface = raster_surface(width, height)?;
             Ok(())
         }
     }
@@ -151,13 +233,13 @@ mod native {
             window: &impl raw_window_handle::HasRawWindowHandle,
             width: i32,
             height: i32,
-        ) -> Result<SkiaSurface, String> {
+        ) -> Result<SkiaSurface, Error> {
             // Try to create a native GL/EGL context using the helper in `skia_gl`.
             match crate::skia_gl::create_context_from_winit(window) {
                 Ok(gl_ctx) => {
                     // Try to make a DirectContext from the GL interface.
                     if let Some(mut dctx) = gl_ctx.into_direct_context() {
-                        eprintln!("[skia_surface] DirectContext created (GPU path available)");
+                        log::info!(target: "velox::skia", "DirectContext created (GPU path available)");
                         // Attempt to build a GPU-backed Skia surface from the DirectContext.
                         // If successful, return a SkiaSurface that owns both the DirectContext
                         // and the native GL context so they are kept alive for the lifetime
@@ -171,11 +253,11 @@ mod native {
                                 _gpu_ctx: Some(dctx),
                                 #[cfg(all(feature = "skia-native", unix))]
                                 _gl_ctx: Some(gl_ctx),
+                                external: None,
                             });
                         }
                         // Fallback to raster until the platform-specific path is implemented.
-                        let surface = sk::surfaces::raster_n32_premul((width, height))
-                            .ok_or_else(|| "skia: failed to create raster fallback surface".to_string())?;
+                        let surface = raster_surface(width, height)?;
                         return Ok(SkiaSurface {
                             surface,
                             width,
@@ -184,13 +266,14 @@ mod native {
                             _gpu_ctx: Some(dctx),
                             #[cfg(all(feature = "skia-native", unix))]
                             _gl_ctx: Some(gl_ctx),
+                            external: None,
                         });
                     } else {
-                        eprintln!("[skia_surface] Could not make DirectContext; falling back to raster");
+                        log::warn!(target: "velox::skia", "could not make DirectContext; falling back to raster");
                     }
                 }
                 Err(e) => {
-                    eprintln!("[skia_surface] create_context_from_winit failed: {}", e);
+                    log::warn!(target: "velox::skia", "create_context_from_winit failed: {}", e);
                 }
             }
 
@@ -222,39 +305,35 @@ mod native {
             // Query the currently bound FBO.
             let fb_binding = gl.get_parameter_i32(glow::FRAMEBUFFER_BINDING) as u32;
 
-            // Choose a color format. We assume a standard RGBA8 buffer here.
-            let gl_format = glow::RGBA8;
-
-            let fb_info = sk::gpu::gl::FramebufferInfo { fboid: fb_binding, format: gl_format, protected: sk::gpu::Protected::No };
-
-            // Create a BackendRenderTarget for GL.
-            let backend = sk::gpu::backend_render_targets::make_gl((width, height), 0, 8, fb_info);
-
-            // Create a Skia GPU-backed Surface from the backend render target.
-            let surface = sk::gpu::surfaces::wrap_backend_render_target(
-                dctx,
-                &backend,
-                sk::gpu::SurfaceOrigin::BottomLeft,
-                sk::ColorType::RGBA8888,
-                None,
-                None,
-            );
+            let surface = wrap_framebuffer(dctx, ExternalFramebuffer::new(fb_binding, width, height));
 
             if surface.is_some() {
-                eprintln!("[skia_surface] created GPU-backed Surface (fbo={})", fb_binding);
+                log::debug!(target: "velox::skia", "created GPU-backed Surface (fbo={})", fb_binding);
             } else {
-                eprintln!("[skia_surface] Surface::from_backend_render_target returned None");
+                log::warn!(target: "velox::skia", "Surface::from_backend_render_target returned None");
             }
 
             surface
         }
     }
+
+    /// Wrap a GL framebuffer (assumed RGBA8) of `dctx`'s context as a Skia surface.
+    fn wrap_framebuffer(dctx: &mut sk::gpu::DirectContext, framebuffer: ExternalFramebuffer) -> Option<sk::Surface> {
+        let fb_info = sk::gpu::gl::FramebufferInfo { fboid: framebuffer.fbo, format: glow::RGBA8, protected: sk::gpu::Protected::No };
+        let backend = sk::gpu::backend_render_targets::make_gl(
+            (framebuffer.width, framebuffer.height),
+            framebuffer.samples,
+            framebuffer.stencil_bits,
+            fb_info,
+        );
+        sk::gpu::surfaces::wrap_backend_render_target(dctx, &backend, sk::gpu::SurfaceOrigin::BottomLeft, sk::ColorType::RGBA8888, None, None)
+    }
 }
 
 #[cfg(not(feature = "skia-native"))]
 mod nostub {
     pub struct Surface { _private: () }
-    pub fn create_window_surface(_w: i32, _h: i32) -> Result<Surface, String> { Err("skia-native feature not enabled".into()) }
+    pub fn create_window_surface(_w: i32, _h: i32) -> Result<Surface, crate::Error> { Err(crate::Error::Unsupported("skia-native")) }
     pub use Surface as SkiaSurface;
 }
 
@@ -0,0 +1,113 @@
+//! Memory introspection: what the renderer keeps alive between frames.
+//!
+//! `stats()` reports, for windows on this thread, how much the renderer retains: the
+//! view tree, per-element animation state, the frame arena, and the backend caches. A
+//! figure that keeps growing while the UI is idle points at a leak; `trim_caches()` drops
+//! everything that is rebuilt on demand, so what remains after it is live data.
+
+use std::cell::Cell;
+
+use velox_dom::VNode;
+
+/// Retained renderer memory. Byte counts a backend doesn't expose are zero.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RendererStats {
+    /// Nodes (elements and text) in the last frame's view tree.
+    pub retained_nodes: usize,
+    /// Rasterized glyphs: Skia's glyph cache.
+    pub glyph_atlas_bytes: usize,
+    /// Decoded images and other bitmaps in Skia's resource cache.
+    pub image_cache_bytes: usize,
+    /// Vertex buffers of the wgpu backend.
+    pub gpu_buffer_bytes: usize,
+    /// Per-element state kept between frames for layout animations and enter/leave
+    /// transitions.
+    pub layout_cache_entries: usize,
+    /// The frame arena's scratch memory and command buffer.
+    pub arena_bytes: usize,
+    /// The last frame, kept for readback (`pixels::enable_readback`).
+    pub readback_bytes: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Reported {
+    nodes: usize,
+    layout_entries: usize,
+    gpu_buffer_bytes: usize,
+}
+
+thread_local! {
+    static REPORTED: Cell<Reported> = const { Cell::new(Reported { nodes: 0, layout_entries: 0, gpu_buffer_bytes: 0 }) };
+}
+
+/// The renderer's retained memory on this thread.
+pub fn stats() -> RendererStats {
+    let reported = REPORTED.with(|r| r.get());
+    let (glyph_atlas_bytes, image_cache_bytes) = skia_cache_bytes();
+    RendererStats {
+        retained_nodes: reported.nodes,
+        glyph_atlas_bytes,
+        image_cache_bytes,
+        gpu_buffer_bytes: reported.gpu_buffer_bytes,
+        layout_cache_entries: reported.layout_entries,
+        arena_bytes: crate::arena::retained_bytes(),
+        readback_bytes: crate::pixels::kept_frame_bytes(),
+    }
+}
+
+/// Free the caches rebuilt on demand: the frame arena and Skia's glyph and resource
+/// caches. The next frame may be slower while they refill.
+pub fn trim_caches() {
+    crate::arena::release();
+    #[cfg(feature = "skia-native")]
+    skia_safe::graphics::purge_all_caches();
+}
+
+#[cfg(feature = "skia-native")]
+fn skia_cache_bytes() -> (usize, usize) {
+    (
+        skia_safe::graphics::font_cache_used(),
+        skia_safe::graphics::resource_cache_total_bytes_used(),
+    )
+}
+
+#[cfg(not(feature = "skia-native"))]
+fn skia_cache_bytes() -> (usize, usize) {
+    (0, 0)
+}
+
+/// Number of nodes in `vnode`'s tree, itself included.
+pub fn count_nodes(vnode: &VNode) -> usize {
+    match vnode {
+        VNode::Text(_) | VNode::Component { .. } => 1,
+        VNode::Element { children, .. } | VNode::Fragment(children) => {
+            1 + children.iter().map(count_nodes).sum::<usize>()
+        }
+    }
+}
+
+/// Runners: the frame just drawn and the per-element animation state they keep.
+#[cfg_attr(
+    not(any(feature = "wgpu", feature = "skia-native", feature = "raster")),
+    allow(dead_code)
+)]
+pub(crate) fn record_frame(vnode: &VNode, layout_entries: usize) {
+    REPORTED.with(|r| {
+        r.set(Reported {
+            nodes: count_nodes(vnode),
+            layout_entries,
+            ..r.get()
+        })
+    });
+}
+
+/// The wgpu runner: bytes in its vertex buffers.
+#[cfg_attr(not(feature = "wgpu"), allow(dead_code))]
+pub(crate) fn record_gpu_buffers(bytes: usize) {
+    REPORTED.with(|r| {
+        r.set(Reported {
+            gpu_buffer_bytes: bytes,
+            ..r.get()
+        })
+    });
+}
@@ -0,0 +1,347 @@
+//! Headless app simulator for component tests.
+//!
+//! `TestApp` drives the same `make_view`/`on_event` pair a window runner takes, without
+//! a window: every frame is styled and laid out like a real one, clicks are hit-tested
+//! against the layout, and the resulting tree can be queried or compared with a snapshot
+//! checked into the repository. `velox test` runs these from a component's `<test>` block
+//! or its `<Name>.test.rs` file.
+//!
+//! Snapshots are the styled tree as text (`crash::dump_tree`). A missing one is written
+//! on the first run and `VELOX_UPDATE_GOLDENS=1` rewrites stale ones.
+
+use std::path::Path;
+
+use velox_dom::VNode;
+use velox_dom::layout::{LayoutNode, Rect};
+use velox_style::Stylesheet;
+
+use crate::pipeline::{self, PreparedFrame};
+
+/// Viewport of a `TestApp` unless `with_size` says otherwise.
+pub const DEFAULT_SIZE: (u32, u32) = (800, 600);
+
+type MakeView = Box<dyn FnMut(u32, u32) -> (VNode, Stylesheet)>;
+type OnEvent = Box<dyn FnMut(&str, Option<&str>)>;
+
+/// A windowless app: see the module docs.
+pub struct TestApp {
+    make_view: MakeView,
+    on_event: OnEvent,
+    frame: PreparedFrame,
+}
+
+impl TestApp {
+    /// Render the first frame of the app at `DEFAULT_SIZE`.
+    pub fn new<V, E>(make_view: V, mut on_event: E) -> Self
+    where
+        V: FnMut(u32, u32) -> (VNode, Stylesheet) + 'static,
+        E: FnMut(&str, Option<&str>) + 'static,
+    {
+        let mut make_view: MakeView = Box::new(make_view);
+        // Effects held back by the flush policy run after each handler, as in a window.
+        let on_event = move |handler: &str, payload: Option<&str>| {
+            on_event(handler, payload);
+            velox_core::signal::end_turn();
+        };
+        let frame = prepare(&mut make_view, DEFAULT_SIZE.0, DEFAULT_SIZE.1);
+        TestApp {
+            make_view,
+            on_event: Box::new(on_event),
+            frame,
+        }
+    }
+
+    /// Resize the viewport and render again.
+    pub fn with_size(mut self, width: u32, height: u32) -> Self {
+        self.frame = prepare(&mut self.make_view, width, height);
+        self
+    }
+
+    /// Build, style and lay out a new frame from the app's current state.
+    pub fn render(&mut self) {
+        self.frame = prepare(&mut self.make_view, self.frame.width, self.frame.height);
+    }
+
+    pub fn frame(&self) -> &PreparedFrame {
+        &self.frame
+    }
+
+    /// The styled tree of the current frame.
+    pub fn tree(&self) -> &VNode {
+        &self.frame.vnode
+    }
+
+    /// The first element matching `selector`, in document order. Selectors are
+    /// whitespace-separated (descendant) compounds of `tag`, `.class`, `#id`, `[attr]`
+    /// and `[attr=value]`.
+    pub fn find(&self, selector: &str) -> Option<&VNode> {
+        self.find_with_rect(selector).map(|(node, _)| node)
+    }
+
+    /// Every element matching `selector`.
+    pub fn find_all(&self, selector: &str) -> Vec<&VNode> {
+        let selector = parse_selector(selector);
+        let mut out = Vec::new();
+        walk(
+            &self.frame.vnode,
+            &self.frame.layout,
+            &mut Vec::new(),
+            &mut |node, _, ancestors| {
+                if matches(&selector, node, ancestors) {
+                    out.push(node);
+                }
+                false
+            },
+        );
+        out
+    }
+
+    /// Text content of the whole frame, whitespace collapsed.
+    pub fn text(&self) -> String {
+        collapse(&crate::vnode_text_content(&self.frame.vnode))
+    }
+
+    /// Text content of the first element matching `selector`; panics if there is none.
+    pub fn text_of(&self, selector: &str) -> String {
+        let node = self
+            .find(selector)
+            .unwrap_or_else(|| panic!("no element matches `{}`", selector));
+        collapse(&crate::vnode_text_content(node))
+    }
+
+    /// Click the center of the first element matching `selector`, as a user would: the
+    /// handler is whichever click target the layout puts under that point. Panics if no
+    /// element matches.
+    pub fn click(&mut self, selector: &str) -> &mut Self {
+        let Some((_, rect)) = self.find_with_rect(selector) else {
+            panic!("no element matches `{}`", selector);
+        };
+        let (x, y) = (
+            rect.x as f32 + rect.w as f32 / 2.0,
+            rect.y as f32 + rect.h as f32 / 2.0,
+        );
+        if !self.click_at(x, y) {
+            panic!("`{}` at ({}, {}) has no click handler", selector, x, y);
+        }
+        self
+    }
+
+    /// Click at viewport coordinates; returns whether a click handler ran. Click-outside
+    /// handlers of elements not under the point run first, as in a window.
+    pub fn click_at(&mut self, x: f32, y: f32) -> bool {
+        let mut outside = Vec::new();
+        crate::events::collect_click_outside_targets(
+            &self.frame.vnode,
+            &self.frame.layout,
+            &mut outside,
+        );
+        let mut targets = Vec::new();
+        crate::events::collect_click_targets(&self.frame.vnode, &self.frame.layout, &mut targets);
+        let owned = |(handler, payload): (&str, Option<&str>)| {
+            (handler.to_string(), payload.map(str::to_string))
+        };
+        let outside: Vec<_> = crate::events::hit_test_click_outside(&outside, x, y)
+            .into_iter()
+            .map(owned)
+            .collect();
+        let click = crate::events::hit_test_click(&targets, x, y).map(owned);
+        for (handler, payload) in &outside {
+            (self.on_event)(handler, payload.as_deref());
+        }
+        let Some((handler, payload)) = click else {
+            if !outside.is_empty() {
+                self.render();
+            }
+            return false;
+        };
+        self.emit(&handler, payload.as_deref());
+        true
+    }
+
+    /// Send `handler` to the app directly, as the runners do for keyboard and input
+    /// events, then render.
+    pub fn emit(&mut self, handler: &str, payload: Option<&str>) -> &mut Self {
+        (self.on_event)(handler, payload);
+        self.render();
+        self
+    }
+
+    /// Panic unless the frame's styled tree matches the snapshot at `path` (see the
+    /// module docs for when it is written instead).
+    pub fn assert_snapshot(&self, path: impl AsRef<Path>) {
+        let path = path.as_ref();
+        let actual = crate::crash::dump_tree(&self.frame.vnode);
+        let update =
+            std::env::var_os(crate::golden::UPDATE_ENV).is_some_and(|v| !v.is_empty() && v != "0");
+        match std::fs::read_to_string(path) {
+            Ok(expected) if !update => {
+                if expected == actual {
+                    return;
+                }
+                let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("txt");
+                let saved = path.with_extension(format!("actual.{}", ext));
+                let _ = std::fs::write(&saved, &actual);
+                panic!(
+                    "tree differs from snapshot {} (rendered: {}); set {}=1 to accept",
+                    path.display(),
+                    saved.display(),
+                    crate::golden::UPDATE_ENV
+                );
+            }
+            _ => {
+                if let Some(dir) = path.parent() {
+                    let _ = std::fs::create_dir_all(dir);
+                }
+                std::fs::write(path, &actual)
+                    .unwrap_or_else(|e| panic!("snapshot {}: {}", path.display(), e));
+                eprintln!("wrote snapshot {}", path.display());
+            }
+        }
+    }
+
+    fn find_with_rect(&self, selector: &str) -> Option<(&VNode, Rect)> {
+        let selector = parse_selector(selector);
+        let mut found = None;
+        walk(
+            &self.frame.vnode,
+            &self.frame.layout,
+            &mut Vec::new(),
+            &mut |node, rect, ancestors| {
+                if matches(&selector, node, ancestors) {
+                    found = Some((node, rect));
+                    return true;
+                }
+                false
+            },
+        );
+        found
+    }
+}
+
+fn prepare(make_view: &mut MakeView, width: u32, height: u32) -> PreparedFrame {
+    let (mut vnode, sheet) = make_view(width, height);
+    crate::components::expand(&mut vnode);
+    pipeline::take_title(&mut vnode);
+    let frame = pipeline::prepare_frame(&vnode, &sheet, &|_, _| false, width, height);
+    velox_core::signal::run_next_tick();
+    frame
+}
+
+/// One compound selector: everything must hold for the same element.
+#[derive(Default)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attrs: Vec<(String, Option<String>)>,
+}
+
+fn parse_selector(selector: &str) -> Vec<Compound> {
+    selector.split_whitespace().map(parse_compound).collect()
+}
+
+fn parse_compound(src: &str) -> Compound {
+    let mut compound = Compound::default();
+    let mut rest = src;
+    while !rest.is_empty() {
+        let (kind, body) = match rest.as_bytes()[0] {
+            b'.' | b'#' => (rest.as_bytes()[0], &rest[1..]),
+            b'[' => {
+                let end = rest.find(']').unwrap_or(rest.len());
+                let inner = &rest[1..end];
+                let attr = match inner.split_once('=') {
+                    Some((k, v)) => (
+                        k.trim().to_string(),
+                        Some(v.trim().trim_matches(['"', '\'']).to_string()),
+                    ),
+                    None => (inner.trim().to_string(), None),
+                };
+                compound.attrs.push(attr);
+                rest = rest.get(end + 1..).unwrap_or("");
+                continue;
+            }
+            _ => (b't', rest),
+        };
+        let end = body.find(['.', '#', '[']).unwrap_or(body.len());
+        let name = body[..end].to_string();
+        match kind {
+            b'.' => compound.classes.push(name),
+            b'#' => compound.id = Some(name),
+            _ => compound.tag = Some(name),
+        }
+        rest = &body[end..];
+    }
+    compound
+}
+
+fn matches_compound(compound: &Compound, node: &VNode) -> bool {
+    let VNode::Element { tag, props, .. } = node else {
+        return false;
+    };
+    // Numbers and flags match as they are written, e.g. `[tabindex="0"]`.
+    let attr = |k: &str| props.get(k).map(ToString::to_string);
+    compound.tag.as_ref().is_none_or(|t| t == tag)
+        && compound
+            .id
+            .as_deref()
+            .is_none_or(|id| attr("id").as_deref() == Some(id))
+        && compound
+            .classes
+            .iter()
+            .all(|c| attr("class").is_some_and(|list| list.split_whitespace().any(|x| x == c)))
+        && compound.attrs.iter().all(|(k, v)| match v {
+            Some(v) => attr(k).as_deref() == Some(v.as_str()),
+            None => props.contains(k),
+        })
+}
+
+/// The last compound matches `node`, the ones before it match ancestors in order.
+fn matches(selector: &[Compound], node: &VNode, ancestors: &[&VNode]) -> bool {
+    let Some((last, rest)) = selector.split_last() else {
+        return false;
+    };
+    if !matches_compound(last, node) {
+        return false;
+    }
+    let mut rest = rest;
+    for ancestor in ancestors.iter().rev() {
+        match rest.split_last() {
+            Some((compound, before)) if matches_compound(compound, ancestor) => rest = before,
+            Some(_) => {}
+            None => break,
+        }
+    }
+    rest.is_empty()
+}
+
+/// Visit elements in document order with their layout rects; `visit` returns true to stop.
+fn walk<'a>(
+    node: &'a VNode,
+    layout: &LayoutNode,
+    ancestors: &mut Vec<&'a VNode>,
+    visit: &mut dyn FnMut(&'a VNode, Rect, &[&'a VNode]) -> bool,
+) -> bool {
+    if let VNode::Fragment(children) = node {
+        return children
+            .iter()
+            .zip(&layout.children)
+            .any(|(child, child_layout)| walk(child, child_layout, ancestors, visit));
+    }
+    let VNode::Element { children, .. } = node else {
+        return false;
+    };
+    if visit(node, layout.rect, ancestors) {
+        return true;
+    }
+    ancestors.push(node);
+    let stop = children
+        .iter()
+        .zip(&layout.children)
+        .any(|(child, child_layout)| walk(child, child_layout, ancestors, visit));
+    ancestors.pop();
+    stop
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,124 @@
+//! Text scaling from the operating system's font-size setting.
+//!
+//! The window runners read the user's text scale when they start and set the root font
+//! size `rem` lengths resolve against to `DEFAULT_ROOT_FONT_SIZE * scale`, so a UI sized
+//! in `rem` grows with the system font size. The setting is polled every `POLL_INTERVAL`
+//! while the window is open and the view restyled when it changes.
+//!
+//! The scale comes from GNOME's `text-scaling-factor` on Linux and the "Make text bigger"
+//! accessibility setting on Windows; macOS has no system-wide equivalent and reports 1.
+//! `VELOX_TEXT_SCALE` overrides it on every platform (`VELOX_TEXT_SCALE=1` opts out), and
+//! deterministic mode ignores it.
+
+use std::time::Duration;
+
+use velox_style::{DEFAULT_ROOT_FONT_SIZE, set_root_font_size};
+
+/// The environment variable that overrides the system text scale.
+pub const ENV_VAR: &str = "VELOX_TEXT_SCALE";
+/// How often the runners re-read the system setting.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(2);
+pub const MIN_SCALE: f32 = 0.5;
+pub const MAX_SCALE: f32 = 3.0;
+
+/// The user's text scale factor, 1 when unset or unknown.
+pub fn system_text_scale() -> f32 {
+    let scale = std::env::var(ENV_VAR)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .or_else(platform_text_scale);
+    match scale {
+        Some(s) if f32::is_finite(s) && s > 0.0 => s.clamp(MIN_SCALE, MAX_SCALE),
+        _ => 1.0,
+    }
+}
+
+/// Resolve `rem` at `scale` times the default root font size.
+pub fn apply(scale: f32) {
+    let scale = if scale.is_finite() && scale > 0.0 {
+        scale.clamp(MIN_SCALE, MAX_SCALE)
+    } else {
+        1.0
+    };
+    set_root_font_size(DEFAULT_ROOT_FONT_SIZE * scale);
+}
+
+#[cfg(target_os = "windows")]
+fn platform_text_scale() -> Option<f32> {
+    // `    TextScaleFactor    REG_DWORD    0x7d`, a percentage.
+    let out = command_output(
+        "reg",
+        &[
+            "query",
+            r"HKCU\Software\Microsoft\Accessibility",
+            "/v",
+            "TextScaleFactor",
+        ],
+    )?;
+    let hex = out.split_whitespace().find_map(|t| t.strip_prefix("0x"))?;
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .map(|pct| pct as f32 / 100.0)
+}
+
+#[cfg(target_os = "macos")]
+fn platform_text_scale() -> Option<f32> {
+    None
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn platform_text_scale() -> Option<f32> {
+    command_output(
+        "gsettings",
+        &["get", "org.gnome.desktop.interface", "text-scaling-factor"],
+    )?
+    .trim()
+    .parse()
+    .ok()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    let out = std::process::Command::new(program)
+        .args(args)
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Apply the system text scale now, then keep following it on a background thread,
+/// calling `wake` after each change (the runners wake their event loop to restyle).
+/// The thread stops when `wake` returns false.
+#[cfg_attr(
+    not(any(feature = "wgpu", feature = "skia-native", feature = "raster")),
+    allow(dead_code)
+)]
+pub(crate) fn follow_system(wake: impl Fn() -> bool + Send + 'static) {
+    if crate::config::is_deterministic() {
+        apply(1.0);
+        return;
+    }
+    let mut last = system_text_scale();
+    apply(last);
+    let spawned = std::thread::Builder::new()
+        .name("velox-text-scale".into())
+        .spawn(move || {
+            loop {
+                std::thread::sleep(POLL_INTERVAL);
+                let scale = system_text_scale();
+                if (scale - last).abs() > 0.001 {
+                    last = scale;
+                    apply(scale);
+                    if !wake() {
+                        break;
+                    }
+                }
+            }
+        });
+    if let Err(e) = spawned {
+        log::warn!(target: "velox::renderer", "not following the system text scale: {}", e);
+    }
+}
@@ -0,0 +1,243 @@
+//! View plumbing shared by the CPU-presented window runners and `VeloxUi`: build the styled tree for the
+//! current viewport and hover state, and refresh the hit-test targets from its layout.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
+
+use velox_dom::{PropValue, Props, VNode};
+use velox_style::Stylesheet;
+
+use crate::enter_leave::EnterLeave;
+use crate::events::{HandlerTable, Targets};
+
+pub(crate) fn logical_size(width: i32, height: i32, scale_factor: f32) -> (u32, u32) {
+    let w = ((width as f32) / scale_factor).round().max(1.0) as u32;
+    let h = ((height as f32) / scale_factor).round().max(1.0) as u32;
+    (w, h)
+}
+
+pub(crate) fn with_hover_ids(vnode: &VNode, next_id: &mut u32) -> VNode {
+    match vnode {
+        VNode::Text(_) | VNode::Component { .. } => vnode.clone(),
+        VNode::Element {
+            tag,
+            props,
+            children,
+        } => {
+            let mut new_props = props.clone();
+            if crate::events::is_hoverable(tag, props) {
+                let id = *next_id;
+                *next_id += 1;
+                new_props = new_props.set("data-hover-id", id);
+            }
+            let new_children = children
+                .iter()
+                .map(|c| with_hover_ids(c, next_id))
+                .collect();
+            VNode::Element {
+                tag: tag.clone(),
+                props: new_props,
+                children: new_children,
+            }
+        }
+        VNode::Fragment(children) => VNode::Fragment(
+            children
+                .iter()
+                .map(|c| with_hover_ids(c, next_id))
+                .collect(),
+        ),
+    }
+}
+
+/// Per-window state carried between `styled_view` calls.
+#[derive(Debug, Default)]
+pub(crate) struct ViewState {
+    pub(crate) transitions: EnterLeave,
+    /// Text of the `<title>` element of the last view, if it had one.
+    title: Option<String>,
+    shown_title: Option<String>,
+    /// The stylesheet of the last view and a counter bumped whenever a view brings a
+    /// different one.
+    sheet: Option<Stylesheet>,
+    sheet_generation: u64,
+    last_cascade: Option<LastCascade>,
+}
+
+/// What the last cascade was for, and its output, reused while the view and stylesheet
+/// stay the same: redrawing an unchanged view skips the cascade, and a hover change only
+/// restyles the subtrees of the elements entering and leaving hover.
+#[derive(Debug)]
+struct LastCascade {
+    sheet_generation: u64,
+    tree_hash: u64,
+    root_font_size: f32,
+    hovered: Option<u32>,
+    styled: Rc<VNode>,
+}
+
+impl ViewState {
+    fn cascade(&mut self, tree: &VNode, sheet: &Stylesheet, hovered: Option<u32>) -> VNode {
+        use crate::pipeline::hover_id_matches;
+
+        if self.sheet.as_ref() != Some(sheet) {
+            self.sheet = Some(sheet.clone());
+            self.sheet_generation += 1;
+        }
+        let (sheet_generation, tree_hash) = (self.sheet_generation, tree_hash(tree));
+        let root_font_size = velox_style::root_font_size();
+        let styled = match &self.last_cascade {
+            Some(last)
+                if last.sheet_generation == sheet_generation
+                    && last.tree_hash == tree_hash
+                    && last.root_font_size.to_bits() == root_font_size.to_bits() =>
+            {
+                if last.hovered == hovered {
+                    return (*last.styled).clone();
+                }
+                velox_style::restyle_hover(
+                    &last.styled,
+                    tree,
+                    sheet,
+                    &|_tag, props| hover_id_matches(props, hovered),
+                    &|_tag, props| {
+                        hover_id_matches(props, hovered) || hover_id_matches(props, last.hovered)
+                    },
+                )
+            }
+            _ => crate::pipeline::style_tree(tree, sheet, &|_tag, props| {
+                hover_id_matches(props, hovered)
+            }),
+        };
+        let styled = Rc::new(styled);
+        self.last_cascade = Some(LastCascade {
+            sheet_generation,
+            tree_hash,
+            root_font_size,
+            hovered,
+            styled: styled.clone(),
+        });
+        (*styled).clone()
+    }
+}
+
+/// A hash that is equal for equal trees, so a cascade can be matched to its input
+/// without keeping a copy of it.
+fn tree_hash(vnode: &VNode) -> u64 {
+    fn node(vnode: &VNode, h: &mut DefaultHasher) {
+        match vnode {
+            VNode::Text(text) => {
+                0u8.hash(h);
+                text.hash(h);
+            }
+            VNode::Element {
+                tag,
+                props,
+                children,
+            }
+            | VNode::Component {
+                name: tag,
+                props,
+                children,
+            } => {
+                (matches!(vnode, VNode::Element { .. }) as u8 + 1).hash(h);
+                tag.hash(h);
+                props_hash(props).hash(h);
+                children.len().hash(h);
+                children.iter().for_each(|c| node(c, h));
+            }
+            VNode::Fragment(children) => {
+                3u8.hash(h);
+                children.len().hash(h);
+                children.iter().for_each(|c| node(c, h));
+            }
+        }
+    }
+    fn value(v: &PropValue, h: &mut DefaultHasher) {
+        match v {
+            PropValue::Str(s) => (0u8, s).hash(h),
+            PropValue::Num(n) => (1u8, n.to_bits()).hash(h),
+            PropValue::Bool(b) => (2u8, b).hash(h),
+            PropValue::Handler(s) => (3u8, s).hash(h),
+            PropValue::List(items) => {
+                (4u8, items.len()).hash(h);
+                items.iter().for_each(|item| value(item, h));
+            }
+        }
+    }
+    // Props are a hash map: hash each entry on its own and add them up, so the order
+    // they are visited in doesn't matter.
+    fn props_hash(props: &Props) -> u64 {
+        props
+            .attrs
+            .iter()
+            .fold(props.attrs.len() as u64, |sum, (k, v)| {
+                let mut h = DefaultHasher::new();
+                k.hash(&mut h);
+                value(v, &mut h);
+                sum.wrapping_add(h.finish())
+            })
+    }
+    let mut h = DefaultHasher::new();
+    node(vnode, &mut h);
+    h.finish()
+}
+
+impl ViewState {
+    /// The window title for the last view (its `<title>` element, else `get_title()`),
+    /// or `None` when it is already the one shown.
+    #[cfg_attr(
+        not(any(feature = "skia-native", feature = "raster")),
+        allow(dead_code)
+    )]
+    pub(crate) fn title_change(&mut self, get_title: impl FnOnce() -> String) -> Option<String> {
+        let title = self.title.take().unwrap_or_else(get_title);
+        if self.shown_title.as_ref() == Some(&title) {
+            return None;
+        }
+        self.shown_title = Some(title.clone());
+        Some(title)
+    }
+}
+
+/// Call `make_view` for a `width`x`height` logical viewport and cascade its stylesheet,
+/// resolving `:hover` rules against `hovered`. Components in the view are expanded (see
+/// `components`). The frame clock (`on_frame` callbacks and
+/// springs) ticks first so the view reads this frame's values, then enter/leave
+/// transitions advance. A `<title>` element is taken out of the tree into `state`. When
+/// the view and stylesheet are those of the last call, the cascade is reused from it.
+pub(crate) fn styled_view<F>(
+    make_view: &mut F,
+    width: u32,
+    height: u32,
+    hovered: Option<u32>,
+    state: &mut ViewState,
+) -> (VNode, Stylesheet)
+where
+    F: FnMut(u32, u32) -> (VNode, Stylesheet),
+{
+    let now = velox_core::clock::now();
+    velox_core::frame::tick(now);
+    let (mut vnode_raw, sheet) = make_view(width, height);
+    crate::components::expand(&mut vnode_raw);
+    state.title = crate::pipeline::take_title(&mut vnode_raw);
+    state.transitions.prepare(&mut vnode_raw, now);
+    let mut next_id = 1u32;
+    let vnode_tagged = with_hover_ids(&vnode_raw, &mut next_id);
+    let mut vnode = state.cascade(&vnode_tagged, &sheet, hovered);
+    state.transitions.apply(&mut vnode, &sheet, now);
+    (vnode, sheet)
+}
+
+/// Lay out `vnode` and collect its targets, resolving handlers through `handlers`, its
+/// table (the mounted `RenderTree`'s once the frame is committed).
+pub(crate) fn recompute_targets(
+    vnode: &VNode,
+    width: u32,
+    height: u32,
+    handlers: &HandlerTable,
+    targets: &mut Targets,
+) {
+    let layout = crate::pipeline::layout_tree(vnode, width, height);
+    targets.collect(vnode, &layout, handlers);
+}
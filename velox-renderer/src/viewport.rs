@@ -0,0 +1,173 @@
+//! Pannable, zoomable viewports.
+//!
+//! An element with an `on:pan` handler is a window onto bigger content, such as a map.
+//! Dragging on it (where no click handler takes the press) and turning the wheel over it
+//! send the handler how far to move the content, in logical pixels (`{"dx":-12,"dy":4}`,
+//! with the element's `on:pan-payload` as `"id"`). The drag keeps the pointer until it
+//! lifts, when the element's `on:pan-end` handler gets where it was last, in layout
+//! coordinates (`{"x":310,"y":42}`, with `"cancelled":true` if a gesture took over).
+//!
+//! Ctrl+wheel over an `on:zoom` element zooms it like a pinch does (see `gesture`), by a
+//! factor of 2 per `WHEEL_ZOOM_PIXELS`; a wheel turn no viewport takes scrolls the window.
+//! The app moves and scales the content with a `transform` (`velox_widgets::Viewport`
+//! keeps the state); hit tests follow it.
+
+use crate::events::Targets;
+use crate::gesture::Gesture;
+use crate::pen::PenPhase;
+
+/// Logical pixels per line of a wheel that reports lines.
+pub const LINE_PIXELS: f32 = 40.0;
+/// Wheel travel, in logical pixels, that zooms by a factor of 2.
+pub const WHEEL_ZOOM_PIXELS: f32 = 300.0;
+
+/// The `on:pan` payload for moving the content by `(dx, dy)`, for the element with the
+/// `on:pan-payload` `id`.
+pub fn pan_payload(dx: f32, dy: f32, id: Option<&str>) -> String {
+    match id {
+        Some(id) => {
+            let id = id.replace('\\', "\\\\").replace('"', "\\\"");
+            format!("{{\"dx\":{},\"dy\":{},\"id\":\"{}\"}}", dx, dy, id)
+        }
+        None => format!("{{\"dx\":{},\"dy\":{}}}", dx, dy),
+    }
+}
+
+/// The `on:pan-end` payload for a drag that ended at `(x, y)`, for the element with the
+/// `on:pan-payload` `id`.
+pub fn pan_end_payload(x: f32, y: f32, id: Option<&str>, cancelled: bool) -> String {
+    let mut payload = format!("{{\"x\":{},\"y\":{}", x, y);
+    if let Some(id) = id {
+        let id = id.replace('\\', "\\\\").replace('"', "\\\"");
+        payload.push_str(&format!(",\"id\":\"{}\"", id));
+    }
+    if cancelled {
+        payload.push_str(",\"cancelled\":true");
+    }
+    payload.push('}');
+    payload
+}
+
+/// A pointer dragging an `on:pan` element.
+#[derive(Debug, Clone)]
+struct Drag {
+    pointer: u64,
+    handler: String,
+    id: Option<String>,
+    /// The element's `on:pan-end` handler.
+    end: Option<String>,
+    /// Where the pointer was last.
+    last: (f32, f32),
+}
+
+/// Drags in progress on `on:pan` elements.
+#[derive(Debug, Clone, Default)]
+pub struct Pans {
+    drags: Vec<Drag>,
+}
+
+impl Pans {
+    pub fn new() -> Self {
+        Pans::default()
+    }
+
+    /// Track `pointer` at `(x, y)` in layout coordinates. A press on an `on:pan` element
+    /// with no click target under it starts a drag, which pans that element until the
+    /// pointer lifts. Returns whether the drag took the press or panned.
+    pub fn pointer(
+        &mut self,
+        pointer: u64,
+        phase: PenPhase,
+        x: f32,
+        y: f32,
+        targets: &Targets,
+        on_event: &mut dyn FnMut(&str, Option<&str>),
+    ) -> bool {
+        let drag = self.drags.iter().position(|d| d.pointer == pointer);
+        match phase {
+            PenPhase::Down => {
+                if let Some(i) = drag {
+                    self.drags.remove(i);
+                }
+                if targets.click_at(x, y).is_some() {
+                    return false;
+                }
+                let Some(target) = targets.pan_at(x, y) else {
+                    return false;
+                };
+                let end = targets
+                    .pan_end_at(x, y)
+                    .filter(|e| e.rect == target.rect && e.payload == target.payload);
+                self.drags.push(Drag {
+                    pointer,
+                    handler: target.handler.clone(),
+                    id: target.payload.clone(),
+                    end: end.map(|e| e.handler.clone()),
+                    last: (x, y),
+                });
+                true
+            }
+            PenPhase::Move => {
+                let Some(i) = drag else { return false };
+                let drag = &mut self.drags[i];
+                let (dx, dy) = (x - drag.last.0, y - drag.last.1);
+                if dx == 0.0 && dy == 0.0 {
+                    return false;
+                }
+                drag.last = (x, y);
+                on_event(
+                    &drag.handler,
+                    Some(&pan_payload(dx, dy, drag.id.as_deref())),
+                );
+                true
+            }
+            PenPhase::Up | PenPhase::Cancel => {
+                let Some(i) = drag else { return false };
+                let drag = self.drags.remove(i);
+                if let Some(end) = &drag.end {
+                    let (x, y) = drag.last;
+                    on_event(
+                        end,
+                        Some(&pan_end_payload(
+                            x,
+                            y,
+                            drag.id.as_deref(),
+                            phase == PenPhase::Cancel,
+                        )),
+                    );
+                }
+                true
+            }
+        }
+    }
+
+    /// Whether a pointer is dragging a viewport.
+    pub fn is_dragging(&self) -> bool {
+        !self.drags.is_empty()
+    }
+}
+
+/// Route a wheel turn of `(dx, dy)` logical pixels (positive to move the content right
+/// and down) at `(x, y)` in layout coordinates: with `zoom` (Ctrl held) to the `on:zoom`
+/// element there, otherwise to the `on:pan` one. Returns whether one took it.
+pub fn wheel(
+    (dx, dy): (f32, f32),
+    zoom: bool,
+    (x, y): (f32, f32),
+    targets: &Targets,
+    on_event: &mut dyn FnMut(&str, Option<&str>),
+) -> bool {
+    if zoom {
+        let scale = 2f32.powf(dy / WHEEL_ZOOM_PIXELS);
+        return dy != 0.0
+            && crate::gesture::dispatch(Gesture::Zoom { scale, x, y }, targets, on_event);
+    }
+    let Some(target) = targets.pan_at(x, y) else {
+        return false;
+    };
+    on_event(
+        &target.handler,
+        Some(&pan_payload(dx, dy, target.payload.as_deref())),
+    );
+    true
+}
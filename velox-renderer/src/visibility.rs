@@ -0,0 +1,128 @@
+//! `on:visible` / `on:hidden` events, like `IntersectionObserver`.
+//!
+//! Elements with an `on:visible` or `on:hidden` handler are checked against the viewport
+//! after every layout. `on:visible` fires when any part of the element enters it (also on
+//! the first frame the element is shown inside it) and `on:hidden` when it leaves it
+//! entirely. `visible-margin="200"` grows the viewport by that many pixels for the
+//! element, so content can load before it is scrolled in. The payload is the element's
+//! `on:visible-payload` / `on:hidden-payload` attribute, if any.
+
+use std::collections::HashMap;
+
+use velox_dom::VNode;
+use velox_dom::layout::{LayoutNode, Rect};
+
+use crate::animation::push_segment;
+
+/// A visibility change to dispatch: handler name and payload.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VisibilityEvent {
+    pub handler: String,
+    pub payload: Option<String>,
+}
+
+/// Remembers which observed elements were visible in the previous frame. Elements are
+/// matched by `key`, falling back to their position among their siblings.
+#[derive(Debug, Default)]
+pub struct VisibilityTracker {
+    /// Path -> (visible, frame it was last laid out in).
+    visible: HashMap<String, (bool, u64)>,
+    frame: u64,
+}
+
+impl VisibilityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare the laid-out frame against `viewport` (in the same coordinates as
+    /// `layout`) and return the events to dispatch, in tree order.
+    pub fn update(
+        &mut self,
+        vnode: &VNode,
+        layout: &LayoutNode,
+        viewport: Rect,
+    ) -> Vec<VisibilityEvent> {
+        self.frame += 1;
+        let mut out = Vec::new();
+        let mut path = String::new();
+        self.walk(vnode, layout, viewport, &mut path, &mut out);
+        let frame = self.frame;
+        self.visible.retain(|_, (_, f)| *f == frame);
+        out
+    }
+
+    fn walk(
+        &mut self,
+        vnode: &VNode,
+        layout: &LayoutNode,
+        viewport: Rect,
+        path: &mut String,
+        out: &mut Vec<VisibilityEvent>,
+    ) {
+        if let VNode::Fragment(children) = vnode {
+            let len = path.len();
+            for (i, (child, child_layout)) in children.iter().zip(&layout.children).enumerate() {
+                push_segment(path, child, i);
+                self.walk(child, child_layout, viewport, path, out);
+                path.truncate(len);
+            }
+            return;
+        }
+        let VNode::Element {
+            props, children, ..
+        } = vnode
+        else {
+            return;
+        };
+        let on_visible = props.str("on:visible");
+        let on_hidden = props.str("on:hidden");
+        if on_visible.is_some() || on_hidden.is_some() {
+            let margin = props
+                .str("visible-margin")
+                .and_then(|m| m.trim().trim_end_matches("px").parse::<i32>().ok())
+                .unwrap_or(0);
+            let area = Rect {
+                x: viewport.x - margin,
+                y: viewport.y - margin,
+                w: viewport.w + 2 * margin,
+                h: viewport.h + 2 * margin,
+            };
+            let now = intersects(layout.rect, area);
+            let was = self
+                .visible
+                .insert(path.clone(), (now, self.frame))
+                .map(|(v, _)| v);
+            let (handler, payload_key) = match (was, now) {
+                (Some(false) | None, true) => (on_visible, "on:visible-payload"),
+                (Some(true), false) => (on_hidden, "on:hidden-payload"),
+                _ => (None, ""),
+            };
+            if let Some(handler) = handler {
+                out.push(VisibilityEvent {
+                    handler: handler.to_string(),
+                    payload: props.str(payload_key).map(str::to_string),
+                });
+            }
+        }
+        let len = path.len();
+        for (i, (child, child_layout)) in children.iter().zip(&layout.children).enumerate() {
+            push_segment(path, child, i);
+            self.walk(child, child_layout, viewport, path, out);
+            path.truncate(len);
+        }
+    }
+}
+
+/// Whether `a` overlaps `b`. A zero-sized element counts when it lies inside `b`, so an
+/// empty sentinel element still reports visibility.
+fn intersects(a: Rect, b: Rect) -> bool {
+    let overlaps = |a0: i32, a_len: i32, b0: i32, b_len: i32| {
+        if a_len > 0 {
+            a0 < b0 + b_len && a0 + a_len > b0
+        } else {
+            a0 >= b0 && a0 <= b0 + b_len
+        }
+    };
+    overlaps(a.x, a.w, b.x, b.w) && overlaps(a.y, a.h, b.y, b.h)
+}
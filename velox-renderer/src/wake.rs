@@ -0,0 +1,35 @@
+//! Waking the window from other threads.
+//!
+//! The runners sleep until input arrives. A thread that changes what the view shows (a
+//! network reply, a message from another process through `ipc`) calls `wake` so the open
+//! window rebuilds its view and draws again. Async tasks (`velox_core::task`) wake it
+//! the same way when their futures make progress.
+
+use std::sync::Mutex;
+
+type Waker = Box<dyn Fn() -> bool + Send>;
+
+static WAKER: Mutex<Option<Waker>> = Mutex::new(None);
+
+/// Install the open window's waker; it returns false once the window is gone.
+#[cfg_attr(
+    not(any(feature = "wgpu", feature = "skia-native", feature = "raster")),
+    allow(dead_code)
+)]
+pub(crate) fn set_waker(wake: impl Fn() -> bool + Send + 'static) {
+    *WAKER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(wake));
+    velox_core::task::set_waker(|| {
+        crate::wake::wake();
+    });
+}
+
+/// Have the open window rebuild its view and redraw. Returns false when no window is
+/// open, so there was nothing to wake.
+pub fn wake() -> bool {
+    let mut waker = WAKER.lock().unwrap_or_else(|e| e.into_inner());
+    let awake = waker.as_ref().is_some_and(|wake| wake());
+    if !awake {
+        *waker = None;
+    }
+    awake
+}
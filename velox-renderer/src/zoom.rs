@@ -0,0 +1,118 @@
+//! UI zoom: a user scale factor on top of the display's DPI scale.
+//!
+//! The CPU-presented window runners (Skia raster and tiny-skia) lay the view out at the
+//! window size divided by `dpi * zoom()` and draw it scaled back up, so zooming in makes
+//! everything larger and wraps text to the narrower layout, like browser zoom. The wgpu
+//! runner draws in physical pixels and ignores both DPI and zoom.
+//!
+//! `Ctrl+=` / `Ctrl++` zoom in, `Ctrl+-` zooms out, and `Ctrl+0` resets (`Meta` on macOS),
+//! through the default `zoom-in`, `zoom-out`, and `zoom-reset` shortcuts. Steps follow
+//! the browser ladder in `LEVELS`. `persist_in` remembers the level across runs in the
+//! app's `Settings`.
+
+use std::cell::{Cell, RefCell};
+
+use velox_core::settings::Settings;
+
+/// Zoom steps for `zoom_in` and `zoom_out`.
+pub const LEVELS: &[f32] = &[
+    0.5, 0.67, 0.75, 0.8, 0.9, 1.0, 1.1, 1.25, 1.5, 1.75, 2.0, 2.5, 3.0,
+];
+pub const MIN_ZOOM: f32 = 0.5;
+pub const MAX_ZOOM: f32 = 3.0;
+/// The settings key `persist_in` stores the level under.
+pub const SETTINGS_KEY: &str = "ui.zoom";
+
+/// Handler names the default bindings dispatch; runners handle them themselves.
+pub const ZOOM_IN: &str = "zoom-in";
+pub const ZOOM_OUT: &str = "zoom-out";
+pub const ZOOM_RESET: &str = "zoom-reset";
+
+thread_local! {
+    static ZOOM: Cell<f32> = const { Cell::new(1.0) };
+    static SETTINGS: RefCell<Option<Settings>> = const { RefCell::new(None) };
+}
+
+/// The zoom factor for windows on this thread.
+pub fn zoom() -> f32 {
+    ZOOM.with(|z| z.get())
+}
+
+/// Set the zoom factor, clamped to `MIN_ZOOM..=MAX_ZOOM`, saving it if persisted.
+pub fn set_zoom(factor: f32) {
+    let factor = if factor.is_finite() {
+        factor.clamp(MIN_ZOOM, MAX_ZOOM)
+    } else {
+        1.0
+    };
+    ZOOM.with(|z| z.set(factor));
+    SETTINGS.with(|s| {
+        if let Some(settings) = s.borrow_mut().as_mut() {
+            settings.set(SETTINGS_KEY, factor);
+            if let Err(e) = settings.save() {
+                log::warn!(target: "velox::renderer", "saving zoom level: {}", e);
+            }
+        }
+    });
+}
+
+/// Step to the next larger level.
+pub fn zoom_in() {
+    let z = zoom();
+    set_zoom(
+        LEVELS
+            .iter()
+            .copied()
+            .find(|&l| l > z + 0.001)
+            .unwrap_or(MAX_ZOOM),
+    );
+}
+
+/// Step to the next smaller level.
+pub fn zoom_out() {
+    let z = zoom();
+    set_zoom(
+        LEVELS
+            .iter()
+            .rev()
+            .copied()
+            .find(|&l| l < z - 0.001)
+            .unwrap_or(MIN_ZOOM),
+    );
+}
+
+pub fn reset_zoom() {
+    set_zoom(1.0);
+}
+
+/// Restore the zoom level saved in `settings`, and save every later change there.
+pub fn persist_in(settings: Settings) {
+    let saved: Option<f32> = settings.get_parsed(SETTINGS_KEY);
+    SETTINGS.with(|s| *s.borrow_mut() = Some(settings));
+    if let Some(factor) = saved {
+        ZOOM.with(|z| z.set(factor.clamp(MIN_ZOOM, MAX_ZOOM)));
+    }
+}
+
+/// The scale views are drawn at: the display's `dpi` scale times the zoom, or 1 in
+/// deterministic mode.
+pub(crate) fn ui_scale(dpi: f32) -> f32 {
+    if crate::config::is_deterministic() {
+        return 1.0;
+    }
+    dpi * zoom()
+}
+
+/// Take a resolved zoom shortcut. Returns whether it was one.
+pub(crate) fn handle_shortcut(handler: &str, payload: Option<&str>) -> bool {
+    if payload.is_some() {
+        return false;
+    }
+    match handler {
+        ZOOM_IN => zoom_in(),
+        ZOOM_OUT => zoom_out(),
+        ZOOM_RESET => reset_zoom(),
+        _ => return false,
+    }
+    true
+}
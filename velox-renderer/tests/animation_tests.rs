@@ -0,0 +1,99 @@
+use std::time::{Duration, Instant};
+
+use velox_dom::layout::compute_layout;
+use velox_dom::{VNode, h, text};
+use velox_renderer::animation::{Easing, LayoutAnimator, parse_transition};
+
+fn panel(height: u32) -> VNode {
+    let style = format!("transition: height 100ms linear; height: {}px", height);
+    h(
+        "div",
+        vec![],
+        vec![
+            h("div", vec![("style", style.as_str())], vec![]),
+            h("p", vec![], vec![text("below")]),
+        ],
+    )
+}
+
+fn rects(tree: &VNode, animator: &mut LayoutAnimator, now: Instant) -> (bool, Vec<(i32, i32)>) {
+    let mut layout = compute_layout(tree, 200, 400);
+    let running = animator.animate(tree, &mut layout, now);
+    (
+        running,
+        layout
+            .children
+            .iter()
+            .map(|c| (c.rect.y, c.rect.h))
+            .collect(),
+    )
+}
+
+#[test]
+fn parses_layout_transitions_and_easings() {
+    let t = parse_transition(
+        "height 200ms ease-out, top 0.5s cubic-bezier(0.2, 0, 0, 1) 50ms, color 1s",
+    );
+    assert_eq!(t.height.unwrap().duration, Duration::from_millis(200));
+    assert_eq!(t.height.unwrap().easing, Easing::EaseOut);
+    let top = t.top.unwrap();
+    assert_eq!(
+        (top.duration, top.delay),
+        (Duration::from_millis(500), Duration::from_millis(50))
+    );
+    assert_eq!(top.easing, Easing::CubicBezier(0.2, 0.0, 0.0, 1.0));
+    assert!(t.left.is_none() && t.width.is_none());
+    assert!(parse_transition("all 1s").width.is_some());
+    assert!((Easing::EaseInOut.apply(0.5) - 0.5).abs() < 1e-3);
+}
+
+#[test]
+fn height_change_interpolates_and_settles() {
+    let mut animator = LayoutAnimator::new();
+    let t0 = Instant::now();
+    let (_, first) = rects(&panel(100), &mut animator, t0);
+    assert_eq!(first[0].1, 100);
+
+    let tall = panel(200);
+    let (running, at_start) = rects(&tall, &mut animator, t0);
+    assert!(running);
+    assert_eq!(at_start[0].1, 100);
+    let (_, halfway) = rects(&tall, &mut animator, t0 + Duration::from_millis(50));
+    assert_eq!(halfway[0].1, 150);
+    let (running, done) = rects(&tall, &mut animator, t0 + Duration::from_millis(100));
+    assert!(!running);
+    assert_eq!(done[0].1, 200);
+    // Siblings without a transition take their final position immediately.
+    assert_eq!(halfway[1].0, done[1].0);
+}
+
+#[test]
+fn keyed_children_slide_to_new_positions_with_their_descendants() {
+    let row = |key: &str| {
+        h(
+            "div",
+            vec![
+                ("key", key),
+                ("style", "transition: top 100ms linear; height: 20px"),
+            ],
+            vec![text(key)],
+        )
+    };
+    let mut animator = LayoutAnimator::new();
+    let t0 = Instant::now();
+    let before = h("div", vec![], vec![row("a"), row("b")]);
+    let after = h("div", vec![], vec![row("b"), row("a")]);
+    let mut layout = compute_layout(&before, 200, 200);
+    animator.animate(&before, &mut layout, t0);
+    let (ya, yb) = (layout.children[0].rect.y, layout.children[1].rect.y);
+
+    let mut layout = compute_layout(&after, 200, 200);
+    assert!(animator.animate(&after, &mut layout, t0));
+    assert_eq!(layout.children[0].rect.y, yb);
+
+    let mut layout = compute_layout(&after, 200, 200);
+    animator.animate(&after, &mut layout, t0 + Duration::from_millis(50));
+    let b = &layout.children[0];
+    assert_eq!(b.rect.y, (ya + yb) / 2);
+    assert_eq!(b.children[0].rect.y, b.rect.y);
+}
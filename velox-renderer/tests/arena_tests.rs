@@ -0,0 +1,61 @@
+use velox_dom::layout::compute_layout;
+use velox_dom::{VNode, h, text};
+use velox_renderer::arena::{FrameArena, with_frame_arena};
+use velox_renderer::display_list::{ApproxMeasure, build_display_list};
+
+fn page() -> VNode {
+    let items = (0..50)
+        .map(|i| {
+            h(
+                "div",
+                vec![(
+                    "style",
+                    if i % 3 == 0 {
+                        "background-color:#EEEEEE;z-index:1"
+                    } else {
+                        "border:1px solid #333333"
+                    },
+                )],
+                vec![text(format!("item {} with a few words that wrap", i))],
+            )
+        })
+        .collect();
+    h("div", vec![("style", "width:120px")], items)
+}
+
+#[test]
+fn arena_matches_owned_build_and_stops_growing() {
+    let vnode = page();
+    let layout = compute_layout(&vnode, 120, 2000);
+    let expected = build_display_list(&vnode, &layout, &mut ApproxMeasure);
+
+    let mut arena = FrameArena::new();
+    let frame = |arena: &mut FrameArena| {
+        arena.reset();
+        assert_eq!(
+            *arena.build_display_list(&vnode, &layout, &mut ApproxMeasure),
+            expected
+        );
+    };
+    // The bump allocator settles on one chunk big enough for a whole frame.
+    for _ in 0..4 {
+        frame(&mut arena);
+    }
+    let (bytes, capacity) = (arena.allocated_bytes(), arena.command_capacity());
+    for _ in 0..4 {
+        frame(&mut arena);
+    }
+    assert_eq!(arena.allocated_bytes(), bytes);
+    assert_eq!(arena.command_capacity(), capacity);
+    assert_eq!(arena.frames(), 8);
+}
+
+#[test]
+fn nested_frame_arena_use_gets_a_fresh_arena() {
+    let outer = with_frame_arena(|outer| {
+        let inner = with_frame_arena(|inner| inner.frames());
+        (outer.frames(), inner)
+    });
+    assert!(outer.0 >= 1);
+    assert_eq!(outer.1, 0);
+}
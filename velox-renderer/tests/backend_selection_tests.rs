@@ -0,0 +1,44 @@
+use velox_renderer::config::{Backend, RendererConfig, parse_window_size, select_backend_with};
+
+#[test]
+fn auto_selects_stub_when_nothing_is_available() {
+    let cfg = RendererConfig::new(Backend::Auto);
+    assert_eq!(select_backend_with(&cfg, |_| false), Backend::Stub);
+}
+
+#[test]
+fn requested_backend_falls_back_down_the_chain() {
+    // Raster is only compiled with skia-native; without it the chain ends at the stub.
+    let cfg = RendererConfig::new(Backend::Wgpu);
+    let expected = if Backend::Raster.is_compiled() {
+        Backend::Raster
+    } else {
+        Backend::Stub
+    };
+    assert_eq!(
+        select_backend_with(&cfg, |b| b == Backend::Raster),
+        expected
+    );
+}
+
+#[test]
+fn backend_names_round_trip() {
+    for b in [
+        Backend::Auto,
+        Backend::Wgpu,
+        Backend::Skia,
+        Backend::Raster,
+        Backend::Stub,
+    ] {
+        assert_eq!(Backend::from_name(b.name()), Some(b));
+    }
+    assert_eq!(Backend::from_name("vulkan"), None);
+}
+
+#[test]
+fn window_size_parses_width_by_height() {
+    assert_eq!(parse_window_size("1024x768"), Some((1024, 768)));
+    assert_eq!(parse_window_size(" 640 X 480 "), Some((640, 480)));
+    assert_eq!(parse_window_size("0x480"), None);
+    assert_eq!(parse_window_size("wide"), None);
+}
@@ -0,0 +1,33 @@
+use velox_dom::{VNode, h};
+use velox_renderer::capabilities::{capabilities_for, strip_unsupported};
+use velox_renderer::{Backend, Renderer};
+
+#[test]
+fn selected_renderer_reports_capabilities() {
+    let r = velox_renderer::new_selected_renderer();
+    let caps = r.capabilities();
+    if r.backend_name() == "stub" {
+        assert_eq!(caps, velox_renderer::Capabilities::default());
+    }
+    assert!(capabilities_for(Backend::Skia).filters);
+    assert!(!capabilities_for(Backend::Wgpu).filters);
+}
+
+#[test]
+fn unsupported_declarations_are_stripped() {
+    let vnode = h(
+        "div",
+        vec![(
+            "style",
+            "background-color:#FF0000; filter: blur(4px); width:10px",
+        )],
+        vec![],
+    );
+    let out = strip_unsupported(&vnode, &capabilities_for(Backend::Wgpu));
+    let VNode::Element { props, .. } = out else {
+        panic!("element expected")
+    };
+    let style = props.str("style").unwrap();
+    assert!(!style.contains("filter"));
+    assert!(style.contains("background-color:#FF0000"));
+}
@@ -0,0 +1,107 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use velox_dom::{Props, VNode, h, text};
+use velox_renderer::testing::TestApp;
+use velox_style::Stylesheet;
+
+#[test]
+fn presses_outside_an_element_dispatch_its_handler() {
+    let vnode = h(
+        "div",
+        Props::new().set("style", "width:200px;height:100px"),
+        vec![h(
+            "div",
+            Props::new()
+                .set("on:click-outside", "close")
+                .set("style", "width:60px;height:30px"),
+            vec![],
+        )],
+    );
+    let layout = velox_dom::layout::compute_layout(&vnode, 200, 100);
+    let mut targets = Vec::new();
+    velox_renderer::events::collect_click_outside_targets(&vnode, &layout, &mut targets);
+
+    assert_eq!(
+        velox_renderer::events::hit_test_click_outside(&targets, 10.0, 10.0),
+        vec![]
+    );
+    assert_eq!(
+        velox_renderer::events::hit_test_click_outside(&targets, 150.0, 80.0),
+        vec![("close", None)]
+    );
+}
+
+#[test]
+fn dropdown_closes_on_a_click_elsewhere_but_not_inside() {
+    let open = Rc::new(RefCell::new(true));
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let view = {
+        let open = open.clone();
+        move |_w: u32, _h: u32| -> (VNode, Stylesheet) {
+            let mut menu = vec![h(
+                "button",
+                Props::new()
+                    .set("class", "toggle")
+                    .set("on:click", "toggle"),
+                vec![text("Menu")],
+            )];
+            if *open.borrow() {
+                menu.push(h(
+                    "button",
+                    Props::new().set("class", "item").set("on:click", "pick"),
+                    vec![text("Item")],
+                ));
+            }
+            let root = h(
+                "div",
+                Props::new().set("class", "app"),
+                vec![
+                    h(
+                        "div",
+                        Props::new()
+                            .set("class", "dropdown")
+                            .set("on:click-outside", "close"),
+                        menu,
+                    ),
+                    h(
+                        "button",
+                        Props::new().set("class", "other").set("on:click", "other"),
+                        vec![text("Other")],
+                    ),
+                ],
+            );
+            (
+                root,
+                Stylesheet::parse("button { width: 80px; height: 30px; }"),
+            )
+        }
+    };
+    let on_event = {
+        let (open, events) = (open.clone(), events.clone());
+        move |name: &str, _payload: Option<&str>| {
+            events.borrow_mut().push(name.to_string());
+            match name {
+                "toggle" => open.replace_with(|o| !*o),
+                "close" => open.replace(false),
+                _ => false,
+            };
+        }
+    };
+    let mut app = TestApp::new(view, on_event);
+
+    app.click(".dropdown .item");
+    assert_eq!(*events.borrow(), ["pick"]);
+    assert!(app.find(".item").is_some());
+
+    app.click(".other");
+    assert_eq!(
+        *events.borrow(),
+        ["pick", "close", "other"],
+        "click-outside runs before the click under the press"
+    );
+    assert!(app.find(".item").is_none());
+
+    app.click(".toggle");
+    assert!(app.find(".item").is_some());
+}
@@ -0,0 +1,48 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use velox_dom::component::Registry;
+use velox_dom::{Props, VNode, component, h, text};
+use velox_renderer::components;
+use velox_renderer::testing::TestApp;
+use velox_style::Stylesheet;
+
+#[test]
+fn views_render_registered_components() {
+    let mut registry = Registry::new();
+    registry.register("TodoItem", |props: &Props, _: &[VNode]| {
+        let item = props.str("item").unwrap_or_default().to_string();
+        let props = Props::new()
+            .set("id", item.as_str())
+            .set("class", "todo")
+            .set("on:click", format!("done:{}", item));
+        h("li", props, vec![text(item)])
+    });
+    components::set_registry(registry);
+
+    let done = Rc::new(Cell::new(false));
+    let mut app = TestApp::new(
+        |_w, _h| {
+            let items = ["Milk", "Eggs"]
+                .iter()
+                .map(|i| component("TodoItem", Props::new().set("item", *i), vec![]))
+                .collect();
+            (
+                h("ul", (), items),
+                Stylesheet::parse(".todo { height: 20px; }"),
+            )
+        },
+        {
+            let done = done.clone();
+            move |name: &str, _: Option<&str>| done.set(name == "done:Eggs")
+        },
+    );
+    assert_eq!(app.find_all(".todo").len(), 2);
+    assert_eq!(app.text_of("ul"), "Milk Eggs");
+    app.click("#Eggs");
+    assert!(done.get());
+
+    components::clear_resolver();
+    app.render();
+    assert!(app.find_all(".todo").is_empty());
+}
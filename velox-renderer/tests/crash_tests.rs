@@ -0,0 +1,39 @@
+use velox_dom::{h, text};
+use velox_renderer::crash::{self, EVENT_HISTORY};
+
+#[test]
+fn a_panic_writes_the_tree_and_recent_events() {
+    let dir = std::env::temp_dir().join(format!("velox-crash-test-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    crash::install_crash_reporter(&dir);
+
+    for i in 0..EVENT_HISTORY + 5 {
+        crash::record_event("increment", Some(&i.to_string()));
+    }
+    crash::record_event("save", None);
+    assert_eq!(crash::recent_events().len(), EVENT_HISTORY);
+    assert_eq!(crash::recent_events()[0].payload.as_deref(), Some("6"));
+    crash::record_tree(&h(
+        "div",
+        vec![("class", "counter"), ("id", "root")],
+        vec![text("Count: 3")],
+    ));
+
+    let caught = std::panic::catch_unwind(|| panic!("counter overflowed"));
+    assert!(caught.is_err());
+
+    let reports: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(reports.len(), 1);
+    let report = std::fs::read_to_string(reports[0].join("report.txt")).unwrap();
+    assert!(report.contains("panic: counter overflowed"), "{}", report);
+    assert!(report.contains("ms save\n"), "{}", report);
+    let tree = std::fs::read_to_string(reports[0].join("tree.txt")).unwrap();
+    assert_eq!(
+        tree,
+        "<div class=\"counter\" id=\"root\">\n  \"Count: 3\"\n"
+    );
+    let _ = std::fs::remove_dir_all(&dir);
+}
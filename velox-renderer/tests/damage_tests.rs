@@ -0,0 +1,66 @@
+use velox_renderer::damage::{TILE, frame_damage};
+use velox_renderer::pixels::PixelRect;
+
+fn frame(width: u32, height: u32) -> Vec<u8> {
+    vec![255; (width * height * 4) as usize]
+}
+
+fn paint(data: &mut [u8], width: u32, x: u32, y: u32) {
+    let i = ((y * width + x) * 4) as usize;
+    data[i] = 0;
+}
+
+#[test]
+fn small_changes_damage_only_their_tiles() {
+    let (w, h) = (TILE * 4, TILE * 4);
+    let prev = frame(w, h);
+    assert_eq!(
+        frame_damage(None, &prev, w, h),
+        vec![PixelRect::new(0, 0, w, h)],
+        "first frame is full"
+    );
+    assert!(
+        frame_damage(Some(&prev), &prev, w, h).is_empty(),
+        "identical frame has no damage"
+    );
+
+    // A caret spanning two tile rows in the second column.
+    let mut next = prev.clone();
+    paint(&mut next, w, TILE + 3, TILE - 1);
+    paint(&mut next, w, TILE + 3, TILE);
+    assert_eq!(
+        frame_damage(Some(&prev), &next, w, h),
+        vec![PixelRect::new(TILE, 0, TILE, TILE * 2)]
+    );
+
+    // Two separate spots stay separate.
+    let mut next = prev.clone();
+    paint(&mut next, w, 0, 0);
+    paint(&mut next, w, w - 1, h - 1);
+    assert_eq!(
+        frame_damage(Some(&prev), &next, w, h),
+        vec![
+            PixelRect::new(0, 0, TILE, TILE),
+            PixelRect::new(TILE * 3, TILE * 3, TILE, TILE)
+        ]
+    );
+}
+
+#[test]
+fn large_changes_and_resizes_damage_the_whole_frame() {
+    let (w, h) = (100, 70);
+    let prev = frame(w, h);
+    let mut next = prev.clone();
+    for y in 0..h {
+        paint(&mut next, w, 0, y);
+        paint(&mut next, w, w - 1, y);
+    }
+    assert_eq!(
+        frame_damage(Some(&prev), &next, w, h),
+        vec![PixelRect::new(0, 0, w, h)]
+    );
+    assert_eq!(
+        frame_damage(Some(&frame(10, 10)), &prev, w, h),
+        vec![PixelRect::new(0, 0, w, h)]
+    );
+}
@@ -0,0 +1,70 @@
+use velox_dom::h;
+use velox_dom::layout::compute_layout;
+use velox_renderer::devtools::{
+    self, BASELINE_COLOR, BORDER_COLOR, CONTENT_COLOR, MARGIN_COLOR, Overlays,
+};
+use velox_renderer::display_list::{ApproxMeasure, RectF, RenderCommand, build_display_list};
+
+fn outline(commands: &[RenderCommand], color: velox_renderer::display_list::Color) -> Vec<RectF> {
+    commands
+        .iter()
+        .filter_map(|c| match c {
+            RenderCommand::Border { rect, color: c, .. } if *c == color => Some(*rect),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn box_and_baseline_overlays() {
+    let vnode = h(
+        "div",
+        vec![],
+        vec![h(
+            "div",
+            vec![("style", "margin: 10px; padding: 5px; width: 100px")],
+            vec![velox_dom::text("Hi")],
+        )],
+    );
+    let layout = compute_layout(&vnode, 200, 200);
+
+    devtools::set_overlays(Overlays::default());
+    let plain = build_display_list(&vnode, &layout, &mut ApproxMeasure);
+    assert!(outline(&plain.commands, BORDER_COLOR).is_empty());
+
+    devtools::set_overlays(Overlays {
+        boxes: true,
+        baselines: true,
+    });
+    let list = build_display_list(&vnode, &layout, &mut ApproxMeasure);
+    assert_eq!(
+        outline(&list.commands, MARGIN_COLOR),
+        vec![RectF::new(0.0, 0.0, 120.0, 46.0)]
+    );
+    assert_eq!(
+        outline(&list.commands, BORDER_COLOR)[1],
+        RectF::new(10.0, 10.0, 100.0, 26.0)
+    );
+    assert_eq!(
+        outline(&list.commands, CONTENT_COLOR),
+        vec![RectF::new(15.0, 15.0, 90.0, 16.0)]
+    );
+
+    let text_at = list
+        .commands
+        .iter()
+        .position(|c| matches!(c, RenderCommand::Text { .. }))
+        .unwrap();
+    let RenderCommand::Text { baseline, .. } = list.commands[text_at] else {
+        unreachable!()
+    };
+    match &list.commands[text_at + 1] {
+        RenderCommand::Rect { rect, color } => {
+            assert_eq!(*color, BASELINE_COLOR);
+            assert_eq!(rect.y, baseline);
+            assert!(rect.w > 0.0);
+        }
+        other => panic!("expected a baseline marker, got {:?}", other),
+    }
+    devtools::set_overlays(Overlays::default());
+}
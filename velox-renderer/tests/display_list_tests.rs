@@ -0,0 +1,88 @@
+use velox_dom::layout::compute_layout;
+use velox_dom::{h, text};
+use velox_renderer::display_list::{ApproxMeasure, Color, RenderCommand, build_display_list};
+
+#[test]
+fn display_list_orders_by_z_index_and_balances_clips() {
+    let vnode = h(
+        "div",
+        vec![(
+            "style",
+            "background-color:#FFFFFF;overflow:hidden;width:64px;height:64px",
+        )],
+        vec![
+            h(
+                "div",
+                vec![(
+                    "style",
+                    "background-color:#FF0000;width:40px;height:40px;z-index:1",
+                )],
+                vec![],
+            ),
+            h(
+                "div",
+                vec![("style", "background-color:#0000FF;width:40px;height:40px")],
+                vec![],
+            ),
+        ],
+    );
+    let layout = compute_layout(&vnode, 64, 64);
+    let list = build_display_list(&vnode, &layout, &mut ApproxMeasure);
+
+    let fills: Vec<Color> = list
+        .commands
+        .iter()
+        .filter_map(|c| match c {
+            RenderCommand::Rect { color, .. } => Some(*color),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        fills,
+        vec![
+            Color::WHITE,
+            Color::rgba(0, 0, 255, 255),
+            Color::rgba(255, 0, 0, 255)
+        ]
+    );
+    assert!(matches!(list.commands[1], RenderCommand::PushClip { .. }));
+    assert_eq!(list.commands.last(), Some(&RenderCommand::PopClip));
+}
+
+#[test]
+fn display_list_text_carries_inherited_style_and_opacity() {
+    let vnode = h(
+        "div",
+        vec![(
+            "style",
+            "color:#102030;font-size:20px;opacity:0.5;text-decoration:underline",
+        )],
+        vec![h("span", vec![], vec![text("hi")])],
+    );
+    let layout = compute_layout(&vnode, 200, 100);
+    let list = build_display_list(&vnode, &layout, &mut ApproxMeasure);
+
+    let text = list
+        .commands
+        .iter()
+        .find_map(|c| match c {
+            RenderCommand::Text {
+                text,
+                size,
+                color,
+                baseline,
+                ..
+            } => Some((text.clone(), *size, *color, *baseline)),
+            _ => None,
+        })
+        .expect("text command");
+    assert_eq!(text.0, "hi");
+    assert_eq!(text.1, 20.0);
+    assert_eq!(text.2, Color::rgba(0x10, 0x20, 0x30, 128));
+    // Underline is emitted as a plain rect just below the baseline.
+    assert!(
+        list.commands
+            .iter()
+            .any(|c| matches!(c, RenderCommand::Rect { rect, .. } if rect.y > text.3))
+    );
+}
@@ -38,7 +38,7 @@ fn end_to_end_reactive_updates_and_mount() {
     // Initial tree should reflect 0 and carry style
     if let VNode::Element { props, children, .. } = &*current.borrow() {
         assert_eq!(props.attrs.get("class").unwrap(), "app");
-        assert!(props.attrs.get("style").unwrap().contains("color: red;"));
+        assert!(props.str("style").unwrap().contains("color: red;"));
         assert!(matches!(children[0], VNode::Text(_)));
     } else { panic!("expected element"); }
 
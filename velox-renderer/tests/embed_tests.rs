@@ -0,0 +1,213 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use velox_dom::{Props, VNode, h, text};
+use velox_renderer::VeloxUi;
+use velox_renderer::display_list::{Color, DisplayList, RectF, RenderCommand};
+use velox_renderer::embed::tessellate;
+use velox_renderer::replay::{self, Input};
+use velox_style::Stylesheet;
+
+const FRAME: Duration = Duration::from_millis(16);
+
+#[test]
+fn host_forwarded_presses_reach_the_view_and_rebuild_it() {
+    let count = Rc::new(RefCell::new(0));
+    let view = {
+        let count = count.clone();
+        move |_w: u32, _h: u32| -> (VNode, Stylesheet) {
+            let label = format!("Count {}", count.borrow());
+            let root = h(
+                "div",
+                Props::new(),
+                vec![h(
+                    "button",
+                    Props::new().set("on:click", "inc"),
+                    vec![text(&label)],
+                )],
+            );
+            (
+                root,
+                Stylesheet::parse("button { width: 100px; height: 40px; }"),
+            )
+        }
+    };
+    let on_event = {
+        let count = count.clone();
+        move |name: &str, _payload: Option<&str>| {
+            if name == "inc" {
+                *count.borrow_mut() += 1;
+            }
+        }
+    };
+    let mut ui = VeloxUi::new(view, on_event);
+    // A 2x display: the 100x40 logical button covers 200x80 physical pixels.
+    ui.resize(800, 600, 2.0);
+    assert!(ui.update(FRAME), "the first update builds the view");
+    assert!(!ui.update(FRAME), "nothing changed since");
+
+    assert!(
+        !ui.pointer_moved(500.0, 500.0),
+        "the host keeps input away from the view"
+    );
+    assert!(!ui.pointer_pressed());
+    assert_eq!(*count.borrow(), 0);
+
+    assert!(ui.pointer_moved(150.0, 60.0));
+    assert!(ui.pointer_pressed());
+    assert_eq!(*count.borrow(), 1);
+    assert!(ui.update(FRAME));
+    let label = ui.display_list().commands.iter().find_map(|c| match c {
+        RenderCommand::Text { text, .. } => Some(text.clone()),
+        _ => None,
+    });
+    assert_eq!(label.as_deref(), Some("Count 1"));
+}
+
+#[test]
+fn host_forwarded_presses_are_recorded_like_window_input() {
+    let view = |_w: u32, _h: u32| -> (VNode, Stylesheet) {
+        let root = h(
+            "div",
+            Props::new(),
+            vec![h(
+                "button",
+                Props::new().set("on:click", "inc"),
+                vec![text("+")],
+            )],
+        );
+        (
+            root,
+            Stylesheet::parse("button { width: 100px; height: 40px; }"),
+        )
+    };
+    let mut ui = VeloxUi::new(view, |_: &str, _: Option<&str>| {});
+    ui.resize(800, 600, 2.0);
+    ui.update(FRAME);
+
+    replay::start_recording();
+    ui.pointer_moved(150.0, 60.0);
+    assert!(ui.pointer_pressed());
+    let script = replay::stop_recording();
+    let inputs: Vec<_> = script.steps.into_iter().map(|step| step.input).collect();
+    // Positions are logical, as a window records them.
+    assert_eq!(
+        inputs,
+        vec![Input::Click {
+            x: 75.0,
+            y: 30.0,
+            target: Some("inc".into())
+        }]
+    );
+}
+
+#[test]
+fn the_first_update_mounts_the_view_and_dropping_the_ui_unmounts_it() {
+    use velox_core::lifecycle::{on_mounted, on_unmounted, on_updated};
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let (l1, l2, l3) = (log.clone(), log.clone(), log.clone());
+    on_mounted(move || l1.borrow_mut().push("mounted"));
+    on_updated(move || l2.borrow_mut().push("updated"));
+    on_unmounted(move || l3.borrow_mut().push("unmounted"));
+
+    let count = Rc::new(RefCell::new(0));
+    let view = {
+        let count = count.clone();
+        move |_w: u32, _h: u32| -> (VNode, Stylesheet) {
+            let label = format!("Count {}", count.borrow());
+            let root = h(
+                "div",
+                Props::new(),
+                vec![h(
+                    "button",
+                    Props::new().set("on:click", "inc"),
+                    vec![text(&label)],
+                )],
+            );
+            (
+                root,
+                Stylesheet::parse("button { width: 100px; height: 40px; }"),
+            )
+        }
+    };
+    let on_event = {
+        let count = count.clone();
+        move |_: &str, _: Option<&str>| *count.borrow_mut() += 1
+    };
+    let mut ui = VeloxUi::new(view, on_event);
+    ui.resize(800, 600, 1.0);
+    assert!(
+        log.borrow().is_empty(),
+        "nothing is mounted before the first frame"
+    );
+    ui.update(FRAME);
+    ui.update(FRAME);
+    assert_eq!(*log.borrow(), vec!["mounted"]);
+
+    ui.pointer_moved(50.0, 20.0);
+    ui.pointer_pressed();
+    ui.update(FRAME);
+    assert_eq!(*log.borrow(), vec!["mounted", "updated"]);
+    drop(ui);
+    assert_eq!(*log.borrow(), vec!["mounted", "updated", "unmounted"]);
+}
+
+#[test]
+fn dropping_a_view_unmounts_only_the_hooks_it_mounted_with() {
+    use velox_core::lifecycle::on_unmounted;
+
+    let view = |_w: u32, _h: u32| -> (VNode, Stylesheet) {
+        (h("div", Props::new(), vec![]), Stylesheet::default())
+    };
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let l1 = log.clone();
+    on_unmounted(move || l1.borrow_mut().push("first"));
+    let mut first = VeloxUi::new(view, |_: &str, _: Option<&str>| {});
+    first.update(FRAME);
+    let l2 = log.clone();
+    on_unmounted(move || l2.borrow_mut().push("second"));
+    let mut second = VeloxUi::new(view, |_: &str, _: Option<&str>| {});
+    second.update(FRAME);
+
+    drop(second);
+    assert_eq!(*log.borrow(), vec!["second"]);
+    drop(first);
+    assert_eq!(*log.borrow(), vec!["second", "first"]);
+}
+
+#[test]
+fn tessellation_scales_clips_and_keeps_alpha() {
+    let list = DisplayList {
+        width: 100.0,
+        height: 100.0,
+        commands: vec![
+            RenderCommand::PushClip {
+                rect: RectF::new(0.0, 0.0, 50.0, 100.0),
+                radius: 0.0,
+            },
+            RenderCommand::Rect {
+                rect: RectF::new(25.0, 0.0, 50.0, 50.0),
+                color: Color::rgba(255, 0, 0, 128),
+            },
+            RenderCommand::PopClip,
+            RenderCommand::Rect {
+                rect: RectF::new(0.0, 0.0, 10.0, 10.0),
+                color: Color::rgba(0, 0, 0, 0),
+            },
+        ],
+    };
+    let mesh = tessellate(&list, 200, 200, 2.0);
+
+    assert_eq!(mesh.vertices.len(), 6, "transparent fills are skipped");
+    let xs: Vec<f32> = mesh.vertices.iter().map(|v| v.pos[0]).collect();
+    assert_eq!(xs.iter().cloned().fold(f32::INFINITY, f32::min), -0.5);
+    assert_eq!(
+        xs.iter().cloned().fold(f32::NEG_INFINITY, f32::max),
+        0.0,
+        "clipped at x = 50 logical, 100 physical"
+    );
+    assert_eq!(mesh.vertices[0].pos[1], 1.0);
+    assert_eq!(mesh.vertices[0].color, [1.0, 0.0, 0.0, 128.0 / 255.0]);
+}
@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use velox_dom::{VNode, h, text};
+use velox_renderer::enter_leave::{EnterLeave, interpolate};
+use velox_style::{Stylesheet, apply_styles};
+
+const CSS: &str = ".fade-enter-active, .fade-leave-active { transition: opacity 100ms linear; }
+.fade-enter-from, .fade-leave-to { opacity: 0; }
+p { opacity: 1; }";
+
+fn view(open: bool) -> VNode {
+    let body = if open {
+        h("p", vec![("data-transition", "fade")], vec![text("hi")])
+    } else {
+        text("")
+    };
+    h("div", vec![], vec![body])
+}
+
+/// Run one frame and return the first child's class and style, if it is an element.
+fn frame(
+    el: &mut EnterLeave,
+    sheet: &Stylesheet,
+    open: bool,
+    now: Instant,
+) -> (bool, Option<(String, String)>) {
+    let mut raw = view(open);
+    el.prepare(&mut raw, now);
+    let mut styled = apply_styles(&raw, sheet);
+    let running = el.apply(&mut styled, sheet, now);
+    let VNode::Element { children, .. } = &styled else {
+        unreachable!()
+    };
+    let child = match &children[0] {
+        VNode::Element { props, .. } => Some((
+            props.str("class").unwrap_or_default().to_string(),
+            props.attrs["style"].to_string(),
+        )),
+        _ => None,
+    };
+    (running, child)
+}
+
+#[test]
+fn enter_fades_in_from_enter_from_rule() {
+    let sheet = Stylesheet::parse(CSS);
+    let mut el = EnterLeave::new();
+    let t0 = Instant::now();
+    assert_eq!(frame(&mut el, &sheet, false, t0), (false, None));
+    let (running, child) = frame(&mut el, &sheet, true, t0 + Duration::from_millis(10));
+    let (class, style) = child.unwrap();
+    assert!(running);
+    assert_eq!(class, "fade-enter-active");
+    assert!(style.contains("opacity: 0;"), "{}", style);
+    let (_, child) = frame(&mut el, &sheet, true, t0 + Duration::from_millis(60));
+    assert!(child.unwrap().1.contains("opacity: 0.5;"));
+    let (running, child) = frame(&mut el, &sheet, true, t0 + Duration::from_millis(200));
+    assert!(!running);
+    assert!(child.unwrap().1.contains("opacity: 1;"));
+}
+
+#[test]
+fn leave_keeps_element_until_finished() {
+    let sheet = Stylesheet::parse(CSS);
+    let mut el = EnterLeave::new();
+    let t0 = Instant::now();
+    frame(&mut el, &sheet, true, t0);
+    let (running, child) = frame(&mut el, &sheet, false, t0 + Duration::from_millis(10));
+    let (class, _) = child.unwrap();
+    assert!(running);
+    assert_eq!(class, "fade-leave-active");
+    let (_, child) = frame(&mut el, &sheet, false, t0 + Duration::from_millis(60));
+    assert!(child.unwrap().1.contains("opacity: 0.5;"));
+    frame(&mut el, &sheet, false, t0 + Duration::from_millis(200));
+    assert_eq!(
+        frame(&mut el, &sheet, false, t0 + Duration::from_millis(210)),
+        (false, None)
+    );
+}
+
+#[test]
+fn interpolates_numbers_colors_and_transforms() {
+    assert_eq!(interpolate("0px", "10px", 0.5), "5px");
+    assert_eq!(interpolate("#000000", "#ffffff", 0.5), "#808080ff");
+    assert_eq!(
+        interpolate("none", "translateY(20px)", 0.5),
+        "translateY(10px)"
+    );
+    assert_eq!(interpolate("left", "right", 0.25), "left");
+}
@@ -0,0 +1,28 @@
+use velox_renderer::Error;
+
+#[cfg(feature = "raster")]
+#[test]
+fn oversized_raster_target_reports_invalid_size() {
+    let vnode = velox_dom::h("div", vec![], vec![]);
+    let err = velox_renderer::raster::render_to_pixmap(&vnode, 100_000, 100_000, 1.0).unwrap_err();
+    assert!(
+        matches!(
+            err,
+            Error::InvalidSize {
+                width: 100_000,
+                height: 100_000
+            }
+        ),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn io_errors_convert_and_keep_their_source() {
+    let err: Error = std::io::Error::new(std::io::ErrorKind::NotFound, "missing.png").into();
+    assert_eq!(err.to_string(), "I/O error: missing.png");
+    assert!(std::error::Error::source(&err).is_some());
+    let boxed: Box<dyn std::error::Error> = Error::AdapterNotFound.into();
+    assert_eq!(boxed.to_string(), "no suitable GPU adapter found");
+}
@@ -39,3 +39,49 @@ fn hit_test_click_targets() {
     let hit_none = velox_renderer::events::hit_test_click(&targets, 200.0, 200.0);
     assert_eq!(hit_none, None);
 }
+
+#[test]
+fn submit_button_dispatches_form_submit() {
+    let vnode = h(
+        "form",
+        vec![("on:submit", "save")],
+        vec![
+            h("button", vec![("type", "submit"), ("style", "width:60px;height:30px")], vec![]),
+            h("button", vec![("style", "width:60px;height:30px")], vec![]),
+        ],
+    );
+    let layout = velox_dom::layout::compute_layout(&vnode, 120, 80);
+    let mut targets = Vec::new();
+    velox_renderer::events::collect_click_targets(&vnode, &layout, &mut targets);
+
+    assert_eq!(velox_renderer::events::hit_test_click(&targets, 10.0, 10.0), Some(("save", None)));
+    assert_eq!(velox_renderer::events::hit_test_click(&targets, 10.0, 45.0), None);
+}
+
+#[test]
+fn hit_grid_agrees_with_a_linear_scan() {
+    use velox_dom::layout::Rect;
+    use velox_renderer::events::{hit_test_hover, HitGrid, HoverTarget};
+
+    let mut rects: Vec<Rect> =
+        (0..400).map(|i| Rect { x: (i % 20) * 30, y: (i / 20) * 25, w: 40 + (i % 3) * 30, h: 20 }).collect();
+    rects.push(Rect { x: -50, y: -50, w: 900, h: 900 });
+    let mut grid = HitGrid::new(&rects);
+    let check = |grid: &HitGrid, rects: &[Rect]| {
+        let targets: Vec<HoverTarget> = rects.iter().enumerate().map(|(i, &rect)| HoverTarget { rect, id: i as u32 }).collect();
+        for y in (-60..560).step_by(7) {
+            for x in (-60..660).step_by(11) {
+                let (x, y) = (x as f32 + 0.5, y as f32);
+                assert_eq!(grid.hit(x, y).map(|i| i as u32), hit_test_hover(&targets, x, y), "at {},{}", x, y);
+            }
+        }
+    };
+    check(&grid, &rects);
+
+    // A patch moved a few targets and removed the last ones.
+    rects[3].x += 200;
+    rects[150].y = 0;
+    rects.truncate(390);
+    assert_eq!(grid.sync(&rects), 2 + 11);
+    check(&grid, &rects);
+}
@@ -0,0 +1,117 @@
+use velox_dom::{Props, VNode, h};
+use velox_renderer::events::{HandlerTable, Targets};
+use velox_renderer::gesture::{Gesture, Gestures, dispatch};
+use velox_renderer::pen::PenPhase;
+
+fn map() -> VNode {
+    let surface = h(
+        "div",
+        Props::new()
+            .set("on:zoom", "zoom")
+            .set("on:rotate", "turn")
+            .set("style", "width: 200px; height: 200px; margin-left: 20px"),
+        vec![],
+    );
+    h("div", Props::new(), vec![surface])
+}
+
+fn targets() -> Targets {
+    let vnode = velox_style::apply_styles(&map(), &velox_style::Stylesheet::default());
+    let layout = velox_renderer::pipeline::layout_tree(&vnode, 400, 300);
+    let mut targets = Targets::default();
+    targets.collect(&vnode, &layout, &HandlerTable::from_tree(&vnode));
+    targets
+}
+
+#[test]
+fn two_fingers_pinch_and_turn_the_element_under_them() {
+    let targets = targets();
+    let rect = targets.zoom[0].rect;
+    let (cx, cy) = (rect.x as f32 + 100.0, rect.y as f32 + 100.0);
+
+    let mut gestures = Gestures::new();
+    let mut got = Vec::new();
+    let mut on_event = |handler: &str, payload: Option<&str>| {
+        got.push((handler.to_string(), payload.unwrap().to_string()))
+    };
+    assert!(!gestures.touch(1, PenPhase::Down, cx - 20.0, cy, &targets, &mut on_event));
+    // One finger alone is not a gesture.
+    assert!(!gestures.touch(1, PenPhase::Move, cx - 30.0, cy, &targets, &mut on_event));
+    assert!(!gestures.touch(2, PenPhase::Down, cx + 30.0, cy, &targets, &mut on_event));
+    assert!(gestures.is_active());
+    // Spread apart: 60px to 120px, no turn.
+    assert!(gestures.touch(2, PenPhase::Move, cx + 90.0, cy, &targets, &mut on_event));
+    // A quarter turn clockwise about the first finger.
+    assert!(gestures.touch(
+        2,
+        PenPhase::Move,
+        cx - 30.0,
+        cy + 120.0,
+        &targets,
+        &mut on_event
+    ));
+    gestures.touch(1, PenPhase::Up, cx - 30.0, cy, &targets, &mut on_event);
+    assert!(!gestures.is_active());
+    assert!(!gestures.touch(2, PenPhase::Move, cx, cy, &targets, &mut on_event));
+
+    assert_eq!(
+        got[0],
+        (
+            "zoom".to_string(),
+            r#"{"scale":2,"x":130,"y":100}"#.to_string()
+        )
+    );
+    let turn = got
+        .iter()
+        .find(|(h, _)| h == "turn")
+        .expect("a rotate event");
+    let angle: f32 = turn
+        .1
+        .split("\"angle\":")
+        .nth(1)
+        .unwrap()
+        .split(',')
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(
+        (angle - std::f32::consts::FRAC_PI_2).abs() < 1e-4,
+        "{}",
+        turn.1
+    );
+}
+
+#[test]
+fn trackpad_gestures_go_to_the_element_at_the_pointer() {
+    let targets = targets();
+    let rect = targets.rotate[0].rect;
+    let mut got = Vec::new();
+    let mut on_event = |handler: &str, payload: Option<&str>| {
+        got.push((handler.to_string(), payload.unwrap().to_string()))
+    };
+    let (x, y) = (rect.x as f32 + 10.0, rect.y as f32 + 20.0);
+    assert!(dispatch(
+        Gesture::magnify(0.5, x, y),
+        &targets,
+        &mut on_event
+    ));
+    assert!(dispatch(
+        Gesture::rotate_degrees(-180.0, x, y),
+        &targets,
+        &mut on_event
+    ));
+    assert!(!dispatch(
+        Gesture::magnify(0.5, 399.0, 299.0),
+        &targets,
+        &mut on_event
+    ));
+    assert_eq!(
+        got[0],
+        (
+            "zoom".to_string(),
+            r#"{"scale":1.5,"x":10,"y":20}"#.to_string()
+        )
+    );
+    assert!(got[1].1.starts_with(r#"{"angle":3.14159"#), "{}", got[1].1);
+}
@@ -0,0 +1,202 @@
+use velox_renderer::golden::{Golden, check_golden, write_golden};
+use velox_renderer::pixels::RgbaImage;
+
+fn solid(w: u32, h: u32, rgba: [u8; 4]) -> RgbaImage {
+    RgbaImage::from_rgba(w, h, rgba.repeat((w * h) as usize)).unwrap()
+}
+
+#[test]
+fn missing_goldens_fail_and_written_ones_compare_by_pixel() {
+    let dir = std::env::temp_dir().join(format!("velox-golden-{}", std::process::id()));
+    let path = dir.join("box.png");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let image = solid(4, 3, [10, 20, 30, 255]);
+    assert_eq!(check_golden(&path, &image).unwrap(), Golden::Missing);
+    assert!(!path.exists());
+    write_golden(&path, &image).unwrap();
+    assert_eq!(
+        RgbaImage::decode_png(&std::fs::read(&path).unwrap()),
+        Some(image.clone())
+    );
+    assert_eq!(check_golden(&path, &image).unwrap(), Golden::Matched);
+
+    let mut changed = image.clone();
+    changed.data[0] = 200;
+    match check_golden(&path, &changed).unwrap() {
+        Golden::Mismatch { actual, differing } => {
+            assert_eq!(differing, 1);
+            assert_eq!(
+                RgbaImage::decode_png(&std::fs::read(actual).unwrap()),
+                Some(changed)
+            );
+        }
+        other => panic!("expected a mismatch, got {:?}", other),
+    }
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[cfg(feature = "raster")]
+#[test]
+fn raster_text_matches_its_golden() {
+    use velox_dom::{h, text};
+
+    velox_renderer::config::set_deterministic(true);
+    let vnode = h(
+        "div",
+        vec![(
+            "style",
+            "background-color:#FFFFFF;color:#202020;border:1px solid #3366CC;width:88px;height:30px",
+        )],
+        vec![text("Golden")],
+    );
+    let image = velox_renderer::raster::render_to_image(&vnode, 96, 32, 1.0).expect("render");
+    velox_renderer::golden::assert_golden(
+        concat!(env!("CARGO_MANIFEST_DIR"), "/tests/goldens/raster_text.png"),
+        &image,
+    );
+}
+
+/// The cases the Skia tests render, drawn by the software backend against committed goldens.
+#[cfg(feature = "raster")]
+fn assert_raster_golden(name: &str, vnode: &velox_dom::VNode, width: u32, height: u32, scale: f32) {
+    velox_renderer::config::set_deterministic(true);
+    let image =
+        velox_renderer::raster::render_to_image(vnode, width, height, scale).expect("render");
+    let path = format!(
+        "{}/tests/goldens/raster_{}.png",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    velox_renderer::golden::assert_golden(path, &image);
+}
+
+#[cfg(feature = "raster")]
+#[test]
+fn raster_boxes_borders_and_clips_match_their_goldens() {
+    use velox_dom::h;
+
+    let border = h(
+        "div",
+        vec![("style", "background-color:#FFFFFF;border:2px solid #FF0000")],
+        vec![],
+    );
+    assert_raster_golden("border", &border, 64, 64, 1.0);
+
+    let rounded = h(
+        "div",
+        vec![("style", "background-color:#FFFFFF;border-radius:12px")],
+        vec![h(
+            "div",
+            vec![("style", "background-color:#FF0000")],
+            vec![],
+        )],
+    );
+    assert_raster_golden("border_radius", &rounded, 64, 64, 1.0);
+
+    let boxes = (0..120)
+        .map(|i| {
+            let color = if i % 2 == 0 { "#44AA44" } else { "#4444AA" };
+            let style = format!("background-color:{};width:6px;height:6px", color);
+            h("div", vec![("style", style.as_str())], vec![])
+        })
+        .collect();
+    let batching = h("div", vec![("style", "width:128px;height:128px")], boxes);
+    assert_raster_golden("batching", &batching, 128, 128, 1.0);
+
+    let overflow = h(
+        "div",
+        vec![(
+            "style",
+            "background-color:#FFFFFF;overflow:hidden;width:40px;height:40px",
+        )],
+        vec![h(
+            "div",
+            vec![("style", "background-color:#FF0000;width:40px;height:80px")],
+            vec![],
+        )],
+    );
+    assert_raster_golden("overflow_clip", &overflow, 64, 64, 1.0);
+
+    let overlap = h(
+        "div",
+        vec![("style", "background-color:#FFFFFF;width:64px;height:64px")],
+        vec![
+            h(
+                "div",
+                vec![(
+                    "style",
+                    "background-color:#FF0000;width:40px;height:40px;z-index:1",
+                )],
+                vec![],
+            ),
+            h(
+                "div",
+                vec![(
+                    "style",
+                    "background-color:#0000FF;width:40px;height:40px;margin-top:-20px;z-index:0",
+                )],
+                vec![],
+            ),
+        ],
+    );
+    assert_raster_golden("z_index", &overlap, 64, 64, 1.0);
+}
+
+#[cfg(feature = "raster")]
+#[test]
+fn raster_text_styles_wrapping_and_scale_match_their_goldens() {
+    use velox_dom::{h, text};
+
+    let styled = h(
+        "div",
+        vec![(
+            "style",
+            "background-color:#FFFFFF;text-align:center;color:#00FF00;text-decoration:underline",
+        )],
+        vec![text("Hello")],
+    );
+    assert_raster_golden("text_styles", &styled, 80, 32, 1.0);
+
+    let wrapped = h(
+        "div",
+        vec![("style", "background-color:#FFFFFF;width:80px;height:64px")],
+        vec![text("Hello from the Velox renderer")],
+    );
+    assert_raster_golden("text_wrap", &wrapped, 96, 64, 1.0);
+
+    let dpr2 = h(
+        "div",
+        vec![(
+            "style",
+            "background-color:#FFFFFF;border:1px solid #000000;width:64px;height:32px",
+        )],
+        vec![text("Hi")],
+    );
+    assert_raster_golden("dpr2", &dpr2, 64, 32, 2.0);
+}
+
+#[cfg(feature = "raster")]
+#[test]
+fn raster_hover_styles_match_their_golden() {
+    use velox_dom::h;
+    use velox_style::{Stylesheet, apply_styles_with_hover};
+
+    let vnode = h(
+        "div",
+        vec![("class", "btn"), ("style", "width:60px;height:24px")],
+        vec![],
+    );
+    let sheet = Stylesheet::parse(
+        ".btn { background-color: #00FF00; } .btn:hover { background-color: #FF0000; }",
+    );
+    let normal = apply_styles_with_hover(&vnode, &sheet, &|_, _| false);
+    let hovered = apply_styles_with_hover(&vnode, &sheet, &|tag, props| {
+        velox_renderer::events::is_hoverable(tag, props)
+    });
+
+    velox_renderer::config::set_deterministic(true);
+    let render = |v| velox_renderer::raster::render_to_image(v, 64, 32, 1.0).expect("render");
+    assert_ne!(render(&normal), render(&hovered));
+    assert_raster_golden("hover", &hovered, 64, 32, 1.0);
+}
@@ -0,0 +1,140 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use velox_dom::{Props, VNode, h, text};
+use velox_renderer::Renderer;
+use velox_renderer::events::{self, HandlerTable};
+
+fn item(label: &str, handler: &str) -> VNode {
+    h(
+        "li",
+        Props::new(),
+        vec![h(
+            "button",
+            Props::new()
+                .set("on:click", handler)
+                .set("on:click-payload", label),
+            vec![text(label)],
+        )],
+    )
+}
+
+fn list(items: Vec<VNode>) -> VNode {
+    h(
+        "div",
+        Props::new().set("on:keydown", "nav"),
+        vec![h("ul", Props::new(), items)],
+    )
+}
+
+#[test]
+fn update_keeps_the_table_in_step_with_the_tree() {
+    let r = velox_renderer::new_selected_renderer();
+    let mut tree = r.mount(&list(vec![item("a", "pick"), item("b", "pick")]));
+    assert_eq!(tree.handlers.get(&[0, 1, 0], "click"), Some("pick"));
+    assert_eq!(tree.handlers.get(&[], "keydown"), Some("nav"));
+    assert_eq!(tree.handlers.get(&[0, 1, 0], "click-payload"), None);
+
+    let steps = [
+        list(vec![
+            item("a", "pick"),
+            item("b", "pick"),
+            item("c", "drop"),
+        ]),
+        list(vec![item("a", "open")]),
+        h("div", Props::new(), vec![text("empty")]),
+        list(vec![item("x", "pick"), item("y", "open")]),
+    ];
+    for next in &steps {
+        tree.update(next);
+        assert_eq!(&tree.root, next);
+        assert_eq!(tree.handlers, HandlerTable::from_tree(next));
+    }
+    assert_eq!(
+        tree.handlers.handlers("click").collect::<Vec<_>>(),
+        ["pick", "open"]
+    );
+}
+
+#[test]
+fn keyed_moves_carry_their_handlers() {
+    let keyed = |label: &str, handler: &str| {
+        h(
+            "li",
+            Props::new().set("key", label),
+            vec![h(
+                "button",
+                Props::new().set("on:click", handler),
+                vec![text(label)],
+            )],
+        )
+    };
+    let r = velox_renderer::new_selected_renderer();
+    let mut tree = r.mount(&list(vec![
+        keyed("a", "pick"),
+        keyed("b", "open"),
+        keyed("c", "drop"),
+    ]));
+    let steps = [
+        list(vec![
+            keyed("c", "drop"),
+            keyed("a", "pick"),
+            keyed("b", "open"),
+        ]),
+        list(vec![
+            keyed("b", "open"),
+            keyed("x", "pick"),
+            keyed("c", "close"),
+        ]),
+    ];
+    for next in &steps {
+        tree.update(next);
+        assert_eq!(&tree.root, next);
+        assert_eq!(tree.handlers, HandlerTable::from_tree(next));
+    }
+}
+
+#[test]
+fn dispatch_reads_the_patched_table() {
+    let r = velox_renderer::new_selected_renderer();
+    let mut tree = r.mount(&list(vec![item("a", "pick")]));
+    let picked = Rc::new(RefCell::new(Vec::new()));
+    let mut reg = events::EventRegistry::new();
+    for name in ["pick", "drop"] {
+        let picked = picked.clone();
+        reg.on(name, move || picked.borrow_mut().push(name));
+    }
+
+    tree.update(&list(vec![item("a", "pick"), item("b", "drop")]));
+    assert_eq!(events::dispatch("click", &tree, &mut reg), 2);
+    assert_eq!(*picked.borrow(), ["pick", "drop"]);
+
+    let table = HandlerTable::from_static(&[(&[], "keydown", "nav"), (&[0, 0, 0], "click", "pick"), (&[0, 1, 0], "click", "drop")]);
+    assert_eq!(tree.handlers, table);
+}
+
+#[test]
+fn mounted_tables_are_checked_against_static_ones() {
+    let r = velox_renderer::new_selected_renderer();
+    let tree = r.mount(&list(vec![item("a", "pick")]));
+    // As a compiled `list` would emit: the `v-for` items are left out.
+    assert!(tree.handlers.missing_static(&[(&[], "keydown", "nav")]).is_empty());
+    let stale: &[events::StaticHandler] = &[(&[], "keydown", "nav"), (&[0, 0, 0], "click", "drop")];
+    assert_eq!(tree.handlers.missing_static(stale), [(&[0usize, 0, 0][..], "click", "drop")]);
+}
+
+#[test]
+fn hit_tests_resolve_handlers_through_the_table() {
+    let r = velox_renderer::new_selected_renderer();
+    let mut tree = r.mount(&list(vec![item("a", "pick")]));
+    tree.update(&list(vec![item("a", "pick"), item("b", "drop")]));
+
+    let layout = velox_renderer::pipeline::layout_tree(&tree.root, 200, 200);
+    let mut targets = events::Targets::default();
+    targets.collect(&tree.root, &layout, &tree.handlers);
+    let handlers: Vec<_> = targets.click.iter().map(|t| (t.handler.as_str(), t.payload.as_deref())).collect();
+    assert_eq!(handlers, [("pick", Some("a")), ("drop", Some("b"))]);
+    let second = targets.click[1].rect;
+    let hit = targets.click_at(second.x as f32 + 1.0, second.y as f32 + 1.0);
+    assert_eq!(hit, Some(("drop", Some("b"))));
+}
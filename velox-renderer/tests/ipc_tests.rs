@@ -0,0 +1,91 @@
+#![cfg(feature = "serde")]
+
+use velox_dom::{Props, h, text};
+use velox_renderer::Error;
+use velox_renderer::ipc::{Message, Request, node_from_json, node_to_json, write_message};
+
+#[test]
+fn host_requests_decode_into_views() {
+    let line = r#"{"type":"render","view":{"tag":"div","children":["Count ",{"tag":"button","attrs":{"on:click":"inc","tabindex":0}}]},"style":"button { width: 10px; }"}"#;
+    let expected = h(
+        "div",
+        Props::new(),
+        vec![
+            text("Count "),
+            h(
+                "button",
+                Props::new().set("on:click", "inc").set("tabindex", 0),
+                vec![],
+            ),
+        ],
+    );
+    assert_eq!(
+        Request::parse(line).expect("render"),
+        Request::Render {
+            view: expected.clone(),
+            style: Some("button { width: 10px; }".into())
+        }
+    );
+    assert_eq!(
+        node_from_json(&node_to_json(&expected)).expect("round trip"),
+        expected
+    );
+    assert_eq!(
+        Request::parse(r#"{"type":"title","title":"Counter"}"#).expect("title"),
+        Request::Title("Counter".into())
+    );
+
+    for (bad, reason) in [
+        (r#"{"type":"paint"}"#, "unknown message type \"paint\""),
+        (
+            r#"{"type":"render","view":{"children":[]}}"#,
+            "element without a tag",
+        ),
+        (
+            r#"{"type":"render","view":{"tag":"p","attrs":{"class":{"a":1}}}}"#,
+            "attribute \"class\" of <p> must be a string, number, boolean or array",
+        ),
+    ] {
+        match Request::parse(bad) {
+            Err(Error::Serialize(e)) => assert_eq!(e, reason),
+            other => panic!("{}: {:?}", bad, other),
+        }
+    }
+}
+
+#[test]
+fn events_are_written_one_per_line() {
+    let mut out = Vec::new();
+    write_message(
+        &mut out,
+        &Message::Resize {
+            width: 800,
+            height: 600,
+        },
+    )
+    .unwrap();
+    write_message(
+        &mut out,
+        &Message::Event {
+            handler: "inc".into(),
+            payload: None,
+        },
+    )
+    .unwrap();
+    write_message(
+        &mut out,
+        &Message::Event {
+            handler: "pick".into(),
+            payload: Some("{\"x\":1}".into()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        concat!(
+            "{\"height\":600,\"type\":\"resize\",\"width\":800}\n",
+            "{\"handler\":\"inc\",\"payload\":null,\"type\":\"event\"}\n",
+            "{\"handler\":\"pick\",\"payload\":\"{\\\"x\\\":1}\",\"type\":\"event\"}\n",
+        )
+    );
+}
@@ -0,0 +1,73 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use velox_dom::{VNode, h, text};
+use velox_renderer::live_regions::{
+    Announcement, LiveRegions, Politeness, announce, set_announcer,
+};
+
+fn form(error: &str, toast: Option<&str>) -> VNode {
+    let mut children = vec![
+        h("input", vec![("value", "x")], vec![]),
+        h(
+            "p",
+            vec![("aria-live", "polite"), ("id", "error")],
+            vec![text(error)],
+        ),
+    ];
+    if let Some(t) = toast {
+        children.insert(0, h("div", vec![("role", "alert")], vec![text(t)]));
+    }
+    h("form", vec![], children)
+}
+
+#[test]
+fn changed_live_text_is_announced_once() {
+    let mut regions = LiveRegions::new();
+    assert!(
+        regions.update(&form("", None)).is_empty(),
+        "the first frame is not announced"
+    );
+    assert!(regions.update(&form("", None)).is_empty());
+
+    let announced = regions.update(&form("Name is required", Some("Saved")));
+    assert_eq!(
+        announced,
+        vec![
+            Announcement {
+                text: "Saved".into(),
+                politeness: Politeness::Assertive
+            },
+            Announcement {
+                text: "Name is required".into(),
+                politeness: Politeness::Polite
+            },
+        ]
+    );
+    assert!(
+        regions
+            .update(&form("Name is required", Some("Saved")))
+            .is_empty(),
+        "unchanged"
+    );
+    assert!(
+        regions.update(&form("", None)).is_empty(),
+        "cleared regions are silent"
+    );
+
+    let tree = velox_renderer::build_a11y_tree(&form("", None), 200, 100);
+    assert_eq!(tree.root.children[1].live, Some(Politeness::Polite));
+    assert_eq!(tree.root.children[0].live, None);
+}
+
+#[test]
+fn announcements_go_to_the_announcer() {
+    let heard = Rc::new(RefCell::new(Vec::new()));
+    let h2 = heard.clone();
+    set_announcer(move |a| h2.borrow_mut().push(a.text.clone()));
+    announce(&Announcement {
+        text: "3 results".into(),
+        politeness: Politeness::Polite,
+    });
+    assert_eq!(*heard.borrow(), vec!["3 results".to_string()]);
+}
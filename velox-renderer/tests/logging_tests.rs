@@ -0,0 +1,24 @@
+use log::LevelFilter;
+use velox_renderer::logging::{DEFAULT_FILTER, LogFilter};
+
+#[test]
+fn longest_target_prefix_wins() {
+    let filter = LogFilter::parse("warn, velox=info, velox::skia=debug, bogus=loud");
+    assert_eq!(filter.level_for("velox::skia"), LevelFilter::Debug);
+    assert_eq!(filter.level_for("velox::skia::gl"), LevelFilter::Debug);
+    assert_eq!(filter.level_for("velox::renderer::wgpu"), LevelFilter::Info);
+    // Prefixes only match on `::` boundaries.
+    assert_eq!(filter.level_for("veloxide"), LevelFilter::Warn);
+    assert_eq!(filter.max_level(), LevelFilter::Debug);
+}
+
+#[test]
+fn default_filter_shows_velox_info_and_other_warnings() {
+    let filter = LogFilter::parse(DEFAULT_FILTER);
+    assert_eq!(filter.level_for("velox::renderer"), LevelFilter::Info);
+    assert_eq!(filter.level_for("wgpu_core::device"), LevelFilter::Warn);
+    assert_eq!(
+        LogFilter::parse("velox::skia").level_for("velox::skia"),
+        LevelFilter::Trace
+    );
+}
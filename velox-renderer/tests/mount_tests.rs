@@ -14,3 +14,23 @@ fn mount_counts_nodes_and_texts() {
     assert_eq!(tree.text_count, 2);
 }
 
+#[test]
+fn render_tree_runs_lifecycle_hooks() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use velox_core::lifecycle::{on_mounted, on_unmounted, on_updated};
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let (l1, l2, l3) = (log.clone(), log.clone(), log.clone());
+    on_mounted(move || l1.borrow_mut().push("mounted"));
+    on_updated(move || l2.borrow_mut().push("updated"));
+    on_unmounted(move || l3.borrow_mut().push("unmounted"));
+
+    let r = velox_renderer::new_selected_renderer();
+    let mut tree = r.mount(&h("p", Props::new(), vec![text("a")]));
+    // A patch that changes nothing is no update.
+    tree.update(&h("p", Props::new(), vec![text("a")]));
+    tree.update(&h("p", Props::new(), vec![text("b")]));
+    drop(tree);
+    assert_eq!(*log.borrow(), vec!["mounted", "updated", "unmounted"]);
+}
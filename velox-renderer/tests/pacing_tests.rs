@@ -0,0 +1,66 @@
+use std::time::{Duration, Instant};
+
+use velox_renderer::pacing::{DEFAULT_REFRESH_MILLIHERTZ, FramePacer};
+
+#[test]
+fn pacer_pauses_while_occluded_or_minimized() {
+    let mut pacer = FramePacer::new();
+    let now = Instant::now();
+    assert!(pacer.begin_frame(now));
+
+    assert!(!pacer.set_occluded(true));
+    assert!(!pacer.begin_frame(now));
+    assert_eq!(pacer.next_frame_at(), None);
+    // Minimizing while occluded does not resume; only the last of both clearing does.
+    assert!(!pacer.set_size(0, 0));
+    assert!(!pacer.set_occluded(false));
+    assert!(pacer.is_paused());
+    assert!(pacer.set_size(800, 600));
+    assert!(pacer.begin_frame(now));
+}
+
+#[test]
+fn pacer_follows_monitor_refresh_rate() {
+    let mut pacer = FramePacer::new();
+    assert_eq!(pacer.refresh_millihertz(), DEFAULT_REFRESH_MILLIHERTZ);
+    pacer.set_refresh_rate(Some(144_000));
+    assert_eq!(pacer.frame_interval(), Duration::from_nanos(6_944_444));
+
+    let now = Instant::now();
+    pacer.begin_frame(now);
+    assert_eq!(pacer.next_frame_at(), Some(now + pacer.frame_interval()));
+
+    pacer.set_refresh_rate(None);
+    assert_eq!(pacer.refresh_millihertz(), DEFAULT_REFRESH_MILLIHERTZ);
+}
+
+#[test]
+fn idle_deadline_stops_before_the_next_frame() {
+    let mut pacer = FramePacer::new();
+    let now = Instant::now();
+    pacer.begin_frame(now);
+    let next = pacer.next_frame_at().unwrap();
+    assert_eq!(
+        pacer.idle_deadline(now, true),
+        next - Duration::from_millis(1)
+    );
+    assert_eq!(
+        pacer.idle_deadline(now, false),
+        now + velox_renderer::pacing::MAX_IDLE_PERIOD
+    );
+}
+
+#[test]
+fn live_resize_draws_at_most_once_per_frame() {
+    let mut pacer = FramePacer::new();
+    let now = Instant::now();
+    assert!(pacer.resize_frame_due(now), "nothing drawn yet");
+    pacer.begin_frame(now);
+    assert!(!pacer.resize_frame_due(now + Duration::from_millis(5)));
+    assert!(pacer.resize_frame_due(now + pacer.frame_interval()));
+    pacer.set_size(0, 0);
+    assert!(
+        !pacer.resize_frame_due(now + Duration::from_secs(1)),
+        "minimized"
+    );
+}
@@ -0,0 +1,84 @@
+use velox_dom::{Props, VNode, h};
+use velox_renderer::events::{HandlerTable, Targets};
+use velox_renderer::pen::{PenPhase, PenSample, PenStrokes};
+
+fn canvas() -> VNode {
+    let surface = h(
+        "div",
+        Props::new()
+            .set("on:pen", "stroke")
+            .set("style", "width: 200px; height: 100px; margin-left: 20px"),
+        vec![],
+    );
+    h("div", Props::new(), vec![surface])
+}
+
+fn sample(phase: PenPhase, x: f32, y: f32, pressure: f32) -> PenSample {
+    PenSample {
+        phase,
+        x,
+        y,
+        pressure,
+        tilt: None,
+    }
+}
+
+#[test]
+fn strokes_stay_with_the_element_they_started_on() {
+    let vnode = velox_style::apply_styles(&canvas(), &velox_style::Stylesheet::default());
+    let layout = velox_renderer::pipeline::layout_tree(&vnode, 400, 300);
+    let mut targets = Targets::default();
+    targets.collect(&vnode, &layout, &HandlerTable::from_tree(&vnode));
+    let rect = targets.pen[0].rect;
+
+    let mut strokes = PenStrokes::new();
+    let mut got = Vec::new();
+    let mut on_event = |handler: &str, payload: Option<&str>| {
+        got.push((handler.to_string(), payload.unwrap().to_string()))
+    };
+    let (x, y) = (rect.x as f32 + 10.0, rect.y as f32 + 5.0);
+    // A stroke starting outside every `on:pen` element goes nowhere.
+    assert!(!strokes.sample(
+        1,
+        sample(PenPhase::Down, 399.0, 299.0, 0.3),
+        &targets,
+        &mut on_event
+    ));
+    assert!(strokes.sample(
+        2,
+        sample(PenPhase::Down, x, y, 0.25),
+        &targets,
+        &mut on_event
+    ));
+    assert!(strokes.sample(
+        2,
+        sample(PenPhase::Move, 399.0, y, 0.75),
+        &targets,
+        &mut on_event
+    ));
+    assert!(strokes.sample(
+        2,
+        sample(PenPhase::Up, 399.0, y, 0.0),
+        &targets,
+        &mut on_event
+    ));
+    assert!(!strokes.sample(
+        2,
+        sample(PenPhase::Move, x, y, 0.5),
+        &targets,
+        &mut on_event
+    ));
+    assert!(!strokes.is_drawing());
+
+    assert_eq!(got.len(), 3);
+    assert!(got.iter().all(|(handler, _)| handler == "stroke"));
+    assert_eq!(
+        got[0].1,
+        r#"{"phase":"down","pointer":2,"x":10,"y":5,"pressure":0.25,"tilt":null}"#
+    );
+    assert!(
+        got[1].1.starts_with(r#"{"phase":"move","pointer":2,"x":"#)
+            && got[1].1.contains(r#""pressure":0.75"#)
+    );
+    assert!(got[2].1.starts_with(r#"{"phase":"up""#));
+}
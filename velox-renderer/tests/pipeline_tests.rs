@@ -0,0 +1,61 @@
+use velox_dom::layout::compute_layout;
+use velox_dom::{VNode, h, text};
+use velox_renderer::pipeline::{LayoutWorker, prepare_frame};
+use velox_style::{Stylesheet, apply_styles};
+
+fn wide_tree() -> VNode {
+    let rows = (0..40)
+        .map(|r| {
+            h(
+                "div",
+                vec![
+                    ("class", "row"),
+                    ("data-hover-id", if r == 3 { "7" } else { "0" }),
+                ],
+                vec![
+                    text(format!("row {}", r)),
+                    h("span", vec![("style", "padding:2px")], vec![text("x")]),
+                ],
+            )
+        })
+        .collect();
+    h(
+        "div",
+        vec![(
+            "style",
+            "display:flex;flex-direction:column;gap:2px;width:300px",
+        )],
+        rows,
+    )
+}
+
+#[test]
+fn prepared_frame_matches_sequential_style_and_layout() {
+    let sheet = Stylesheet::parse(".row { padding: 3px; margin-bottom: 1px; color: #112233; }");
+    let tree = wide_tree();
+    let styled = apply_styles(&tree, &sheet);
+    let expected = compute_layout(&styled, 320, 240);
+
+    let frame = prepare_frame(&tree, &sheet, &|_, _| false, 320, 240);
+    assert_eq!(frame.vnode, styled);
+    assert_eq!(frame.layout, expected);
+}
+
+#[test]
+fn layout_worker_prepares_frames_in_order() {
+    let sheet = Stylesheet::parse(".row:hover { color: #FF0000; }");
+    let worker = LayoutWorker::spawn().expect("spawn layout worker");
+    worker.submit(wide_tree(), sheet.clone(), Some(7), 320, 240);
+    worker.submit(wide_tree(), sheet, None, 160, 120);
+
+    let first = worker.recv().expect("first frame");
+    let second = worker.recv().expect("second frame");
+    assert_eq!((first.width, second.width), (320, 160));
+    let VNode::Element { children, .. } = &first.vnode else {
+        panic!("element root")
+    };
+    let VNode::Element { props, .. } = &children[3] else {
+        panic!("row")
+    };
+    assert!(props.str("style").unwrap().contains("color: #FF0000"));
+}
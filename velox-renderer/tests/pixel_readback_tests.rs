@@ -0,0 +1,34 @@
+use velox_renderer::pixels::{PixelRect, RgbaImage, enable_readback, read_pixels};
+
+#[test]
+fn crop_clamps_to_image_bounds() {
+    let mut data = Vec::new();
+    for i in 0..16u8 {
+        data.extend_from_slice(&[i, 0, 0, 255]);
+    }
+    let img = RgbaImage::from_rgba(4, 4, data).expect("4x4 image");
+    let out = img.crop(PixelRect::new(2, 3, 10, 10));
+    assert_eq!((out.width, out.height), (2, 1));
+    assert_eq!(out.pixel(0, 0), Some([14, 0, 0, 255]));
+    assert_eq!(out.pixel(1, 0), Some([15, 0, 0, 255]));
+}
+
+#[test]
+fn read_pixels_is_empty_before_a_frame_is_presented() {
+    enable_readback(true);
+    assert_eq!(read_pixels(PixelRect::new(0, 0, 1, 1)), None);
+    enable_readback(false);
+}
+
+#[cfg(feature = "raster")]
+#[test]
+fn eyedropper_reads_rendered_color() {
+    let vnode = velox_dom::h(
+        "div",
+        vec![("style", "background-color:#336699;width:20px;height:20px")],
+        vec![],
+    );
+    let img = velox_renderer::raster::render_to_image(&vnode, 20, 20, 2.0).expect("render");
+    let picked = img.crop(PixelRect::new(10, 10, 1, 1));
+    assert_eq!(picked.pixel(0, 0), Some([0x33, 0x66, 0x99, 255]));
+}
@@ -0,0 +1,76 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use velox_dom::{Props, VNode, h, text};
+use velox_renderer::VeloxUi;
+use velox_renderer::pointer::{self, PointerMoves};
+use velox_style::Stylesheet;
+
+#[test]
+fn moves_coalesce_and_raw_moves_are_opt_in() {
+    let mut seen = Vec::new();
+    let mut on_event = |handler: &str, payload: Option<&str>| {
+        seen.push(format!("{} {}", handler, payload.unwrap_or("")))
+    };
+    let mut moves = PointerMoves::new();
+    assert!(!moves.moved(1, 1.0, 2.0, &mut on_event));
+    assert!(!moves.moved(1, 3.0, 4.0, &mut on_event));
+    assert_eq!(moves.take(), Some((3.0, 4.0)));
+    assert_eq!(moves.take(), None);
+    assert_eq!(moves.coalesced(), 1);
+
+    pointer::set_raw_moves(Some("pointer-move"));
+    assert!(moves.moved(2, 5.0, 6.5, &mut on_event));
+    pointer::set_raw_moves(None);
+    assert_eq!(seen, ["pointer-move {\"x\":5,\"y\":6.5,\"pointer\":2}"]);
+}
+
+#[test]
+fn hover_follows_the_last_move_at_the_next_update() {
+    let builds = Rc::new(RefCell::new(0));
+    let view = {
+        let builds = builds.clone();
+        move |_w: u32, _h: u32| -> (VNode, Stylesheet) {
+            *builds.borrow_mut() += 1;
+            let root = h(
+                "div",
+                Props::new(),
+                vec![h("button", Props::new(), vec![text("Hover me")])],
+            );
+            (
+                root,
+                Stylesheet::parse(
+                    "button { width: 100px; height: 40px; } button:hover { color: red; }",
+                ),
+            )
+        }
+    };
+    let mut ui = VeloxUi::new(view, |_: &str, _: Option<&str>| {});
+    let frame = Duration::from_millis(16);
+    assert!(ui.update(frame));
+    let built = *builds.borrow();
+
+    // A burst of moves across the button, ending outside it: hover never changes.
+    for x in [10.0, 50.0, 90.0, 400.0] {
+        ui.pointer_moved(x, 20.0);
+    }
+    assert!(!ui.update(frame));
+    assert_eq!(*builds.borrow(), built);
+
+    ui.pointer_moved(500.0, 500.0);
+    ui.pointer_moved(20.0, 20.0);
+    assert!(ui.update(frame), "the last move hovers the button");
+    assert_eq!(*builds.borrow(), built + 1);
+}
+
+#[test]
+fn pointer_ids_are_stable_per_device_until_released() {
+    let mut ids = pointer::PointerIds::new();
+    assert_eq!(ids.id("mouse"), pointer::PRIMARY_POINTER);
+    let finger = ids.id("finger");
+    assert_ne!(finger, pointer::PRIMARY_POINTER);
+    assert_eq!(ids.id("mouse"), pointer::PRIMARY_POINTER);
+    assert_eq!(ids.release(&"finger"), Some(finger));
+    assert_ne!(ids.id("finger"), finger, "a new touch gets a new id");
+}
@@ -0,0 +1,56 @@
+use velox_dom::layout::compute_layout;
+use velox_dom::{VNode, h, text};
+use velox_renderer::print::{PageSize, paginate};
+
+fn rows(styles: &[&str]) -> VNode {
+    let rows = styles
+        .iter()
+        .enumerate()
+        .map(|(i, style)| {
+            h(
+                "div",
+                vec![("style", *style)],
+                vec![text(format!("row {}", i))],
+            )
+        })
+        .collect();
+    h("div", vec![], rows)
+}
+
+#[test]
+fn page_sizes_and_pagination_avoid_cutting_lines() {
+    assert_eq!(PageSize::A4.landscape(), PageSize::new(842.0, 595.0));
+    assert_eq!(PageSize::LETTER.size_px(), (816.0, 1056.0));
+
+    // Ten 30px rows in 100px pages: each page ends above the row crossing its edge.
+    let vnode = rows(&["height: 30px"; 10]);
+    let layout = compute_layout(&vnode, 200, 100);
+    let pages = paginate(&vnode, &layout, 100.0);
+    assert_eq!(
+        pages,
+        vec![(0.0, 90.0), (90.0, 180.0), (180.0, 270.0), (270.0, 300.0)]
+    );
+
+    let short = h("div", vec![], vec![text("hi")]);
+    assert_eq!(
+        paginate(&short, &compute_layout(&short, 200, 100), 100.0),
+        vec![(0.0, 16.0)]
+    );
+}
+
+#[test]
+fn page_break_hints() {
+    let vnode = rows(&[
+        "height: 20px",
+        "height: 20px; page-break-after: always",
+        "height: 20px; break-before: page",
+        "height: 90px; break-inside: avoid",
+    ]);
+    let layout = compute_layout(&vnode, 200, 100);
+    // Breaks after row 1 (and row 2's break-before lands on the same spot), then the
+    // unbreakable row 3 moves to its own page instead of being cut at 140.
+    assert_eq!(
+        paginate(&vnode, &layout, 100.0),
+        vec![(0.0, 40.0), (40.0, 60.0), (60.0, 150.0)]
+    );
+}
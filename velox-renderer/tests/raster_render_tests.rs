@@ -0,0 +1,44 @@
+#![cfg(feature = "raster")]
+
+use velox_dom::{h, text};
+
+fn pixel(pixmap: &tiny_skia::Pixmap, x: u32, y: u32) -> (u8, u8, u8) {
+    let p = pixmap.pixel(x, y).expect("pixel in bounds");
+    (p.red(), p.green(), p.blue())
+}
+
+#[test]
+fn raster_draws_backgrounds_and_clips_overflow() {
+    let vnode = h(
+        "div",
+        vec![(
+            "style",
+            "background-color:#0000FF;overflow:hidden;width:40px;height:40px",
+        )],
+        vec![h(
+            "div",
+            vec![("style", "background-color:#FF0000;width:40px;height:80px")],
+            vec![],
+        )],
+    );
+    let pixmap = velox_renderer::raster::render_to_pixmap(&vnode, 64, 64, 1.0).expect("render");
+    assert_eq!(pixel(&pixmap, 10, 10), (255, 0, 0));
+    // The child overflows its 40px parent; the clip keeps it off the white page.
+    assert_eq!(pixel(&pixmap, 10, 50), (255, 255, 255));
+}
+
+#[test]
+fn raster_scales_and_renders_text() {
+    let vnode = h(
+        "div",
+        vec![("style", "width:64px;height:32px;color:#000000")],
+        vec![text("Hi")],
+    );
+    let pixmap = velox_renderer::raster::render_to_pixmap(&vnode, 64, 32, 2.0).expect("render");
+    assert_eq!((pixmap.width(), pixmap.height()), (128, 64));
+    let dark = pixmap.pixels().iter().filter(|p| p.red() < 128).count();
+    assert!(dark > 0, "text should leave dark pixels");
+
+    let png = velox_renderer::raster::render_vnode_to_png(&vnode, 64, 32, 1.0).expect("png");
+    assert_eq!(&png[1..4], b"PNG");
+}
@@ -28,8 +28,8 @@ fn reconcile_reorders_and_reuses_by_key() {
     // old[0] should be the previously-existing node with key "b" (uid old-b)
     match &old[0] {
         VNode::Element { props, children, .. } => {
-            assert_eq!(props.attrs.get("key").map(|s| s.as_str()), Some("b"));
-            assert_eq!(props.attrs.get("uid").map(|s| s.as_str()), Some("old-b"));
+            assert_eq!(props.str("key"), Some("b"));
+            assert_eq!(props.str("uid"), Some("old-b"));
             // since we reused the old node, its child text remains the original "B"
             assert!(matches!(children.get(0), Some(VNode::Text(t)) if t == "B"));
         }
@@ -39,8 +39,8 @@ fn reconcile_reorders_and_reuses_by_key() {
     // old[1] should be the previously-existing node with key "a"
     match &old[1] {
         VNode::Element { props, .. } => {
-            assert_eq!(props.attrs.get("key").map(|s| s.as_str()), Some("a"));
-            assert_eq!(props.attrs.get("uid").map(|s| s.as_str()), Some("old-a"));
+            assert_eq!(props.str("key"), Some("a"));
+            assert_eq!(props.str("uid"), Some("old-a"));
         }
         _ => panic!("expected element at old[1]"),
     }
@@ -48,7 +48,7 @@ fn reconcile_reorders_and_reuses_by_key() {
     // old[2] is the new node with key "d" and has no uid
     match &old[2] {
         VNode::Element { props, .. } => {
-            assert_eq!(props.attrs.get("key").map(|s| s.as_str()), Some("d"));
+            assert_eq!(props.str("key"), Some("d"));
             assert!(props.attrs.get("uid").is_none());
         }
         _ => panic!("expected element at old[2]"),
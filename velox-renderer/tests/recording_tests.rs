@@ -0,0 +1,31 @@
+#![cfg(feature = "raster")]
+
+use velox_dom::h;
+use velox_renderer::recording::{record_frames, record_offscreen, take_frames};
+
+#[test]
+fn offscreen_recording_captures_each_frame() {
+    let colors = ["#FF0000", "#00FF00", "#0000FF"];
+    let frames = record_offscreen(colors.len(), 16, 8, 1.0, |i| {
+        let style = format!("background-color:{};width:16px;height:8px", colors[i]);
+        h("div", vec![("style", style.as_str())], vec![])
+    })
+    .expect("record");
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[1].pixel(4, 4), Some([0, 255, 0, 255]));
+
+    #[cfg(feature = "gif")]
+    {
+        let mut gif = Vec::new();
+        velox_renderer::recording::encode_gif(&frames, 100, &mut gif).expect("encode gif");
+        assert!(gif.starts_with(b"GIF89a"));
+    }
+}
+
+#[test]
+fn window_recording_is_empty_until_frames_are_presented() {
+    record_frames(2);
+    assert!(velox_renderer::recording::is_recording());
+    assert!(take_frames().is_empty());
+    assert!(!velox_renderer::recording::is_recording());
+}
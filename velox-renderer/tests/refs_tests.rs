@@ -0,0 +1,88 @@
+use velox_dom::layout::compute_layout;
+use velox_dom::{VNode, h};
+use velox_renderer::refs::{Refs, scroll_layout};
+
+fn rows(n: usize) -> VNode {
+    let names: Vec<String> = (0..n).map(|i| format!("row-{}", i)).collect();
+    let children = names
+        .iter()
+        .map(|name| {
+            h(
+                "div",
+                vec![("ref", name.as_str()), ("style", "height: 50px")],
+                vec![],
+            )
+        })
+        .collect();
+    h("div", vec![], children)
+}
+
+#[test]
+fn refs_report_rects_and_scroll_into_view() {
+    let tree = rows(20);
+    let layout = compute_layout(&tree, 200, 300);
+    let refs = Refs::new();
+    refs.update(&tree, &layout, 200, 300);
+
+    let r = refs.rect("row-10").unwrap();
+    assert_eq!((r.y - refs.rect("row-9").unwrap().y, r.h), (50, 50));
+    assert_eq!(refs.names().len(), 20);
+    assert!(!refs.scroll_into_view("missing"));
+
+    // Scrolling down aligns the row's bottom with the viewport's; already visible rows don't move.
+    assert!(refs.scroll_into_view("row-10"));
+    assert_eq!(refs.scroll_offset(), (0, r.y + 50 - 300));
+    refs.scroll_into_view("row-9");
+    assert_eq!(refs.scroll_offset(), (0, r.y + 50 - 300));
+    // Scrolling up aligns its top; scrolling never passes the content.
+    refs.scroll_into_view("row-0");
+    assert_eq!(refs.scroll_offset(), (0, 0));
+    refs.scroll_to(0, 10_000);
+    let bottom = refs.rect("row-19").unwrap();
+    assert_eq!(refs.scroll_offset(), (0, bottom.y + bottom.h - 300));
+
+    let mut shifted = layout.clone();
+    scroll_layout(&mut shifted, (0, 100));
+    assert_eq!(shifted.rect, layout.rect);
+    assert_eq!(shifted.children[3].rect.y, layout.children[3].rect.y - 100);
+}
+
+#[test]
+fn focus_follows_refs() {
+    let tree = rows(2);
+    let refs = Refs::new();
+    refs.update(&tree, &compute_layout(&tree, 200, 300), 200, 300);
+    assert!(refs.focus("row-1"));
+    assert!(!refs.focus("nope"));
+    assert_eq!(refs.focused().as_deref(), Some("row-1"));
+
+    let fewer = rows(1);
+    refs.update(&fewer, &compute_layout(&fewer, 200, 300), 200, 300);
+    assert_eq!(refs.focused(), None);
+}
+
+#[test]
+fn observers_fire_on_first_layout_and_on_change() {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    let refs = Refs::new();
+    let seen: Rc<RefCell<Vec<i32>>> = Rc::new(RefCell::new(vec![]));
+    let seen_cb = seen.clone();
+    let id = refs.observe("bar", move |rect| seen_cb.borrow_mut().push(rect.h));
+
+    let bar = |height: &str| {
+        h(
+            "div",
+            vec![],
+            vec![h("div", vec![("ref", "bar"), ("style", height)], vec![])],
+        )
+    };
+    let frame = |tree: VNode| refs.update(&tree, &compute_layout(&tree, 200, 300), 200, 300);
+    assert!(frame(bar("height: 40px")));
+    assert!(!frame(bar("height: 40px")));
+    assert!(frame(bar("height: 60px")));
+    refs.unobserve(id);
+    assert!(!frame(bar("height: 80px")));
+    assert_eq!(&*seen.borrow(), &vec![40, 60]);
+}
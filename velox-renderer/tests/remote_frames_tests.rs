@@ -0,0 +1,50 @@
+#![cfg(feature = "serde")]
+
+use velox_dom::h;
+use velox_dom::layout::compute_layout;
+use velox_renderer::Error;
+use velox_renderer::display_list::{ApproxMeasure, DisplayList, build_display_list};
+use velox_renderer::remote::{FrameReader, FrameWriter, play};
+
+fn frame(color: &str) -> DisplayList {
+    let style = format!(
+        "background-color:{};border-radius:4px;width:20px;height:20px",
+        color
+    );
+    let vnode = h("div", vec![("style", style.as_str())], vec![]);
+    let layout = compute_layout(&vnode, 32, 32);
+    build_display_list(&vnode, &layout, &mut ApproxMeasure)
+}
+
+#[test]
+fn frames_round_trip_through_a_stream() {
+    let frames = vec![frame("#FF0000"), frame("#00FF00")];
+    let mut writer = FrameWriter::new(Vec::new());
+    for f in &frames {
+        writer.write(f).expect("write frame");
+    }
+    assert_eq!(writer.frames(), 2);
+    let bytes = writer.into_inner();
+
+    let decoded: Vec<DisplayList> = FrameReader::new(bytes.as_slice())
+        .collect::<Result<_, _>>()
+        .expect("decode");
+    assert_eq!(decoded, frames);
+
+    let mut seen = Vec::new();
+    let mut sink = |list: &DisplayList| {
+        seen.push(list.commands.len());
+        Ok(())
+    };
+    assert_eq!(play(bytes.as_slice(), &mut sink).expect("play"), 2);
+    assert_eq!(
+        seen,
+        vec![frames[0].commands.len(), frames[1].commands.len()]
+    );
+}
+
+#[test]
+fn malformed_frame_is_reported() {
+    let err = play(&b"{not json}\n"[..], &mut |_: &DisplayList| Ok(())).unwrap_err();
+    assert!(matches!(err, Error::Serialize(_)), "{}", err);
+}
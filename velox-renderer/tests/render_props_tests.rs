@@ -0,0 +1,95 @@
+use velox_dom::h;
+use velox_dom::layout::compute_layout;
+use velox_renderer::display_list::{
+    ApproxMeasure, Color, RenderCommand, Transform2D, build_display_list,
+};
+use velox_renderer::render_props::{self, RenderProps, RetainedFrame};
+
+fn view() -> velox_dom::VNode {
+    h(
+        "div",
+        vec![],
+        vec![
+            h(
+                "div",
+                vec![
+                    ("ref", "toast"),
+                    (
+                        "style",
+                        "background-color: #ff0000; width: 40px; height: 20px",
+                    ),
+                ],
+                vec![],
+            ),
+            h(
+                "div",
+                vec![(
+                    "style",
+                    "background-color: #0000ff; width: 40px; height: 20px",
+                )],
+                vec![],
+            ),
+        ],
+    )
+}
+
+fn rect_colors(commands: &[RenderCommand]) -> Vec<Color> {
+    commands
+        .iter()
+        .filter_map(|c| match c {
+            RenderCommand::Rect { color, .. } => Some(*color),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn retained_frame_applies_props_without_rebuilding() {
+    let vnode = view();
+    let layout = compute_layout(&vnode, 100, 100);
+    let mut frame = RetainedFrame::build(&vnode, &layout, &mut ApproxMeasure);
+    assert_eq!(frame.spans().len(), 1);
+    assert_eq!(frame.display_list(), *frame.base());
+
+    frame.set_props("toast", RenderProps::default().with_opacity(0.5));
+    let faded = frame.display_list();
+    assert_eq!(
+        rect_colors(&faded.commands),
+        vec![Color::rgba(255, 0, 0, 128), Color::rgba(0, 0, 255, 255)]
+    );
+
+    let slide = Transform2D {
+        sx: 1.0,
+        sy: 1.0,
+        tx: 30.0,
+        ty: 0.0,
+    };
+    frame.set_props("toast", RenderProps::default().with_transform(slide));
+    let moved = frame.display_list();
+    assert!(matches!(&moved.commands[0], RenderCommand::PushTransform(t) if t.tx == 30.0));
+    assert_eq!(moved.commands[2], RenderCommand::PopTransform);
+
+    frame.set_props("toast", RenderProps::default().with_visible(false));
+    assert_eq!(
+        rect_colors(&frame.display_list().commands),
+        vec![Color::rgba(0, 0, 255, 255)]
+    );
+    assert_eq!(
+        rect_colors(&frame.base().commands).len(),
+        2,
+        "the built list is untouched"
+    );
+}
+
+#[test]
+fn thread_render_props_apply_to_built_lists() {
+    let vnode = view();
+    let layout = compute_layout(&vnode, 100, 100);
+    render_props::set_render_props("toast", RenderProps::default().with_opacity(0.0));
+    let list = build_display_list(&vnode, &layout, &mut ApproxMeasure);
+    assert_eq!(rect_colors(&list.commands)[0].a, 0);
+
+    render_props::clear_render_props("toast");
+    let list = build_display_list(&vnode, &layout, &mut ApproxMeasure);
+    assert_eq!(rect_colors(&list.commands)[0].a, 255);
+}
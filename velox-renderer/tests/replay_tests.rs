@@ -0,0 +1,88 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use velox_dom::{Props, VNode, h, text};
+use velox_renderer::Error;
+use velox_renderer::replay::{Input, Script, Step};
+use velox_renderer::testing::TestApp;
+use velox_style::Stylesheet;
+
+fn counter(count: &Rc<Cell<i32>>) -> TestApp {
+    let view = move |_w: u32, _h: u32| -> (VNode, Stylesheet) {
+        let root = h(
+            "div",
+            Props::new(),
+            vec![h(
+                "button",
+                Props::new().set("on:click", "inc"),
+                vec![text("+")],
+            )],
+        );
+        (
+            root,
+            Stylesheet::parse("button { width: 80px; height: 40px; }"),
+        )
+    };
+    let on_event = {
+        let count = count.clone();
+        move |name: &str, payload: Option<&str>| match name {
+            "inc" => count.set(count.get() + 1),
+            "set" => count.set(payload.and_then(|p| p.parse().ok()).unwrap_or(0)),
+            _ => {}
+        }
+    };
+    TestApp::new(view, on_event)
+}
+
+#[test]
+fn scripts_round_trip_through_text() {
+    let src = "# a session\n0 move 10 5\n350 click 10 5 inc\n400 click 300 5\n900 key Ctrl+Z\n1200 emit set 7\\nand \\\\ more\n";
+    let script = Script::parse(src).unwrap();
+    assert_eq!(script.steps.len(), 5);
+    assert_eq!(
+        script.steps[1],
+        Step {
+            at_ms: 350,
+            input: Input::Click {
+                x: 10.0,
+                y: 5.0,
+                target: Some("inc".into())
+            }
+        }
+    );
+    assert_eq!(
+        script.steps[4].input,
+        Input::Emit {
+            handler: "set".into(),
+            payload: Some("7\nand \\ more".into())
+        }
+    );
+    assert_eq!(Script::parse(&script.to_string()).unwrap(), script);
+
+    let err = Script::parse("0 move 1 2\n10 hover 3 4\n").unwrap_err();
+    assert!(
+        matches!(&err, Error::Replay(msg) if msg.starts_with("line 2:")),
+        "{err}"
+    );
+}
+
+#[test]
+fn play_drives_a_test_app_and_catches_moved_targets() {
+    let count = Rc::new(Cell::new(0));
+    let mut app = counter(&count);
+    let script = Script::parse(
+        "0 click 10 5 inc\n100 click 10 5 inc\n200 emit set 40\n300 click 10 5 inc\n",
+    )
+    .unwrap();
+    script.play(&mut app, None).unwrap();
+    assert_eq!(count.get(), 41);
+
+    // The button is no longer under (300, 5): the flow is stale.
+    let stale = Script::parse("0 click 300 5 inc\n").unwrap();
+    let err = stale.play(&mut app, None).unwrap_err();
+    assert!(
+        matches!(&err, Error::Replay(msg) if msg.contains("hit nothing")),
+        "{err}"
+    );
+    assert_eq!(count.get(), 41);
+}
@@ -0,0 +1,36 @@
+use velox_renderer::pixels::RgbaImage;
+use velox_renderer::screenshot;
+use velox_renderer::shortcuts::{Shortcut, Shortcuts};
+
+#[test]
+fn png_round_trips_through_a_decoder() {
+    let mut data = Vec::new();
+    for i in 0..6u8 {
+        data.extend_from_slice(&[i * 40, 255 - i * 40, 7, 255]);
+    }
+    let img = RgbaImage::from_rgba(3, 2, data).expect("3x2 image");
+    let png = img.encode_png();
+    assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+    let decoded = tiny_skia::Pixmap::decode_png(&png).expect("valid png");
+    assert_eq!((decoded.width(), decoded.height()), (3, 2));
+    assert_eq!(decoded.data(), &img.data[..]);
+}
+
+#[test]
+fn f12_requests_a_screenshot_by_default() {
+    let shortcuts = Shortcuts::new();
+    let f12 = Shortcut::parse(screenshot::SHORTCUT).unwrap();
+    assert_eq!(
+        shortcuts.resolve(&f12),
+        Some((screenshot::HANDLER.to_string(), None))
+    );
+
+    assert!(!screenshot::is_pending());
+    screenshot::capture_screenshot(screenshot::default_path());
+    assert!(screenshot::is_pending());
+    assert!(
+        screenshot::default_path()
+            .extension()
+            .is_some_and(|e| e == "png")
+    );
+}
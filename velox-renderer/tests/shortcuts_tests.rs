@@ -0,0 +1,150 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use velox_dom::h;
+use velox_renderer::events::EventRegistry;
+use velox_renderer::shortcuts::{Shortcut, Shortcuts};
+
+fn combo(s: &str) -> Shortcut {
+    Shortcut::parse(s).unwrap()
+}
+
+#[test]
+fn parses_and_resolves_with_scopes() {
+    assert_eq!(
+        combo("shift+ctrl+s"),
+        Shortcut::new("S", true, false, true, false)
+    );
+    assert_eq!(combo("Cmd+Esc").to_string(), "Meta+Escape");
+    assert_eq!(Shortcut::parse("Hyper+K"), None);
+
+    let shortcuts = Shortcuts::new();
+    assert_eq!(
+        shortcuts.resolve(&combo("Ctrl+Z")),
+        Some(("undo".into(), None)),
+        "default binding"
+    );
+    shortcuts.bind("Escape", "quit").unwrap();
+    shortcuts
+        .push_scope("dialog", &[("Escape", "close-dialog")])
+        .unwrap();
+    assert_eq!(
+        shortcuts.resolve(&combo("Escape")).unwrap().0,
+        "close-dialog"
+    );
+    assert!(shortcuts.pop_scope("dialog"));
+    assert_eq!(shortcuts.resolve(&combo("Escape")).unwrap().0, "quit");
+
+    shortcuts.bind("Escape", "other").unwrap();
+    assert_eq!(shortcuts.conflicts().len(), 1);
+    assert_eq!(
+        shortcuts.conflicts()[0].handlers,
+        ("quit".into(), "other".into())
+    );
+
+    let hits = Rc::new(Cell::new(0));
+    let mut registry = EventRegistry::new();
+    let h2 = hits.clone();
+    registry.on("other", move || h2.set(h2.get() + 1));
+    assert!(shortcuts.dispatch(&combo("Escape"), &mut registry));
+    assert_eq!(hits.get(), 1);
+}
+
+#[test]
+fn view_bindings_with_modal_scope_override() {
+    let view = h(
+        "div",
+        vec![],
+        vec![
+            h(
+                "button",
+                vec![("on:click", "save"), ("shortcut", "Ctrl+S")],
+                vec![],
+            ),
+            h(
+                "button",
+                vec![("on:click", "close-app"), ("shortcut", "Escape")],
+                vec![],
+            ),
+            h(
+                "div",
+                vec![("shortcut-scope", "modal")],
+                vec![
+                    h(
+                        "button",
+                        vec![("on:click", "close-modal"), ("shortcut", "Escape")],
+                        vec![],
+                    ),
+                    h(
+                        "button",
+                        vec![
+                            ("on:click", "confirm"),
+                            ("on:click-payload", "yes"),
+                            ("shortcut", "Enter"),
+                        ],
+                        vec![],
+                    ),
+                    h(
+                        "button",
+                        vec![("on:click", "accept"), ("shortcut", "Enter")],
+                        vec![],
+                    ),
+                ],
+            ),
+        ],
+    );
+    let shortcuts = Shortcuts::new();
+    shortcuts.update_from_view(&view);
+    assert_eq!(
+        shortcuts.resolve(&combo("Ctrl+S")),
+        Some(("save".into(), None))
+    );
+    assert_eq!(
+        shortcuts.resolve(&combo("Escape")).unwrap().0,
+        "close-modal"
+    );
+    assert_eq!(
+        shortcuts.resolve(&combo("Enter")),
+        Some(("confirm".into(), Some("yes".into()))),
+        "first in tree order wins"
+    );
+    assert_eq!(shortcuts.conflicts()[0].scope, "modal");
+
+    shortcuts.update_from_view(&h("div", vec![], vec![]));
+    assert_eq!(
+        shortcuts.resolve(&combo("Escape")),
+        None,
+        "bindings follow the view"
+    );
+}
+
+#[test]
+fn shortcut_payloads_and_listing() {
+    let view = h(
+        "div",
+        vec![("shortcut-scope", "palette")],
+        vec![h(
+            "span",
+            vec![
+                ("on:shortcut", "palette"),
+                ("on:shortcut-payload", "close"),
+                ("shortcut", "Escape"),
+            ],
+            vec![],
+        )],
+    );
+    let shortcuts = Shortcuts::new();
+    shortcuts.bind("Escape", "quit").unwrap();
+    shortcuts.update_from_view(&view);
+    assert_eq!(
+        shortcuts.resolve(&combo("Escape")),
+        Some(("palette".into(), Some("close".into())))
+    );
+    let bindings = shortcuts.bindings();
+    assert_eq!(bindings[0], ("Escape".to_string(), "palette".to_string()));
+    assert!(
+        !bindings.iter().any(|(_, handler)| handler == "quit"),
+        "shadowed"
+    );
+    assert!(bindings.contains(&("Ctrl+Z".to_string(), "undo".to_string())));
+}
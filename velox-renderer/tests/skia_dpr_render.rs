@@ -1,11 +1,11 @@
-//! Integration test: render with DPR=2 and verify PNG checksum.
+//! Integration test: render with DPR=2 and compare it with its golden image (`tests/goldens`).
 //!
 //! Ignored by default since it requires `--features skia-native` and native libs.
 
 #[cfg(all(feature = "skia-native", unix))]
 #[test]
 #[ignore]
-fn render_dpr2_checksum() {
+fn render_dpr2_golden() {
     use velox_dom::{h, text};
     use velox_style::Stylesheet;
 
@@ -15,29 +15,10 @@ fn render_dpr2_checksum() {
         vec![text("Hi")],
     );
 
-    let png = match velox_renderer::render_vnode_to_raster_png_with_scale(
-        &vnode,
-        &Stylesheet::default(),
-        64,
-        32,
-        2.0,
-    ) {
-        Ok(b) => b,
+    velox_renderer::config::set_deterministic(true);
+    let image = match velox_renderer::render_vnode_to_raster_image(&vnode, &Stylesheet::default(), 64, 32, 2.0) {
+        Ok(img) => img,
         Err(e) => panic!("render failed: {}", e),
     };
-
-    let checksum = fnv1a(&png);
-    println!("dpr2 checksum: 0x{checksum:08x}");
-    // Update this checksum after regenerating the raster output.
-    const EXPECTED_DPR_CHECKSUM: u32 = 0x9659a433;
-    assert_eq!(checksum, EXPECTED_DPR_CHECKSUM);
-}
-
-fn fnv1a(bytes: &[u8]) -> u32 {
-    let mut hash: u32 = 0x811c9dc5;
-    for b in bytes {
-        hash ^= *b as u32;
-        hash = hash.wrapping_mul(0x01000193);
-    }
-    hash
+    velox_renderer::golden::assert_golden_or_record(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/goldens/skia_dpr2.png"), &image);
 }
@@ -0,0 +1,73 @@
+// Ignored by default; needs an EGL display. The test plays the host: it creates and owns
+// the GL context, and velox only draws into its default framebuffer.
+#![cfg(all(feature = "skia-native", unix))]
+
+use velox_dom::{Props, h, text};
+use velox_renderer::skia_backend::{ExternalFramebuffer, SkiaRenderer};
+use velox_style::Stylesheet;
+
+#[test]
+#[ignore]
+fn renders_into_a_host_owned_context() {
+    let display = egl::get_display(egl::EGL_DEFAULT_DISPLAY).expect("egl display");
+    let (mut major, mut minor) = (0, 0);
+    assert!(egl::initialize(display, &mut major, &mut minor));
+    let attribs = [
+        egl::EGL_RED_SIZE as egl::EGLint,
+        8,
+        egl::EGL_GREEN_SIZE as egl::EGLint,
+        8,
+        egl::EGL_BLUE_SIZE as egl::EGLint,
+        8,
+        egl::EGL_ALPHA_SIZE as egl::EGLint,
+        8,
+        egl::EGL_STENCIL_SIZE as egl::EGLint,
+        8,
+        egl::EGL_NONE as egl::EGLint,
+    ];
+    let config = egl::choose_config(display, &attribs, 1).expect("egl config");
+    let ctx_attribs = [
+        egl::EGL_CONTEXT_CLIENT_VERSION as egl::EGLint,
+        2,
+        egl::EGL_NONE as egl::EGLint,
+    ];
+    let context = egl::create_context(display, config, egl::EGL_NO_CONTEXT, &ctx_attribs)
+        .expect("egl context");
+    let pbuffer_attribs = [
+        egl::EGL_WIDTH as egl::EGLint,
+        64,
+        egl::EGL_HEIGHT as egl::EGLint,
+        64,
+        egl::EGL_NONE as egl::EGLint,
+    ];
+    let surface = egl::create_pbuffer_surface(display, config, &pbuffer_attribs).expect("pbuffer");
+    assert!(egl::make_current(display, surface, surface, context));
+
+    let mut renderer = SkiaRenderer::with_external_gl(
+        |name| egl::get_proc_address(name) as *const _,
+        ExternalFramebuffer::new(0, 64, 64),
+    )
+    .expect("wrap the host framebuffer");
+    let view = h("div", Props::new(), vec![text("overlay")]);
+    for _ in 0..2 {
+        renderer
+            .render(&view, &Stylesheet::parse("div { background: #336699; }"))
+            .expect("render");
+    }
+    renderer.resize(32, 32).expect("resize");
+    renderer
+        .render(&view, &Stylesheet::default())
+        .expect("render after resize");
+
+    // The context is still the host's to tear down.
+    drop(renderer);
+    assert!(egl::make_current(
+        display,
+        egl::EGL_NO_SURFACE,
+        egl::EGL_NO_SURFACE,
+        egl::EGL_NO_CONTEXT
+    ));
+    egl::destroy_surface(display, surface);
+    egl::destroy_context(display, context);
+    egl::terminate(display);
+}
@@ -1,4 +1,4 @@
-//! Integration test: render image with opacity and filters and verify PNG checksum.
+//! Integration test: render image with opacity and filters and compare it with its golden image (`tests/goldens`).
 //!
 //! Ignored by default since it requires `--features skia-native` and native libs.
 
@@ -8,7 +8,7 @@ use skia_safe as sk;
 #[cfg(all(feature = "skia-native", unix))]
 #[test]
 #[ignore]
-fn render_image_filters_checksum() {
+fn render_image_filters_golden() {
     use velox_dom::h;
     use velox_style::Stylesheet;
 
@@ -25,16 +25,12 @@ fn render_image_filters_checksum() {
         vec![],
     );
 
-    let png = match velox_renderer::render_vnode_to_raster_png(&vnode, &Stylesheet::default(), 32, 32) {
-        Ok(b) => b,
+    velox_renderer::config::set_deterministic(true);
+    let image = match velox_renderer::render_vnode_to_raster_image(&vnode, &Stylesheet::default(), 32, 32, 1.0) {
+        Ok(img) => img,
         Err(e) => panic!("render failed: {}", e),
     };
-
-    let checksum = fnv1a(&png);
-    println!("image filter checksum: 0x{checksum:08x}");
-    // Update this checksum after regenerating the raster output.
-    const EXPECTED_IMAGE_FILTER_CHECKSUM: u32 = 0xc2e1032a;
-    assert_eq!(checksum, EXPECTED_IMAGE_FILTER_CHECKSUM);
+    velox_renderer::golden::assert_golden_or_record(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/goldens/skia_image_filter.png"), &image);
 }
 
 #[cfg(all(feature = "skia-native", unix))]
@@ -55,12 +51,3 @@ fn create_test_image(path: &str) {
         .expect("encode png");
     std::fs::write(path, data.as_bytes()).expect("write png");
 }
-
-fn fnv1a(bytes: &[u8]) -> u32 {
-    let mut hash: u32 = 0x811c9dc5;
-    for b in bytes {
-        hash ^= *b as u32;
-        hash = hash.wrapping_mul(0x01000193);
-    }
-    hash
-}
@@ -1,11 +1,11 @@
-//! Integration test: render text color, alignment, and underline and verify PNG checksum.
+//! Integration test: render text color, alignment, and underline and compare it with its golden image (`tests/goldens`).
 //!
 //! Ignored by default since it requires `--features skia-native` and native libs.
 
 #[cfg(all(feature = "skia-native", unix))]
 #[test]
 #[ignore]
-fn render_text_styles_checksum() {
+fn render_text_styles_golden() {
     use velox_dom::{h, text};
     use velox_style::Stylesheet;
 
@@ -18,23 +18,10 @@ fn render_text_styles_checksum() {
         vec![text("Hello")],
     );
 
-    let png = match velox_renderer::render_vnode_to_raster_png(&vnode, &Stylesheet::default(), 80, 32) {
-        Ok(b) => b,
+    velox_renderer::config::set_deterministic(true);
+    let image = match velox_renderer::render_vnode_to_raster_image(&vnode, &Stylesheet::default(), 80, 32, 1.0) {
+        Ok(img) => img,
         Err(e) => panic!("render failed: {}", e),
     };
-
-    let checksum = fnv1a(&png);
-    println!("text checksum: 0x{checksum:08x}");
-    // Update this checksum after regenerating the raster output.
-    const EXPECTED_TEXT_CHECKSUM: u32 = 0xdbcd3a71;
-    assert_eq!(checksum, EXPECTED_TEXT_CHECKSUM);
-}
-
-fn fnv1a(bytes: &[u8]) -> u32 {
-    let mut hash: u32 = 0x811c9dc5;
-    for b in bytes {
-        hash ^= *b as u32;
-        hash = hash.wrapping_mul(0x01000193);
-    }
-    hash
+    velox_renderer::golden::assert_golden_or_record(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/goldens/skia_text.png"), &image);
 }
@@ -0,0 +1,34 @@
+use velox_dom::layout::compute_layout;
+use velox_dom::{h, text};
+use velox_renderer::arena::with_frame_arena;
+use velox_renderer::display_list::ApproxMeasure;
+use velox_renderer::stats::count_nodes;
+
+#[test]
+fn counts_nodes_and_trims_the_frame_arena() {
+    let vnode = h(
+        "div",
+        vec![("style", "width:200px")],
+        (0..50)
+            .map(|i| {
+                h(
+                    "p",
+                    vec![("style", "color:#333333")],
+                    vec![text(format!("line {}", i))],
+                )
+            })
+            .collect(),
+    );
+    assert_eq!(count_nodes(&vnode), 101);
+
+    let layout = compute_layout(&vnode, 200, 2000);
+    with_frame_arena(|arena| {
+        arena.build_display_list(&vnode, &layout, &mut ApproxMeasure);
+    });
+    assert!(velox_renderer::stats().arena_bytes > 0);
+
+    velox_renderer::trim_caches();
+    let stats = velox_renderer::stats();
+    assert_eq!(stats.arena_bytes, 0);
+    assert_eq!(stats.readback_bytes, 0);
+}
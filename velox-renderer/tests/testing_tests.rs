@@ -0,0 +1,103 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use velox_dom::{Props, VNode, fragment, h, text};
+use velox_renderer::testing::TestApp;
+use velox_style::Stylesheet;
+
+fn counter(count: &Rc<Cell<i32>>) -> TestApp {
+    let view = {
+        let count = count.clone();
+        move |_w: u32, _h: u32| -> (VNode, Stylesheet) {
+            let root = h(
+                "div",
+                Props::new().set("class", "app"),
+                vec![
+                    h(
+                        "button",
+                        Props::new().set("class", "btn inc").set("on:click", "inc"),
+                        vec![text("+")],
+                    ),
+                    h(
+                        "span",
+                        Props::new().set("class", "count"),
+                        vec![text(count.get().to_string())],
+                    ),
+                ],
+            );
+            (
+                root,
+                Stylesheet::parse(".btn { width: 80px; height: 40px; }"),
+            )
+        }
+    };
+    let on_event = {
+        let count = count.clone();
+        move |name: &str, _payload: Option<&str>| {
+            if name == "inc" {
+                count.set(count.get() + 1);
+            }
+        }
+    };
+    TestApp::new(view, on_event)
+}
+
+#[test]
+fn clicks_are_hit_tested_and_rerender() {
+    let count = Rc::new(Cell::new(0));
+    let mut app = counter(&count);
+    assert_eq!(app.text_of(".app .count"), "0");
+    app.click("button.inc").click("button.inc");
+    assert_eq!(count.get(), 2);
+    assert_eq!(app.text_of("span.count"), "2");
+    assert_eq!(app.find_all("[on:click=inc]").len(), 1);
+    assert!(app.find("div .missing").is_none());
+}
+
+#[test]
+fn snapshots_are_written_then_compared() {
+    let path = std::env::temp_dir().join(format!("velox-snapshot-{}.txt", std::process::id()));
+    let count = Rc::new(Cell::new(0));
+    let mut app = counter(&count);
+    app.assert_snapshot(&path);
+    app.assert_snapshot(&path);
+    assert!(std::fs::read_to_string(&path).unwrap().contains("<button"));
+
+    app.emit("inc", None);
+    let changed =
+        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| app.assert_snapshot(&path)));
+    assert!(changed.is_err());
+    let _ = std::fs::remove_file(path.with_extension("actual.txt"));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn a_fragment_root_lays_out_styles_and_hit_tests_each_node() {
+    let count = Rc::new(Cell::new(0));
+    let view = |_w: u32, _h: u32| -> (VNode, Stylesheet) {
+        let root = fragment(vec![
+            h("header", Props::new(), vec![text("Title")]),
+            h(
+                "button",
+                Props::new().set("class", "btn").set("on:click", "inc"),
+                vec![text("+")],
+            ),
+        ]);
+        (
+            root,
+            Stylesheet::parse(".btn { width: 80px; height: 40px; }"),
+        )
+    };
+    let on_event = {
+        let count = count.clone();
+        move |name: &str, _payload: Option<&str>| {
+            if name == "inc" {
+                count.set(count.get() + 1);
+            }
+        }
+    };
+    let mut app = TestApp::new(view, on_event);
+    assert_eq!(app.text(), "Title +");
+    app.click("button.btn");
+    assert_eq!(count.get(), 1);
+}
@@ -0,0 +1,61 @@
+use velox_renderer::config::{Hinting, TextAntialias, TextQuality};
+
+#[test]
+fn aliased_coverage_is_binary_and_gamma_brightens_midtones() {
+    let aliased = TextQuality {
+        antialias: TextAntialias::Aliased,
+        ..TextQuality::default()
+    };
+    assert_eq!(aliased.adjust_coverage(0.4), 0.0);
+    assert_eq!(aliased.adjust_coverage(0.6), 1.0);
+
+    let plain = TextQuality::default();
+    assert_eq!(plain.adjust_coverage(0.25), 0.25);
+    let heavy = TextQuality {
+        gamma: 2.0,
+        ..TextQuality::default()
+    };
+    assert_eq!(heavy.adjust_coverage(0.25), 0.5);
+    assert_eq!(plain.hinting, Hinting::Slight);
+}
+
+#[cfg(feature = "raster")]
+#[test]
+fn raster_text_follows_antialias_mode() {
+    use velox_renderer::config::set_text_quality;
+    use velox_renderer::raster::render_to_image;
+
+    let vnode = velox_dom::h(
+        "div",
+        vec![(
+            "style",
+            "background-color:#FFFFFF;color:#000000;font-size:18px;width:120px;height:40px",
+        )],
+        vec![velox_dom::text("Velox")],
+    );
+    let render = |antialias| {
+        set_text_quality(TextQuality {
+            antialias,
+            ..TextQuality::default()
+        });
+        render_to_image(&vnode, 120, 40, 1.0).expect("render")
+    };
+
+    let aliased = render(TextAntialias::Aliased);
+    assert!(
+        aliased
+            .data
+            .chunks_exact(4)
+            .all(|p| p[0] == 0 || p[0] == 255)
+    );
+    assert!(aliased.data.chunks_exact(4).any(|p| p[0] == 0));
+
+    // LCD text has colour fringes: channels of an edge pixel differ.
+    let lcd = render(TextAntialias::Subpixel);
+    assert!(
+        lcd.data
+            .chunks_exact(4)
+            .any(|p| p[0] != p[1] || p[1] != p[2])
+    );
+    set_text_quality(TextQuality::default());
+}
@@ -0,0 +1,12 @@
+use velox_renderer::text_scale::{self, MAX_SCALE};
+use velox_style::{DEFAULT_ROOT_FONT_SIZE, root_font_size};
+
+#[test]
+fn apply_scales_the_rem_basis() {
+    text_scale::apply(1.25);
+    assert_eq!(root_font_size(), 20.0);
+    text_scale::apply(10.0);
+    assert_eq!(root_font_size(), DEFAULT_ROOT_FONT_SIZE * MAX_SCALE);
+    text_scale::apply(f32::NAN);
+    assert_eq!(root_font_size(), DEFAULT_ROOT_FONT_SIZE);
+}
@@ -0,0 +1,35 @@
+use velox_dom::{h, text};
+use velox_renderer::pipeline::take_title;
+
+#[test]
+fn take_title_removes_title_element_and_joins_its_text() {
+    let mut tree = h(
+        "div",
+        vec![],
+        vec![
+            h(
+                "section",
+                vec![],
+                vec![h(
+                    "title",
+                    vec![],
+                    vec![text("Count: "), text("3"), text("\n  items")],
+                )],
+            ),
+            h("p", vec![], vec![text("body")]),
+        ],
+    );
+    assert_eq!(take_title(&mut tree).as_deref(), Some("Count: 3 items"));
+    assert_eq!(
+        tree,
+        h(
+            "div",
+            vec![],
+            vec![
+                h("section", vec![], vec![]),
+                h("p", vec![], vec![text("body")])
+            ]
+        )
+    );
+    assert_eq!(take_title(&mut tree), None);
+}
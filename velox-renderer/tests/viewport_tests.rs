@@ -0,0 +1,220 @@
+use velox_dom::{Props, VNode, h};
+use velox_renderer::events::{HandlerTable, Targets};
+use velox_renderer::pen::PenPhase;
+use velox_renderer::viewport::{Pans, wheel};
+
+/// A 200x200 viewport whose content is moved 50px right and drawn at twice its size,
+/// with a 20x20 button at the content's top-left corner.
+fn map() -> VNode {
+    let button = h(
+        "div",
+        Props::new()
+            .set("on:click", "pin")
+            .set("style", "width: 20px; height: 20px"),
+        vec![],
+    );
+    let content = h(
+        "div",
+        Props::new().set("style", "width: 400px; height: 400px; transform-origin: 0 0; transform: translate(50px, 0) scale(2)"),
+        vec![button],
+    );
+    h(
+        "div",
+        Props::new()
+            .set("on:pan", "map")
+            .set("on:zoom", "map")
+            .set("style", "width: 200px; height: 200px; overflow: hidden"),
+        vec![content],
+    )
+}
+
+fn targets() -> Targets {
+    let vnode = velox_style::apply_styles(&map(), &velox_style::Stylesheet::default());
+    let layout = velox_renderer::pipeline::layout_tree(&vnode, 400, 300);
+    let mut targets = Targets::default();
+    targets.collect(&vnode, &layout, &HandlerTable::from_tree(&vnode));
+    targets
+}
+
+#[test]
+fn drags_off_click_targets_pan_and_presses_hit_the_drawn_content() {
+    let targets = targets();
+    let origin = targets.pan[0].rect;
+    let (ox, oy) = (origin.x as f32, origin.y as f32);
+    // The button is drawn at 50..90 across, not where its layout box is.
+    assert!(targets.click_at(ox + 10.0, oy + 10.0).is_none());
+    assert_eq!(
+        targets.click_at(ox + 80.0, oy + 30.0).map(|t| t.0),
+        Some("pin")
+    );
+
+    let mut pans = Pans::new();
+    let mut got = Vec::new();
+    let mut on_event = |handler: &str, payload: Option<&str>| {
+        got.push((handler.to_string(), payload.unwrap().to_string()))
+    };
+    assert!(
+        !pans.pointer(
+            1,
+            PenPhase::Down,
+            ox + 80.0,
+            oy + 30.0,
+            &targets,
+            &mut on_event
+        ),
+        "the button takes it"
+    );
+    assert!(!pans.pointer(
+        1,
+        PenPhase::Move,
+        ox + 90.0,
+        oy + 30.0,
+        &targets,
+        &mut on_event
+    ));
+    assert!(pans.pointer(
+        2,
+        PenPhase::Down,
+        ox + 150.0,
+        oy + 150.0,
+        &targets,
+        &mut on_event
+    ));
+    assert!(pans.is_dragging());
+    assert!(pans.pointer(
+        2,
+        PenPhase::Move,
+        ox + 140.0,
+        oy + 155.0,
+        &targets,
+        &mut on_event
+    ));
+    // Outside the viewport, the drag still pans it.
+    assert!(pans.pointer(
+        2,
+        PenPhase::Move,
+        ox + 340.0,
+        oy + 155.0,
+        &targets,
+        &mut on_event
+    ));
+    assert!(pans.pointer(
+        2,
+        PenPhase::Up,
+        ox + 340.0,
+        oy + 155.0,
+        &targets,
+        &mut on_event
+    ));
+    assert!(!pans.is_dragging());
+    assert!(!pans.pointer(
+        2,
+        PenPhase::Move,
+        ox + 300.0,
+        oy + 155.0,
+        &targets,
+        &mut on_event
+    ));
+
+    let got: Vec<&str> = got.iter().map(|(_, p)| p.as_str()).collect();
+    assert_eq!(got, [r#"{"dx":-10,"dy":5}"#, r#"{"dx":200,"dy":0}"#]);
+}
+
+#[test]
+fn the_wheel_pans_or_with_ctrl_zooms_the_viewport_under_it() {
+    let targets = targets();
+    let origin = targets.pan[0].rect;
+    let (x, y) = (origin.x as f32 + 100.0, origin.y as f32 + 40.0);
+    let mut got = Vec::new();
+    let mut on_event = |handler: &str, payload: Option<&str>| {
+        got.push((handler.to_string(), payload.unwrap().to_string()))
+    };
+    assert!(wheel((0.0, -40.0), false, (x, y), &targets, &mut on_event));
+    assert!(wheel((0.0, 300.0), true, (x, y), &targets, &mut on_event));
+    assert!(!wheel((0.0, 0.0), true, (x, y), &targets, &mut on_event));
+    assert!(
+        !wheel((0.0, -40.0), false, (390.0, 290.0), &targets, &mut on_event),
+        "nothing to pan there"
+    );
+
+    assert_eq!(
+        got[0],
+        ("map".to_string(), r#"{"dx":0,"dy":-40}"#.to_string())
+    );
+    assert_eq!(
+        got[1],
+        (
+            "map".to_string(),
+            r#"{"scale":2,"x":100,"y":40}"#.to_string()
+        )
+    );
+    assert_eq!(got.len(), 2);
+}
+
+#[test]
+fn a_pan_payload_names_the_dragged_element() {
+    let divider = |id: &str| {
+        h(
+            "div",
+            Props::new()
+                .set("on:pan", "split")
+                .set("on:pan-payload", id)
+                .set("style", "width: 4px; height: 100px"),
+            vec![],
+        )
+    };
+    let vnode = velox_style::apply_styles(
+        &h("div", Props::new(), vec![divider("0"), divider("a\"b")]),
+        &velox_style::Stylesheet::default(),
+    );
+    let layout = velox_renderer::pipeline::layout_tree(&vnode, 400, 300);
+    let mut targets = Targets::default();
+    targets.collect(&vnode, &layout, &HandlerTable::from_tree(&vnode));
+    let second = targets.pan[1].rect;
+
+    let mut pans = Pans::new();
+    let mut got = Vec::new();
+    let mut on_event = |_: &str, payload: Option<&str>| got.push(payload.unwrap().to_string());
+    let (x, y) = (second.x as f32 + 2.0, second.y as f32 + 50.0);
+    assert!(pans.pointer(1, PenPhase::Down, x, y, &targets, &mut on_event));
+    assert!(pans.pointer(1, PenPhase::Move, x + 30.0, y, &targets, &mut on_event));
+    assert_eq!(got, [r#"{"dx":30,"dy":0,"id":"a\"b"}"#]);
+}
+
+#[test]
+fn a_drag_ends_where_the_pointer_lifts() {
+    let tab = h(
+        "div",
+        Props::new()
+            .set("on:pan", "dock")
+            .set("on:pan-end", "dock")
+            .set("on:pan-payload", "tab")
+            .set("style", "width: 80px; height: 20px"),
+        vec![],
+    );
+    let vnode = velox_style::apply_styles(&tab, &velox_style::Stylesheet::default());
+    let layout = velox_renderer::pipeline::layout_tree(&vnode, 400, 300);
+    let mut targets = Targets::default();
+    targets.collect(&vnode, &layout, &HandlerTable::from_tree(&vnode));
+    let rect = targets.pan_end[0].rect;
+    let (x, y) = (rect.x as f32 + 10.0, rect.y as f32 + 10.0);
+
+    let mut pans = Pans::new();
+    let mut got = Vec::new();
+    let mut on_event = |_: &str, payload: Option<&str>| got.push(payload.unwrap().to_string());
+    assert!(pans.pointer(1, PenPhase::Down, x, y, &targets, &mut on_event));
+    assert!(pans.pointer(
+        1,
+        PenPhase::Move,
+        x + 200.0,
+        y + 50.0,
+        &targets,
+        &mut on_event
+    ));
+    assert!(pans.pointer(1, PenPhase::Up, 0.0, 0.0, &targets, &mut on_event));
+    assert!(pans.pointer(2, PenPhase::Down, x, y, &targets, &mut on_event));
+    assert!(pans.pointer(2, PenPhase::Cancel, x, y, &targets, &mut on_event));
+    let end = |dx: f32, dy: f32| format!(r#"{{"x":{},"y":{},"id":"tab""#, x + dx, y + dy);
+    assert_eq!(got[1], end(200.0, 50.0) + "}");
+    assert_eq!(got[2], end(0.0, 0.0) + r#","cancelled":true}"#);
+}
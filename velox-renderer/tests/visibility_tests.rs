@@ -0,0 +1,82 @@
+use velox_dom::layout::{Rect, compute_layout};
+use velox_dom::{VNode, h};
+use velox_renderer::refs::scroll_layout;
+use velox_renderer::visibility::{VisibilityEvent, VisibilityTracker};
+
+fn page() -> VNode {
+    h(
+        "div",
+        vec![],
+        vec![
+            h("div", vec![("style", "height: 500px")], vec![]),
+            h(
+                "div",
+                vec![
+                    ("on:visible", "seen"),
+                    ("on:hidden", "gone"),
+                    ("on:visible-payload", "7"),
+                    ("style", "height: 20px"),
+                ],
+                vec![],
+            ),
+            h(
+                "div",
+                vec![
+                    ("on:visible", "near"),
+                    ("visible-margin", "100"),
+                    ("style", "height: 20px"),
+                ],
+                vec![],
+            ),
+        ],
+    )
+}
+
+fn events(tracker: &mut VisibilityTracker, scroll: i32) -> Vec<String> {
+    let tree = page();
+    let mut layout = compute_layout(&tree, 200, 300);
+    scroll_layout(&mut layout, (0, scroll));
+    let viewport = Rect {
+        x: 0,
+        y: 0,
+        w: 200,
+        h: 300,
+    };
+    tracker
+        .update(&tree, &layout, viewport)
+        .into_iter()
+        .map(|e| e.handler)
+        .collect()
+}
+
+#[test]
+fn fires_on_enter_and_leave_with_margin() {
+    let mut tracker = VisibilityTracker::new();
+    assert!(events(&mut tracker, 0).is_empty());
+    // The margin reports the second element 100px before it is on screen.
+    assert_eq!(events(&mut tracker, 150), vec!["near"]);
+    assert_eq!(events(&mut tracker, 230), vec!["seen"]);
+    assert!(events(&mut tracker, 240).is_empty());
+    assert_eq!(events(&mut tracker, 0), vec!["gone"]);
+}
+
+#[test]
+fn payload_comes_from_the_payload_attribute() {
+    let mut tracker = VisibilityTracker::new();
+    let tree = page();
+    let layout = compute_layout(&tree, 200, 1000);
+    let viewport = Rect {
+        x: 0,
+        y: 0,
+        w: 200,
+        h: 1000,
+    };
+    let evs = tracker.update(&tree, &layout, viewport);
+    assert_eq!(
+        evs[0],
+        VisibilityEvent {
+            handler: "seen".into(),
+            payload: Some("7".into())
+        }
+    );
+}
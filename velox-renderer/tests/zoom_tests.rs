@@ -0,0 +1,47 @@
+use velox_core::settings::Settings;
+use velox_renderer::shortcuts::{Shortcut, Shortcuts};
+use velox_renderer::zoom::{self, MAX_ZOOM, MIN_ZOOM, SETTINGS_KEY};
+
+#[test]
+fn zoom_steps_through_levels_and_clamps() {
+    assert_eq!(zoom::zoom(), 1.0);
+    zoom::zoom_in();
+    assert_eq!(zoom::zoom(), 1.1);
+    zoom::zoom_out();
+    zoom::zoom_out();
+    assert_eq!(zoom::zoom(), 0.9);
+    zoom::set_zoom(10.0);
+    assert_eq!(zoom::zoom(), MAX_ZOOM);
+    zoom::zoom_in();
+    assert_eq!(zoom::zoom(), MAX_ZOOM);
+    zoom::set_zoom(0.1);
+    assert_eq!(zoom::zoom(), MIN_ZOOM);
+    zoom::reset_zoom();
+    assert_eq!(zoom::zoom(), 1.0);
+
+    let shortcuts = Shortcuts::new();
+    let resolve = |combo: &str| {
+        shortcuts
+            .resolve(&Shortcut::parse(combo).unwrap())
+            .map(|(h, _)| h)
+    };
+    assert_eq!(resolve("Ctrl+=").as_deref(), Some(zoom::ZOOM_IN));
+    assert_eq!(resolve("Ctrl+-").as_deref(), Some(zoom::ZOOM_OUT));
+    assert_eq!(resolve("Ctrl+0").as_deref(), Some(zoom::ZOOM_RESET));
+}
+
+#[test]
+fn zoom_is_restored_from_and_saved_to_settings() {
+    let path = std::env::temp_dir().join(format!("velox-zoom-test-{}.conf", std::process::id()));
+    let mut settings = Settings::load(&path);
+    settings.set(SETTINGS_KEY, 1.5);
+    zoom::persist_in(settings);
+    assert_eq!(zoom::zoom(), 1.5);
+
+    zoom::zoom_in();
+    assert_eq!(
+        Settings::load(&path).get_parsed::<f32>(SETTINGS_KEY),
+        Some(1.75)
+    );
+    let _ = std::fs::remove_file(&path);
+}
@@ -48,6 +48,26 @@ pub fn to_stub_rs(sfc: &Sfc, component_name: &str) -> String {
     out
 }
 
+/// The component's tests as a `#[cfg(test)]` module to place inside its generated module:
+/// the `<test>` block followed by an `include!` of each of `test_files` (absolute paths).
+/// `None` when there are no tests.
+pub fn to_test_rs(sfc: &Sfc, test_files: &[String]) -> Option<String> {
+    let inline = sfc.test.as_ref().map(|b| b.content.as_str()).unwrap_or("");
+    if inline.trim().is_empty() && test_files.is_empty() {
+        return None;
+    }
+    let mut out = String::new();
+    out.push_str("#[cfg(test)]\nmod velox_tests {\n    #![allow(unused_imports)]\n    use super::*;\n");
+    // User code goes in unindented so multi-line string literals keep their contents.
+    out.push_str(inline.trim_matches('\n'));
+    out.push('\n');
+    for file in test_files {
+        out.push_str(&format!("    include!({:?});\n", file));
+    }
+    out.push_str("}\n");
+    Some(out)
+}
+
 fn sanitize_ident(raw: &str) -> String {
     let mut out = String::with_capacity(raw.len());
     for (i, ch) in raw.chars().enumerate() {
@@ -0,0 +1,174 @@
+//! Machine-readable description of a component's interface, for tooling outside Rust.
+//!
+//! `velox build` writes one next to each generated module (`App.rs` gets `App.json`), so
+//! apps embedding velox views, editor plugins and docs generators can see what a
+//! component needs from its host without compiling it. It is an object with the
+//! component's `name` and three sorted, duplicate-free lists:
+//!
+//! - `props`: the names the template reads from its host through `resolve`, leaving out
+//!   loop variables and `state.*` paths (the component's own state);
+//! - `events`: the handler names it dispatches, `update:<name>` for each `v-model`;
+//! - `slots`: its `<slot>` elements, `default` for one without a `name`.
+
+use std::collections::BTreeSet;
+
+use crate::sfc::Sfc;
+use crate::template_ast::{AttrKind, Node};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ComponentInterface {
+    pub name: String,
+    pub props: Vec<String>,
+    pub events: Vec<String>,
+    pub slots: Vec<String>,
+}
+
+impl ComponentInterface {
+    /// The interface of a parsed component; fails if its template doesn't parse.
+    pub fn from_sfc(sfc: &Sfc, name: &str) -> Result<Self, String> {
+        let template = sfc
+            .template
+            .as_ref()
+            .map(|t| t.content.as_str())
+            .unwrap_or("");
+        let nodes = crate::template_parse::parse_template_to_ast(template)?;
+        Ok(Self::from_nodes(name, &nodes))
+    }
+
+    /// The interface of a template already parsed (and transformed by plugins).
+    pub fn from_nodes(name: &str, nodes: &[Node]) -> Self {
+        let mut found = Found::default();
+        for node in nodes {
+            found.visit(node, &mut Vec::new());
+        }
+        ComponentInterface {
+            name: name.to_string(),
+            props: found.props.into_iter().collect(),
+            events: found.events.into_iter().collect(),
+            slots: found.slots.into_iter().collect(),
+        }
+    }
+
+    /// Pretty-printed JSON, as in the module docs, with a trailing newline.
+    pub fn to_json(&self) -> String {
+        let list = |items: &[String]| {
+            let items: Vec<String> = items.iter().map(|s| json_string(s)).collect();
+            format!("[{}]", items.join(", "))
+        };
+        format!(
+            "{{\n  \"name\": {},\n  \"props\": {},\n  \"events\": {},\n  \"slots\": {}\n}}\n",
+            json_string(&self.name),
+            list(&self.props),
+            list(&self.events),
+            list(&self.slots)
+        )
+    }
+}
+
+#[derive(Default)]
+struct Found {
+    props: BTreeSet<String>,
+    events: BTreeSet<String>,
+    slots: BTreeSet<String>,
+}
+
+impl Found {
+    /// `locals` are the loop variables of the `v-for`s around `node`.
+    fn visit(&mut self, node: &Node, locals: &mut Vec<String>) {
+        match node {
+            Node::Text(_) => {}
+            Node::Interpolation(expr) => self.prop(expr, locals),
+            Node::Element {
+                tag,
+                attrs,
+                children,
+                ..
+            } => {
+                let scope = locals.len();
+                for a in attrs {
+                    let value = a.value.as_deref().unwrap_or("").trim();
+                    match (&a.kind, a.name.as_str()) {
+                        (AttrKind::On, _) | (AttrKind::Directive, "click-outside")
+                            if !value.is_empty() =>
+                        {
+                            self.events.insert(value.to_string());
+                        }
+                        (AttrKind::Bind, _) => {
+                            self.prop(if value.is_empty() { &a.name } else { value }, locals)
+                        }
+                        (AttrKind::Directive, "model") if !value.is_empty() => {
+                            self.props.insert(value.to_string());
+                            self.events.insert(format!("update:{}", value));
+                        }
+                        (AttrKind::Directive, "for") => {
+                            if let Some((vars, collection)) = value.split_once(" in ") {
+                                self.prop(collection, locals);
+                                let vars =
+                                    vars.trim().trim_start_matches('(').trim_end_matches(')');
+                                locals.extend(
+                                    vars.split(',')
+                                        .map(|v| v.trim().to_string())
+                                        .filter(|v| !v.is_empty()),
+                                );
+                            }
+                        }
+                        (AttrKind::Directive, "if" | "else-if" | "elseif" | "show") => {
+                            for ident in identifiers(value) {
+                                self.prop(ident, locals);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                if tag == "slot" {
+                    let name = attrs
+                        .iter()
+                        .find(|a| a.kind == AttrKind::Static && a.name == "name");
+                    self.slots.insert(
+                        name.and_then(|a| a.value.clone())
+                            .unwrap_or_else(|| "default".to_string()),
+                    );
+                }
+                for child in children {
+                    self.visit(child, locals);
+                }
+                locals.truncate(scope);
+            }
+        }
+    }
+
+    fn prop(&mut self, expr: &str, locals: &[String]) {
+        let expr = expr.trim();
+        let root = expr.split(['.', '[']).next().unwrap_or("");
+        if expr.is_empty() || root == "state" || locals.iter().any(|l| l == root) {
+            return;
+        }
+        self.props.insert(expr.to_string());
+    }
+}
+
+/// The bare identifiers of a condition, which the compiled template resolves from the
+/// host; dotted paths are left to Rust (see `rewrite_if_expr` in the codegen).
+fn identifiers(expr: &str) -> Vec<&str> {
+    expr.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.'))
+        .filter(|t| t.starts_with(|c: char| c.is_alphabetic() || c == '_') && !t.contains('.'))
+        .filter(|t| !matches!(*t, "true" | "false" | "resolve" | "state"))
+        .collect()
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -1,15 +1,21 @@
 pub mod codegen;
+pub mod interface;
+pub mod plugin;
+pub mod selectors;
 pub mod sfc;
 
 pub mod template_ast;
 pub mod template_codegen;
 pub mod template_parse;
 
-pub use sfc::{Attr, ScriptBlock, Sfc, StyleBlock, TemplateBlock, parse_sfc};
+pub use sfc::{Attr, ScriptBlock, Sfc, StyleBlock, TemplateBlock, TestBlock, parse_sfc};
 
-pub use template_ast::{AttrKind, Node, TemplateAttr};
-pub use template_codegen::compile_template_to_rs;
+pub use template_ast::{AttrKind, Node, TemplateAttr, to_template_string};
+pub use template_codegen::{compile_template_to_rs, compile_template_with_plugins};
+pub use interface::ComponentInterface;
+pub use selectors::TemplateSelectors;
+pub use plugin::{CompilerPlugin, register_plugin};
 pub use template_parse::parse_template_to_ast;
 
 // NEW: re-export so velox_sfc::to_stub_rs works in the CLI
-pub use codegen::to_stub_rs;
+pub use codegen::{to_stub_rs, to_test_rs};
@@ -0,0 +1,242 @@
+//! Compiler plugins: custom directives and blocks without forking the compiler.
+//!
+//! A `CompilerPlugin` hooks into three stages of compiling a component: the raw source
+//! before it is split into blocks, the template AST before code generation (where a
+//! directive plugin rewrites the attributes it owns, e.g. with `visit_elements`), and
+//! the generated Rust afterwards. Plugins are registered by name (`register_plugin`,
+//! usually from the app's build script before it calls `velox_cli::build_cmd`) and
+//! enabled per project by `build.plugins` in `velox.toml`, in the order they run.
+//!
+//! `markdown` is built in: a `<markdown>` element in a template becomes a
+//! `<div class="markdown">` of headings, paragraphs and lists, with `**strong**`,
+//! `*emphasis*` and `` `code` `` inline and `{{ interpolations }}` kept.
+
+use std::sync::{Arc, Mutex};
+
+use crate::template_ast::{AttrKind, Node, TemplateAttr};
+
+pub trait CompilerPlugin: Send + Sync {
+    /// The name `velox.toml` enables the plugin by.
+    fn name(&self) -> &str;
+
+    /// Rewrite the component source before it is parsed.
+    fn transform_source(&self, source: String) -> Result<String, String> {
+        Ok(source)
+    }
+
+    /// Rewrite the parsed template before code is generated for it.
+    fn transform_template(&self, nodes: &mut Vec<Node>) -> Result<(), String> {
+        let _ = nodes;
+        Ok(())
+    }
+
+    /// Rewrite the generated module of the component named `component`.
+    fn post_process(&self, component: &str, code: String) -> Result<String, String> {
+        let _ = component;
+        Ok(code)
+    }
+}
+
+static REGISTRY: Mutex<Vec<Arc<dyn CompilerPlugin>>> = Mutex::new(Vec::new());
+
+/// Make `plugin` available under its name, replacing a plugin registered with the same name.
+pub fn register_plugin(plugin: impl CompilerPlugin + 'static) {
+    let mut registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    registry.retain(|p| p.name() != plugin.name());
+    registry.push(Arc::new(plugin));
+}
+
+/// The registered plugin called `name`, built-ins included.
+pub fn find_plugin(name: &str) -> Option<Arc<dyn CompilerPlugin>> {
+    let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(p) = registry.iter().find(|p| p.name() == name) {
+        return Some(p.clone());
+    }
+    match name {
+        "markdown" => Some(Arc::new(Markdown)),
+        _ => None,
+    }
+}
+
+/// Names of the plugins that can be enabled, sorted.
+pub fn plugin_names() -> Vec<String> {
+    let registry = REGISTRY.lock().unwrap_or_else(|e| e.into_inner());
+    let mut names: Vec<String> = registry.iter().map(|p| p.name().to_string()).collect();
+    names.push("markdown".to_string());
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Look up `names` in order; unknown names are an error listing what is available.
+pub fn resolve_plugins(names: &[String]) -> Result<Vec<Arc<dyn CompilerPlugin>>, String> {
+    names
+        .iter()
+        .map(|name| {
+            find_plugin(name).ok_or_else(|| {
+                format!(
+                    "unknown compiler plugin `{}` (available: {})",
+                    name,
+                    plugin_names().join(", ")
+                )
+            })
+        })
+        .collect()
+}
+
+/// What `visit_elements` calls for each element: its tag, attributes and children.
+pub type ElementVisitor<'a> = dyn FnMut(&mut String, &mut Vec<TemplateAttr>, &mut Vec<Node>) + 'a;
+
+/// Call `f` with the tag, attributes and children of every element, parents first.
+pub fn visit_elements(nodes: &mut [Node], f: &mut ElementVisitor) {
+    for node in nodes {
+        if let Node::Element {
+            tag,
+            attrs,
+            children,
+            ..
+        } = node
+        {
+            f(tag, attrs, children);
+            visit_elements(children, f);
+        }
+    }
+}
+
+/// The built-in `markdown` plugin (see the module docs).
+pub struct Markdown;
+
+impl CompilerPlugin for Markdown {
+    fn name(&self) -> &str {
+        "markdown"
+    }
+
+    fn transform_template(&self, nodes: &mut Vec<Node>) -> Result<(), String> {
+        visit_elements(nodes, &mut |tag, attrs, children| {
+            if tag != "markdown" {
+                return;
+            }
+            *tag = "div".to_string();
+            match attrs
+                .iter_mut()
+                .find(|a| a.name == "class" && a.kind == AttrKind::Static)
+            {
+                Some(class) => {
+                    class.value = Some(format!("markdown {}", class.value.as_deref().unwrap_or("")))
+                }
+                None => attrs.push(TemplateAttr {
+                    name: "class".into(),
+                    value: Some("markdown".into()),
+                    kind: AttrKind::Static,
+                }),
+            }
+            *children = markdown_to_nodes(children);
+        });
+        Ok(())
+    }
+}
+
+/// Stands in for an interpolation while the text around it is parsed.
+const HOLE: char = '\u{0}';
+
+fn markdown_to_nodes(children: &[Node]) -> Vec<Node> {
+    let mut source = String::new();
+    let mut holes = Vec::new();
+    for child in children {
+        match child {
+            Node::Text(t) => source.push_str(t),
+            other => {
+                source.push(HOLE);
+                holes.push(other.clone());
+            }
+        }
+    }
+    let mut holes = holes.into_iter();
+    let mut out = Vec::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut list: Vec<Node> = Vec::new();
+    let flush = |paragraph: &mut Vec<&str>,
+                 list: &mut Vec<Node>,
+                 out: &mut Vec<Node>,
+                 holes: &mut dyn Iterator<Item = Node>| {
+        if !paragraph.is_empty() {
+            out.push(element("p", inline(&paragraph.join(" "), holes)));
+            paragraph.clear();
+        }
+        if !list.is_empty() {
+            out.push(element("ul", std::mem::take(list)));
+        }
+    };
+    for line in source.lines().map(str::trim) {
+        let heading = line.len() - line.trim_start_matches('#').len();
+        if line.is_empty() {
+            flush(&mut paragraph, &mut list, &mut out, &mut holes);
+        } else if (1..=6).contains(&heading) && line[heading..].starts_with(' ') {
+            flush(&mut paragraph, &mut list, &mut out, &mut holes);
+            out.push(element(
+                &format!("h{}", heading),
+                inline(line[heading..].trim(), &mut holes),
+            ));
+        } else if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            if !paragraph.is_empty() {
+                let mut none = Vec::new();
+                flush(&mut paragraph, &mut none, &mut out, &mut holes);
+            }
+            list.push(element("li", inline(item.trim(), &mut holes)));
+        } else {
+            if !list.is_empty() {
+                let mut none = Vec::new();
+                flush(&mut none, &mut list, &mut out, &mut holes);
+            }
+            paragraph.push(line);
+        }
+    }
+    flush(&mut paragraph, &mut list, &mut out, &mut holes);
+    out
+}
+
+/// Inline markup of one block: `**strong**`, `*em*`/`_em_`, `` `code` ``.
+fn inline(text: &str, holes: &mut dyn Iterator<Item = Node>) -> Vec<Node> {
+    let mut out = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let next = [("**", "strong"), ("`", "code"), ("*", "em"), ("_", "em")]
+            .iter()
+            .filter_map(|(marker, tag)| {
+                let start = rest.find(marker)?;
+                let end = rest[start + marker.len()..].find(marker)? + start + marker.len();
+                Some((start, end, *marker, *tag))
+            })
+            .min_by_key(|(start, _, marker, _)| (*start, std::cmp::Reverse(marker.len())));
+        let Some((start, end, marker, tag)) = next else {
+            push_text(&mut out, rest, holes);
+            break;
+        };
+        push_text(&mut out, &rest[..start], holes);
+        let mut inner = Vec::new();
+        push_text(&mut inner, &rest[start + marker.len()..end], holes);
+        out.push(element(tag, inner));
+        rest = &rest[end + marker.len()..];
+    }
+    out
+}
+
+fn push_text(out: &mut Vec<Node>, text: &str, holes: &mut dyn Iterator<Item = Node>) {
+    for (i, part) in text.split(HOLE).enumerate() {
+        if i > 0 {
+            out.extend(holes.next());
+        }
+        if !part.is_empty() {
+            out.push(Node::Text(part.to_string()));
+        }
+    }
+}
+
+fn element(tag: &str, children: Vec<Node>) -> Node {
+    Node::Element {
+        tag: tag.to_string(),
+        attrs: Vec::new(),
+        children,
+        self_closing: false,
+    }
+}
@@ -0,0 +1,119 @@
+//! Which CSS selectors a component's template can match, so `velox build` can leave the
+//! rules it never uses out of the component's `STYLE`.
+//!
+//! Every element the template can produce is recorded with its tag and static classes.
+//! The analysis errs on the side of keeping rules: an element with a `:class` binding may
+//! have any class, the children of `<transition name="x">` may have the `x-enter-*` and
+//! `x-leave-*` classes the renderer adds while they animate, and a template with a
+//! `<slot>` shows content from its host, so it can match every selector.
+//!
+//! Where an element sits is not tracked: a descendant selector like `.card .title` can
+//! match when some element can match `.card` and some element can match `.title`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::template_ast::{AttrKind, Node};
+
+/// The classes the renderer adds to the elements of a transition named `name`.
+fn transition_classes(name: &str) -> [String; 4] {
+    ["enter-from", "enter-active", "leave-active", "leave-to"]
+        .map(|phase| format!("{}-{}", name, phase))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Classes {
+    names: BTreeSet<String>,
+    /// Bound with `:class`: any class is possible.
+    any: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TemplateSelectors {
+    /// The classes each tag can have.
+    tags: BTreeMap<String, Classes>,
+    /// The template has a `<slot>`.
+    open: bool,
+}
+
+impl TemplateSelectors {
+    /// Analyze a template string; fails if it doesn't parse.
+    pub fn from_template(template: &str) -> Result<Self, String> {
+        Ok(Self::from_nodes(
+            &crate::template_parse::parse_template_to_ast(template)?,
+        ))
+    }
+
+    /// Analyze a template already parsed (and transformed by plugins).
+    pub fn from_nodes(nodes: &[Node]) -> Self {
+        let mut found = TemplateSelectors::default();
+        for node in nodes {
+            found.visit(node, None);
+        }
+        found
+    }
+
+    /// Whether content from outside the template (a `<slot>`) can be styled too, so
+    /// every selector may match.
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Whether some element can match a compound selector with `tag` (any tag when
+    /// `None`) and `class` (no class needed when `None`).
+    pub fn can_match(&self, tag: Option<&str>, class: Option<&str>) -> bool {
+        if self.open {
+            return true;
+        }
+        let has =
+            |classes: &Classes| class.is_none_or(|c| classes.any || classes.names.contains(c));
+        match tag {
+            Some(tag) => self.tags.get(tag).is_some_and(has),
+            None => self.tags.values().any(has),
+        }
+    }
+
+    /// `transition` is the name of the `<transition>` directly around `node`.
+    fn visit(&mut self, node: &Node, transition: Option<&str>) {
+        let Node::Element {
+            tag,
+            attrs,
+            children,
+            ..
+        } = node
+        else {
+            return;
+        };
+        if tag == "slot" {
+            self.open = true;
+        }
+        let static_attr = |name: &str| {
+            attrs
+                .iter()
+                .find(|a| a.kind == AttrKind::Static && a.name == name)
+                .and_then(|a| a.value.as_deref())
+        };
+        if tag == "transition" {
+            let name = static_attr("name").unwrap_or("v");
+            for child in children {
+                self.visit(child, Some(name));
+            }
+            return;
+        }
+        let classes = self.tags.entry(tag.clone()).or_default();
+        classes.names.extend(
+            static_attr("class")
+                .unwrap_or("")
+                .split_whitespace()
+                .map(str::to_string),
+        );
+        classes.any |= attrs
+            .iter()
+            .any(|a| a.kind == AttrKind::Bind && a.name == "class");
+        if let Some(name) = transition.or(static_attr("data-transition")) {
+            classes.names.extend(transition_classes(name));
+        }
+        for child in children {
+            self.visit(child, None);
+        }
+    }
+}
@@ -30,12 +30,21 @@ pub struct StyleBlock {
     pub content: String,
 }
 
+/// Component tests: Rust test functions compiled into the component's module under
+/// `#[cfg(test)]`, run by `velox test`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TestBlock {
+    pub attrs: Vec<Attr>,
+    pub content: String,
+}
+
 #[derive(Debug, Clone, PartialEq, Default)]
 pub struct Sfc {
     pub template: Option<TemplateBlock>,
     pub script_setup: Option<ScriptBlock>,
     pub script: Option<ScriptBlock>,
     pub style: Option<StyleBlock>,
+    pub test: Option<TestBlock>,
 }
 
 pub fn parse_sfc(source: &str) -> Result<Sfc, String> {
@@ -56,7 +65,7 @@ pub fn parse_sfc(source: &str) -> Result<Sfc, String> {
                 }
             }
             // (Defensive: in case grammar changes and blocks appear directly)
-            Rule::template | Rule::script | Rule::style => {
+            Rule::template | Rule::script | Rule::style | Rule::test => {
                 consume_top_level(node, &mut sfc);
             }
             _ => {}
@@ -90,6 +99,10 @@ fn consume_top_level(node: Pair<Rule>, sfc: &mut Sfc) {
             let (attrs, content) = parse_style(node);
             sfc.style = Some(StyleBlock { attrs, content });
         }
+        Rule::test => {
+            let (attrs, content) = parse_test(node);
+            sfc.test = Some(TestBlock { attrs, content });
+        }
         _ => {}
     }
 }
@@ -155,6 +168,26 @@ fn parse_style(sty: Pair<Rule>) -> (Vec<Attr>, String) {
     (attrs, content)
 }
 
+fn parse_test(test: Pair<Rule>) -> (Vec<Attr>, String) {
+    let mut attrs = Vec::new();
+    let mut content = String::new();
+
+    for p in test.into_inner() {
+        match p.as_rule() {
+            Rule::test_open => {
+                for a in p.into_inner() {
+                    if a.as_rule() == Rule::attribute {
+                        attrs.push(parse_attr(a));
+                    }
+                }
+            }
+            Rule::test_body => content = p.as_str().to_string(),
+            _ => {}
+        }
+    }
+    (attrs, content)
+}
+
 fn parse_attr(attr: Pair<Rule>) -> Attr {
     // attribute = ident ( "=" quoted )?
     let mut name = String::new();
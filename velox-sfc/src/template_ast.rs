@@ -24,3 +24,72 @@ pub enum Node {
     Text(String),
     Interpolation(String), // {{ expr }}
 }
+
+impl Node {
+    /// Canonical markup for this node, which `parse_template_to_ast` reads back as the same
+    /// node. Attributes keep their order and shorthand (`:x`, `@x`, `v-x`); values are
+    /// double-quoted unless they contain `"`. Whitespace-only text (which the parser keeps
+    /// as a single space) is written as a line break and indentation, so a tree of elements
+    /// comes out one per line; other text is written as is.
+    pub fn to_template_string(&self) -> String {
+        let mut out = String::new();
+        self.write_template(&mut out, 0);
+        out
+    }
+
+    fn write_template(&self, out: &mut String, depth: usize) {
+        match self {
+            Node::Text(t) => out.push_str(t),
+            Node::Interpolation(expr) => {
+                out.push_str("{{ ");
+                out.push_str(expr.trim());
+                out.push_str(" }}");
+            }
+            Node::Element { tag, attrs, children, self_closing } => {
+                out.push('<');
+                out.push_str(tag);
+                for a in attrs {
+                    out.push(' ');
+                    out.push_str(match a.kind {
+                        AttrKind::Static => "",
+                        AttrKind::Bind => ":",
+                        AttrKind::On => "@",
+                        AttrKind::Directive => "v-",
+                    });
+                    out.push_str(&a.name);
+                    if let Some(value) = &a.value {
+                        let quote = if value.contains('"') { '\'' } else { '"' };
+                        out.push('=');
+                        out.push(quote);
+                        out.push_str(value);
+                        out.push(quote);
+                    }
+                }
+                if *self_closing {
+                    out.push_str(" />");
+                    return;
+                }
+                out.push('>');
+                for (i, child) in children.iter().enumerate() {
+                    match child {
+                        Node::Text(t) if !t.is_empty() && t.chars().all(char::is_whitespace) => {
+                            // Before the closing tag, line up with the opening one.
+                            let indent = if i + 1 == children.len() { depth } else { depth + 1 };
+                            out.push('\n');
+                            out.push_str(&"  ".repeat(indent));
+                        }
+                        child => child.write_template(out, depth + 1),
+                    }
+                }
+                out.push_str("</");
+                out.push_str(tag);
+                out.push('>');
+            }
+        }
+    }
+}
+
+/// `Node::to_template_string` for a whole template, one root per line.
+pub fn to_template_string(nodes: &[Node]) -> String {
+    nodes.iter().map(Node::to_template_string).collect::<Vec<_>>().join("\n")
+}
@@ -1,22 +1,45 @@
 use crate::template_ast::{AttrKind, Node, TemplateAttr};
 use std::collections::HashSet;
 
-/// Public API: compile `<template>` string to a Rust module body with `render()`.
-pub fn compile_template_to_rs(template_src: &str, _component_name: &str) -> Result<String, String> {
-    let nodes = crate::template_parse::parse_template_to_ast(template_src)?;
+/// Public API: compile `<template>` string to a Rust module body with `render()`, and
+/// `render_component` for registering it as a component whose props feed its bindings.
+pub fn compile_template_to_rs(template_src: &str, component_name: &str) -> Result<String, String> {
+    compile_template_with_plugins(template_src, component_name, &[])
+}
+
+/// `compile_template_to_rs`, with each plugin's `transform_template` applied to the parsed
+/// template in order.
+pub fn compile_template_with_plugins(
+    template_src: &str,
+    _component_name: &str,
+    plugins: &[std::sync::Arc<dyn crate::plugin::CompilerPlugin>],
+) -> Result<String, String> {
+    let mut nodes = crate::template_parse::parse_template_to_ast(template_src)?;
+    for plugin in plugins {
+        plugin.transform_template(&mut nodes).map_err(|e| format!("plugin `{}`: {}", plugin.name(), e))?;
+    }
+    let nodes = expand_transitions(nodes);
     if nodes.is_empty() {
         return Ok(format!(
             r#"pub fn render() -> velox_dom::VNode {{
     use velox_dom::*;
     text("")
+}}
+
+pub fn render_component(_props: &velox_dom::Props, _children: &[velox_dom::VNode]) -> velox_dom::VNode {{
+    render()
 }}"#
         ));
     }
 
-    // For MVP, assume a single root node.
-    let root = &nodes[0];
-    let body_with = emit_node_with(root);
-    let body_with_state = emit_node_with_state(root);
+    // Several root nodes render as a fragment of them.
+    let (body_with, body_with_state) = match nodes.as_slice() {
+        [root] => (emit_node_with(root), emit_node_with_state(root)),
+        roots => (
+            format!("fragment({})", emit_children_with(roots)),
+            format!("fragment({})", emit_children_with_state(roots)),
+        ),
+    };
 
     let mut out = format!(
         r#"pub fn render() -> velox_dom::VNode {{
@@ -26,6 +49,10 @@ pub fn compile_template_to_rs(template_src: &str, _component_name: &str) -> Resu
 pub fn render_with<F>(mut resolve: F) -> velox_dom::VNode where F: FnMut(&str) -> String {{
     use velox_dom::*;
     {body_with}
+}}
+
+pub fn render_component(props: &velox_dom::Props, _children: &[velox_dom::VNode]) -> velox_dom::VNode {{
+    render_with(|key| props.get(key).map(|v| v.to_string()).unwrap_or_default())
 }}"#,
         body_with = body_with
     );
@@ -42,14 +69,49 @@ pub fn render_with<F>(mut resolve: F) -> velox_dom::VNode where F: FnMut(&str) -
 
     // Collect event handler names from the template and generate a helper
     let handlers = collect_handlers(&nodes);
-    if !handlers.is_empty() {
+    let models = collect_models(&nodes);
+    if !handlers.is_empty() || !models.is_empty() {
+        out.push_str("\n\n");
+        out.push_str(&generate_make_on_event(&handlers, &models));
         out.push_str("\n\n");
-        out.push_str(&generate_make_on_event(&handlers));
+        out.push_str(&generate_handler_table(&nodes));
     }
 
     Ok(out)
 }
 
+/// Replace each `<transition name="x">` wrapper with its element children, marked
+/// `data-transition="x"` for the renderer's enter/leave tracking, and compile `v-show`
+/// like `v-if`: hidden content leaves the tree (there is no `display: none`).
+fn expand_transitions(nodes: Vec<Node>) -> Vec<Node> {
+    let mut out = Vec::with_capacity(nodes.len());
+    for n in nodes {
+        match n {
+            Node::Element { tag, attrs, children, .. } if tag == "transition" => {
+                let name = attrs
+                    .iter()
+                    .find(|a| a.name == "name" && matches!(a.kind, AttrKind::Static))
+                    .and_then(|a| a.value.clone())
+                    .unwrap_or_else(|| "v".to_string());
+                for child in expand_transitions(children) {
+                    if let Node::Element { tag, mut attrs, children, self_closing } = child {
+                        attrs.push(TemplateAttr { name: "data-transition".into(), value: Some(name.clone()), kind: AttrKind::Static });
+                        out.push(Node::Element { tag, attrs, children, self_closing });
+                    }
+                }
+            }
+            Node::Element { tag, mut attrs, children, self_closing } => {
+                for a in attrs.iter_mut().filter(|a| matches!(a.kind, AttrKind::Directive) && a.name == "show") {
+                    a.name = "if".into();
+                }
+                out.push(Node::Element { tag, attrs, children: expand_transitions(children), self_closing });
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
 fn collect_handlers(nodes: &[Node]) -> Vec<String> {
     let mut set: HashSet<String> = HashSet::new();
     fn walk(n: &Node, set: &mut HashSet<String>) {
@@ -75,13 +137,51 @@ fn collect_handlers(nodes: &[Node]) -> Vec<String> {
     v
 }
 
-fn generate_make_on_event(handlers: &[String]) -> String {
+/// The `v-model` targets of a template that name a `State` field (`email`, `form.email`),
+/// sorted. Other expressions are left to the app to route.
+fn collect_models(nodes: &[Node]) -> Vec<String> {
+    fn is_field_path(expr: &str) -> bool {
+        !expr.is_empty()
+            && expr.split('.').all(|part| {
+                part.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                    && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            })
+    }
+    fn walk(n: &Node, set: &mut HashSet<String>) {
+        if let Node::Element { attrs, children, .. } = n {
+            for a in attrs {
+                if matches!(a.kind, AttrKind::Directive) && a.name == "model"
+                    && let Some(v) = &a.value
+                    && is_field_path(v.trim())
+                {
+                    set.insert(v.trim().to_string());
+                }
+            }
+            for c in children {
+                walk(c, set);
+            }
+        }
+    }
+    let mut set = HashSet::new();
+    for n in nodes { walk(n, &mut set); }
+    let mut v: Vec<String> = set.into_iter().collect();
+    v.sort();
+    v
+}
+
+fn generate_make_on_event(handlers: &[String], models: &[String]) -> String {
     // Generate a simple dispatch helper that calls methods on `app::script_rs::State`.
     // This assumes methods are zero-arg; handling payloads or arity will be added later.
     let mut arms = String::new();
     for h in handlers {
         arms.push_str(&format!("        \"{name}\" => {{ state.{name}(); }},\n", name = h));
     }
+    // `v-model` fields are `velox_core::model::Model`s (or signals): they take the input.
+    for m in models {
+        arms.push_str(&format!(
+            "        \"update:{m}\" => {{ velox_core::model::Bind::bind_input(&state.{m}, _payload.unwrap_or(\"\")); }},\n"
+        ));
+    }
 
     format!(
         r#"pub fn make_on_event(state: std::sync::Arc<script_rs::State>) -> impl FnMut(&str, Option<&str>) + 'static {{
@@ -95,6 +195,90 @@ fn generate_make_on_event(handlers: &[String]) -> String {
     )
 }
 
+fn has_directive(attrs: &[TemplateAttr], names: &[&str]) -> bool {
+    attrs.iter().any(|a| matches!(a.kind, AttrKind::Directive) && names.contains(&a.name.as_str()))
+}
+
+/// `(node key, event, handler)` for the handlers of the tree `render` builds from `roots`,
+/// where a node key is the path of child indices from the root (the fragment, when there
+/// are several roots). Only elements whose position doesn't depend on data are listed:
+/// those on or under a `v-if` chain branch or a `v-for` item, and those after a `v-for`
+/// among their siblings, are left out.
+fn collect_static_handlers(roots: &[Node]) -> Vec<(Vec<usize>, String, String)> {
+    fn visit(node: &Node, path: &mut Vec<usize>, out: &mut Vec<(Vec<usize>, String, String)>) {
+        let Node::Element { attrs, children, .. } = node else { return };
+        for a in attrs {
+            let value = a.value.clone().unwrap_or_default();
+            match a.kind {
+                AttrKind::On => out.push((path.clone(), a.name.clone(), value)),
+                AttrKind::Directive if a.name == "model" => {
+                    out.push((path.clone(), "input".into(), format!("update:{}", value.trim())))
+                }
+                AttrKind::Directive if a.name == "click-outside" => {
+                    out.push((path.clone(), "click-outside".into(), value.trim().to_string()))
+                }
+                _ => {}
+            }
+        }
+        visit_children(children, path, out);
+    }
+
+    // Walk the children as `emit_children_with` emits them: a `v-if` chain is one node
+    // whichever branch is taken, and a `v-for` any number of them.
+    fn visit_children(children: &[Node], path: &mut Vec<usize>, out: &mut Vec<(Vec<usize>, String, String)>) {
+        let mut i = 0;
+        let mut index = 0;
+        while i < children.len() {
+            let attrs = match &children[i] {
+                Node::Element { attrs, .. } => attrs.as_slice(),
+                _ => &[],
+            };
+            if has_directive(attrs, &["for"]) {
+                break;
+            }
+            if has_directive(attrs, &["if"]) {
+                i += 1;
+                while let Some(Node::Element { attrs, .. }) = children.get(i) {
+                    if has_directive(attrs, &["else-if", "elseif"]) {
+                        i += 1;
+                    } else {
+                        if has_directive(attrs, &["else"]) {
+                            i += 1;
+                        }
+                        break;
+                    }
+                }
+            } else {
+                path.push(index);
+                visit(&children[i], path, out);
+                path.pop();
+                i += 1;
+            }
+            index += 1;
+        }
+    }
+
+    let mut out = Vec::new();
+    match roots {
+        [root @ Node::Element { attrs, .. }] if !has_directive(attrs, &["if", "for"]) => {
+            visit(root, &mut Vec::new(), &mut out)
+        }
+        [_] => {}
+        roots => visit_children(roots, &mut Vec::new(), &mut out),
+    }
+    out
+}
+
+fn generate_handler_table(roots: &[Node]) -> String {
+    // Seeds `velox_renderer::events::HandlerTable::from_static` without building the tree.
+    let mut entries = String::new();
+    for (path, event, handler) in collect_static_handlers(roots) {
+        let path: Vec<String> = path.iter().map(usize::to_string).collect();
+        entries.push_str(&format!("    (&[{}], {}, {}),\n", path.join(", "), string_lit(&event), string_lit(&handler)));
+    }
+    format!("pub const HANDLERS: &[(&[usize], &str, &str)] = &[\n{}];", entries)
+}
+
 pub(crate) fn emit_node(n: &Node) -> String {
     match n {
         Node::Text(t) => format!(r#"text({})"#, string_lit(t)),
@@ -110,11 +294,20 @@ pub(crate) fn emit_node(n: &Node) -> String {
         } => {
             let props = emit_props(attrs);
             let kids = emit_children(children);
-            format!(r#"h("{}", {props}, {kids})"#, tag)
+            emit_element(tag, &props, &kids)
         }
     }
 }
 
+/// An element, or a component use when the tag is capitalized (`<TodoItem :item="x"/>`).
+fn emit_element(tag: &str, props: &str, kids: &str) -> String {
+    if tag.starts_with(|c: char| c.is_ascii_uppercase()) {
+        format!(r#"component("{}", {props}, {kids})"#, tag)
+    } else {
+        format!(r#"h("{}", {props}, {kids})"#, tag)
+    }
+}
+
 pub(crate) fn emit_props(attrs: &[TemplateAttr]) -> String {
     if attrs.is_empty() {
         return "Props::new()".to_string();
@@ -128,11 +321,14 @@ pub(crate) fn emit_props(attrs: &[TemplateAttr]) -> String {
             }
             AttrKind::Bind => {
                 let expr = a.value.clone().unwrap_or_else(|| a.name.clone());
-                parts.push(format!(
-                    r#".set("{}", &format!("{{}}", {}))"#,
-                    a.name,
-                    expr.trim()
-                ));
+                match literal_value(&expr) {
+                    Some(value) => parts.push(format!(r#".set("{}", {})"#, a.name, value)),
+                    None => parts.push(format!(
+                        r#".set("{}", &format!("{{}}", {}))"#,
+                        a.name,
+                        expr.trim()
+                    )),
+                }
             }
             AttrKind::Directive => {
                 // directives are not emitted as props
@@ -250,7 +446,7 @@ fn emit_node_with(n: &Node) -> String {
 
             let props = emit_props_with(attrs);
             let kids = emit_children_with(children);
-            format!(r#"h("{}", {props}, {kids})"#, tag)
+            emit_element(tag, &props, &kids)
         }
     }
 }
@@ -266,8 +462,26 @@ fn emit_props_with(attrs: &[TemplateAttr]) -> String {
             }
             AttrKind::Bind => {
                 let expr = a.value.clone().unwrap_or_else(|| a.name.clone());
-                let key = string_lit(expr.trim());
-                parts.push(format!(r#".set("{}", &resolve({}))"#, a.name, key));
+                match literal_value(&expr) {
+                    Some(value) => parts.push(format!(r#".set("{}", {})"#, a.name, value)),
+                    None => parts.push(format!(r#".set("{}", &resolve({}))"#, a.name, string_lit(expr.trim()))),
+                }
+            }
+            AttrKind::Directive if a.name == "model" => {
+                // `v-model="x"`: bind `value` and dispatch `update:x` with the new value.
+                let model = a.value.clone().unwrap_or_default();
+                parts.push(format!(r#".set("value", &resolve({}))"#, string_lit(model.trim())));
+                parts.push(format!(r#".set("on:input", {})"#, string_lit(&format!("update:{}", model.trim()))));
+            }
+            AttrKind::Directive if a.name == "shortcut" => {
+                // `v-shortcut="Ctrl+S"`: the renderer binds it to this element's handler.
+                let combo = a.value.clone().unwrap_or_default();
+                parts.push(format!(r#".set("shortcut", {})"#, string_lit(combo.trim())));
+            }
+            AttrKind::Directive if a.name == "click-outside" => {
+                // `v-click-outside="close"`: the renderer dispatches it on presses outside the element.
+                let handler = a.value.clone().unwrap_or_default();
+                parts.push(format!(r#".set("on:click-outside", {})"#, string_lit(handler.trim())));
             }
             AttrKind::Directive => {
                 // do not emit directives as props
@@ -490,7 +704,7 @@ fn emit_node_with_state(n: &Node) -> String {
         Node::Element { tag, attrs, children, .. } => {
             let props = emit_props_with(attrs);
             let kids = emit_children_with_state(children);
-            format!(r#"h("{}", {props}, {kids})"#, tag)
+            emit_element(tag, &props, &kids)
         }
     }
 }
@@ -516,7 +730,7 @@ fn emit_node_with_ctx_state(n: &Node, item_name: Option<&str>, idx_name: Option<
                 k_items.push(emit_node_with_ctx_state(c, item_name, idx_name));
             }
             let kids = format!("vec![{}]", k_items.join(", "));
-            format!(r#"h("{}", {props}, {kids})"#, tag)
+            emit_element(tag, &props, &kids)
         }
     }
 }
@@ -543,11 +757,21 @@ fn emit_node_with_ctx(n: &Node, loop_var: Option<&str>) -> String {
                 }
                 format!("vec![{}]", k_items.join(", "))
             };
-            format!(r#"h("{}", {props}, {kids})"#, tag)
+            emit_element(tag, &props, &kids)
         }
     }
 }
 
+/// The Rust for a bound flag or number literal (`:disabled="true"`, `:step="0.5"`), which
+/// is set as a typed `PropValue` instead of resolved as text.
+fn literal_value(expr: &str) -> Option<String> {
+    let expr = expr.trim();
+    match expr {
+        "true" | "false" => Some(expr.to_string()),
+        _ => expr.parse::<f64>().ok().filter(|n| n.is_finite()).map(|n| format!("{:?}f64", n)),
+    }
+}
+
 fn string_lit(s: &str) -> String {
     // Basic escape for quotes and backslashes; good enough for tests
     let mut out = String::with_capacity(s.len() + 8);
@@ -0,0 +1,99 @@
+//! Golden-file tests for SFC codegen: every `tests/golden/*.vx` fixture is compiled and the
+//! generated Rust compared with the checked-in `<name>.golden` next to it. After an
+//! intended codegen change, regenerate with `VELOX_UPDATE_GOLDEN=1 cargo test -p velox-sfc
+//! --test golden_tests` and review the golden diff like any other code change.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use velox_sfc::{compile_template_to_rs, parse_sfc, to_stub_rs};
+
+/// Stub module followed by the render function, or the error text for invalid input.
+fn generate(src: &str, name: &str) -> String {
+    let sfc = match parse_sfc(src) {
+        Ok(sfc) => sfc,
+        Err(e) => return format!("// parse error\n{}\n", e),
+    };
+    let mut out = to_stub_rs(&sfc, name);
+    out.push_str("\n// ---- render ----\n");
+    let template = sfc
+        .template
+        .as_ref()
+        .map(|t| t.content.as_str())
+        .unwrap_or("");
+    match compile_template_to_rs(template, name) {
+        Ok(code) => out.push_str(&code),
+        Err(e) => out.push_str(&format!("// template error\n{}\n", e)),
+    }
+    out
+}
+
+fn fixtures(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .expect("read tests/golden")
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "vx"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn first_difference(expected: &str, actual: &str) -> String {
+    let mut exp = expected.lines();
+    let mut act = actual.lines();
+    for line in 1.. {
+        match (exp.next(), act.next()) {
+            (Some(e), Some(a)) if e == a => continue,
+            (e, a) => {
+                return format!(
+                    "line {}:\n  golden: {}\n  actual: {}",
+                    line,
+                    e.unwrap_or("<eof>"),
+                    a.unwrap_or("<eof>")
+                );
+            }
+        }
+    }
+    unreachable!()
+}
+
+#[test]
+fn sfc_codegen_matches_golden_files() {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden");
+    let update = std::env::var_os("VELOX_UPDATE_GOLDEN").is_some();
+    let paths = fixtures(&dir);
+    assert!(!paths.is_empty(), "no .vx fixtures in {}", dir.display());
+
+    let mut failures = Vec::new();
+    for path in paths {
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .expect("fixture name");
+        let src = fs::read_to_string(&path)
+            .expect("read fixture")
+            .replace("\r\n", "\n");
+        let actual = generate(&src, name);
+        let golden = path.with_extension("golden");
+        if update {
+            fs::write(&golden, &actual).expect("write golden");
+            continue;
+        }
+        match fs::read_to_string(&golden) {
+            Ok(expected) if expected.replace("\r\n", "\n") == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{}: {}",
+                name,
+                first_difference(&expected, &actual)
+            )),
+            Err(_) => failures.push(format!("{}: missing {}", name, golden.display())),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "codegen differs from golden files (set VELOX_UPDATE_GOLDEN=1 to accept):\n{}",
+        failures.join("\n")
+    );
+}
@@ -0,0 +1,37 @@
+use velox_sfc::{ComponentInterface, parse_sfc};
+
+#[test]
+fn interface_lists_props_events_and_slots() {
+    let sfc = parse_sfc(
+        r#"<template>
+  <div class="item" v-click-outside="blur">
+    <input v-model="title" />
+    <span v-if="done && count > 0">{{ label }}</span>
+    <ul><li v-for="tag in tags" :title="tag">{{ tag }}</li></ul>
+    <span>{{ state.internal }}</span>
+    <button @click="remove">x</button>
+    <slot></slot>
+    <slot name="actions"></slot>
+  </div>
+</template>"#,
+    )
+    .unwrap();
+    let interface = ComponentInterface::from_sfc(&sfc, "TodoItem").unwrap();
+    assert_eq!(interface.props, ["count", "done", "label", "tags", "title"]);
+    assert_eq!(interface.events, ["blur", "remove", "update:title"]);
+    assert_eq!(interface.slots, ["actions", "default"]);
+}
+
+#[test]
+fn interface_json_is_stable_and_escaped() {
+    let interface = ComponentInterface {
+        name: "Say \"hi\"".into(),
+        props: vec!["a".into(), "b".into()],
+        events: vec![],
+        slots: vec!["default".into()],
+    };
+    assert_eq!(
+        interface.to_json(),
+        "{\n  \"name\": \"Say \\\"hi\\\"\",\n  \"props\": [\"a\", \"b\"],\n  \"events\": [],\n  \"slots\": [\"default\"]\n}\n"
+    );
+}
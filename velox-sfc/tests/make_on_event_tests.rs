@@ -10,3 +10,15 @@ fn emits_make_on_event_helper() {
     assert!(out.contains("\"inc\""), "handler 'inc' not emitted");
     assert!(out.contains("\"dec\""), "handler 'dec' not emitted");
 }
+
+#[test]
+fn emits_static_handler_table() {
+    let src = r#"<div @keydown="nav"><p v-if="busy"><button @click="cancel">Stop</button></p><button @click="inc">Inc</button><input v-model="name"/><li v-for="i in items" @click="pick">{{ i }}</li><button @click="dec">Dec</button></div>"#;
+    let out = template_codegen::compile_template_to_rs(src, "App").expect("compile");
+    let table = out.split("pub const HANDLERS").nth(1).expect("HANDLERS table missing");
+    assert!(table.contains(r#"(&[], "keydown", "nav")"#), "{}", table);
+    assert!(table.contains(r#"(&[1], "click", "inc")"#), "{}", table);
+    assert!(table.contains(r#"(&[2], "input", "update:name")"#), "{}", table);
+    // Inside a `v-if` branch or from a `v-for` on, positions depend on data.
+    assert!(!table.contains("cancel") && !table.contains("pick") && !table.contains("dec"), "{}", table);
+}
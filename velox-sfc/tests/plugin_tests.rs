@@ -0,0 +1,97 @@
+use velox_sfc::plugin::{Markdown, resolve_plugins, visit_elements};
+use velox_sfc::{
+    AttrKind, CompilerPlugin, Node, compile_template_with_plugins, parse_template_to_ast,
+    register_plugin,
+};
+
+struct Tooltip;
+
+impl CompilerPlugin for Tooltip {
+    fn name(&self) -> &str {
+        "tooltip"
+    }
+
+    fn transform_template(&self, nodes: &mut Vec<Node>) -> Result<(), String> {
+        visit_elements(nodes, &mut |_, attrs, _| {
+            for a in attrs
+                .iter_mut()
+                .filter(|a| a.kind == AttrKind::Directive && a.name == "tooltip")
+            {
+                a.kind = AttrKind::Static;
+                a.name = "title".into();
+            }
+        });
+        Ok(())
+    }
+
+    fn post_process(&self, component: &str, code: String) -> Result<String, String> {
+        Ok(format!("// tooltips: {}\n{}", component, code))
+    }
+}
+
+#[test]
+fn registered_plugins_add_directives() {
+    register_plugin(Tooltip);
+    let plugins = resolve_plugins(&["tooltip".to_string()]).expect("registered");
+    let code =
+        compile_template_with_plugins(r#"<button v-tooltip="Save">S</button>"#, "App", &plugins)
+            .unwrap();
+    assert!(code.contains(r#".set("title", "Save")"#), "{}", code);
+    assert!(
+        plugins[0]
+            .post_process("App", String::new())
+            .unwrap()
+            .starts_with("// tooltips: App")
+    );
+
+    let err = resolve_plugins(&["emoji".to_string()]).err().unwrap();
+    assert!(
+        err.contains("unknown compiler plugin `emoji`") && err.contains("markdown, tooltip"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn markdown_blocks_become_elements() {
+    let src = "<markdown class=\"intro\">\n  # Hello {{ name }}\n\n  Some **bold** and `code`.\n  - one\n  - *two*\n</markdown>";
+    let mut nodes = parse_template_to_ast(src).unwrap();
+    Markdown.transform_template(&mut nodes).unwrap();
+    let Node::Element {
+        tag,
+        attrs,
+        children,
+        ..
+    } = &nodes[0]
+    else {
+        panic!("expected element")
+    };
+    assert_eq!(tag, "div");
+    assert_eq!(attrs[0].value.as_deref(), Some("markdown intro"));
+    let tags: Vec<_> = children
+        .iter()
+        .map(|c| match c {
+            Node::Element { tag, .. } => tag.as_str(),
+            _ => "#text",
+        })
+        .collect();
+    assert_eq!(tags, ["h1", "p", "ul"]);
+    let Node::Element {
+        children: heading, ..
+    } = &children[0]
+    else {
+        unreachable!()
+    };
+    assert_eq!(
+        heading,
+        &[
+            Node::Text("Hello ".into()),
+            Node::Interpolation("name".into())
+        ]
+    );
+    let Node::Element { children: para, .. } = &children[1] else {
+        unreachable!()
+    };
+    assert!(matches!(&para[1], Node::Element { tag, .. } if tag == "strong"));
+    assert!(matches!(&para[3], Node::Element { tag, .. } if tag == "code"));
+}
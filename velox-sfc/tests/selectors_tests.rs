@@ -0,0 +1,35 @@
+use velox_sfc::TemplateSelectors;
+
+#[test]
+fn records_the_tags_and_classes_a_template_can_produce() {
+    let selectors = TemplateSelectors::from_template(
+        r#"<div class="card wide">
+  <h2 class="title">{{ title }}</h2>
+  <li v-for="item in items" :class="item.kind">{{ item.name }}</li>
+  <transition name="fade"><p v-if="open">Hi</p></transition>
+</div>"#,
+    )
+    .unwrap();
+    assert!(!selectors.is_open());
+    assert!(selectors.can_match(Some("div"), Some("wide")));
+    assert!(selectors.can_match(None, Some("title")));
+    assert!(
+        !selectors.can_match(Some("div"), Some("title")),
+        "the title class is on the h2"
+    );
+    assert!(
+        selectors.can_match(Some("li"), Some("anything")),
+        "`:class` may bind any class"
+    );
+    assert!(selectors.can_match(Some("p"), Some("fade-enter-from")));
+    assert!(
+        !selectors.can_match(Some("transition"), None),
+        "the wrapper is not rendered"
+    );
+    assert!(!selectors.can_match(Some("span"), None));
+
+    let slotted =
+        TemplateSelectors::from_template(r#"<section class="panel"><slot /></section>"#).unwrap();
+    assert!(slotted.is_open());
+    assert!(slotted.can_match(Some("span"), Some("from-the-host")));
+}
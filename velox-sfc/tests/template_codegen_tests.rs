@@ -28,3 +28,30 @@ fn codegen_attrs() {
     assert!(rs.contains(r#".set("value", &resolve("count"))"#));
     assert!(rs.contains(r#".set("on:input", "onInput")"#));
 }
+
+#[test]
+fn codegen_literal_bindings_are_typed() {
+    let rs = compile_template_to_rs(r#"<input :disabled="true" :step="0.5" :max="10" :value="count"/>"#, "App").unwrap();
+    assert!(rs.contains(r#".set("disabled", true)"#), "{}", rs);
+    assert!(rs.contains(r#".set("step", 0.5f64)"#), "{}", rs);
+    assert!(rs.contains(r#".set("max", 10.0f64)"#), "{}", rs);
+    assert!(rs.contains(r#".set("value", &resolve("count"))"#), "{}", rs);
+}
+
+#[test]
+fn codegen_several_roots_render_a_fragment() {
+    let rs = compile_template_to_rs(r#"<header>Title</header><main @click="open">Body</main>"#, "App").unwrap();
+    assert!(rs.contains("fragment("), "{}", rs);
+    assert!(rs.contains(r#"h("header""#) && rs.contains(r#"h("main""#));
+    // Handler paths start at the fragment.
+    assert!(rs.contains(r#"(&[1], "click", "open")"#), "{}", rs);
+}
+
+#[test]
+fn codegen_capitalized_tags_are_components() {
+    let rs = compile_template_to_rs(r#"<ul><TodoItem :item="first" /></ul>"#, "TodoList").unwrap();
+    assert!(rs.contains(r#"component("TodoItem", Props::new().set("item", &resolve("first")), vec![])"#), "{}", rs);
+    assert!(rs.contains(r#"h("ul""#));
+    // Every template can itself be registered as a component.
+    assert!(rs.contains("pub fn render_component(props: &velox_dom::Props"), "{}", rs);
+}
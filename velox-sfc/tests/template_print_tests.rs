@@ -0,0 +1,103 @@
+use velox_sfc::{AttrKind, Node, TemplateAttr, parse_template_to_ast, to_template_string};
+
+#[test]
+fn prints_canonical_markup_that_parses_back() {
+    let src = r#"<div class='app'   :title="title" @click="inc" v-if="open" disabled>
+      <span>Count: {{count}}</span>
+      <input v-model="name"/>
+      <p title='say "hi"'>a <b>b</b></p>
+    </div>"#;
+    let ast = parse_template_to_ast(src).unwrap();
+    let printed = to_template_string(&ast);
+    assert_eq!(
+        printed,
+        r#"<div class="app" :title="title" @click="inc" v-if="open" disabled>
+  <span>Count: {{ count }}</span>
+  <input v-model="name" />
+  <p title='say "hi"'>a <b>b</b></p>
+</div>"#
+    );
+    assert_eq!(parse_template_to_ast(&printed).unwrap(), ast);
+}
+
+/// Small deterministic generator, so failures reproduce from the seed in the message.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self, n: u64) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
+
+    fn word(&mut self) -> String {
+        let words = ["a", "item", "count", "x-y", "on_off", "b2"];
+        words[self.next(words.len() as u64) as usize].to_string()
+    }
+
+    fn element(&mut self, depth: u32) -> Node {
+        let kinds = [
+            AttrKind::Static,
+            AttrKind::Bind,
+            AttrKind::On,
+            AttrKind::Directive,
+        ];
+        let attrs = (0..self.next(4))
+            .map(|_| {
+                let kind = kinds[self.next(4) as usize].clone();
+                let value = match self.next(3) {
+                    0 => None,
+                    1 => Some(format!("{} {}", self.word(), self.word())),
+                    _ => Some(format!("say \"{}\"", self.word())),
+                };
+                // The parser normalizes directive names to kebab-case.
+                let name = if kind == AttrKind::Directive {
+                    self.word().replace('_', "-")
+                } else {
+                    self.word()
+                };
+                TemplateAttr { name, value, kind }
+            })
+            .collect();
+        let self_closing = depth > 2 || self.next(5) == 0;
+        let mut children = Vec::new();
+        if !self_closing {
+            for _ in 0..self.next(5) {
+                // The parser merges adjacent text, so never generate two in a row.
+                let after_text = matches!(children.last(), Some(Node::Text(_)));
+                children.push(match self.next(4) {
+                    0 if !after_text => Node::Text(" ".into()),
+                    1 if !after_text => Node::Text(format!("{} {}!", self.word(), self.word())),
+                    2 => Node::Interpolation(self.word()),
+                    _ => self.element(depth + 1),
+                });
+            }
+        }
+        Node::Element {
+            tag: self.word(),
+            attrs,
+            children,
+            self_closing,
+        }
+    }
+}
+
+#[test]
+fn random_trees_round_trip() {
+    for seed in 0..500 {
+        let mut rng = Rng(seed);
+        let ast: Vec<Node> = (0..1 + rng.next(3)).map(|_| rng.element(0)).collect();
+        let printed = to_template_string(&ast);
+        let reparsed =
+            parse_template_to_ast(&printed).unwrap_or_else(|e| panic!("seed {}: {}", seed, e));
+        assert_eq!(reparsed, ast, "seed {}:\n{}", seed, printed);
+        assert_eq!(
+            to_template_string(&reparsed),
+            printed,
+            "seed {}: printing is not stable",
+            seed
+        );
+    }
+}
@@ -0,0 +1,19 @@
+use velox_sfc::{parse_sfc, to_test_rs};
+
+#[test]
+fn test_block_is_parsed_and_wrapped_in_a_cfg_test_module() {
+    let src = "<template><div/></template>\n<test>\n#[test]\nfn renders() {}\n</test>\n";
+    let sfc = parse_sfc(src).expect("parse");
+    assert!(sfc.test.as_ref().unwrap().content.contains("fn renders()"));
+
+    let tests = to_test_rs(&sfc, &["/app/src/App.test.rs".to_string()]).expect("tests");
+    assert!(tests.starts_with("#[cfg(test)]\nmod velox_tests {"));
+    assert!(tests.contains("#[test]\nfn renders() {}"));
+    assert!(tests.contains("include!(\"/app/src/App.test.rs\");"));
+}
+
+#[test]
+fn components_without_tests_get_no_module() {
+    let sfc = parse_sfc("<template><div/></template>").expect("parse");
+    assert_eq!(to_test_rs(&sfc, &[]), None);
+}
@@ -0,0 +1,17 @@
+use velox_sfc::compile_template_to_rs;
+
+#[test]
+fn transition_marks_children_and_drops_wrapper() {
+    let tpl = r#"<div><transition name="fade"><p v-if="open">Hi</p></transition></div>"#;
+    let rs = compile_template_to_rs(tpl, "App").unwrap();
+    assert!(!rs.contains(r#"h("transition""#));
+    assert!(rs.contains(r#".set("data-transition", "fade")"#));
+    assert!(rs.contains(r#"if (!resolve("open").is_empty())"#));
+}
+
+#[test]
+fn v_show_compiles_like_v_if() {
+    let rs = compile_template_to_rs(r#"<div><p v-show="open">Hi</p></div>"#, "App").unwrap();
+    assert!(rs.contains(r#"if (!resolve("open").is_empty())"#));
+    assert!(rs.contains(r#"text("")"#));
+}
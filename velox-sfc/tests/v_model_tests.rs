@@ -0,0 +1,45 @@
+use velox_sfc::compile_template_to_rs;
+
+#[test]
+fn v_model_binds_value_and_update_handler() {
+    let rs = compile_template_to_rs(r#"<div><date-picker v-model="due" /></div>"#, "App").unwrap();
+    assert!(rs.contains(r#".set("value", &resolve("due")).set("on:input", "update:due")"#));
+    assert!(
+        !rs.contains("state.update:due"),
+        "model updates go to the field, not a method named after the event"
+    );
+}
+
+#[test]
+fn v_model_fields_take_input_in_make_on_event() {
+    let rs = compile_template_to_rs(
+        r#"<form><input v-model="form.email" /><input v-model="items[0]" /></form>"#,
+        "App",
+    )
+    .unwrap();
+    assert!(rs.contains(r#""update:form.email" => { velox_core::model::Bind::bind_input(&state.form.email, _payload.unwrap_or("")); }"#), "{}", rs);
+    assert!(
+        !rs.contains("update:items[0]\" =>"),
+        "expressions that aren't fields are left to the app"
+    );
+}
+
+#[test]
+fn v_shortcut_becomes_a_shortcut_attribute() {
+    let rs = compile_template_to_rs(
+        r#"<div><button @click="save" v-shortcut="Ctrl+S">Save</button></div>"#,
+        "App",
+    )
+    .unwrap();
+    assert!(rs.contains(r#".set("on:click", "save").set("shortcut", "Ctrl+S")"#));
+}
+
+#[test]
+fn v_click_outside_becomes_a_click_outside_handler() {
+    let rs = compile_template_to_rs(
+        r#"<div v-click-outside="close"><button @click="toggle">Menu</button></div>"#,
+        "App",
+    )
+    .unwrap();
+    assert!(rs.contains(r#".set("on:click-outside", "close")"#));
+}
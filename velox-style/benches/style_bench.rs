@@ -0,0 +1,84 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+
+use velox_dom::{VNode, h, text};
+use velox_style::{Stylesheet, apply_styles};
+
+/// `n` rules: a mix of tag, class, and tag.class selectors, a few with `:hover`.
+fn sheet_with_rules(n: usize) -> Stylesheet {
+    let mut css = String::new();
+    for i in 0..n {
+        let selector = match i % 4 {
+            0 => format!(".c{}", i),
+            1 => format!("div.c{}", i),
+            2 => format!(".c{}:hover", i),
+            _ => ["div", "span", "p", "button"][i / 4 % 4].to_string(),
+        };
+        css.push_str(&format!(
+            "{} {{ color: #{:06X}; padding: {}px; margin-bottom: 1px; }}\n",
+            selector,
+            i * 997 % 0xFFFFFF,
+            i % 8
+        ));
+    }
+    Stylesheet::parse(&css)
+}
+
+/// ~1k nodes whose classes hit a spread of the rules.
+fn tree(rules: usize) -> VNode {
+    let rows = (0..100)
+        .map(|r| {
+            let cells = (0..4)
+                .map(|c| {
+                    let class = format!(
+                        "c{} c{}",
+                        (r * 4 + c) % rules.max(1),
+                        (r + c * 7) % rules.max(1)
+                    );
+                    h("span", vec![("class", class.as_str())], vec![text("cell")])
+                })
+                .collect();
+            let class = format!("c{}", r % rules.max(1));
+            h("div", vec![("class", class.as_str())], cells)
+        })
+        .collect();
+    h("div", vec![("class", "root")], rows)
+}
+
+/// An app-wide sheet of `n` component rules (`.cN`, `.cN .title`, `button.cN:hover`) of
+/// which a view uses a few, the case the cascade's rule index is for.
+fn shared_sheet(n: usize) -> Stylesheet {
+    let mut css = String::from("div { margin-bottom: 1px; } span { font-size: 12px; }\n");
+    for i in 0..n / 3 {
+        css.push_str(&format!(".c{i} {{ padding: {}px; }}\n.c{i} .title {{ color: #333333; }}\nbutton.c{i}:hover {{ color: #FFFFFF; }}\n", i % 8));
+    }
+    Stylesheet::parse(&css)
+}
+
+fn bench_apply_styles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("apply_styles_1k_nodes");
+    for &rules in &[10usize, 100, 500, 2000] {
+        let sheet = sheet_with_rules(rules);
+        let vnode = tree(rules);
+        group.bench_with_input(BenchmarkId::from_parameter(rules), &vnode, |b, v| {
+            b.iter(|| apply_styles(v, &sheet))
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("apply_styles_1k_nodes_shared_sheet");
+    let vnode = tree(10);
+    for &rules in &[30usize, 300, 3000] {
+        let sheet = shared_sheet(rules);
+        group.bench_with_input(BenchmarkId::from_parameter(rules), &vnode, |b, v| {
+            b.iter(|| apply_styles(v, &sheet))
+        });
+    }
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().without_plots();
+    targets = bench_apply_styles
+}
+criterion_main!(benches);
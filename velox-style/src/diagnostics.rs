@@ -0,0 +1,182 @@
+//! Warnings for stylesheet input that `Stylesheet::parse` drops or cannot honor.
+//!
+//! Positions are 1-based lines and columns into the parsed text. Declaration warnings
+//! point at the declaration's value, selector warnings at the start of the rule.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// A property missing from `properties::PROPERTIES`.
+    UnknownProperty,
+    /// A selector outside the supported subset (`tag`, `.class`, `tag.class`, `:hover`,
+    /// descendant combinators).
+    BadSelector,
+    /// A `{` without its `}` or a stray `}`.
+    UnbalancedBraces,
+    /// A declaration or rule cssparser rejected (e.g. a missing `:`).
+    InvalidSyntax,
+    /// An at-rule other than `@media`, such as `@supports`; its contents are skipped.
+    UnsupportedAtRule,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub message: String,
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+/// Which checks `Stylesheet::parse_with_options` runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticOptions {
+    pub unknown_properties: bool,
+    /// Accepted in addition to `properties::PROPERTIES` (e.g. properties an app reads itself).
+    pub extra_properties: Vec<String>,
+    pub selectors: bool,
+}
+
+impl Default for DiagnosticOptions {
+    fn default() -> Self {
+        DiagnosticOptions {
+            unknown_properties: true,
+            extra_properties: Vec::new(),
+            selectors: true,
+        }
+    }
+}
+
+impl DiagnosticOptions {
+    pub(crate) fn is_known_property(&self, name: &str) -> bool {
+        name.starts_with("--")
+            || crate::properties::property(name).is_some()
+            || self
+                .extra_properties
+                .iter()
+                .any(|p| p.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Why a single comma-separated selector falls outside the supported subset, if it does.
+pub(crate) fn selector_problem(selector: &str) -> Option<String> {
+    let sel = selector.trim();
+    if sel.is_empty() {
+        return Some("empty selector in selector list".into());
+    }
+    if sel.contains(['>', '+', '~']) {
+        return Some(format!(
+            "'{}': only descendant combinators are supported",
+            sel
+        ));
+    }
+    if sel.contains('&') {
+        return Some(format!("'{}': '&' is only allowed in nested rules", sel));
+    }
+    sel.split_whitespace()
+        .find_map(|compound| compound_problem(sel, compound))
+}
+
+fn compound_problem(sel: &str, compound: &str) -> Option<String> {
+    if compound.contains('#') {
+        return Some(format!("'{}': id selectors are not supported", sel));
+    }
+    if compound.contains('[') {
+        return Some(format!("'{}': attribute selectors are not supported", sel));
+    }
+    if compound.contains('*') {
+        return Some(format!(
+            "'{}': the universal selector is not supported",
+            sel
+        ));
+    }
+    let (base, pseudo) = match compound.split_once(':') {
+        Some((base, pseudo)) => (base, Some(pseudo)),
+        None => (compound, None),
+    };
+    if let Some(pseudo) = pseudo.filter(|p| *p != "hover") {
+        return Some(format!(
+            "'{}': only ':hover' is supported, not ':{}'",
+            sel, pseudo
+        ));
+    }
+    if base.matches('.').count() > 1 {
+        return Some(format!(
+            "'{}': only one class per selector is supported",
+            sel
+        ));
+    }
+    if base.is_empty() || base.ends_with('.') {
+        return Some(format!("'{}': missing tag or class name", sel));
+    }
+    None
+}
+
+/// Report every stray `}` and every `{` left open at the end of `css`. Comments and
+/// quoted strings are skipped.
+pub(crate) fn check_braces(css: &str, out: &mut Vec<Diagnostic>) {
+    let mut open: Vec<(u32, u32)> = Vec::new();
+    let (mut line, mut column) = (1u32, 0u32);
+    let mut chars = css.chars().peekable();
+    let mut quote: Option<char> = None;
+    let mut in_comment = false;
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+            column = 0;
+        } else {
+            column += 1;
+        }
+        if in_comment {
+            if c == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                column += 1;
+                in_comment = false;
+            }
+            continue;
+        }
+        if let Some(q) = quote {
+            if c == '\\' {
+                if chars.next() == Some('\n') {
+                    line += 1;
+                    column = 0;
+                } else {
+                    column += 1;
+                }
+            } else if c == q || c == '\n' {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                column += 1;
+                in_comment = true;
+            }
+            '"' | '\'' => quote = Some(c),
+            '{' => open.push((line, column)),
+            '}' if open.pop().is_none() => out.push(Diagnostic {
+                kind: DiagnosticKind::UnbalancedBraces,
+                message: "unmatched '}'".into(),
+                line,
+                column,
+            }),
+            _ => {}
+        }
+    }
+    for (line, column) in open {
+        out.push(Diagnostic {
+            kind: DiagnosticKind::UnbalancedBraces,
+            message: "'{' is never closed".into(),
+            line,
+            column,
+        });
+    }
+}
@@ -1,161 +1,507 @@
 use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU32, Ordering};
 
-use cssparser::{Parser, ParserInput, RuleListParser, ToCss};
+use cssparser::{
+    BasicParseErrorKind, Delimiter, ParseError, ParseErrorKind, Parser, ParserInput, RuleListParser, SourceLocation,
+    ToCss, Token,
+};
 use velox_dom::{VNode, Props};
 
+pub mod diagnostics;
+pub mod properties;
+
+pub use diagnostics::{Diagnostic, DiagnosticKind, DiagnosticOptions};
+pub use properties::PropertyInfo;
+
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SimpleSelectorKind { Tag, Class, TagClass }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SimpleSelector {
     pub kind: SimpleSelectorKind,
     pub tag: String,
     pub class: String,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub hover: bool,
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rule {
     pub selector: SimpleSelector,
+    /// Descendant-combinator context (`.card .title`): each must match some ancestor,
+    /// outermost first.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub ancestors: Vec<SimpleSelector>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "velox_dom::wire::serialize_sorted"))]
     pub decls: HashMap<String, String>,
+    /// `!important` declarations, with the flag stripped from the value.
+    #[cfg_attr(feature = "serde", serde(default, serialize_with = "velox_dom::wire::serialize_sorted"))]
+    pub important: HashMap<String, String>,
+    /// Queries of the enclosing `@media` blocks, all of which must match (see
+    /// `media_matches`). Empty for rules that apply to every medium.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub media: Vec<String>,
+}
+
+impl Rule {
+    /// CSS specificity as (ids, classes and pseudo-classes, tags).
+    pub fn specificity(&self) -> (u32, u32, u32) {
+        let mut spec = (0, 0, 0);
+        for sel in self.ancestors.iter().chain(std::iter::once(&self.selector)) {
+            match sel.kind {
+                SimpleSelectorKind::Tag => spec.2 += 1,
+                SimpleSelectorKind::Class => spec.1 += 1,
+                SimpleSelectorKind::TagClass => {
+                    spec.1 += 1;
+                    spec.2 += 1;
+                }
+            }
+            if sel.hover {
+                spec.1 += 1;
+            }
+        }
+        spec
+    }
+}
+
+/// The selector as CSS: `div`, `.card`, `li.done`, with `:hover` when set.
+impl fmt::Display for SimpleSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            SimpleSelectorKind::Tag => f.write_str(&self.tag)?,
+            SimpleSelectorKind::Class => write!(f, ".{}", self.class)?,
+            SimpleSelectorKind::TagClass => write!(f, "{}.{}", self.tag, self.class)?,
+        }
+        if self.hover {
+            f.write_str(":hover")?;
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Stylesheet {
     pub rules: Vec<Rule>,
 }
 
 impl Stylesheet {
+    /// Parse `css`, silently skipping anything outside the supported subset.
     pub fn parse(css: &str) -> Self {
-        struct SheetParser {
-            rules: Vec<Rule>,
-        }
-
-        impl<'i> cssparser::QualifiedRuleParser<'i> for &mut SheetParser {
-            type Prelude = String;
-            type QualifiedRule = ();
-            type Error = ();
-
-            fn parse_prelude<'t>(
-                &mut self,
-                input: &mut Parser<'i, 't>,
-            ) -> Result<Self::Prelude, cssparser::ParseError<'i, Self::Error>> {
-                let mut selector = String::new();
-                while let Ok(token) = input.next_including_whitespace() {
-                    let _ = token.to_css(&mut selector);
+        Stylesheet { rules: parse_rules(css, None) }
+    }
+
+    /// Parse `css` and report what was skipped or will have no effect.
+    pub fn parse_with_diagnostics(css: &str) -> (Self, Vec<Diagnostic>) {
+        Self::parse_with_options(css, &DiagnosticOptions::default())
+    }
+
+    /// Like `parse_with_diagnostics`, with the checks selected by `options`.
+    pub fn parse_with_options(css: &str, options: &DiagnosticOptions) -> (Self, Vec<Diagnostic>) {
+        let mut diagnostics = Vec::new();
+        diagnostics::check_braces(css, &mut diagnostics);
+        let rules = parse_rules(css, Some((options, &mut diagnostics)));
+        diagnostics.sort_by_key(|d| (d.line, d.column));
+        (Stylesheet { rules }, diagnostics)
+    }
+
+    /// The rules that apply on `medium` (`"print"`, `"screen"`), with their media
+    /// conditions resolved. Applying a sheet directly styles for `screen`.
+    pub fn for_media(&self, medium: &str) -> Stylesheet {
+        let rules = self
+            .rules
+            .iter()
+            .filter(|r| r.media.iter().all(|q| media_matches(q, medium)))
+            .map(|r| Rule { media: Vec::new(), ..r.clone() })
+            .collect();
+        Stylesheet { rules }
+    }
+
+    /// CSS that `parse` reads back as this stylesheet, one rule per line, in order.
+    /// Declarations are sorted by property.
+    pub fn to_css(&self) -> String {
+        let mut out = String::new();
+        for rule in &self.rules {
+            for query in &rule.media {
+                out.push_str(&format!("@media {} {{ ", query));
+            }
+            for ancestor in &rule.ancestors {
+                out.push_str(&format!("{} ", ancestor));
+            }
+            out.push_str(&format!("{} {{", rule.selector));
+            let mut decls: Vec<(&String, &String, &str)> = rule.decls.iter().map(|(k, v)| (k, v, "")).collect();
+            decls.extend(rule.important.iter().map(|(k, v)| (k, v, " !important")));
+            decls.sort();
+            for (name, value, flag) in decls {
+                out.push_str(&format!(" {}: {}{};", name, value, flag));
+            }
+            out.push_str(" }");
+            out.push_str(&" }".repeat(rule.media.len()));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Whether the media query list `query` (as in `@media print, screen`) matches
+/// `medium`. Media types, `only`, `not`, and `all` are understood; queries with feature
+/// conditions like `(max-width: 600px)` never match, since there is no viewport to test.
+pub fn media_matches(query: &str, medium: &str) -> bool {
+    query.split(',').any(|q| {
+        let mut words: Vec<&str> = q.split_whitespace().collect();
+        let negated = words.first().is_some_and(|w| w.eq_ignore_ascii_case("not"));
+        if negated || words.first().is_some_and(|w| w.eq_ignore_ascii_case("only")) {
+            words.remove(0);
+        }
+        let mut parts = words.split(|w| w.eq_ignore_ascii_case("and"));
+        let Some([kind]) = parts.next() else { return false };
+        if parts.next().is_some() {
+            return false;
+        }
+        let matched = kind.eq_ignore_ascii_case("all") || kind.eq_ignore_ascii_case(medium);
+        matched != negated
+    })
+}
+
+fn diagnostic(kind: DiagnosticKind, message: String, location: SourceLocation) -> Diagnostic {
+    // cssparser lines are 0-based, columns 1-based.
+    Diagnostic { kind, message, line: location.line + 1, column: location.column }
+}
+
+fn parse_error_diagnostic(error: &ParseError<'_, ()>, what: &str) -> Diagnostic {
+    match &error.kind {
+        ParseErrorKind::Basic(BasicParseErrorKind::AtRuleInvalid(name)) => {
+            // The error is raised after the name; point at the `@` instead.
+            let mut at = error.location;
+            at.column = at.column.saturating_sub(name.len() as u32 + 1).max(1);
+            diagnostic(DiagnosticKind::UnsupportedAtRule, format!("unsupported at-rule '@{}' skipped", name), at)
+        }
+        _ => diagnostic(DiagnosticKind::InvalidSyntax, format!("invalid {} skipped", what), error.location),
+    }
+}
+
+type Declarations = HashMap<String, String>;
+
+/// The value without its `!important` flag, if it has one.
+fn strip_important(value: &str) -> Option<&str> {
+    let value = value.trim_end();
+    let split = value.len().checked_sub("important".len())?;
+    if !value.is_char_boundary(split) || !value[split..].eq_ignore_ascii_case("important") {
+        return None;
+    }
+    value[..split].trim_end().strip_suffix('!').map(str::trim_end)
+}
+
+fn parse_rules(css: &str, diagnostics: Option<(&DiagnosticOptions, &mut Vec<Diagnostic>)>) -> Vec<Rule> {
+    struct SheetParser<'d> {
+        rules: Vec<Rule>,
+        diagnostics: Option<(&'d DiagnosticOptions, &'d mut Vec<Diagnostic>)>,
+        /// Queries of the `@media` blocks being parsed.
+        media: Vec<String>,
+    }
+
+    impl SheetParser<'_> {
+        fn report(&mut self, kind: DiagnosticKind, message: String, location: SourceLocation) {
+            if let Some((_, out)) = self.diagnostics.as_mut() {
+                out.push(diagnostic(kind, message, location));
+            }
+        }
+
+        /// Parse one rule's block. Nested rules are flattened into `self.rules` after it,
+        /// so they win over the parent's declarations like later rules do.
+        fn parse_rule<'i>(&mut self, selectors: Vec<String>, location: SourceLocation, input: &mut Parser<'i, '_>) {
+            if let Some((options, out)) = self.diagnostics.as_mut()
+                && options.selectors
+            {
+                for message in selectors.iter().filter_map(|s| diagnostics::selector_problem(s)) {
+                    out.push(diagnostic(DiagnosticKind::BadSelector, message, location));
                 }
-                Ok(selector.trim().to_string())
             }
+            let index = self.rules.len();
+            let (decls, important) = self.parse_body(&selectors, input);
+            if decls.is_empty() && important.is_empty() {
+                return;
+            }
+            let rules: Vec<Rule> = selectors
+                .iter()
+                .filter_map(|s| parse_complex_selector(s))
+                .map(|(ancestors, selector)| Rule {
+                    selector,
+                    ancestors,
+                    decls: decls.clone(),
+                    important: important.clone(),
+                    media: self.media.clone(),
+                })
+                .collect();
+            self.rules.splice(index..index, rules);
+        }
 
-            fn parse_block<'t>(
-                &mut self,
-                prelude: Self::Prelude,
-                _start: &cssparser::ParserState,
-                input: &mut Parser<'i, 't>,
-            ) -> Result<Self::QualifiedRule, cssparser::ParseError<'i, Self::Error>> {
-                let mut decls = HashMap::new();
-                for decl in cssparser::DeclarationListParser::new(input, DeclarationParser) {
-                    if let Ok((name, value)) = decl {
-                        if !name.is_empty() {
-                            decls.insert(name, value);
+        /// Declarations and nested rules, in any order. Anything that is not a valid
+        /// declaration and is followed by a `{}` block is a nested rule.
+        fn parse_body<'i>(&mut self, selectors: &[String], input: &mut Parser<'i, '_>) -> (Declarations, Declarations) {
+            let (mut decls, mut important) = (HashMap::new(), HashMap::new());
+            loop {
+                input.skip_whitespace();
+                let start = input.state();
+                let location = input.current_source_location();
+                match input.next_including_whitespace() {
+                    Err(_) => break,
+                    Ok(Token::Semicolon) => continue,
+                    Ok(Token::AtKeyword(name)) => {
+                        let message = format!("unsupported at-rule '@{}' skipped", name);
+                        while let Ok(token) = input.next_including_whitespace() {
+                            if matches!(token, Token::Semicolon | Token::CurlyBracketBlock) {
+                                break;
+                            }
                         }
+                        self.report(DiagnosticKind::UnsupportedAtRule, message, location);
+                        continue;
                     }
+                    Ok(_) => input.reset(&start),
                 }
-                if decls.is_empty() {
-                    return Ok(());
+                if let Ok((name, value, value_at)) = input.try_parse(parse_declaration) {
+                    if let Some((options, out)) = self.diagnostics.as_mut()
+                        && options.unknown_properties
+                        && !options.is_known_property(&name)
+                    {
+                        out.push(diagnostic(
+                            DiagnosticKind::UnknownProperty,
+                            format!("unknown property '{}' has no effect", name),
+                            value_at,
+                        ));
+                    }
+                    match strip_important(&value) {
+                        Some(value) => important.insert(name, value.to_string()),
+                        None => decls.insert(name, value),
+                    };
+                    continue;
                 }
-                for selector in parse_selector_list(&prelude) {
-                    self.rules.push(Rule { selector, decls: decls.clone() });
+                let mut prelude = String::new();
+                let has_block = loop {
+                    match input.next_including_whitespace() {
+                        Ok(Token::CurlyBracketBlock) => break true,
+                        Ok(Token::Semicolon) | Err(_) => break false,
+                        Ok(token) => {
+                            let _ = token.to_css(&mut prelude);
+                        }
+                    }
+                };
+                if !has_block {
+                    self.report(DiagnosticKind::InvalidSyntax, "invalid declaration skipped".into(), location);
+                    continue;
                 }
-                Ok(())
+                let nested = nest_selectors(selectors, &prelude);
+                let _ = input.parse_nested_block(|block| {
+                    self.parse_rule(nested, location, block);
+                    Ok::<_, ParseError<'i, ()>>(())
+                });
             }
+            (decls, important)
         }
+    }
+
+    impl<'i> cssparser::QualifiedRuleParser<'i> for SheetParser<'_> {
+        type Prelude = (String, SourceLocation);
+        type QualifiedRule = ();
+        type Error = ();
 
-        impl<'i> cssparser::AtRuleParser<'i> for &mut SheetParser {
-            type Prelude = ();
-            type AtRule = ();
-            type Error = ();
+        fn parse_prelude<'t>(
+            &mut self,
+            input: &mut Parser<'i, 't>,
+        ) -> Result<Self::Prelude, cssparser::ParseError<'i, Self::Error>> {
+            let location = input.current_source_location();
+            let mut selector = String::new();
+            while let Ok(token) = input.next_including_whitespace() {
+                let _ = token.to_css(&mut selector);
+            }
+            Ok((selector.trim().to_string(), location))
+        }
+
+        fn parse_block<'t>(
+            &mut self,
+            (prelude, location): Self::Prelude,
+            _start: &cssparser::ParserState,
+            input: &mut Parser<'i, 't>,
+        ) -> Result<Self::QualifiedRule, cssparser::ParseError<'i, Self::Error>> {
+            let selectors = prelude.split(',').map(|s| s.trim().to_string()).collect();
+            self.parse_rule(selectors, location, input);
+            Ok(())
         }
+    }
 
-        struct DeclarationParser;
-        impl<'i> cssparser::DeclarationParser<'i> for DeclarationParser {
-            type Declaration = (String, String);
-            type Error = ();
+    /// `@media` blocks; every other at-rule is rejected and reported.
+    impl<'i> cssparser::AtRuleParser<'i> for SheetParser<'_> {
+        type Prelude = String;
+        type AtRule = ();
+        type Error = ();
 
-            fn parse_value<'t>(
-                &mut self,
-                name: cssparser::CowRcStr<'i>,
-                input: &mut Parser<'i, 't>,
-            ) -> Result<Self::Declaration, cssparser::ParseError<'i, Self::Error>> {
-                let mut value = String::new();
-                while let Ok(token) = input.next_including_whitespace() {
-                    let _ = token.to_css(&mut value);
-                }
-                Ok((name.to_string(), value.trim().to_string()))
+        fn parse_prelude<'t>(
+            &mut self,
+            name: cssparser::CowRcStr<'i>,
+            input: &mut Parser<'i, 't>,
+        ) -> Result<Self::Prelude, ParseError<'i, Self::Error>> {
+            if !name.eq_ignore_ascii_case("media") {
+                return Err(input.new_error(BasicParseErrorKind::AtRuleInvalid(name)));
+            }
+            let mut query = String::new();
+            while let Ok(token) = input.next_including_whitespace() {
+                let _ = token.to_css(&mut query);
             }
+            Ok(query.trim().to_string())
         }
 
-        impl<'i> cssparser::AtRuleParser<'i> for DeclarationParser {
-            type Prelude = ();
-            type AtRule = (String, String);
-            type Error = ();
+        fn parse_block<'t>(
+            &mut self,
+            query: Self::Prelude,
+            _start: &cssparser::ParserState,
+            input: &mut Parser<'i, 't>,
+        ) -> Result<Self::AtRule, ParseError<'i, Self::Error>> {
+            let mut media = self.media.clone();
+            media.push(query);
+            let diagnostics = self.diagnostics.as_mut().map(|(options, out)| (*options, &mut **out));
+            let mut rule_list = RuleListParser::new_for_nested_rule(input, SheetParser { rules: Vec::new(), diagnostics, media });
+            while let Some(rule) = rule_list.next() {
+                if let Err((error, _)) = rule
+                    && let Some((_, out)) = rule_list.parser.diagnostics.as_mut()
+                {
+                    out.push(parse_error_diagnostic(&error, "rule"));
+                }
+            }
+            let rules = std::mem::take(&mut rule_list.parser.rules);
+            self.rules.extend(rules);
+            Ok(())
         }
+    }
 
-        fn parse_selector_list(selector: &str) -> Vec<SimpleSelector> {
-            let mut out = Vec::new();
-            for part in selector.split(',') {
-                let raw = part.trim();
-                if raw.is_empty() {
-                    continue;
+    fn parse_declaration<'i>(input: &mut Parser<'i, '_>) -> Result<(String, String, SourceLocation), ParseError<'i, ()>> {
+        let name = input.expect_ident_cloned()?;
+        input.skip_whitespace();
+        input.expect_colon()?;
+        input.parse_until_after(Delimiter::Semicolon, |input| {
+            input.skip_whitespace();
+            let location = input.current_source_location();
+            let mut value = String::new();
+            while let Ok(token) = input.next_including_whitespace() {
+                // `a:hover { ... }` reads like a declaration until its block.
+                if matches!(token, Token::CurlyBracketBlock) {
+                    return Err(input.new_custom_error(()));
                 }
-                let (name_raw, hover) = if let Some((base, pseudo)) = raw.split_once(':') {
-                    (base.trim(), pseudo.trim() == "hover")
+                let _ = token.to_css(&mut value);
+            }
+            Ok((name.to_string(), value.trim().to_string(), location))
+        })
+    }
+
+    /// Resolve a nested rule's selector list against its parent's: `&` is replaced by
+    /// each parent selector, and selectors without `&` become descendants of it.
+    fn nest_selectors(parents: &[String], prelude: &str) -> Vec<String> {
+        let mut out = Vec::new();
+        for part in prelude.split(',').map(str::trim) {
+            for parent in parents {
+                if part.is_empty() {
+                    out.push(String::new());
+                } else if part.contains('&') {
+                    out.push(part.replace('&', parent));
                 } else {
-                    (raw, false)
-                };
-                if let Some(rest) = name_raw.strip_prefix('.') {
-                    let name = rest.trim();
-                    if !name.is_empty() {
-                        out.push(SimpleSelector {
-                            kind: SimpleSelectorKind::Class,
-                            tag: String::new(),
-                            class: name.to_string(),
-                            hover,
-                        });
-                    }
-                } else if let Some((tag, class)) = name_raw.split_once('.') {
-                    let tag = tag.trim();
-                    let class = class.trim();
-                    if !tag.is_empty() && !class.is_empty() {
-                        out.push(SimpleSelector {
-                            kind: SimpleSelectorKind::TagClass,
-                            tag: tag.to_string(),
-                            class: class.to_string(),
-                            hover,
-                        });
-                    }
-                } else if !name_raw.is_empty() {
-                    out.push(SimpleSelector {
-                        kind: SimpleSelectorKind::Tag,
-                        tag: name_raw.to_string(),
-                        class: String::new(),
-                        hover,
-                    });
+                    out.push(format!("{} {}", parent, part));
                 }
             }
-            out
         }
+        out
+    }
 
-        let mut input = ParserInput::new(css);
-        let mut parser = Parser::new(&mut input);
-        let mut sheet_parser = SheetParser { rules: Vec::new() };
-        let mut rule_list = RuleListParser::new_for_stylesheet(&mut parser, &mut sheet_parser);
-        for rule in &mut rule_list {
-            let _ = rule;
+    /// Split `a .b c:hover` into its ancestor compounds and the subject. Selectors with
+    /// an unsupported compound or combinator are dropped.
+    fn parse_complex_selector(selector: &str) -> Option<(Vec<SimpleSelector>, SimpleSelector)> {
+        let mut compounds = selector
+            .split_whitespace()
+            .map(parse_compound)
+            .collect::<Option<Vec<_>>>()?;
+        let subject = compounds.pop()?;
+        Some((compounds, subject))
+    }
+
+    fn parse_compound(raw: &str) -> Option<SimpleSelector> {
+        if raw.contains(['&', '>', '+', '~']) {
+            return None;
+        }
+        let (name_raw, hover) = if let Some((base, pseudo)) = raw.split_once(':') {
+            (base.trim(), pseudo.trim() == "hover")
+        } else {
+            (raw, false)
+        };
+        if let Some(rest) = name_raw.strip_prefix('.') {
+            let name = rest.trim();
+            if !name.is_empty() {
+                return Some(SimpleSelector {
+                    kind: SimpleSelectorKind::Class,
+                    tag: String::new(),
+                    class: name.to_string(),
+                    hover,
+                });
+            }
+        } else if let Some((tag, class)) = name_raw.split_once('.') {
+            let tag = tag.trim();
+            let class = class.trim();
+            if !tag.is_empty() && !class.is_empty() {
+                return Some(SimpleSelector {
+                    kind: SimpleSelectorKind::TagClass,
+                    tag: tag.to_string(),
+                    class: class.to_string(),
+                    hover,
+                });
+            }
+        } else if !name_raw.is_empty() {
+            return Some(SimpleSelector {
+                kind: SimpleSelectorKind::Tag,
+                tag: name_raw.to_string(),
+                class: String::new(),
+                hover,
+            });
         }
+        None
+    }
 
-        Stylesheet { rules: sheet_parser.rules }
+    let mut input = ParserInput::new(css);
+    let mut parser = Parser::new(&mut input);
+    let sheet_parser = SheetParser { rules: Vec::new(), diagnostics, media: Vec::new() };
+    let mut rule_list = RuleListParser::new_for_stylesheet(&mut parser, sheet_parser);
+    while let Some(rule) = rule_list.next() {
+        if let Err((error, _)) = rule
+            && let Some((_, out)) = rule_list.parser.diagnostics.as_mut()
+        {
+            out.push(parse_error_diagnostic(&error, "rule"));
+        }
     }
+    rule_list.parser.rules
+}
+
+/// An element on the path from the root to the node being styled, for matching
+/// descendant selectors.
+struct Ancestor<'a> {
+    tag: &'a str,
+    class: Option<&'a str>,
+    hovered: bool,
+    parent: Option<&'a Ancestor<'a>>,
+}
+
+/// Each selector must match a distinct ancestor, outermost first. Matching the innermost
+/// selector against the nearest candidate is enough for descendant-only chains.
+fn matches_ancestors(selectors: &[SimpleSelector], mut parent: Option<&Ancestor<'_>>) -> bool {
+    for sel in selectors.iter().rev() {
+        loop {
+            let Some(ancestor) = parent else { return false };
+            parent = ancestor.parent;
+            if matches_selector(sel, ancestor.tag, ancestor.class, ancestor.hovered) {
+                break;
+            }
+        }
+    }
+    true
 }
 
 fn matches_selector(sel: &SimpleSelector, tag: &str, class_attr: Option<&str>, hovered: bool) -> bool {
@@ -180,36 +526,70 @@ fn matches_selector(sel: &SimpleSelector, tag: &str, class_attr: Option<&str>, h
     }
 }
 
-fn merge_styles(existing: Option<&str>, new_map: &HashMap<String, String>) -> String {
-    // Convert existing inline style to map
-    let mut map: HashMap<String,String> = HashMap::new();
-    if let Some(s) = existing {
-        for decl in s.split(';') {
-            let decl = decl.trim();
-            if decl.is_empty() { continue; }
-            if let Some((k,v)) = decl.split_once(':') {
-                map.insert(k.trim().to_string(), v.trim().to_string());
-            }
-        }
-    }
-    // Override/add new
-    for (k,v) in new_map {
-        map.insert(k.clone(), v.clone());
+/// Split an inline `style` attribute into normal and `!important` declarations.
+fn parse_inline_style(style: &str) -> (Declarations, Declarations) {
+    let (mut normal, mut important) = (HashMap::new(), HashMap::new());
+    for decl in style.split(';') {
+        let Some((k, v)) = decl.split_once(':') else { continue };
+        let k = k.trim().to_string();
+        match strip_important(v) {
+            Some(v) => important.insert(k, v.trim().to_string()),
+            None => normal.insert(k, v.trim().to_string()),
+        };
     }
+    (normal, important)
+}
+
+fn serialize_style(map: &HashMap<String, String>) -> String {
     // Serialize deterministically by key
-    let mut keys: Vec<_> = map.keys().cloned().collect();
+    let mut keys: Vec<_> = map.keys().collect();
     keys.sort();
     let mut out = String::new();
-    for (i,k) in keys.iter().enumerate() {
-        if i>0 { out.push_str(" "); }
+    for (i, k) in keys.into_iter().enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
         out.push_str(k);
         out.push_str(": ");
-        out.push_str(map.get(k).unwrap());
-        out.push_str(";");
+        out.push_str(&map[k]);
+        out.push(';');
     }
     out
 }
 
+/// The `rem` basis when nothing sets one: the browser default root font size.
+pub const DEFAULT_ROOT_FONT_SIZE: f32 = 16.0;
+
+// f32 bits; shared by all threads so `apply_styles_parallel` workers see it.
+static ROOT_FONT_SIZE: AtomicU32 = AtomicU32::new(DEFAULT_ROOT_FONT_SIZE.to_bits());
+
+/// Set the root font size `rem` lengths resolve against, in px. The window runners set it
+/// from the system's text scaling so `rem`-sized UIs follow the user's font size.
+pub fn set_root_font_size(px: f32) {
+    let px = if px.is_finite() && px > 0.0 { px } else { DEFAULT_ROOT_FONT_SIZE };
+    ROOT_FONT_SIZE.store(px.to_bits(), Ordering::Relaxed);
+}
+
+pub fn root_font_size() -> f32 {
+    f32::from_bits(ROOT_FONT_SIZE.load(Ordering::Relaxed))
+}
+
+/// `value` with each `Nrem` length converted to px at `root` (`1.5rem 2rem` at 16 is
+/// `24px 32px`). Other tokens are left alone.
+pub fn resolve_rem(value: &str, root: f32) -> String {
+    if !value.contains("rem") {
+        return value.to_string();
+    }
+    value
+        .split(' ')
+        .map(|token| match token.strip_suffix("rem").and_then(|n| n.parse::<f32>().ok()) {
+            Some(n) => format!("{}px", n * root),
+            None => token.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Apply stylesheet to a VNode recursively, returning a new VNode
 /// with inline `style` attributes populated.
 pub fn apply_styles(node: &VNode, sheet: &Stylesheet) -> VNode {
@@ -222,90 +602,270 @@ pub fn apply_styles_with_hover<F>(node: &VNode, sheet: &Stylesheet, is_hovered:
 where
     F: Fn(&str, &Props) -> bool,
 {
-    fn has_style_key(style: &str, key: &str) -> bool {
-        for decl in style.split(';') {
-            let d = decl.trim();
-            if d.is_empty() {
-                continue;
-            }
-            if let Some((k, _)) = d.split_once(':') {
-                if k.trim() == key {
-                    return true;
-                }
-            }
+    let inherited_root: HashMap<String,String> = HashMap::new();
+    cascade(node, &RuleIndex::new(sheet), is_hovered, None, &inherited_root)
+}
+
+/// Style `node` and its subtree below `parent`, whose children inherit `inherited`.
+fn cascade<F>(
+    node: &VNode,
+    index: &RuleIndex<'_>,
+    is_hovered: &F,
+    parent: Option<&Ancestor<'_>>,
+    inherited: &HashMap<String, String>,
+) -> VNode
+where
+    F: Fn(&str, &Props) -> bool,
+{
+    match node {
+        // Components are styled once expanded.
+        VNode::Text(_) | VNode::Component { .. } => node.clone(),
+        VNode::Element { tag, props, children } => {
+            let hovered = is_hovered(tag, props);
+            let (new_props, inherit_next) = style_element(tag, props, index, hovered, parent, inherited);
+            let me = Ancestor { tag, class: props.str("class"), hovered, parent };
+            let new_children = children.iter().map(|c| cascade(c, index, is_hovered, Some(&me), &inherit_next)).collect();
+            VNode::Element { tag: tag.clone(), props: new_props, children: new_children }
         }
-        false
-    }
-
-    // Cascade and inheritance for a subset of text properties
-    fn filter_inheritable(style: Option<&str>) -> HashMap<String, String> {
-        let mut map = HashMap::new();
-        if let Some(s) = style {
-            for decl in s.split(';') {
-                let d = decl.trim();
-                if d.is_empty() { continue; }
-                if let Some((k, v)) = d.split_once(':') {
-                    let k = k.trim();
-                    let v = v.trim();
-                    match k {
-                        "color" | "font-size" | "font-weight" | "text-decoration" | "line-height" => {
-                            map.insert(k.to_string(), v.to_string());
-                        }
-                        _ => {}
-                    }
-                }
+        // Transparent: its nodes are styled as children of `parent`.
+        VNode::Fragment(children) => {
+            VNode::Fragment(children.iter().map(|c| cascade(c, index, is_hovered, parent, inherited)).collect())
+        }
+    }
+}
+
+/// Re-style `node` after the hover state of some of its elements flipped, given `prev`:
+/// what `apply_styles_with_hover` returned for the same `node` and `sheet` before the
+/// flip. `flipped(tag, props)` says whether an element's hover state changed.
+///
+/// A `:hover` rule can only restyle the hovered element and its descendants, so only
+/// the subtrees of flipped elements are cascaded again; everything else is taken from
+/// `prev`. The result is the same as `apply_styles_with_hover(node, sheet, is_hovered)`.
+pub fn restyle_hover<F, G>(prev: &VNode, node: &VNode, sheet: &Stylesheet, is_hovered: &F, flipped: &G) -> VNode
+where
+    F: Fn(&str, &Props) -> bool,
+    G: Fn(&str, &Props) -> bool,
+{
+    fn rec<F, G>(
+        prev: &VNode,
+        node: &VNode,
+        index: &RuleIndex<'_>,
+        is_hovered: &F,
+        flipped: &G,
+        parent: Option<&Ancestor<'_>>,
+        inherited: &HashMap<String, String>,
+    ) -> VNode
+    where
+        F: Fn(&str, &Props) -> bool,
+        G: Fn(&str, &Props) -> bool,
+    {
+        match (prev, node) {
+            (VNode::Text(_), VNode::Text(_)) => node.clone(),
+            (VNode::Element { props: styled, children: prev_children, .. }, VNode::Element { tag, props, children })
+                if prev_children.len() == children.len() && !flipped(tag, props) =>
+            {
+                let hovered = is_hovered(tag, props);
+                let me = Ancestor { tag, class: props.str("class"), hovered, parent };
+                let inherit_next = filter_inheritable(styled.str("style"));
+                let new_children = prev_children
+                    .iter()
+                    .zip(children)
+                    .map(|(p, c)| rec(p, c, index, is_hovered, flipped, Some(&me), &inherit_next))
+                    .collect();
+                VNode::Element { tag: tag.clone(), props: styled.clone(), children: new_children }
             }
+            (VNode::Fragment(prev_children), VNode::Fragment(children)) if prev_children.len() == children.len() => {
+                VNode::Fragment(
+                    prev_children
+                        .iter()
+                        .zip(children)
+                        .map(|(p, c)| rec(p, c, index, is_hovered, flipped, parent, inherited))
+                        .collect(),
+                )
+            }
+            _ => cascade(node, index, is_hovered, parent, inherited),
         }
-        map
     }
 
-    fn apply_rec<FN>(node: &VNode, sheet: &Stylesheet, is_hovered: &FN, inherited: &HashMap<String, String>) -> VNode
-    where FN: Fn(&str, &Props) -> bool {
+    if !sheet.rules.iter().any(|r| r.selector.hover || r.ancestors.iter().any(|a| a.hover)) {
+        return prev.clone();
+    }
+    rec(prev, node, &RuleIndex::new(sheet), is_hovered, flipped, None, &HashMap::new())
+}
+
+/// Parallel `apply_styles`: sibling subtrees are styled concurrently on the rayon pool.
+#[cfg(feature = "parallel")]
+pub fn apply_styles_parallel(node: &VNode, sheet: &Stylesheet) -> VNode {
+    apply_styles_with_hover_parallel(node, sheet, &|_, _| false)
+}
+
+/// Parallel `apply_styles_with_hover`. Produces the same tree; children of a node are
+/// styled concurrently once there are enough of them to outweigh the scheduling cost.
+#[cfg(feature = "parallel")]
+pub fn apply_styles_with_hover_parallel<F>(node: &VNode, sheet: &Stylesheet, is_hovered: &F) -> VNode
+where
+    F: Fn(&str, &Props) -> bool + Sync,
+{
+    use rayon::prelude::*;
+
+    fn apply_rec<FN>(
+        node: &VNode,
+        index: &RuleIndex<'_>,
+        is_hovered: &FN,
+        parent: Option<&Ancestor<'_>>,
+        inherited: &HashMap<String, String>,
+    ) -> VNode
+    where FN: Fn(&str, &Props) -> bool + Sync {
         match node {
-            VNode::Text(_) => node.clone(),
+            VNode::Text(_) | VNode::Component { .. } => node.clone(),
             VNode::Element { tag, props, children } => {
-                let class_attr = props.attrs.get("class").map(|s| s.as_str());
                 let hovered = is_hovered(tag, props);
-                let mut acc: HashMap<String,String> = inherited.clone();
-                // Apply rules in two passes: tag then class (class overrides tag)
-                for pass in ["tag", "class"] {
-                    for rule in &sheet.rules {
-                        let is_tag = matches!(rule.selector.kind, SimpleSelectorKind::Tag);
-                        let pass_tag = (pass == "tag" && is_tag) || (pass == "class" && !is_tag);
-                        if !pass_tag { continue; }
-                        if matches_selector(&rule.selector, tag, class_attr, hovered) {
-                            for (k, v) in &rule.decls {
-                                acc.insert(k.clone(), v.clone());
-                            }
-                        }
-                    }
-                }
-                // Inline style has highest precedence
-                let mut new_props = props.clone();
-                let merged = merge_styles(new_props.attrs.get("style").map(|s| s.as_str()), &acc);
-                let mut final_style = merged.clone();
-                if tag == "button" {
-                    let has_padding = has_style_key(&final_style, "padding")
-                        || has_style_key(&final_style, "padding-left")
-                        || has_style_key(&final_style, "padding-right")
-                        || has_style_key(&final_style, "padding-top")
-                        || has_style_key(&final_style, "padding-bottom");
-                    if !has_padding {
-                        final_style.push_str(" padding: 6px 12px;");
-                    }
-                    if !has_style_key(&final_style, "text-align") {
-                        final_style.push_str(" text-align: center;");
-                    }
-                }
-                if !final_style.is_empty() { new_props = new_props.set("style", final_style.clone()); }
-                // Inherit only inheritable props to children
-                let inherit_next = filter_inheritable(Some(&final_style));
-                let new_children = children.iter().map(|c| apply_rec(c, sheet, is_hovered, &inherit_next)).collect();
+                let (new_props, inherit_next) = style_element(tag, props, index, hovered, parent, inherited);
+                let me = Ancestor { tag, class: props.str("class"), hovered, parent };
+                let new_children = if children.len() >= PARALLEL_MIN_CHILDREN {
+                    children.par_iter().map(|c| apply_rec(c, index, is_hovered, Some(&me), &inherit_next)).collect()
+                } else {
+                    children.iter().map(|c| apply_rec(c, index, is_hovered, Some(&me), &inherit_next)).collect()
+                };
                 VNode::Element { tag: tag.clone(), props: new_props, children: new_children }
             }
+            VNode::Fragment(children) => {
+                VNode::Fragment(children.iter().map(|c| apply_rec(c, index, is_hovered, parent, inherited)).collect())
+            }
         }
     }
 
-    let inherited_root: HashMap<String,String> = HashMap::new();
-    apply_rec(node, sheet, is_hovered, &inherited_root)
+    apply_rec(node, &RuleIndex::new(sheet), is_hovered, None, &HashMap::new())
+}
+
+/// Below this many children a node's subtrees are styled on the current thread.
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_CHILDREN: usize = 8;
+
+fn has_style_key(style: &str, key: &str) -> bool {
+    for decl in style.split(';') {
+        let d = decl.trim();
+        if d.is_empty() {
+            continue;
+        }
+        if let Some((k, _)) = d.split_once(':') {
+            if k.trim() == key {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// The declarations in `style` that descendants inherit (see `properties::is_inherited`).
+fn filter_inheritable(style: Option<&str>) -> HashMap<String, String> {
+    let mut map = HashMap::new();
+    if let Some(s) = style {
+        for decl in s.split(';') {
+            if let Some((k, v)) = decl.split_once(':')
+                && properties::is_inherited(k.trim())
+            {
+                map.insert(k.trim().to_string(), v.trim().to_string());
+            }
+        }
+    }
+    map
+}
+
+/// The screen rules of a stylesheet by the tag or class their subject needs, so an
+/// element is only matched against rules that name its tag or one of its classes. Built
+/// once per `apply_styles` call: `Stylesheet::rules` is public and may change between calls.
+struct RuleIndex<'a> {
+    rules: &'a [Rule],
+    /// Rules whose subject is a bare tag.
+    by_tag: HashMap<&'a str, Vec<usize>>,
+    /// Rules whose subject has a class, with or without a tag.
+    by_class: HashMap<&'a str, Vec<usize>>,
+}
+
+impl<'a> RuleIndex<'a> {
+    fn new(sheet: &'a Stylesheet) -> Self {
+        let (mut by_tag, mut by_class) = (HashMap::<&str, Vec<usize>>::new(), HashMap::<&str, Vec<usize>>::new());
+        for (i, rule) in sheet.rules.iter().enumerate() {
+            if !rule.media.iter().all(|q| media_matches(q, "screen")) {
+                continue;
+            }
+            match rule.selector.kind {
+                SimpleSelectorKind::Tag => by_tag.entry(&rule.selector.tag).or_default().push(i),
+                SimpleSelectorKind::Class | SimpleSelectorKind::TagClass => {
+                    by_class.entry(&rule.selector.class).or_default().push(i)
+                }
+            }
+        }
+        RuleIndex { rules: &sheet.rules, by_tag, by_class }
+    }
+
+    /// The rules matching the element, in source order.
+    fn matching(&self, tag: &str, class_attr: Option<&str>, hovered: bool, parent: Option<&Ancestor<'_>>) -> Vec<&'a Rule> {
+        let mut candidates: Vec<usize> = self.by_tag.get(tag).cloned().unwrap_or_default();
+        for class in class_attr.unwrap_or("").split_whitespace() {
+            candidates.extend(self.by_class.get(class).into_iter().flatten());
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+            .into_iter()
+            .map(|i| &self.rules[i])
+            .filter(|rule| matches_selector(&rule.selector, tag, class_attr, hovered) && matches_ancestors(&rule.ancestors, parent))
+            .collect()
+    }
+}
+
+/// Cascade one element: returns its props with the computed inline `style` and the
+/// inheritable declarations for its children.
+fn style_element(
+    tag: &str,
+    props: &Props,
+    index: &RuleIndex<'_>,
+    hovered: bool,
+    parent: Option<&Ancestor<'_>>,
+    inherited: &HashMap<String, String>,
+) -> (Props, HashMap<String, String>) {
+    let class_attr = props.str("class");
+    let mut matched = index.matching(tag, class_attr, hovered, parent);
+    // Stable sort: equal specificity keeps source order, so later rules win.
+    matched.sort_by_key(|rule| rule.specificity());
+    let (inline, inline_important) = parse_inline_style(props.str("style").unwrap_or(""));
+
+    // Lowest to highest: inherited values, rules, inline style, `!important` rules,
+    // `!important` inline style.
+    let mut computed = inherited.clone();
+    for rule in &matched {
+        computed.extend(rule.decls.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    computed.extend(inline);
+    for rule in &matched {
+        computed.extend(rule.important.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    computed.extend(inline_important);
+    let root = root_font_size();
+    for value in computed.values_mut() {
+        if value.contains("rem") {
+            *value = resolve_rem(value, root);
+        }
+    }
+    let mut new_props = props.clone();
+    let mut final_style = serialize_style(&computed);
+    if tag == "button" {
+        let has_padding = has_style_key(&final_style, "padding")
+            || has_style_key(&final_style, "padding-left")
+            || has_style_key(&final_style, "padding-right")
+            || has_style_key(&final_style, "padding-top")
+            || has_style_key(&final_style, "padding-bottom");
+        if !has_padding {
+            final_style.push_str(" padding: 6px 12px;");
+        }
+        if !has_style_key(&final_style, "text-align") {
+            final_style.push_str(" text-align: center;");
+        }
+    }
+    if !final_style.is_empty() { new_props = new_props.set("style", final_style.clone()); }
+    // Inherit only inheritable props to children
+    let inherit_next = filter_inheritable(Some(&final_style));
+    (new_props, inherit_next)
 }
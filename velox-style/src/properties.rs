@@ -0,0 +1,86 @@
+//! Metadata for the CSS properties velox understands: whether a property is inherited by
+//! descendants and the value it takes when nothing sets or inherits it.
+//!
+//! The cascade uses `inherited` to decide what flows to children, and style diagnostics
+//! treat anything missing from `PROPERTIES` as an unknown property.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PropertyInfo {
+    pub name: &'static str,
+    pub inherited: bool,
+    /// Value used when the property is neither set nor inherited, as velox renders it.
+    pub initial: &'static str,
+}
+
+const fn prop(name: &'static str, inherited: bool, initial: &'static str) -> PropertyInfo {
+    PropertyInfo {
+        name,
+        inherited,
+        initial,
+    }
+}
+
+/// Sorted by name.
+pub const PROPERTIES: &[PropertyInfo] = &[
+    prop("backdrop-filter", false, "none"),
+    prop("background", false, "transparent"),
+    prop("background-color", false, "transparent"),
+    prop("border", false, "none"),
+    prop("border-radius", false, "0"),
+    prop("break-after", false, "auto"),
+    prop("break-before", false, "auto"),
+    prop("break-inside", false, "auto"),
+    prop("clip-path", false, "none"),
+    prop("color", true, "black"),
+    prop("display", false, "block"),
+    prop("filter", false, "none"),
+    prop("flex-direction", false, "row"),
+    prop("font-family", true, "sans-serif"),
+    prop("font-size", true, "14px"),
+    prop("font-weight", true, "normal"),
+    prop("gap", false, "0"),
+    prop("height", false, "auto"),
+    prop("letter-spacing", true, "normal"),
+    prop("line-height", true, "normal"),
+    prop("margin", false, "0"),
+    prop("margin-bottom", false, "0"),
+    prop("margin-left", false, "0"),
+    prop("margin-right", false, "0"),
+    prop("margin-top", false, "0"),
+    prop("opacity", false, "1"),
+    prop("overflow", false, "visible"),
+    prop("padding", false, "0"),
+    prop("padding-bottom", false, "0"),
+    prop("padding-left", false, "0"),
+    prop("padding-right", false, "0"),
+    prop("padding-top", false, "0"),
+    prop("page-break-after", false, "auto"),
+    prop("page-break-before", false, "auto"),
+    prop("page-break-inside", false, "auto"),
+    prop("text-align", true, "left"),
+    // Not inherited in CSS, but underlines propagate to descendant text the same way.
+    prop("text-decoration", true, "none"),
+    prop("transform", false, "none"),
+    prop("transition", false, "none"),
+    prop("width", false, "auto"),
+    prop("z-index", false, "auto"),
+];
+
+/// Look up a property by name (case-insensitive).
+pub fn property(name: &str) -> Option<&'static PropertyInfo> {
+    let name = name.to_ascii_lowercase();
+    PROPERTIES
+        .binary_search_by(|p| p.name.cmp(name.as_str()))
+        .ok()
+        .map(|i| &PROPERTIES[i])
+}
+
+/// Whether descendants inherit `name` when they do not set it.
+pub fn is_inherited(name: &str) -> bool {
+    property(name).is_some_and(|p| p.inherited)
+}
+
+/// The value `name` takes when nothing sets or inherits it.
+pub fn initial_value(name: &str) -> Option<&'static str> {
+    property(name).map(|p| p.initial)
+}
@@ -1,5 +1,5 @@
 use velox_dom::{h, text, Props, VNode};
-use velox_style::{Stylesheet, apply_styles_with_hover};
+use velox_style::{Stylesheet, apply_styles_with_hover, restyle_hover};
 
 #[test]
 fn hover_selector_applies_conditionally() {
@@ -17,9 +17,24 @@ div:hover { color: blue; }
     // Hovered
     let styled2 = apply_styles_with_hover(&vnode, &ss, &|tag, _| tag == "div");
     if let VNode::Element { props, .. } = styled2 {
-        let style = props.attrs.get("style").unwrap();
+        let style = props.str("style").unwrap();
         assert!(style.contains("color: blue;"));
         assert!(style.contains("background: yellow;"));
     } else { panic!("expected element"); }
 }
 
+#[test]
+fn restyle_hover_matches_a_full_cascade() {
+    let ss = Stylesheet::parse(".card { color: gray; } .card:hover .title { color: red; } .title { font-size: 12px; }");
+    let card = |id: &str| {
+        h("div", Props::new().set("class", "card").set("id", id), vec![h("p", Props::new().set("class", "title"), vec![text(id)])])
+    };
+    let vnode = h("div", Props::new(), vec![card("a"), card("b")]);
+    let hovering = |id: &'static str| move |_: &str, p: &Props| p.str("id") == Some(id);
+
+    let before = apply_styles_with_hover(&vnode, &ss, &hovering("a"));
+    let flipped = |_: &str, p: &Props| matches!(p.str("id"), Some("a" | "b"));
+    let after = restyle_hover(&before, &vnode, &ss, &hovering("b"), &flipped);
+    assert_eq!(after, apply_styles_with_hover(&vnode, &ss, &hovering("b")));
+    assert_ne!(after, before);
+}
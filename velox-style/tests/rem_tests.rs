@@ -0,0 +1,41 @@
+use velox_dom::{VNode, h, text};
+use velox_style::{
+    DEFAULT_ROOT_FONT_SIZE, Stylesheet, apply_styles, resolve_rem, set_root_font_size,
+};
+
+fn style_of(node: &VNode) -> String {
+    match node {
+        VNode::Element { props, .. } => props.str("style").unwrap_or_default().to_string(),
+        _ => panic!("expected element"),
+    }
+}
+
+#[test]
+fn resolve_rem_converts_lengths_only() {
+    assert_eq!(resolve_rem("1.5rem 2rem", 16.0), "24px 32px");
+    assert_eq!(resolve_rem("0 1rem solid", 20.0), "0 20px solid");
+    assert_eq!(resolve_rem("remote", 16.0), "remote");
+}
+
+// The root font size is process-wide, so everything that changes it is in one test.
+#[test]
+fn rem_follows_root_font_size() {
+    let sheet = Stylesheet::parse("p { font-size: 1.25rem; padding: 0.5rem 1rem; }");
+    let vnode = h("div", (), vec![h("p", (), vec![text("hi")])]);
+
+    let styled = apply_styles(&vnode, &sheet);
+    let VNode::Element { children, .. } = &styled else {
+        panic!()
+    };
+    let style = style_of(&children[0]);
+    assert!(style.contains("font-size: 20px;"), "{}", style);
+    assert!(style.contains("padding: 8px 16px;"), "{}", style);
+
+    set_root_font_size(DEFAULT_ROOT_FONT_SIZE * 1.5);
+    let styled = apply_styles(&vnode, &sheet);
+    let VNode::Element { children, .. } = &styled else {
+        panic!()
+    };
+    assert!(style_of(&children[0]).contains("font-size: 30px;"));
+    set_root_font_size(DEFAULT_ROOT_FONT_SIZE);
+}
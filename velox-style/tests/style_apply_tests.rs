@@ -1,5 +1,5 @@
 use velox_dom::{h, text, Props, VNode};
-use velox_style::{Stylesheet, apply_styles};
+use velox_style::{Stylesheet, apply_styles, media_matches};
 
 #[test]
 fn applies_tag_and_class_rules() {
@@ -18,7 +18,7 @@ div { color: red; }
     let styled = apply_styles(&vnode, &ss);
     match styled {
         VNode::Element { props, .. } => {
-            let style = props.attrs.get("style").expect("style present");
+            let style = props.str("style").expect("style present");
             // Both declarations applied
             assert!(style.contains("color: red;"));
             assert!(style.contains("font-weight: bold;"));
@@ -37,7 +37,7 @@ div { color: blue; }
     let vnode = h("div", Props::new().set("class", "btn"), vec![]);
     let styled = apply_styles(&vnode, &ss);
     if let VNode::Element { props, .. } = styled {
-        let style = props.attrs.get("style").unwrap();
+        let style = props.str("style").unwrap();
         // Our simple merger creates deterministic order by key; just assert final value
         assert!(style.contains("color: red;"));
     } else { panic!("expected element"); }
@@ -51,9 +51,23 @@ fn children_receive_styles_recursively() {
     let styled = apply_styles(&vnode, &ss);
     if let VNode::Element { children, .. } = styled {
         if let VNode::Element { props, .. } = &children[0] {
-            let style = props.attrs.get("style").unwrap();
+            let style = props.str("style").unwrap();
             assert!(style.contains("color: green;"));
         } else { panic!("expected span element"); }
     } else { panic!("expected div element"); }
 }
 
+#[test]
+fn media_rules_apply_per_medium() {
+    let sheet = Stylesheet::parse(".a { color: red; } @media print { .a { color: black; } .nav { display: none; } } @media screen, (max-width: 10px) { .a { font-size: 20px; } }");
+    assert_eq!(sheet.rules[1].media, vec!["print".to_string()]);
+    let node = h("div", vec![("class", "a")], vec![]);
+    let style = |n: &VNode| match n {
+        VNode::Element { props, .. } => props.str("style").unwrap_or_default().to_string(),
+        _ => String::new(),
+    };
+    assert_eq!(style(&apply_styles(&node, &sheet)), "color: red; font-size: 20px;");
+    assert_eq!(style(&apply_styles(&node, &sheet.for_media("print"))), "color: black;");
+    assert!(media_matches("not screen", "print"));
+    assert!(!media_matches("print and (orientation: landscape)", "print"));
+}
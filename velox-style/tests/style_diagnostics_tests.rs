@@ -0,0 +1,59 @@
+use velox_style::{DiagnosticKind, DiagnosticOptions, Stylesheet};
+
+#[test]
+fn reports_unknown_properties_selectors_and_at_rules_with_positions() {
+    let css = ".btn { color: red; colr: blue; --accent: #fff; }\ndiv > span { color: red; }\n@supports (gap: 1px) { .a { color: red; } }\na:focus { color: red; }\n";
+    let (sheet, diags) = Stylesheet::parse_with_diagnostics(css);
+    assert_eq!(sheet, Stylesheet::parse(css));
+    let summary: Vec<_> = diags.iter().map(|d| (d.kind, d.line, d.column)).collect();
+    assert_eq!(
+        summary,
+        vec![
+            (DiagnosticKind::UnknownProperty, 1, 26),
+            (DiagnosticKind::BadSelector, 2, 1),
+            (DiagnosticKind::UnsupportedAtRule, 3, 1),
+            (DiagnosticKind::BadSelector, 4, 1),
+        ]
+    );
+    assert!(diags[0].message.contains("colr"));
+    assert_eq!(
+        diags[3].to_string(),
+        "4:1: 'a:focus': only ':hover' is supported, not ':focus'"
+    );
+}
+
+#[test]
+fn reports_unbalanced_braces_and_invalid_declarations() {
+    let css = "p { color red; font-size: 12px; }\n}\n.open { color: red;\n";
+    let (sheet, diags) = Stylesheet::parse_with_diagnostics(css);
+    assert_eq!(sheet.rules.len(), 2);
+    let summary: Vec<_> = diags.iter().map(|d| (d.kind, d.line)).collect();
+    assert!(
+        summary.contains(&(DiagnosticKind::InvalidSyntax, 1)),
+        "{:?}",
+        diags
+    );
+    assert!(
+        summary.contains(&(DiagnosticKind::UnbalancedBraces, 2)),
+        "{:?}",
+        diags
+    );
+    assert!(
+        summary.contains(&(DiagnosticKind::UnbalancedBraces, 3)),
+        "{:?}",
+        diags
+    );
+}
+
+#[test]
+fn options_can_allow_extra_properties_and_skip_checks() {
+    let css = "div:active { cursor: pointer; }";
+    let options = DiagnosticOptions {
+        extra_properties: vec!["cursor".into()],
+        selectors: false,
+        ..Default::default()
+    };
+    let (_, diags) = Stylesheet::parse_with_options(css, &options);
+    assert!(diags.is_empty(), "{:?}", diags);
+    assert_eq!(Stylesheet::parse_with_diagnostics(css).1.len(), 2);
+}
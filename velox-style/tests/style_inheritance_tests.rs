@@ -0,0 +1,57 @@
+use velox_dom::{Props, VNode, h, text};
+use velox_style::{Stylesheet, apply_styles, properties};
+
+fn style_of(node: &VNode) -> String {
+    match node {
+        VNode::Element { props, .. } => props.str("style").unwrap_or_default().to_string(),
+        _ => panic!("expected element"),
+    }
+}
+
+#[test]
+fn inherited_properties_flow_to_descendants() {
+    let css = ".app { font-family: Inter; text-align: center; letter-spacing: 1px; margin: 8px; border: 1px solid red; }";
+    let tree = h(
+        "div",
+        Props::new().set("class", "app"),
+        vec![h(
+            "section",
+            Props::new(),
+            vec![h(
+                "p",
+                Props::new().set("style", "text-align: right"),
+                vec![text("x")],
+            )],
+        )],
+    );
+    let out = apply_styles(&tree, &Stylesheet::parse(css));
+    let VNode::Element { children, .. } = &out else {
+        unreachable!()
+    };
+    let section = &children[0];
+    assert_eq!(
+        style_of(section),
+        "font-family: Inter; letter-spacing: 1px; text-align: center;"
+    );
+    let VNode::Element { children, .. } = section else {
+        unreachable!()
+    };
+    assert_eq!(
+        style_of(&children[0]),
+        "font-family: Inter; letter-spacing: 1px; text-align: right;"
+    );
+}
+
+#[test]
+fn property_table_is_sorted_and_answers_lookups() {
+    assert!(
+        properties::PROPERTIES
+            .windows(2)
+            .all(|w| w[0].name < w[1].name)
+    );
+    assert!(properties::is_inherited("Font-Family"));
+    assert!(!properties::is_inherited("padding"));
+    assert!(!properties::is_inherited("--accent"));
+    assert_eq!(properties::initial_value("flex-direction"), Some("row"));
+    assert_eq!(properties::initial_value("cursor"), None);
+}
@@ -0,0 +1,63 @@
+use velox_dom::{Props, VNode, h, text};
+use velox_style::{DiagnosticKind, Stylesheet, apply_styles, apply_styles_with_hover};
+
+fn style_of(node: &VNode) -> &str {
+    match node {
+        VNode::Element { props, .. } => props.str("style").unwrap_or(""),
+        _ => panic!("expected element"),
+    }
+}
+
+fn child(node: &VNode, i: usize) -> &VNode {
+    match node {
+        VNode::Element { children, .. } => &children[i],
+        _ => panic!("expected element"),
+    }
+}
+
+#[test]
+fn nested_rules_are_flattened_after_their_parent() {
+    let css = r#"
+.card {
+    color: red;
+    &:hover { color: blue; }
+    .title { font-size: 20px; }
+    padding: 4px;
+}
+"#;
+    let sheet = Stylesheet::parse(css);
+    let flat = Stylesheet::parse(
+        ".card { color: red; padding: 4px; } .card:hover { color: blue; } .card .title { font-size: 20px; }",
+    );
+    assert_eq!(sheet, flat);
+
+    let tree = h(
+        "div",
+        Props::new().set("class", "card"),
+        vec![h(
+            "span",
+            Props::new().set("class", "title"),
+            vec![text("t")],
+        )],
+    );
+    let loose = h("span", Props::new().set("class", "title"), vec![text("t")]);
+
+    let styled = apply_styles_with_hover(&tree, &sheet, &|tag, _| tag == "div");
+    assert!(style_of(&styled).contains("color: blue;"));
+    assert!(style_of(&styled).contains("padding: 4px;"));
+    assert!(style_of(child(&styled, 0)).contains("font-size: 20px;"));
+    assert!(!style_of(&apply_styles(&loose, &sheet)).contains("font-size"));
+}
+
+#[test]
+fn ampersand_expands_against_every_parent_selector() {
+    let css = ".a, .b { & span, &:hover { color: red; } }";
+    let sheet = Stylesheet::parse(css);
+    let flat = Stylesheet::parse(".a span, .b span, .a:hover, .b:hover { color: red; }");
+    assert_eq!(sheet, flat);
+
+    let (_, diags) =
+        Stylesheet::parse_with_diagnostics(".card {\n  > .x { color: red; }\n  color: blue;\n}");
+    let summary: Vec<_> = diags.iter().map(|d| (d.kind, d.line, d.column)).collect();
+    assert_eq!(summary, vec![(DiagnosticKind::BadSelector, 2, 3)]);
+}
@@ -7,3 +7,17 @@ fn parses_color_and_fontsize_from_style_string() {
     assert_eq!(ss.rules.len(), 1);
 }
 
+#[test]
+fn to_css_reads_back_as_the_same_sheet() {
+    let css = r#"
+        .card .title:hover { color: red; font-weight: bold !important; }
+        li.done, p { opacity: 0.5 }
+        @media print { @media not screen { .nav { display: none; } } }
+        .list { .item { padding: 4px 8px; } }
+    "#;
+    let sheet = Stylesheet::parse(css);
+    let printed = sheet.to_css();
+    assert_eq!(printed.lines().count(), sheet.rules.len());
+    assert!(printed.contains(".card .title:hover { color: red; font-weight: bold !important; }"), "{}", printed);
+    assert_eq!(Stylesheet::parse(&printed), sheet);
+}
@@ -0,0 +1,73 @@
+use velox_dom::{Props, VNode, h, text};
+use velox_style::{Stylesheet, apply_styles};
+
+fn styled(css: &str, node: &VNode) -> VNode {
+    apply_styles(node, &Stylesheet::parse(css))
+}
+
+fn style_of(node: &VNode) -> String {
+    match node {
+        VNode::Element { props, .. } => props.str("style").unwrap_or_default().to_string(),
+        _ => panic!("expected element"),
+    }
+}
+
+#[test]
+fn inline_beats_rules_and_rules_beat_inherited_values() {
+    let css = "div { color: red; } p { color: green; } .note { font-size: 12px; }";
+    let tree = h(
+        "div",
+        Props::new().set("style", "color: black;"),
+        vec![h(
+            "p",
+            Props::new()
+                .set("class", "note")
+                .set("style", "font-size: 20px"),
+            vec![text("x")],
+        )],
+    );
+    let out = styled(css, &tree);
+    assert_eq!(style_of(&out), "color: black;");
+    let VNode::Element { children, .. } = &out else {
+        unreachable!()
+    };
+    assert_eq!(style_of(&children[0]), "color: green; font-size: 20px;");
+}
+
+#[test]
+fn important_and_specificity_order_the_cascade() {
+    let css = ".btn { color: blue !important; } button.btn { color: red; } button { color: green; padding: 1px; }";
+    let node = h(
+        "button",
+        Props::new()
+            .set("class", "btn")
+            .set("style", "color: black; padding: 2px !important"),
+        vec![],
+    );
+    assert_eq!(
+        style_of(&styled(css, &node)),
+        "color: blue; padding: 2px; text-align: center;"
+    );
+
+    let node = h("button", Props::new().set("class", "btn"), vec![]);
+    let css = "button.btn { padding: 3px; } .btn { padding: 9px; }";
+    assert!(style_of(&styled(css, &node)).contains("padding: 3px;"));
+}
+
+#[test]
+fn rules_matched_through_different_classes_keep_source_order() {
+    // Each rule is found through a different class or tag; equal specificity still
+    // means the later rule wins.
+    let mut css = String::from(
+        ".b { color: blue; } .a { color: red; } em { font-size: 9px; } .a { font-size: 10px; }",
+    );
+    for i in 0..300 {
+        css.push_str(&format!(
+            " .unused{} {{ color: black; }} em.x{} {{ color: black; }}",
+            i, i
+        ));
+    }
+    css.push_str(" @media print { .a { color: black; } }");
+    let out = styled(&css, &h("em", Props::new().set("class", "a b a"), vec![]));
+    assert_eq!(style_of(&out), "color: red; font-size: 10px;");
+}
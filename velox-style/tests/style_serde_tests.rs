@@ -0,0 +1,23 @@
+#![cfg(feature = "serde")]
+
+use velox_dom::wire::Versioned;
+use velox_style::Stylesheet;
+
+#[test]
+fn stylesheets_round_trip_through_json() {
+    let sheet = Stylesheet::parse(
+        ".card .title:hover { color: red; font-size: 14px !important; }
+         @media (max-width: 600px) { p { margin: 0; } }",
+    );
+    let json = serde_json::to_string(&Versioned::new(&sheet)).unwrap();
+    assert!(json.contains(r#""decls":{"color":"red"}"#), "{}", json);
+    let read: Versioned<Stylesheet> = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.into_data().unwrap(), sheet);
+
+    // Fields with defaults may be left out.
+    let minimal =
+        r#"{"rules":[{"selector":{"kind":"Tag","tag":"p","class":""},"decls":{"margin":"0"}}]}"#;
+    let sheet: Stylesheet = serde_json::from_str(minimal).unwrap();
+    assert_eq!(sheet.rules[0].decls["margin"], "0");
+    assert!(sheet.rules[0].media.is_empty() && !sheet.rules[0].selector.hover);
+}
@@ -0,0 +1,141 @@
+//! Stack of collapsible sections.
+//!
+//! Each section is a header button followed, while expanded, by its panel. Collapsed
+//! panels are not built: `render` calls the content function only for expanded
+//! sections. Panels are keyed by section id and marked `data-transition="accordion"`,
+//! so `.accordion-enter-from` / `.accordion-leave-to` rules animate them open and shut.
+//!
+//! Header clicks dispatch the handler name with the section id as payload, for `handle`.
+//! Keys forwarded to `key` move focus between headers (`ArrowUp`/`ArrowDown` wrap,
+//! `Home`/`End`) and toggle the focused one with `Enter` or space. By default one section
+//! is open at a time; `with_multiple` lets several stay open.
+
+use std::collections::HashSet;
+
+use velox_dom::{Props, VNode, h, text};
+
+use crate::tabs::Tab;
+
+#[derive(Clone, Debug, Default)]
+pub struct Accordion {
+    sections: Vec<Tab>,
+    expanded: HashSet<String>,
+    focused: usize,
+    multiple: bool,
+}
+
+impl Accordion {
+    /// Sections use the same `Tab` description as `Tabs`; all start collapsed.
+    pub fn new(sections: Vec<Tab>) -> Self {
+        Accordion {
+            sections,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_multiple(mut self, multiple: bool) -> Self {
+        self.multiple = multiple;
+        self
+    }
+
+    pub fn is_expanded(&self, id: &str) -> bool {
+        self.expanded.contains(id)
+    }
+
+    pub fn focused(&self) -> Option<&str> {
+        self.sections.get(self.focused).map(|s| s.id.as_str())
+    }
+
+    pub fn expand(&mut self, id: &str) {
+        if !self.sections.iter().any(|s| s.id == id && !s.disabled) {
+            return;
+        }
+        if !self.multiple {
+            self.expanded.clear();
+        }
+        self.expanded.insert(id.to_string());
+    }
+
+    pub fn collapse(&mut self, id: &str) {
+        self.expanded.remove(id);
+    }
+
+    pub fn toggle(&mut self, id: &str) {
+        if self.is_expanded(id) {
+            self.collapse(id);
+        } else {
+            self.expand(id);
+        }
+    }
+
+    /// Apply a header click payload (a section id), which also focuses the header.
+    pub fn handle(&mut self, payload: &str) {
+        if let Some(i) = self.sections.iter().position(|s| s.id == payload) {
+            self.focused = i;
+        }
+        self.toggle(payload);
+    }
+
+    /// Apply a key press (named like the DOM's `KeyboardEvent.key`). Returns whether it
+    /// was handled.
+    pub fn key(&mut self, key: &str) -> bool {
+        let n = self.sections.len();
+        if n == 0 {
+            return false;
+        }
+        match key {
+            "ArrowDown" => self.focused = (self.focused + 1) % n,
+            "ArrowUp" => self.focused = (self.focused + n - 1) % n,
+            "Home" => self.focused = 0,
+            "End" => self.focused = n - 1,
+            "Enter" | " " => {
+                let id = self.sections[self.focused].id.clone();
+                self.toggle(&id);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Render the headers and the expanded panels, built by `content` from the section id.
+    pub fn render(&self, handler: &str, mut content: impl FnMut(&str) -> VNode) -> VNode {
+        let mut children = Vec::with_capacity(self.sections.len() * 2);
+        for (i, section) in self.sections.iter().enumerate() {
+            let expanded = self.is_expanded(&section.id);
+            let mut props = Props::new()
+                .set("key", format!("accordion-header-{}", section.id))
+                .set(
+                    "class",
+                    if expanded {
+                        "accordion-header expanded"
+                    } else {
+                        "accordion-header"
+                    },
+                )
+                .set("aria-expanded", expanded.to_string())
+                .set("aria-controls", format!("accordion-panel-{}", section.id))
+                .set("tabindex", if i == self.focused { "0" } else { "-1" });
+            if section.disabled {
+                props = props.set("aria-disabled", "true");
+            } else {
+                props = props
+                    .set("on:click", handler)
+                    .set("on:click-payload", section.id.clone());
+            }
+            children.push(h("button", props, vec![text(section.label.clone())]));
+            if expanded {
+                children.push(h(
+                    "div",
+                    Props::new()
+                        .set("key", format!("accordion-panel-{}", section.id))
+                        .set("id", format!("accordion-panel-{}", section.id))
+                        .set("role", "region")
+                        .set("class", "accordion-panel")
+                        .set("data-transition", "accordion"),
+                    vec![content(&section.id)],
+                ));
+            }
+        }
+        h("div", Props::new().set("class", "accordion"), children)
+    }
+}
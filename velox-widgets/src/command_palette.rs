@@ -0,0 +1,310 @@
+//! Command palette: a `Ctrl+K` overlay that filters registered commands as you type.
+//!
+//! The palette keeps a list of `Command`s and a query. While closed it renders a hidden
+//! element binding `Ctrl+K` (through the renderer's shortcut registry) to its handler;
+//! while open it renders an overlay marked `shortcut-scope="command-palette"` whose
+//! `Escape`, arrow, and `Enter` bindings shadow the app's own while it is up. Every
+//! binding and click dispatches the handler with a payload for `handle`, which returns
+//! the id of the command to run, if any.
+//!
+//! Shortcut hints next to each command come from `with_shortcuts`, fed with the
+//! registry's `Shortcuts::bindings()`: a binding whose handler is a command's id labels
+//! that command.
+
+use velox_dom::{Props, VNode, h, text};
+
+use crate::fuzzy::{self, Match};
+use crate::text_input::TextInput;
+
+/// The combination that opens and closes the palette.
+pub const SHORTCUT: &str = "Ctrl+K";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Command {
+    pub id: String,
+    pub title: String,
+    pub group: Option<String>,
+    pub shortcut: Option<String>,
+}
+
+impl Command {
+    pub fn new(id: impl Into<String>, title: impl Into<String>) -> Self {
+        Command {
+            id: id.into(),
+            title: title.into(),
+            group: None,
+            shortcut: None,
+        }
+    }
+
+    /// A category shown before the title, e.g. "File" for "File: Save".
+    pub fn with_group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    pub fn with_shortcut(mut self, shortcut: impl Into<String>) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+
+    /// The text the query is matched against.
+    fn label(&self) -> String {
+        match &self.group {
+            Some(group) => format!("{}: {}", group, self.title),
+            None => self.title.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CommandPalette {
+    commands: Vec<Command>,
+    query: TextInput,
+    open: bool,
+    selected: usize,
+    max_results: usize,
+}
+
+impl CommandPalette {
+    pub fn new(commands: Vec<Command>) -> Self {
+        CommandPalette {
+            commands,
+            query: TextInput::new().with_placeholder("Type a command"),
+            open: false,
+            selected: 0,
+            max_results: 20,
+        }
+    }
+
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.query = self.query.with_placeholder(placeholder);
+        self
+    }
+
+    /// Show at most `max` matches (20 by default).
+    pub fn with_max_results(mut self, max: usize) -> Self {
+        self.max_results = max.max(1);
+        self
+    }
+
+    /// Label commands with the shortcuts bound to their ids, as listed by
+    /// `Shortcuts::bindings()`. Commands with an explicit shortcut keep it.
+    pub fn with_shortcuts(mut self, bindings: &[(String, String)]) -> Self {
+        for command in self.commands.iter_mut().filter(|c| c.shortcut.is_none()) {
+            command.shortcut = bindings
+                .iter()
+                .find(|(_, handler)| *handler == command.id)
+                .map(|(combo, _)| combo.clone());
+        }
+        self
+    }
+
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Register a command, replacing one with the same id.
+    pub fn add(&mut self, command: Command) {
+        match self.commands.iter_mut().find(|c| c.id == command.id) {
+            Some(existing) => *existing = command,
+            None => self.commands.push(command),
+        }
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.commands.retain(|c| c.id != id);
+        self.selected = 0;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Open with an empty query and the first command selected.
+    pub fn open(&mut self) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    pub fn toggle(&mut self) {
+        if self.open { self.close() } else { self.open() }
+    }
+
+    pub fn query(&self) -> &str {
+        self.query.value()
+    }
+
+    /// Take the typed query (the `on:input` payload) and select the best match.
+    pub fn set_query(&mut self, query: &str) {
+        self.query.set(query);
+        self.selected = 0;
+    }
+
+    /// Commands matching the query, best first. An empty query lists every command in
+    /// registration order.
+    pub fn results(&self) -> Vec<&Command> {
+        self.matches().into_iter().map(|(c, _)| c).collect()
+    }
+
+    pub fn selected(&self) -> Option<&Command> {
+        self.results().get(self.selected).copied()
+    }
+
+    /// Apply a payload dispatched by the rendered palette: `toggle`, `close`, `up`,
+    /// `down`, `run` (the selection), or `run:<id>` (a clicked command). Returns the id
+    /// of the command to run, which also closes the palette.
+    pub fn handle(&mut self, payload: &str) -> Option<String> {
+        match payload {
+            "toggle" => self.toggle(),
+            "close" => self.close(),
+            "up" => self.move_selection(-1),
+            "down" => self.move_selection(1),
+            "run" => return self.run(self.selected().map(|c| c.id.clone())),
+            _ => {
+                let id = payload.strip_prefix("run:")?;
+                let known = self.commands.iter().any(|c| c.id == id);
+                return self.run(known.then(|| id.to_string()));
+            }
+        }
+        None
+    }
+
+    /// Apply a key press (named like the DOM's `KeyboardEvent.key`) for apps that forward
+    /// keys themselves instead of going through the shortcut registry. Returns the id of
+    /// the command to run, if the key ran one.
+    pub fn key(&mut self, key: &str) -> Option<String> {
+        if !self.open {
+            return None;
+        }
+        match key {
+            "ArrowUp" => self.handle("up"),
+            "ArrowDown" => self.handle("down"),
+            "Enter" => self.handle("run"),
+            "Escape" => self.handle("close"),
+            _ => None,
+        }
+    }
+
+    /// The palette's view. `handler` receives the payloads for `handle`; `on_input`
+    /// receives the query text for `set_query`.
+    pub fn render(&self, handler: &str, on_input: &str) -> VNode {
+        let bind = |combo: &str, payload: &str| {
+            h(
+                "span",
+                Props::new()
+                    .set("shortcut", combo)
+                    .set("on:shortcut", handler)
+                    .set("on:shortcut-payload", payload)
+                    .set("style", "display: none"),
+                vec![],
+            )
+        };
+        if !self.open {
+            return bind(SHORTCUT, "toggle");
+        }
+
+        let items: Vec<VNode> = self
+            .matches()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (c, m))| {
+                let selected = i == self.selected;
+                let mut children = vec![h(
+                    "span",
+                    Props::new().set("class", "command-palette-title"),
+                    vec![fuzzy::highlight(&c.label(), &m.ranges)],
+                )];
+                if let Some(shortcut) = &c.shortcut {
+                    children.push(h(
+                        "kbd",
+                        Props::new().set("class", "command-palette-shortcut"),
+                        vec![text(shortcut)],
+                    ));
+                }
+                h(
+                    "div",
+                    Props::new()
+                        .set(
+                            "class",
+                            if selected {
+                                "command-palette-item selected"
+                            } else {
+                                "command-palette-item"
+                            },
+                        )
+                        .set("role", "option")
+                        .set("aria-selected", selected.to_string())
+                        .set("style", "display: flex; justify-content: space-between")
+                        .set("on:click", handler)
+                        .set("on:click-payload", format!("run:{}", c.id)),
+                    children,
+                )
+            })
+            .collect();
+        let list = if items.is_empty() {
+            h(
+                "div",
+                Props::new().set("class", "command-palette-empty"),
+                vec![text("No matching commands")],
+            )
+        } else {
+            h(
+                "div",
+                Props::new()
+                    .set("class", "command-palette-list")
+                    .set("role", "listbox"),
+                items,
+            )
+        };
+
+        let dialog = h(
+            "div",
+            Props::new()
+                .set("class", "command-palette")
+                .set("role", "dialog")
+                .set("aria-modal", "true")
+                .set("aria-label", "Command palette"),
+            vec![self.query.render(on_input), list],
+        );
+        h(
+            "div",
+            Props::new()
+                .set("class", "command-palette-backdrop")
+                .set("style", "z-index: 100")
+                .set("shortcut-scope", "command-palette"),
+            vec![
+                bind(SHORTCUT, "toggle"),
+                bind("Escape", "close"),
+                bind("ArrowUp", "up"),
+                bind("ArrowDown", "down"),
+                bind("Enter", "run"),
+                dialog,
+            ],
+        )
+    }
+
+    fn matches(&self) -> Vec<(&Command, Match)> {
+        let mut matches = fuzzy::filter(self.query.value(), &self.commands, |c| c.label());
+        matches.truncate(self.max_results);
+        matches
+    }
+
+    fn move_selection(&mut self, delta: i64) {
+        let len = self.results().len() as i64;
+        if len > 0 {
+            self.selected = (self.selected as i64 + delta).rem_euclid(len) as usize;
+        }
+    }
+
+    fn run(&mut self, id: Option<String>) -> Option<String> {
+        let id = id?;
+        self.close();
+        Some(id)
+    }
+}
@@ -0,0 +1,438 @@
+//! Calendar date picker.
+//!
+//! The picker is a field button that toggles a popup with a month grid. Everything it
+//! renders dispatches one handler name, with the action as payload (`toggle`, `prev`,
+//! `next`, or an ISO date for a day cell), for `handle`, which returns the new date when
+//! one was picked. For `<date-picker v-model="due" />` in a template, `mount` swaps the
+//! picker in for the element with `update:due` as its handler.
+//!
+//! Keys forwarded to `key` move the focused day (arrows, `PageUp`/`PageDown` by month,
+//! `Home`/`End` to the week's ends), pick it with `Enter`, and close with `Escape`. Days
+//! outside `min`/`max` can't be picked or focused. The focused cell carries
+//! `ref="date-picker-focus"`, so the app can move focus to it with `Refs::focus`.
+
+use std::fmt;
+
+use velox_dom::{Props, VNode, h, text};
+
+/// A calendar date in the proleptic Gregorian calendar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    /// `None` if the month or day is out of range.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        ((1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day))
+            .then_some(Date { year, month, day })
+    }
+
+    /// Parse `YYYY-MM-DD`.
+    pub fn parse(s: &str) -> Option<Date> {
+        let mut parts = s.trim().splitn(3, '-');
+        let year = parts.next()?.parse().ok()?;
+        let month = parts.next()?.parse().ok()?;
+        let day = parts.next()?.parse().ok()?;
+        Date::new(year, month, day)
+    }
+
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    pub fn month(self) -> u32 {
+        self.month
+    }
+
+    pub fn day(self) -> u32 {
+        self.day
+    }
+
+    /// Day of the week, 0 for Monday through 6 for Sunday.
+    pub fn weekday(self) -> u32 {
+        // 1970-01-01 was a Thursday.
+        (self.days_since_epoch() + 3).rem_euclid(7) as u32
+    }
+
+    pub fn add_days(self, days: i64) -> Date {
+        Date::from_days_since_epoch(self.days_since_epoch() + days)
+    }
+
+    /// Move by whole months, keeping the day where the target month has it and using its
+    /// last day otherwise.
+    pub fn add_months(self, months: i32) -> Date {
+        let index = self.year as i64 * 12 + self.month as i64 - 1 + months as i64;
+        let year = index.div_euclid(12) as i32;
+        let month = index.rem_euclid(12) as u32 + 1;
+        Date {
+            year,
+            month,
+            day: self.day.min(days_in_month(year, month)),
+        }
+    }
+
+    fn days_since_epoch(self) -> i64 {
+        // Howard Hinnant's days_from_civil.
+        let y = self.year as i64 - (self.month <= 2) as i64;
+        let era = y.div_euclid(400);
+        let yoe = y - era * 400;
+        let m = self.month as i64;
+        let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146097 + doe - 719468
+    }
+
+    fn from_days_since_epoch(days: i64) -> Date {
+        let z = days + 719468;
+        let era = z.div_euclid(146097);
+        let doe = z - era * 146097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + (month <= 2) as i64) as i32;
+        Date { year, month, day }
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAYS: [&str; 7] = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+
+#[derive(Clone, Debug)]
+pub struct DatePicker {
+    value: Option<Date>,
+    /// The focused day; its month is the one shown.
+    cursor: Date,
+    min: Option<Date>,
+    max: Option<Date>,
+    open: bool,
+    placeholder: String,
+}
+
+impl DatePicker {
+    /// A closed picker with no value that opens on `today`'s month.
+    pub fn new(today: Date) -> Self {
+        DatePicker {
+            value: None,
+            cursor: today,
+            min: None,
+            max: None,
+            open: false,
+            placeholder: "Select a date".to_string(),
+        }
+    }
+
+    pub fn with_range(mut self, min: Option<Date>, max: Option<Date>) -> Self {
+        self.min = min;
+        self.max = max;
+        self.cursor = self.clamp(self.cursor);
+        self
+    }
+
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    pub fn value(&self) -> Option<Date> {
+        self.value
+    }
+
+    /// Set the value from the model; the popup moves to its month.
+    pub fn set_value(&mut self, value: Option<Date>) {
+        self.value = value;
+        if let Some(v) = value {
+            self.cursor = self.clamp(v);
+        }
+    }
+
+    pub fn focused(&self) -> Date {
+        self.cursor
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Whether `date` is within `min` and `max`.
+    pub fn is_selectable(&self, date: Date) -> bool {
+        self.min.is_none_or(|m| date >= m) && self.max.is_none_or(|m| date <= m)
+    }
+
+    /// Apply a payload dispatched by `render`. Returns the picked date, if any.
+    pub fn handle(&mut self, payload: &str) -> Option<Date> {
+        match payload {
+            "toggle" => self.open = !self.open,
+            "prev" => self.cursor = self.clamp(self.cursor.add_months(-1)),
+            "next" => self.cursor = self.clamp(self.cursor.add_months(1)),
+            iso => return Date::parse(iso).and_then(|d| self.pick(d)),
+        }
+        None
+    }
+
+    /// Apply a key press (named like the DOM's `KeyboardEvent.key`). Returns the picked
+    /// date, if any.
+    pub fn key(&mut self, key: &str) -> Option<Date> {
+        let moved = match key {
+            "ArrowLeft" => self.cursor.add_days(-1),
+            "ArrowRight" => self.cursor.add_days(1),
+            "ArrowUp" => self.cursor.add_days(-7),
+            "ArrowDown" => self.cursor.add_days(7),
+            "PageUp" => self.cursor.add_months(-1),
+            "PageDown" => self.cursor.add_months(1),
+            "Home" => self.cursor.add_days(-(self.cursor.weekday() as i64)),
+            "End" => self.cursor.add_days(6 - self.cursor.weekday() as i64),
+            "Enter" | " " if self.open => return self.pick(self.cursor),
+            "Enter" | " " => {
+                self.open = true;
+                return None;
+            }
+            "Escape" => {
+                self.open = false;
+                return None;
+            }
+            _ => return None,
+        };
+        self.open = true;
+        self.cursor = self.clamp(moved);
+        None
+    }
+
+    /// Render the field and, while open, the month grid. Every element dispatches
+    /// `handler` with its action as payload.
+    pub fn render(&self, handler: &str) -> VNode {
+        let label = self
+            .value
+            .map_or_else(|| self.placeholder.clone(), |d| d.to_string());
+        let field = h(
+            "button",
+            Props::new()
+                .set(
+                    "class",
+                    if self.value.is_some() {
+                        "date-picker-field"
+                    } else {
+                        "date-picker-field empty"
+                    },
+                )
+                .set("aria-haspopup", "dialog")
+                .set("aria-expanded", self.open.to_string())
+                .set("on:click", handler)
+                .set("on:click-payload", "toggle"),
+            vec![text(label)],
+        );
+        let mut props = Props::new().set("class", "date-picker");
+        if let Some(v) = self.value {
+            props = props.set("value", v.to_string());
+        }
+        let mut children = vec![field];
+        if self.open {
+            children.push(self.render_popup(handler));
+        }
+        h("div", props, children)
+    }
+
+    /// Replace the `<date-picker>` element in `root` whose `on:input` is `handler` (as
+    /// compiled from `<date-picker v-model="due" />`, handler `update:due`) with the
+    /// rendered picker. Returns whether one was found.
+    pub fn mount(&self, root: &mut VNode, handler: &str) -> bool {
+        match root {
+            VNode::Element { tag, props, .. }
+                if tag == "date-picker" && props.str("on:input") == Some(handler) =>
+            {
+                *root = self.render(handler);
+                true
+            }
+            VNode::Element { children, .. } | VNode::Fragment(children) => {
+                children.iter_mut().any(|c| self.mount(c, handler))
+            }
+            VNode::Text(_) | VNode::Component { .. } => false,
+        }
+    }
+
+    fn render_popup(&self, handler: &str) -> VNode {
+        let nav = |payload: &str, label: &str, enabled: bool| {
+            let mut p = Props::new()
+                .set("class", format!("date-picker-{}", payload))
+                .set("aria-label", label);
+            if enabled {
+                p = p.set("on:click", handler).set("on:click-payload", payload);
+            } else {
+                p = p.set("aria-disabled", "true");
+            }
+            h(
+                "button",
+                p,
+                vec![text(if payload == "prev" { "‹" } else { "›" })],
+            )
+        };
+        let first = Date {
+            day: 1,
+            ..self.cursor
+        };
+        let last = Date {
+            day: days_in_month(first.year, first.month),
+            ..first
+        };
+        let header = h(
+            "div",
+            Props::new()
+                .set("class", "date-picker-header")
+                .set("style", "display: flex; justify-content: space-between"),
+            vec![
+                nav("prev", "Previous month", self.min.is_none_or(|m| m < first)),
+                h(
+                    "span",
+                    Props::new()
+                        .set("class", "date-picker-title")
+                        .set("aria-live", "polite"),
+                    vec![text(format!(
+                        "{} {}",
+                        MONTHS[first.month as usize - 1],
+                        first.year
+                    ))],
+                ),
+                nav("next", "Next month", self.max.is_none_or(|m| m > last)),
+            ],
+        );
+
+        let row = |cells: Vec<VNode>| {
+            h(
+                "div",
+                Props::new()
+                    .set("role", "row")
+                    .set("style", "display: flex"),
+                cells,
+            )
+        };
+        let mut rows = vec![row(WEEKDAYS
+            .iter()
+            .map(|d| {
+                h(
+                    "span",
+                    Props::new()
+                        .set("role", "columnheader")
+                        .set("class", "date-picker-weekday"),
+                    vec![text(*d)],
+                )
+            })
+            .collect())];
+        let mut day = first.add_days(-(first.weekday() as i64));
+        while day <= last {
+            let cells = (0..7)
+                .map(|_| {
+                    let cell = self.render_day(day, handler);
+                    day = day.add_days(1);
+                    cell
+                })
+                .collect();
+            rows.push(row(cells));
+        }
+        h(
+            "div",
+            Props::new()
+                .set("class", "date-picker-popup")
+                .set("role", "dialog"),
+            vec![
+                header,
+                h(
+                    "div",
+                    Props::new()
+                        .set("class", "date-picker-grid")
+                        .set("role", "grid"),
+                    rows,
+                ),
+            ],
+        )
+    }
+
+    fn render_day(&self, day: Date, handler: &str) -> VNode {
+        let mut class = String::from("date-picker-day");
+        if day.month != self.cursor.month {
+            class.push_str(" outside");
+        }
+        if Some(day) == self.value {
+            class.push_str(" selected");
+        }
+        let focused = day == self.cursor;
+        if focused {
+            class.push_str(" focused");
+        }
+        let mut props = Props::new()
+            .set("role", "gridcell")
+            .set("aria-label", day.to_string())
+            .set("aria-selected", (Some(day) == self.value).to_string())
+            .set("tabindex", if focused { "0" } else { "-1" });
+        if focused {
+            props = props.set("ref", "date-picker-focus");
+        }
+        if self.is_selectable(day) {
+            props = props
+                .set("on:click", handler)
+                .set("on:click-payload", day.to_string());
+        } else {
+            class.push_str(" disabled");
+            props = props.set("aria-disabled", "true");
+        }
+        h(
+            "button",
+            props.set("class", class),
+            vec![text(day.day.to_string())],
+        )
+    }
+
+    fn pick(&mut self, date: Date) -> Option<Date> {
+        if !self.is_selectable(date) {
+            return None;
+        }
+        self.value = Some(date);
+        self.cursor = date;
+        self.open = false;
+        Some(date)
+    }
+
+    fn clamp(&self, date: Date) -> Date {
+        let date = self.min.map_or(date, |m| date.max(m));
+        self.max.map_or(date, |m| date.min(m))
+    }
+}
@@ -0,0 +1,1008 @@
+//! Dockable panels: tab groups in resizable splits, dragged between them and out into
+//! windows of their own.
+//!
+//! A `Dock` lays its panels out as a tree of `DockNode`s: tab groups showing one panel at
+//! a time, inside `SplitPane`s. A tab dropped on a group's outer quarter at an edge
+//! splits the group (`Zone::Left`, `Right`, `Top`, `Bottom`), in the middle or on the tab
+//! strip it joins the group's tabs (`Zone::Center`), and outside the dock it is undocked
+//! into a `DockWindow`, for the app to show with `render_window` (in a `VeloxUi` of its
+//! own, for a host with its own event loop). Tabs and dividers are `on:pan` elements
+//! whose drags, drops and clicks all go to one handler, for `handle`.
+//!
+//! The whole layout, windows included, is saved and restored as JSON (`to_json`,
+//! `restore_json`), under the dock's id in the app's `Settings` with `save` and `restore`.
+
+use velox_core::settings::Settings;
+use velox_dom::{Props, VNode, h, text};
+
+use crate::split_pane::{DIVIDER_SIZE, Direction, SplitPane, string_field};
+use crate::zoom_view::number_field;
+
+/// Height of a tab group's tab strip, in logical pixels.
+pub const TAB_HEIGHT: f32 = 28.0;
+/// How far a tab moves before it is dragged rather than clicked, in logical pixels.
+pub const DRAG_THRESHOLD: f32 = 4.0;
+/// Size of the window a panel is undocked into, in logical pixels.
+pub const WINDOW_SIZE: (f32, f32) = (480.0, 360.0);
+
+/// Where on a tab group a panel is dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Zone {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    /// Among the group's tabs.
+    Center,
+}
+
+impl Zone {
+    /// The zone `(x, y)` is in on a group of `width` by `height` with its top-left corner
+    /// at the origin: the edge it is within a quarter of the size from (the nearest one),
+    /// or the middle.
+    pub fn at(x: f32, y: f32, width: f32, height: f32) -> Zone {
+        let (fx, fy) = (x / width.max(1.0), y / height.max(1.0));
+        let edges = [
+            (fx, Zone::Left),
+            (1.0 - fx, Zone::Right),
+            (fy, Zone::Top),
+            (1.0 - fy, Zone::Bottom),
+        ];
+        let (near, zone) =
+            edges.into_iter().fold(
+                (f32::INFINITY, Zone::Center),
+                |a, b| if b.0 < a.0 { b } else { a },
+            );
+        if near < 0.25 { zone } else { Zone::Center }
+    }
+
+    /// The direction of the split an edge zone makes.
+    fn direction(self) -> Option<Direction> {
+        match self {
+            Zone::Left | Zone::Right => Some(Direction::Horizontal),
+            Zone::Top | Zone::Bottom => Some(Direction::Vertical),
+            Zone::Center => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum DockNode {
+    /// Panels shown one at a time under a strip of their tabs.
+    Tabs { panels: Vec<String>, active: usize },
+    /// Nodes side by side or stacked, resized by dragging the dividers.
+    Split {
+        split: SplitPane,
+        children: Vec<DockNode>,
+    },
+}
+
+impl DockNode {
+    /// A tab group with the first panel active.
+    pub fn tabs(panels: &[&str]) -> Self {
+        DockNode::Tabs {
+            panels: panels.iter().map(|p| p.to_string()).collect(),
+            active: 0,
+        }
+    }
+
+    /// `children` (at least one) sharing the space equally.
+    pub fn split(direction: Direction, children: Vec<DockNode>) -> Self {
+        DockNode::Split {
+            split: SplitPane::new("", direction, children.len()),
+            children,
+        }
+    }
+
+    /// `split`, sharing the space in proportion to `ratios`.
+    pub fn split_with(direction: Direction, ratios: &[f32], children: Vec<DockNode>) -> Self {
+        DockNode::Split {
+            split: SplitPane::new("", direction, children.len()).with_ratios(ratios),
+            children,
+        }
+    }
+
+    /// The panels in the node, in order.
+    pub fn panels(&self) -> Vec<&str> {
+        match self {
+            DockNode::Tabs { panels, .. } => panels.iter().map(String::as_str).collect(),
+            DockNode::Split { children, .. } => {
+                children.iter().flat_map(DockNode::panels).collect()
+            }
+        }
+    }
+
+    pub fn contains(&self, panel: &str) -> bool {
+        match self {
+            DockNode::Tabs { panels, .. } => panels.iter().any(|p| p == panel),
+            DockNode::Split { children, .. } => children.iter().any(|c| c.contains(panel)),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        matches!(self, DockNode::Tabs { panels, .. } if panels.is_empty())
+    }
+
+    fn select(&mut self, panel: &str) -> bool {
+        match self {
+            DockNode::Tabs { panels, active } => match panels.iter().position(|p| p == panel) {
+                Some(i) if i != *active => {
+                    *active = i;
+                    true
+                }
+                _ => false,
+            },
+            DockNode::Split { children, .. } => children.iter_mut().any(|c| c.select(panel)),
+        }
+    }
+
+    /// Take `panel` out, dropping the groups and splits it leaves empty or alone.
+    fn remove(&mut self, panel: &str) -> bool {
+        match self {
+            DockNode::Tabs { panels, active } => {
+                let Some(i) = panels.iter().position(|p| p == panel) else {
+                    return false;
+                };
+                panels.remove(i);
+                if i < *active || *active >= panels.len() {
+                    *active = active.saturating_sub(1);
+                }
+                true
+            }
+            DockNode::Split { split, children } => {
+                let Some(i) = children.iter_mut().position(|c| c.remove(panel)) else {
+                    return false;
+                };
+                if children[i].is_empty() && children.len() > 1 {
+                    children.remove(i);
+                    let mut ratios = split.ratios().to_vec();
+                    ratios.remove(i);
+                    *split =
+                        SplitPane::new("", split.direction(), children.len()).with_ratios(&ratios);
+                }
+                if children.len() == 1 {
+                    *self = children.remove(0);
+                }
+                true
+            }
+        }
+    }
+
+    /// Dock `panel` in `zone` of the group holding `target`: beside it in the split it is
+    /// in when that runs the zone's way, otherwise in a new split with it.
+    fn insert(&mut self, target: &str, panel: &str, zone: Zone) -> bool {
+        match self {
+            DockNode::Tabs { panels, active } => {
+                if !panels.iter().any(|p| p == target) {
+                    return false;
+                }
+                let Some(direction) = zone.direction() else {
+                    panels.push(panel.to_string());
+                    *active = panels.len() - 1;
+                    return true;
+                };
+                let group = std::mem::replace(self, DockNode::tabs(&[]));
+                let new = DockNode::tabs(&[panel]);
+                let children = if matches!(zone, Zone::Left | Zone::Top) {
+                    vec![new, group]
+                } else {
+                    vec![group, new]
+                };
+                *self = DockNode::split(direction, children);
+                true
+            }
+            DockNode::Split { split, children } => {
+                let Some(i) = children.iter().position(|c| c.contains(target)) else {
+                    return false;
+                };
+                let beside = matches!(children[i], DockNode::Tabs { .. })
+                    && zone.direction() == Some(split.direction());
+                if !beside {
+                    return children[i].insert(target, panel, zone);
+                }
+                let at = if matches!(zone, Zone::Left | Zone::Top) {
+                    i
+                } else {
+                    i + 1
+                };
+                let mut ratios = split.ratios().to_vec();
+                ratios[i] /= 2.0;
+                ratios.insert(at, ratios[i]);
+                children.insert(at, DockNode::tabs(&[panel]));
+                *split = SplitPane::new("", split.direction(), children.len()).with_ratios(&ratios);
+                true
+            }
+        }
+    }
+
+    /// Size the splits for the node to fill `width` by `height`.
+    fn fit(&mut self, width: f32, height: f32) {
+        if let DockNode::Split { split, children } = self {
+            let horizontal = split.direction() == Direction::Horizontal;
+            split.set_length(if horizontal { width } else { height });
+            for (i, child) in children.iter_mut().enumerate() {
+                let length = split.pane_length(i);
+                if horizontal {
+                    child.fit(length, height)
+                } else {
+                    child.fit(width, length)
+                }
+            }
+        }
+    }
+
+    /// The rects the children of a split filling `rect` are laid out in.
+    fn child_rects(split: &SplitPane, (x, y, width, height): Rect) -> Vec<Rect> {
+        let mut at = 0.0;
+        (0..split.ratios().len())
+            .map(|i| {
+                let length = split.pane_length(i);
+                let rect = match split.direction() {
+                    Direction::Horizontal => (x + at, y, length, height),
+                    Direction::Vertical => (x, y + at, width, length),
+                };
+                at += length + DIVIDER_SIZE;
+                rect
+            })
+            .collect()
+    }
+
+    /// The panels of the group at `(px, py)` when the node fills `rect`, and the group's
+    /// rect.
+    fn group_at(&self, rect: Rect, px: f32, py: f32) -> Option<(&[String], Rect)> {
+        let (x, y, width, height) = rect;
+        if px < x || py < y || px >= x + width || py >= y + height {
+            return None;
+        }
+        match self {
+            DockNode::Tabs { panels, .. } => Some((panels, rect)),
+            DockNode::Split { split, children } => {
+                let rects = DockNode::child_rects(split, rect);
+                children
+                    .iter()
+                    .zip(rects)
+                    .find_map(|(child, rect)| child.group_at(rect, px, py))
+            }
+        }
+    }
+
+    fn at_path_mut(&mut self, path: &str) -> Option<&mut DockNode> {
+        let mut node = self;
+        for step in path.split('.').filter(|s| !s.is_empty()) {
+            let DockNode::Split { children, .. } = node else {
+                return None;
+            };
+            node = children.get_mut(step.parse::<usize>().ok()?)?;
+        }
+        Some(node)
+    }
+
+    fn render(
+        &self,
+        at: &Place,
+        path: &str,
+        size: (f32, f32),
+        content: &mut dyn FnMut(&str) -> VNode,
+    ) -> VNode {
+        let (width, height) = size;
+        match self {
+            DockNode::Tabs { panels, active } => {
+                let tabs = panels
+                    .iter()
+                    .enumerate()
+                    .map(|(i, panel)| {
+                        let mut class = if i == *active {
+                            "dock-tab active".to_string()
+                        } else {
+                            "dock-tab".to_string()
+                        };
+                        if at.dragging == Some(panel.as_str()) {
+                            class.push_str(" dragging");
+                        }
+                        let props = Props::new()
+                            .set("key", panel.clone())
+                            .set("role", "tab")
+                            .set("class", class)
+                            .set("aria-selected", (i == *active).to_string())
+                            .set("on:pan", at.handler)
+                            .set("on:pan-end", at.handler)
+                            .set("on:pan-payload", format!("tab:{}:{}", at.area, panel));
+                        h("div", props, vec![text(panel.clone())])
+                    })
+                    .collect();
+                let strip = Props::new()
+                    .set("class", "dock-tabs")
+                    .set("role", "tablist")
+                    .set("style", format!("display: flex; height: {}px;", TAB_HEIGHT));
+                let body = panels
+                    .get(*active)
+                    .map(|p| vec![content(p)])
+                    .unwrap_or_default();
+                let panel_style = format!(
+                    "height: {}px; overflow: hidden;",
+                    (height - TAB_HEIGHT).max(0.0).round()
+                );
+                let group_style = format!(
+                    "display: flex; flex-direction: column; width: {}px; height: {}px;",
+                    width.round(),
+                    height.round()
+                );
+                h(
+                    "div",
+                    Props::new()
+                        .set("class", "dock-group")
+                        .set("style", group_style),
+                    vec![
+                        h("div", strip, tabs),
+                        h(
+                            "div",
+                            Props::new()
+                                .set("class", "dock-panel")
+                                .set("style", panel_style),
+                            body,
+                        ),
+                    ],
+                )
+            }
+            DockNode::Split { split, children } => {
+                let rects = DockNode::child_rects(split, (0.0, 0.0, width, height));
+                let panes = children
+                    .iter()
+                    .zip(rects)
+                    .enumerate()
+                    .map(|(i, (child, (_, _, w, h)))| {
+                        let path = if path.is_empty() {
+                            i.to_string()
+                        } else {
+                            format!("{}.{}", path, i)
+                        };
+                        child.render(at, &path, (w, h), content)
+                    })
+                    .collect();
+                split.render_with_ids(at.handler, panes, &format!("split:{}:{}:", at.area, path))
+            }
+        }
+    }
+}
+
+/// A rect as `(x, y, width, height)`.
+type Rect = (f32, f32, f32, f32);
+
+/// What a render needs to know besides the node.
+struct Place<'a> {
+    handler: &'a str,
+    /// `main`, or the id of the window being rendered.
+    area: String,
+    dragging: Option<&'a str>,
+}
+
+/// Panels undocked into a window of their own.
+#[derive(Clone, Debug)]
+pub struct DockWindow {
+    pub id: u32,
+    pub root: DockNode,
+    pub width: f32,
+    pub height: f32,
+}
+
+#[derive(Clone, Debug)]
+pub struct Dock {
+    id: String,
+    root: DockNode,
+    windows: Vec<DockWindow>,
+    next_window: u32,
+    bounds: Rect,
+    /// The tab being dragged, and how far it has moved.
+    drag: Option<(String, f32)>,
+}
+
+impl Dock {
+    /// `panels` in one tab group. `id` names the dock in the settings.
+    pub fn new(id: impl Into<String>, panels: &[&str]) -> Self {
+        Dock {
+            id: id.into(),
+            root: DockNode::tabs(panels),
+            windows: Vec::new(),
+            next_window: 1,
+            bounds: (0.0, 0.0, 0.0, 0.0),
+            drag: None,
+        }
+    }
+
+    /// Start with the panels laid out as `root`.
+    pub fn with_layout(mut self, root: DockNode) -> Self {
+        self.root = root;
+        self.fit();
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn root(&self) -> &DockNode {
+        &self.root
+    }
+
+    pub fn windows(&self) -> &[DockWindow] {
+        &self.windows
+    }
+
+    pub fn window(&self, id: u32) -> Option<&DockWindow> {
+        self.windows.iter().find(|w| w.id == id)
+    }
+
+    /// Every panel, docked or in a window.
+    pub fn panels(&self) -> Vec<&str> {
+        let mut panels = self.root.panels();
+        panels.extend(self.windows.iter().flat_map(|w| w.root.panels()));
+        panels
+    }
+
+    /// The window `panel` is in, or `None` when it is docked (or unknown).
+    pub fn window_of(&self, panel: &str) -> Option<u32> {
+        self.windows
+            .iter()
+            .find(|w| w.root.contains(panel))
+            .map(|w| w.id)
+    }
+
+    /// Where the dock is in the view and its size, in logical pixels: where drops land.
+    pub fn set_bounds(&mut self, x: f32, y: f32, width: f32, height: f32) {
+        if [x, y, width, height].iter().all(|v| v.is_finite()) {
+            self.bounds = (x, y, width.max(0.0), height.max(0.0));
+            self.fit();
+        }
+    }
+
+    /// The size of window `id`'s view, in logical pixels. Returns whether there is one.
+    pub fn set_window_size(&mut self, id: u32, width: f32, height: f32) -> bool {
+        let Some(window) = self.windows.iter_mut().find(|w| w.id == id) else {
+            return false;
+        };
+        window.width = width.max(0.0);
+        window.height = height.max(0.0);
+        window.root.fit(window.width, window.height);
+        true
+    }
+
+    /// Show `panel` in its tab group. Returns whether that changed the group's tab.
+    pub fn select(&mut self, panel: &str) -> bool {
+        self.root.select(panel) || self.windows.iter_mut().any(|w| w.root.select(panel))
+    }
+
+    /// Move `panel` into `zone` of the group holding `target`, wherever either is.
+    /// Returns whether it moved.
+    pub fn dock(&mut self, panel: &str, target: &str, zone: Zone) -> bool {
+        let panels = self.panels();
+        if panel == target || !panels.contains(&panel) || !panels.contains(&target) {
+            return false;
+        }
+        self.take(panel);
+        let inserted = match self.windows.iter_mut().find(|w| w.root.contains(target)) {
+            Some(window) => window.root.insert(target, panel, zone),
+            None => self.root.insert(target, panel, zone),
+        };
+        self.fit();
+        inserted
+    }
+
+    /// Move `panel` into a window of its own. Returns the window's id, or `None` when the
+    /// panel is unknown or already alone in a window.
+    pub fn undock(&mut self, panel: &str) -> Option<u32> {
+        let alone = self
+            .window_of(panel)
+            .and_then(|id| self.window(id))
+            .is_some_and(|w| w.root.panels().len() == 1);
+        if alone || !self.panels().contains(&panel) {
+            return None;
+        }
+        self.take(panel);
+        let id = self.next_window;
+        self.next_window += 1;
+        let (width, height) = WINDOW_SIZE;
+        let mut root = DockNode::tabs(&[panel]);
+        root.fit(width, height);
+        self.windows.push(DockWindow {
+            id,
+            root,
+            width,
+            height,
+        });
+        self.fit();
+        Some(id)
+    }
+
+    /// Dock window `id`'s panels back among the tabs of the dock's first group (the app
+    /// calls this when the window is closed). Returns whether there was a window.
+    pub fn close_window(&mut self, id: u32) -> bool {
+        let Some(i) = self.windows.iter().position(|w| w.id == id) else {
+            return false;
+        };
+        let window = self.windows.remove(i);
+        for panel in window.root.panels() {
+            match self.root.panels().first().map(|p| p.to_string()) {
+                Some(first) => {
+                    self.root.insert(&first, panel, Zone::Center);
+                }
+                None => self.root = DockNode::tabs(&[panel]),
+            }
+        }
+        self.fit();
+        true
+    }
+
+    /// Apply a tab's or a divider's `on:pan` or `on:pan-end` payload. Returns whether the
+    /// layout (or the tab shown as dragged) changed.
+    pub fn handle(&mut self, payload: &str) -> bool {
+        let Some(id) = string_field(payload, "id") else {
+            return false;
+        };
+        let mut parts = id.splitn(3, ':');
+        let (Some(kind), Some(area), Some(rest)) = (parts.next(), parts.next(), parts.next())
+        else {
+            return false;
+        };
+        match kind {
+            "split" => {
+                let Some((path, divider)) = rest.rsplit_once(':') else {
+                    return false;
+                };
+                let Some(divider) = divider.parse().ok() else {
+                    return false;
+                };
+                let Some(node) = self.area_mut(area).and_then(|root| root.at_path_mut(path)) else {
+                    return false;
+                };
+                let DockNode::Split { split, .. } = node else {
+                    return false;
+                };
+                let key = if split.direction() == Direction::Horizontal {
+                    "dx"
+                } else {
+                    "dy"
+                };
+                let moved =
+                    number_field(payload, key).is_some_and(|by| split.move_divider(divider, by));
+                if moved {
+                    self.fit();
+                }
+                moved
+            }
+            "tab" => {
+                let panel = rest.to_string();
+                if let (Some(x), Some(y)) = (number_field(payload, "x"), number_field(payload, "y"))
+                {
+                    let dragged = self
+                        .drag
+                        .take()
+                        .filter(|(p, _)| *p == panel)
+                        .map_or(0.0, |(_, d)| d);
+                    if payload.contains("\"cancelled\":true") {
+                        return dragged >= DRAG_THRESHOLD;
+                    }
+                    if dragged < DRAG_THRESHOLD {
+                        return self.select(&panel);
+                    }
+                    self.drop(area, &panel, x, y);
+                    return true;
+                }
+                let by = number_field(payload, "dx").unwrap_or(0.0).abs()
+                    + number_field(payload, "dy").unwrap_or(0.0).abs();
+                let before = match &self.drag {
+                    Some((p, d)) if *p == panel => *d,
+                    _ => 0.0,
+                };
+                self.drag = Some((panel, before + by));
+                before < DRAG_THRESHOLD && before + by >= DRAG_THRESHOLD
+            }
+            _ => false,
+        }
+    }
+
+    /// The settings key the layout is saved under.
+    pub fn settings_key(&self) -> String {
+        format!("dock.{}", self.id)
+    }
+
+    /// The layout, windows included, as JSON: an object with the dock's `root` node and
+    /// its `windows`. A split is `{"split":..,"ratios":[..],"children":[..]}`, a group
+    /// `{"tabs":[..],"active":0}`, and a window has its `id`, `width`, `height` and `root`.
+    pub fn to_json(&self) -> String {
+        let windows: Vec<String> = self
+            .windows
+            .iter()
+            .map(|w| {
+                format!(
+                    "{{\"id\":{},\"width\":{},\"height\":{},\"root\":{}}}",
+                    w.id,
+                    w.width,
+                    w.height,
+                    node_json(&w.root)
+                )
+            })
+            .collect();
+        format!(
+            "{{\"root\":{},\"windows\":[{}]}}",
+            node_json(&self.root),
+            windows.join(",")
+        )
+    }
+
+    /// Take the layout in `json` (from `to_json`). Panels the dock doesn't have are left
+    /// out, and ones the layout doesn't mention join the first group's tabs. Returns
+    /// whether the JSON was a layout.
+    pub fn restore_json(&mut self, json: &str) -> bool {
+        let Some(layout) = Json::parse(json) else {
+            return false;
+        };
+        let Some(mut root) = layout.get("root").and_then(node_from_json) else {
+            return false;
+        };
+        let mut windows = Vec::new();
+        for window in layout.get("windows").and_then(Json::as_list).unwrap_or(&[]) {
+            let (Some(id), Some(node)) = (
+                window.get("id").and_then(Json::as_num),
+                window.get("root").and_then(node_from_json),
+            ) else {
+                return false;
+            };
+            let size = |key| {
+                window
+                    .get(key)
+                    .and_then(Json::as_num)
+                    .map_or(0.0, |v| v as f32)
+            };
+            windows.push(DockWindow {
+                id: id as u32,
+                root: node,
+                width: size("width"),
+                height: size("height"),
+            });
+        }
+
+        let known: Vec<String> = self.panels().iter().map(|p| p.to_string()).collect();
+        let mut seen = Vec::new();
+        for node in std::iter::once(&mut root).chain(windows.iter_mut().map(|w| &mut w.root)) {
+            for panel in node
+                .panels()
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+            {
+                if !known.contains(&panel) || seen.contains(&panel) {
+                    node.remove(&panel);
+                } else {
+                    seen.push(panel);
+                }
+            }
+        }
+        windows.retain(|w| !w.root.is_empty());
+        for panel in known.iter().filter(|p| !seen.contains(p)) {
+            match root.panels().first().map(|p| p.to_string()) {
+                Some(first) => {
+                    root.insert(&first, panel, Zone::Center);
+                }
+                None => root = DockNode::tabs(&[panel]),
+            }
+        }
+
+        self.root = root;
+        self.next_window = windows.iter().map(|w| w.id + 1).max().unwrap_or(1);
+        self.windows = windows;
+        self.drag = None;
+        self.fit();
+        true
+    }
+
+    /// Take the layout saved in `settings`, if there is one. Returns whether there was.
+    pub fn restore(&mut self, settings: &Settings) -> bool {
+        settings
+            .get(&self.settings_key())
+            .is_some_and(|json| self.restore_json(json))
+    }
+
+    /// Store the layout in `settings`; the app saves them.
+    pub fn save(&self, settings: &mut Settings) {
+        settings.set(&self.settings_key(), self.to_json());
+    }
+
+    /// The docked panels, each built by `content` from its name while it is the shown
+    /// tab of its group.
+    pub fn render(&self, handler: &str, mut content: impl FnMut(&str) -> VNode) -> VNode {
+        let at = Place {
+            handler,
+            area: "main".to_string(),
+            dragging: self.dragging(),
+        };
+        let (_, _, width, height) = self.bounds;
+        let root = self.root.render(&at, "", (width, height), &mut content);
+        h("div", Props::new().set("class", "dock"), vec![root])
+    }
+
+    /// The view of window `id`'s panels, or `None` when there is no such window.
+    pub fn render_window(
+        &self,
+        id: u32,
+        handler: &str,
+        mut content: impl FnMut(&str) -> VNode,
+    ) -> Option<VNode> {
+        let window = self.window(id)?;
+        let at = Place {
+            handler,
+            area: id.to_string(),
+            dragging: self.dragging(),
+        };
+        let root = window
+            .root
+            .render(&at, "", (window.width, window.height), &mut content);
+        Some(h(
+            "div",
+            Props::new().set("class", "dock dock-window"),
+            vec![root],
+        ))
+    }
+
+    /// The tab dragged far enough to be shown as dragged.
+    fn dragging(&self) -> Option<&str> {
+        self.drag
+            .as_ref()
+            .filter(|(_, d)| *d >= DRAG_THRESHOLD)
+            .map(|(p, _)| p.as_str())
+    }
+
+    /// The layout of `area` (`main`, or a window id).
+    fn area_mut(&mut self, area: &str) -> Option<&mut DockNode> {
+        if area == "main" {
+            return Some(&mut self.root);
+        }
+        let id: u32 = area.parse().ok()?;
+        self.windows
+            .iter_mut()
+            .find(|w| w.id == id)
+            .map(|w| &mut w.root)
+    }
+
+    /// Drop `panel`, dragged in `area`, at `(x, y)` in that area's view.
+    fn drop(&mut self, area: &str, panel: &str, x: f32, y: f32) -> bool {
+        let (root, bounds) = if area == "main" {
+            (&self.root, self.bounds)
+        } else {
+            let Some(window) = area.parse().ok().and_then(|id| self.window(id)) else {
+                return false;
+            };
+            (&window.root, (0.0, 0.0, window.width, window.height))
+        };
+        let Some((panels, (gx, gy, gw, gh))) = root.group_at(bounds, x, y) else {
+            return area == "main" && self.undock(panel).is_some();
+        };
+        let zone = if y - gy < TAB_HEIGHT {
+            Zone::Center
+        } else {
+            Zone::at(x - gx, y - gy - TAB_HEIGHT, gw, gh - TAB_HEIGHT)
+        };
+        let target = if panels.iter().any(|p| p == panel) {
+            if zone == Zone::Center {
+                return false;
+            }
+            panels.iter().find(|p| *p != panel).cloned()
+        } else {
+            panels.first().cloned()
+        };
+        target.is_some_and(|target| self.dock(panel, &target, zone))
+    }
+
+    /// Take `panel` out of the layout, closing the window it leaves empty.
+    fn take(&mut self, panel: &str) {
+        self.root.remove(panel);
+        for window in &mut self.windows {
+            window.root.remove(panel);
+        }
+        self.windows.retain(|w| !w.root.is_empty());
+    }
+
+    fn fit(&mut self) {
+        let (_, _, width, height) = self.bounds;
+        self.root.fit(width, height);
+        for window in &mut self.windows {
+            window.root.fit(window.width, window.height);
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn node_json(node: &DockNode) -> String {
+    match node {
+        DockNode::Tabs { panels, active } => {
+            let panels: Vec<String> = panels.iter().map(|p| quote(p)).collect();
+            format!("{{\"tabs\":[{}],\"active\":{}}}", panels.join(","), active)
+        }
+        DockNode::Split { split, children } => {
+            let direction = match split.direction() {
+                Direction::Horizontal => "horizontal",
+                Direction::Vertical => "vertical",
+            };
+            let ratios: Vec<String> = split.ratios().iter().map(|r| r.to_string()).collect();
+            let children: Vec<String> = children.iter().map(node_json).collect();
+            format!(
+                "{{\"split\":\"{}\",\"ratios\":[{}],\"children\":[{}]}}",
+                direction,
+                ratios.join(","),
+                children.join(",")
+            )
+        }
+    }
+}
+
+fn node_from_json(json: &Json) -> Option<DockNode> {
+    if let Some(tabs) = json.get("tabs").and_then(Json::as_list) {
+        let panels: Vec<String> = tabs
+            .iter()
+            .map(|t| t.as_str().map(str::to_string))
+            .collect::<Option<_>>()?;
+        let active = json
+            .get("active")
+            .and_then(Json::as_num)
+            .map_or(0, |a| a as usize);
+        return Some(DockNode::Tabs {
+            active: active.min(panels.len().saturating_sub(1)),
+            panels,
+        });
+    }
+    let direction = match json.get("split")?.as_str()? {
+        "horizontal" => Direction::Horizontal,
+        "vertical" => Direction::Vertical,
+        _ => return None,
+    };
+    let children: Vec<DockNode> = json
+        .get("children")?
+        .as_list()?
+        .iter()
+        .map(node_from_json)
+        .collect::<Option<_>>()?;
+    if children.is_empty() {
+        return None;
+    }
+    let mut split = SplitPane::new("", direction, children.len());
+    if let Some(ratios) = json.get("ratios").and_then(Json::as_list) {
+        let ratios: Option<Vec<f32>> = ratios
+            .iter()
+            .map(|r| r.as_num().map(|r| r as f32))
+            .collect();
+        split.set_ratios(&ratios?);
+    }
+    Some(DockNode::Split { split, children })
+}
+
+/// Just enough JSON to read a saved layout back.
+#[derive(Debug)]
+enum Json {
+    Null,
+    Bool,
+    Num(f64),
+    Str(String),
+    List(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(src: &str) -> Option<Json> {
+        let mut chars = src.chars().peekable();
+        let value = Json::value(&mut chars)?;
+        skip_space(&mut chars);
+        chars.peek().is_none().then_some(value)
+    }
+
+    fn value(chars: &mut std::iter::Peekable<std::str::Chars>) -> Option<Json> {
+        skip_space(chars);
+        match *chars.peek()? {
+            '{' => {
+                chars.next();
+                let mut fields = Vec::new();
+                skip_space(chars);
+                if chars.next_if_eq(&'}').is_some() {
+                    return Some(Json::Object(fields));
+                }
+                loop {
+                    skip_space(chars);
+                    let Json::Str(key) = Json::value(chars)? else {
+                        return None;
+                    };
+                    skip_space(chars);
+                    chars.next_if_eq(&':')?;
+                    fields.push((key, Json::value(chars)?));
+                    skip_space(chars);
+                    match chars.next()? {
+                        ',' => continue,
+                        '}' => return Some(Json::Object(fields)),
+                        _ => return None,
+                    }
+                }
+            }
+            '[' => {
+                chars.next();
+                let mut items = Vec::new();
+                skip_space(chars);
+                if chars.next_if_eq(&']').is_some() {
+                    return Some(Json::List(items));
+                }
+                loop {
+                    items.push(Json::value(chars)?);
+                    skip_space(chars);
+                    match chars.next()? {
+                        ',' => continue,
+                        ']' => return Some(Json::List(items)),
+                        _ => return None,
+                    }
+                }
+            }
+            '"' => {
+                chars.next();
+                let mut s = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => return Some(Json::Str(s)),
+                        '\\' => match chars.next()? {
+                            'n' => s.push('\n'),
+                            't' => s.push('\t'),
+                            'u' => {
+                                let hex: String = (0..4).filter_map(|_| chars.next()).collect();
+                                s.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                            }
+                            c => s.push(c),
+                        },
+                        c => s.push(c),
+                    }
+                }
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(c) =
+                    chars.next_if(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '.'))
+                {
+                    word.push(c);
+                }
+                match word.as_str() {
+                    "null" => Some(Json::Null),
+                    "true" | "false" => Some(Json::Bool),
+                    _ => word.parse().ok().map(Json::Num),
+                }
+            }
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_list(&self) -> Option<&[Json]> {
+        match self {
+            Json::List(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_num(&self) -> Option<f64> {
+        match self {
+            Json::Num(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+fn skip_space(chars: &mut std::iter::Peekable<std::str::Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
@@ -0,0 +1,240 @@
+//! Forms with field validation.
+//!
+//! Register fields with their validators, feed input into `set`, and call `submit` from
+//! the form's `on:submit` handler. A `<button type="submit">` inside a `<form @submit="save">`
+//! dispatches `save` when clicked. `submit` validates every field and only hands the values
+//! to the callback when all pass; otherwise the errors are published and nothing is
+//! submitted.
+//!
+//! Error messages live in a signal, so views and effects that read `error` re-render when
+//! they change. `resolve` answers template lookups: `form.email` is a field value and
+//! `form.errors.email` its error message (empty when valid).
+
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use regex::Regex;
+use velox_core::signal::Signal;
+
+type CheckFn = Rc<dyn Fn(&str) -> Result<(), String>>;
+
+/// A check on a field's text value. Except for `Required`, validators pass empty values,
+/// so optional fields are only checked once filled in.
+#[derive(Clone)]
+pub enum Validator {
+    Required,
+    MinLength(usize),
+    MaxLength(usize),
+    /// The value must be a number no smaller than this.
+    Min(f64),
+    /// The value must be a number no larger than this.
+    Max(f64),
+    /// The value must match; anchor the expression to match the whole value.
+    Pattern(Regex),
+    /// Returns the error message for an invalid value.
+    Custom(CheckFn),
+    /// Another validator with its own error message.
+    WithMessage(Box<Validator>, String),
+}
+
+impl Validator {
+    pub fn pattern(re: &str) -> Result<Validator, regex::Error> {
+        Ok(Validator::Pattern(Regex::new(re)?))
+    }
+
+    pub fn custom(f: impl Fn(&str) -> Result<(), String> + 'static) -> Validator {
+        Validator::Custom(Rc::new(f))
+    }
+
+    /// Replace the default error message.
+    pub fn message(self, message: impl Into<String>) -> Validator {
+        Validator::WithMessage(Box::new(self), message.into())
+    }
+
+    /// The error message for `value`, or `None` if it passes.
+    pub fn check(&self, value: &str) -> Option<String> {
+        let v = value.trim();
+        if v.is_empty() && !matches!(self, Validator::Required | Validator::WithMessage(..)) {
+            return None;
+        }
+        let number = || v.parse::<f64>().ok();
+        match self {
+            Validator::Required => v.is_empty().then(|| "This field is required".to_string()),
+            Validator::MinLength(n) => {
+                (v.chars().count() < *n).then(|| format!("Must be at least {} characters", n))
+            }
+            Validator::MaxLength(n) => {
+                (v.chars().count() > *n).then(|| format!("Must be at most {} characters", n))
+            }
+            Validator::Min(min) => match number() {
+                None => Some("Must be a number".to_string()),
+                Some(x) => (x < *min).then(|| format!("Must be at least {}", min)),
+            },
+            Validator::Max(max) => match number() {
+                None => Some("Must be a number".to_string()),
+                Some(x) => (x > *max).then(|| format!("Must be at most {}", max)),
+            },
+            Validator::Pattern(re) => (!re.is_match(v)).then(|| "Invalid format".to_string()),
+            Validator::Custom(f) => f(value).err(),
+            Validator::WithMessage(inner, message) => inner.check(value).map(|_| message.clone()),
+        }
+    }
+}
+
+impl fmt::Debug for Validator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Validator::Required => write!(f, "Required"),
+            Validator::MinLength(n) => write!(f, "MinLength({})", n),
+            Validator::MaxLength(n) => write!(f, "MaxLength({})", n),
+            Validator::Min(n) => write!(f, "Min({})", n),
+            Validator::Max(n) => write!(f, "Max({})", n),
+            Validator::Pattern(re) => write!(f, "Pattern({:?})", re.as_str()),
+            Validator::Custom(_) => write!(f, "Custom(..)"),
+            Validator::WithMessage(inner, message) => write!(f, "{:?} ({:?})", inner, message),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Field {
+    name: String,
+    value: String,
+    validators: Vec<Validator>,
+}
+
+impl Field {
+    fn error(&self) -> Option<String> {
+        self.validators.iter().find_map(|v| v.check(&self.value))
+    }
+}
+
+pub struct Form {
+    fields: Vec<Field>,
+    errors: Rc<Signal<HashMap<String, String>>>,
+    /// After the first submit attempt, edits revalidate their field immediately.
+    submitted: bool,
+}
+
+impl Default for Form {
+    fn default() -> Self {
+        Form {
+            fields: Vec::new(),
+            errors: Rc::new(Signal::new(HashMap::new())),
+            submitted: false,
+        }
+    }
+}
+
+impl fmt::Debug for Form {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Form")
+            .field("fields", &self.fields)
+            .field("submitted", &self.submitted)
+            .finish()
+    }
+}
+
+impl Form {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `name` with its validators, replacing an earlier registration.
+    pub fn field(&mut self, name: &str, validators: Vec<Validator>) -> &mut Self {
+        match self.fields.iter_mut().find(|f| f.name == name) {
+            Some(field) => field.validators = validators,
+            None => self.fields.push(Field {
+                name: name.to_string(),
+                value: String::new(),
+                validators,
+            }),
+        }
+        self
+    }
+
+    /// Update a field's value. Once a submit was attempted, the field is revalidated so
+    /// its error clears as soon as it is fixed. Unregistered names are ignored.
+    pub fn set(&mut self, name: &str, value: impl Into<String>) {
+        let Some(field) = self.fields.iter_mut().find(|f| f.name == name) else {
+            return;
+        };
+        field.value = value.into();
+        if self.submitted {
+            let error = field.error();
+            let mut errors = self.errors.get();
+            match error {
+                Some(e) => errors.insert(name.to_string(), e),
+                None => errors.remove(name),
+            };
+            self.errors.set(errors);
+        }
+    }
+
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|f| f.name == name)
+            .map(|f| f.value.as_str())
+    }
+
+    pub fn values(&self) -> HashMap<String, String> {
+        self.fields
+            .iter()
+            .map(|f| (f.name.clone(), f.value.clone()))
+            .collect()
+    }
+
+    /// The published error of `name`. Reading it inside an effect subscribes to changes.
+    pub fn error(&self, name: &str) -> Option<String> {
+        self.errors.get().get(name).cloned()
+    }
+
+    /// The error signal, to share with script state.
+    pub fn errors(&self) -> Rc<Signal<HashMap<String, String>>> {
+        self.errors.clone()
+    }
+
+    /// Validate every field and publish the errors. Returns whether the form is valid.
+    pub fn validate(&mut self) -> bool {
+        let errors: HashMap<String, String> = self
+            .fields
+            .iter()
+            .filter_map(|f| Some((f.name.clone(), f.error()?)))
+            .collect();
+        let valid = errors.is_empty();
+        self.errors.set(errors);
+        valid
+    }
+
+    /// Validate and, only if valid, call `on_valid` with the field values. Returns
+    /// whether it was called.
+    pub fn submit(&mut self, on_valid: impl FnOnce(HashMap<String, String>)) -> bool {
+        self.submitted = true;
+        if !self.validate() {
+            return false;
+        }
+        on_valid(self.values());
+        true
+    }
+
+    /// Clear values and errors, e.g. after a successful submit.
+    pub fn reset(&mut self) {
+        for field in &mut self.fields {
+            field.value.clear();
+        }
+        self.submitted = false;
+        self.errors.set(HashMap::new());
+    }
+
+    /// Template lookup: `<prefix>.<field>` is the value and `<prefix>.errors.<field>` the
+    /// error message (empty when valid).
+    pub fn resolve(&self, prefix: &str, key: &str) -> Option<String> {
+        let rest = key.strip_prefix(prefix)?.strip_prefix('.')?;
+        match rest.strip_prefix("errors.") {
+            Some(name) => Some(self.error(name).unwrap_or_default()),
+            None => self.value(rest).map(str::to_string),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Fuzzy matching for filterable lists: scoring, highlight ranges, and rendering.
+//!
+//! `fuzzy_match` finds the query's characters in order (ignoring case and spaces in the
+//! query) and scores the match: consecutive characters, word starts, and an early first
+//! match rank higher, and a query found as a whole substring ranks above any scattered
+//! match. `filter` ranks a list by that score, keeping the original order among equals.
+//! `highlight` renders a string with the matched characters wrapped in
+//! `<mark class="fuzzy-match">`.
+
+use std::ops::Range;
+
+use velox_dom::{Props, VNode, h, text};
+
+/// A successful match: its score and the matched byte ranges of the text, merged where
+/// adjacent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Match {
+    pub score: i32,
+    pub ranges: Vec<Range<usize>>,
+}
+
+/// Match `query` against `text`. An empty query matches everything with score 0 and no
+/// ranges; `None` means some query character is missing.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<Match> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if query.is_empty() {
+        return Some(Match {
+            score: 0,
+            ranges: Vec::new(),
+        });
+    }
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|&(_, c)| c.to_lowercase().next().unwrap_or(c))
+        .collect();
+    let word_start = |i: usize| {
+        i == 0
+            || !chars[i - 1].1.is_alphanumeric()
+            || (chars[i].1.is_uppercase() && chars[i - 1].1.is_lowercase())
+    };
+
+    let substring = (0..lower.len()).find(|&i| lower[i..].starts_with(&query));
+    let positions: Vec<usize> = match substring {
+        Some(start) => (start..start + query.len()).collect(),
+        None => {
+            let mut positions = Vec::with_capacity(query.len());
+            let mut from = 0;
+            for q in &query {
+                let i = from + lower[from..].iter().position(|c| c == q)?;
+                positions.push(i);
+                from = i + 1;
+            }
+            positions
+        }
+    };
+
+    let mut score = if substring.is_some() { 10 } else { 0 };
+    for (n, &i) in positions.iter().enumerate() {
+        score += 1;
+        if n > 0 && positions[n - 1] + 1 == i {
+            score += 5;
+        }
+        if word_start(i) {
+            score += 3;
+        }
+    }
+    score -= positions[0].min(5) as i32;
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for &i in &positions {
+        let (start, c) = chars[i];
+        let end = start + c.len_utf8();
+        match ranges.last_mut() {
+            Some(last) if last.end == start => last.end = end,
+            _ => ranges.push(start..end),
+        }
+    }
+    Some(Match { score, ranges })
+}
+
+/// The items matching `query`, best first; ties keep their order in `items`.
+pub fn filter<'a, T, K: AsRef<str>>(
+    query: &str,
+    items: &'a [T],
+    key: impl Fn(&T) -> K,
+) -> Vec<(&'a T, Match)> {
+    let mut matches: Vec<(&T, Match)> = items
+        .iter()
+        .filter_map(|item| Some((item, fuzzy_match(query, key(item).as_ref())?)))
+        .collect();
+    matches.sort_by_key(|(_, m)| std::cmp::Reverse(m.score));
+    matches
+}
+
+/// `value` as a row of spans, with the byte `ranges` (as returned in a `Match`) wrapped
+/// in bold `<mark class="fuzzy-match">` elements. Ranges off char boundaries are skipped.
+pub fn highlight(value: &str, ranges: &[Range<usize>]) -> VNode {
+    let mut children = Vec::new();
+    let mut pos = 0;
+    for range in ranges {
+        if range.start < pos || value.get(range.clone()).is_none() {
+            continue;
+        }
+        if range.start > pos {
+            children.push(text(&value[pos..range.start]));
+        }
+        children.push(h(
+            "mark",
+            Props::new()
+                .set("class", "fuzzy-match")
+                .set("style", "font-weight: bold"),
+            vec![text(&value[range.clone()])],
+        ));
+        pos = range.end;
+    }
+    if pos < value.len() {
+        children.push(text(&value[pos..]));
+    }
+    h(
+        "span",
+        Props::new()
+            .set("class", "fuzzy-text")
+            .set("style", "display: flex"),
+        children,
+    )
+}
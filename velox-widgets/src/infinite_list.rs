@@ -0,0 +1,186 @@
+//! A list that loads more items as it is scrolled to the end.
+//!
+//! `render` ends the list with an empty sentinel element that has an `on:visible`
+//! handler named `load-more` (see the renderer's `visibility` module). When the sentinel
+//! comes within `threshold` pixels of the viewport, the app receives `load-more`, calls
+//! `begin_load` (which guards against duplicate requests), fetches the next page, and
+//! hands it to `append`. An empty page marks the list exhausted and removes the sentinel.
+//!
+//! Memory stays bounded: with `max_items` set, the oldest items are dropped once the list
+//! grows past it, and a spacer of `item_height * dropped` pixels takes their place so the
+//! content below does not jump.
+
+use std::collections::VecDeque;
+
+use velox_dom::{Props, VNode, h};
+
+/// Handler name the sentinel dispatches.
+pub const LOAD_MORE: &str = "load-more";
+
+#[derive(Clone, Debug)]
+pub struct InfiniteList<T> {
+    items: VecDeque<T>,
+    /// Items dropped from the front to respect `max_items`.
+    dropped: usize,
+    max_items: Option<usize>,
+    item_height: u32,
+    threshold: u32,
+    loading: bool,
+    exhausted: bool,
+}
+
+impl<T> Default for InfiniteList<T> {
+    fn default() -> Self {
+        InfiniteList {
+            items: VecDeque::new(),
+            dropped: 0,
+            max_items: None,
+            item_height: 0,
+            threshold: 200,
+            loading: false,
+            exhausted: false,
+        }
+    }
+}
+
+impl<T> InfiniteList<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep at most `max` items, dropping the oldest. `item_height` is the height in
+    /// pixels of one item, used to size the spacer that replaces dropped items.
+    pub fn with_max_items(mut self, max: usize, item_height: u32) -> Self {
+        self.max_items = Some(max.max(1));
+        self.item_height = item_height;
+        self
+    }
+
+    /// How close (in pixels) the end of the list must come to the viewport before
+    /// `load-more` fires. Defaults to 200.
+    pub fn with_threshold(mut self, px: u32) -> Self {
+        self.threshold = px;
+        self
+    }
+
+    /// Start a load if none is running and the list is not exhausted. Returns whether
+    /// the caller should fetch the next page.
+    pub fn begin_load(&mut self) -> bool {
+        if self.loading || self.exhausted {
+            return false;
+        }
+        self.loading = true;
+        true
+    }
+
+    /// Add a fetched page and end the current load. An empty page means there is
+    /// nothing more to load.
+    pub fn append(&mut self, page: Vec<T>) {
+        self.loading = false;
+        if page.is_empty() {
+            self.exhausted = true;
+            return;
+        }
+        self.items.extend(page);
+        if let Some(max) = self.max_items {
+            while self.items.len() > max {
+                self.items.pop_front();
+                self.dropped += 1;
+            }
+        }
+    }
+
+    /// Drop everything and start over, e.g. after the query changed.
+    pub fn reset(&mut self) {
+        let (max_items, item_height, threshold) =
+            (self.max_items, self.item_height, self.threshold);
+        *self = InfiniteList {
+            max_items,
+            item_height,
+            threshold,
+            ..Self::default()
+        };
+    }
+
+    /// Items currently held in memory.
+    pub fn items(&self) -> impl Iterator<Item = &T> {
+        self.items.iter()
+    }
+
+    /// Index in the full list of the first item held.
+    pub fn first_index(&self) -> usize {
+        self.dropped
+    }
+
+    /// Items loaded so far, including dropped ones; the offset of the next page.
+    pub fn total_loaded(&self) -> usize {
+        self.dropped + self.items.len()
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.exhausted
+    }
+
+    /// Render the list: a spacer for dropped items, one keyed node per item, and the
+    /// sentinel while more can be loaded. `key` must be unique per item; `item` renders
+    /// one item as an element.
+    pub fn render(&self, key: impl Fn(&T) -> String, item: impl Fn(&T) -> VNode) -> VNode {
+        let mut children = Vec::with_capacity(self.items.len() + 2);
+        if self.dropped > 0 {
+            let height = self.dropped as u64 * self.item_height as u64;
+            children.push(h(
+                "div",
+                Props::new()
+                    .set("key", "infinite-list-spacer")
+                    .set("style", format!("height: {}px", height)),
+                vec![],
+            ));
+        }
+        for it in &self.items {
+            children.push(with_key(item(it), key(it)));
+        }
+        if !self.exhausted {
+            // A new key per page, so a sentinel that stays on screen after a short page
+            // counts as newly visible and asks for the next one.
+            children.push(h(
+                "div",
+                Props::new()
+                    .set(
+                        "key",
+                        format!("infinite-list-sentinel-{}", self.total_loaded()),
+                    )
+                    .set(
+                        "class",
+                        if self.loading {
+                            "infinite-list-sentinel loading"
+                        } else {
+                            "infinite-list-sentinel"
+                        },
+                    )
+                    .set("on:visible", LOAD_MORE)
+                    .set("visible-margin", self.threshold.to_string()),
+                vec![],
+            ));
+        }
+        h("div", Props::new().set("class", "infinite-list"), children)
+    }
+}
+
+fn with_key(node: VNode, key: String) -> VNode {
+    match node {
+        VNode::Element {
+            tag,
+            props,
+            children,
+        } => VNode::Element {
+            tag,
+            props: props.set("key", key),
+            children,
+        },
+        text => h("div", Props::new().set("key", key), vec![text]),
+    }
+}
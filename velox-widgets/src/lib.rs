@@ -0,0 +1,37 @@
+//! Ready-made components built from `velox_dom` nodes.
+//!
+//! Each widget is a plain state struct owned by the app (usually behind an `Rc<RefCell<_>>`
+//! in its script state) with a `render` method that returns the widget's `VNode`. Widgets
+//! talk back through the normal `on:<event>` handler names, which the app routes to the
+//! widget's methods from its `on_event` callback.
+
+pub mod accordion;
+pub mod command_palette;
+pub mod date_picker;
+pub mod dock;
+pub mod form;
+pub mod fuzzy;
+pub mod infinite_list;
+pub mod masked_input;
+pub mod number_input;
+pub mod split_pane;
+pub mod tabs;
+pub mod text_input;
+pub mod tree_view;
+pub mod viewport;
+pub mod zoom_view;
+
+pub use accordion::Accordion;
+pub use command_palette::{Command, CommandPalette};
+pub use date_picker::{Date, DatePicker};
+pub use dock::{Dock, DockNode, DockWindow, Zone};
+pub use form::{Form, Validator};
+pub use infinite_list::InfiniteList;
+pub use masked_input::MaskedInput;
+pub use number_input::NumberInput;
+pub use split_pane::{Direction, SplitPane};
+pub use tabs::{Tab, Tabs};
+pub use text_input::TextInput;
+pub use tree_view::{SelectionMode, TreeItem, TreeView};
+pub use viewport::Viewport;
+pub use zoom_view::ZoomView;
@@ -0,0 +1,185 @@
+//! Input that follows a fixed pattern, such as a phone number or a date.
+//!
+//! In the mask, `9` takes a digit, `a` a letter, and `*` either; any other character is
+//! a literal that is filled in automatically. `set` takes the raw `on:input` text, fits
+//! what was typed into the mask, and returns the typed value once the mask is complete.
+//! The value comes from a parser over the entered characters without the literals, so a
+//! date mask can yield a date rather than a string.
+
+use std::fmt;
+use std::rc::Rc;
+
+use velox_dom::VNode;
+
+use crate::date_picker::Date;
+use crate::text_input::TextInput;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Slot {
+    Digit,
+    Letter,
+    Any,
+    Literal(char),
+}
+
+impl Slot {
+    fn accepts(self, c: char) -> bool {
+        match self {
+            Slot::Digit => c.is_ascii_digit(),
+            Slot::Letter => c.is_alphabetic(),
+            Slot::Any => c.is_alphanumeric(),
+            Slot::Literal(_) => false,
+        }
+    }
+}
+
+type Parser<T> = Rc<dyn Fn(&str) -> Option<T>>;
+
+#[derive(Clone)]
+pub struct MaskedInput<T = String> {
+    input: TextInput,
+    slots: Vec<Slot>,
+    parse: Parser<T>,
+}
+
+impl<T> fmt::Debug for MaskedInput<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MaskedInput")
+            .field("input", &self.input)
+            .field("slots", &self.slots)
+            .finish()
+    }
+}
+
+impl MaskedInput<String> {
+    /// A mask whose value is the entered characters.
+    pub fn new(mask: &str) -> Self {
+        Self::with_parser(mask, |raw| Some(raw.to_string()))
+    }
+}
+
+impl MaskedInput<Date> {
+    /// A date mask whose value is a `Date`: `9999-99-99` is entered as year, month, day
+    /// and `99/99/9999` (any mask starting with two digits) as day, month, year.
+    pub fn date(mask: &str) -> Self {
+        let groups: Vec<usize> = mask
+            .split(|c: char| c != '9')
+            .filter(|g| !g.is_empty())
+            .map(str::len)
+            .collect();
+        Self::with_parser(mask, move |raw| {
+            let mut parts = Vec::with_capacity(3);
+            let mut at = 0;
+            for &len in &groups {
+                parts.push(raw.get(at..at + len)?.parse::<u32>().ok()?);
+                at += len;
+            }
+            let (y, m, d) = match (groups.as_slice(), parts.as_slice()) {
+                ([4, 2, 2], [y, m, d]) => (*y, *m, *d),
+                ([2, 2, 4], [d, m, y]) => (*y, *m, *d),
+                _ => return None,
+            };
+            Date::new(y as i32, m, d)
+        })
+    }
+}
+
+impl<T> MaskedInput<T> {
+    /// A mask whose value is `parse` applied to the entered characters (literals left
+    /// out) once the mask is complete.
+    pub fn with_parser(mask: &str, parse: impl Fn(&str) -> Option<T> + 'static) -> Self {
+        let slots = mask
+            .chars()
+            .map(|c| match c {
+                '9' => Slot::Digit,
+                'a' => Slot::Letter,
+                '*' => Slot::Any,
+                c => Slot::Literal(c),
+            })
+            .collect();
+        let placeholder: String = mask
+            .chars()
+            .map(|c| if matches!(c, '9' | 'a' | '*') { '_' } else { c })
+            .collect();
+        MaskedInput {
+            input: TextInput::new().with_placeholder(placeholder),
+            slots,
+            parse: Rc::new(parse),
+        }
+    }
+
+    /// Fit typed text into the mask and return the value if the mask is complete.
+    /// Characters that do not fit the next open slot are dropped; literals are filled in
+    /// as soon as the next character needs them.
+    pub fn set(&mut self, raw: &str) -> Option<T> {
+        let mut out = String::new();
+        let mut slot = 0;
+        for c in raw.chars() {
+            // Typed literals are skipped, they come from the mask.
+            if let Some(Slot::Literal(l)) = self.slots.get(slot)
+                && *l == c
+            {
+                out.push(c);
+                slot += 1;
+                continue;
+            }
+            let mut at = slot;
+            while let Some(Slot::Literal(_)) = self.slots.get(at) {
+                at += 1;
+            }
+            match self.slots.get(at) {
+                Some(s) if s.accepts(c) => {
+                    for s in &self.slots[slot..at] {
+                        if let Slot::Literal(l) = s {
+                            out.push(*l);
+                        }
+                    }
+                    out.push(c);
+                    slot = at + 1;
+                }
+                Some(_) => {}
+                None => break,
+            }
+        }
+        self.input.set(out);
+        self.value()
+    }
+
+    /// The entered characters without the literals.
+    pub fn raw(&self) -> String {
+        self.input
+            .value()
+            .chars()
+            .zip(&self.slots)
+            .filter(|(_, s)| !matches!(s, Slot::Literal(_)))
+            .map(|(c, _)| c)
+            .collect()
+    }
+
+    /// Whether every slot is filled.
+    pub fn is_complete(&self) -> bool {
+        self.input.value().chars().count() == self.slots.len()
+    }
+
+    /// The parsed value, or `None` while incomplete or when the parser rejects it.
+    pub fn value(&self) -> Option<T> {
+        if !self.is_complete() {
+            return None;
+        }
+        (self.parse)(&self.raw())
+    }
+
+    /// The displayed text, literals included.
+    pub fn text(&self) -> &str {
+        self.input.value()
+    }
+
+    pub fn clear(&mut self) {
+        self.input.clear();
+    }
+
+    /// Render as `<input>`; the placeholder shows the mask with `_` for open slots.
+    pub fn render(&self, on_input: &str) -> VNode {
+        self.input.render(on_input)
+    }
+}
@@ -0,0 +1,187 @@
+//! Number input with range, step, and locale decimal separator.
+//!
+//! `set` takes the raw `on:input` text, drops anything that cannot be part of a number,
+//! and returns the parsed value. Both `.` and the locale separator are accepted while
+//! typing; the text is shown with the locale separator. `commit` (on blur or enter)
+//! clamps to the range and snaps to the step; `step_up` / `step_down` serve arrow keys or
+//! spinner buttons.
+
+use velox_core::i18n;
+use velox_dom::VNode;
+
+use crate::text_input::TextInput;
+
+/// The decimal separator of a BCP 47 locale such as `de-DE` or `en_US`.
+pub fn decimal_separator(locale: &str) -> char {
+    i18n::number_symbols(locale).decimal
+}
+
+#[derive(Clone, Debug)]
+pub struct NumberInput {
+    input: TextInput,
+    min: Option<f64>,
+    max: Option<f64>,
+    step: f64,
+    separator: char,
+}
+
+impl Default for NumberInput {
+    fn default() -> Self {
+        NumberInput {
+            input: TextInput::new(),
+            min: None,
+            max: None,
+            step: 1.0,
+            separator: '.',
+        }
+    }
+}
+
+impl NumberInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min.min(max));
+        self.max = Some(max.max(min));
+        self
+    }
+
+    /// Increment for `step_up` / `step_down`, and the grid `commit` snaps to (counted
+    /// from `min`, or zero). Defaults to 1; non-positive steps are ignored.
+    pub fn with_step(mut self, step: f64) -> Self {
+        if step > 0.0 {
+            self.step = step;
+        }
+        self
+    }
+
+    pub fn with_locale(self, locale: &str) -> Self {
+        self.with_separator(decimal_separator(locale))
+    }
+
+    pub fn with_separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        let text = self
+            .input
+            .value()
+            .replace(['.', ','], &separator.to_string());
+        self.input.set(text);
+        self
+    }
+
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.input = self.input.with_placeholder(placeholder);
+        self
+    }
+
+    /// Take typed text, keep what can form a number, and return its value.
+    pub fn set(&mut self, raw: &str) -> Option<f64> {
+        let allow_negative = self.min.is_none_or(|m| m < 0.0);
+        let mut out = String::with_capacity(raw.len());
+        let mut seen_separator = false;
+        for c in raw.trim().chars() {
+            match c {
+                '0'..='9' => out.push(c),
+                '-' if allow_negative && out.is_empty() => out.push('-'),
+                '.' | ',' if !seen_separator && self.is_separator(c) => {
+                    seen_separator = true;
+                    out.push(self.separator);
+                }
+                _ => {}
+            }
+        }
+        self.input.set(out);
+        self.value()
+    }
+
+    /// The current value, or `None` while the text is not a complete number.
+    pub fn value(&self) -> Option<f64> {
+        self.input.value().replace(self.separator, ".").parse().ok()
+    }
+
+    /// The displayed text.
+    pub fn text(&self) -> &str {
+        self.input.value()
+    }
+
+    /// Clamp and snap the current value, rewrite the text, and return it. Returns `None`
+    /// and leaves the text alone when it is not a number.
+    pub fn commit(&mut self) -> Option<f64> {
+        let value = self.snap(self.value()?);
+        self.set_value(value);
+        Some(value)
+    }
+
+    pub fn set_value(&mut self, value: f64) {
+        let value = self.clamp(value);
+        let text = format_number(value, self.step).replace('.', &self.separator.to_string());
+        self.input.set(text);
+    }
+
+    pub fn step_up(&mut self) -> f64 {
+        self.step_by(1.0)
+    }
+
+    pub fn step_down(&mut self) -> f64 {
+        self.step_by(-1.0)
+    }
+
+    pub fn clear(&mut self) {
+        self.input.clear();
+    }
+
+    pub fn render(&self, on_input: &str) -> VNode {
+        self.input.render(on_input)
+    }
+
+    fn step_by(&mut self, dir: f64) -> f64 {
+        let start = self.value().unwrap_or_else(|| {
+            self.min
+                .unwrap_or(0.0)
+                .max(0.0)
+                .min(self.max.unwrap_or(f64::MAX))
+        });
+        let value = self.snap(start + dir * self.step);
+        self.set_value(value);
+        value
+    }
+
+    /// `.` is always accepted; `,` only when it is the locale separator, since it is a
+    /// thousands separator elsewhere.
+    fn is_separator(&self, c: char) -> bool {
+        c == '.' || c == self.separator
+    }
+
+    fn snap(&self, value: f64) -> f64 {
+        let base = self.min.unwrap_or(0.0);
+        let snapped = base + ((value - base) / self.step).round() * self.step;
+        let mut v = self.clamp(snapped);
+        // Clamping to a max that is off the grid: step back onto it.
+        if self.max.is_some_and(|max| snapped > max) && v > base {
+            v = base + ((v - base) / self.step).floor() * self.step;
+        }
+        v
+    }
+
+    fn clamp(&self, value: f64) -> f64 {
+        let v = self.min.map_or(value, |m| value.max(m));
+        self.max.map_or(v, |m| v.min(m))
+    }
+}
+
+/// Format with as many decimals as the step has, trimming float noise.
+fn format_number(value: f64, step: f64) -> String {
+    let step_text = format!("{}", step);
+    let decimals = step_text
+        .split_once('.')
+        .map_or(0, |(_, d)| d.len())
+        .min(10);
+    let text = format!("{:.*}", decimals, value);
+    if text.starts_with("-") && text.trim_start_matches(['-', '0', '.']).is_empty() {
+        text[1..].to_string()
+    } else {
+        text
+    }
+}
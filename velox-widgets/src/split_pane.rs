@@ -0,0 +1,235 @@
+//! Panes side by side or stacked, with dividers dragged to resize them.
+//!
+//! `render` lays the panes out in a row (`Direction::Horizontal`) or a column
+//! (`Direction::Vertical`) with a divider between each pair. A divider is an `on:pan`
+//! element (see the renderer's `viewport` module), so a drag keeps it until the button is
+//! released, wherever the pointer goes; its index comes along as the payload's `id`, for
+//! `handle`.
+//!
+//! Panes are sized in pixels from their share of the `length` the app gives the split
+//! (the window's width, or `pane_length` of the pane a nested split fills), and never
+//! shrink below their minimum. The shares outlive a restart with `save` and `restore`,
+//! under the split's id in the app's `Settings`.
+
+use velox_core::settings::Settings;
+use velox_dom::{Props, VNode, h};
+
+use crate::zoom_view::number_field;
+
+/// Thickness of a divider, in logical pixels.
+pub const DIVIDER_SIZE: f32 = 4.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Panes side by side, divided by vertical bars.
+    Horizontal,
+    /// Panes stacked, divided by horizontal bars.
+    Vertical,
+}
+
+#[derive(Clone, Debug)]
+pub struct SplitPane {
+    id: String,
+    direction: Direction,
+    /// Each pane's share of the space the dividers leave; they sum to 1.
+    ratios: Vec<f32>,
+    min_sizes: Vec<f32>,
+    length: f32,
+}
+
+impl SplitPane {
+    /// `panes` panes (at least one) sharing the space equally. `id` names the split in
+    /// the settings.
+    pub fn new(id: impl Into<String>, direction: Direction, panes: usize) -> Self {
+        let panes = panes.max(1);
+        SplitPane {
+            id: id.into(),
+            direction,
+            ratios: vec![1.0 / panes as f32; panes],
+            min_sizes: vec![0.0; panes],
+            length: 0.0,
+        }
+    }
+
+    /// Start with the panes sharing the space in proportion to `ratios`, one per pane.
+    pub fn with_ratios(mut self, ratios: &[f32]) -> Self {
+        self.set_ratios(ratios);
+        self
+    }
+
+    /// Never make `pane` smaller than `size` logical pixels while there is room for it.
+    pub fn with_min_size(mut self, pane: usize, size: f32) -> Self {
+        if let Some(min) = self.min_sizes.get_mut(pane) {
+            *min = size.max(0.0);
+        }
+        self
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    pub fn ratios(&self) -> &[f32] {
+        &self.ratios
+    }
+
+    /// Share the space in proportion to `ratios`, one per pane. Returns whether they
+    /// were usable (the right count, none negative, not all zero).
+    pub fn set_ratios(&mut self, ratios: &[f32]) -> bool {
+        let total: f32 = ratios.iter().sum();
+        if ratios.len() != self.ratios.len()
+            || ratios.iter().any(|r| !r.is_finite() || *r < 0.0)
+            || total <= 0.0
+        {
+            return false;
+        }
+        self.ratios = ratios.iter().map(|r| r / total).collect();
+        true
+    }
+
+    /// The split's size along its direction (its width when horizontal), in logical
+    /// pixels.
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    pub fn set_length(&mut self, length: f32) {
+        if length.is_finite() {
+            self.length = length.max(0.0);
+        }
+    }
+
+    /// The size of `pane` along the split's direction, in logical pixels.
+    pub fn pane_length(&self, pane: usize) -> f32 {
+        self.ratios.get(pane).map_or(0.0, |r| r * self.space())
+    }
+
+    /// Move divider `divider` (between panes `divider` and `divider + 1`) by `by` pixels,
+    /// as far as the two panes' minimum sizes allow. Returns whether it moved.
+    pub fn move_divider(&mut self, divider: usize, by: f32) -> bool {
+        let space = self.space();
+        if divider + 1 >= self.ratios.len() || space <= 0.0 || !by.is_finite() {
+            return false;
+        }
+        let (a, b) = (
+            self.ratios[divider] * space,
+            self.ratios[divider + 1] * space,
+        );
+        let (min_a, min_b) = (
+            self.min_sizes[divider].min(a),
+            self.min_sizes[divider + 1].min(b),
+        );
+        let by = by.clamp(min_a - a, b - min_b);
+        if by == 0.0 {
+            return false;
+        }
+        self.ratios[divider] = (a + by) / space;
+        self.ratios[divider + 1] = (b - by) / space;
+        true
+    }
+
+    /// Apply a divider's `on:pan` payload (`{"dx":..,"dy":..,"id":"<divider>"}`).
+    /// Returns whether the panes changed size.
+    pub fn handle(&mut self, payload: &str) -> bool {
+        let Some(divider) = string_field(payload, "id").and_then(|id| id.parse().ok()) else {
+            return false;
+        };
+        let key = match self.direction {
+            Direction::Horizontal => "dx",
+            Direction::Vertical => "dy",
+        };
+        number_field(payload, key).is_some_and(|by| self.move_divider(divider, by))
+    }
+
+    /// The settings key the ratios are saved under.
+    pub fn settings_key(&self) -> String {
+        format!("split.{}", self.id)
+    }
+
+    /// Take the ratios saved in `settings`, if they fit this split. Returns whether
+    /// there were.
+    pub fn restore(&mut self, settings: &Settings) -> bool {
+        let Some(saved) = settings.get(&self.settings_key()) else {
+            return false;
+        };
+        let ratios: Option<Vec<f32>> = saved.split(',').map(|r| r.trim().parse().ok()).collect();
+        ratios.is_some_and(|r| self.set_ratios(&r))
+    }
+
+    /// Store the ratios in `settings`; the app saves them.
+    pub fn save(&self, settings: &mut Settings) {
+        let ratios: Vec<String> = self.ratios.iter().map(|r| r.to_string()).collect();
+        settings.set(&self.settings_key(), ratios.join(","));
+    }
+
+    /// The panes (one per pane; missing ones are left empty, extra ones dropped) with the
+    /// dividers between them.
+    pub fn render(&self, handler: &str, panes: Vec<VNode>) -> VNode {
+        self.render_with_ids(handler, panes, "")
+    }
+
+    /// `render`, with each divider's index after `prefix` in its payload `id`.
+    pub(crate) fn render_with_ids(&self, handler: &str, panes: Vec<VNode>, prefix: &str) -> VNode {
+        let (flow, axis, cross) = match self.direction {
+            Direction::Horizontal => ("row", "width", "height"),
+            Direction::Vertical => ("column", "height", "width"),
+        };
+        let mut panes = panes.into_iter();
+        let mut children = Vec::with_capacity(self.ratios.len() * 2);
+        for i in 0..self.ratios.len() {
+            if i > 0 {
+                let props = Props::new()
+                    .set("class", "split-divider")
+                    .set(
+                        "style",
+                        format!("{}: {}px; {}: 100%;", axis, DIVIDER_SIZE, cross),
+                    )
+                    .set("on:pan", handler)
+                    .set("on:pan-payload", format!("{}{}", prefix, i - 1));
+                children.push(h("div", props, vec![]));
+            }
+            let style = format!(
+                "{}: {}px; {}: 100%; overflow: hidden;",
+                axis,
+                self.pane_length(i).round(),
+                cross
+            );
+            let content = panes.next().map(|p| vec![p]).unwrap_or_default();
+            children.push(h(
+                "div",
+                Props::new()
+                    .set("class", "split-pane-item")
+                    .set("style", style),
+                content,
+            ));
+        }
+        let style = format!(
+            "display: flex; flex-direction: {}; {}: {}px; {}: 100%;",
+            flow, axis, self.length, cross
+        );
+        let class = match self.direction {
+            Direction::Horizontal => "split-pane split-horizontal",
+            Direction::Vertical => "split-pane split-vertical",
+        };
+        h(
+            "div",
+            Props::new().set("class", class).set("style", style),
+            children,
+        )
+    }
+
+    /// What the panes share: the length less the dividers.
+    fn space(&self) -> f32 {
+        (self.length - DIVIDER_SIZE * (self.ratios.len() - 1) as f32).max(0.0)
+    }
+}
+
+/// The string after `"key":` in a flat JSON object.
+pub(crate) fn string_field<'a>(payload: &'a str, key: &str) -> Option<&'a str> {
+    let rest = &payload[payload.find(&format!("\"{}\":\"", key))? + key.len() + 4..];
+    rest.find('"').map(|end| &rest[..end])
+}
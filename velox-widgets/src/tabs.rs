@@ -0,0 +1,147 @@
+//! Tab bar with a single visible panel.
+//!
+//! `render_bar` emits the tab buttons and `render_panel` the active tab's content. Only
+//! the active panel is built: `render_panel` calls the content function for that tab
+//! alone, so inactive panels are never constructed until selected. Panels are keyed by
+//! tab id and marked `data-transition="tab"`, so the renderer's enter/leave transitions
+//! animate switching when the stylesheet has `.tab-enter-from` / `.tab-leave-to` rules.
+//!
+//! Clicks dispatch the handler name with the tab id as payload, for `handle`. Keys
+//! forwarded to `key` move between tabs (arrows wrap, `Home`/`End` jump to the ends) and
+//! select as they move, skipping disabled tabs.
+
+use velox_dom::{Props, VNode, h, text};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tab {
+    pub id: String,
+    pub label: String,
+    pub disabled: bool,
+}
+
+impl Tab {
+    pub fn new(id: impl Into<String>, label: impl Into<String>) -> Self {
+        Tab {
+            id: id.into(),
+            label: label.into(),
+            disabled: false,
+        }
+    }
+
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Tabs {
+    tabs: Vec<Tab>,
+    active: usize,
+}
+
+impl Tabs {
+    /// Tabs with the first enabled one active.
+    pub fn new(tabs: Vec<Tab>) -> Self {
+        let active = tabs.iter().position(|t| !t.disabled).unwrap_or(0);
+        Tabs { tabs, active }
+    }
+
+    pub fn tabs(&self) -> &[Tab] {
+        &self.tabs
+    }
+
+    pub fn active(&self) -> Option<&str> {
+        self.tabs.get(self.active).map(|t| t.id.as_str())
+    }
+
+    /// Activate the tab with `id`. Returns whether the active tab changed.
+    pub fn select(&mut self, id: &str) -> bool {
+        match self.tabs.iter().position(|t| t.id == id && !t.disabled) {
+            Some(i) if i != self.active => {
+                self.active = i;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Apply a click payload (a tab id). Returns whether the active tab changed.
+    pub fn handle(&mut self, payload: &str) -> bool {
+        self.select(payload)
+    }
+
+    /// Apply a key press (named like the DOM's `KeyboardEvent.key`). Returns whether the
+    /// active tab changed.
+    pub fn key(&mut self, key: &str) -> bool {
+        let enabled: Vec<usize> = (0..self.tabs.len())
+            .filter(|&i| !self.tabs[i].disabled)
+            .collect();
+        let Some(pos) = enabled.iter().position(|&i| i == self.active) else {
+            return false;
+        };
+        let next = match key {
+            "ArrowRight" => enabled[(pos + 1) % enabled.len()],
+            "ArrowLeft" => enabled[(pos + enabled.len() - 1) % enabled.len()],
+            "Home" => enabled[0],
+            "End" => enabled[enabled.len() - 1],
+            _ => return false,
+        };
+        let changed = next != self.active;
+        self.active = next;
+        changed
+    }
+
+    /// The tab buttons; each dispatches `handler` with its id.
+    pub fn render_bar(&self, handler: &str) -> VNode {
+        let buttons = self
+            .tabs
+            .iter()
+            .enumerate()
+            .map(|(i, tab)| {
+                let active = i == self.active;
+                let mut props = Props::new()
+                    .set("key", tab.id.clone())
+                    .set("role", "tab")
+                    .set("class", if active { "tab active" } else { "tab" })
+                    .set("aria-selected", active.to_string())
+                    .set("aria-controls", format!("tab-panel-{}", tab.id))
+                    .set("tabindex", if active { "0" } else { "-1" });
+                if tab.disabled {
+                    props = props.set("aria-disabled", "true");
+                } else {
+                    props = props
+                        .set("on:click", handler)
+                        .set("on:click-payload", tab.id.clone());
+                }
+                h("button", props, vec![text(tab.label.clone())])
+            })
+            .collect();
+        h(
+            "div",
+            Props::new()
+                .set("class", "tab-bar")
+                .set("role", "tablist")
+                .set("style", "display: flex"),
+            buttons,
+        )
+    }
+
+    /// The active tab's panel, built by `content` from the tab id.
+    pub fn render_panel(&self, content: impl FnOnce(&str) -> VNode) -> VNode {
+        let Some(id) = self.active() else {
+            return h("div", Props::new().set("class", "tab-panels"), vec![]);
+        };
+        let panel = h(
+            "div",
+            Props::new()
+                .set("key", format!("tab-panel-{}", id))
+                .set("id", format!("tab-panel-{}", id))
+                .set("role", "tabpanel")
+                .set("class", "tab-panel")
+                .set("data-transition", "tab"),
+            vec![content(id)],
+        );
+        h("div", Props::new().set("class", "tab-panels"), vec![panel])
+    }
+}
@@ -0,0 +1,58 @@
+//! A single-line text input.
+//!
+//! The widget owns the text; the app forwards the `on:input` payload (the new text) to
+//! `set`. `render` emits an `<input>` element that displays the text, or the placeholder
+//! while empty, and names the handler to dispatch. `NumberInput` and `MaskedInput` wrap
+//! it and filter what is typed.
+
+use velox_dom::{Props, VNode, h, text};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextInput {
+    value: String,
+    placeholder: String,
+}
+
+impl TextInput {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    pub fn placeholder(&self) -> &str {
+        &self.placeholder
+    }
+
+    pub fn set(&mut self, value: impl Into<String>) {
+        self.value = value.into();
+    }
+
+    pub fn clear(&mut self) {
+        self.value.clear();
+    }
+
+    /// Render as `<input>` dispatching `on_input`. The text is also a child so the
+    /// renderer paints it.
+    pub fn render(&self, on_input: &str) -> VNode {
+        let mut props = Props::new()
+            .set("value", self.value.clone())
+            .set("on:input", on_input);
+        if !self.placeholder.is_empty() {
+            props = props.set("placeholder", self.placeholder.clone());
+        }
+        let shown = if self.value.is_empty() {
+            &self.placeholder
+        } else {
+            &self.value
+        };
+        h("input", props, vec![text(shown)])
+    }
+}
@@ -0,0 +1,621 @@
+//! A tree of expandable rows, with lazily loaded children and virtualized rendering.
+//!
+//! Rows are `TreeItem`s: leaves, branches with their children, or lazy branches whose
+//! children come from the async loader given to `with_loader` the first time they are
+//! expanded; it runs on the UI thread (`velox_core::task::spawn_local`), and the row
+//! shows as loading until they arrive. Clicks dispatch the handler with `select:<id>` or
+//! `toggle:<id>`, for `handle`, and keys forwarded to `key` work like a WAI-ARIA tree,
+//! with `Shift+` ranges and `Ctrl+` moves under `SelectionMode::Multiple`.
+//!
+//! Only the rows in the viewport `set_viewport` describes (and `OVERSCAN` more on each
+//! side) are built, between spacers as tall as the rows left out, and the shown rows are
+//! only listed again after a row is expanded, collapsed or given children, so scrolling
+//! or moving through a tree of a million rows is as fast as through one of fifty.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use velox_dom::{Props, VNode, h, text};
+
+/// Rows built beyond each edge of the viewport.
+pub const OVERSCAN: usize = 5;
+/// Indentation per level, in logical pixels.
+pub const INDENT: f32 = 16.0;
+
+#[derive(Clone, Debug, PartialEq)]
+enum ItemKind {
+    Leaf,
+    Branch(Vec<TreeItem>),
+    Lazy,
+}
+
+/// A row of a `TreeView`, with its children.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeItem {
+    pub id: String,
+    pub label: String,
+    kind: ItemKind,
+}
+
+impl TreeItem {
+    pub fn leaf(id: impl Into<String>, label: impl Into<String>) -> Self {
+        TreeItem {
+            id: id.into(),
+            label: label.into(),
+            kind: ItemKind::Leaf,
+        }
+    }
+
+    pub fn branch(
+        id: impl Into<String>,
+        label: impl Into<String>,
+        children: Vec<TreeItem>,
+    ) -> Self {
+        TreeItem {
+            id: id.into(),
+            label: label.into(),
+            kind: ItemKind::Branch(children),
+        }
+    }
+
+    /// A branch whose children the tree's loader fetches when it is first expanded.
+    pub fn lazy(id: impl Into<String>, label: impl Into<String>) -> Self {
+        TreeItem {
+            id: id.into(),
+            label: label.into(),
+            kind: ItemKind::Lazy,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SelectionMode {
+    #[default]
+    Single,
+    Multiple,
+}
+
+#[derive(Debug)]
+enum Children {
+    Leaf,
+    Unloaded,
+    Loading,
+    Loaded(Vec<String>),
+}
+
+#[derive(Debug)]
+struct Node {
+    label: String,
+    parent: Option<String>,
+    children: Children,
+}
+
+/// The rows, shared with the loads in flight.
+#[derive(Debug, Default)]
+struct Nodes {
+    map: HashMap<String, Node>,
+    roots: Vec<String>,
+    /// Bumped whenever children are replaced, so a load finishing in the background
+    /// invalidates the shown rows.
+    generation: u64,
+}
+
+impl Nodes {
+    /// Add `items` under `parent`, returning their ids.
+    fn add(&mut self, parent: Option<&str>, items: Vec<TreeItem>) -> Vec<String> {
+        let mut ids = Vec::with_capacity(items.len());
+        for item in items {
+            let children = match item.kind {
+                ItemKind::Leaf => Children::Leaf,
+                ItemKind::Lazy => Children::Unloaded,
+                ItemKind::Branch(children) => Children::Loaded(self.add(Some(&item.id), children)),
+            };
+            let node = Node {
+                label: item.label,
+                parent: parent.map(str::to_string),
+                children,
+            };
+            self.map.insert(item.id.clone(), node);
+            ids.push(item.id);
+        }
+        ids
+    }
+
+    /// Replace the children of `id` with `items`.
+    fn set_children(&mut self, id: &str, items: Vec<TreeItem>) -> bool {
+        if !self.map.contains_key(id) {
+            return false;
+        }
+        let old =
+            match std::mem::replace(&mut self.map.get_mut(id).unwrap().children, Children::Leaf) {
+                Children::Loaded(old) => old,
+                _ => Vec::new(),
+            };
+        for child in old {
+            self.remove(&child);
+        }
+        let ids = self.add(Some(id), items);
+        self.map.get_mut(id).unwrap().children = Children::Loaded(ids);
+        self.generation += 1;
+        true
+    }
+
+    fn remove(&mut self, id: &str) {
+        if let Some(Node {
+            children: Children::Loaded(children),
+            ..
+        }) = self.map.remove(id)
+        {
+            for child in children {
+                self.remove(&child);
+            }
+        }
+    }
+}
+
+type Loader = Rc<dyn Fn(&str) -> Pin<Box<dyn Future<Output = Vec<TreeItem>>>>>;
+type Rows = Rc<ShownRows>;
+
+/// The shown rows with their depth, in order, and the index of each row by id.
+struct ShownRows {
+    list: Vec<(String, usize)>,
+    index: HashMap<String, usize>,
+}
+
+pub struct TreeView {
+    nodes: Rc<RefCell<Nodes>>,
+    expanded: HashSet<String>,
+    selected: Vec<String>,
+    /// Where Shift ranges start: the row last selected on its own.
+    anchor: Option<String>,
+    focused: Option<String>,
+    mode: SelectionMode,
+    loader: Option<Loader>,
+    row_height: f32,
+    /// How far the tree's top is scrolled above the viewport, and the viewport's height.
+    viewport: Option<(f32, f32)>,
+    /// The shown rows and their index, with the `Nodes::generation` they were flattened
+    /// at; dropped when a row is expanded or collapsed.
+    rows: RefCell<Option<(u64, Rows)>>,
+}
+
+impl TreeView {
+    /// A tree of `roots`, all collapsed, with the first focused.
+    pub fn new(roots: Vec<TreeItem>) -> Self {
+        let mut nodes = Nodes::default();
+        nodes.roots = nodes.add(None, roots);
+        let focused = nodes.roots.first().cloned();
+        TreeView {
+            nodes: Rc::new(RefCell::new(nodes)),
+            expanded: Default::default(),
+            selected: Vec::new(),
+            anchor: None,
+            focused,
+            mode: SelectionMode::Single,
+            loader: None,
+            row_height: 24.0,
+            viewport: None,
+            rows: RefCell::new(None),
+        }
+    }
+
+    pub fn with_selection(mut self, mode: SelectionMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Fetch the children of a lazy branch, from its id, when it is first expanded.
+    pub fn with_loader<F, Fut>(mut self, load: F) -> Self
+    where
+        F: Fn(&str) -> Fut + 'static,
+        Fut: Future<Output = Vec<TreeItem>> + 'static,
+    {
+        self.loader = Some(Rc::new(move |id: &str| {
+            Box::pin(load(id)) as Pin<Box<dyn Future<Output = Vec<TreeItem>>>>
+        }));
+        self
+    }
+
+    /// Height of a row, in logical pixels. Defaults to 24.
+    pub fn with_row_height(mut self, px: f32) -> Self {
+        self.row_height = px.max(1.0);
+        self
+    }
+
+    /// The part of the tree on screen: `top` is how far the tree's top edge is scrolled
+    /// above the viewport's, and `height` the viewport's height, in logical pixels.
+    /// Until this is called every row is rendered.
+    pub fn set_viewport(&mut self, top: f32, height: f32) {
+        if top.is_finite() && height.is_finite() {
+            self.viewport = Some((top.max(0.0), height.max(0.0)));
+        }
+    }
+
+    /// The label of `id`.
+    pub fn label(&self, id: &str) -> Option<String> {
+        self.nodes.borrow().map.get(id).map(|n| n.label.clone())
+    }
+
+    /// The ids of `id`'s children, once they are loaded.
+    pub fn children(&self, id: &str) -> Option<Vec<String>> {
+        match &self.nodes.borrow().map.get(id)?.children {
+            Children::Loaded(children) => Some(children.clone()),
+            _ => None,
+        }
+    }
+
+    /// Replace the children of `id`, e.g. after what they show changed. Returns whether
+    /// there is such a row.
+    pub fn set_children(&mut self, id: &str, children: Vec<TreeItem>) -> bool {
+        let changed = self.nodes.borrow_mut().set_children(id, children);
+        let nodes = self.nodes.borrow();
+        self.selected.retain(|s| nodes.map.contains_key(s));
+        self.expanded.retain(|e| nodes.map.contains_key(e));
+        if self
+            .focused
+            .as_ref()
+            .is_some_and(|f| !nodes.map.contains_key(f))
+        {
+            self.focused = Some(id.to_string());
+        }
+        changed
+    }
+
+    pub fn is_expanded(&self, id: &str) -> bool {
+        self.expanded.contains(id)
+    }
+
+    /// Whether `id`'s children are being loaded.
+    pub fn is_loading(&self, id: &str) -> bool {
+        matches!(
+            self.nodes.borrow().map.get(id),
+            Some(Node {
+                children: Children::Loading,
+                ..
+            })
+        )
+    }
+
+    pub fn focused(&self) -> Option<&str> {
+        self.focused.as_deref()
+    }
+
+    /// The selected rows, in the order they were selected.
+    pub fn selected(&self) -> &[String] {
+        &self.selected
+    }
+
+    /// Show the children of `id`, loading them first for a lazy branch. Returns whether
+    /// it was collapsed.
+    pub fn expand(&mut self, id: &str) -> bool {
+        let mut nodes = self.nodes.borrow_mut();
+        let Some(node) = nodes.map.get_mut(id) else {
+            return false;
+        };
+        if matches!(node.children, Children::Leaf) || self.expanded.contains(id) {
+            return false;
+        }
+        if matches!(node.children, Children::Unloaded) {
+            match &self.loader {
+                Some(load) => {
+                    node.children = Children::Loading;
+                    let (load, weak, id) = (load(id), Rc::downgrade(&self.nodes), id.to_string());
+                    velox_core::task::spawn_local(async move {
+                        let children = load.await;
+                        if let Some(nodes) = weak.upgrade() {
+                            nodes.borrow_mut().set_children(&id, children);
+                        }
+                    });
+                }
+                None => node.children = Children::Loaded(Vec::new()),
+            }
+        }
+        self.expanded.insert(id.to_string());
+        self.rows.get_mut().take();
+        true
+    }
+
+    /// Hide the children of `id`. Returns whether it was expanded. Focus inside it moves
+    /// to it.
+    pub fn collapse(&mut self, id: &str) -> bool {
+        if !self.expanded.remove(id) {
+            return false;
+        }
+        self.rows.get_mut().take();
+        if let Some(focused) = self.focused.clone()
+            && self.ancestors(&focused).iter().any(|a| a == id)
+        {
+            self.focused = Some(id.to_string());
+        }
+        true
+    }
+
+    pub fn toggle(&mut self, id: &str) -> bool {
+        if self.is_expanded(id) {
+            self.collapse(id)
+        } else {
+            self.expand(id)
+        }
+    }
+
+    /// The rows shown, in order, with their depth: the roots and the children of the
+    /// expanded rows.
+    pub fn visible_rows(&self) -> Vec<(String, usize)> {
+        self.rows().list.clone()
+    }
+
+    /// `visible_rows`, flattened again only after the tree changed.
+    fn rows(&self) -> Rows {
+        let generation = self.nodes.borrow().generation;
+        let mut cached = self.rows.borrow_mut();
+        match &*cached {
+            Some((at, rows)) if *at == generation => rows.clone(),
+            _ => {
+                let list = self.flatten();
+                let index = list
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (id, _))| (id.clone(), i))
+                    .collect();
+                let rows = Rc::new(ShownRows { list, index });
+                *cached = Some((generation, rows.clone()));
+                rows
+            }
+        }
+    }
+
+    fn flatten(&self) -> Vec<(String, usize)> {
+        let nodes = self.nodes.borrow();
+        let mut rows = Vec::new();
+        let mut stack: Vec<(&String, usize)> = nodes.roots.iter().rev().map(|id| (id, 0)).collect();
+        while let Some((id, depth)) = stack.pop() {
+            rows.push((id.clone(), depth));
+            if self.expanded.contains(id)
+                && let Some(Node {
+                    children: Children::Loaded(children),
+                    ..
+                }) = nodes.map.get(id)
+            {
+                stack.extend(children.iter().rev().map(|c| (c, depth + 1)));
+            }
+        }
+        rows
+    }
+
+    /// Where the row of `id` starts below the tree's top, in logical pixels, while it is
+    /// shown; to scroll the focused row into view.
+    pub fn row_offset(&self, id: &str) -> Option<f32> {
+        self.rows()
+            .index
+            .get(id)
+            .map(|&i| i as f32 * self.row_height)
+    }
+
+    /// Click on the row of `id` with Shift and Ctrl held or not. Returns whether the
+    /// selection or focus changed.
+    pub fn click(&mut self, id: &str, shift: bool, ctrl: bool) -> bool {
+        if !self.nodes.borrow().map.contains_key(id) {
+            return false;
+        }
+        let before = (self.selected.clone(), self.focused.clone());
+        self.focused = Some(id.to_string());
+        match self.mode {
+            SelectionMode::Multiple if shift => self.select_range(id),
+            SelectionMode::Multiple if ctrl => self.toggle_selected(id),
+            _ => self.select_only(id),
+        }
+        (self.selected.clone(), self.focused.clone()) != before
+    }
+
+    /// Apply a click payload (`select:<id>` or `toggle:<id>`). Returns whether anything
+    /// changed.
+    pub fn handle(&mut self, payload: &str) -> bool {
+        if let Some(id) = payload.strip_prefix("toggle:") {
+            return self.toggle(id);
+        }
+        payload
+            .strip_prefix("select:")
+            .is_some_and(|id| self.click(id, false, false))
+    }
+
+    /// Apply a key press (named like the DOM's `KeyboardEvent.key`, with `Ctrl+` and
+    /// `Shift+` in front when held, as shortcuts are written). Returns whether it was handled.
+    pub fn key(&mut self, key: &str) -> bool {
+        let multiple = self.mode == SelectionMode::Multiple;
+        let (ctrl, key) = key
+            .strip_prefix("Ctrl+")
+            .map_or((false, key), |k| (multiple, k));
+        let (shift, key) = key
+            .strip_prefix("Shift+")
+            .map_or((false, key), |k| (multiple, k));
+        let shown = self.rows();
+        let rows = &shown.list;
+        let Some(focused) = self.focused.clone() else {
+            return false;
+        };
+        let Some(&at) = shown.index.get(&focused) else {
+            return false;
+        };
+        let target = match key {
+            "ArrowDown" => rows.get(at + 1).map(|r| r.0.clone()),
+            "ArrowUp" => at.checked_sub(1).map(|i| rows[i].0.clone()),
+            "Home" => rows.first().map(|r| r.0.clone()),
+            "End" => rows.last().map(|r| r.0.clone()),
+            "ArrowRight" => {
+                if !self.is_expanded(&focused) {
+                    return self.expand(&focused);
+                }
+                rows.get(at + 1)
+                    .filter(|r| r.1 > rows[at].1)
+                    .map(|r| r.0.clone())
+            }
+            "ArrowLeft" => {
+                if self.collapse(&focused) {
+                    return true;
+                }
+                self.nodes
+                    .borrow()
+                    .map
+                    .get(&focused)
+                    .and_then(|n| n.parent.clone())
+            }
+            "Enter" => return self.toggle(&focused),
+            " " => {
+                if ctrl {
+                    self.toggle_selected(&focused);
+                } else if shift {
+                    self.select_range(&focused);
+                } else {
+                    self.select_only(&focused);
+                }
+                return true;
+            }
+            _ => return false,
+        };
+        let Some(target) = target else { return false };
+        self.focused = Some(target.clone());
+        if shift {
+            self.select_range(&target);
+        } else if !ctrl {
+            self.select_only(&target);
+        }
+        true
+    }
+
+    /// The rows in the viewport, with spacers for the rest.
+    pub fn render(&self, handler: &str) -> VNode {
+        let shown = self.rows();
+        let rows = &shown.list;
+        let (first, end) = match self.viewport {
+            Some((top, height)) => {
+                let first = (top / self.row_height) as usize;
+                let last = ((top + height) / self.row_height).ceil() as usize;
+                (
+                    first.saturating_sub(OVERSCAN).min(rows.len()),
+                    (last + OVERSCAN).min(rows.len()),
+                )
+            }
+            None => (0, rows.len()),
+        };
+        let nodes = self.nodes.borrow();
+        let spacer = |key: &str, rows: usize| {
+            let style = format!("height: {}px", rows as f32 * self.row_height);
+            h(
+                "div",
+                Props::new()
+                    .set("key", key)
+                    .set("class", "tree-spacer")
+                    .set("style", style),
+                vec![],
+            )
+        };
+        let mut children = Vec::with_capacity(end - first + 2);
+        if first > 0 {
+            children.push(spacer("tree-spacer-top", first));
+        }
+        for (id, depth) in &rows[first..end] {
+            let Some(node) = nodes.map.get(id) else {
+                continue;
+            };
+            let selected = self.selected.contains(id);
+            let focused = self.focused.as_ref() == Some(id);
+            let mut class = String::from("tree-row");
+            for (on, name) in [
+                (selected, " selected"),
+                (focused, " focused"),
+                (matches!(node.children, Children::Loading), " loading"),
+            ] {
+                if on {
+                    class.push_str(name);
+                }
+            }
+            let style = format!(
+                "display: flex; height: {}px; padding-left: {}px;",
+                self.row_height,
+                *depth as f32 * INDENT
+            );
+            let mut props = Props::new()
+                .set("key", id.clone())
+                .set("role", "treeitem")
+                .set("class", class)
+                .set("style", style)
+                .set("aria-level", (depth + 1).to_string())
+                .set("aria-selected", selected.to_string())
+                .set("tabindex", if focused { "0" } else { "-1" })
+                .set("on:click", handler)
+                .set("on:click-payload", format!("select:{}", id));
+            let mut arrow = Props::new()
+                .set("class", "tree-arrow")
+                .set("style", format!("width: {}px;", INDENT));
+            let mut glyph = "";
+            if !matches!(node.children, Children::Leaf) {
+                let expanded = self.is_expanded(id);
+                props = props.set("aria-expanded", expanded.to_string());
+                arrow = arrow
+                    .set("on:click", handler)
+                    .set("on:click-payload", format!("toggle:{}", id));
+                glyph = if expanded { "▾" } else { "▸" };
+            }
+            let label = h(
+                "span",
+                Props::new().set("class", "tree-label"),
+                vec![text(node.label.clone())],
+            );
+            children.push(h(
+                "div",
+                props,
+                vec![h("span", arrow, vec![text(glyph)]), label],
+            ));
+        }
+        if end < rows.len() {
+            children.push(spacer("tree-spacer-bottom", rows.len() - end));
+        }
+        let mut props = Props::new().set("class", "tree-view").set("role", "tree");
+        if self.mode == SelectionMode::Multiple {
+            props = props.set("aria-multiselectable", "true");
+        }
+        h("div", props, children)
+    }
+
+    fn ancestors(&self, id: &str) -> Vec<String> {
+        let nodes = self.nodes.borrow();
+        let mut out = Vec::new();
+        let mut at = nodes.map.get(id).and_then(|n| n.parent.clone());
+        while let Some(parent) = at {
+            at = nodes.map.get(&parent).and_then(|n| n.parent.clone());
+            out.push(parent);
+        }
+        out
+    }
+
+    fn select_only(&mut self, id: &str) {
+        self.selected = vec![id.to_string()];
+        self.anchor = Some(id.to_string());
+    }
+
+    fn toggle_selected(&mut self, id: &str) {
+        match self.selected.iter().position(|s| s == id) {
+            Some(i) => {
+                self.selected.remove(i);
+            }
+            None => self.selected.push(id.to_string()),
+        }
+        self.anchor = Some(id.to_string());
+    }
+
+    /// Select the shown rows from the anchor to `id`.
+    fn select_range(&mut self, id: &str) {
+        let rows = self.rows();
+        let anchor = self.anchor.clone().unwrap_or_else(|| id.to_string());
+        let (Some(&a), Some(&b)) = (rows.index.get(&anchor), rows.index.get(id)) else {
+            return self.select_only(id);
+        };
+        self.selected = rows.list[a.min(b)..=a.max(b)]
+            .iter()
+            .map(|r| r.0.clone())
+            .collect();
+    }
+}
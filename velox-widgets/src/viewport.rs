@@ -0,0 +1,130 @@
+//! A window onto content bigger than it, dragged to pan and pinched or Ctrl+wheeled to
+//! zoom, such as a diagram, a map or a large image.
+//!
+//! `render` puts the content in a clipping box whose `on:pan` and `on:zoom` handlers (see
+//! the renderer's `viewport` and `gesture` modules) both go to one handler name; the app
+//! hands their payloads to `handle`.
+//!
+//! The content is drawn translated by `offset` and scaled by `scale` about its top-left
+//! corner, so zooming keeps the point under the pointer or fingers where it is.
+
+use velox_dom::{Props, VNode, h};
+
+use crate::zoom_view::number_field;
+
+#[derive(Clone, Debug)]
+pub struct Viewport {
+    scale: f32,
+    offset: (f32, f32),
+    min_scale: f32,
+    max_scale: f32,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Viewport {
+            scale: 1.0,
+            offset: (0.0, 0.0),
+            min_scale: 0.25,
+            max_scale: 8.0,
+        }
+    }
+}
+
+impl Viewport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The zoom range; defaults to 0.25 to 8.
+    pub fn with_limits(mut self, min: f32, max: f32) -> Self {
+        self.min_scale = min.min(max).max(f32::EPSILON);
+        self.max_scale = max.max(self.min_scale);
+        self.scale = self.scale.clamp(self.min_scale, self.max_scale);
+        self
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Where the content's top-left corner is drawn, relative to the viewport's.
+    pub fn offset(&self) -> (f32, f32) {
+        self.offset
+    }
+
+    /// Move the content by `(dx, dy)`.
+    pub fn pan_by(&mut self, dx: f32, dy: f32) {
+        if dx.is_finite() && dy.is_finite() {
+            self.offset = (self.offset.0 + dx, self.offset.1 + dy);
+        }
+    }
+
+    /// Zoom by a factor of `by` (within the limits), keeping the content under `(x, y)`
+    /// in the viewport where it is. Returns whether the zoom changed.
+    pub fn zoom_at(&mut self, by: f32, x: f32, y: f32) -> bool {
+        if !by.is_finite() || !x.is_finite() || !y.is_finite() {
+            return false;
+        }
+        let before = self.scale;
+        self.scale = (self.scale * by).clamp(self.min_scale, self.max_scale);
+        let ratio = self.scale / before;
+        self.offset = (
+            x - (x - self.offset.0) * ratio,
+            y - (y - self.offset.1) * ratio,
+        );
+        self.scale != before
+    }
+
+    /// The content point drawn at `(x, y)` in the viewport.
+    pub fn to_content(&self, x: f32, y: f32) -> (f32, f32) {
+        (
+            (x - self.offset.0) / self.scale,
+            (y - self.offset.1) / self.scale,
+        )
+    }
+
+    /// Back to the content's top-left corner at no zoom.
+    pub fn reset(&mut self) {
+        self.scale = 1.0_f32.clamp(self.min_scale, self.max_scale);
+        self.offset = (0.0, 0.0);
+    }
+
+    /// Apply an `on:zoom` (`{"scale":..,"x":..,"y":..}`) or `on:pan` (`{"dx":..,"dy":..}`)
+    /// payload. Returns whether the view changed.
+    pub fn handle(&mut self, payload: &str) -> bool {
+        if let Some(by) = number_field(payload, "scale") {
+            let x = number_field(payload, "x").unwrap_or(0.0);
+            let y = number_field(payload, "y").unwrap_or(0.0);
+            return self.zoom_at(by, x, y);
+        }
+        let dx = number_field(payload, "dx");
+        let dy = number_field(payload, "dy");
+        if dx.is_none() && dy.is_none() {
+            return false;
+        }
+        let before = self.offset;
+        self.pan_by(dx.unwrap_or(0.0), dy.unwrap_or(0.0));
+        self.offset != before
+    }
+
+    pub fn render(&self, handler: &str, content: VNode) -> VNode {
+        let style = format!(
+            "transform-origin: 0 0; transform: translate({}px, {}px) scale({});",
+            self.offset.0, self.offset.1, self.scale
+        );
+        let inner = h(
+            "div",
+            Props::new()
+                .set("class", "viewport-content")
+                .set("style", style),
+            vec![content],
+        );
+        let props = Props::new()
+            .set("class", "viewport")
+            .set("style", "overflow: hidden;")
+            .set("on:pan", handler)
+            .set("on:zoom", handler);
+        h("div", props, vec![inner])
+    }
+}
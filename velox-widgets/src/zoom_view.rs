@@ -0,0 +1,104 @@
+//! A container its content can be pinched to zoom and twisted to rotate.
+//!
+//! `render` puts the content in a clipping box whose `on:zoom` and `on:rotate` handlers
+//! (see the renderer's `gesture` module) both go to one handler name; the app hands
+//! their payloads to `handle`.
+//!
+//! The zoom is drawn as a `scale` transform about the box's center and kept within
+//! `with_limits`. The renderer's transforms don't rotate yet, so `rotation` is state for
+//! the app to use, e.g. to turn a map's tiles or show a compass.
+
+use velox_dom::{Props, VNode, h};
+
+#[derive(Clone, Debug)]
+pub struct ZoomView {
+    scale: f32,
+    rotation: f32,
+    min_scale: f32,
+    max_scale: f32,
+}
+
+impl Default for ZoomView {
+    fn default() -> Self {
+        ZoomView {
+            scale: 1.0,
+            rotation: 0.0,
+            min_scale: 0.25,
+            max_scale: 8.0,
+        }
+    }
+}
+
+impl ZoomView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The zoom range; defaults to 0.25 to 8.
+    pub fn with_limits(mut self, min: f32, max: f32) -> Self {
+        self.min_scale = min.min(max).max(f32::EPSILON);
+        self.max_scale = max.max(self.min_scale);
+        self.scale = self.scale.clamp(self.min_scale, self.max_scale);
+        self
+    }
+
+    pub fn scale(&self) -> f32 {
+        self.scale
+    }
+
+    /// Clockwise, in radians, within `-PI..=PI`.
+    pub fn rotation(&self) -> f32 {
+        self.rotation
+    }
+
+    pub fn set_scale(&mut self, scale: f32) {
+        if scale.is_finite() {
+            self.scale = scale.clamp(self.min_scale, self.max_scale);
+        }
+    }
+
+    /// Back to no zoom and no rotation.
+    pub fn reset(&mut self) {
+        self.scale = 1.0_f32.clamp(self.min_scale, self.max_scale);
+        self.rotation = 0.0;
+    }
+
+    /// Apply an `on:zoom` (`{"scale":..}`) or `on:rotate` (`{"angle":..}`) payload.
+    /// Returns whether the view changed.
+    pub fn handle(&mut self, payload: &str) -> bool {
+        if let Some(by) = number_field(payload, "scale") {
+            let before = self.scale;
+            self.set_scale(self.scale * by);
+            return self.scale != before;
+        }
+        if let Some(angle) = number_field(payload, "angle").filter(|a| a.is_finite() && *a != 0.0) {
+            use std::f32::consts::{PI, TAU};
+            self.rotation = (self.rotation + angle + PI).rem_euclid(TAU) - PI;
+            return true;
+        }
+        false
+    }
+
+    pub fn render(&self, handler: &str, content: VNode) -> VNode {
+        let inner = h(
+            "div",
+            Props::new()
+                .set("class", "zoom-view-content")
+                .set("style", format!("transform: scale({});", self.scale)),
+            vec![content],
+        );
+        let props = Props::new()
+            .set("class", "zoom-view")
+            .set("style", "overflow: hidden;")
+            .set("on:zoom", handler)
+            .set("on:rotate", handler);
+        h("div", props, vec![inner])
+    }
+}
+
+/// The number after `"key":` in a flat JSON object.
+pub(crate) fn number_field(payload: &str, key: &str) -> Option<f32> {
+    let rest = &payload[payload.find(&format!("\"{}\":", key))? + key.len() + 3..];
+    let end = rest.find([',', '}']).unwrap_or(rest.len());
+    rest[..end].trim().parse().ok()
+}
@@ -0,0 +1,67 @@
+use velox_dom::VNode;
+use velox_widgets::{Command, CommandPalette};
+
+fn find<'a>(node: &'a VNode, attr: &str, value: &str) -> Option<&'a VNode> {
+    match node {
+        VNode::Element {
+            props, children, ..
+        } => {
+            if props.str(attr) == Some(value) {
+                return Some(node);
+            }
+            children.iter().find_map(|c| find(c, attr, value))
+        }
+        _ => None,
+    }
+}
+
+fn palette() -> CommandPalette {
+    CommandPalette::new(vec![
+        Command::new("open", "Open File").with_group("File"),
+        Command::new("save", "Save").with_group("File"),
+        Command::new("theme", "Toggle Theme"),
+    ])
+    .with_shortcuts(&[("Ctrl+S".to_string(), "save".to_string())])
+}
+
+#[test]
+fn filters_navigates_and_runs() {
+    let mut p = palette();
+    assert_eq!(p.commands()[1].shortcut.as_deref(), Some("Ctrl+S"));
+    assert_eq!(p.handle("toggle"), None);
+    assert!(p.is_open());
+    assert_eq!(p.results().len(), 3);
+
+    p.set_query("fsv");
+    let ids: Vec<&str> = p.results().iter().map(|c| c.id.as_str()).collect();
+    assert_eq!(ids, ["save"]);
+    p.set_query("t");
+    assert_eq!(p.selected().unwrap().id, "theme", "word start ranks first");
+    assert_eq!(p.key("ArrowUp"), None);
+    let last = p.results().last().unwrap().id.clone();
+    assert_eq!(p.selected().unwrap().id, last, "wraps");
+    assert_eq!(p.key("Enter"), Some(last));
+    assert!(!p.is_open());
+
+    p.open();
+    assert_eq!(p.query(), "");
+    assert_eq!(p.handle("run:missing"), None);
+    assert_eq!(p.handle("run:open"), Some("open".into()));
+}
+
+#[test]
+fn render_binds_keys_through_a_shortcut_scope() {
+    let mut p = palette();
+    let closed = p.render("palette", "palette-query");
+    assert!(find(&closed, "shortcut", "Ctrl+K").is_some());
+
+    p.open();
+    let view = p.render("palette", "palette-query");
+    assert!(find(&view, "shortcut-scope", "command-palette").is_some());
+    let VNode::Element { props, .. } = find(&view, "shortcut", "Escape").unwrap() else {
+        panic!()
+    };
+    assert_eq!(props.attrs["on:shortcut-payload"], "close");
+    assert!(find(&view, "on:input", "palette-query").is_some());
+    assert!(find(&view, "on:click-payload", "run:save").is_some());
+}
@@ -0,0 +1,82 @@
+use velox_dom::VNode;
+use velox_widgets::{Date, DatePicker};
+
+fn d(y: i32, m: u32, day: u32) -> Date {
+    Date::new(y, m, day).unwrap()
+}
+
+fn find<'a>(
+    node: &'a VNode,
+    pred: &dyn Fn(&velox_dom::Props) -> bool,
+    out: &mut Vec<&'a velox_dom::Props>,
+) {
+    if let VNode::Element {
+        props, children, ..
+    } = node
+    {
+        if pred(props) {
+            out.push(props);
+        }
+        for c in children {
+            find(c, pred, out);
+        }
+    }
+}
+
+#[test]
+fn date_arithmetic() {
+    assert_eq!(d(2024, 2, 29).weekday(), 3);
+    assert_eq!(d(2024, 1, 31).add_months(1), d(2024, 2, 29));
+    assert_eq!(d(2023, 12, 31).add_days(1), d(2024, 1, 1));
+    assert_eq!(Date::parse("2024-03-05"), Some(d(2024, 3, 5)));
+    assert_eq!(Date::new(2023, 2, 29), None);
+}
+
+#[test]
+fn keyboard_navigation_respects_range_and_picks() {
+    let mut picker =
+        DatePicker::new(d(2024, 2, 10)).with_range(Some(d(2024, 2, 5)), Some(d(2024, 3, 15)));
+    picker.key("ArrowDown");
+    assert!(picker.is_open());
+    assert_eq!(picker.focused(), d(2024, 2, 17));
+    picker.key("PageDown");
+    assert_eq!(picker.focused(), d(2024, 3, 15), "clamped to max");
+    picker.key("Home");
+    assert_eq!(picker.focused(), d(2024, 3, 11));
+    assert_eq!(picker.key("Enter"), Some(d(2024, 3, 11)));
+    assert!(!picker.is_open());
+    assert_eq!(picker.handle("2024-01-01"), None, "before min");
+}
+
+#[test]
+fn render_grid_marks_focus_and_disabled_days() {
+    let mut picker = DatePicker::new(d(2024, 2, 10)).with_range(Some(d(2024, 2, 5)), None);
+    picker.handle("toggle");
+    let mut node = velox_dom::h(
+        "div",
+        vec![],
+        vec![velox_dom::h(
+            "date-picker",
+            vec![("on:input", "update:due")],
+            vec![],
+        )],
+    );
+    assert!(picker.mount(&mut node, "update:due"));
+
+    let mut cells = Vec::new();
+    find(&node, &|p| p.str("role") == Some("gridcell"), &mut cells);
+    assert_eq!(
+        cells.len(),
+        35,
+        "five Monday-first weeks cover February 2024"
+    );
+    let focused: Vec<_> = cells
+        .iter()
+        .filter(|p| p.attrs["tabindex"] == "0")
+        .collect();
+    assert_eq!(focused.len(), 1);
+    assert_eq!(focused[0].attrs["on:click-payload"], "2024-02-10");
+    let disabled = cells.iter().filter(|p| p.contains("aria-disabled")).count();
+    assert_eq!(disabled, 3 + 4, "January overflow and Feb 1-4");
+    assert_eq!(picker.handle("2024-02-20"), Some(d(2024, 2, 20)));
+}
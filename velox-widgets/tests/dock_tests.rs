@@ -0,0 +1,95 @@
+use velox_core::settings::Settings;
+use velox_dom::{Props, VNode, h};
+use velox_widgets::{Direction, Dock, DockNode, Zone};
+
+fn drag(dock: &mut Dock, id: &str, (x, y): (f32, f32)) -> bool {
+    dock.handle(&format!(r#"{{"dx":30,"dy":0,"id":"{}"}}"#, id));
+    dock.handle(&format!(r#"{{"x":{},"y":{},"id":"{}"}}"#, x, y, id))
+}
+
+fn tabs(node: &DockNode) -> Vec<Vec<&str>> {
+    match node {
+        DockNode::Tabs { panels, .. } => vec![panels.iter().map(String::as_str).collect()],
+        DockNode::Split { children, .. } => children.iter().flat_map(tabs).collect(),
+    }
+}
+
+#[test]
+fn tabs_dragged_onto_groups_dock_at_the_zone_dropped_on() {
+    let mut dock = Dock::new("main", &["files", "editor", "output"]);
+    dock.set_bounds(0.0, 0.0, 800.0, 600.0);
+    assert!(
+        !dock.handle(r#"{"x":10,"y":10,"id":"tab:main:files"}"#),
+        "a click on the shown tab"
+    );
+    assert!(dock.handle(r#"{"x":10,"y":10,"id":"tab:main:editor"}"#));
+
+    // Onto the group's left edge: a split with the panel on the left.
+    assert!(drag(&mut dock, "tab:main:files", (20.0, 300.0)));
+    assert_eq!(tabs(dock.root()), [vec!["files"], vec!["editor", "output"]]);
+    let DockNode::Split { split, .. } = dock.root() else {
+        panic!("not split")
+    };
+    assert_eq!(split.direction(), Direction::Horizontal);
+    assert_eq!(split.pane_length(0), 398.0);
+
+    // Onto the right group's tab strip: among its tabs again.
+    assert!(drag(&mut dock, "tab:main:files", (600.0, 10.0)));
+    assert_eq!(tabs(dock.root()), [vec!["editor", "output", "files"]]);
+
+    assert!(dock.dock("output", "editor", Zone::Bottom));
+    assert!(dock.handle(r#"{"dx":0,"dy":-50,"id":"split:main::0"}"#));
+    let DockNode::Split { split, .. } = dock.root() else {
+        panic!("not split")
+    };
+    assert_eq!(split.pane_length(0), 248.0);
+    assert!(!dock.dock("output", "output", Zone::Left));
+}
+
+#[test]
+fn a_tab_dropped_outside_the_dock_moves_into_a_window() {
+    let mut dock = Dock::new("main", &["files", "editor"]);
+    dock.set_bounds(0.0, 0.0, 800.0, 600.0);
+    assert!(drag(&mut dock, "tab:main:editor", (900.0, 300.0)));
+    assert_eq!(dock.root().panels(), ["files"]);
+    assert_eq!(dock.window_of("editor"), Some(1));
+    assert_eq!(dock.undock("editor"), None, "already alone");
+
+    let content = |panel: &str| h("p", Props::new().set("class", panel), vec![]);
+    let view = dock.render_window(1, "dock", content).unwrap();
+    let VNode::Element { children, .. } = &view else {
+        panic!("not an element")
+    };
+    assert_eq!(children.len(), 1);
+    assert!(dock.render_window(2, "dock", content).is_none());
+
+    assert!(dock.close_window(1));
+    assert!(dock.windows().is_empty());
+    assert_eq!(dock.root().panels(), ["files", "editor"]);
+}
+
+#[test]
+fn layouts_are_saved_and_restored_as_json() {
+    let mut dock = Dock::new("main", &["files", "editor", "output"]);
+    dock.set_bounds(0.0, 0.0, 800.0, 600.0);
+    dock.dock("files", "editor", Zone::Left);
+    dock.undock("output");
+    let mut settings = Settings::in_memory();
+    dock.save(&mut settings);
+    assert_eq!(
+        settings.get("dock.main"),
+        Some(
+            r#"{"root":{"split":"horizontal","ratios":[0.5,0.5],"children":[{"tabs":["files"],"active":0},{"tabs":["editor"],"active":0}]},"windows":[{"id":1,"width":480,"height":360,"root":{"tabs":["output"],"active":0}}]}"#
+        )
+    );
+
+    // A newer version of the app: `output` became `terminal`.
+    let mut restored = Dock::new("main", &["files", "editor", "terminal"]);
+    assert!(restored.restore(&settings));
+    assert_eq!(
+        tabs(restored.root()),
+        [vec!["files", "terminal"], vec!["editor"]]
+    );
+    assert!(restored.windows().is_empty(), "its only panel is gone");
+    assert!(!restored.restore_json(r#"{"root":{"split":"diagonal","children":[]}}"#));
+}
@@ -0,0 +1,113 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use velox_core::signal::effect;
+use velox_widgets::{Form, Validator};
+
+fn signup() -> Form {
+    let mut form = Form::new();
+    form.field(
+        "email",
+        vec![
+            Validator::Required,
+            Validator::pattern(r"^[^@\s]+@[^@\s]+$").unwrap(),
+        ],
+    );
+    form.field(
+        "age",
+        vec![
+            Validator::Min(18.0).message("Adults only"),
+            Validator::Max(130.0),
+        ],
+    );
+    form.field(
+        "name",
+        vec![
+            Validator::MinLength(2),
+            Validator::custom(|v| {
+                if v == "root" {
+                    Err("Reserved".into())
+                } else {
+                    Ok(())
+                }
+            }),
+        ],
+    );
+    form
+}
+
+#[test]
+fn validators_report_messages() {
+    assert_eq!(
+        Validator::Required.check("  "),
+        Some("This field is required".into())
+    );
+    assert_eq!(
+        Validator::MinLength(3).check(""),
+        None,
+        "optional fields pass when empty"
+    );
+    assert_eq!(
+        Validator::MaxLength(2).check("abc"),
+        Some("Must be at most 2 characters".into())
+    );
+    assert_eq!(
+        Validator::Min(1.0).check("x"),
+        Some("Must be a number".into())
+    );
+    assert_eq!(
+        Validator::Max(5.0).message("Too big").check("6"),
+        Some("Too big".into())
+    );
+
+    let mut form = signup();
+    form.set("email", "a@b");
+    form.set("age", "12");
+    form.set("name", "root");
+    assert!(!form.validate());
+    assert_eq!(form.error("email"), None);
+    assert_eq!(form.error("age").as_deref(), Some("Adults only"));
+    assert_eq!(form.error("name").as_deref(), Some("Reserved"));
+    assert_eq!(
+        form.resolve("form", "form.errors.age").as_deref(),
+        Some("Adults only")
+    );
+    assert_eq!(form.resolve("form", "form.email").as_deref(), Some("a@b"));
+}
+
+#[test]
+fn submit_only_calls_back_when_valid_and_errors_are_reactive() {
+    let form = Rc::new(RefCell::new(signup()));
+    let shown: Rc<RefCell<Vec<Option<String>>>> = Rc::new(RefCell::new(vec![]));
+    {
+        let errors = form.borrow().errors();
+        let shown = shown.clone();
+        effect(move || shown.borrow_mut().push(errors.get().get("email").cloned()));
+    }
+
+    let submitted = Rc::new(RefCell::new(None));
+    let sink = submitted.clone();
+    assert!(
+        !form
+            .borrow_mut()
+            .submit(|values| *sink.borrow_mut() = Some(values))
+    );
+    assert!(submitted.borrow().is_none());
+
+    // After a failed submit, fixing a field clears its error right away.
+    form.borrow_mut().set("email", "me@example.com");
+    assert_eq!(
+        &*shown.borrow(),
+        &vec![None, Some("This field is required".into()), None]
+    );
+
+    let sink = submitted.clone();
+    assert!(
+        form.borrow_mut()
+            .submit(|values| *sink.borrow_mut() = Some(values))
+    );
+    assert_eq!(
+        submitted.borrow().as_ref().unwrap()["email"],
+        "me@example.com"
+    );
+}
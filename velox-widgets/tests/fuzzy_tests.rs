@@ -0,0 +1,49 @@
+use velox_dom::VNode;
+use velox_widgets::fuzzy::{filter, fuzzy_match, highlight};
+
+#[test]
+fn scores_and_ranges() {
+    let m = fuzzy_match("ofi", "Open File").unwrap();
+    assert_eq!(m.ranges, vec![0..1, 5..7]);
+    assert!(fuzzy_match("xyz", "Open File").is_none());
+    assert!(fuzzy_match("", "anything").unwrap().ranges.is_empty());
+    assert_eq!(
+        fuzzy_match("ÄB", "xäbc").unwrap().ranges,
+        vec![1..4],
+        "case-insensitive, byte ranges"
+    );
+
+    let contiguous = fuzzy_match("save", "Save As").unwrap().score;
+    let scattered = fuzzy_match("save", "Show All Visible Entries")
+        .unwrap()
+        .score;
+    assert!(contiguous > scattered);
+
+    let items = ["Reload Window", "Toggle Sidebar", "Show Recent"];
+    let ranked: Vec<&str> = filter("re", &items, |s| *s)
+        .into_iter()
+        .map(|(s, _)| *s)
+        .collect();
+    assert_eq!(ranked, ["Reload Window", "Show Recent"]);
+}
+
+#[test]
+fn highlight_wraps_matched_text() {
+    let m = fuzzy_match("fi", "Open File").unwrap();
+    let VNode::Element { children, .. } = highlight("Open File", &m.ranges) else {
+        panic!()
+    };
+    assert_eq!(children.len(), 3);
+    assert_eq!(children[0], VNode::Text("Open ".into()));
+    let VNode::Element {
+        tag,
+        children: marked,
+        ..
+    } = &children[1]
+    else {
+        panic!()
+    };
+    assert_eq!(tag, "mark");
+    assert_eq!(marked[0], VNode::Text("Fi".into()));
+    assert_eq!(children[2], VNode::Text("le".into()));
+}
@@ -0,0 +1,77 @@
+use velox_dom::{VNode, h, text};
+use velox_widgets::InfiniteList;
+use velox_widgets::infinite_list::LOAD_MORE;
+
+fn keys(node: &VNode) -> Vec<String> {
+    let VNode::Element { children, .. } = node else {
+        return vec![];
+    };
+    children
+        .iter()
+        .filter_map(|c| match c {
+            VNode::Element { props, .. } => props.get("key").map(ToString::to_string),
+            _ => None,
+        })
+        .collect()
+}
+
+fn render(list: &InfiniteList<u32>) -> VNode {
+    list.render(
+        |n| n.to_string(),
+        |n| h("p", vec![], vec![text(n.to_string())]),
+    )
+}
+
+#[test]
+fn loads_pages_until_exhausted() {
+    let mut list = InfiniteList::new();
+    assert!(list.begin_load());
+    assert!(!list.begin_load(), "a load is already running");
+    list.append(vec![1, 2]);
+    assert_eq!(
+        keys(&render(&list)),
+        vec!["1", "2", "infinite-list-sentinel-2"]
+    );
+
+    let VNode::Element { children, .. } = render(&list) else {
+        unreachable!()
+    };
+    let VNode::Element { props, .. } = &children[2] else {
+        unreachable!()
+    };
+    assert_eq!(props.attrs["on:visible"], LOAD_MORE);
+    assert_eq!(props.attrs["visible-margin"], "200");
+
+    assert!(list.begin_load());
+    list.append(vec![]);
+    assert!(list.is_exhausted() && !list.begin_load());
+    assert_eq!(keys(&render(&list)), vec!["1", "2"]);
+}
+
+#[test]
+fn max_items_drops_oldest_behind_a_spacer() {
+    let mut list = InfiniteList::new().with_max_items(3, 40);
+    list.append(vec![1, 2]);
+    list.append(vec![3, 4, 5]);
+    assert_eq!(list.items().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+    assert_eq!((list.first_index(), list.total_loaded()), (2, 5));
+
+    let tree = render(&list);
+    assert_eq!(
+        keys(&tree),
+        vec![
+            "infinite-list-spacer",
+            "3",
+            "4",
+            "5",
+            "infinite-list-sentinel-5"
+        ]
+    );
+    let VNode::Element { children, .. } = tree else {
+        unreachable!()
+    };
+    let VNode::Element { props, .. } = &children[0] else {
+        unreachable!()
+    };
+    assert_eq!(props.attrs["style"], "height: 80px");
+}
@@ -0,0 +1,48 @@
+use velox_dom::VNode;
+use velox_widgets::{Date, MaskedInput, NumberInput};
+
+#[test]
+fn number_input_filters_clamps_and_steps() {
+    let mut n = NumberInput::new()
+        .with_range(0.0, 10.0)
+        .with_step(0.5)
+        .with_locale("de-DE");
+    assert_eq!(n.set("3.2x5"), Some(3.25));
+    assert_eq!(n.text(), "3,25");
+    assert_eq!(
+        n.set("-1,5,2"),
+        Some(1.52),
+        "no sign below a non-negative min, one separator"
+    );
+    assert_eq!(n.commit(), Some(1.5));
+    assert_eq!(n.step_up(), 2.0);
+    assert_eq!(n.text(), "2,0");
+    n.set("99");
+    assert_eq!(n.commit(), Some(10.0));
+
+    let mut en = NumberInput::new();
+    assert_eq!(
+        en.set("1,234.5"),
+        Some(1234.5),
+        "commas are thousands separators in en"
+    );
+    assert_eq!(en.set(""), None);
+}
+
+#[test]
+fn masked_input_fills_literals_and_parses() {
+    let mut phone = MaskedInput::new("(999) 999-9999");
+    assert_eq!(phone.set("555abc12"), None);
+    assert_eq!(phone.text(), "(555) 12");
+    assert_eq!(phone.set("(555) 123-4567").as_deref(), Some("5551234567"));
+
+    let mut day = MaskedInput::date("99/99/9999");
+    assert_eq!(day.set("29022024"), Date::new(2024, 2, 29));
+    assert_eq!(day.text(), "29/02/2024");
+    assert_eq!(day.set("29022023"), None, "not a leap year");
+    let placeholder = match day.render("date") {
+        VNode::Element { props, .. } => props.attrs["placeholder"].to_string(),
+        _ => unreachable!(),
+    };
+    assert_eq!(placeholder, "__/__/____");
+}
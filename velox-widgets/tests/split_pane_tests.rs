@@ -0,0 +1,90 @@
+use velox_core::settings::Settings;
+use velox_dom::{VNode, text};
+use velox_widgets::{Direction, SplitPane};
+
+#[test]
+fn dividers_resize_neighbours_within_min_sizes() {
+    // 404px less one 4px divider leaves 400 to share.
+    let mut split = SplitPane::new("main", Direction::Horizontal, 2)
+        .with_ratios(&[1.0, 3.0])
+        .with_min_size(0, 50.0);
+    split.set_length(404.0);
+    assert_eq!((split.pane_length(0), split.pane_length(1)), (100.0, 300.0));
+
+    assert!(split.handle(r#"{"dx":40,"dy":-7,"id":"0"}"#));
+    assert_eq!(split.pane_length(0), 140.0);
+    assert!(split.handle(r#"{"dx":-500,"dy":0,"id":"0"}"#));
+    assert_eq!(split.pane_length(0), 50.0, "stops at the minimum");
+    assert!(!split.handle(r#"{"dx":-10,"dy":0,"id":"0"}"#));
+    assert!(
+        !split.handle(r#"{"dx":10,"dy":0,"id":"1"}"#),
+        "no second divider"
+    );
+    assert!(!split.handle(r#"{"dx":10,"dy":0}"#));
+
+    let mut stacked = SplitPane::new("side", Direction::Vertical, 3);
+    stacked.set_length(308.0);
+    assert!(
+        !stacked.handle(r#"{"dx":30,"dy":0,"id":"1"}"#),
+        "only the vertical drag counts"
+    );
+    assert!(stacked.handle(r#"{"dx":30,"dy":-20,"id":"1"}"#));
+    assert!((stacked.pane_length(1) - 80.0).abs() < 1e-3);
+    assert!((stacked.pane_length(2) - 120.0).abs() < 1e-3);
+}
+
+#[test]
+fn ratios_persist_in_settings() {
+    let mut settings = Settings::in_memory();
+    let mut split = SplitPane::new("main", Direction::Horizontal, 2);
+    split.set_length(204.0);
+    split.move_divider(0, 50.0);
+    split.save(&mut settings);
+    assert_eq!(settings.get("split.main"), Some("0.75,0.25"));
+
+    let mut again = SplitPane::new("main", Direction::Horizontal, 2);
+    assert!(again.restore(&settings));
+    assert_eq!(again.ratios(), &[0.75, 0.25]);
+    let mut other = SplitPane::new("main", Direction::Horizontal, 3);
+    assert!(
+        !other.restore(&settings),
+        "saved for a different number of panes"
+    );
+}
+
+#[test]
+fn renders_sized_panes_with_draggable_dividers() {
+    let mut split = SplitPane::new("main", Direction::Horizontal, 2);
+    split.set_length(204.0);
+    let VNode::Element {
+        props, children, ..
+    } = split.render("main-split", vec![text("a"), text("b")])
+    else {
+        panic!()
+    };
+    assert!(
+        props
+            .str("style")
+            .unwrap()
+            .starts_with("display: flex; flex-direction: row; width: 204px;")
+    );
+    assert_eq!(children.len(), 3);
+    let VNode::Element { props: pane, .. } = &children[0] else {
+        panic!()
+    };
+    assert_eq!(
+        pane.attrs["style"],
+        "width: 100px; height: 100%; overflow: hidden;"
+    );
+    let VNode::Element { props: divider, .. } = &children[1] else {
+        panic!()
+    };
+    assert_eq!(divider.attrs["on:pan"], "main-split");
+    assert_eq!(divider.attrs["on:pan-payload"], "0");
+
+    // Laid out, the divider sits between the panes.
+    let vnode = split.render("main-split", vec![text("a"), text("b")]);
+    let layout = velox_dom::layout::compute_layout(&vnode, 204, 100);
+    assert_eq!(layout.children[1].rect.x, 100);
+    assert_eq!(layout.children[2].rect.x, 104);
+}
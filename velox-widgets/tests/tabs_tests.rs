@@ -0,0 +1,58 @@
+use std::cell::Cell;
+
+use velox_dom::{VNode, text};
+use velox_widgets::{Accordion, Tab, Tabs};
+
+fn children(node: &VNode) -> &[VNode] {
+    match node {
+        VNode::Element { children, .. } | VNode::Fragment(children) => children,
+        VNode::Text(_) | VNode::Component { .. } => &[],
+    }
+}
+
+#[test]
+fn tabs_keyboard_skips_disabled_and_builds_only_the_active_panel() {
+    let mut tabs = Tabs::new(vec![
+        Tab::new("a", "A"),
+        Tab::new("b", "B").disabled(),
+        Tab::new("c", "C"),
+    ]);
+    assert!(tabs.key("ArrowRight"));
+    assert_eq!(tabs.active(), Some("c"));
+    assert!(tabs.key("ArrowRight"), "wraps around");
+    assert_eq!(tabs.active(), Some("a"));
+    assert!(!tabs.handle("b"), "disabled");
+    assert!(tabs.key("End"));
+
+    let built = Cell::new(0);
+    let panels = tabs.render_panel(|id| {
+        built.set(built.get() + 1);
+        text(id)
+    });
+    assert_eq!(built.get(), 1);
+    match &children(&panels)[0] {
+        VNode::Element {
+            props, children, ..
+        } => {
+            assert_eq!(props.attrs["data-transition"], "tab");
+            assert_eq!(children[0], text("c"));
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn accordion_single_and_multiple_expansion() {
+    let sections = || vec![Tab::new("x", "X"), Tab::new("y", "Y")];
+    let mut single = Accordion::new(sections());
+    single.handle("x");
+    single.key("ArrowDown");
+    single.key("Enter");
+    assert!(!single.is_expanded("x") && single.is_expanded("y"));
+    assert_eq!(children(&single.render("acc", |id| text(id))).len(), 3);
+
+    let mut multi = Accordion::new(sections()).with_multiple(true);
+    multi.toggle("x");
+    multi.toggle("y");
+    assert!(multi.is_expanded("x") && multi.is_expanded("y"));
+}
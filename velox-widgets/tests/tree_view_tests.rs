@@ -0,0 +1,106 @@
+use velox_dom::VNode;
+use velox_widgets::{SelectionMode, TreeItem, TreeView};
+
+fn files() -> TreeView {
+    TreeView::new(vec![
+        TreeItem::branch(
+            "src",
+            "src",
+            vec![
+                TreeItem::leaf("src/lib.rs", "lib.rs"),
+                TreeItem::leaf("src/main.rs", "main.rs"),
+            ],
+        ),
+        TreeItem::lazy("target", "target"),
+        TreeItem::leaf("Cargo.toml", "Cargo.toml"),
+    ])
+}
+
+fn ids(tree: &TreeView) -> Vec<String> {
+    tree.visible_rows().into_iter().map(|(id, _)| id).collect()
+}
+
+#[test]
+fn keys_move_expand_and_select_like_a_tree() {
+    let mut tree = files().with_selection(SelectionMode::Multiple);
+    assert_eq!(ids(&tree), ["src", "target", "Cargo.toml"]);
+    assert!(tree.key("ArrowRight"));
+    assert_eq!(
+        ids(&tree),
+        ["src", "src/lib.rs", "src/main.rs", "target", "Cargo.toml"]
+    );
+    assert!(tree.key("ArrowRight"));
+    assert_eq!(tree.focused(), Some("src/lib.rs"));
+    assert_eq!(tree.selected(), ["src/lib.rs"]);
+    assert!(tree.key("Shift+ArrowDown"));
+    assert!(tree.key("Shift+ArrowDown"));
+    assert_eq!(tree.selected(), ["src/lib.rs", "src/main.rs", "target"]);
+    assert!(tree.key("Ctrl+ArrowDown"));
+    assert!(tree.key("Ctrl+ "));
+    assert_eq!(tree.selected().len(), 4);
+
+    assert!(
+        tree.click("src/main.rs", false, true),
+        "ctrl-click deselects"
+    );
+    assert!(
+        tree.click("Cargo.toml", true, false),
+        "shift-click ranges from the last ctrl-click"
+    );
+    assert_eq!(tree.selected(), ["src/main.rs", "target", "Cargo.toml"]);
+    assert!(tree.handle("select:src/lib.rs"));
+    assert_eq!(tree.selected(), ["src/lib.rs"]);
+    assert!(tree.key("ArrowLeft"), "to the parent");
+    assert!(tree.key("ArrowLeft"), "collapses it");
+    assert_eq!(ids(&tree), ["src", "target", "Cargo.toml"]);
+    assert!(!tree.key("ArrowUp"));
+}
+
+#[test]
+fn lazy_branches_load_their_children_when_first_expanded() {
+    let mut tree = files().with_loader(|id| {
+        let id = id.to_string();
+        async move { vec![TreeItem::leaf(format!("{}/debug", id), "debug")] }
+    });
+    assert!(tree.handle("toggle:target"));
+    assert!(tree.is_loading("target"));
+    assert_eq!(ids(&tree), ["src", "target", "Cargo.toml"]);
+    velox_core::task::run_ready();
+    assert!(!tree.is_loading("target"));
+    assert_eq!(ids(&tree), ["src", "target", "target/debug", "Cargo.toml"]);
+    assert!(tree.collapse("target"));
+    assert!(tree.expand("target"));
+    assert_eq!(
+        tree.children("target"),
+        Some(vec!["target/debug".to_string()])
+    );
+    assert!(tree.set_children("target", vec![TreeItem::leaf("target/release", "release")]));
+    assert_eq!(
+        ids(&tree),
+        ["src", "target", "target/release", "Cargo.toml"]
+    );
+}
+
+#[test]
+fn only_the_rows_in_the_viewport_are_built() {
+    let items = (0..10_000)
+        .map(|i| TreeItem::leaf(i.to_string(), format!("row {}", i)))
+        .collect();
+    let mut tree = TreeView::new(items).with_row_height(20.0);
+    tree.set_viewport(2_000.0, 200.0);
+    let VNode::Element { children, .. } = tree.render("tree") else {
+        panic!("not an element")
+    };
+    // Rows 100..110 in view, 5 more on each side, and a spacer above and below.
+    assert_eq!(children.len(), 22);
+    assert_eq!(style(&children[0]), Some("height: 1900px"));
+    assert_eq!(style(&children[21]), Some("height: 197700px"));
+    assert_eq!(tree.row_offset("100"), Some(2000.0));
+}
+
+fn style(node: &VNode) -> Option<&str> {
+    match node {
+        VNode::Element { props, .. } => props.str("style"),
+        _ => None,
+    }
+}
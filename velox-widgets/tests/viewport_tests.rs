@@ -0,0 +1,41 @@
+use velox_dom::{VNode, text};
+use velox_widgets::Viewport;
+
+#[test]
+fn pans_and_zooms_about_the_pointer() {
+    let mut view = Viewport::new().with_limits(0.5, 4.0);
+    assert!(view.handle(r#"{"dx":-30,"dy":10}"#));
+    assert_eq!(view.offset(), (-30.0, 10.0));
+    assert!(!view.handle(r#"{"dx":0,"dy":0}"#));
+
+    // The content point under (100, 50) stays there.
+    let under = view.to_content(100.0, 50.0);
+    assert!(view.handle(r#"{"scale":2,"x":100,"y":50}"#));
+    assert_eq!(view.scale(), 2.0);
+    assert_eq!(view.to_content(100.0, 50.0), under);
+    assert!(view.handle(r#"{"scale":10,"x":0,"y":0}"#));
+    assert_eq!(view.scale(), 4.0);
+    assert!(
+        !view.handle(r#"{"scale":2,"x":0,"y":0}"#),
+        "already at the limit"
+    );
+    assert!(!view.handle("nonsense"));
+
+    view.reset();
+    view.pan_by(12.0, -4.0);
+    let VNode::Element {
+        props, children, ..
+    } = view.render("map", text("tiles"))
+    else {
+        panic!()
+    };
+    assert_eq!(props.attrs["on:pan"], "map");
+    assert_eq!(props.attrs["on:zoom"], "map");
+    let VNode::Element { props: inner, .. } = &children[0] else {
+        panic!()
+    };
+    assert_eq!(
+        inner.attrs["style"],
+        "transform-origin: 0 0; transform: translate(12px, -4px) scale(1);"
+    );
+}
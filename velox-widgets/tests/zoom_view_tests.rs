@@ -0,0 +1,38 @@
+use velox_dom::{VNode, text};
+use velox_widgets::ZoomView;
+
+#[test]
+fn gestures_zoom_within_limits_and_turn() {
+    let mut view = ZoomView::new().with_limits(0.5, 2.0);
+    assert!(view.handle(r#"{"scale":1.5,"x":10,"y":20}"#));
+    assert!(view.handle(r#"{"scale":2,"x":10,"y":20}"#));
+    assert_eq!(view.scale(), 2.0);
+    assert!(
+        !view.handle(r#"{"scale":1.1,"x":10,"y":20}"#),
+        "already at the limit"
+    );
+
+    assert!(view.handle(r#"{"angle":3,"x":0,"y":0}"#));
+    assert!(view.handle(r#"{"angle":1,"x":0,"y":0}"#));
+    assert!(
+        (view.rotation() - (4.0 - std::f32::consts::TAU)).abs() < 1e-5,
+        "wraps to -PI..PI"
+    );
+    assert!(!view.handle("nonsense"));
+
+    let VNode::Element {
+        props, children, ..
+    } = view.render("canvas", text("drawing"))
+    else {
+        panic!()
+    };
+    assert_eq!(props.attrs["on:zoom"], "canvas");
+    assert_eq!(props.attrs["on:rotate"], "canvas");
+    let VNode::Element { props: inner, .. } = &children[0] else {
+        panic!()
+    };
+    assert_eq!(inner.attrs["style"], "transform: scale(2);");
+
+    view.reset();
+    assert_eq!((view.scale(), view.rotation()), (1.0, 0.0));
+}